@@ -0,0 +1,116 @@
+//! Tag suggestions from an LLM, constrained to prefer the vault's existing tags
+
+use crate::config::LlmSettings;
+use crate::llm::{self, LlmError};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Tags the model may suggest for one prompt
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Prompt text sent to the model is cut to this many characters
+const MAX_PROMPT_CHARS: usize = 6000;
+
+const SYSTEM_PROMPT: &str = "You tag prompts in a prompt library. \
+Reply with a JSON array of at most {max} lowercase tags and nothing else. \
+Prefer tags from the existing vocabulary; only invent a tag when none fits. \
+Tags may be hierarchical with `/` (e.g. `lang/rust`) and must not contain spaces.\n\n\
+Existing vocabulary:\n{vocabulary}";
+
+/// Suggested tags for a prompt - returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagSuggestions {
+    pub prompt_id: String,
+    /// Suggestions already used elsewhere in the vault
+    pub existing: Vec<String>,
+    /// Suggestions not in the vault yet
+    pub new: Vec<String>,
+}
+
+/// Ask the model for tags for `text`, splitting them into known and new ones.
+/// Tags the prompt already has are left out.
+pub async fn suggest_tags(
+    settings: &LlmSettings,
+    prompt_id: &str,
+    text: &str,
+    current_tags: &[String],
+    vocabulary: &[String],
+) -> Result<TagSuggestions, LlmError> {
+    let system = SYSTEM_PROMPT
+        .replace("{max}", &MAX_SUGGESTIONS.to_string())
+        .replace(
+            "{vocabulary}",
+            &if vocabulary.is_empty() {
+                "(none yet)".to_string()
+            } else {
+                vocabulary.join(", ")
+            },
+        );
+    let text: String = text.chars().take(MAX_PROMPT_CHARS).collect();
+    let completion = llm::complete(settings, &settings.model, Some(&system), &text).await?;
+
+    let mut suggestions = TagSuggestions {
+        prompt_id: prompt_id.to_string(),
+        existing: Vec::new(),
+        new: Vec::new(),
+    };
+    for tag in parse_tags(&completion.text)
+        .into_iter()
+        .filter(|tag| !current_tags.contains(tag))
+        .take(MAX_SUGGESTIONS)
+    {
+        if vocabulary.contains(&tag) {
+            suggestions.existing.push(tag);
+        } else {
+            suggestions.new.push(tag);
+        }
+    }
+    Ok(suggestions)
+}
+
+/// Read tags from the model's reply: a JSON array if there is one, otherwise a
+/// comma or newline separated list. Tags are normalized and deduplicated.
+fn parse_tags(reply: &str) -> Vec<String> {
+    let raw: Vec<String> = reply
+        .find('[')
+        .zip(reply.rfind(']'))
+        .and_then(|(start, end)| serde_json::from_str(&reply[start..=end]).ok())
+        .unwrap_or_else(|| {
+            reply
+                .split([',', '\n'])
+                .map(|item| item.trim_start_matches(['-', '*', ' ']).to_string())
+                .collect()
+        });
+
+    let mut tags: Vec<String> = Vec::new();
+    for tag in raw {
+        let tag = tag
+            .trim()
+            .trim_matches(|c| matches!(c, '"' | '\'' | '`' | '#' | '.'))
+            .to_lowercase()
+            .replace(char::is_whitespace, "-");
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags("Sure! [\"Coding\", \"lang/rust\", \"code review\", \"coding\"]"),
+            vec!["coding", "lang/rust", "code-review"]
+        );
+        assert_eq!(
+            parse_tags("- writing\n- #email\n- `tone`"),
+            vec!["writing", "email", "tone"]
+        );
+        assert_eq!(parse_tags("summary, notes"), vec!["summary", "notes"]);
+    }
+}
@@ -0,0 +1,364 @@
+//! Timestamped zip snapshots of the vault, taken on a schedule and pruned to a retention count
+
+use crate::config::{self, BackupSettings};
+use crate::error::AppError;
+use crate::vault;
+use chrono::{DateTime, NaiveDateTime, TimeDelta, Utc};
+use serde::Serialize;
+use specta::Type;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tracing::{error, info};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Snapshots are `prompt-manager-backup-{UTC time}.zip`; other files in the folder are ignored
+const FILE_PREFIX: &str = "prompt-manager-backup-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// How often the scheduler checks whether a snapshot is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// A vault snapshot in the backup folder
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub path: String,
+    /// When the snapshot was taken (RFC 3339)
+    pub created: String,
+    pub size_bytes: u64,
+    pub prompt_count: usize,
+}
+
+/// Folder snapshots go to: the configured one, or `backups` in the app data directory
+pub fn backup_dir(app: &AppHandle, settings: &BackupSettings) -> Result<PathBuf, AppError> {
+    match settings
+        .directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+    {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join("backups"))
+            .map_err(|e| AppError::io(e.to_string())),
+    }
+}
+
+/// Zip the prompt files (those with one of the prompt file `extensions`) of the vault
+/// into a new snapshot in `dir`
+pub fn create_backup(
+    vault_path: &Path,
+    dir: &Path,
+    extensions: &[String],
+) -> Result<BackupInfo, AppError> {
+    create_backup_at(vault_path, dir, extensions, Utc::now())
+}
+
+fn create_backup_at(
+    vault_path: &Path,
+    dir: &Path,
+    extensions: &[String],
+    now: DateTime<Utc>,
+) -> Result<BackupInfo, AppError> {
+    fs::create_dir_all(dir)
+        .map_err(|e| AppError::io(format!("Failed to create backup folder: {}", e)))?;
+    let path = dir.join(format!(
+        "{}{}.zip",
+        FILE_PREFIX,
+        now.format(TIMESTAMP_FORMAT)
+    ));
+    let file = File::create_new(&path)
+        .map_err(|e| AppError::io(format!("Failed to create {}: {}", path.display(), e)))?;
+
+    let names = prompt_files(vault_path, extensions)?;
+    if let Err(e) = write_snapshot(file, vault_path, &names) {
+        // Never leave a truncated snapshot behind to be restored later
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+    let size_bytes = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+    info!("Backed up {} prompts to {:?}", names.len(), path);
+    Ok(BackupInfo {
+        path: path.to_string_lossy().to_string(),
+        created: now.to_rfc3339(),
+        size_bytes,
+        prompt_count: names.len(),
+    })
+}
+
+fn write_snapshot(file: File, vault_path: &Path, names: &[String]) -> Result<(), AppError> {
+    let failed = |e: zip::result::ZipError| AppError::io(format!("Failed to write backup: {}", e));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut zip = ZipWriter::new(file);
+    for name in names {
+        let content = fs::read(vault_path.join(name))
+            .map_err(|e| AppError::io(format!("Failed to read {}: {}", name, e)))?;
+        zip.start_file(name.as_str(), options).map_err(failed)?;
+        zip.write_all(&content)
+            .map_err(|e| AppError::io(format!("Failed to write backup: {}", e)))?;
+    }
+    zip.finish().map_err(failed)?;
+    Ok(())
+}
+
+/// Snapshots in `dir`, newest first
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>, AppError> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let entries = fs::read_dir(dir)
+        .map_err(|e| AppError::io(format!("Failed to read backup folder: {}", e)))?;
+
+    let mut backups: Vec<(DateTime<Utc>, BackupInfo)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            let created = snapshot_time(&path)?;
+            let file = File::open(&path).ok()?;
+            let size_bytes = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+            // Unreadable zips were cut short or are not ours; they are never restored
+            let prompt_count = ZipArchive::new(file).ok()?.len();
+            Some((
+                created,
+                BackupInfo {
+                    path: path.to_string_lossy().to_string(),
+                    created: created.to_rfc3339(),
+                    size_bytes,
+                    prompt_count,
+                },
+            ))
+        })
+        .collect();
+    backups.sort_by_key(|(created, _)| std::cmp::Reverse(*created));
+    Ok(backups.into_iter().map(|(_, backup)| backup).collect())
+}
+
+/// Delete all but the `retention` newest snapshots in `dir` (0 keeps them all)
+pub fn prune_backups(dir: &Path, retention: u32) -> Result<Vec<String>, AppError> {
+    if retention == 0 {
+        return Ok(Vec::new());
+    }
+    let mut removed = Vec::new();
+    for backup in list_backups(dir)?.into_iter().skip(retention as usize) {
+        fs::remove_file(&backup.path)
+            .map_err(|e| AppError::io(format!("Failed to delete {}: {}", backup.path, e)))?;
+        info!("Pruned old backup {}", backup.path);
+        removed.push(backup.path);
+    }
+    Ok(removed)
+}
+
+/// Make the vault match the snapshot at `backup_path`: its files are written back and
+/// prompt files it does not hold are deleted. Returns the vault-relative paths that
+/// changed. The whole snapshot is read first, so a broken one leaves the vault untouched.
+pub fn restore_backup(
+    backup_path: &Path,
+    vault_path: &Path,
+    extensions: &[String],
+) -> Result<Vec<String>, AppError> {
+    let invalid = |message: String| AppError::invalid_input(format!("Invalid backup: {}", message));
+
+    let file = File::open(backup_path)
+        .map_err(|e| AppError::io(format!("Failed to open backup: {}", e)))?;
+    let mut zip = ZipArchive::new(file).map_err(|e| invalid(e.to_string()))?;
+    let mut files = BTreeMap::new();
+    for index in 0..zip.len() {
+        let mut entry = zip.by_index(index).map_err(|e| invalid(e.to_string()))?;
+        // Never let an entry escape the vault
+        let name = entry.name().to_string();
+        if !vault::has_prompt_extension(&name, extensions) {
+            return Err(invalid(format!("{} is not a prompt file", name)));
+        }
+        let path = vault::normalize_relative_path(&name, extensions)
+            .map_err(|e| invalid(format!("{}: {}", name, e)))?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| invalid(format!("{}: {}", name, e)))?;
+        files.insert(path, content);
+    }
+
+    let mut changed = Vec::new();
+    for name in prompt_files(vault_path, extensions)? {
+        if !files.contains_key(&name) {
+            fs::remove_file(vault_path.join(&name))
+                .map_err(|e| AppError::io(format!("Failed to delete {}: {}", name, e)))?;
+            changed.push(name);
+        }
+    }
+    for (path, content) in files {
+        let file_path = vault_path.join(&path);
+        if fs::read_to_string(&file_path).is_ok_and(|current| current == content) {
+            continue;
+        }
+        vault::write_atomic(&file_path, &content)
+            .map_err(|e| AppError::io(format!("Failed to write {}: {}", path, e)))?;
+        changed.push(path);
+    }
+    info!(
+        "Restored {:?}: {} files changed",
+        backup_path,
+        changed.len()
+    );
+    Ok(changed)
+}
+
+/// Check every few minutes whether a snapshot is due and take it. Settings are re-read on
+/// every check, so turning backups on or changing the interval needs no restart.
+pub fn start_backup_scheduler(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = run_scheduled_backup(&app) {
+                error!("Scheduled backup failed: {}", e);
+            }
+        }
+    });
+    info!("Started backup scheduler");
+}
+
+fn run_scheduled_backup(app: &AppHandle) -> Result<(), AppError> {
+    let config = config::current(app);
+    let (true, Some(vault_path)) = (config.backup.enabled, config.vault_path.as_deref()) else {
+        return Ok(());
+    };
+    let dir = backup_dir(app, &config.backup)?;
+    let latest = list_backups(&dir)?
+        .first()
+        .and_then(|backup| snapshot_time(Path::new(&backup.path)));
+    if !is_due(latest, config.backup.interval_hours, Utc::now()) {
+        return Ok(());
+    }
+    create_backup(
+        Path::new(vault_path),
+        &dir,
+        &config.frontmatter.file_extensions,
+    )?;
+    prune_backups(&dir, config.backup.retention)?;
+    Ok(())
+}
+
+fn is_due(latest: Option<DateTime<Utc>>, interval_hours: u32, now: DateTime<Utc>) -> bool {
+    latest.is_none_or(|latest| now - latest >= TimeDelta::hours(interval_hours.max(1) as i64))
+}
+
+/// When the snapshot at `path` was taken, from its file name; `None` for other files
+fn snapshot_time(path: &Path) -> Option<DateTime<Utc>> {
+    let name = path.file_name()?.to_str()?;
+    let timestamp = name.strip_prefix(FILE_PREFIX)?.strip_suffix(".zip")?;
+    NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+/// Names of the prompt files in the vault, sorted
+fn prompt_files(vault_path: &Path, extensions: &[String]) -> Result<Vec<String>, AppError> {
+    let entries = fs::read_dir(vault_path)
+        .map_err(|e| AppError::io(format!("Failed to read vault: {}", e)))?;
+    let mut names: Vec<String> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| Some(path.file_name()?.to_str()?.to_string()))
+        .filter(|name| vault::has_prompt_extension(name, extensions))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_backup_and_restore() {
+        let root = TempDir::new("backup");
+        let (vault_path, dir) = (root.join("vault"), root.join("backups"));
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(vault_path.join("review.md"), "Review this").unwrap();
+        fs::write(vault_path.join("summary.md"), "Summarize this").unwrap();
+        fs::write(vault_path.join("notes.txt"), "not a prompt").unwrap();
+
+        let day = |d| Utc.with_ymd_and_hms(2024, 5, d, 12, 0, 0).unwrap();
+        let first = create_backup_at(&vault_path, &dir, vault::MARKDOWN_ONLY, day(1)).unwrap();
+        assert_eq!(first.prompt_count, 2);
+        create_backup_at(&vault_path, &dir, vault::MARKDOWN_ONLY, day(2)).unwrap();
+        create_backup_at(&vault_path, &dir, vault::MARKDOWN_ONLY, day(3)).unwrap();
+        fs::write(dir.join("unrelated.zip"), "").unwrap();
+
+        let backups = list_backups(&dir).unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(backups[0].created, day(3).to_rfc3339());
+        assert_eq!(prune_backups(&dir, 2).unwrap(), vec![first.path]);
+        assert_eq!(list_backups(&dir).unwrap().len(), 2);
+        assert!(dir.join("unrelated.zip").exists());
+
+        assert!(is_due(None, 24, day(3)));
+        assert!(!is_due(Some(day(3)), 24, day(3) + TimeDelta::hours(23)));
+        assert!(is_due(Some(day(3)), 24, day(4)));
+
+        fs::write(vault_path.join("review.md"), "Review this carefully").unwrap();
+        fs::remove_file(vault_path.join("summary.md")).unwrap();
+        fs::write(vault_path.join("draft.md"), "Draft").unwrap();
+        let mut changed = restore_backup(
+            Path::new(&backups[0].path),
+            &vault_path,
+            vault::MARKDOWN_ONLY,
+        )
+        .unwrap();
+        changed.sort();
+        assert_eq!(changed, vec!["draft.md", "review.md", "summary.md"]);
+        assert_eq!(
+            prompt_files(&vault_path, vault::MARKDOWN_ONLY).unwrap(),
+            vec!["review.md", "summary.md"]
+        );
+        assert_eq!(
+            fs::read_to_string(vault_path.join("review.md")).unwrap(),
+            "Review this"
+        );
+        assert!(vault_path.join("notes.txt").exists());
+        assert!(restore_backup(
+            &dir.join("unrelated.zip"),
+            &vault_path,
+            vault::MARKDOWN_ONLY
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_backup_other_prompt_extensions() {
+        let root = TempDir::new("backup");
+        let (vault_path, dir) = (root.join("vault"), root.join("backups"));
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::write(vault_path.join("review.md"), "Review this").unwrap();
+        fs::write(vault_path.join("notes.txt"), "Summarize this").unwrap();
+        fs::write(vault_path.join("data.json"), "{}").unwrap();
+        let extensions = vec!["md".to_string(), "txt".to_string()];
+
+        let backup = create_backup(&vault_path, &dir, &extensions).unwrap();
+        assert_eq!(backup.prompt_count, 2);
+
+        fs::write(vault_path.join("notes.txt"), "Summarize this briefly").unwrap();
+        let changed = restore_backup(Path::new(&backup.path), &vault_path, &extensions).unwrap();
+        assert_eq!(changed, vec!["notes.txt"]);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("notes.txt")).unwrap(),
+            "Summarize this"
+        );
+        // A markdown-only vault refuses the snapshot rather than dropping the text prompt
+        assert!(
+            restore_backup(Path::new(&backup.path), &vault_path, vault::MARKDOWN_ONLY).is_err()
+        );
+    }
+}
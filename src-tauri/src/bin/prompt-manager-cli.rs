@@ -0,0 +1,329 @@
+//! Terminal companion to the app, working on the same vault and cache database
+
+use clap::{Parser, Subcommand};
+use prompt_manager_lib::config::{self, AppConfig};
+use prompt_manager_lib::crypto;
+use prompt_manager_lib::db::{self, DbPool};
+use prompt_manager_lib::models::{FilterConfig, Prompt, SearchConfig};
+use prompt_manager_lib::{git, prompts, sync, template, vault};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+
+/// Clipboard programs tried in order by `copy`
+#[cfg(target_os = "macos")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["pbcopy"]];
+#[cfg(target_os = "windows")]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[&["clip"]];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const CLIPBOARD_COMMANDS: &[&[&str]] = &[
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Programs that print the clipboard, mirroring `CLIPBOARD_COMMANDS`
+#[cfg(target_os = "macos")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["pbpaste"]];
+#[cfg(target_os = "windows")]
+const PASTE_COMMANDS: &[&[&str]] = &[&["powershell", "-NoProfile", "-Command", "Get-Clipboard"]];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const PASTE_COMMANDS: &[&[&str]] = &[
+    &["wl-paste", "--no-newline"],
+    &["xclip", "-selection", "clipboard", "-o"],
+    &["xsel", "--clipboard", "--output"],
+];
+
+/// Use your prompt vault from the terminal.
+/// Reads come from the app's cache; run `sync` after editing files outside the app.
+#[derive(Parser)]
+#[command(name = "prompt-manager-cli", version)]
+struct Cli {
+    /// Config file (defaults to the app's)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// Cache database (defaults to the app's)
+    #[arg(long, global = true)]
+    db: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List prompts, optionally filtered by tag (`-t=-tag` excludes)
+    List {
+        #[arg(short, long)]
+        tag: Vec<String>,
+    },
+    /// List prompts matching QUERY (words, `tag:`, `title:"..."`, `/regex/i`; `-` negates)
+    Search {
+        query: String,
+        #[arg(short, long)]
+        tag: Vec<String>,
+    },
+    /// Print a prompt's text
+    Show { id: String },
+    /// Fill in a prompt's variables and copy it to the clipboard
+    Copy {
+        id: String,
+        /// Variable value, as `name=value`; JSON arrays, objects and booleans are
+        /// passed as JSON for `{{#each}}` and `{{#if}}`
+        #[arg(short, long = "set", value_parser = parse_value)]
+        set: Vec<(String, JsonValue)>,
+        /// Print the rendered prompt instead of copying it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Create a prompt from TEXT (or stdin) and print its id
+    New {
+        text: Option<String>,
+        #[arg(long)]
+        title: Option<String>,
+        #[arg(short, long)]
+        tag: Vec<String>,
+    },
+    /// Re-read the vault into the cache
+    Sync,
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(e) = run(Cli::parse()).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let config_path = cli
+        .config
+        .or_else(config::default_config_path)
+        .ok_or("Could not locate the config directory")?;
+    let config = config::load_config_from(&config_path).map_err(|e| e.to_string())?;
+    let vault_path = config
+        .vault_path
+        .clone()
+        .map(PathBuf::from)
+        .ok_or("Vault path not configured; choose one in the app first")?;
+    let db_path = cli
+        .db
+        .or_else(|| db::default_db_path(&config))
+        .ok_or("Could not locate the data directory")?;
+    let pool = db::connect(&db_path).await.map_err(|e| e.to_string())?;
+
+    match cli.command {
+        Command::List { tag } => print_prompts(&list_prompts(&pool, tag, None).await?),
+        Command::Search { query, tag } => {
+            print_prompts(&list_prompts(&pool, tag, Some(query)).await?)
+        }
+        Command::Show { id } => println!("{}", find_prompt(&pool, &id).await?.text),
+        Command::Copy { id, set, print } => {
+            let prompt = find_prompt(&pool, &id).await?;
+            let mut values: HashMap<String, JsonValue> = set.into_iter().collect();
+            template::apply_declared(&prompt.variables, &mut values).map_err(|e| e.to_string())?;
+            // The CLI has no passphrase, so encrypted includes fail as locked
+            let text = prompts::expand_includes(&pool, &prompt, &crypto::VaultKey::default())
+                .await
+                .map_err(|e| e.to_string())?;
+            let globals = prompts::global_variables(&pool)
+                .await
+                .map_err(|e| e.to_string())?;
+            let builtins =
+                template::Builtins::new(&config.templates, read_clipboard(&text), globals);
+            let rendered =
+                template::render(&text, &values, &builtins).map_err(|e| e.to_string())?;
+            if !rendered.missing.is_empty() {
+                eprintln!("warning: no value for {}", rendered.missing.join(", "));
+            }
+            if print {
+                println!("{}", rendered.text);
+            } else {
+                copy_to_clipboard(&rendered.text)?;
+                eprintln!("Copied {}", prompt.id);
+            }
+        }
+        Command::New { text, title, tag } => {
+            let id = new_prompt(&pool, &config, &vault_path, text, title, tag).await?;
+            println!("{}", id);
+        }
+        Command::Sync => {
+            let stats = sync::sync_vault(&pool, &vault_path, &config.frontmatter)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!(
+                "Synced {} prompts ({} removed from cache)",
+                stats.found, stats.deleted
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn list_prompts(
+    pool: &DbPool,
+    tags: Vec<String>,
+    search: Option<String>,
+) -> Result<Vec<Prompt>, String> {
+    let filter = FilterConfig {
+        tags: Some(tags),
+        search: search.map(SearchConfig::from),
+        ..FilterConfig::default()
+    };
+    prompts::query_prompts(pool, Some(filter), None)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Look a prompt up by id, allowing the `.md` extension to be left off. Encrypted prompts
+/// are refused: the passphrase only ever lives in the app.
+async fn find_prompt(pool: &DbPool, id: &str) -> Result<Prompt, String> {
+    for candidate in [id.to_string(), format!("{}.md", id)] {
+        if let Some(prompt) = prompts::load_prompt(pool, &candidate)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            if crypto::is_sealed(&prompt.text) {
+                return Err(format!("{} is encrypted; open it in the app", prompt.id));
+            }
+            return Ok(prompt);
+        }
+    }
+    Err(format!("No prompt with id {}", id))
+}
+
+/// One line per prompt: id, title (or first line of text) and tags
+fn print_prompts(prompts: &[Prompt]) {
+    for prompt in prompts {
+        let title = prompt
+            .title
+            .clone()
+            .filter(|title| !title.trim().is_empty())
+            .or_else(|| {
+                prompt
+                    .text
+                    .lines()
+                    .find(|line| !line.trim().is_empty())
+                    .map(str::to_string)
+            })
+            .unwrap_or_default();
+        let tags: Vec<String> = prompt.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        println!("{}\t{}\t{}", prompt.id, title.trim(), tags.join(" "));
+    }
+}
+
+async fn new_prompt(
+    pool: &DbPool,
+    config: &AppConfig,
+    vault_path: &Path,
+    text: Option<String>,
+    title: Option<String>,
+    tags: Vec<String>,
+) -> Result<String, String> {
+    let text = match text {
+        Some(text) => text,
+        None => {
+            let mut text = String::new();
+            std::io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| e.to_string())?;
+            text
+        }
+    };
+    if text.trim().is_empty() {
+        return Err("Prompt text is empty".to_string());
+    }
+
+    let file_path =
+        vault::generate_unique_file_path(vault_path, &config.filename_template, title.as_deref())
+            .map_err(|e| e.to_string())?;
+    let prompt = vault::PromptFile {
+        id: file_path.clone(),
+        file_path: file_path.clone(),
+        tags,
+        created: None,
+        content: text.trim_end().to_string(),
+        file_hash: None,
+        title,
+        description: None,
+        color: None,
+        icon: None,
+        notes: None,
+        model_targets: Vec::new(),
+        language: None,
+        status: None,
+        custom_fields: HashMap::new(),
+        variants: Vec::new(),
+        archived: false,
+        links: Vec::new(),
+        variables: Vec::new(),
+    };
+    vault::write_prompt_file(vault_path, &prompt, &config.frontmatter)
+        .map_err(|e| e.to_string())?;
+    sync::sync_paths(
+        pool,
+        vault_path,
+        &config.frontmatter,
+        std::slice::from_ref(&file_path),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    if config.git_history {
+        if let Err(e) = git::commit_paths(
+            vault_path,
+            &[&file_path],
+            &format!("Add prompt {}", file_path),
+        ) {
+            eprintln!("warning: failed to record vault history: {}", e);
+        }
+    }
+    Ok(file_path)
+}
+
+/// Pipe `text` into the first clipboard program that works
+fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    for command in CLIPBOARD_COMMANDS {
+        let Ok(mut child) = Process::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(text.as_bytes());
+        }
+        if child.wait().is_ok_and(|status| status.success()) {
+            return Ok(());
+        }
+    }
+    Err("No clipboard program found; use --print instead".to_string())
+}
+
+/// Clipboard text for `{{clipboard}}`, only read when `text` uses it
+fn read_clipboard(text: &str) -> Option<String> {
+    if !template::uses_clipboard(text) {
+        return None;
+    }
+    PASTE_COMMANDS.iter().find_map(|command| {
+        let output = Process::new(command[0]).args(&command[1..]).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    })
+}
+
+fn parse_value(raw: &str) -> Result<(String, JsonValue), String> {
+    let (name, value) = raw
+        .split_once('=')
+        .ok_or_else(|| format!("expected name=value, got `{}`", raw))?;
+    let value = match serde_json::from_str(value) {
+        Ok(json @ (JsonValue::Array(_) | JsonValue::Object(_) | JsonValue::Bool(_))) => json,
+        _ => JsonValue::from(value),
+    };
+    Ok((name.trim().to_string(), value))
+}
@@ -0,0 +1,101 @@
+//! In-memory copies of cache reads repeated on every keystroke (the tag list and the quick
+//! picker), dropped whenever prompts or tags change
+
+use crate::db::{queries::SELECT_ALL_TAGS, DbPool};
+use crate::models::{DbError, Prompt, TagRow};
+use crate::prompts::query_prompts;
+use std::sync::{Arc, Mutex, PoisonError};
+
+#[derive(Default)]
+struct Entries {
+    /// Bumped by every `invalidate`, so a read racing a change never stores stale data
+    generation: u64,
+    tags: Option<Arc<Vec<String>>>,
+    prompts: Option<Arc<Vec<Prompt>>>,
+}
+
+/// Managed as Tauri state; `events::emit` invalidates it along with prompt and tag events
+#[derive(Default)]
+pub struct CacheState {
+    entries: Mutex<Entries>,
+}
+
+impl CacheState {
+    /// Every tag name, sorted
+    pub async fn tags(&self, pool: &DbPool) -> Result<Arc<Vec<String>>, DbError> {
+        let generation = {
+            let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(tags) = &entries.tags {
+                return Ok(tags.clone());
+            }
+            entries.generation
+        };
+
+        let rows = sqlx::query_as::<_, TagRow>(SELECT_ALL_TAGS)
+            .fetch_all(pool)
+            .await?;
+        let tags = Arc::new(rows.into_iter().map(|row| row.name).collect());
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.generation == generation {
+            entries.tags = Some(Arc::clone(&tags));
+        }
+        Ok(tags)
+    }
+
+    /// Unarchived prompts, newest first, as `query_prompts` returns them without a filter
+    pub async fn prompts(&self, pool: &DbPool) -> Result<Arc<Vec<Prompt>>, DbError> {
+        let generation = {
+            let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(prompts) = &entries.prompts {
+                return Ok(prompts.clone());
+            }
+            entries.generation
+        };
+
+        let prompts = Arc::new(query_prompts(pool, None, None).await?);
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.generation == generation {
+            entries.prompts = Some(Arc::clone(&prompts));
+        }
+        Ok(prompts)
+    }
+
+    /// Drop everything; the next read goes to SQLite again
+    pub fn invalidate(&self) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.generation += 1;
+        entries.tags = None;
+        entries.prompts = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{migrations, queries::INSERT_TAG};
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_cache_invalidation() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let insert_tag = |id: &'static str, name: &'static str| {
+            sqlx::query(INSERT_TAG).bind(id).bind(name).execute(&pool)
+        };
+        insert_tag("t1", "rust").await.unwrap();
+
+        let cache = CacheState::default();
+        assert_eq!(*cache.tags(&pool).await.unwrap(), vec!["rust"]);
+
+        // Served from memory until invalidated
+        insert_tag("t2", "ai").await.unwrap();
+        assert_eq!(*cache.tags(&pool).await.unwrap(), vec!["rust"]);
+        cache.invalidate();
+        assert_eq!(*cache.tags(&pool).await.unwrap(), vec!["ai", "rust"]);
+        assert!(cache.prompts(&pool).await.unwrap().is_empty());
+    }
+}
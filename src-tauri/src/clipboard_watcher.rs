@@ -0,0 +1,154 @@
+//! Opt-in clipboard monitor that suggests saving long, prompt-like text copied in other apps
+
+use crate::config::{self, CaptureSettings};
+use crate::events::{self, CaptureSuggested};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tokio::sync::oneshot;
+use tracing::info;
+
+/// How often the clipboard is read; there is no cross-platform change notification
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Copies longer than this are never suggested (whole documents rather than prompts)
+const MAX_SUGGESTED_CHARS: usize = 20_000;
+
+#[derive(Default)]
+pub struct ClipboardWatcherState {
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    /// Clipboard text as last seen by the watcher or copied by the app itself
+    last_text: Arc<Mutex<Option<String>>>,
+}
+
+impl ClipboardWatcherState {
+    /// Remember text the app put on the clipboard so it is not suggested back
+    pub fn record_self_copy(&self, text: &str) {
+        if let Ok(mut last) = self.last_text.lock() {
+            *last = Some(text.to_string());
+        }
+    }
+
+    /// Stop polling (no-op when the watcher is not running)
+    pub fn stop(&self) -> Result<(), String> {
+        let mut guard = self
+            .shutdown
+            .lock()
+            .map_err(|_| "Clipboard watcher lock poisoned".to_string())?;
+        if let Some(shutdown) = guard.take() {
+            let _ = shutdown.send(());
+            info!("Stopped watching the clipboard");
+        }
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> Result<bool, String> {
+        let guard = self
+            .shutdown
+            .lock()
+            .map_err(|_| "Clipboard watcher lock poisoned".to_string())?;
+        Ok(guard.is_some())
+    }
+}
+
+/// Whether `text` is worth suggesting as a prompt: long enough, not huge and, when
+/// keywords are set, containing one of them
+pub fn looks_like_prompt(text: &str, settings: &CaptureSettings) -> bool {
+    let text = text.trim();
+    let length = text.chars().count();
+    if length < settings.min_length || length > MAX_SUGGESTED_CHARS {
+        return false;
+    }
+    let lower = text.to_lowercase();
+    let mut keywords = settings
+        .keywords
+        .iter()
+        .map(|keyword| keyword.trim().to_lowercase())
+        .filter(|keyword| !keyword.is_empty())
+        .peekable();
+    keywords.peek().is_none() || keywords.any(|keyword| lower.contains(&keyword))
+}
+
+/// Poll the clipboard and emit `CaptureSuggested` for new prompt-like text, replacing a
+/// running watcher. Capture settings are re-read on every change, so edits apply right away.
+pub fn start_clipboard_watch(app: AppHandle, state: &ClipboardWatcherState) -> Result<(), String> {
+    state.stop()?;
+
+    // Whatever is on the clipboard already was not copied while watching
+    let last_text = state.last_text.clone();
+    if let Ok(mut last) = last_text.lock() {
+        *last = app.clipboard().read_text().ok();
+    }
+
+    let (shutdown, mut shutdown_rx) = oneshot::channel();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+                _ = interval.tick() => {}
+            }
+            let Ok(text) = app.clipboard().read_text() else {
+                continue;
+            };
+            let changed = match last_text.lock() {
+                Ok(mut last) if last.as_deref() != Some(text.as_str()) => {
+                    *last = Some(text.clone());
+                    true
+                }
+                _ => false,
+            };
+            if !changed {
+                continue;
+            }
+            if looks_like_prompt(&text, &config::current(&app).capture) {
+                events::emit(&app, CaptureSuggested { text });
+            }
+        }
+    });
+
+    let mut guard = state
+        .shutdown
+        .lock()
+        .map_err(|_| "Clipboard watcher lock poisoned".to_string())?;
+    *guard = Some(shutdown);
+    info!("Started watching the clipboard");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_like_prompt() {
+        let settings = CaptureSettings {
+            min_length: 20,
+            keywords: vec!["You are".to_string()],
+            ..CaptureSettings::default()
+        };
+        assert!(looks_like_prompt(
+            "you are a careful reviewer. List every bug.",
+            &settings
+        ));
+        assert!(!looks_like_prompt("You are short", &settings));
+        assert!(!looks_like_prompt(
+            "A long copied sentence without the keyword.",
+            &settings
+        ));
+        assert!(!looks_like_prompt(
+            &format!("You are {}", "x".repeat(MAX_SUGGESTED_CHARS)),
+            &settings
+        ));
+
+        let any_text = CaptureSettings {
+            keywords: vec![" ".to_string()],
+            ..settings
+        };
+        assert!(looks_like_prompt(
+            "A long copied sentence without the keyword.",
+            &any_text
+        ));
+    }
+}
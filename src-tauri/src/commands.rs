@@ -1,24 +1,64 @@
-use crate::config::{self, AppConfig, ConfigError};
-use crate::db::{queries::*, DbPool};
+use crate::autotag::{self, TagSuggestions};
+use crate::backup::{self, BackupInfo};
+use crate::cache::CacheState;
+use crate::clipboard_watcher::{self, ClipboardWatcherState};
+use crate::config::{self, AppConfig, ConfigIssue, ConfigState, EmbeddingSettings, Profile};
+use crate::crypto::{self, VaultKey};
+use crate::db::{self, migrations, queries::*, DbPool, DbState};
+use crate::deep_link::{self, DeepLink};
+use crate::diff::{self, PromptDiff};
+use crate::doctor::{self, RepairKind, VaultHealthReport, VaultRepairReport};
+use crate::embeddings;
+use crate::error::AppError;
+use crate::events::{
+    self, DeepLinkOpened, PromptDeleted, PromptRenamed, PromptSaved, SyncCompleted, TagChanged,
+    ViewDeleted, ViewSaved, ViewsReordered,
+};
+use crate::export::{
+    self, ExportFormat, ExportTool, ExportedPrompt, ImportItemReport, ImportStatus, ImportStrategy,
+};
+use crate::git::{self, PromptCommit};
+use crate::importers::chatgpt::{self, ChatGptImportOptions};
+use crate::importers::obsidian;
+use crate::integrations::gist::{self, GistError, SharedGist};
+use crate::lint::{self, LintContext, PromptLint};
+use crate::llm;
+use crate::logging::{self, LogEntry, LogLevel, LoggingState};
 use crate::models::{self, *};
+use crate::promptpack::{self, PackFile};
+use crate::prompts::{
+    expand_includes, global_variables, group_prompts, linked_prompts, load_prompt,
+    prompt_summaries, query_prompts, recent_prompts, stale_prompts, summary_page, tag_graph,
+};
+use crate::sample_vault::{self, SamplePack};
+use crate::search::{self, FuzzyPromptMatch, SearchHit, SearchQuery};
+use crate::secrets::{self, SecretName};
+use crate::serve::{self, ApiServerState, ApiServerStatus};
+use crate::store::{
+    self, commit_vault_history, rewrite_input, write_prompt_input, VaultContext, WrittenPrompt,
+};
+use crate::sync::{self, SyncCancellation, SyncControl};
+use crate::template::{self, MatrixOutput, RenderedPrompt};
+use crate::tokens::{self, TokenCount};
+use crate::undo::{self, Snapshot, UndoState, UndoStatus, UndoneOperation};
 use crate::vault::{self, PromptFile, VaultError};
-use crate::vault_watcher::{self, VaultWatcherState};
-use log::info;
-use specta::Type;
+use crate::vault_backend::{self, RemoteBackend, RemoteSyncStats, SyncDirection};
+use crate::vault_watcher::{self, SelfWrites, VaultWatchStatus, VaultWatcherState};
+use serde_json::Value as JsonValue;
 use sqlx::Row;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::path::Path;
-use tauri::{AppHandle, State};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tracing::{error, info};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, serde::Serialize, Type)]
-pub struct SyncStats {
-    pub found: usize,
-    pub updated: usize,
-    pub deleted: usize,
-}
-
 // ============================================================================
 // PROMPTS (Cache Layer)
 // ============================================================================
@@ -26,98 +66,129 @@ pub struct SyncStats {
 /// Get all prompts with their tags from cache
 #[tauri::command]
 #[specta::specta]
+#[tracing::instrument(skip_all)]
 pub async fn get_prompts(
-    db: State<'_, DbPool>,
+    db: State<'_, DbState>,
     filter: Option<FilterConfig>,
     sort: Option<SortConfig>,
-) -> Result<Vec<Prompt>, DbError> {
+) -> Result<Vec<Prompt>, AppError> {
     info!("get_prompts called");
 
     // Auto-sync behavior?
     // For now, let's assume specific sync call is made, or we can trigger it here lazily if config allows.
     // Given the request "reads from DB (cache)", we just read. Sync is explicit.
+    Ok(query_prompts(&db.pool(), filter, sort).await?)
+}
 
-    // Fetch all prompts from cache
-    let prompt_rows = sqlx::query_as::<_, PromptRow>(SELECT_ALL_PROMPTS)
-        .fetch_all(db.inner())
-        .await?;
-
-    // Build prompts with tags
-    let mut prompts = Vec::new();
-    for row in prompt_rows {
-        let tags = get_tags_for_prompt(db.inner(), &row.id).await?;
-
-        prompts.push(Prompt {
-            id: row.id,
-            created: row.created,
-            text: row.text,
-            tags,
-            file_path: row.file_path,
-            title: row.title,
-            description: row.description,
-        });
+/// Like `get_prompts` without the full texts, for lists of large vaults; `page` limits the
+/// result to one page of summaries
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_prompt_summaries(
+    db: State<'_, DbState>,
+    cache: State<'_, CacheState>,
+    filter: Option<FilterConfig>,
+    sort: Option<SortConfig>,
+    page: Option<PageRequest>,
+) -> Result<PromptSummaryPage, AppError> {
+    info!("get_prompt_summaries called: {:?}", page);
+
+    // The unfiltered default list is the one the picker asks for on every keystroke
+    if filter.is_none() && sort.is_none() {
+        let prompts = cache.prompts(&db.pool()).await?;
+        return Ok(summary_page(prompts.to_vec(), page)?);
     }
+    Ok(prompt_summaries(&db.pool(), filter, sort, page).await?)
+}
 
-    // Apply filters in memory
-    if let Some(filter) = filter {
-        // Filter by tags (AND logic + negative tags)
-        if let Some(filter_tags) = &filter.tags {
-            if !filter_tags.is_empty() {
-                let mut positive_tags: Vec<String> = Vec::new();
-                let mut negative_tags: Vec<String> = Vec::new();
-
-                for tag in filter_tags {
-                    let trimmed = tag.trim();
-                    if trimmed.is_empty() {
-                        continue;
-                    }
-                    if let Some(stripped) = trimmed.strip_prefix('-') {
-                        let raw = stripped.trim();
-                        if !raw.is_empty() {
-                            negative_tags.push(raw.to_string());
-                        }
-                    } else {
-                        positive_tags.push(trimmed.to_string());
-                    }
-                }
-
-                if !positive_tags.is_empty() || !negative_tags.is_empty() {
-                    prompts.retain(|p| {
-                        let has_all_positive =
-                            positive_tags.iter().all(|t| p.tags.contains(t));
-                        let has_no_negative =
-                            negative_tags.iter().all(|t| !p.tags.contains(t));
-                        has_all_positive && has_no_negative
-                    });
-                }
-            }
-        }
+/// Like `get_prompts`, split into groups by tag, created month, model target or folder, each
+/// with its number of prompts
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_prompt_groups(
+    db: State<'_, DbState>,
+    filter: Option<FilterConfig>,
+    sort: Option<SortConfig>,
+    group_by: GroupBy,
+) -> Result<Vec<PromptGroup>, AppError> {
+    info!("get_prompt_groups called: {:?}", group_by);
 
-        // Filter by search
-        if let Some(search) = &filter.search {
-            if !search.is_empty() {
-                let lower_search = search.to_lowercase();
-                prompts.retain(|p| p.text.to_lowercase().contains(&lower_search));
-            }
-        }
-    }
+    Ok(group_prompts(&db.pool(), filter, sort, group_by).await?)
+}
+
+/// A single cached prompt with its full text
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_prompt(db: State<'_, DbState>, id: String) -> Result<Option<Prompt>, AppError> {
+    info!("get_prompt called for id: {}", id);
+
+    Ok(load_prompt(&db.pool(), &id).await?)
+}
 
-    // Apply sort
-    if let Some(sort) = sort {
-        prompts.sort_by(|a, b| {
-            let cmp = match sort.by.as_str() {
-                "created" | _ => a.created.cmp(&b.created),
-            };
+/// Like `get_prompts`, with where the search query hit each prompt for highlighting
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn search_prompts(
+    db: State<'_, DbState>,
+    filter: FilterConfig,
+    sort: Option<SortConfig>,
+) -> Result<Vec<SearchHit>, AppError> {
+    info!("search_prompts called: {:?}", filter.search);
 
-            if sort.order == "desc" {
-                cmp.reverse()
-            } else {
-                cmp
-            }
-        });
-    }
+    let query = match &filter.search {
+        Some(search) => SearchQuery::from_config(search).map_err(DbError::from)?,
+        None => SearchQuery::default(),
+    };
+    let prompts = query_prompts(&db.pool(), Some(filter), sort).await?;
+    Ok(prompts
+        .into_iter()
+        .map(|prompt| query.highlight(prompt))
+        .collect())
+}
+
+/// Quick list of the prompts used or edited most recently
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_recent_prompts(
+    db: State<'_, DbState>,
+    kind: RecentKind,
+    limit: u32,
+) -> Result<Vec<Prompt>, AppError> {
+    info!("get_recent_prompts called: {:?} (limit {})", kind, limit);
+
+    Ok(recent_prompts(&db.pool(), kind, limit).await?)
+}
+
+/// Unarchived prompts neither used nor edited in the last `days` days, stalest first, for
+/// periodic review
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_stale_prompts(db: State<'_, DbState>, days: u32) -> Result<Vec<Prompt>, AppError> {
+    info!("get_stale_prompts called: {} days", days);
+
+    Ok(stale_prompts(&db.pool(), days).await?)
+}
 
-    Ok(prompts)
+/// Fuzzy-match titles and file names for the quick picker, best match first
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn fuzzy_search_prompts(
+    db: State<'_, DbState>,
+    cache: State<'_, CacheState>,
+    query: String,
+    limit: u32,
+) -> Result<Vec<FuzzyPromptMatch>, AppError> {
+    info!("fuzzy_search_prompts called: {:?} (limit {})", query, limit);
+
+    let prompts = cache.prompts(&db.pool()).await?.to_vec();
+    Ok(search::fuzzy_search(prompts, &query, limit as usize))
 }
 
 /// Save a prompt to cache (upsert)
@@ -127,129 +198,73 @@ pub async fn get_prompts(
 /// 3. Update database (Cache)
 #[tauri::command]
 #[specta::specta]
+#[tracing::instrument(skip_all)]
 pub async fn save_prompt(
     app: AppHandle,
-    db: State<'_, DbPool>,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
     prompt: PromptInput,
-) -> Result<(), DbError> {
+) -> Result<(), AppError> {
     info!("save_prompt called for id: {}", prompt.id);
 
     // 1. Load config to check vault path
-    let config = config::load_config(&app)
-        .map_err(|e| DbError::Database(format!("Failed to load config: {}", e)))?; // reusing DbError for now or should genericize
-
-    let vault_path_str = config
-        .vault_path
-        .ok_or_else(|| DbError::Database("Vault path not configured".to_string()))?;
-
-    let vault_path = Path::new(&vault_path_str);
-
-    // 2. Prepare PromptFile for vault write
-    let file_path_raw = match prompt.file_path.clone() {
-        Some(path) if !path.trim().is_empty() => path,
-        _ => vault::generate_unique_file_path(vault_path)
-            .map_err(|e| DbError::Database(format!("Failed to generate filename: {}", e)))?,
-    };
-    let file_path = vault::normalize_relative_path(&file_path_raw)
-        .map_err(|e| DbError::Database(format!("Invalid file path: {}", e)))?;
-
-    let previous_file_path = prompt
-        .previous_file_path
-        .clone()
-        .filter(|p| !p.trim().is_empty())
-        .map(|p| vault::normalize_relative_path(&p))
-        .transpose()
-        .map_err(|e| DbError::Database(format!("Invalid previous path: {}", e)))?;
-
-    if let Some(prev_path) = &previous_file_path {
-        if prev_path != &file_path {
-            let target_path = vault_path.join(&file_path);
-            if target_path.exists() {
-                return Err(DbError::Database(format!(
-                    "File name already exists: {}",
-                    file_path
-                )));
-            }
-        }
-    } else if vault_path.join(&file_path).exists() {
-        return Err(DbError::Database(format!(
-            "File name already exists: {}",
-            file_path
-        )));
-    }
-
-    let prompt_file = vault::PromptFile {
-        id: file_path.clone(),
-        // We calculate relative path just for completeness, but write_prompt_file uses ID for filename
-        file_path: file_path.clone(),
-        tags: prompt.tags.clone(),
-        created: prompt.created.clone(),
-        content: prompt.text.clone(),
-        file_hash: None,
-        title: prompt.title.clone(),
-        description: prompt.description.clone(),
-    };
-
-    // 3. Write to Filesystem
-    vault::write_prompt_file(vault_path, &prompt_file, &config.frontmatter)
-        .map_err(|e| DbError::Database(format!("Failed to write to vault: {}", e)))?;
-
-    // 4. Update Database (Cache)
-    // Use a transaction for atomicity
-    let mut tx = db.inner().begin().await?;
-
-    // Remove old prompt row if file was renamed
-    if let Some(ref prev_path) = previous_file_path {
-        if prev_path != &file_path {
-            sqlx::query(DELETE_PROMPT)
-                .bind(prev_path)
-                .execute(&mut *tx)
-                .await?;
-        }
-    }
-
-    let file_hash = vault::compute_file_hash_from_path(&vault_path.join(&file_path))
-        .ok();
-
-    // Upsert the prompt
-    sqlx::query(UPSERT_PROMPT)
-        .bind(&file_path)
-        .bind(prompt.created)
-        .bind(&prompt.text)
-        .bind(prompt.title.clone())
-        .bind(prompt.description.clone())
-        .bind(Some(file_path.clone())) // Store the relative path
-        .bind(file_hash) // file_hash placeholder
-        .execute(&mut *tx)
-        .await?;
-
-    // Delete existing tags
-    sqlx::query(DELETE_PROMPT_TAGS)
-        .bind(&file_path)
-        .execute(&mut *tx)
-        .await?;
-
-    // Insert new tags
-    for tag_name in &prompt.tags {
-        let tag_id = get_or_create_tag(&mut tx, tag_name).await?;
-        sqlx::query(INSERT_PROMPT_TAG)
-            .bind(&file_path)
-            .bind(&tag_id)
-            .execute(&mut *tx)
-            .await?;
-    }
-
-    tx.commit().await?;
-    if let Some(prev_path) = previous_file_path {
-        if prev_path != file_path {
-            let _ = vault::delete_prompt_file(vault_path, &prev_path);
-        }
-    }
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(
+        &vault,
+        format!("Save prompt {}", prompt.id),
+        input_paths(&prompt),
+    )
+    .await?;
+
+    // 2. Write to Filesystem (Master), 3. Update Database (Cache)
+    let written = store::save_prompt(&db.pool(), &vault, prompt).await?;
+    snapshot
+        .finish(&vault, &undo, vec![written.file_path.clone()])
+        .await;
+    emit_written(&app, &db.pool(), &[written]).await?;
 
     info!("save_prompt completed successfully (Vault and DB updated)");
     Ok(())
 }
 
+/// Save many prompts at once: every file is written first, then the cache is updated
+/// in a single transaction. A prompt that fails to write is reported without stopping the rest.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn save_prompts(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    prompts: Vec<PromptInput>,
+) -> Result<Vec<SavePromptResult>, AppError> {
+    info!("save_prompts called for {} prompts", prompts.len());
+
+    let config = config_state.get();
+    let count = prompts.len();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(
+        &vault,
+        format!("Save {} prompts", count),
+        prompts.iter().flat_map(input_paths),
+    )
+    .await?;
+    let (results, written) = store::save_prompts(&db.pool(), &vault, prompts).await?;
+    let created = written.iter().map(|w| w.file_path.clone()).collect();
+    snapshot.finish(&vault, &undo, created).await;
+    emit_written(&app, &db.pool(), &written).await?;
+
+    info!(
+        "save_prompts completed: {} of {} saved",
+        written.len(),
+        count
+    );
+    Ok(results)
+}
+
 /// Delete a prompt from cache
 /// STRICT VAULT-FIRST:
 /// 1. Check if vault is configured
@@ -257,55 +272,29 @@ pub async fn save_prompt(
 /// 3. Delete from database (Cache)
 #[tauri::command]
 #[specta::specta]
+#[tracing::instrument(skip_all)]
 pub async fn delete_prompt(
     app: AppHandle,
-    db: State<'_, DbPool>,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
     id: String,
-) -> Result<(), DbError> {
+) -> Result<(), AppError> {
     info!("delete_prompt called for id: {}", id);
 
     // 1. Load config
-    let config = config::load_config(&app)
-        .map_err(|e| DbError::Database(format!("Failed to load config: {}", e)))?;
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(&vault, format!("Delete prompt {}", id), [id.clone()]).await?;
 
-    let vault_path_str = config
-        .vault_path
-        .ok_or_else(|| DbError::Database("Vault path not configured".to_string()))?;
+    // 2. Delete from Filesystem, 3. Delete from Database (Cache)
+    let tags = store::delete_prompt(&db.pool(), &vault, &id).await?;
+    snapshot.finish(&vault, &undo, Vec::new()).await;
 
-    // 2. Delete from Filesystem
-    // We try to delete, but if file is already gone, we proceed to ensure DB is clean
-    let row = sqlx::query_as::<_, PromptRow>(SELECT_PROMPT_BY_ID)
-        .bind(&id)
-        .fetch_optional(db.inner())
-        .await?;
-    let file_path = row.as_ref().and_then(|r| r.file_path.clone());
-
-    if let Err(e) = vault::delete_prompt_file(
-        Path::new(&vault_path_str),
-        file_path.as_deref().unwrap_or(&id),
-    ) {
-        match e {
-            VaultError::PathNotFound(_) => {
-                info!(
-                    "File for prompt {} not found in vault, proceeding to delete from DB",
-                    id
-                );
-            }
-            _ => {
-                return Err(DbError::Database(format!(
-                    "Failed to delete from vault: {}",
-                    e
-                )))
-            }
-        }
+    events::emit(&app, PromptDeleted { id });
+    if !tags.is_empty() {
+        emit_tag_changed(&app, &db.pool()).await?;
     }
-
-    // 3. Delete from Database (Cache)
-    sqlx::query(DELETE_PROMPT)
-        .bind(&id)
-        .execute(db.inner())
-        .await?;
-
     Ok(())
 }
 
@@ -316,101 +305,297 @@ pub async fn delete_prompt(
 /// 3. Update database (Cache)
 #[tauri::command]
 #[specta::specta]
+#[tracing::instrument(skip_all)]
 pub async fn duplicate_prompt(
     app: AppHandle,
-    db: State<'_, DbPool>,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
     id: String,
-) -> Result<Option<Prompt>, DbError> {
+) -> Result<Option<Prompt>, AppError> {
     info!("duplicate_prompt called for id: {}", id);
 
-    // 0. Load Config
-    let config = config::load_config(&app)
-        .map_err(|e| DbError::Database(format!("Failed to load config: {}", e)))?;
+    // 1. Load config
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(&vault, format!("Duplicate prompt {}", id), Vec::new()).await?;
+
+    // 2. Write new file to Filesystem, 3. Update Database (Cache)
+    let Some(written) = store::duplicate_prompt(&db.pool(), &vault, &id).await? else {
+        return Ok(None);
+    };
+    snapshot
+        .finish(&vault, &undo, vec![written.file_path.clone()])
+        .await;
+    emit_written(&app, &db.pool(), std::slice::from_ref(&written)).await?;
 
-    let vault_path_str = config
-        .vault_path
-        .ok_or_else(|| DbError::Database("Vault path not configured".to_string()))?;
-    let vault_path = Path::new(&vault_path_str);
+    load_prompt(&db.pool(), &written.file_path).await
+}
 
-    // Get the original prompt
-    let row = sqlx::query_as::<_, PromptRow>(SELECT_PROMPT_BY_ID)
-        .bind(&id)
-        .fetch_optional(db.inner())
-        .await?;
+/// Rename (move) a prompt file within the vault
+/// 1. Validate the target path
+/// 2. Re-key the cache rows in a transaction
+/// 3. Move the file, then commit (moving it back if the commit fails)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn rename_prompt(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    id: String,
+    new_path: String,
+) -> Result<(), AppError> {
+    info!("rename_prompt called for id: {} -> {}", id, new_path);
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(
+        &vault,
+        format!("Rename prompt {}", id),
+        [id.clone(), new_path.clone()],
+    )
+    .await?;
+    if let Some((old_id, new_id)) = store::rename_prompt(&db.pool(), &vault, &id, &new_path).await?
+    {
+        snapshot.finish(&vault, &undo, Vec::new()).await;
+        events::emit(&app, PromptRenamed { old_id, new_id });
+    }
+    Ok(())
+}
+
+/// Archive or unarchive a prompt; archived prompts are hidden from views unless
+/// their filter sets `archived`
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn set_archived(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    id: String,
+    archived: bool,
+) -> Result<(), AppError> {
+    info!("set_archived called for id: {} ({})", id, archived);
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+
+    let file_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+    let action = if archived { "Archive" } else { "Unarchive" };
+    let snapshot = Snapshot::take(
+        &vault,
+        format!("{} prompt {}", action, id),
+        [file_path.clone()],
+    )
+    .await?;
+    let key = vault.key.clone();
+    let written = store::vault_io(&vault, move |vault_path, config, self_writes| {
+        let file = vault::read_prompt_file(
+            vault_path,
+            &vault_path.join(&file_path),
+            &config.frontmatter,
+        )?;
+        if file.archived == archived {
+            return Ok(None);
+        }
 
-    let row = match row {
-        Some(r) => r,
-        None => return Ok(None),
+        let input = PromptInput {
+            archived: Some(archived),
+            ..rewrite_input(file_path, file)
+        };
+        write_prompt_input(vault_path, config, self_writes, &key, &input).map(Some)
+    })
+    .await?;
+    let Some(written) = written else {
+        return Ok(());
     };
+    snapshot.finish(&vault, &undo, Vec::new()).await;
+    cache_written_prompts(&app, &db.pool(), &config, &[written]).await?;
+    Ok(())
+}
 
-    let tags = get_tags_for_prompt(db.inner(), &row.id).await?;
+/// Move a prompt to another lifecycle stage (`status` in its frontmatter)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn set_prompt_status(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    id: String,
+    status: PromptStatus,
+) -> Result<(), AppError> {
+    info!("set_prompt_status called for id: {} ({:?})", id, status);
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+
+    let file_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+    let snapshot = Snapshot::take(
+        &vault,
+        format!("Set status of {} to {}", id, status.as_str()),
+        [file_path.clone()],
+    )
+    .await?;
+    let key = vault.key.clone();
+    let written = store::vault_io(&vault, move |vault_path, config, self_writes| {
+        let file = vault::read_prompt_file(
+            vault_path,
+            &vault_path.join(&file_path),
+            &config.frontmatter,
+        )?;
+        if file.status == Some(status) {
+            return Ok(None);
+        }
 
-    let new_created = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
-
-    let file_path = vault::generate_unique_file_path(vault_path)
-        .map_err(|e| DbError::Database(format!("Failed to generate filename: {}", e)))?;
-
-    let new_prompt = PromptInput {
-        id: file_path.clone(),
-        created: Some(new_created.clone()),
-        text: row.text.clone(),
-        tags: tags.clone(),
-        file_path: None, // New file will be created
-        previous_file_path: None,
-        title: row.title.clone(),
-        description: row.description.clone(),
-    };
-
-    // 1. Prepare PromptFile for vault write
-    let prompt_file = vault::PromptFile {
-        id: file_path.clone(),
-        file_path: file_path.clone(),
-        tags: new_prompt.tags.clone(),
-        created: new_prompt.created.clone(),
-        content: new_prompt.text.clone(),
-        file_hash: None,
-        title: new_prompt.title.clone(),
-        description: new_prompt.description.clone(),
-    };
+        let input = PromptInput {
+            status: Some(status),
+            ..rewrite_input(file_path, file)
+        };
+        write_prompt_input(vault_path, config, self_writes, &key, &input).map(Some)
+    })
+    .await?;
+    let Some(written) = written else {
+        return Ok(());
+    };
+    snapshot.finish(&vault, &undo, Vec::new()).await;
+    cache_written_prompts(&app, &db.pool(), &config, &[written]).await?;
+    Ok(())
+}
 
-    // 2. Write to Filesystem
-    vault::write_prompt_file(vault_path, &prompt_file, &config.frontmatter)
-        .map_err(|e| DbError::Database(format!("Failed to write to vault: {}", e)))?;
-
-    // 3. Save the new prompt using the existing function logic (upsert to DB)
-    let mut tx = db.inner().begin().await?;
-
-    sqlx::query(UPSERT_PROMPT)
-        .bind(&file_path)
-        .bind(new_prompt.created)
-        .bind(&new_prompt.text)
-        .bind(new_prompt.title.clone())
-        .bind(new_prompt.description.clone())
-        .bind(Some(file_path.clone()))
-        .bind::<Option<String>>(None)
-        .execute(&mut *tx)
-        .await?;
+/// Split a prompt into new files (e.g. a list of prompts pasted into one), each keeping its
+/// tags and created date, and archive the original. Returns the new prompt ids.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn split_prompt(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    id: String,
+    split: SplitBy,
+) -> Result<Vec<String>, AppError> {
+    info!("split_prompt called for id: {}", id);
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(&vault, format!("Split prompt {}", id), [id.clone()]).await?;
+    let written = store::split_prompt(&db.pool(), &vault, &id, split).await?;
+    // The archived original comes last
+    let created: Vec<String> = written[..written.len() - 1]
+        .iter()
+        .map(|w| w.file_path.clone())
+        .collect();
+    snapshot.finish(&vault, &undo, created.clone()).await;
+    emit_written(&app, &db.pool(), &written).await?;
+
+    info!("split_prompt completed: {} new prompts", created.len());
+    Ok(created)
+}
 
-    for tag_name in &new_prompt.tags {
-        let tag_id = get_or_create_tag(&mut tx, tag_name).await?;
-        sqlx::query(INSERT_PROMPT_TAG)
-            .bind(&file_path)
-            .bind(&tag_id)
-            .execute(&mut *tx)
-            .await?;
-    }
+/// Deleted prompts whose contents can still be recovered, newest first
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn list_recently_deleted(
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+) -> Result<Vec<DeletedPrompt>, AppError> {
+    info!("list_recently_deleted called");
+
+    let config = config_state.get();
+    store::list_recently_deleted(&db.pool(), config.deleted_retention_days).await
+}
 
-    tx.commit().await?;
+/// Re-create the vault file of a deleted prompt from its kept contents
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn recover_deleted_prompt(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    id: String,
+) -> Result<Option<Prompt>, AppError> {
+    info!("recover_deleted_prompt called for id: {}", id);
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(&vault, format!("Recover prompt {}", id), [id.clone()]).await?;
+    store::recover_deleted_prompt(&db.pool(), &vault, &id).await?;
+    snapshot.finish(&vault, &undo, Vec::new()).await;
+
+    let recovered = load_prompt(&db.pool(), &id).await?;
+    if let Some(prompt) = &recovered {
+        events::emit(&app, PromptSaved(prompt.clone()));
+        if !prompt.tags.is_empty() {
+            emit_tag_changed(&app, &db.pool()).await?;
+        }
+    }
+    Ok(recovered)
+}
+
+// ============================================================================
+// UNDO
+// ============================================================================
+
+/// Put back the files of the last save, delete, rename or bulk edit and resync them.
+/// Returns `None` when there is nothing to undo.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn undo_last_operation(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+) -> Result<Option<UndoneOperation>, AppError> {
+    info!("undo_last_operation called");
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let undone = undo::undo_last_operation(&db.pool(), &vault, &undo).await?;
+    if let Some(undone) = &undone {
+        events::emit(&app, SyncCompleted(undone.stats.clone()));
+    }
+    Ok(undone)
+}
+
+/// Apply the last undone operation again. Returns `None` when there is nothing to redo.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn redo(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+) -> Result<Option<UndoneOperation>, AppError> {
+    info!("redo called");
+
+    let config = config_state.get();
+    let vault = vault_context(&app, &config)?;
+    let redone = undo::redo(&db.pool(), &vault, &undo).await?;
+    if let Some(redone) = &redone {
+        events::emit(&app, SyncCompleted(redone.stats.clone()));
+    }
+    Ok(redone)
+}
 
-    Ok(Some(Prompt {
-        id: file_path.clone(),
-        created: Some(new_created),
-        text: row.text,
-        tags,
-        file_path: Some(file_path),
-        title: row.title,
-        description: row.description,
-    }))
+/// Labels of the operations undo and redo would apply next
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn get_undo_status(undo: State<'_, UndoState>) -> Result<UndoStatus, AppError> {
+    info!("get_undo_status called");
+
+    Ok(undo.status())
 }
 
 // ============================================================================
@@ -420,11 +605,12 @@ pub async fn duplicate_prompt(
 /// Get all views
 #[tauri::command]
 #[specta::specta]
-pub async fn get_views(db: State<'_, DbPool>) -> Result<Vec<View>, DbError> {
+#[tracing::instrument(skip_all)]
+pub async fn get_views(db: State<'_, DbState>) -> Result<Vec<View>, AppError> {
     info!("get_views called");
 
     let rows = sqlx::query_as::<_, ViewRow>(SELECT_ALL_VIEWS)
-        .fetch_all(db.inner())
+        .fetch_all(&db.pool())
         .await?;
 
     let mut views = Vec::new();
@@ -445,12 +631,13 @@ pub async fn get_views(db: State<'_, DbPool>) -> Result<Vec<View>, DbError> {
 /// Get a view by ID
 #[tauri::command]
 #[specta::specta]
-pub async fn get_view_by_id(db: State<'_, DbPool>, id: String) -> Result<Option<View>, DbError> {
+#[tracing::instrument(skip_all)]
+pub async fn get_view_by_id(db: State<'_, DbState>, id: String) -> Result<Option<View>, AppError> {
     info!("get_view_by_id called for id: {}", id);
 
     let row = sqlx::query_as::<_, ViewRow>(SELECT_VIEW_BY_ID)
         .bind(&id)
-        .fetch_optional(db.inner())
+        .fetch_optional(&db.pool())
         .await?;
 
     match row {
@@ -471,9 +658,18 @@ pub async fn get_view_by_id(db: State<'_, DbPool>, id: String) -> Result<Option<
 /// Save a view (upsert)
 #[tauri::command]
 #[specta::specta]
-pub async fn save_view(db: State<'_, DbPool>, view: ViewInput) -> Result<(), DbError> {
+#[tracing::instrument(skip_all)]
+pub async fn save_view(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    view: ViewInput,
+) -> Result<(), AppError> {
     info!("save_view called for id: {}", view.id);
 
+    if let Some(layout) = &view.config.layout {
+        layout.validate()?;
+    }
+
     let config_json = serde_json::to_string(&view.config)?;
 
     sqlx::query(UPSERT_VIEW)
@@ -481,384 +677,3153 @@ pub async fn save_view(db: State<'_, DbPool>, view: ViewInput) -> Result<(), DbE
         .bind(&view.name)
         .bind(&view.view_type)
         .bind(&config_json)
-        .bind(view.created)
-        .execute(db.inner())
+        .bind(&view.created)
+        .execute(&db.pool())
         .await?;
 
+    events::emit(
+        &app,
+        ViewSaved(View {
+            id: view.id,
+            name: view.name,
+            view_type: view.view_type,
+            config: view.config,
+            created: view.created,
+        }),
+    );
     Ok(())
 }
 
 /// Delete a view
 #[tauri::command]
 #[specta::specta]
-pub async fn delete_view(db: State<'_, DbPool>, id: String) -> Result<(), DbError> {
+#[tracing::instrument(skip_all)]
+pub async fn delete_view(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<(), AppError> {
     info!("delete_view called for id: {}", id);
 
+    let row = sqlx::query_as::<_, ViewRow>(SELECT_VIEW_BY_ID)
+        .bind(&id)
+        .fetch_optional(&db.pool())
+        .await?;
+    if row.is_some_and(|row| row.view_type == "system") {
+        return Err(AppError::invalid_input(format!(
+            "System view cannot be deleted: {}",
+            id
+        )));
+    }
+
     sqlx::query(DELETE_VIEW)
         .bind(&id)
-        .execute(db.inner())
+        .execute(&db.pool())
         .await?;
 
+    events::emit(&app, ViewDeleted { id });
     Ok(())
 }
 
-// ============================================================================
-// TAGS
-// ============================================================================
-
-/// Get all tag names
+/// Put views in the order of `ids`; views not listed keep their position after them
 #[tauri::command]
 #[specta::specta]
-pub async fn get_all_tags(db: State<'_, DbPool>) -> Result<Vec<String>, DbError> {
-    info!("get_all_tags called");
+#[tracing::instrument(skip_all)]
+pub async fn reorder_views(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    ids: Vec<String>,
+) -> Result<(), AppError> {
+    info!("reorder_views called for {} views", ids.len());
 
-    let rows = sqlx::query_as::<_, TagRow>(SELECT_ALL_TAGS)
-        .fetch_all(db.inner())
+    let pool = &db.pool();
+    let rows = sqlx::query_as::<_, ViewRow>(SELECT_ALL_VIEWS)
+        .fetch_all(pool)
         .await?;
+    let mut ordered: Vec<String> = Vec::with_capacity(rows.len());
+    for id in &ids {
+        if !rows.iter().any(|row| &row.id == id) {
+            return Err(DbError::NotFound(format!("View not found: {}", id)).into());
+        }
+        if !ordered.contains(id) {
+            ordered.push(id.clone());
+        }
+    }
+    for row in rows {
+        if !ordered.contains(&row.id) {
+            ordered.push(row.id);
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    for (position, id) in ordered.iter().enumerate() {
+        sqlx::query(UPDATE_VIEW_POSITION)
+            .bind(position as i64)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
 
-    Ok(rows.into_iter().map(|r| r.name).collect())
+    events::emit(&app, ViewsReordered { ids: ordered });
+    Ok(())
 }
 
 // ============================================================================
-// DEBUG
+// TAGS
 // ============================================================================
 
-/// Get all table names (for debugging)
+/// Get all tag names
 #[tauri::command]
 #[specta::specta]
-pub async fn get_table_names(db: State<'_, DbPool>) -> Result<Vec<String>, DbError> {
-    info!("get_table_names called");
-
-    let rows = sqlx::query(SELECT_TABLE_NAMES)
-        .fetch_all(db.inner())
-        .await?;
+#[tracing::instrument(skip_all)]
+pub async fn get_all_tags(
+    db: State<'_, DbState>,
+    cache: State<'_, CacheState>,
+) -> Result<Vec<String>, AppError> {
+    info!("get_all_tags called");
 
-    Ok(rows.iter().map(|r| r.get::<String, _>("name")).collect())
+    Ok(cache.tags(&db.pool()).await?.to_vec())
 }
 
-/// Get table schema information
+/// Get all tags as a nested tree built from `/`-separated tag paths
 #[tauri::command]
 #[specta::specta]
-pub async fn get_table_info(
-    db: State<'_, DbPool>,
-    table_name: String,
-) -> Result<Vec<models::TableColumn>, DbError> {
-    info!("get_table_info called for table: {}", table_name);
+#[tracing::instrument(skip_all)]
+pub async fn get_tag_tree(db: State<'_, DbState>) -> Result<Vec<TagNode>, AppError> {
+    info!("get_tag_tree called");
 
-    let query = format!("PRAGMA table_info({})", sanitize_identifier(&table_name));
-    let rows = sqlx::query_as::<_, models::TableColumn>(&query)
-        .fetch_all(db.inner())
+    let rows = sqlx::query_as::<_, TagUsageRow>(SELECT_TAG_USAGE)
+        .fetch_all(&db.pool())
         .await?;
 
-    Ok(rows)
-}
-
-/// Get all rows from a table (for debugging)
-#[tauri::command]
-#[specta::specta]
-pub async fn get_table_rows(
-    db: State<'_, DbPool>,
-    table_name: String,
-) -> Result<Vec<models::TableRow>, DbError> {
-    info!("get_table_rows called for table: {}", table_name);
-
-    let query = format!("SELECT * FROM {}", sanitize_identifier(&table_name));
-
-    let rows = sqlx::query(&query).fetch_all(db.inner()).await?;
-
-    let columns_query = format!("PRAGMA table_info({})", sanitize_identifier(&table_name));
-    let column_rows = sqlx::query(&columns_query).fetch_all(db.inner()).await?;
-
-    // Extract column names
-    let col_names: Vec<String> = column_rows.iter().map(|r| r.get("name")).collect();
-
-    let mut results = Vec::new();
+    // Collect prompt ids per tag path, counting each prompt once for every ancestor
+    let mut usage: BTreeMap<String, HashSet<String>> = BTreeMap::new();
     for row in rows {
-        let mut map = HashMap::new();
-
-        for col_name in &col_names {
-            let value = extract_column_value(&row, col_name);
-            map.insert(col_name.clone(), value);
+        let mut path = String::new();
+        for segment in row.name.split('/') {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(segment);
+            let entry = usage.entry(path.clone()).or_default();
+            if let Some(prompt_id) = &row.prompt_id {
+                entry.insert(prompt_id.clone());
+            }
         }
-
-        results.push(models::TableRow::new(map));
     }
 
-    Ok(results)
+    Ok(build_tag_tree(&usage, ""))
 }
 
-/// Clear all rows from a table (for debugging)
+/// Get tags as a co-occurrence graph: nodes are tags with prompt counts, edges link
+/// tags that appear on the same prompts
 #[tauri::command]
 #[specta::specta]
-pub async fn clear_table(db: State<'_, DbPool>, table_name: String) -> Result<(), DbError> {
-    info!("clear_table called for table: {}", table_name);
+#[tracing::instrument(skip_all)]
+pub async fn get_tag_graph(db: State<'_, DbState>) -> Result<TagGraph, AppError> {
+    info!("get_tag_graph called");
 
-    let query = format!("DELETE FROM {}", sanitize_identifier(&table_name));
-    sqlx::query(&query).execute(db.inner()).await?;
-
-    Ok(())
+    Ok(tag_graph(&db.pool()).await?)
 }
 
-/// Export entire database as JSON (for debugging)
+/// Add and remove tags on many prompts: each vault file is rewritten, then the cache is
+/// updated in one transaction. Prompts whose tags would not change are left untouched.
 #[tauri::command]
 #[specta::specta]
-pub async fn export_database_as_json(
-    db: State<'_, DbPool>,
-) -> Result<models::ExportedDatabase, DbError> {
-    info!("export_database_as_json called");
+#[tracing::instrument(skip_all)]
+pub async fn bulk_update_tags(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    undo: State<'_, UndoState>,
+    prompt_ids: Vec<String>,
+    add_tags: Vec<String>,
+    remove_tags: Vec<String>,
+) -> Result<Vec<SavePromptResult>, AppError> {
+    info!(
+        "bulk_update_tags called for {} prompts (+{:?} -{:?})",
+        prompt_ids.len(),
+        add_tags,
+        remove_tags
+    );
 
-    let table_names = get_table_names(State::clone(&db)).await?;
+    let config = config_state.get();
+
+    let add_tags: Vec<String> = add_tags
+        .iter()
+        .filter_map(|t| vault::normalize_tag(t))
+        .collect();
+    let remove_tags: HashSet<String> = remove_tags
+        .iter()
+        .filter_map(|t| vault::normalize_tag(t))
+        .collect();
+
+    let vault = vault_context(&app, &config)?;
+    let snapshot = Snapshot::take(
+        &vault,
+        format!("Update tags of {} prompts", prompt_ids.len()),
+        prompt_ids.clone(),
+    )
+    .await?;
+    let key = vault.key.clone();
+    let (results, written) = store::vault_io(&vault, move |vault_path, config, self_writes| {
+        let mut results = Vec::with_capacity(prompt_ids.len());
+        let mut written = Vec::new();
+        for id in prompt_ids {
+            let retagged = retag_prompt_file(
+                vault_path,
+                config,
+                self_writes,
+                &key,
+                &id,
+                &add_tags,
+                &remove_tags,
+            );
+            match retagged {
+                Ok(file) => {
+                    results.push(SavePromptResult {
+                        id: id.clone(),
+                        file_path: Some(file.as_ref().map_or(id, |f| f.file_path.clone())),
+                        error: None,
+                    });
+                    written.extend(file);
+                }
+                Err(e) => {
+                    error!("Failed to update tags of {}: {}", id, e);
+                    results.push(SavePromptResult {
+                        id,
+                        file_path: None,
+                        error: Some(e),
+                    });
+                }
+            }
+        }
+        Ok((results, written))
+    })
+    .await?;
 
-    let mut tables = HashMap::new();
+    snapshot.finish(&vault, &undo, Vec::new()).await;
+    cache_written_prompts(&app, &db.pool(), &config, &written).await?;
 
-    for table_name in table_names {
-        let schema = get_table_info(State::clone(&db), table_name.clone()).await?;
-        let rows = get_table_rows(State::clone(&db), table_name.clone()).await?;
+    info!(
+        "bulk_update_tags completed: {} files rewritten",
+        written.len()
+    );
+    Ok(results)
+}
 
-        tables.insert(table_name.clone(), models::ExportedTable { schema, rows });
+// ============================================================================
+// LINKS
+// ============================================================================
+
+/// Link prompt `source` to prompt `target` (e.g. a system prompt to its follow-ups).
+/// Linking the same pair with the same relation again is a no-op.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn link_prompts(
+    db: State<'_, DbState>,
+    source: String,
+    target: String,
+    relation: String,
+) -> Result<(), AppError> {
+    info!(
+        "link_prompts called: {} -[{}]-> {}",
+        source, relation, target
+    );
+
+    let relation = relation.trim();
+    if relation.is_empty() || relation == WIKILINK_RELATION {
+        return Err(AppError::invalid_input(format!(
+            "Invalid link relation: {:?}",
+            relation
+        )));
+    }
+    if source == target {
+        return Err(AppError::invalid_input("A prompt cannot link to itself"));
+    }
+    for id in [&source, &target] {
+        if load_prompt(&db.pool(), id).await?.is_none() {
+            return Err(DbError::NotFound(id.clone()).into());
+        }
     }
 
-    Ok(models::ExportedDatabase { tables })
+    sqlx::query(INSERT_PROMPT_LINK)
+        .bind(&source)
+        .bind(&target)
+        .bind(relation)
+        .execute(&db.pool())
+        .await?;
+    Ok(())
 }
 
-/// Get the database file path
+/// Remove a link made with `link_prompts`
 #[tauri::command]
 #[specta::specta]
-pub async fn get_database_path(db: State<'_, DbPool>) -> Result<String, DbError> {
-    info!("get_database_path called");
+#[tracing::instrument(skip_all)]
+pub async fn unlink_prompts(
+    db: State<'_, DbState>,
+    source: String,
+    target: String,
+    relation: String,
+) -> Result<(), AppError> {
+    info!(
+        "unlink_prompts called: {} -[{}]-> {}",
+        source, relation, target
+    );
 
-    let path = sqlx::query("PRAGMA database_list")
-        .fetch_one(db.inner())
+    let result = sqlx::query(DELETE_PROMPT_LINK)
+        .bind(&source)
+        .bind(&target)
+        .bind(relation.trim())
+        .execute(&db.pool())
         .await?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::NotFound(format!("Link from {} to {}", source, target)).into());
+    }
+    Ok(())
+}
 
-    let db_path: String = path.try_get("file")?;
+/// Prompts linked to or from a prompt, by `link_prompts` or by `[[wikilinks]]` in its file
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_linked_prompts(
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<Vec<LinkedPrompt>, AppError> {
+    info!("get_linked_prompts called for id: {}", id);
 
-    Ok(db_path)
+    Ok(linked_prompts(&db.pool(), &id).await?)
 }
 
 // ============================================================================
-// CONFIG COMMANDS
+// DEBUG
 // ============================================================================
 
-/// Get application configuration
+/// Get all table names (for debugging)
 #[tauri::command]
 #[specta::specta]
-pub fn get_config(app: AppHandle) -> Result<AppConfig, ConfigError> {
-    info!("get_config called");
-    config::load_config(&app)
+#[tracing::instrument(skip_all)]
+pub async fn get_table_names(db: State<'_, DbState>) -> Result<Vec<String>, AppError> {
+    info!("get_table_names called");
+
+    let rows = sqlx::query(SELECT_TABLE_NAMES)
+        .fetch_all(&db.pool())
+        .await?;
+
+    Ok(rows.iter().map(|r| r.get::<String, _>("name")).collect())
 }
 
-/// Save application configuration
+/// Get table schema information
 #[tauri::command]
 #[specta::specta]
-pub fn save_config(app: AppHandle, config: AppConfig) -> Result<(), ConfigError> {
-    info!("save_config called");
-    config::save_config(&app, &config)
-}
+#[tracing::instrument(skip_all)]
+pub async fn get_table_info(
+    db: State<'_, DbState>,
+    table_name: String,
+) -> Result<Vec<models::TableColumn>, AppError> {
+    info!("get_table_info called for table: {}", table_name);
 
-// ============================================================================
-// VAULT COMMANDS
-// ============================================================================
+    let query = format!("PRAGMA table_info({})", sanitize_identifier(&table_name));
+    let rows = sqlx::query_as::<_, models::TableColumn>(&query)
+        .fetch_all(&db.pool())
+        .await?;
 
-/// Scan vault and return all prompt files
+    Ok(rows)
+}
+
+/// Get all rows from a table (for debugging)
 #[tauri::command]
 #[specta::specta]
-pub fn scan_vault(app: AppHandle) -> Result<Vec<PromptFile>, VaultError> {
-    info!("scan_vault called");
+#[tracing::instrument(skip_all)]
+pub async fn get_table_rows(
+    db: State<'_, DbState>,
+    table_name: String,
+) -> Result<Vec<models::TableRow>, AppError> {
+    info!("get_table_rows called for table: {}", table_name);
+
+    let query = format!("SELECT * FROM {}", sanitize_identifier(&table_name));
 
-    let config = config::load_config(&app).map_err(|e| VaultError::IoError(e.to_string()))?;
+    let rows = sqlx::query(&query).fetch_all(&db.pool()).await?;
 
-    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    let columns_query = format!("PRAGMA table_info({})", sanitize_identifier(&table_name));
+    let column_rows = sqlx::query(&columns_query).fetch_all(&db.pool()).await?;
+
+    // Extract column names
+    let col_names: Vec<String> = column_rows.iter().map(|r| r.get("name")).collect();
+
+    let mut results = Vec::new();
+    for row in rows {
+        let mut map = HashMap::new();
+
+        for col_name in &col_names {
+            let value = extract_column_value(&row, col_name);
+            map.insert(col_name.clone(), value);
+        }
+
+        results.push(models::TableRow::new(map));
+    }
 
-    vault::scan_vault(Path::new(&vault_path), &config.frontmatter)
+    Ok(results)
 }
 
-/// Sync vault files to database cache
-/// STRICT VAULT-FIRST:
-/// 1. Scan filesystem
-/// 2. Upsert all found files to DB
-/// 3. Remove DB entries that are not in the scan
+/// Clear all rows from a table (for debugging)
 #[tauri::command]
 #[specta::specta]
-pub async fn sync_vault(app: AppHandle, db: State<'_, DbPool>) -> Result<SyncStats, DbError> {
-    info!("sync_vault called");
+#[tracing::instrument(skip_all)]
+pub async fn clear_table(
+    db: State<'_, DbState>,
+    cache: State<'_, CacheState>,
+    table_name: String,
+) -> Result<(), AppError> {
+    info!("clear_table called for table: {}", table_name);
 
-    let config = config::load_config(&app)
-        .map_err(|e| DbError::Database(format!("Failed to load config: {}", e)))?;
+    let query = format!("DELETE FROM {}", sanitize_identifier(&table_name));
+    sqlx::query(&query).execute(&db.pool()).await?;
+    cache.invalidate();
 
-    let vault_path_str = config
-        .vault_path
-        .ok_or_else(|| DbError::Database("Vault path not configured".to_string()))?;
+    Ok(())
+}
 
-    let vault_path = Path::new(&vault_path_str);
+/// Export entire database as JSON (for debugging)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn export_database_as_json(
+    db: State<'_, DbState>,
+) -> Result<models::ExportedDatabase, AppError> {
+    info!("export_database_as_json called");
 
-    // 1. Scan Vault
-    let files = vault::scan_vault(vault_path, &config.frontmatter)
-        .map_err(|e| DbError::Database(format!("Failed to scan vault: {}", e)))?;
-
-    let mut tx = db.inner().begin().await?;
-    let mut found_ids = HashSet::new();
-    let found_count = files.len();
-
-    // 2. Upsert all files
-    for file in files {
-        found_ids.insert(file.file_path.clone());
-
-        // Upsert prompt
-        sqlx::query(UPSERT_PROMPT)
-            .bind(&file.file_path)
-            .bind(file.created)
-            .bind(&file.content)
-            .bind(file.title.clone())
-            .bind(file.description.clone())
-            .bind(Some(&file.file_path))
-            .bind(file.file_hash.clone())
-            .execute(&mut *tx)
-            .await?;
+    let table_names = get_table_names(State::clone(&db)).await?;
 
-        // Replace tags
-        sqlx::query(DELETE_PROMPT_TAGS)
-            .bind(&file.file_path)
-            .execute(&mut *tx)
-            .await?;
-
-        for tag_name in &file.tags {
-            let tag_id = get_or_create_tag(&mut tx, tag_name).await?;
-            sqlx::query(INSERT_PROMPT_TAG)
-                .bind(&file.file_path)
-                .bind(&tag_id)
-                .execute(&mut *tx)
-                .await?;
-        }
-    }
+    let mut tables = HashMap::new();
 
-    // 3. Prune DB entries not in Vault
-    let all_db_rows = sqlx::query("SELECT id FROM prompts")
-        .fetch_all(&mut *tx)
-        .await?;
+    for table_name in table_names {
+        let schema = get_table_info(State::clone(&db), table_name.clone()).await?;
+        let rows = get_table_rows(State::clone(&db), table_name.clone()).await?;
 
-    let mut deleted_count = 0;
-    for row in all_db_rows {
-        let id: String = row.get("id");
-        if !found_ids.contains(&id) {
-            // Delete
-            sqlx::query(DELETE_PROMPT)
-                .bind(&id)
-                .execute(&mut *tx)
-                .await?;
-            deleted_count += 1;
-        }
+        tables.insert(table_name.clone(), models::ExportedTable { schema, rows });
     }
 
-    tx.commit().await?;
+    Ok(models::ExportedDatabase { tables })
+}
 
+/// Export prompts matching `filter` to a JSON, CSV or YAML file; returns the number exported
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn export_prompts(
+    db: State<'_, DbState>,
+    format: ExportFormat,
+    filter: Option<FilterConfig>,
+    destination_path: String,
+) -> Result<usize, AppError> {
     info!(
-        "sync_vault completed. Found: {}, Deleted: {}",
-        found_count, deleted_count
+        "export_prompts called: {:?} -> {}",
+        format, destination_path
     );
 
-    Ok(SyncStats {
-        found: found_count,
-        updated: found_count, // Effectively all found are "updated" via upsert
-        deleted: deleted_count,
+    let prompts = get_prompts(State::clone(&db), filter, None).await?;
+    let count = prompts.len();
+    let content = export::render_export(prompts, format)?;
+
+    vault::blocking(move || {
+        std::fs::write(&destination_path, content)
+            .map_err(|e| AppError::io(format!("Failed to write export: {}", e)))
     })
+    .await?;
+
+    Ok(count)
 }
 
-/// Read a single prompt file by ID
+/// Export prompts matching `filter` for another tool (promptfoo config or LangChain
+/// templates), with includes expanded and placeholders converted; returns the number exported
 #[tauri::command]
 #[specta::specta]
-pub fn read_prompt_file(app: AppHandle, id: String) -> Result<PromptFile, VaultError> {
-    info!("read_prompt_file called for id: {}", id);
-
-    let config = config::load_config(&app).map_err(|e| VaultError::IoError(e.to_string()))?;
-
-    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
-
-    vault::find_prompt_by_id(Path::new(&vault_path), &id, &config.frontmatter)
-}
+#[tracing::instrument(skip_all)]
+pub async fn export_prompts_for(
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    tool: ExportTool,
+    filter: Option<FilterConfig>,
+    destination_path: String,
+) -> Result<usize, AppError> {
+    info!(
+        "export_prompts_for called: {:?} -> {}",
+        tool, destination_path
+    );
 
-/// Write a prompt file
+    let mut prompts = get_prompts(State::clone(&db), filter, None).await?;
+    for prompt in &mut prompts {
+        prompt.text = key.open(&prompt.text)?;
+        prompt.text = expand_includes(&db.pool(), prompt, &key).await?;
+    }
+    let count = prompts.len();
+    let content = export::render_for_tool(prompts, tool)?;
+
+    vault::blocking(move || {
+        std::fs::write(&destination_path, content)
+            .map_err(|e| AppError::io(format!("Failed to write export: {}", e)))
+    })
+    .await?;
+
+    Ok(count)
+}
+
+/// Import prompts from a JSON, CSV or YAML export into the vault.
+/// `strategy` decides what happens when an item's id or title matches an existing prompt.
 #[tauri::command]
 #[specta::specta]
-pub fn write_prompt_file(app: AppHandle, prompt: PromptFile) -> Result<(), VaultError> {
-    info!("write_prompt_file called for id: {}", prompt.id);
+#[tracing::instrument(skip_all)]
+pub async fn import_prompts(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    path: String,
+    format: ExportFormat,
+    strategy: ImportStrategy,
+) -> Result<Vec<ImportItemReport>, AppError> {
+    info!(
+        "import_prompts called: {} ({:?}, {:?})",
+        path, format, strategy
+    );
 
-    let config = config::load_config(&app).map_err(|e| VaultError::IoError(e.to_string()))?;
+    let config = config_state.get();
 
-    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    let content = vault::blocking(move || {
+        std::fs::read_to_string(&path)
+            .map_err(|e| AppError::io(format!("Failed to read import file: {}", e)))
+    })
+    .await?;
+    let items = export::parse_import(&content, format)?;
+
+    // Title (case-insensitive) -> prompt id, including prompts created by this import
+    let mut titles: HashMap<String, String> = get_prompts(State::clone(&db), None, None)
+        .await?
+        .into_iter()
+        .filter_map(|p| p.title.map(|title| (title.trim().to_lowercase(), p.id)))
+        .collect();
+
+    let key = app.state::<VaultKey>().inner().clone();
+    let (reports, written) = vault_io(&app, &config, move |vault_path, config, self_writes| {
+        let mut reports = Vec::with_capacity(items.len());
+        let mut written = Vec::new();
+        for item in items {
+            let title_key = item
+                .title
+                .as_deref()
+                .map(|title| title.trim().to_lowercase())
+                .filter(|title| !title.is_empty());
+            let mut report = ImportItemReport {
+                source_id: Some(item.id.clone()).filter(|id| !id.trim().is_empty()),
+                title: item.title.clone(),
+                file_path: None,
+                status: ImportStatus::Skipped,
+                message: None,
+            };
+
+            // Keep the exported file name when it is a usable vault path
+            let requested_path =
+                vault::normalize_relative_path(&item.id, &config.frontmatter.file_extensions)
+                    .ok()
+                    .filter(|p| {
+                        vault::has_prompt_extension(p, &config.frontmatter.file_extensions)
+                    });
+            let collision = requested_path
+                .clone()
+                .filter(|p| vault_path.join(p).exists())
+                .or_else(|| title_key.as_ref().and_then(|t| titles.get(t).cloned()));
+
+            let target = match (collision, strategy) {
+                (Some(existing), ImportStrategy::Skip) => {
+                    report.message = Some(format!("Already exists: {}", existing));
+                    report.file_path = Some(existing);
+                    reports.push(report);
+                    continue;
+                }
+                (Some(existing), ImportStrategy::Overwrite) => {
+                    report.status = ImportStatus::Overwritten;
+                    Ok(existing)
+                }
+                (Some(_), ImportStrategy::Duplicate) => {
+                    report.status = ImportStatus::Duplicated;
+                    vault::generate_unique_file_path(
+                        vault_path,
+                        &config.filename_template,
+                        item.title.as_deref(),
+                    )
+                }
+                (None, _) => {
+                    report.status = ImportStatus::Created;
+                    match requested_path {
+                        Some(path) => Ok(path),
+                        None => vault::generate_unique_file_path(
+                            vault_path,
+                            &config.filename_template,
+                            item.title.as_deref(),
+                        ),
+                    }
+                }
+            };
+            let target = match target {
+                Ok(target) => target,
+                Err(e) => {
+                    report.status = ImportStatus::Failed;
+                    report.message = Some(format!("Failed to generate filename: {}", e));
+                    reports.push(report);
+                    continue;
+                }
+            };
+
+            let overwrite = report.status == ImportStatus::Overwritten;
+            let saved = write_imported_prompt(
+                vault_path,
+                config,
+                self_writes,
+                &key,
+                &item,
+                &target,
+                overwrite,
+            );
+            match saved {
+                Ok(file) => {
+                    if let Some(title_key) = title_key {
+                        titles.insert(title_key, target.clone());
+                    }
+                    report.file_path = Some(target);
+                    written.push(file);
+                }
+                Err(e) => {
+                    error!("Failed to import prompt {:?}: {}", item.id, e);
+                    report.status = ImportStatus::Failed;
+                    report.message = Some(e.to_string());
+                }
+            }
+            reports.push(report);
+        }
+        Ok((reports, written))
+    })
+    .await?;
+    cache_written_prompts(&app, &db.pool(), &config, &written).await?;
 
-    vault::write_prompt_file(Path::new(&vault_path), &prompt, &config.frontmatter)
+    info!("import_prompts completed: {} items", reports.len());
+    Ok(reports)
 }
 
-/// Delete a prompt file
+/// Import user messages from a ChatGPT `conversations.json` export as new prompts
 #[tauri::command]
 #[specta::specta]
-pub fn delete_prompt_file(app: AppHandle, id: String) -> Result<(), VaultError> {
-    info!("delete_prompt_file called for id: {}", id);
+#[tracing::instrument(skip_all)]
+pub async fn import_chatgpt_export(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    path: String,
+    options: ChatGptImportOptions,
+) -> Result<Vec<ImportItemReport>, AppError> {
+    info!("import_chatgpt_export called: {} ({:?})", path, options);
+
+    let config = config_state.get();
+
+    let content = vault::blocking(move || {
+        std::fs::read_to_string(&path)
+            .map_err(|e| AppError::io(format!("Failed to read import file: {}", e)))
+    })
+    .await?;
+    let items = chatgpt::parse_conversations(&content, &options)?;
+
+    let key = app.state::<VaultKey>().inner().clone();
+    let (reports, written) = vault_io(&app, &config, move |vault_path, config, self_writes| {
+        // The same prompt is often pasted into many conversations; import it once
+        let mut seen_texts = HashSet::new();
+        let mut reports = Vec::with_capacity(items.len());
+        let mut written = Vec::new();
+        for item in items {
+            let mut report = ImportItemReport {
+                source_id: Some(item.id.clone()).filter(|id| !id.is_empty()),
+                title: item.title.clone(),
+                file_path: None,
+                status: ImportStatus::Created,
+                message: None,
+            };
+            if !seen_texts.insert(item.text.clone()) {
+                report.status = ImportStatus::Skipped;
+                report.message = Some("Duplicate message".to_string());
+                reports.push(report);
+                continue;
+            }
 
-    let config = config::load_config(&app).map_err(|e| VaultError::IoError(e.to_string()))?;
+            let target = vault::generate_unique_file_path(
+                vault_path,
+                &config.filename_template,
+                item.title.as_deref(),
+            );
+            let saved = match target {
+                Ok(target) => write_imported_prompt(
+                    vault_path,
+                    config,
+                    self_writes,
+                    &key,
+                    &item,
+                    &target,
+                    false,
+                ),
+                Err(e) => Err(e.into()),
+            };
+            match saved {
+                Ok(file) => {
+                    report.file_path = Some(file.file_path.clone());
+                    written.push(file);
+                }
+                Err(e) => {
+                    error!("Failed to import ChatGPT message from {:?}: {}", item.id, e);
+                    report.status = ImportStatus::Failed;
+                    report.message = Some(e.to_string());
+                }
+            }
+            reports.push(report);
+        }
+        Ok((reports, written))
+    })
+    .await?;
+    cache_written_prompts(&app, &db.pool(), &config, &written).await?;
 
-    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    info!("import_chatgpt_export completed: {} items", reports.len());
+    Ok(reports)
+}
 
-    vault::delete_prompt_file(Path::new(&vault_path), &id)
-}
-
-/// Start watching the vault for external changes
-#[tauri::command]
-#[specta::specta]
-pub fn start_vault_watch(app: AppHandle, state: State<'_, VaultWatcherState>) -> Result<(), VaultError> {
-    info!("start_vault_watch called");
-
-    let config = config::load_config(&app).map_err(|e| VaultError::IoError(e.to_string()))?;
-    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
-    if !Path::new(&vault_path).exists() {
-        return Err(VaultError::PathNotFound(vault_path));
-    }
-
-    vault_watcher::start_vault_watch(app, &state, vault_path)
-        .map_err(|e| VaultError::IoError(e))?;
-    Ok(())
-}
+/// Copy notes tagged `tag_filter` from an Obsidian vault into the prompt vault.
+/// Notes whose file name is already taken are skipped.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn import_obsidian(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    path: String,
+    tag_filter: String,
+) -> Result<Vec<ImportItemReport>, AppError> {
+    info!("import_obsidian called: {} (tag {})", path, tag_filter);
+
+    let config = config_state.get();
+
+    let tag_filter = tag_filter.trim().trim_start_matches('#').trim().to_string();
+    if tag_filter.is_empty() {
+        return Err(AppError::invalid_input("Tag filter is required"));
+    }
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
+    let key = app.state::<VaultKey>().inner().clone();
+    let (reports, written) = vault_io(&app, &config, move |vault_path, config, self_writes| {
+        let notes = obsidian::find_tagged_notes(Path::new(&path), &tag_filter)?;
+
+        let mut reports = Vec::with_capacity(notes.len());
+        let mut written = Vec::new();
+        for note in notes {
+            let mut report = ImportItemReport {
+                source_id: Some(note.source_path.clone()),
+                title: note.title.clone(),
+                file_path: Some(note.file_name.clone()),
+                status: ImportStatus::Created,
+                message: None,
+            };
+            if vault_path.join(&note.file_name).exists() {
+                report.status = ImportStatus::Skipped;
+                report.message = Some(format!("Already exists: {}", note.file_name));
+                reports.push(report);
+                continue;
+            }
 
-async fn get_tags_for_prompt(
-    pool: &sqlx::Pool<sqlx::Sqlite>,
-    prompt_id: &str,
-) -> Result<Vec<String>, DbError> {
-    let rows = sqlx::query_as::<_, TagNameRow>(SELECT_TAGS_FOR_PROMPT)
-        .bind(prompt_id)
-        .fetch_all(pool)
-        .await?;
+            let input = PromptInput {
+                id: note.file_name.clone(),
+                created: note.created,
+                text: note.text,
+                tags: note.tags,
+                file_path: Some(note.file_name.clone()),
+                previous_file_path: None,
+                title: note.title,
+                description: note.description,
+                color: None,
+                icon: None,
+                notes: None,
+                model_targets: None,
+                language: None,
+                status: None,
+                file_hash: None,
+                custom_fields: Some(note.custom_fields),
+                variant: None,
+                archived: None,
+                expected_revision: None,
+            };
+            match write_prompt_input(vault_path, config, self_writes, &key, &input) {
+                Ok(file) => written.push(file),
+                Err(e) => {
+                    error!("Failed to import note {}: {}", note.source_path, e);
+                    report.status = ImportStatus::Failed;
+                    report.file_path = None;
+                    report.message = Some(e.to_string());
+                }
+            }
+            reports.push(report);
+        }
+        Ok((reports, written))
+    })
+    .await?;
+    cache_written_prompts(&app, &db.pool(), &config, &written).await?;
 
-    Ok(rows.into_iter().map(|r| r.name).collect())
+    info!("import_obsidian completed: {} notes", reports.len());
+    Ok(reports)
 }
 
-async fn get_or_create_tag<'c>(
-    tx: &mut sqlx::Transaction<'c, sqlx::Sqlite>,
-    tag_name: &str,
-) -> Result<String, DbError> {
-    // Try to find existing tag
-    let existing = sqlx::query_as::<_, TagRow>(SELECT_TAG_BY_NAME)
-        .bind(tag_name)
-        .fetch_optional(&mut **tx)
-        .await?;
+/// Export the prompts `ids`, the prompts they include and their tags as a `.promptpack`
+/// archive at `path` (private notes left out unless `share_notes` is on); returns the number
+/// of files packed
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn export_promptpack(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    ids: Vec<String>,
+    path: String,
+) -> Result<usize, AppError> {
+    info!(
+        "export_promptpack called: {} prompts -> {}",
+        ids.len(),
+        path
+    );
 
-    if let Some(tag) = existing {
-        return Ok(tag.id);
+    if ids.is_empty() {
+        return Err(AppError::invalid_input("No prompts to export"));
     }
+    let config = config_state.get();
+
+    vault_io(&app, &config, move |vault_path, config, _| {
+        let mut manifest = promptpack::PackManifest {
+            schema_version: promptpack::PACK_SCHEMA_VERSION,
+            created: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+            prompts: Vec::new(),
+            snippets: Vec::new(),
+            tags: Vec::new(),
+        };
+        let mut files: Vec<PackFile> = Vec::new();
+        let mut tags = Vec::new();
+        // Picked prompts first, then whatever they include (recursively) as snippets
+        let mut pending: VecDeque<(String, bool)> = ids.into_iter().map(|id| (id, true)).collect();
+        while let Some((id, picked)) = pending.pop_front() {
+            let file_path =
+                vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+            if files.iter().any(|file| file.path == file_path) {
+                continue;
+            }
+            let content = std::fs::read_to_string(vault_path.join(&file_path))
+                .map_err(|_| DbError::NotFound(file_path.clone()))?;
+            let file = vault::parse_prompt_file(&file_path, &content, &config.frontmatter);
+            let content = if config.share_notes {
+                content
+            } else {
+                vault::strip_notes(&content)?
+            };
 
-    // Create new tag
-    let id = Uuid::new_v4().to_string();
-    sqlx::query(INSERT_TAG)
-        .bind(&id)
-        .bind(tag_name)
-        .execute(&mut **tx)
+            if picked {
+                tags.push(file.tags);
+                manifest.prompts.push(file_path.clone());
+            } else {
+                manifest.snippets.push(file_path.clone());
+            }
+            pending.extend(
+                template::includes(&file.content)
+                    .into_iter()
+                    .map(|path| (path, false)),
+            );
+            files.push(PackFile {
+                path: file_path,
+                content,
+            });
+        }
+        manifest.tags = promptpack::tag_counts(tags.iter().map(Vec::as_slice));
+
+        let archive = std::fs::File::create(&path)
+            .map_err(|e| AppError::io(format!("Failed to create pack: {}", e)))?;
+        promptpack::write_pack(archive, &manifest, &files)?;
+
+        Ok(files.len())
+    })
+    .await
+}
+
+/// Import the prompts and snippets of a `.promptpack` archive into the vault.
+/// `strategy` decides what happens when a file already exists; duplicated files get a
+/// numbered name and includes of them are updated to match.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn import_promptpack(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    path: String,
+    strategy: ImportStrategy,
+) -> Result<Vec<ImportItemReport>, AppError> {
+    info!("import_promptpack called: {} ({:?})", path, strategy);
+
+    let config = config_state.get();
+
+    let (reports, written) = vault_io(&app, &config, move |vault_path, config, self_writes| {
+        let archive = std::fs::File::open(&path)
+            .map_err(|e| AppError::io(format!("Failed to read pack: {}", e)))?;
+        let (_, files) = promptpack::read_pack(archive)?;
+
+        // Pick every target first so includes can follow files renamed by `Duplicate`
+        // Paths in the pack are taken too, so a duplicate never lands on a file imported later
+        let mut reserved: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+        let mut targets = Vec::with_capacity(files.len());
+        let mut reports = Vec::with_capacity(files.len());
+        let mut renames = HashMap::new();
+        for file in &files {
+            let mut target = file.path.clone();
+            let mut report = ImportItemReport {
+                source_id: Some(file.path.clone()),
+                title: None,
+                file_path: None,
+                status: ImportStatus::Created,
+                message: None,
+            };
+            if vault_path.join(&file.path).exists() {
+                match strategy {
+                    ImportStrategy::Skip => {
+                        report.status = ImportStatus::Skipped;
+                        report.message = Some(format!("Already exists: {}", file.path));
+                    }
+                    ImportStrategy::Overwrite => report.status = ImportStatus::Overwritten,
+                    ImportStrategy::Duplicate => {
+                        target = promptpack::available_path(vault_path, &file.path, &reserved);
+                        reserved.push(target.clone());
+                        renames.insert(file.path.clone(), target.clone());
+                        report.status = ImportStatus::Duplicated;
+                    }
+                }
+            }
+            report.file_path = Some(target.clone());
+            targets.push(target);
+            reports.push(report);
+        }
+
+        let mut written = Vec::new();
+        for ((file, target), report) in files.iter().zip(targets).zip(reports.iter_mut()) {
+            if report.status == ImportStatus::Skipped {
+                continue;
+            }
+            let content = template::rename_includes(&file.content, &renames);
+            match write_raw_prompt(vault_path, config, self_writes, &target, &content) {
+                Ok(prompt) => {
+                    report.title = prompt.file.title.clone();
+                    written.push(prompt);
+                }
+                Err(e) => {
+                    error!("Failed to import {} from pack: {}", file.path, e);
+                    report.status = ImportStatus::Failed;
+                    report.message = Some(e.to_string());
+                }
+            }
+        }
+        Ok((reports, written))
+    })
+    .await?;
+    cache_written_prompts(&app, &db.pool(), &config, &written).await?;
+
+    info!("import_promptpack completed: {} files", reports.len());
+    Ok(reports)
+}
+
+/// Write one imported prompt to `target`; it is cached with the rest of the import.
+/// Overwriting keeps the existing file's custom fields and variants.
+fn write_imported_prompt(
+    vault_path: &Path,
+    config: &AppConfig,
+    self_writes: &SelfWrites,
+    key: &VaultKey,
+    item: &ExportedPrompt,
+    target: &str,
+    overwrite: bool,
+) -> Result<WrittenPrompt, AppError> {
+    if let Some(parent) = vault_path.join(target).parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
+    let input = PromptInput {
+        id: target.to_string(),
+        created: item.created.clone(),
+        text: item.text.clone(),
+        tags: item.tags.clone(),
+        file_path: Some(target.to_string()),
+        previous_file_path: overwrite.then(|| target.to_string()),
+        title: item.title.clone(),
+        description: item.description.clone(),
+        color: None,
+        icon: None,
+        notes: None,
+        model_targets: None,
+        language: None,
+        status: None,
+        file_hash: None,
+        custom_fields: (!overwrite).then(HashMap::new),
+        variant: None,
+        archived: None,
+        expected_revision: None,
+    };
+    write_prompt_input(vault_path, config, self_writes, key, &input)
+}
+
+/// Write the markdown of a prompt file to `target` as is (frontmatter included); it is
+/// cached with the rest of the import
+fn write_raw_prompt(
+    vault_path: &Path,
+    config: &AppConfig,
+    self_writes: &SelfWrites,
+    target: &str,
+    content: &str,
+) -> Result<WrittenPrompt, AppError> {
+    let file_path = vault::normalize_relative_path(target, &config.frontmatter.file_extensions)?;
+    let previous_tags = vault::read_prompt_file(
+        vault_path,
+        &vault_path.join(&file_path),
+        &config.frontmatter,
+    )
+    .ok()
+    .map(|existing| existing.tags);
+
+    vault::write_atomic(&vault_path.join(&file_path), content)
+        .map_err(|e| VaultError::IoError(e.to_string()))?;
+    let file = vault::parse_prompt_file(&file_path, content, &config.frontmatter);
+    self_writes.record(&file_path, file.file_hash.clone());
+
+    Ok(WrittenPrompt {
+        file_path,
+        renamed_from: None,
+        file_hash: file.file_hash.clone(),
+        file,
+        previous_tags,
+    })
+}
+
+/// Get the current and latest schema versions (for debugging)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_schema_version(db: State<'_, DbState>) -> Result<models::SchemaVersion, AppError> {
+    info!("get_schema_version called");
+
+    Ok(models::SchemaVersion {
+        current: migrations::current_version(&db.pool()).await?,
+        latest: migrations::latest_version(),
+    })
+}
+
+/// Get the database file path
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_database_path(db: State<'_, DbState>) -> Result<String, AppError> {
+    info!("get_database_path called");
+
+    let path = sqlx::query("PRAGMA database_list")
+        .fetch_one(&db.pool())
         .await?;
 
-    Ok(id)
+    let db_path: String = path.try_get("file")?;
+
+    Ok(db_path)
+}
+
+/// Reopen the cache at the path the config names now (e.g. after `databasePath` changed)
+/// and return that path; a new cache is empty until the next sync
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn reload_database(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    cache: State<'_, CacheState>,
+    cancellation: State<'_, SyncCancellation>,
+) -> Result<String, AppError> {
+    info!("reload_database called");
+
+    // Open the new cache first so a path that does not work leaves the current one in use
+    let path = db::get_db_path(&app, &config_state.get());
+    let pool = db::connect(&path).await?;
+
+    // A full sync would keep filling the old cache
+    cancellation.cancel();
+    let previous = db.replace(pool);
+    cache.invalidate();
+    // Waits for queries still running on the old pool
+    previous.close().await;
+
+    info!("Reloaded database at {:?}", path);
+    Ok(path.to_string_lossy().into_owned())
+}
+
+// ============================================================================
+// CONFIG COMMANDS
+// ============================================================================
+
+/// Get application configuration
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn get_config(config_state: State<'_, ConfigState>) -> Result<AppConfig, AppError> {
+    info!("get_config called");
+    Ok(config_state.get())
+}
+
+/// Save application configuration
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn save_config(app: AppHandle, config: AppConfig) -> Result<(), AppError> {
+    info!("save_config called");
+    Ok(config::save_config(&app, &config)?)
+}
+
+/// Check a configuration without saving it, for inline feedback in settings
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn validate_config(config: AppConfig) -> Result<Vec<ConfigIssue>, AppError> {
+    info!("validate_config called");
+    Ok(config::validate_config(&config))
+}
+
+/// Get the saved settings profiles
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn list_profiles(config_state: State<'_, ConfigState>) -> Result<Vec<Profile>, AppError> {
+    info!("list_profiles called");
+    Ok(config_state.get().profiles)
+}
+
+/// Switch to another settings profile and return the config it results in; the frontend
+/// then restarts the vault watcher and syncs the cache as after any vault change
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn switch_profile(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    name: String,
+) -> Result<AppConfig, AppError> {
+    info!("switch_profile called: {}", name);
+
+    let mut config = config_state.get();
+    config.switch_profile(&name)?;
+    config::save_config(&app, &config)?;
+    Ok(config_state.get())
+}
+
+// ============================================================================
+// SECRETS
+// ============================================================================
+
+/// Store an API key or token in the OS keyring; a blank value removes it
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn set_secret(name: SecretName, value: String) -> Result<(), AppError> {
+    info!("set_secret called: {:?}", name);
+    Ok(secrets::set_secret(name, &value)?)
+}
+
+/// Whether a secret is stored; its value never goes back to the frontend
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn has_secret(name: SecretName) -> Result<bool, AppError> {
+    info!("has_secret called: {:?}", name);
+    Ok(secrets::has_secret(name)?)
+}
+
+/// Remove an API key or token from the OS keyring; removing one that isn't stored is not an
+/// error
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn delete_secret(name: SecretName) -> Result<(), AppError> {
+    info!("delete_secret called: {:?}", name);
+    Ok(secrets::delete_secret(name)?)
+}
+
+// ============================================================================
+// ENCRYPTION
+// ============================================================================
+
+/// Keep `passphrase` in memory to read and save prompts marked `encrypted`. It is checked
+/// against an encrypted prompt in the cache, if there is one.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn unlock_vault(
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    passphrase: String,
+) -> Result<(), AppError> {
+    info!("unlock_vault called");
+
+    if passphrase.is_empty() {
+        return Err(AppError::invalid_input("Passphrase is empty"));
+    }
+    let sealed = sqlx::query_scalar::<_, String>(SELECT_SEALED_PROMPT_TEXT)
+        .bind(format!("{}%", crypto::SEALED_PREFIX))
+        .fetch_optional(&db.pool())
+        .await?;
+    if let Some(sealed) = sealed {
+        crypto::open(&passphrase, &sealed)?;
+    }
+    key.unlock(passphrase);
+    Ok(())
+}
+
+/// Forget the passphrase; encrypted prompts can't be read or saved until the next unlock
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn lock_vault(key: State<'_, VaultKey>) -> Result<(), AppError> {
+    info!("lock_vault called");
+    key.lock();
+    Ok(())
+}
+
+/// Whether a passphrase is set, so encrypted prompts can be opened
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn is_vault_unlocked(key: State<'_, VaultKey>) -> Result<bool, AppError> {
+    info!("is_vault_unlocked called");
+    Ok(key.is_unlocked())
+}
+
+/// The text of an encrypted prompt for the editor; plain prompts are returned as they are
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn decrypt_prompt(
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+) -> Result<String, AppError> {
+    info!("decrypt_prompt called for id: {}", id);
+
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    Ok(key.open(&prompt.text)?)
+}
+
+// ============================================================================
+// VAULT COMMANDS
+// ============================================================================
+
+/// Scan vault and return all prompt files
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn scan_vault(config_state: State<'_, ConfigState>) -> Result<Vec<PromptFile>, AppError> {
+    info!("scan_vault called");
+
+    let config = config_state.get();
+
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+
+    Ok(vault::scan_vault(Path::new(&vault_path), &config.frontmatter)?.prompts)
+}
+
+/// Sync vault files to database cache (full resync of every file)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn sync_vault(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    cancellation: State<'_, SyncCancellation>,
+) -> Result<SyncStats, AppError> {
+    info!("sync_vault called");
+
+    let config = config_state.get();
+
+    let vault_path_str = config.vault_path.clone().ok_or(VaultError::NotConfigured)?;
+
+    // The vault folder still works as a copy of the server when it can't be reached
+    if let Some(remote) = RemoteBackend::from_config(&config)? {
+        let vault_path = Path::new(&vault_path_str);
+        let extensions = &config.frontmatter.file_extensions;
+        if let Err(e) =
+            vault_backend::sync_remote(vault_path, &remote, extensions, SyncDirection::Both, false)
+                .await
+        {
+            error!("Failed to sync vault with remote: {}", e);
+        }
+    }
+
+    let progress_app = app.clone();
+    let control = SyncControl {
+        cancel: cancellation.token(),
+        on_progress: Arc::new(move |progress| events::emit(&progress_app, progress)),
+    };
+    let stats = sync::sync_vault_with(
+        &db.pool(),
+        Path::new(&vault_path_str),
+        &config.frontmatter,
+        &control,
+    )
+    .await?;
+    events::emit(&app, SyncCompleted(stats.clone()));
+    Ok(stats)
+}
+
+/// Stop running `sync_vault` calls between files; a cancelled sync leaves the cache as it
+/// was and fails with a `cancelled` error
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn cancel_sync(cancellation: State<'_, SyncCancellation>) -> Result<(), AppError> {
+    info!("cancel_sync called");
+
+    cancellation.cancel();
+    Ok(())
+}
+
+/// Two-way sync of the vault folder with the configured server, then re-read what it changed.
+/// Fails with `needsConfirmation` before deleting many local files unless `confirm_deletions`.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn sync_remote_vault(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    confirm_deletions: bool,
+) -> Result<RemoteSyncStats, AppError> {
+    info!("sync_remote_vault called");
+    sync_with_remote(&app, &db.pool(), SyncDirection::Both, confirm_deletions).await
+}
+
+/// Upload vault changes and deletions to the configured server
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn push_vault(
+    app: AppHandle,
+    db: State<'_, DbState>,
+) -> Result<RemoteSyncStats, AppError> {
+    info!("push_vault called");
+    sync_with_remote(&app, &db.pool(), SyncDirection::Push, false).await
+}
+
+/// Apply changes and deletions from the configured server to the vault; see `sync_remote_vault`
+/// for `confirm_deletions`
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn pull_vault(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    confirm_deletions: bool,
+) -> Result<RemoteSyncStats, AppError> {
+    info!("pull_vault called");
+    sync_with_remote(&app, &db.pool(), SyncDirection::Pull, confirm_deletions).await
+}
+
+/// Re-read only the given vault-relative paths into the cache
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn sync_paths(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    paths: Vec<String>,
+) -> Result<SyncStats, AppError> {
+    info!("sync_paths called for {} paths", paths.len());
+
+    let config = config_state.get();
+
+    let vault_path_str = config.vault_path.ok_or(VaultError::NotConfigured)?;
+
+    let stats = sync::sync_paths(
+        &db.pool(),
+        Path::new(&vault_path_str),
+        &config.frontmatter,
+        &paths,
+    )
+    .await?;
+    events::emit(&app, SyncCompleted(stats.clone()));
+    Ok(stats)
+}
+
+/// Read a single prompt file by ID
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn read_prompt_file(
+    config_state: State<'_, ConfigState>,
+    id: String,
+) -> Result<PromptFile, AppError> {
+    info!("read_prompt_file called for id: {}", id);
+
+    let config = config_state.get();
+
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+
+    Ok(vault::find_prompt_by_id(
+        Path::new(&vault_path),
+        &id,
+        &config.frontmatter,
+    )?)
+}
+
+/// Write a prompt file
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn write_prompt_file(
+    config_state: State<'_, ConfigState>,
+    watcher: State<'_, VaultWatcherState>,
+    prompt: PromptFile,
+) -> Result<(), AppError> {
+    info!("write_prompt_file called for id: {}", prompt.id);
+
+    let config = config_state.get();
+
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    let vault_path = Path::new(&vault_path);
+
+    vault::write_prompt_file(vault_path, &prompt, &config.frontmatter)?;
+
+    let relative_path =
+        vault::normalize_relative_path(&prompt.file_path, &config.frontmatter.file_extensions)?;
+    let file_hash = vault::compute_file_hash_from_path(&vault_path.join(&relative_path)).ok();
+    watcher.self_writes().record(&relative_path, file_hash);
+    if config.git_history {
+        commit_vault_history(
+            vault_path,
+            &[&relative_path],
+            &format!("Update prompt {}", relative_path),
+        );
+    }
+    Ok(())
+}
+
+/// Delete a prompt file
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn delete_prompt_file(
+    config_state: State<'_, ConfigState>,
+    watcher: State<'_, VaultWatcherState>,
+    id: String,
+) -> Result<(), AppError> {
+    info!("delete_prompt_file called for id: {}", id);
+
+    let config = config_state.get();
+
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+
+    let relative_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+    watcher.self_writes().record(&relative_path, None);
+    vault::delete_prompt_file(Path::new(&vault_path), &id, &config.frontmatter)?;
+    if config.git_history {
+        commit_vault_history(
+            Path::new(&vault_path),
+            &[&relative_path],
+            &format!("Delete prompt {}", relative_path),
+        );
+    }
+    Ok(())
+}
+
+/// Get the git history of a prompt file (empty when git history was never enabled)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn get_prompt_git_history(
+    config_state: State<'_, ConfigState>,
+    id: String,
+) -> Result<Vec<PromptCommit>, AppError> {
+    info!("get_prompt_git_history called for id: {}", id);
+
+    let config = config_state.get();
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    let relative_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+
+    git::file_history(Path::new(&vault_path), &relative_path)
+        .map_err(|e| AppError::io(format!("Failed to read git history: {}", e)))
+}
+
+/// Restore a prompt file to its contents at commit `sha` and refresh it in the cache
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn restore_prompt_from_commit(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    id: String,
+    sha: String,
+) -> Result<(), AppError> {
+    info!("restore_prompt_from_commit called for id: {} ({})", id, sha);
+
+    let config = config_state.get();
+    let vault_path = PathBuf::from(config.vault_path.clone().ok_or(VaultError::NotConfigured)?);
+    let relative_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+
+    let restored_path = relative_path.clone();
+    vault_io(&app, &config, move |vault_path, config, self_writes| {
+        let relative_path = restored_path;
+        let content = git::file_at_commit(vault_path, &relative_path, &sha)
+            .map_err(|e| AppError::io(format!("Failed to read commit {}: {}", sha, e)))?
+            .ok_or_else(|| DbError::NotFound(format!("{} at {}", relative_path, sha)))?;
+
+        let target = vault_path.join(&relative_path);
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::io(format!("Failed to create folder: {}", e)))?;
+        }
+        self_writes.record(&relative_path, Some(vault::compute_file_hash(&content)));
+        vault::write_atomic(&target, &content)
+            .map_err(|e| AppError::io(format!("Failed to write to vault: {}", e)))?;
+
+        if config.git_history {
+            let short_sha = &sha[..sha.len().min(7)];
+            commit_vault_history(
+                vault_path,
+                &[&relative_path],
+                &format!("Restore prompt {} from {}", relative_path, short_sha),
+            );
+        }
+        Ok(())
+    })
+    .await?;
+
+    sync::sync_paths(
+        &db.pool(),
+        &vault_path,
+        &config.frontmatter,
+        std::slice::from_ref(&relative_path),
+    )
+    .await?;
+
+    if let Some(restored) = load_prompt(&db.pool(), &relative_path).await? {
+        events::emit(&app, PromptSaved(restored));
+    }
+    emit_tag_changed(&app, &db.pool()).await?;
+    Ok(())
+}
+
+/// Diff the text of two prompts (e.g. near-duplicates)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn diff_prompts(
+    db: State<'_, DbState>,
+    id_a: String,
+    id_b: String,
+    word_level: Option<bool>,
+) -> Result<PromptDiff, AppError> {
+    info!("diff_prompts called: {} vs {}", id_a, id_b);
+
+    let mut texts = Vec::with_capacity(2);
+    for id in [&id_a, &id_b] {
+        let row = sqlx::query_as::<_, PromptRow>(SELECT_PROMPT_BY_ID)
+            .bind(id)
+            .fetch_optional(&db.pool())
+            .await?
+            .ok_or_else(|| DbError::NotFound(id.clone()))?;
+        texts.push(row.text);
+    }
+
+    Ok(diff::diff_text(
+        &texts[0],
+        &texts[1],
+        word_level.unwrap_or(false),
+    ))
+}
+
+/// Diff a prompt's text between two commits of its git history.
+/// A missing version (`None`) means the current file in the vault.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn diff_prompt_versions(
+    config_state: State<'_, ConfigState>,
+    id: String,
+    v1: Option<String>,
+    v2: Option<String>,
+    word_level: Option<bool>,
+) -> Result<PromptDiff, AppError> {
+    info!(
+        "diff_prompt_versions called for id: {} ({:?} vs {:?})",
+        id, v1, v2
+    );
+
+    let config = config_state.get();
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    let vault_path = Path::new(&vault_path);
+    let relative_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+
+    // A version where the file did not exist diffs as empty text
+    let version_text = |version: &Option<String>| -> Result<String, VaultError> {
+        let content = match version {
+            Some(sha) => git::file_at_commit(vault_path, &relative_path, sha).map_err(|e| {
+                VaultError::IoError(format!("Failed to read commit {}: {}", sha, e))
+            })?,
+            None => std::fs::read_to_string(vault_path.join(&relative_path)).ok(),
+        };
+        Ok(content
+            .map(|content| {
+                vault::parse_prompt_file(&relative_path, &content, &config.frontmatter).content
+            })
+            .unwrap_or_default())
+    };
+
+    Ok(diff::diff_text(
+        &version_text(&v1)?,
+        &version_text(&v2)?,
+        word_level.unwrap_or(false),
+    ))
+}
+
+/// Default number of results for semantic search and similar prompts
+const DEFAULT_SEMANTIC_RESULTS: usize = 10;
+
+/// Find prompts by meaning rather than exact words.
+/// Embeddings of new or edited prompts are computed first.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn semantic_search(
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    query: String,
+    top_k: Option<usize>,
+) -> Result<Vec<SemanticMatch>, AppError> {
+    info!("semantic_search called with query: {}", query);
+
+    let config = config_state.get();
+    semantic_matches(
+        &db.pool(),
+        &config.embeddings,
+        query,
+        top_k.unwrap_or(DEFAULT_SEMANTIC_RESULTS),
+    )
+    .await
+}
+
+/// Rank cached prompts against `query` (shared by `semantic_search` and the HTTP API)
+pub(crate) async fn semantic_matches(
+    pool: &DbPool,
+    settings: &EmbeddingSettings,
+    query: String,
+    top_k: usize,
+) -> Result<Vec<SemanticMatch>, AppError> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    embeddings::refresh_embeddings(pool, settings).await?;
+    let query_vector = embeddings::embed_texts(settings, &[query])
+        .await?
+        .pop()
+        .unwrap_or_default();
+    let candidates = embeddings::load_embeddings(pool, settings).await?;
+    let ranked = embeddings::rank(&query_vector, &candidates, top_k, None);
+
+    load_semantic_matches(pool, ranked).await
+}
+
+/// Prompts whose embedding is closest to the given prompt's
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn find_similar_prompts(
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    id: String,
+    top_k: Option<usize>,
+) -> Result<Vec<SemanticMatch>, AppError> {
+    info!("find_similar_prompts called for id: {}", id);
+
+    let config = config_state.get();
+    let settings = &config.embeddings;
+
+    embeddings::refresh_embeddings(&db.pool(), settings).await?;
+    let candidates = embeddings::load_embeddings(&db.pool(), settings).await?;
+    let (_, vector) = candidates
+        .iter()
+        .find(|(prompt_id, _)| *prompt_id == id)
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    let ranked = embeddings::rank(
+        vector,
+        &candidates,
+        top_k.unwrap_or(DEFAULT_SEMANTIC_RESULTS),
+        Some(&id),
+    );
+
+    load_semantic_matches(&db.pool(), ranked).await
+}
+
+/// Fill a prompt's `{{variable}}` placeholders and built-ins after expanding its `{{include:...}}`s.
+/// With a `preset`, its saved values are used where `values` has none. Declared variables
+/// supply defaults and fail the render when required ones are missing or a value isn't allowed.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn render_prompt(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+    values: HashMap<String, JsonValue>,
+    preset: Option<String>,
+) -> Result<RenderedPrompt, AppError> {
+    info!("render_prompt called for id: {} (preset {:?})", id, preset);
+
+    let config = config_state.get();
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    let prompt = crypto::open_prompt(prompt, &key)?;
+    let mut merged = match preset {
+        Some(name) => load_template_preset(&db.pool(), &id, &name).await?.values,
+        None => HashMap::new(),
+    };
+    merged.extend(values);
+    template::apply_declared(&prompt.variables, &mut merged)?;
+    let text = expand_includes(&db.pool(), &prompt, &key).await?;
+    let builtins = template_builtins(&app, &db.pool(), &config, &text).await?;
+    Ok(template::render(&text, &merged, &builtins)?)
+}
+
+/// Render a prompt once per combination of `value_sets` (cartesian product), e.g. to
+/// build a benchmark suite from one base prompt
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn render_prompt_matrix(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+    value_sets: BTreeMap<String, Vec<JsonValue>>,
+) -> Result<Vec<MatrixOutput>, AppError> {
+    info!(
+        "render_prompt_matrix called for id: {} ({} variables)",
+        id,
+        value_sets.len()
+    );
+
+    let config = config_state.get();
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    let prompt = crypto::open_prompt(prompt, &key)?;
+    let text = expand_includes(&db.pool(), &prompt, &key).await?;
+    let builtins = template_builtins(&app, &db.pool(), &config, &text).await?;
+    Ok(template::render_matrix(
+        &text,
+        &prompt.variables,
+        &value_sets,
+        &builtins,
+    )?)
+}
+
+/// Estimate how many tokens `text` takes up for `model` (defaults to GPT-4's tokenizer)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn count_tokens(text: String, model: Option<String>) -> TokenCount {
+    info!("count_tokens called for model: {:?}", model);
+    tokens::count_for_model(&text, model.as_deref())
+}
+
+/// Render a prompt, format it per the copy settings, put it on the clipboard and
+/// count the use. Returns what was copied.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn copy_prompt_to_clipboard(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+    values: Option<HashMap<String, JsonValue>>,
+) -> Result<RenderedPrompt, AppError> {
+    info!("copy_prompt_to_clipboard called for id: {}", id);
+
+    let config = config_state.get();
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    let prompt = crypto::open_prompt(prompt, &key)?;
+
+    let text = expand_includes(&db.pool(), &prompt, &key).await?;
+    let mut values = values.unwrap_or_default();
+    template::apply_declared(&prompt.variables, &mut values)?;
+    let builtins = template_builtins(&app, &db.pool(), &config, &text).await?;
+    let rendered = template::render(&text, &values, &builtins)?;
+    let text = template::format_for_copy(&rendered.text, &config.copy);
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| AppError::io(format!("Failed to write clipboard: {}", e)))?;
+    app.state::<ClipboardWatcherState>().record_self_copy(&text);
+
+    sqlx::query(RECORD_PROMPT_USAGE)
+        .bind(&id)
+        .bind(chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string())
+        .execute(&db.pool())
+        .await?;
+
+    Ok(RenderedPrompt {
+        text,
+        missing: rendered.missing,
+    })
+}
+
+/// Save `text` (the clipboard when omitted) as a new prompt, with the capture tags from
+/// settings added to `tags`; meant for a shortcut that grabs prompts on the fly
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn quick_capture(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    text: Option<String>,
+    title: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<Prompt, AppError> {
+    info!("quick_capture called (text given: {})", text.is_some());
+
+    let config = config_state.get();
+    let text = match text {
+        Some(text) => text,
+        None => app
+            .clipboard()
+            .read_text()
+            .map_err(|e| AppError::io(format!("Failed to read clipboard: {}", e)))?,
+    };
+    if text.trim().is_empty() {
+        return Err(AppError::invalid_input("Nothing to capture"));
+    }
+
+    let mut tags = tags.unwrap_or_default();
+    for tag in &config.capture.tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    let title = title.filter(|title| !title.trim().is_empty());
+    let id = create_prompt(&app, &db.pool(), text, title, tags).await?;
+
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    Ok(prompt)
+}
+
+// ============================================================================
+// TEMPLATE PRESETS
+// ============================================================================
+
+/// Save a named set of variable values for a prompt, replacing a preset of the same name
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn save_template_preset(
+    db: State<'_, DbState>,
+    prompt_id: String,
+    name: String,
+    values: HashMap<String, JsonValue>,
+) -> Result<TemplatePreset, AppError> {
+    info!("save_template_preset called: {} for {}", name, prompt_id);
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::invalid_input("Preset name cannot be empty"));
+    }
+    if load_prompt(&db.pool(), &prompt_id).await?.is_none() {
+        return Err(DbError::NotFound(prompt_id).into());
+    }
+
+    sqlx::query(UPSERT_TEMPLATE_PRESET)
+        .bind(&prompt_id)
+        .bind(name)
+        .bind(serde_json::to_string(&values)?)
+        .execute(&db.pool())
+        .await?;
+    load_template_preset(&db.pool(), &prompt_id, name).await
+}
+
+/// Presets saved for a prompt, by name
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn list_template_presets(
+    db: State<'_, DbState>,
+    prompt_id: String,
+) -> Result<Vec<TemplatePreset>, AppError> {
+    info!("list_template_presets called for id: {}", prompt_id);
+
+    let rows = sqlx::query_as::<_, TemplatePresetRow>(SELECT_TEMPLATE_PRESETS)
+        .bind(&prompt_id)
+        .fetch_all(&db.pool())
+        .await?;
+    let presets = rows
+        .into_iter()
+        .map(TemplatePreset::try_from)
+        .collect::<Result<_, _>>()?;
+    Ok(presets)
+}
+
+/// Delete a saved preset
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn delete_template_preset(
+    db: State<'_, DbState>,
+    prompt_id: String,
+    name: String,
+) -> Result<(), AppError> {
+    info!("delete_template_preset called: {} for {}", name, prompt_id);
+
+    let result = sqlx::query(DELETE_TEMPLATE_PRESET)
+        .bind(&prompt_id)
+        .bind(&name)
+        .execute(&db.pool())
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::NotFound(format!("Preset {} of {}", name, prompt_id)).into());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// GLOBAL VARIABLES
+// ============================================================================
+
+/// Set a value every prompt can use as `{{global:name}}`
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn set_global_variable(
+    db: State<'_, DbState>,
+    name: String,
+    value: String,
+) -> Result<(), AppError> {
+    info!("set_global_variable called: {}", name);
+
+    let name = name.trim();
+    if !template::is_variable_name(name) {
+        return Err(AppError::invalid_input(format!(
+            "Invalid global variable name: {:?}",
+            name
+        )));
+    }
+    sqlx::query(UPSERT_GLOBAL)
+        .bind(name)
+        .bind(&value)
+        .execute(&db.pool())
+        .await?;
+    Ok(())
+}
+
+/// Global variables by name
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_global_variables(
+    db: State<'_, DbState>,
+) -> Result<HashMap<String, String>, AppError> {
+    info!("get_global_variables called");
+    Ok(global_variables(&db.pool()).await?)
+}
+
+/// Remove a global variable; prompts using it render its placeholder as missing
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn delete_global_variable(db: State<'_, DbState>, name: String) -> Result<(), AppError> {
+    info!("delete_global_variable called: {}", name);
+
+    let result = sqlx::query(DELETE_GLOBAL)
+        .bind(&name)
+        .execute(&db.pool())
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::NotFound(format!("Global variable {}", name)).into());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// LINT
+// ============================================================================
+
+/// Check one prompt file for tidiness problems
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn lint_prompt(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    id: String,
+) -> Result<PromptLint, AppError> {
+    info!("lint_prompt called for id: {}", id);
+
+    let config = config_state.get();
+    let file = vault_io(&app, &config, move |vault_path, config, _| {
+        Ok(vault::find_prompt_by_id(
+            vault_path,
+            &id,
+            &config.frontmatter,
+        )?)
+    })
+    .await?;
+
+    let prompt_ids: HashSet<String> = sqlx::query_as::<_, PromptIdRow>(SELECT_PROMPT_IDS)
+        .fetch_all(&db.pool())
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+    let builtins =
+        template::Builtins::new(&config.templates, None, global_variables(&db.pool()).await?);
+    let context = LintContext {
+        settings: &config.lint,
+        builtins: &builtins,
+        prompt_ids: &prompt_ids,
+    };
+    Ok(lint::lint(&file, &context))
+}
+
+/// Check every prompt in the vault; only prompts with warnings are returned, sorted by id
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn lint_vault(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+) -> Result<Vec<PromptLint>, AppError> {
+    info!("lint_vault called");
+
+    let config = config_state.get();
+    let files = vault_io(&app, &config, |vault_path, config, _| {
+        Ok(vault::scan_vault(vault_path, &config.frontmatter)?.prompts)
+    })
+    .await?;
+
+    let prompt_ids: HashSet<String> = files.iter().map(|file| file.id.clone()).collect();
+    let builtins =
+        template::Builtins::new(&config.templates, None, global_variables(&db.pool()).await?);
+    let context = LintContext {
+        settings: &config.lint,
+        builtins: &builtins,
+        prompt_ids: &prompt_ids,
+    };
+    let mut reports: Vec<PromptLint> = files
+        .iter()
+        .map(|file| lint::lint(file, &context))
+        .filter(|report| !report.warnings.is_empty())
+        .collect();
+    reports.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(reports)
+}
+
+/// Check the vault for broken prompt files and for drift between the files and the cache
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn check_vault_health(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+) -> Result<VaultHealthReport, AppError> {
+    info!("check_vault_health called");
+
+    let config = config_state.get();
+    if config.vault_path.is_none() {
+        return Err(VaultError::NotConfigured.into());
+    }
+
+    let mut cached_tags: BTreeMap<String, BTreeSet<String>> =
+        sqlx::query_as::<_, PromptIdRow>(SELECT_PROMPT_IDS)
+            .fetch_all(&db.pool())
+            .await?
+            .into_iter()
+            .map(|row| (row.id, BTreeSet::new()))
+            .collect();
+    for row in sqlx::query_as::<_, TagUsageRow>(SELECT_TAG_USAGE)
+        .fetch_all(&db.pool())
+        .await?
+    {
+        if let Some(tags) = row
+            .prompt_id
+            .and_then(|prompt_id| cached_tags.get_mut(&prompt_id))
+        {
+            tags.insert(row.name);
+        }
+    }
+
+    vault_io(&app, &config, move |vault_path, config, _| {
+        Ok(doctor::check_vault(
+            vault_path,
+            &config.frontmatter,
+            &cached_tags,
+        )?)
+    })
+    .await
+}
+
+/// Apply `fixes` to the prompt files that need them and re-sync those files. A dry run
+/// only returns the planned changes.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn repair_vault(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    fixes: Vec<RepairKind>,
+    dry_run: bool,
+) -> Result<VaultRepairReport, AppError> {
+    info!("repair_vault called: {:?} (dry run: {})", fixes, dry_run);
+
+    let config = config_state.get();
+    let vault_path = PathBuf::from(config.vault_path.clone().ok_or(VaultError::NotConfigured)?);
+
+    let report = vault_io(&app, &config, move |vault_path, config, _| {
+        let report = doctor::repair_vault(vault_path, &config.frontmatter, &fixes, dry_run)?;
+        if !dry_run && config.git_history && !report.files.is_empty() {
+            let paths: Vec<&str> = report.files.iter().map(|file| file.path.as_str()).collect();
+            commit_vault_history(vault_path, &paths, "Repair vault");
+        }
+        Ok(report)
+    })
+    .await?;
+    if !dry_run && !report.files.is_empty() {
+        let changed: Vec<String> = report.files.iter().map(|file| file.path.clone()).collect();
+        let stats =
+            sync::sync_paths(&db.pool(), &vault_path, &config.frontmatter, &changed).await?;
+        events::emit(&app, SyncCompleted(stats));
+    }
+    Ok(report)
+}
+
+// ============================================================================
+// RUNS
+// ============================================================================
+
+/// Render a prompt, send it to the configured LLM endpoint and record the run
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn run_prompt(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+    values: Option<HashMap<String, JsonValue>>,
+    model: Option<String>,
+) -> Result<PromptRun, AppError> {
+    info!("run_prompt called for id: {}", id);
+
+    let config = config_state.get();
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    let prompt = crypto::open_prompt(prompt, &key)?;
+    let text = expand_includes(&db.pool(), &prompt, &key).await?;
+    let mut values = values.unwrap_or_default();
+    template::apply_declared(&prompt.variables, &mut values)?;
+    let builtins = template_builtins(&app, &db.pool(), &config, &text).await?;
+    let rendered = template::render(&text, &values, &builtins)?;
+    let model = model
+        .filter(|model| !model.trim().is_empty())
+        .unwrap_or_else(|| config.llm.model.clone());
+
+    execute_run(&db.pool(), &config, &key, &prompt, rendered.text, &model).await
+}
+
+/// Recorded runs of a prompt, newest first. Runs of an encrypted prompt are opened with
+/// the unlocked passphrase.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_prompt_runs(
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+) -> Result<Vec<PromptRun>, AppError> {
+    info!("get_prompt_runs called for id: {}", id);
+
+    let mut runs = sqlx::query_as::<_, PromptRun>(SELECT_PROMPT_RUNS)
+        .bind(&id)
+        .fetch_all(&db.pool())
+        .await?;
+    for run in &mut runs {
+        run.rendered_text = key.open(&run.rendered_text)?;
+        run.response = key.open(&run.response)?;
+    }
+    Ok(runs)
+}
+
+/// Delete a single recorded run
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn delete_prompt_run(db: State<'_, DbState>, run_id: String) -> Result<(), AppError> {
+    info!("delete_prompt_run called for id: {}", run_id);
+
+    let result = sqlx::query(DELETE_PROMPT_RUN)
+        .bind(&run_id)
+        .execute(&db.pool())
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::NotFound(run_id).into());
+    }
+    Ok(())
+}
+
+// ============================================================================
+// PROMPT TESTS
+// ============================================================================
+
+/// Create or update a prompt test
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn save_prompt_test(
+    db: State<'_, DbState>,
+    test: PromptTestInput,
+) -> Result<PromptTest, AppError> {
+    info!(
+        "save_prompt_test called for {}: {}",
+        test.prompt_id, test.name
+    );
+
+    test.validate()?;
+    load_prompt(&db.pool(), &test.prompt_id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(test.prompt_id.clone()))?;
+
+    let id = test.id.unwrap_or_else(|| Uuid::new_v4().to_string());
+    sqlx::query(UPSERT_PROMPT_TEST)
+        .bind(&id)
+        .bind(&test.prompt_id)
+        .bind(test.name.trim())
+        .bind(serde_json::to_string(&test.values)?)
+        .bind(&test.assertion)
+        .bind(test.is_regex)
+        .execute(&db.pool())
+        .await?;
+
+    let row = sqlx::query_as::<_, PromptTestRow>(SELECT_PROMPT_TEST)
+        .bind(&id)
+        .fetch_one(&db.pool())
+        .await?;
+    Ok(PromptTest::try_from(row)?)
+}
+
+/// Tests of a prompt with the outcome of their latest run, by name
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn list_prompt_tests(
+    db: State<'_, DbState>,
+    prompt_id: String,
+) -> Result<Vec<PromptTest>, AppError> {
+    info!("list_prompt_tests called for id: {}", prompt_id);
+
+    let rows = sqlx::query_as::<_, PromptTestRow>(SELECT_PROMPT_TESTS)
+        .bind(&prompt_id)
+        .fetch_all(&db.pool())
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(PromptTest::try_from)
+        .collect::<Result<_, _>>()?)
+}
+
+/// Delete a prompt test and its recorded results
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn delete_prompt_test(db: State<'_, DbState>, id: String) -> Result<(), AppError> {
+    info!("delete_prompt_test called for id: {}", id);
+
+    let result = sqlx::query(DELETE_PROMPT_TEST)
+        .bind(&id)
+        .execute(&db.pool())
+        .await?;
+    if result.rows_affected() == 0 {
+        return Err(DbError::NotFound(format!("Prompt test {}", id)).into());
+    }
+    Ok(())
+}
+
+/// Run every test of a prompt against the configured LLM endpoint (with `model`, or the
+/// configured one) and record whether each response passed its assertion
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn run_prompt_tests(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    prompt_id: String,
+    model: Option<String>,
+) -> Result<Vec<PromptTestResult>, AppError> {
+    info!("run_prompt_tests called for id: {}", prompt_id);
+
+    let config = config_state.get();
+    let prompt = load_prompt(&db.pool(), &prompt_id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(prompt_id.clone()))?;
+    let prompt = crypto::open_prompt(prompt, &key)?;
+    let tests = sqlx::query_as::<_, PromptTestRow>(SELECT_PROMPT_TESTS)
+        .bind(&prompt_id)
+        .fetch_all(&db.pool())
+        .await?
+        .into_iter()
+        .map(PromptTest::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+    let text = expand_includes(&db.pool(), &prompt, &key).await?;
+    let builtins = template_builtins(&app, &db.pool(), &config, &text).await?;
+    let model = model
+        .filter(|model| !model.trim().is_empty())
+        .unwrap_or_else(|| config.llm.model.clone());
+
+    let mut results = Vec::with_capacity(tests.len());
+    for test in tests {
+        let mut values = test.values.clone();
+        template::apply_declared(&prompt.variables, &mut values)?;
+        let rendered = template::render(&text, &values, &builtins)?;
+        let run = execute_run(&db.pool(), &config, &key, &prompt, rendered.text, &model).await?;
+        let passed = test.passes(&run.response)?;
+        sqlx::query(INSERT_PROMPT_TEST_RESULT)
+            .bind(&test.id)
+            .bind(&run.id)
+            .bind(passed)
+            .execute(&db.pool())
+            .await?;
+        results.push(PromptTestResult {
+            test_id: test.id,
+            name: test.name,
+            passed,
+            run,
+        });
+    }
+    Ok(results)
+}
+
+// ============================================================================
+// TAG SUGGESTIONS
+// ============================================================================
+
+/// Ask the configured LLM for tags for a prompt, preferring existing tags
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn suggest_tags_for_prompt(
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    key: State<'_, VaultKey>,
+    id: String,
+) -> Result<TagSuggestions, AppError> {
+    info!("suggest_tags_for_prompt called for id: {}", id);
+
+    let config = config_state.get();
+    let prompt = load_prompt(&db.pool(), &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    let prompt = crypto::open_prompt(prompt, &key)?;
+    let vocabulary = tag_vocabulary(&db.pool()).await?;
+
+    let suggestions = autotag::suggest_tags(
+        &config.llm,
+        &prompt.id,
+        &prompt.text,
+        &prompt.tags,
+        &vocabulary,
+    )
+    .await?;
+    Ok(suggestions)
+}
+
+/// Suggest tags for every prompt without tags. Prompts the LLM fails on are
+/// logged and skipped so one bad reply does not lose the rest of the batch.
+/// Encrypted prompts are never sent.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn suggest_tags_for_untagged(
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+) -> Result<Vec<TagSuggestions>, AppError> {
+    info!("suggest_tags_for_untagged called");
+
+    let config = config_state.get();
+    let vocabulary = tag_vocabulary(&db.pool()).await?;
+    let untagged: Vec<Prompt> = query_prompts(&db.pool(), None, None)
+        .await?
+        .into_iter()
+        .filter(|prompt| prompt.tags.is_empty() && !crypto::is_sealed(&prompt.text))
+        .collect();
+
+    let mut results = Vec::with_capacity(untagged.len());
+    for prompt in untagged {
+        match autotag::suggest_tags(&config.llm, &prompt.id, &prompt.text, &[], &vocabulary).await {
+            Ok(suggestions) => results.push(suggestions),
+            Err(e) => error!("Failed to suggest tags for {}: {}", prompt.id, e),
+        }
+    }
+    Ok(results)
+}
+
+async fn tag_vocabulary(pool: &DbPool) -> Result<Vec<String>, AppError> {
+    let rows = sqlx::query_as::<_, TagRow>(SELECT_ALL_TAGS)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(|row| row.name).collect())
+}
+
+async fn emit_tag_changed(app: &AppHandle, pool: &DbPool) -> Result<(), AppError> {
+    let tags = tag_vocabulary(pool).await?;
+    events::emit(app, TagChanged { tags });
+    Ok(())
+}
+
+/// Whether two tag lists hold the same tags, ignoring order
+fn same_tags(a: &[String], b: &[String]) -> bool {
+    let a: HashSet<&String> = a.iter().collect();
+    let b: HashSet<&String> = b.iter().collect();
+    a == b
+}
+
+// ============================================================================
+// HTTP API
+// ============================================================================
+
+/// Start the local HTTP API on `port` (0 picks a free port); requests must send
+/// `Authorization: Bearer <token>`, with a random token generated when none is given
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn start_api_server(
+    app: AppHandle,
+    state: State<'_, ApiServerState>,
+    port: u16,
+    token: Option<String>,
+) -> Result<ApiServerStatus, AppError> {
+    info!("start_api_server called on port {}", port);
+
+    serve::start_api_server(app, &state, port, token)
+        .await
+        .map_err(AppError::io)?;
+    state.status().map_err(AppError::io)
+}
+
+/// Stop the local HTTP API
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn stop_api_server(state: State<'_, ApiServerState>) -> Result<(), AppError> {
+    info!("stop_api_server called");
+
+    state.stop().map_err(AppError::io)
+}
+
+/// Get whether the HTTP API is running and on which port
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn get_api_server_status(
+    state: State<'_, ApiServerState>,
+) -> Result<ApiServerStatus, AppError> {
+    info!("get_api_server_status called");
+
+    state.status().map_err(AppError::io)
+}
+
+/// Start watching the vault for external changes
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn start_vault_watch(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    state: State<'_, VaultWatcherState>,
+) -> Result<(), AppError> {
+    info!("start_vault_watch called");
+
+    let config = config_state.get();
+    let vault_path = config.vault_path.ok_or(VaultError::NotConfigured)?;
+    if !Path::new(&vault_path).exists() {
+        return Err(VaultError::PathNotFound(vault_path).into());
+    }
+
+    vault_watcher::start_vault_watch(app, &state, vault_path).map_err(AppError::io)?;
+    Ok(())
+}
+
+/// Stop watching the vault (e.g. before switching to another vault path)
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn stop_vault_watch(state: State<'_, VaultWatcherState>) -> Result<(), AppError> {
+    info!("stop_vault_watch called");
+
+    state.stop().map_err(AppError::io)
+}
+
+/// Get the watched path, running flag and last change timestamp
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn get_vault_watch_status(
+    state: State<'_, VaultWatcherState>,
+) -> Result<VaultWatchStatus, AppError> {
+    info!("get_vault_watch_status called");
+
+    state.status().map_err(AppError::io)
+}
+
+/// Turn the clipboard watcher on or off and remember the choice in settings
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn set_clipboard_watch(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    state: State<'_, ClipboardWatcherState>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    info!("set_clipboard_watch called: {}", enabled);
+
+    let mut config = config_state.get();
+    config.capture.watch_clipboard = enabled;
+    config::save_config(&app, &config)?;
+
+    if enabled {
+        clipboard_watcher::start_clipboard_watch(app, &state).map_err(AppError::io)
+    } else {
+        state.stop().map_err(AppError::io)
+    }
+}
+
+/// Get whether the clipboard watcher is running
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn get_clipboard_watch_status(
+    state: State<'_, ClipboardWatcherState>,
+) -> Result<bool, AppError> {
+    info!("get_clipboard_watch_status called");
+
+    state.is_running().map_err(AppError::io)
+}
+
+// ============================================================================
+// BACKUPS
+// ============================================================================
+
+/// Snapshot the vault into the backup folder now, pruning old snapshots like the scheduler
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn create_backup_now(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+) -> Result<BackupInfo, AppError> {
+    info!("create_backup_now called");
+
+    let config = config_state.get();
+    let vault_path_str = config.vault_path.clone().ok_or(VaultError::NotConfigured)?;
+    let dir = backup::backup_dir(&app, &config.backup)?;
+
+    let info = backup::create_backup(
+        Path::new(&vault_path_str),
+        &dir,
+        &config.frontmatter.file_extensions,
+    )?;
+    backup::prune_backups(&dir, config.backup.retention)?;
+    Ok(info)
+}
+
+/// Snapshots in the backup folder, newest first
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn list_backups(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+) -> Result<Vec<BackupInfo>, AppError> {
+    info!("list_backups called");
+
+    let config = config_state.get();
+    backup::list_backups(&backup::backup_dir(&app, &config.backup)?)
+}
+
+/// Replace the vault contents with the snapshot at `path`, then re-sync the cache. The
+/// current vault is snapshotted first so the restore itself can be undone.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn restore_backup(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    path: String,
+) -> Result<SyncStats, AppError> {
+    info!("restore_backup called: {}", path);
+
+    let config = config_state.get();
+    let vault_path = PathBuf::from(config.vault_path.clone().ok_or(VaultError::NotConfigured)?);
+
+    let backup_dir = backup::backup_dir(&app, &config.backup)?;
+    vault_io(&app, &config, move |vault_path, config, _| {
+        let extensions = &config.frontmatter.file_extensions;
+        backup::create_backup(vault_path, &backup_dir, extensions)?;
+        let changed = backup::restore_backup(Path::new(&path), vault_path, extensions)?;
+        if config.git_history && !changed.is_empty() {
+            let paths: Vec<&str> = changed.iter().map(String::as_str).collect();
+            commit_vault_history(vault_path, &paths, "Restore backup");
+        }
+        Ok(())
+    })
+    .await?;
+
+    let stats = sync::sync_vault(&db.pool(), &vault_path, &config.frontmatter).await?;
+    events::emit(&app, SyncCompleted(stats.clone()));
+    Ok(stats)
+}
+
+// ============================================================================
+// SAMPLE VAULT
+// ============================================================================
+
+/// Scaffold a new vault at `path` with the starter prompts of `pack` and views for them.
+/// The vault is not opened; the caller points the config at `path` afterwards.
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn create_sample_vault(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    db: State<'_, DbState>,
+    path: String,
+    pack: SamplePack,
+) -> Result<Vec<String>, AppError> {
+    info!("create_sample_vault called: {} ({:?})", path, pack);
+
+    let config = config_state.get();
+    let frontmatter = config.frontmatter.clone();
+    let written = vault::blocking(move || {
+        sample_vault::create_sample_vault(Path::new(&path), pack, &frontmatter)
+    })
+    .await?;
+
+    let created = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    for (id, name, config_json) in sample_vault::sample_views(pack) {
+        sqlx::query(UPSERT_VIEW)
+            .bind(id)
+            .bind(name)
+            .bind("custom")
+            .bind(config_json)
+            .bind(&created)
+            .execute(&db.pool())
+            .await?;
+        events::emit(
+            &app,
+            ViewSaved(View {
+                id: id.to_string(),
+                name: name.to_string(),
+                view_type: "custom".to_string(),
+                config: serde_json::from_str(config_json)?,
+                created: created.clone(),
+            }),
+        );
+    }
+    Ok(written)
+}
+
+// ============================================================================
+// GIST SHARING
+// ============================================================================
+
+/// Share the markdown file of prompt `id` (frontmatter included, private notes left out unless
+/// `share_notes` is on) as a GitHub gist, using the token from settings
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn share_prompt_as_gist(
+    app: AppHandle,
+    config_state: State<'_, ConfigState>,
+    id: String,
+    public: bool,
+) -> Result<SharedGist, AppError> {
+    info!("share_prompt_as_gist called: {} (public: {})", id, public);
+
+    let config = config_state.get();
+    let token = secrets::get_secret(SecretName::GithubToken)?.ok_or(GistError::MissingToken)?;
+
+    let file_path = vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?;
+    let read_path = file_path.clone();
+    let content = vault_io(&app, &config, move |vault_path, _, _| {
+        std::fs::read_to_string(vault_path.join(&read_path))
+            .map_err(|_| DbError::NotFound(read_path).into())
+    })
+    .await?;
+    let file = vault::parse_prompt_file(&file_path, &content, &config.frontmatter);
+    let description = file.title.unwrap_or_else(|| file_path.clone());
+    let content = if config.share_notes {
+        content
+    } else {
+        vault::strip_notes(&content)?
+    };
+
+    let shared = gist::create_gist(&token, &file_path, &content, &description, public).await?;
+    info!("share_prompt_as_gist completed: {}", shared.url);
+    Ok(shared)
+}
+
+/// Import the markdown file of a gist (its first `.md` file, else its first file) as a new
+/// prompt; a numbered name is used when the file name is taken
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn import_prompt_from_gist(
+    app: AppHandle,
+    db: State<'_, DbState>,
+    url: String,
+) -> Result<ImportItemReport, AppError> {
+    info!("import_prompt_from_gist called: {}", url);
+
+    import_gist(&app, &db.pool(), url).await
+}
+
+// ============================================================================
+// LOGS
+// ============================================================================
+
+/// The newest `limit` log entries at `level` or more severe, oldest first, for bug reports
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub async fn get_recent_logs(
+    logging: State<'_, LoggingState>,
+    level: LogLevel,
+    limit: u32,
+) -> Result<Vec<LogEntry>, AppError> {
+    info!("get_recent_logs called: {:?} (limit {})", level, limit);
+
+    let log_dir = logging.log_dir().to_path_buf();
+    vault::blocking(move || logging::recent_logs(&log_dir, level, limit as usize)).await
+}
+
+/// Record entries at `level` or more severe from now on, until the app restarts
+#[tauri::command]
+#[specta::specta]
+#[tracing::instrument(skip_all)]
+pub fn set_log_level(logging: State<LoggingState>, level: LogLevel) -> Result<(), AppError> {
+    info!("set_log_level called: {:?}", level);
+
+    logging.set_level(level)
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Import a gist as a new prompt (`import_prompt_from_gist`)
+async fn import_gist(
+    app: &AppHandle,
+    pool: &DbPool,
+    url: String,
+) -> Result<ImportItemReport, AppError> {
+    let config = config::current(app);
+    if config.vault_path.is_none() {
+        return Err(VaultError::NotConfigured.into());
+    }
+
+    let gist_id = gist::gist_id(&url)?;
+    let files = gist::fetch_gist(
+        secrets::lookup(SecretName::GithubToken).as_deref(),
+        &gist_id,
+    )
+    .await?;
+    let file = files
+        .iter()
+        .find(|file| file.filename.ends_with(".md"))
+        .or_else(|| files.first())
+        .cloned()
+        .ok_or_else(|| GistError::InvalidResponse("gist has no files".into()))?;
+
+    let (target, written) = vault_io(app, &config, move |vault_path, config, self_writes| {
+        let target = promptpack::available_path(
+            vault_path,
+            &vault::normalize_relative_path(&file.filename, &config.frontmatter.file_extensions)?,
+            &[],
+        );
+        let written = write_raw_prompt(vault_path, config, self_writes, &target, &file.content)?;
+        Ok((target, written))
+    })
+    .await?;
+    let report = ImportItemReport {
+        source_id: Some(url),
+        title: written.file.title.clone(),
+        file_path: Some(target),
+        status: ImportStatus::Created,
+        message: None,
+    };
+    cache_written_prompts(app, pool, &config, &[written]).await?;
+
+    Ok(report)
+}
+
+/// Save `text` as a new vault file named by the filename template; returns its id
+async fn create_prompt(
+    app: &AppHandle,
+    pool: &DbPool,
+    text: String,
+    title: Option<String>,
+    tags: Vec<String>,
+) -> Result<String, AppError> {
+    let config = config::current(app);
+
+    let mut input = PromptInput {
+        id: String::new(),
+        created: Some(chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string()),
+        text,
+        tags,
+        file_path: None,
+        previous_file_path: None,
+        title,
+        description: None,
+        color: None,
+        icon: None,
+        notes: None,
+        model_targets: None,
+        language: None,
+        status: None,
+        file_hash: None,
+        custom_fields: None,
+        variant: None,
+        archived: None,
+        expected_revision: None,
+    };
+    store::apply_new_prompt_defaults(&mut input, &config);
+    let key = app.state::<VaultKey>().inner().clone();
+    let written = vault_io(app, &config, move |vault_path, config, self_writes| {
+        write_prompt_input(vault_path, config, self_writes, &key, &input)
+    })
+    .await?;
+    let id = written.file_path.clone();
+    cache_written_prompts(app, pool, &config, &[written]).await?;
+    Ok(id)
+}
+
+/// Act on a `prompt-manager://` link, then have the frontend show the prompt it was about
+pub(crate) async fn handle_deep_link(app: &AppHandle, url: &str) -> Result<(), AppError> {
+    info!("handle_deep_link called: {}", url);
+
+    let pool = app.state::<DbState>().pool();
+    let id = match deep_link::parse(url)? {
+        DeepLink::Open { id } => {
+            let config = config::current(app);
+            vault::normalize_relative_path(&id, &config.frontmatter.file_extensions)?
+        }
+        DeepLink::New { text, tags } => create_prompt(app, &pool, text, None, tags).await?,
+        DeepLink::Import { url } => import_gist(app, &pool, url)
+            .await?
+            .file_path
+            .unwrap_or_default(),
+    };
+
+    let prompt = load_prompt(&pool, &id)
+        .await?
+        .ok_or_else(|| DbError::NotFound(id.clone()))?;
+    events::emit(app, DeepLinkOpened(prompt));
+    Ok(())
+}
+
+/// Rewrite the tags of one vault file; `None` when they are already as requested
+fn retag_prompt_file(
+    vault_path: &Path,
+    config: &AppConfig,
+    self_writes: &SelfWrites,
+    key: &VaultKey,
+    id: &str,
+    add_tags: &[String],
+    remove_tags: &HashSet<String>,
+) -> Result<Option<WrittenPrompt>, AppError> {
+    let file_path = vault::normalize_relative_path(id, &config.frontmatter.file_extensions)?;
+    let file = vault::read_prompt_file(
+        vault_path,
+        &vault_path.join(&file_path),
+        &config.frontmatter,
+    )?;
+
+    let mut tags: Vec<String> = file
+        .tags
+        .iter()
+        .filter(|tag| !remove_tags.contains(*tag))
+        .cloned()
+        .collect();
+    for tag in add_tags {
+        if !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    if tags == file.tags {
+        return Ok(None);
+    }
+
+    let input = PromptInput {
+        tags,
+        ..rewrite_input(file_path, file)
+    };
+    write_prompt_input(vault_path, config, self_writes, key, &input).map(Some)
+}
+
+/// Cache prompts written by `write_prompt_input` (see `store::cache_written`), then notify
+/// the frontend
+async fn cache_written_prompts(
+    app: &AppHandle,
+    pool: &DbPool,
+    config: &AppConfig,
+    written: &[WrittenPrompt],
+) -> Result<(), AppError> {
+    store::cache_written(pool, &vault_context(app, config)?, written).await?;
+    emit_written(app, pool, written).await
+}
+
+/// Emit the events for prompts saved through the store
+async fn emit_written(
+    app: &AppHandle,
+    pool: &DbPool,
+    written: &[WrittenPrompt],
+) -> Result<(), AppError> {
+    let mut tags_changed = false;
+    for prompt in written {
+        if let Some(prev_path) = &prompt.renamed_from {
+            events::emit(
+                app,
+                PromptRenamed {
+                    old_id: prev_path.clone(),
+                    new_id: prompt.file_path.clone(),
+                },
+            );
+        }
+        if let Some(saved) = load_prompt(pool, &prompt.file_path).await? {
+            events::emit(app, PromptSaved(saved));
+        }
+        let previous_tags = prompt.previous_tags.as_deref().unwrap_or_default();
+        tags_changed |= !same_tags(previous_tags, &prompt.file.tags);
+    }
+    if tags_changed {
+        emit_tag_changed(app, pool).await?;
+    }
+    Ok(())
+}
+
+/// Load ranked prompt ids from the cache, dropping any removed since ranking
+async fn load_semantic_matches(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    ranked: Vec<(String, f32)>,
+) -> Result<Vec<SemanticMatch>, AppError> {
+    let mut matches = Vec::with_capacity(ranked.len());
+    for (id, score) in ranked {
+        if let Some(prompt) = load_prompt(pool, &id).await? {
+            matches.push(SemanticMatch { prompt, score });
+        }
+    }
+    Ok(matches)
+}
+
+/// A saved template preset, or `NotFound`
+async fn load_template_preset(
+    pool: &DbPool,
+    prompt_id: &str,
+    name: &str,
+) -> Result<TemplatePreset, AppError> {
+    let row = sqlx::query_as::<_, TemplatePresetRow>(SELECT_TEMPLATE_PRESET)
+        .bind(prompt_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| DbError::NotFound(format!("Preset {} of {}", name, prompt_id)))?;
+    Ok(TemplatePreset::try_from(row)?)
+}
+
+/// Send rendered prompt text to the configured LLM endpoint and record the run. The run of an
+/// encrypted prompt is stored sealed.
+async fn execute_run(
+    pool: &DbPool,
+    config: &AppConfig,
+    key: &VaultKey,
+    prompt: &Prompt,
+    rendered_text: String,
+    model: &str,
+) -> Result<PromptRun, AppError> {
+    let started = Instant::now();
+    let completion = llm::complete(&config.llm, model, None, &rendered_text).await?;
+    let run = PromptRun {
+        id: Uuid::new_v4().to_string(),
+        prompt_id: prompt.id.clone(),
+        prompt_hash: prompt.file_hash.clone(),
+        rendered_text,
+        provider: llm::provider_name(&config.llm),
+        model: model.to_string(),
+        response: completion.text,
+        prompt_tokens: completion.prompt_tokens,
+        completion_tokens: completion.completion_tokens,
+        latency_ms: started.elapsed().as_millis() as i64,
+        created: chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string(),
+    };
+
+    let (rendered_text, response) = if crypto::wants_encryption(&prompt.custom_fields) {
+        (key.seal(&run.rendered_text)?, key.seal(&run.response)?)
+    } else {
+        (run.rendered_text.clone(), run.response.clone())
+    };
+    sqlx::query(INSERT_PROMPT_RUN)
+        .bind(&run.id)
+        .bind(&run.prompt_id)
+        .bind(&run.prompt_hash)
+        .bind(&rendered_text)
+        .bind(&run.provider)
+        .bind(&run.model)
+        .bind(&response)
+        .bind(run.prompt_tokens)
+        .bind(run.completion_tokens)
+        .bind(run.latency_ms)
+        .bind(&run.created)
+        .execute(pool)
+        .await?;
+
+    Ok(run)
+}
+
+/// Template built-ins for rendering `text`; the clipboard is only read when `text` uses it
+async fn template_builtins(
+    app: &AppHandle,
+    pool: &DbPool,
+    config: &AppConfig,
+    text: &str,
+) -> Result<template::Builtins, AppError> {
+    let clipboard = template::uses_clipboard(text)
+        .then(|| app.clipboard().read_text().ok())
+        .flatten();
+    let globals = global_variables(pool).await?;
+    Ok(template::Builtins::new(
+        &config.templates,
+        clipboard,
+        globals,
+    ))
+}
+
+/// Sync the vault folder with the configured server and re-read the files it changed
+async fn sync_with_remote(
+    app: &AppHandle,
+    pool: &DbPool,
+    direction: SyncDirection,
+    allow_deletions: bool,
+) -> Result<RemoteSyncStats, AppError> {
+    let config = config::current(app);
+    let vault_path_str = config.vault_path.clone().ok_or(VaultError::NotConfigured)?;
+    let vault_path = Path::new(&vault_path_str);
+    let remote = RemoteBackend::from_config(&config)?
+        .ok_or_else(|| AppError::invalid_input("No remote vault configured"))?;
+
+    let stats = vault_backend::sync_remote(
+        vault_path,
+        &remote,
+        &config.frontmatter.file_extensions,
+        direction,
+        allow_deletions,
+    )
+    .await?;
+    let changed: Vec<String> = stats
+        .pulled
+        .iter()
+        .chain(&stats.conflicts)
+        .cloned()
+        .collect();
+    if !changed.is_empty() {
+        let sync_stats = sync::sync_paths(pool, vault_path, &config.frontmatter, &changed).await?;
+        events::emit(app, SyncCompleted(sync_stats));
+    }
+    Ok(stats)
+}
+
+/// `store::vault_io` against the configured vault, recording writes for the vault watcher
+async fn vault_io<T, F>(app: &AppHandle, config: &AppConfig, f: F) -> Result<T, AppError>
+where
+    F: FnOnce(&Path, &AppConfig, &SelfWrites) -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    store::vault_io(&vault_context(app, config)?, f).await
+}
+
+/// Files a save of `prompt` may touch: the file it was loaded from and the one it is saved to
+fn input_paths(prompt: &PromptInput) -> Vec<String> {
+    [
+        Some(&prompt.id),
+        prompt.previous_file_path.as_ref(),
+        prompt.file_path.as_ref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|path| !path.trim().is_empty())
+    .cloned()
+    .collect()
+}
+
+/// The vault of `config`, with the vault watcher's record of the app's own writes and the
+/// passphrase for encrypted prompts
+fn vault_context(app: &AppHandle, config: &AppConfig) -> Result<VaultContext, AppError> {
+    let self_writes = app.state::<VaultWatcherState>().self_writes();
+    Ok(VaultContext {
+        key: app.state::<VaultKey>().inner().clone(),
+        ..VaultContext::new(config, self_writes)?
+    })
+}
+
+fn build_tag_tree(usage: &BTreeMap<String, HashSet<String>>, parent: &str) -> Vec<TagNode> {
+    usage
+        .iter()
+        .filter(|(path, _)| match path.rsplit_once('/') {
+            Some((prefix, _)) => prefix == parent,
+            None => parent.is_empty(),
+        })
+        .map(|(path, prompt_ids)| TagNode {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: path.clone(),
+            prompt_count: prompt_ids.len() as i64,
+            children: build_tag_tree(usage, path),
+        })
+        .collect()
 }
 
 // ============================================================================
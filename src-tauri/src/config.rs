@@ -1,15 +1,39 @@
-use log::info;
+use crate::secrets;
+use crate::vault;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use specta::Type;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
 use tauri::AppHandle;
 use tauri::Manager;
+use tracing::{info, warn};
+
+/// Bundle identifier from `tauri.conf.json`; Tauri names the app config and data folders after it
+pub const APP_IDENTIFIER: &str = "com.qustrolabe.prompt-manager";
+
+/// Layout version of config files written by this release; bump it and add a step to
+/// `MIGRATIONS` when a field is renamed or changes shape
+pub const CONFIG_VERSION: u32 = 2;
+
+/// `MIGRATIONS[n]` upgrades the table of a version `n` config file to version `n + 1`
+const MIGRATIONS: &[fn(&mut toml::Table)] = &[
+    // 0 -> 1: files from before versioning already have the version 1 layout
+    |_| {},
+    // 1 -> 2: API keys, the GitHub token, the WebDAV password and the S3 secret key move to
+    // the OS keyring
+    secrets::move_config_keys,
+];
 
 /// Application configuration stored in TOML format
 #[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct AppConfig {
+    /// Layout version of the file (`CONFIG_VERSION` when saved); files without one are version 0
+    #[serde(default)]
+    pub version: u32,
     /// Path to the vault directory containing prompt markdown files (as string for TypeScript)
     pub vault_path: Option<String>,
     /// UI theme name
@@ -21,6 +45,325 @@ pub struct AppConfig {
     /// Frontmatter preferences
     #[serde(default)]
     pub frontmatter: FrontmatterSettings,
+    /// File name for new prompts; supports `{title}`, `{date}`, `{time}`, `{uuid}` and `{random}`
+    #[serde(default = "default_filename_template")]
+    pub filename_template: String,
+    /// Tags every new prompt file starts with
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    /// Frontmatter fields every new prompt file starts with (e.g. `status = "draft"`); a
+    /// field the prompt sets itself wins
+    #[serde(default)]
+    pub default_frontmatter: BTreeMap<String, JsonValue>,
+    /// Commit every prompt file change to a git repository in the vault
+    #[serde(default)]
+    pub git_history: bool,
+    /// Days a deleted prompt can be recovered from the cache; 0 keeps it until recovered
+    #[serde(default = "default_deleted_retention_days")]
+    pub deleted_retention_days: u32,
+    /// Keep private prompt notes in shared gists and prompt packs (other exports never have them)
+    #[serde(default)]
+    pub share_notes: bool,
+    /// How prompt embeddings for semantic search are computed
+    #[serde(default)]
+    pub embeddings: EmbeddingSettings,
+    /// How prompts are prepared when copied to the clipboard
+    #[serde(default)]
+    pub copy: CopySettings,
+    /// Chat completion endpoint used to run prompts
+    #[serde(default)]
+    pub llm: LlmSettings,
+    /// Built-in template functions like `{{env:VAR}}`
+    #[serde(default)]
+    pub templates: TemplateSettings,
+    /// Thresholds used when linting prompts
+    #[serde(default)]
+    pub lint: LintSettings,
+    /// How `quick_capture` saves prompts
+    #[serde(default)]
+    pub capture: CaptureSettings,
+    /// Server the vault is kept on; `vault_path` then holds its local copy
+    #[serde(default)]
+    pub remote: RemoteVaultSettings,
+    /// Scheduled zip snapshots of the vault
+    #[serde(default)]
+    pub backup: BackupSettings,
+    /// Named vault setups to switch between, e.g. "work" and "personal"
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    /// Profile in use; `vault_path`, `frontmatter` and `view` hold its settings
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Cache database file (or a folder to keep `cache.db` in) instead of the app data
+    /// folder, e.g. on a RAM disk; applied by `reload_database` or on the next start
+    #[serde(default)]
+    pub database_path: Option<String>,
+}
+
+impl AppConfig {
+    /// Make profile `name` active: the current vault, frontmatter and view settings are kept
+    /// in the active profile, then replaced by those of `name`
+    pub fn switch_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        let target = self
+            .profiles
+            .iter()
+            .position(|profile| profile.name == name)
+            .ok_or_else(|| ConfigError::Invalid(format!("No profile named `{}`", name)))?;
+        if let Some(active) = self.active_profile.as_deref() {
+            if let Some(profile) = self.profiles.iter_mut().find(|p| p.name == active) {
+                profile.vault_path = self.vault_path.clone();
+                profile.frontmatter = self.frontmatter.clone();
+                profile.view = self.view.clone();
+            }
+        }
+
+        let profile = &self.profiles[target];
+        self.vault_path = profile.vault_path.clone();
+        self.frontmatter = profile.frontmatter.clone();
+        self.view = profile.view.clone();
+        self.active_profile = Some(profile.name.clone());
+        Ok(())
+    }
+}
+
+/// The per-vault part of the config, saved under a name
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    pub vault_path: Option<String>,
+    #[serde(default)]
+    pub frontmatter: FrontmatterSettings,
+    /// How prompt lists look in this profile
+    #[serde(default)]
+    pub view: ViewSettings,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// Take snapshots in the background
+    #[serde(default)]
+    pub enabled: bool,
+    /// Folder the snapshots are written to; `backups` in the app data directory when unset
+    #[serde(default)]
+    pub directory: Option<String>,
+    /// Hours between scheduled snapshots
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u32,
+    /// Number of snapshots kept; older ones are deleted, 0 keeps them all
+    #[serde(default = "default_backup_retention")]
+    pub retention: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            interval_hours: default_backup_interval_hours(),
+            retention: default_backup_retention(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteVaultSettings {
+    #[serde(default)]
+    pub backend: VaultBackendKind,
+    #[serde(default)]
+    pub webdav: WebDavSettings,
+    #[serde(default)]
+    pub s3: S3Settings,
+}
+
+/// Where the vault files are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum VaultBackendKind {
+    /// Only in the vault folder
+    #[default]
+    Local,
+    /// In a WebDAV folder (e.g. Nextcloud), synced with the vault folder
+    WebDav,
+    /// In an S3-compatible bucket (AWS, MinIO, Backblaze B2), synced with the vault folder
+    S3,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WebDavSettings {
+    /// Folder holding the prompt files, e.g.
+    /// `https://cloud.example.com/remote.php/dav/files/me/prompts/`
+    #[serde(default)]
+    pub url: String,
+    /// Sent with the password or app token stored as `SecretName::WebDavPassword`
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct S3Settings {
+    /// Service URL, e.g. `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000`
+    #[serde(default)]
+    pub endpoint: String,
+    #[serde(default = "default_s3_region")]
+    pub region: String,
+    #[serde(default)]
+    pub bucket: String,
+    /// Folder in the bucket holding the prompt files (e.g. `prompts/`)
+    #[serde(default)]
+    pub prefix: String,
+    /// Paired with the secret key stored as `SecretName::S3SecretAccessKey`
+    #[serde(default)]
+    pub access_key_id: Option<String>,
+}
+
+impl Default for S3Settings {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            region: default_s3_region(),
+            bucket: String::new(),
+            prefix: String::new(),
+            access_key_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureSettings {
+    /// Tags added to every captured prompt (e.g. `inbox`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Suggest saving prompt-like text copied in other apps
+    #[serde(default)]
+    pub watch_clipboard: bool,
+    /// Copies shorter than this many characters are not suggested
+    #[serde(default = "default_capture_min_length")]
+    pub min_length: usize,
+    /// Copies must contain one of these (case-insensitive) to be suggested; empty accepts any
+    #[serde(default = "default_capture_keywords")]
+    pub keywords: Vec<String>,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        Self {
+            tags: Vec::new(),
+            watch_clipboard: false,
+            min_length: default_capture_min_length(),
+            keywords: default_capture_keywords(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LintSettings {
+    /// Prompt lines longer than this many characters are flagged
+    #[serde(default = "default_max_line_length")]
+    pub max_line_length: usize,
+    /// Prompts estimated at more tokens than this are flagged
+    #[serde(default = "default_token_budget")]
+    pub token_budget: usize,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        Self {
+            max_line_length: default_max_line_length(),
+            token_budget: default_token_budget(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateSettings {
+    /// Environment variables `{{env:VAR}}` may read; any other name is rejected
+    #[serde(default)]
+    pub env_allowlist: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmSettings {
+    /// OpenAI-compatible chat completions endpoint
+    #[serde(default = "default_llm_api_url")]
+    pub api_url: String,
+    /// Model used when a run does not name one
+    #[serde(default = "default_llm_model")]
+    pub model: String,
+}
+
+impl Default for LlmSettings {
+    fn default() -> Self {
+        Self {
+            api_url: default_llm_api_url(),
+            model: default_llm_model(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CopySettings {
+    /// Drop leading/trailing blank lines and trailing spaces on each line
+    #[serde(default = "default_trim_whitespace")]
+    pub trim_whitespace: bool,
+    /// Text added before the copied prompt (e.g. an opening code fence)
+    #[serde(default)]
+    pub wrap_prefix: String,
+    /// Text added after the copied prompt
+    #[serde(default)]
+    pub wrap_suffix: String,
+}
+
+impl Default for CopySettings {
+    fn default() -> Self {
+        Self {
+            trim_whitespace: default_trim_whitespace(),
+            wrap_prefix: String::new(),
+            wrap_suffix: String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddingSettings {
+    #[serde(default)]
+    pub provider: EmbeddingProvider,
+    /// OpenAI-compatible embeddings endpoint (used by the `api` provider)
+    #[serde(default = "default_embedding_api_url")]
+    pub api_url: String,
+    #[serde(default = "default_embedding_api_model")]
+    pub api_model: String,
+}
+
+/// Where prompt embeddings come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum EmbeddingProvider {
+    /// Built-in hashed word embedding, no model download or network needed
+    #[default]
+    Local,
+    /// An embeddings API such as a local Ollama server or OpenAI
+    Api,
+}
+
+impl Default for EmbeddingSettings {
+    fn default() -> Self {
+        Self {
+            provider: EmbeddingProvider::default(),
+            api_url: default_embedding_api_url(),
+            api_model: default_embedding_api_model(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -30,6 +373,44 @@ pub struct FrontmatterSettings {
     pub prompt_tags_property: String,
     #[serde(default)]
     pub add_prompts_tag_to_tags: bool,
+    /// Frontmatter key holding the created timestamp (e.g. `date`)
+    #[serde(default = "default_created_property")]
+    pub created_property: String,
+    /// Frontmatter key holding the title (e.g. `aliases`; lists use their first item)
+    #[serde(default = "default_title_property")]
+    pub title_property: String,
+    /// Frontmatter key holding the description
+    #[serde(default = "default_description_property")]
+    pub description_property: String,
+    /// Where the prompt text lives in a file (a `content_mode` frontmatter key overrides it)
+    #[serde(default)]
+    pub content_mode: ContentMode,
+    /// Index Obsidian-style `[[wikilinks]]` in prompt files as links to other prompts
+    #[serde(default)]
+    pub parse_wikilinks: bool,
+    /// Extensions (without the leading dot) of the files treated as prompts, e.g. `txt` or
+    /// `prompt.md`; names without one of them get `.md`. Files that do not end in `.md`
+    /// are read and written as raw text without frontmatter.
+    #[serde(default = "default_file_extensions")]
+    pub file_extensions: Vec<String>,
+    /// Larger files are skipped by scans and syncs instead of being read (0 for no limit)
+    #[serde(default = "default_max_file_size_kb")]
+    pub max_file_size_kb: u64,
+    /// Read and write prompt files with LF line endings and no byte order mark, as Windows
+    /// editors sometimes leave them
+    #[serde(default)]
+    pub normalize_line_endings: bool,
+}
+
+/// Where the prompt text is stored in a markdown file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentMode {
+    /// Inside a ```` ```prompt ```` code fence
+    #[default]
+    Fence,
+    /// The whole body after the frontmatter
+    Body,
 }
 
 impl Default for FrontmatterSettings {
@@ -37,6 +418,14 @@ impl Default for FrontmatterSettings {
         Self {
             prompt_tags_property: default_prompt_tags_property(),
             add_prompts_tag_to_tags: false,
+            created_property: default_created_property(),
+            title_property: default_title_property(),
+            description_property: default_description_property(),
+            content_mode: ContentMode::default(),
+            parse_wikilinks: false,
+            file_extensions: default_file_extensions(),
+            max_file_size_kb: default_max_file_size_kb(),
+            normalize_line_endings: false,
         }
     }
 }
@@ -85,9 +474,95 @@ fn default_show_created_date() -> bool {
     true
 }
 
+fn default_filename_template() -> String {
+    "{date}-{random}".to_string()
+}
+
+fn default_llm_api_url() -> String {
+    "http://localhost:11434/v1/chat/completions".to_string()
+}
+
+fn default_llm_model() -> String {
+    "llama3.2".to_string()
+}
+
+fn default_trim_whitespace() -> bool {
+    true
+}
+
+fn default_max_line_length() -> usize {
+    200
+}
+
+fn default_token_budget() -> usize {
+    4000
+}
+
+fn default_s3_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_backup_interval_hours() -> u32 {
+    24
+}
+
+fn default_backup_retention() -> u32 {
+    10
+}
+
+fn default_deleted_retention_days() -> u32 {
+    30
+}
+
+fn default_capture_min_length() -> usize {
+    200
+}
+
+fn default_capture_keywords() -> Vec<String> {
+    [
+        "you are",
+        "act as",
+        "your task",
+        "step by step",
+        "respond",
+        "write",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_embedding_api_url() -> String {
+    "http://localhost:11434/v1/embeddings".to_string()
+}
+
+fn default_embedding_api_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
 fn default_prompt_tags_property() -> String {
     "tags".to_string()
 }
+
+pub fn default_file_extensions() -> Vec<String> {
+    vec!["md".to_string()]
+}
+
+fn default_max_file_size_kb() -> u64 {
+    1024
+}
+
+fn default_created_property() -> String {
+    "created".to_string()
+}
+
+fn default_title_property() -> String {
+    "title".to_string()
+}
+
+fn default_description_property() -> String {
+    "description".to_string()
+}
 
 /// Get the config file path using Tauri's app config directory
 fn get_config_path(app: &AppHandle) -> Result<PathBuf, ConfigError> {
@@ -99,38 +574,242 @@ fn get_config_path(app: &AppHandle) -> Result<PathBuf, ConfigError> {
     Ok(config_dir.join("config.toml"))
 }
 
-/// Load configuration from disk
+/// Config file the app uses, resolved without a running Tauri app (for the CLI)
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_IDENTIFIER).join("config.toml"))
+}
+
+/// Load configuration from disk; a running app reads it once at startup into `ConfigState`
 pub fn load_config(app: &AppHandle) -> Result<AppConfig, ConfigError> {
-    let config_path = get_config_path(app)?;
+    load_config_from(&get_config_path(app)?)
+}
+
+/// The configuration loaded at startup, kept in managed state and replaced by `save_config`
+#[derive(Default)]
+pub struct ConfigState(RwLock<AppConfig>);
+
+impl ConfigState {
+    pub fn new(config: AppConfig) -> Self {
+        Self(RwLock::new(config))
+    }
+
+    /// A copy of the current configuration
+    pub fn get(&self) -> AppConfig {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn set(&self, config: AppConfig) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = config;
+    }
+}
+
+/// Current configuration of a running app, for code outside commands
+pub fn current(app: &AppHandle) -> AppConfig {
+    app.state::<ConfigState>().get()
+}
 
+/// Load configuration from a specific file (defaults when it does not exist).
+/// Files from older releases are migrated and rewritten in place, next to a
+/// `config.toml.v{version}.bak` copy of the original; sections that no longer parse
+/// fall back to their defaults instead of failing the whole load.
+pub fn load_config_from(config_path: &Path) -> Result<AppConfig, ConfigError> {
     if !config_path.exists() {
         info!("Config file not found, using defaults");
         return Ok(AppConfig::default());
     }
 
     let content =
-        fs::read_to_string(&config_path).map_err(|e| ConfigError::IoError(e.to_string()))?;
+        fs::read_to_string(config_path).map_err(|e| ConfigError::IoError(e.to_string()))?;
 
-    let config: AppConfig =
+    let mut table: toml::Table =
         toml::from_str(&content).map_err(|e| ConfigError::ParseError(e.to_string()))?;
+    let version = table
+        .get("version")
+        .and_then(toml::Value::as_integer)
+        .and_then(|version| u32::try_from(version).ok())
+        .unwrap_or(0);
+    if version > CONFIG_VERSION {
+        warn!(
+            "Config file is version {}, newer than this release (version {}); loading what it can",
+            version, CONFIG_VERSION
+        );
+    }
+    for (from, migrate) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        info!("Migrating config from version {} to {}", from, from + 1);
+        migrate(&mut table);
+    }
+
+    // A key the keyring refused is still in the table; rewriting the file would drop it, so
+    // the file is left as it is and the migration runs again on the next load
+    let keys_left = secrets::has_config_keys(&table);
+    let (mut config, reset) = from_table_lenient(table)?;
+    if keys_left {
+        warn!(
+            "Config file {:?} has keys the keyring refused; leaving it as it is",
+            config_path
+        );
+    } else if version < CONFIG_VERSION || (version == CONFIG_VERSION && !reset.is_empty()) {
+        let mut backup_name = config_path.file_name().unwrap_or_default().to_os_string();
+        backup_name.push(format!(".v{}.bak", version));
+        fs::write(config_path.with_file_name(backup_name), &content)
+            .map_err(|e| ConfigError::IoError(e.to_string()))?;
+        write_config(config_path, &config)?;
+        config.version = CONFIG_VERSION;
+        info!("Upgraded config file {:?}", config_path);
+    }
 
     info!("Loaded config from {:?}", config_path);
     Ok(config)
 }
 
-/// Save configuration to disk
-pub fn save_config(app: &AppHandle, config: &AppConfig) -> Result<(), ConfigError> {
-    let config_path = get_config_path(app)?;
+/// Deserialize `table`, dropping top-level sections that fail to parse so they take their
+/// defaults. Returns the config and the names of the dropped sections.
+fn from_table_lenient(mut table: toml::Table) -> Result<(AppConfig, Vec<String>), ConfigError> {
+    let mut reset = Vec::new();
+    loop {
+        let error = match toml::Value::Table(table.clone()).try_into::<AppConfig>() {
+            Ok(config) => return Ok((config, reset)),
+            Err(e) => e,
+        };
+        let invalid = table
+            .iter()
+            .find(|(key, value)| {
+                let section = toml::Table::from_iter([(key.to_string(), (*value).clone())]);
+                toml::Value::Table(section).try_into::<AppConfig>().is_err()
+            })
+            .map(|(key, _)| key.clone());
+        let Some(key) = invalid else {
+            return Err(ConfigError::ParseError(error.to_string()));
+        };
+        warn!("Resetting config section `{}` to defaults: {}", key, error);
+        table.remove(&key);
+        reset.push(key);
+    }
+}
 
+/// Write `config` to `config_path` as the current layout version
+fn write_config(config_path: &Path, config: &AppConfig) -> Result<(), ConfigError> {
     // Ensure config directory exists
     if let Some(parent) = config_path.parent() {
         fs::create_dir_all(parent).map_err(|e| ConfigError::IoError(e.to_string()))?;
     }
 
+    let config = AppConfig {
+        version: CONFIG_VERSION,
+        ..config.clone()
+    };
     let content =
-        toml::to_string_pretty(config).map_err(|e| ConfigError::SerializeError(e.to_string()))?;
+        toml::to_string_pretty(&config).map_err(|e| ConfigError::SerializeError(e.to_string()))?;
+
+    fs::write(config_path, content).map_err(|e| ConfigError::IoError(e.to_string()))
+}
+
+/// Themes the UI has styles for
+const THEMES: [&str; 2] = ["dark", "light"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ConfigIssueSeverity {
+    /// The config saves, but part of the app will not work with it
+    Warning,
+    /// `save_config` refuses the config
+    Error,
+}
+
+/// A problem with one setting, for the settings page to show next to it
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    pub severity: ConfigIssueSeverity,
+    /// Setting the issue is about, named as in the config file (`vaultPath`, `frontmatter`)
+    pub field: String,
+    pub message: String,
+}
+
+/// Check `config` without saving it
+pub fn validate_config(config: &AppConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let mut issue = |severity, field: &str, message: String| {
+        issues.push(ConfigIssue {
+            severity,
+            field: field.to_string(),
+            message,
+        })
+    };
 
-    fs::write(&config_path, content).map_err(|e| ConfigError::IoError(e.to_string()))?;
+    match config.vault_path.as_deref().map(str::trim) {
+        None | Some("") => issue(
+            ConfigIssueSeverity::Warning,
+            "vaultPath",
+            "No vault folder is set, so prompts cannot be saved".to_string(),
+        ),
+        Some(path) => match fs::metadata(path) {
+            Err(_) => issue(
+                ConfigIssueSeverity::Warning,
+                "vaultPath",
+                format!("`{}` does not exist", path),
+            ),
+            Ok(metadata) if !metadata.is_dir() => issue(
+                ConfigIssueSeverity::Warning,
+                "vaultPath",
+                format!("`{}` is not a folder", path),
+            ),
+            Ok(metadata) if metadata.permissions().readonly() => issue(
+                ConfigIssueSeverity::Warning,
+                "vaultPath",
+                format!("`{}` is read-only, so prompts cannot be saved", path),
+            ),
+            Ok(_) => {}
+        },
+    }
+    if let Err(message) = vault::check_filename_template(&config.filename_template) {
+        issue(ConfigIssueSeverity::Warning, "filenameTemplate", message);
+    }
+    if !THEMES.contains(&config.theme.as_str()) {
+        issue(
+            ConfigIssueSeverity::Warning,
+            "theme",
+            format!("`{}` is not a theme; the dark theme is used", config.theme),
+        );
+    }
+    if let Err(message) = vault::validate_frontmatter_settings(&config.frontmatter) {
+        issue(ConfigIssueSeverity::Error, "frontmatter", message);
+    }
+    for (i, profile) in config.profiles.iter().enumerate() {
+        if profile.name.trim().is_empty() {
+            issue(
+                ConfigIssueSeverity::Warning,
+                "profiles",
+                "A profile has no name, so it cannot be switched to".to_string(),
+            );
+        } else if config.profiles[..i].iter().any(|p| p.name == profile.name) {
+            issue(
+                ConfigIssueSeverity::Warning,
+                "profiles",
+                format!(
+                    "Two profiles are named `{}`; switching picks the first",
+                    profile.name
+                ),
+            );
+        }
+    }
+    issues
+}
+
+/// Save configuration to disk and to `ConfigState`, refusing settings that would mangle
+/// prompt files
+pub fn save_config(app: &AppHandle, config: &AppConfig) -> Result<(), ConfigError> {
+    vault::validate_frontmatter_settings(&config.frontmatter).map_err(ConfigError::Invalid)?;
+    let config_path = get_config_path(app)?;
+    let config = AppConfig {
+        version: CONFIG_VERSION,
+        ..config.clone()
+    };
+    write_config(&config_path, &config)?;
+    app.state::<ConfigState>().set(config);
 
     info!("Saved config to {:?}", config_path);
     Ok(())
@@ -147,4 +826,125 @@ pub enum ConfigError {
     ParseError(String),
     #[error("Serialize error: {0}")]
     SerializeError(String),
+    #[error("Invalid config: {0}")]
+    Invalid(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn temp_config(content: &str) -> (TempDir, PathBuf) {
+        let dir = TempDir::new("config");
+        let path = dir.join("config.toml");
+        fs::write(&path, content).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn test_old_config_is_migrated_with_backup() {
+        let original = "vaultPath = \"/vault\"\ntheme = \"dark\"\nview = \"wide\"\n";
+        let (dir, path) = temp_config(original);
+
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert_eq!(config.vault_path.as_deref(), Some("/vault"));
+        assert_eq!(config.theme, "dark");
+        assert_eq!(
+            toml::to_string(&config.view).unwrap(),
+            toml::to_string(&ViewSettings::default()).unwrap()
+        );
+        assert_eq!(
+            fs::read_to_string(dir.join("config.toml.v0.bak")).unwrap(),
+            original
+        );
+
+        // The rewritten file loads as-is
+        let rewritten = fs::read_to_string(&path).unwrap();
+        assert!(rewritten.starts_with(&format!("version = {}\n", CONFIG_VERSION)));
+        load_config_from(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), rewritten);
+    }
+
+    #[test]
+    fn test_validate_config() {
+        let (dir, _) = temp_config("");
+        let config = AppConfig {
+            vault_path: Some(dir.to_string_lossy().into_owned()),
+            theme: "light".to_string(),
+            filename_template: "{title}-{date}".to_string(),
+            ..Default::default()
+        };
+        assert!(validate_config(&config).is_empty());
+
+        let config = AppConfig {
+            vault_path: Some(dir.join("missing").to_string_lossy().into_owned()),
+            theme: "neon".to_string(),
+            filename_template: "{slug}".to_string(),
+            frontmatter: FrontmatterSettings {
+                title_property: "tags".to_string(),
+                ..Default::default()
+            },
+            ..config
+        };
+        let issues = validate_config(&config)
+            .into_iter()
+            .map(|issue| (issue.field, issue.severity))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            [
+                ("vaultPath".to_string(), ConfigIssueSeverity::Warning),
+                ("filenameTemplate".to_string(), ConfigIssueSeverity::Warning),
+                ("theme".to_string(), ConfigIssueSeverity::Warning),
+                ("frontmatter".to_string(), ConfigIssueSeverity::Error),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_switch_profile() {
+        let profile = |name: &str, vault_path: &str| Profile {
+            name: name.to_string(),
+            vault_path: Some(vault_path.to_string()),
+            frontmatter: FrontmatterSettings::default(),
+            view: ViewSettings::default(),
+        };
+        let mut config = AppConfig {
+            vault_path: Some("/work".to_string()),
+            profiles: vec![profile("work", "/old"), profile("personal", "/home")],
+            active_profile: Some("work".to_string()),
+            ..Default::default()
+        };
+        config.frontmatter.title_property = "name".to_string();
+
+        config.switch_profile("personal").unwrap();
+        assert_eq!(config.vault_path.as_deref(), Some("/home"));
+        assert_eq!(config.frontmatter.title_property, "title");
+        assert_eq!(config.active_profile.as_deref(), Some("personal"));
+
+        // The settings in use when switching away were kept in the profile
+        config.switch_profile("work").unwrap();
+        assert_eq!(config.vault_path.as_deref(), Some("/work"));
+        assert_eq!(config.frontmatter.title_property, "name");
+
+        assert!(matches!(
+            config.switch_profile("missing"),
+            Err(ConfigError::Invalid(_))
+        ));
+        assert_eq!(config.active_profile.as_deref(), Some("work"));
+    }
+
+    #[test]
+    fn test_newer_config_is_not_rewritten() {
+        let original = format!("version = {}\ntheme = \"dark\"\n", CONFIG_VERSION + 1);
+        let (dir, path) = temp_config(&original);
+
+        let config = load_config_from(&path).unwrap();
+        assert_eq!(config.version, CONFIG_VERSION + 1);
+        assert_eq!(config.theme, "dark");
+        assert_eq!(fs::read_to_string(&path).unwrap(), original);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+    }
 }
@@ -0,0 +1,184 @@
+//! Encryption at rest for prompts marked `encrypted: true` in their frontmatter. The prompt
+//! text is stored sealed in the file (and so in the cache); it is opened on demand with the
+//! passphrase given to `unlock_vault`, which is only ever kept in memory.
+
+use crate::models::Prompt;
+use aes_gcm::aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+use specta::Type;
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Frontmatter flag that marks a prompt for encryption
+pub const ENCRYPTED_FIELD: &str = "encrypted";
+
+/// Start of sealed text: `pm-encrypted:v1:` then base64 of salt, nonce and ciphertext
+pub const SEALED_PREFIX: &str = "pm-encrypted:v1:";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// Encryption errors
+#[derive(Debug, Clone, Serialize, thiserror::Error, Type)]
+pub enum CryptoError {
+    #[error("The vault is locked; unlock it to read or save encrypted prompts")]
+    Locked,
+    #[error("Wrong passphrase")]
+    WrongPassphrase,
+    #[error("Invalid encrypted text: {0}")]
+    Invalid(String),
+}
+
+/// Passphrase set by `unlock_vault`, shared by the commands and the vault writes.
+/// Clones share the same passphrase.
+#[derive(Clone, Default)]
+pub struct VaultKey(Arc<RwLock<Option<String>>>);
+
+impl VaultKey {
+    pub fn unlock(&self, passphrase: String) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = Some(passphrase);
+    }
+
+    pub fn lock(&self) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_some()
+    }
+
+    fn passphrase(&self) -> Result<String, CryptoError> {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .ok_or(CryptoError::Locked)
+    }
+
+    /// Seal `text` with the unlocked passphrase
+    pub fn seal(&self, text: &str) -> Result<String, CryptoError> {
+        seal(&self.passphrase()?, text)
+    }
+
+    /// Open sealed `text`; text that is not sealed is returned as it is
+    pub fn open(&self, text: &str) -> Result<String, CryptoError> {
+        if !is_sealed(text) {
+            return Ok(text.to_string());
+        }
+        open(&self.passphrase()?, text)
+    }
+}
+
+/// Whether the frontmatter fields ask for the prompt to be encrypted
+pub fn wants_encryption(custom_fields: &HashMap<String, JsonValue>) -> bool {
+    custom_fields.get(ENCRYPTED_FIELD) == Some(&JsonValue::Bool(true))
+}
+
+pub fn is_sealed(text: &str) -> bool {
+    text.starts_with(SEALED_PREFIX)
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key<Aes256Gcm>, CryptoError> {
+    let mut key = Key::<Aes256Gcm>::default();
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| CryptoError::Invalid(e.to_string()))?;
+    Ok(key)
+}
+
+/// Encrypt `text` with AES-256-GCM under a key derived from `passphrase` (Argon2id with a
+/// fresh salt)
+pub fn seal(passphrase: &str, text: &str) -> Result<String, CryptoError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, &salt)?);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, text.as_bytes())
+        .map_err(|e| CryptoError::Invalid(e.to_string()))?;
+
+    let mut sealed = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(format!("{}{}", SEALED_PREFIX, BASE64.encode(sealed)))
+}
+
+/// Decrypt text written by `seal`
+pub fn open(passphrase: &str, sealed: &str) -> Result<String, CryptoError> {
+    let encoded = sealed
+        .trim()
+        .strip_prefix(SEALED_PREFIX)
+        .ok_or_else(|| CryptoError::Invalid("missing header".to_string()))?;
+    let bytes = BASE64
+        .decode(encoded)
+        .map_err(|e| CryptoError::Invalid(e.to_string()))?;
+    if bytes.len() < SALT_LEN + NONCE_LEN {
+        return Err(CryptoError::Invalid("too short".to_string()));
+    }
+    let (salt, rest) = bytes.split_at(SALT_LEN);
+    let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let cipher = Aes256Gcm::new(&derive_key(passphrase, salt)?);
+    let text = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| CryptoError::WrongPassphrase)?;
+    String::from_utf8(text).map_err(|e| CryptoError::Invalid(e.to_string()))
+}
+
+/// Seal the text of a prompt being saved. Text that is already sealed (a save that only
+/// changed tags or the title) is written as it is.
+pub fn seal_text(text: &str, key: &VaultKey) -> Result<String, CryptoError> {
+    if is_sealed(text) {
+        return Ok(text.to_string());
+    }
+    key.seal(text)
+}
+
+/// `prompt` with its text opened, for commands that render, copy or run it
+pub fn open_prompt(mut prompt: Prompt, key: &VaultKey) -> Result<Prompt, CryptoError> {
+    prompt.text = key.open(&prompt.text)?;
+    Ok(prompt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_open() {
+        let sealed = seal("correct horse", "Client: ACME\nBudget: 10k").unwrap();
+        assert!(is_sealed(&sealed));
+        assert!(!sealed.contains("ACME"));
+        // A fresh salt and nonce every time
+        assert_ne!(
+            sealed,
+            seal("correct horse", "Client: ACME\nBudget: 10k").unwrap()
+        );
+
+        assert_eq!(
+            open("correct horse", &sealed).unwrap(),
+            "Client: ACME\nBudget: 10k"
+        );
+        assert!(matches!(
+            open("wrong", &sealed),
+            Err(CryptoError::WrongPassphrase)
+        ));
+        assert!(matches!(
+            open("correct horse", "pm-encrypted:v1:AAAA"),
+            Err(CryptoError::Invalid(_))
+        ));
+
+        let key = VaultKey::default();
+        assert!(matches!(key.open(&sealed), Err(CryptoError::Locked)));
+        assert_eq!(key.open("plain text").unwrap(), "plain text");
+        key.unlock("correct horse".to_string());
+        assert_eq!(key.open(&sealed).unwrap(), "Client: ACME\nBudget: 10k");
+    }
+}
@@ -0,0 +1,398 @@
+use chrono::Utc;
+use sqlx::{Row, SqliteConnection, SqlitePool};
+use tracing::info;
+
+use super::queries::*;
+
+/// A single schema change applied as part of a migration
+pub enum Step {
+    /// Execute a raw SQL statement
+    Sql(&'static str),
+    /// Add a column unless the table already has it (caches created before versioning)
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+}
+
+/// An ordered, versioned schema migration
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub steps: &'static [Step],
+}
+
+/// All migrations in ascending version order. Never edit an applied migration;
+/// append a new one instead.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        steps: &[
+            Step::Sql(CREATE_PROMPTS_TABLE),
+            Step::Sql(CREATE_TAGS_TABLE),
+            Step::Sql(CREATE_VIEWS_TABLE),
+            Step::Sql(CREATE_PROMPT_TAGS_TABLE),
+            Step::Sql(CREATE_PROMPT_TAGS_INDEX),
+            Step::AddColumn {
+                table: "prompts",
+                column: "title",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "prompts",
+                column: "description",
+                definition: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "custom frontmatter fields",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "custom_fields",
+            definition: "TEXT",
+        }],
+    },
+    Migration {
+        version: 3,
+        description: "prompt embeddings",
+        steps: &[Step::Sql(CREATE_PROMPT_EMBEDDINGS_TABLE)],
+    },
+    Migration {
+        version: 4,
+        description: "prompt usage",
+        steps: &[Step::Sql(CREATE_PROMPT_USAGE_TABLE)],
+    },
+    Migration {
+        version: 5,
+        description: "prompt run history",
+        steps: &[
+            Step::Sql(CREATE_PROMPT_RUNS_TABLE),
+            Step::Sql(CREATE_PROMPT_RUNS_INDEX),
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "prompt token counts",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "token_count",
+            definition: "INTEGER",
+        }],
+    },
+    Migration {
+        version: 7,
+        description: "archived prompts",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "archived",
+            definition: "INTEGER NOT NULL DEFAULT 0",
+        }],
+    },
+    Migration {
+        version: 8,
+        description: "prompt update times",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "updated_at",
+            definition: "TEXT",
+        }],
+    },
+    Migration {
+        version: 9,
+        description: "view ordering",
+        steps: &[
+            Step::AddColumn {
+                table: "views",
+                column: "position",
+                definition: "INTEGER NOT NULL DEFAULT 0",
+            },
+            Step::Sql(NUMBER_VIEW_POSITIONS),
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "prompt links",
+        steps: &[
+            Step::Sql(CREATE_PROMPT_LINKS_TABLE),
+            Step::Sql(CREATE_PROMPT_LINKS_INDEX),
+        ],
+    },
+    Migration {
+        version: 11,
+        description: "template presets",
+        steps: &[Step::Sql(CREATE_TEMPLATE_PRESETS_TABLE)],
+    },
+    Migration {
+        version: 12,
+        description: "declared template variables",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "variables",
+            definition: "TEXT",
+        }],
+    },
+    Migration {
+        version: 13,
+        description: "global template variables",
+        steps: &[Step::Sql(CREATE_GLOBALS_TABLE)],
+    },
+    Migration {
+        version: 14,
+        description: "prompt tests",
+        steps: &[
+            Step::Sql(CREATE_PROMPT_TESTS_TABLE),
+            Step::Sql(CREATE_PROMPT_TESTS_INDEX),
+            Step::Sql(CREATE_PROMPT_TEST_RESULTS_TABLE),
+            Step::Sql(CREATE_PROMPT_TEST_RESULTS_INDEX),
+        ],
+    },
+    Migration {
+        version: 15,
+        description: "prompt revisions",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "revision",
+            definition: "INTEGER NOT NULL DEFAULT 1",
+        }],
+    },
+    Migration {
+        version: 16,
+        description: "recently deleted prompts",
+        steps: &[Step::Sql(CREATE_DELETED_PROMPTS_TABLE)],
+    },
+    Migration {
+        version: 17,
+        description: "prompt color and icon",
+        steps: &[
+            Step::AddColumn {
+                table: "prompts",
+                column: "color",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "prompts",
+                column: "icon",
+                definition: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 18,
+        description: "private prompt notes",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "notes",
+            definition: "TEXT",
+        }],
+    },
+    Migration {
+        version: 19,
+        description: "prompt model targets and language",
+        steps: &[
+            Step::AddColumn {
+                table: "prompts",
+                column: "model_targets",
+                definition: "TEXT",
+            },
+            Step::AddColumn {
+                table: "prompts",
+                column: "language",
+                definition: "TEXT",
+            },
+        ],
+    },
+    Migration {
+        version: 20,
+        description: "prompt status",
+        steps: &[Step::AddColumn {
+            table: "prompts",
+            column: "status",
+            definition: "TEXT",
+        }],
+    },
+    Migration {
+        version: 21,
+        description: "prompt character, word and line counts",
+        steps: &[
+            Step::AddColumn {
+                table: "prompts",
+                column: "char_count",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "prompts",
+                column: "word_count",
+                definition: "INTEGER",
+            },
+            Step::AddColumn {
+                table: "prompts",
+                column: "line_count",
+                definition: "INTEGER",
+            },
+        ],
+    },
+    Migration {
+        version: 22,
+        description: "structured view searches",
+        steps: &[Step::Sql(NEST_VIEW_SEARCHES)],
+    },
+];
+
+/// Latest schema version known to this build
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|m| m.version).unwrap_or(0)
+}
+
+/// Current schema version recorded in the database (0 if none applied)
+pub async fn current_version(pool: &SqlitePool) -> Result<i64, sqlx::Error> {
+    sqlx::query(CREATE_SCHEMA_VERSION_TABLE)
+        .execute(pool)
+        .await?;
+    let row = sqlx::query(SELECT_SCHEMA_VERSION).fetch_one(pool).await?;
+    Ok(row.get::<Option<i64>, _>("version").unwrap_or(0))
+}
+
+/// Apply all pending migrations, each in its own transaction
+pub async fn run_migrations(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let current = current_version(pool).await?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        info!(
+            "Applying migration {}: {}",
+            migration.version, migration.description
+        );
+
+        let mut tx = pool.begin().await?;
+        for step in migration.steps {
+            apply_step(&mut tx, step).await?;
+        }
+        sqlx::query(INSERT_SCHEMA_VERSION)
+            .bind(migration.version)
+            .bind(migration.description)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+async fn apply_step(conn: &mut SqliteConnection, step: &Step) -> Result<(), sqlx::Error> {
+    match step {
+        Step::Sql(sql) => {
+            sqlx::query(sql).execute(&mut *conn).await?;
+        }
+        Step::AddColumn {
+            table,
+            column,
+            definition,
+        } => {
+            let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+                .fetch_all(&mut *conn)
+                .await?;
+            let exists = columns
+                .iter()
+                .any(|row| row.get::<String, _>("name") == *column);
+            if !exists {
+                sqlx::query(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} {}",
+                    table, column, definition
+                ))
+                .execute(&mut *conn)
+                .await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ViewConfig;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_migrations_are_idempotent() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        run_migrations(&pool).await.unwrap();
+        run_migrations(&pool).await.unwrap();
+
+        assert_eq!(current_version(&pool).await.unwrap(), latest_version());
+    }
+
+    #[tokio::test]
+    async fn test_view_searches_are_nested() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        run_migrations(&pool).await.unwrap();
+        for (id, config) in [
+            (
+                "old",
+                r#"{"filter":{"search":"tag:rust review","tags":["x"]}}"#,
+            ),
+            (
+                "new",
+                r#"{"filter":{"search":{"query":"ai","wholeWord":true}}}"#,
+            ),
+            ("none", r#"{"sort":{"by":"title","order":"asc"}}"#),
+        ] {
+            sqlx::query("INSERT INTO views (id, name, config, created) VALUES (?, ?, ?, '')")
+                .bind(id)
+                .bind(id)
+                .bind(config)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        sqlx::query(NEST_VIEW_SEARCHES)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Every saved view, the built-in ones included, reads as a `ViewConfig` again
+        let mut configs: HashMap<String, ViewConfig> = sqlx::query("SELECT id, config FROM views")
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| {
+                let config = serde_json::from_str(&row.get::<String, _>("config")).unwrap();
+                (row.get("id"), config)
+            })
+            .collect();
+        let search = configs
+            .remove("old")
+            .unwrap()
+            .filter
+            .unwrap()
+            .search
+            .unwrap();
+        assert_eq!(search.query, "tag:rust review");
+        assert!(!search.whole_word);
+        let search = configs
+            .remove("new")
+            .unwrap()
+            .filter
+            .unwrap()
+            .search
+            .unwrap();
+        assert_eq!(search.query, "ai");
+        assert!(search.whole_word);
+        assert!(configs.remove("none").unwrap().filter.is_none());
+    }
+}
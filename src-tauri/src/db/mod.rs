@@ -1,34 +1,110 @@
-use log::info;
-use sqlx::{Pool, Row, Sqlite, SqlitePool};
-use std::path::PathBuf;
+use sqlx::{Pool, Sqlite, SqlitePool};
+use std::path::{Path, PathBuf};
+use std::sync::{PoisonError, RwLock};
 use tauri::Manager;
+use tracing::info;
 
+use crate::config::{self, AppConfig, APP_IDENTIFIER};
+
+pub mod migrations;
 pub mod queries;
-use queries::*;
 
 pub type DbPool = Pool<Sqlite>;
 
-/// Get the database path in the app data directory
-fn get_db_path(app_handle: &tauri::AppHandle) -> PathBuf {
-    let path = app_handle
+/// The cache pool in managed state, swappable so `reload_database` can move the cache to
+/// another file while the app runs
+pub struct DbState(RwLock<DbPool>);
+
+impl DbState {
+    pub fn new(pool: DbPool) -> Self {
+        Self(RwLock::new(pool))
+    }
+
+    /// Handle to the current pool; cheap to clone, and still usable after a reload until
+    /// the old pool is closed
+    pub fn pool(&self) -> DbPool {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Put `pool` in place, returning the previous one
+    pub fn replace(&self, pool: DbPool) -> DbPool {
+        std::mem::replace(
+            &mut *self.0.write().unwrap_or_else(PoisonError::into_inner),
+            pool,
+        )
+    }
+}
+
+/// Built-in views (id, name, `ViewConfig` JSON) that every cache starts with and
+/// that cannot be deleted
+pub const SYSTEM_VIEWS: &[(&str, &str, &str)] = &[
+    ("system-all", "All", "{}"),
+    (
+        "system-untagged",
+        "Untagged",
+        r#"{"filter":{"untagged":true}}"#,
+    ),
+    (
+        "system-favorites",
+        "Favorites",
+        r#"{"filter":{"tags":["favorite"]}}"#,
+    ),
+    (
+        "system-recently-used",
+        "Recently used",
+        r#"{"sort":{"by":"last_used","order":"desc"}}"#,
+    ),
+];
+
+/// Get the database path: `database_path` from the config, or `cache.db` in the app data
+/// directory
+pub fn get_db_path(app_handle: &tauri::AppHandle, config: &AppConfig) -> PathBuf {
+    let data_dir = app_handle
         .path()
         .app_data_dir()
-        .expect("failed to get app data dir")
-        .join("cache.db");
+        .expect("failed to get app data dir");
+    resolve_db_path(&data_dir, config.database_path.as_deref())
+}
 
-    // Ensure parent directory exists
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
+/// Cache database the app uses, resolved without a running Tauri app (for the CLI)
+pub fn default_db_path(config: &AppConfig) -> Option<PathBuf> {
+    dirs::data_dir()
+        .map(|dir| resolve_db_path(&dir.join(APP_IDENTIFIER), config.database_path.as_deref()))
+}
 
-    path
+/// `database_path` taken relative to `data_dir`, with `cache.db` inside it when it names a
+/// folder; `cache.db` in `data_dir` when it is not set
+fn resolve_db_path(data_dir: &Path, database_path: Option<&str>) -> PathBuf {
+    match database_path.map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => {
+            let path = data_dir.join(path);
+            if path.is_dir() {
+                path.join("cache.db")
+            } else {
+                path
+            }
+        }
+        None => data_dir.join("cache.db"),
+    }
 }
 
 /// Initialize the database connection pool and create tables
 pub async fn init_db(app_handle: &tauri::AppHandle) -> Result<DbPool, sqlx::Error> {
-    let db_path = get_db_path(app_handle);
+    connect(&get_db_path(app_handle, &config::current(app_handle))).await
+}
+
+/// Open (creating if needed) the cache database at `db_path` and apply migrations
+pub async fn connect(db_path: &Path) -> Result<DbPool, sqlx::Error> {
     info!("Initializing database at: {:?}", db_path);
 
+    // Ensure parent directory exists
+    if let Some(parent) = db_path.parent() {
+        let _ = std::fs::create_dir_all(parent);
+    }
+
     let db_url = format!("sqlite:{}?mode=rwc", db_path.display());
     let pool = SqlitePool::connect(&db_url).await?;
 
@@ -37,47 +113,90 @@ pub async fn init_db(app_handle: &tauri::AppHandle) -> Result<DbPool, sqlx::Erro
         .execute(&pool)
         .await?;
 
-    // Create tables
-    sqlx::query(CREATE_PROMPTS_TABLE).execute(&pool).await?;
-    sqlx::query(CREATE_TAGS_TABLE).execute(&pool).await?;
-    sqlx::query(CREATE_VIEWS_TABLE).execute(&pool).await?;
-    sqlx::query(CREATE_PROMPT_TAGS_TABLE).execute(&pool).await?;
+    // Create tables and apply pending schema changes
+    migrations::run_migrations(&pool).await?;
+    seed_system_views(&pool).await?;
 
-    // Create indexes
-    sqlx::query(CREATE_PROMPT_TAGS_INDEX).execute(&pool).await?;
-
-    ensure_prompt_columns(&pool).await?;
-
-    info!("Database initialized successfully");
-    Ok(pool)
-}
-
-async fn ensure_prompt_columns(pool: &SqlitePool) -> Result<(), sqlx::Error> {
-    let columns = sqlx::query("PRAGMA table_info(prompts)")
-        .fetch_all(pool)
-        .await?;
-    let mut has_title = false;
-    let mut has_description = false;
-    for row in columns {
-        let name: String = row.get("name");
-        if name == "title" {
-            has_title = true;
-        }
-        if name == "description" {
-            has_description = true;
-        }
-    }
+    info!("Database initialized successfully");
+    Ok(pool)
+}
 
-    if !has_title {
-        sqlx::query("ALTER TABLE prompts ADD COLUMN title TEXT")
+/// Insert any missing system views in front of the existing ones, keeping their order
+async fn seed_system_views(pool: &SqlitePool) -> Result<(), sqlx::Error> {
+    let created = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    for (id, name, config) in SYSTEM_VIEWS.iter().rev() {
+        sqlx::query(queries::SEED_SYSTEM_VIEW)
+            .bind(id)
+            .bind(name)
+            .bind(config)
+            .bind(&created)
             .execute(pool)
             .await?;
     }
-    if !has_description {
-        sqlx::query("ALTER TABLE prompts ADD COLUMN description TEXT")
-            .execute(pool)
-            .await?;
-    }
-
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use sqlx::Row;
+
+    #[tokio::test]
+    async fn test_system_views_are_seeded_first() {
+        let dir = TempDir::new("prompt-manager");
+        let db_path = dir.join("cache.db");
+
+        let pool = connect(&db_path).await.unwrap();
+        sqlx::query(queries::UPSERT_VIEW)
+            .bind("custom")
+            .bind("Custom")
+            .bind("custom")
+            .bind("{}")
+            .bind("2024-01-01T00:00:00")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool.close().await;
+
+        // Seeding again must not duplicate or move the system views
+        let pool = connect(&db_path).await.unwrap();
+        let ids: Vec<String> = sqlx::query(queries::SELECT_ALL_VIEWS)
+            .fetch_all(&pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
+        pool.close().await;
+
+        let mut expected: Vec<&str> = SYSTEM_VIEWS.iter().map(|(id, _, _)| *id).collect();
+        expected.push("custom");
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn test_resolve_db_path() {
+        let data_dir = TempDir::new("prompt-manager");
+        std::fs::create_dir_all(data_dir.join("shared")).unwrap();
+
+        assert_eq!(resolve_db_path(&data_dir, None), data_dir.join("cache.db"));
+        assert_eq!(
+            resolve_db_path(&data_dir, Some(" ")),
+            data_dir.join("cache.db")
+        );
+        assert_eq!(
+            resolve_db_path(&data_dir, Some("shared")),
+            data_dir.join("shared").join("cache.db")
+        );
+        assert_eq!(
+            resolve_db_path(&data_dir, Some("other.db")),
+            data_dir.join("other.db")
+        );
+        let ram_disk = std::env::temp_dir().join("ram.db");
+        assert_eq!(
+            resolve_db_path(&data_dir, Some(&ram_disk.to_string_lossy())),
+            ram_disk
+        );
+    }
+}
@@ -43,6 +43,116 @@ CREATE TABLE IF NOT EXISTS prompt_tags (
 )
 "#;
 
+pub const CREATE_PROMPT_EMBEDDINGS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS prompt_embeddings (
+    prompt_id TEXT PRIMARY KEY NOT NULL,
+    model TEXT NOT NULL,
+    text_hash TEXT NOT NULL,
+    vector BLOB NOT NULL,
+    FOREIGN KEY (prompt_id) REFERENCES prompts(id) ON DELETE CASCADE
+)
+"#;
+
+pub const CREATE_PROMPT_USAGE_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS prompt_usage (
+    prompt_id TEXT PRIMARY KEY NOT NULL,
+    use_count INTEGER NOT NULL DEFAULT 0,
+    last_used_at TEXT,
+    FOREIGN KEY (prompt_id) REFERENCES prompts(id) ON DELETE CASCADE
+)
+"#;
+
+/// Run history outlives cache rows (a resync or rename must not drop it), so no foreign key
+pub const CREATE_PROMPT_RUNS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS prompt_runs (
+    id TEXT PRIMARY KEY NOT NULL,
+    prompt_id TEXT NOT NULL,
+    prompt_hash TEXT,
+    rendered_text TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    model TEXT NOT NULL,
+    response TEXT NOT NULL,
+    prompt_tokens INTEGER,
+    completion_tokens INTEGER,
+    latency_ms INTEGER NOT NULL,
+    created TEXT NOT NULL
+)
+"#;
+
+/// Presets outlive cache rows like run history, so no foreign key
+pub const CREATE_TEMPLATE_PRESETS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS template_presets (
+    prompt_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    values_json TEXT NOT NULL,
+    updated TEXT NOT NULL,
+    PRIMARY KEY (prompt_id, name)
+)
+"#;
+
+pub const CREATE_PROMPT_TESTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS prompt_tests (
+    id TEXT PRIMARY KEY NOT NULL,
+    prompt_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    values_json TEXT NOT NULL,
+    assertion TEXT NOT NULL,
+    is_regex INTEGER NOT NULL DEFAULT 0,
+    updated TEXT NOT NULL
+)
+"#;
+
+/// One row per executed test; the response is in the referenced `prompt_runs` row
+pub const CREATE_PROMPT_TEST_RESULTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS prompt_test_results (
+    test_id TEXT NOT NULL,
+    run_id TEXT NOT NULL,
+    passed INTEGER NOT NULL,
+    created TEXT NOT NULL,
+    FOREIGN KEY (test_id) REFERENCES prompt_tests(id) ON DELETE CASCADE
+)
+"#;
+
+/// Values for `{{global:name}}`, shared by every prompt
+pub const CREATE_GLOBALS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS globals (
+    name TEXT PRIMARY KEY NOT NULL,
+    value TEXT NOT NULL,
+    updated TEXT NOT NULL
+)
+"#;
+
+/// Targets are not foreign keys: a wikilink may name a prompt that does not exist yet
+pub const CREATE_PROMPT_LINKS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS prompt_links (
+    source_id TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    relation TEXT NOT NULL,
+    created TEXT NOT NULL,
+    PRIMARY KEY (source_id, target_id, relation),
+    FOREIGN KEY (source_id) REFERENCES prompts(id) ON DELETE CASCADE
+)
+"#;
+
+/// Last file contents of deleted prompts, kept for `deleted_retention_days` so they can be
+/// recovered; keyed by vault-relative path like `prompts`
+pub const CREATE_DELETED_PROMPTS_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS deleted_prompts (
+    id TEXT PRIMARY KEY NOT NULL,
+    title TEXT,
+    content TEXT NOT NULL,
+    deleted_at TEXT NOT NULL
+)
+"#;
+
+pub const CREATE_SCHEMA_VERSION_TABLE: &str = r#"
+CREATE TABLE IF NOT EXISTS schema_version (
+    version INTEGER PRIMARY KEY NOT NULL,
+    description TEXT NOT NULL,
+    applied_at TEXT NOT NULL
+)
+"#;
+
 // ============================================================================
 // INDEXES
 // ============================================================================
@@ -51,41 +161,202 @@ pub const CREATE_PROMPT_TAGS_INDEX: &str = r#"
 CREATE INDEX IF NOT EXISTS idx_prompt_tags_prompt_id ON prompt_tags(prompt_id)
 "#;
 
+pub const CREATE_PROMPT_RUNS_INDEX: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_prompt_runs_prompt_id ON prompt_runs(prompt_id, created)
+"#;
+
+pub const CREATE_PROMPT_TESTS_INDEX: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_prompt_tests_prompt_id ON prompt_tests(prompt_id)
+"#;
+
+pub const CREATE_PROMPT_TEST_RESULTS_INDEX: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_prompt_test_results_test_id ON prompt_test_results(test_id)
+"#;
+
+pub const CREATE_PROMPT_LINKS_INDEX: &str = r#"
+CREATE INDEX IF NOT EXISTS idx_prompt_links_target_id ON prompt_links(target_id)
+"#;
+
 // ============================================================================
 // PROMPTS QUERIES
 // ============================================================================
 
+/// Every prompt with its usage joined in as `u`; `query_prompts` appends the `ORDER BY`
 pub const SELECT_ALL_PROMPTS: &str = r#"
-SELECT id, created, text, title, description, file_path, file_hash
-FROM prompts
-ORDER BY created DESC
+SELECT p.id, p.created, p.text, p.title, p.description, p.file_path, p.file_hash,
+    p.custom_fields, p.token_count, p.archived, p.updated_at, p.variables, p.revision,
+    p.color, p.icon, p.notes, p.model_targets, p.language, p.status, p.char_count,
+    p.word_count, p.line_count
+FROM prompts p
+LEFT JOIN prompt_usage u ON u.prompt_id = p.id
 "#;
 
 pub const SELECT_PROMPT_BY_ID: &str = r#"
-SELECT id, created, text, title, description, file_path, file_hash
+SELECT id, created, text, title, description, file_path, file_hash, custom_fields, token_count,
+    archived, updated_at, variables, revision, color, icon, notes, model_targets,
+    language, status, char_count, word_count, line_count
 FROM prompts
 WHERE id = ?
 "#;
 
+pub const SELECT_PROMPT_REVISION: &str = "SELECT revision FROM prompts WHERE id = ?";
+
+pub const UPDATE_PROMPT_REVISION: &str = "UPDATE prompts SET revision = ? WHERE id = ?";
+
+pub const SELECT_PROMPT_IDS: &str = "SELECT id FROM prompts";
+
+/// Text of one encrypted prompt (bind the sealed text prefix followed by `%`)
+pub const SELECT_SEALED_PROMPT_TEXT: &str = "SELECT text FROM prompts WHERE text LIKE ? LIMIT 1";
+
 pub const UPSERT_PROMPT: &str = r#"
-INSERT INTO prompts (id, created, text, title, description, file_path, file_hash)
-VALUES (?, ?, ?, ?, ?, ?, ?)
+INSERT INTO prompts (
+    id, created, text, title, description, file_path, file_hash, custom_fields, token_count,
+    archived, variables, color, icon, notes, model_targets, language, status, char_count,
+    word_count, line_count, updated_at
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime'))
 ON CONFLICT(id) DO UPDATE SET
     text = excluded.text,
     title = excluded.title,
     description = excluded.description,
     file_path = excluded.file_path,
-    file_hash = excluded.file_hash
+    file_hash = excluded.file_hash,
+    custom_fields = excluded.custom_fields,
+    token_count = excluded.token_count,
+    archived = excluded.archived,
+    variables = excluded.variables,
+    color = excluded.color,
+    icon = excluded.icon,
+    notes = excluded.notes,
+    model_targets = excluded.model_targets,
+    language = excluded.language,
+    status = excluded.status,
+    char_count = excluded.char_count,
+    word_count = excluded.word_count,
+    line_count = excluded.line_count,
+    updated_at = CASE
+        WHEN prompts.file_hash IS excluded.file_hash THEN prompts.updated_at
+        ELSE excluded.updated_at
+    END,
+    revision = CASE
+        WHEN prompts.file_hash IS excluded.file_hash THEN prompts.revision
+        ELSE prompts.revision + 1
+    END
 "#;
 
 pub const DELETE_PROMPT: &str = "DELETE FROM prompts WHERE id = ?";
 
+/// Copy a prompt row under a new id (and file path) when its file is renamed, which counts
+/// as a new revision
+pub const COPY_PROMPT_TO_ID: &str = r#"
+INSERT INTO prompts (
+    id, created, text, title, description, file_path, file_hash, custom_fields, token_count,
+    archived, updated_at, variables, revision, color, icon, notes, model_targets,
+    language, status, char_count, word_count, line_count
+)
+SELECT ?, created, text, title, description, ?, file_hash, custom_fields, token_count, archived,
+    updated_at, variables, revision + 1, color, icon, notes, model_targets, language, status,
+    char_count, word_count, line_count
+FROM prompts
+WHERE id = ?
+"#;
+
+/// Condition on `p` that the prompt has one of the tags in the bound JSON array or a tag
+/// nested under one (`lang` matches `lang/rust`)
+pub const PROMPT_HAS_ANY_TAG: &str = r#"EXISTS (
+    SELECT 1 FROM prompt_tags pt
+    JOIN tags t ON t.id = pt.tag_id
+    JOIN json_each(?) f
+    WHERE pt.prompt_id = p.id
+        AND (t.name = f.value OR substr(t.name, 1, length(f.value) + 1) = f.value || '/')
+)"#;
+
 // ============================================================================
 // TAGS QUERIES
 // ============================================================================
 
 pub const SELECT_ALL_TAGS: &str = "SELECT id, name FROM tags ORDER BY name";
 
+pub const SELECT_TAG_USAGE: &str = r#"
+SELECT t.name, pt.prompt_id
+FROM tags t
+LEFT JOIN prompt_tags pt ON t.id = pt.tag_id
+ORDER BY t.name
+"#;
+
+pub const SELECT_TAG_COUNTS: &str = r#"
+SELECT t.name, COUNT(pt.prompt_id) AS prompt_count
+FROM tags t
+JOIN prompt_tags pt ON t.id = pt.tag_id
+GROUP BY t.id
+ORDER BY t.name
+"#;
+
+/// Tag pairs sharing at least one prompt, each pair listed once (`source < target`)
+pub const SELECT_TAG_PAIRS: &str = r#"
+SELECT a.name AS source, b.name AS target, COUNT(*) AS weight
+FROM prompt_tags x
+JOIN prompt_tags y ON x.prompt_id = y.prompt_id
+JOIN tags a ON a.id = x.tag_id
+JOIN tags b ON b.id = y.tag_id AND a.name < b.name
+GROUP BY a.id, b.id
+ORDER BY weight DESC, source, target
+"#;
+
+// Group members of the prompts whose ids are bound as a JSON array, with the size of each
+// group; prompts outside every group (no tags, no created date, ...) have a NULL key
+
+pub const SELECT_PROMPT_GROUPS_BY_TAG: &str = r#"
+SELECT t.name AS key, p.id AS prompt_id, COUNT(*) OVER (PARTITION BY t.name) AS count
+FROM prompts p
+LEFT JOIN prompt_tags pt ON pt.prompt_id = p.id
+LEFT JOIN tags t ON t.id = pt.tag_id
+WHERE p.id IN (SELECT value FROM json_each(?))
+ORDER BY key IS NULL, key
+"#;
+
+pub const SELECT_PROMPT_GROUPS_BY_CREATED_MONTH: &str = r#"
+SELECT substr(p.created, 1, 7) AS key, p.id AS prompt_id,
+    COUNT(*) OVER (PARTITION BY substr(p.created, 1, 7)) AS count
+FROM prompts p
+WHERE p.id IN (SELECT value FROM json_each(?))
+ORDER BY key IS NULL, key DESC
+"#;
+
+pub const SELECT_PROMPT_GROUPS_BY_MODEL_TARGET: &str = r#"
+SELECT m.value AS key, p.id AS prompt_id, COUNT(*) OVER (PARTITION BY m.value) AS count
+FROM prompts p
+LEFT JOIN json_each(p.model_targets) m
+WHERE p.id IN (SELECT value FROM json_each(?))
+ORDER BY key IS NULL, key
+"#;
+
+pub const SELECT_PROMPT_GROUPS_BY_STATUS: &str = r#"
+SELECT p.status AS key, p.id AS prompt_id, COUNT(*) OVER (PARTITION BY p.status) AS count
+FROM prompts p
+WHERE p.id IN (SELECT value FROM json_each(?))
+ORDER BY CASE key
+    WHEN 'draft' THEN 0
+    WHEN 'testing' THEN 1
+    WHEN 'production' THEN 2
+    WHEN 'deprecated' THEN 3
+    ELSE 4
+END
+"#;
+
+/// The folder is the id up to its last `/` (NULL at the vault root). Prompt files can't be in
+/// subfolders yet, so every prompt lands in the single NULL group.
+pub const SELECT_PROMPT_GROUPS_BY_FOLDER: &str = r#"
+SELECT key, prompt_id, COUNT(*) OVER (PARTITION BY key) AS count
+FROM (
+    SELECT NULLIF(rtrim(rtrim(p.id, replace(p.id, '/', '')), '/'), '') AS key,
+        p.id AS prompt_id
+    FROM prompts p
+    WHERE p.id IN (SELECT value FROM json_each(?))
+)
+ORDER BY key IS NULL, key
+"#;
+
 pub const SELECT_TAG_BY_NAME: &str = "SELECT id, name FROM tags WHERE name = ?";
 
 pub const INSERT_TAG: &str = "INSERT INTO tags (id, name) VALUES (?, ?)";
@@ -100,6 +371,9 @@ ORDER BY t.name
 
 pub const DELETE_PROMPT_TAGS: &str = "DELETE FROM prompt_tags WHERE prompt_id = ?";
 
+pub const UPDATE_PROMPT_TAGS_PROMPT_ID: &str =
+    "UPDATE prompt_tags SET prompt_id = ? WHERE prompt_id = ?";
+
 pub const INSERT_PROMPT_TAG: &str = r#"
 INSERT INTO prompt_tags (prompt_id, tag_id) VALUES (?, ?)
 ON CONFLICT DO NOTHING
@@ -112,7 +386,7 @@ ON CONFLICT DO NOTHING
 pub const SELECT_ALL_VIEWS: &str = r#"
 SELECT id, name, type, config, created
 FROM views
-ORDER BY created DESC
+ORDER BY position, created DESC
 "#;
 
 pub const SELECT_VIEW_BY_ID: &str = r#"
@@ -121,9 +395,10 @@ FROM views
 WHERE id = ?
 "#;
 
+/// New views go after all existing ones; updates keep their position
 pub const UPSERT_VIEW: &str = r#"
-INSERT INTO views (id, name, type, config, created)
-VALUES (?, ?, ?, ?, ?)
+INSERT INTO views (id, name, type, config, created, position)
+VALUES (?, ?, ?, ?, ?, (SELECT COALESCE(MAX(position) + 1, 0) FROM views))
 ON CONFLICT(id) DO UPDATE SET
     name = excluded.name,
     config = excluded.config
@@ -131,6 +406,291 @@ ON CONFLICT(id) DO UPDATE SET
 
 pub const DELETE_VIEW: &str = "DELETE FROM views WHERE id = ?";
 
+pub const UPDATE_VIEW_POSITION: &str = "UPDATE views SET position = ? WHERE id = ?";
+
+/// Number existing views in their previous `created DESC` order
+pub const NUMBER_VIEW_POSITIONS: &str = r#"
+UPDATE views SET position = (
+    SELECT COUNT(*) FROM views AS newer
+    WHERE newer.created > views.created
+        OR (newer.created = views.created AND newer.id < views.id)
+)
+"#;
+
+/// Turn the plain search strings of saved views into `SearchConfig` objects
+pub const NEST_VIEW_SEARCHES: &str = r#"
+UPDATE views
+SET config = json_set(
+    config,
+    '$.filter.search',
+    json_object('query', json_extract(config, '$.filter.search'))
+)
+WHERE json_valid(config) AND json_type(config, '$.filter.search') = 'text'
+"#;
+
+/// Insert a system view in front of all others unless it already exists
+pub const SEED_SYSTEM_VIEW: &str = r#"
+INSERT INTO views (id, name, type, config, created, position)
+VALUES (?, ?, 'system', ?, ?, (SELECT COALESCE(MIN(position) - 1, 0) FROM views))
+ON CONFLICT(id) DO NOTHING
+"#;
+
+// ============================================================================
+// EMBEDDINGS QUERIES
+// ============================================================================
+
+/// Every prompt with the text hash of its embedding for the given model (if any)
+pub const SELECT_PROMPT_EMBEDDING_STATE: &str = r#"
+SELECT p.id, p.text, e.text_hash
+FROM prompts p
+LEFT JOIN prompt_embeddings e ON e.prompt_id = p.id AND e.model = ?
+"#;
+
+pub const SELECT_EMBEDDINGS_FOR_MODEL: &str =
+    "SELECT prompt_id, vector FROM prompt_embeddings WHERE model = ?";
+
+pub const UPSERT_PROMPT_EMBEDDING: &str = r#"
+INSERT INTO prompt_embeddings (prompt_id, model, text_hash, vector)
+VALUES (?, ?, ?, ?)
+ON CONFLICT(prompt_id) DO UPDATE SET
+    model = excluded.model,
+    text_hash = excluded.text_hash,
+    vector = excluded.vector
+"#;
+
+// ============================================================================
+// USAGE QUERIES
+// ============================================================================
+
+pub const RECORD_PROMPT_USAGE: &str = r#"
+INSERT INTO prompt_usage (prompt_id, use_count, last_used_at)
+VALUES (?, 1, ?)
+ON CONFLICT(prompt_id) DO UPDATE SET
+    use_count = use_count + 1,
+    last_used_at = excluded.last_used_at
+"#;
+
+/// Unarchived prompts by when they were last copied, newest first (`?` = limit)
+pub const SELECT_RECENTLY_USED_PROMPTS: &str = r#"
+SELECT p.id, p.created, p.text, p.title, p.description, p.file_path, p.file_hash,
+    p.custom_fields, p.token_count, p.archived, p.updated_at, p.variables, p.revision,
+    p.color, p.icon, p.notes, p.model_targets, p.language, p.status, p.char_count,
+    p.word_count, p.line_count
+FROM prompts p
+JOIN prompt_usage u ON u.prompt_id = p.id
+WHERE p.archived = 0 AND u.last_used_at IS NOT NULL
+ORDER BY u.last_used_at DESC, p.id
+LIMIT ?
+"#;
+
+/// Unarchived prompts by when their file last changed, newest first (`?` = limit)
+pub const SELECT_RECENTLY_EDITED_PROMPTS: &str = r#"
+SELECT p.id, p.created, p.text, p.title, p.description, p.file_path, p.file_hash,
+    p.custom_fields, p.token_count, p.archived, p.updated_at, p.variables, p.revision,
+    p.color, p.icon, p.notes, p.model_targets, p.language, p.status, p.char_count,
+    p.word_count, p.line_count
+FROM prompts p
+WHERE p.archived = 0
+ORDER BY COALESCE(p.updated_at, p.created) DESC, p.id
+LIMIT ?
+"#;
+
+/// Unarchived prompts neither used nor edited since a cutoff, least recently touched first
+/// (`?` = cutoff timestamp)
+pub const SELECT_STALE_PROMPTS: &str = r#"
+SELECT p.id, p.created, p.text, p.title, p.description, p.file_path, p.file_hash,
+    p.custom_fields, p.token_count, p.archived, p.updated_at, p.variables, p.revision,
+    p.color, p.icon, p.notes, p.model_targets, p.language, p.status, p.char_count,
+    p.word_count, p.line_count
+FROM prompts p
+LEFT JOIN prompt_usage u ON u.prompt_id = p.id
+WHERE p.archived = 0
+    AND MAX(COALESCE(p.updated_at, p.created, ''), COALESCE(u.last_used_at, '')) < ?
+ORDER BY MAX(COALESCE(p.updated_at, p.created, ''), COALESCE(u.last_used_at, '')), p.id
+"#;
+
+pub const UPDATE_PROMPT_USAGE_PROMPT_ID: &str =
+    "UPDATE prompt_usage SET prompt_id = ? WHERE prompt_id = ?";
+
+// ============================================================================
+// RUN HISTORY QUERIES
+// ============================================================================
+
+pub const INSERT_PROMPT_RUN: &str = r#"
+INSERT INTO prompt_runs (
+    id, prompt_id, prompt_hash, rendered_text, provider, model, response,
+    prompt_tokens, completion_tokens, latency_ms, created
+)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
+pub const SELECT_PROMPT_RUNS: &str = r#"
+SELECT id, prompt_id, prompt_hash, rendered_text, provider, model, response,
+    prompt_tokens, completion_tokens, latency_ms, created
+FROM prompt_runs
+WHERE prompt_id = ?
+ORDER BY created DESC
+"#;
+
+pub const DELETE_PROMPT_RUN: &str = "DELETE FROM prompt_runs WHERE id = ?";
+
+pub const UPDATE_PROMPT_RUNS_PROMPT_ID: &str =
+    "UPDATE prompt_runs SET prompt_id = ? WHERE prompt_id = ?";
+
+// ============================================================================
+// LINK QUERIES
+// ============================================================================
+
+pub const INSERT_PROMPT_LINK: &str = r#"
+INSERT OR IGNORE INTO prompt_links (source_id, target_id, relation, created)
+VALUES (?, ?, ?, strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime'))
+"#;
+
+pub const DELETE_PROMPT_LINK: &str =
+    "DELETE FROM prompt_links WHERE source_id = ? AND target_id = ? AND relation = ?";
+
+pub const DELETE_PROMPT_LINKS_BY_RELATION: &str =
+    "DELETE FROM prompt_links WHERE source_id = ? AND relation = ?";
+
+pub const SELECT_OUTGOING_LINKS: &str = r#"
+SELECT source_id, target_id, relation FROM prompt_links WHERE source_id = ? ORDER BY created
+"#;
+
+/// Links pointing at any of up to three names of a prompt (id, id without `.md`, file stem)
+pub const SELECT_INCOMING_LINKS: &str = r#"
+SELECT source_id, target_id, relation FROM prompt_links
+WHERE target_id IN (?, ?, ?)
+ORDER BY created
+"#;
+
+pub const UPDATE_PROMPT_LINKS_SOURCE_ID: &str =
+    "UPDATE OR IGNORE prompt_links SET source_id = ? WHERE source_id = ?";
+
+pub const UPDATE_PROMPT_LINKS_TARGET_ID: &str =
+    "UPDATE OR IGNORE prompt_links SET target_id = ? WHERE target_id = ?";
+
+// ============================================================================
+// TEMPLATE PRESET QUERIES
+// ============================================================================
+
+pub const UPSERT_TEMPLATE_PRESET: &str = r#"
+INSERT INTO template_presets (prompt_id, name, values_json, updated)
+VALUES (?, ?, ?, strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime'))
+ON CONFLICT(prompt_id, name) DO UPDATE SET
+    values_json = excluded.values_json,
+    updated = excluded.updated
+"#;
+
+pub const SELECT_TEMPLATE_PRESETS: &str = r#"
+SELECT prompt_id, name, values_json, updated
+FROM template_presets
+WHERE prompt_id = ?
+ORDER BY name
+"#;
+
+pub const SELECT_TEMPLATE_PRESET: &str = r#"
+SELECT prompt_id, name, values_json, updated
+FROM template_presets
+WHERE prompt_id = ? AND name = ?
+"#;
+
+pub const DELETE_TEMPLATE_PRESET: &str =
+    "DELETE FROM template_presets WHERE prompt_id = ? AND name = ?";
+
+pub const UPDATE_TEMPLATE_PRESETS_PROMPT_ID: &str =
+    "UPDATE OR IGNORE template_presets SET prompt_id = ? WHERE prompt_id = ?";
+
+// ============================================================================
+// PROMPT TEST QUERIES
+// ============================================================================
+
+pub const UPSERT_PROMPT_TEST: &str = r#"
+INSERT INTO prompt_tests (id, prompt_id, name, values_json, assertion, is_regex, updated)
+VALUES (?, ?, ?, ?, ?, ?, strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime'))
+ON CONFLICT(id) DO UPDATE SET
+    name = excluded.name,
+    values_json = excluded.values_json,
+    assertion = excluded.assertion,
+    is_regex = excluded.is_regex,
+    updated = excluded.updated
+"#;
+
+pub const SELECT_PROMPT_TESTS: &str = r#"
+SELECT t.id, t.prompt_id, t.name, t.values_json, t.assertion, t.is_regex, t.updated,
+    (SELECT r.passed FROM prompt_test_results r WHERE r.test_id = t.id
+     ORDER BY r.rowid DESC LIMIT 1) AS last_passed
+FROM prompt_tests t
+WHERE t.prompt_id = ?
+ORDER BY t.name
+"#;
+
+pub const SELECT_PROMPT_TEST: &str = r#"
+SELECT t.id, t.prompt_id, t.name, t.values_json, t.assertion, t.is_regex, t.updated,
+    (SELECT r.passed FROM prompt_test_results r WHERE r.test_id = t.id
+     ORDER BY r.rowid DESC LIMIT 1) AS last_passed
+FROM prompt_tests t
+WHERE t.id = ?
+"#;
+
+pub const DELETE_PROMPT_TEST: &str = "DELETE FROM prompt_tests WHERE id = ?";
+
+pub const INSERT_PROMPT_TEST_RESULT: &str = r#"
+INSERT INTO prompt_test_results (test_id, run_id, passed, created)
+VALUES (?, ?, ?, strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime'))
+"#;
+
+pub const UPDATE_PROMPT_TESTS_PROMPT_ID: &str =
+    "UPDATE prompt_tests SET prompt_id = ? WHERE prompt_id = ?";
+
+// ============================================================================
+// GLOBAL VARIABLE QUERIES
+// ============================================================================
+
+pub const UPSERT_GLOBAL: &str = r#"
+INSERT INTO globals (name, value, updated)
+VALUES (?, ?, strftime('%Y-%m-%dT%H:%M:%S', 'now', 'localtime'))
+ON CONFLICT(name) DO UPDATE SET
+    value = excluded.value,
+    updated = excluded.updated
+"#;
+
+pub const SELECT_GLOBALS: &str = "SELECT name, value FROM globals ORDER BY name";
+
+pub const DELETE_GLOBAL: &str = "DELETE FROM globals WHERE name = ?";
+
+// ============================================================================
+// DELETED PROMPT QUERIES
+// ============================================================================
+
+pub const UPSERT_DELETED_PROMPT: &str = r#"
+INSERT INTO deleted_prompts (id, title, content, deleted_at)
+VALUES (?, ?, ?, ?)
+ON CONFLICT(id) DO UPDATE SET
+    title = excluded.title,
+    content = excluded.content,
+    deleted_at = excluded.deleted_at
+"#;
+
+pub const SELECT_DELETED_PROMPTS: &str = r#"
+SELECT id, title, deleted_at FROM deleted_prompts ORDER BY deleted_at DESC
+"#;
+
+pub const SELECT_DELETED_PROMPT_CONTENT: &str = "SELECT content FROM deleted_prompts WHERE id = ?";
+
+pub const DELETE_DELETED_PROMPT: &str = "DELETE FROM deleted_prompts WHERE id = ?";
+
+pub const PURGE_DELETED_PROMPTS: &str = "DELETE FROM deleted_prompts WHERE deleted_at < ?";
+
+// ============================================================================
+// SCHEMA VERSION QUERIES
+// ============================================================================
+
+pub const SELECT_SCHEMA_VERSION: &str = "SELECT MAX(version) AS version FROM schema_version";
+
+pub const INSERT_SCHEMA_VERSION: &str = r#"
+INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)
+"#;
+
 // ============================================================================
 // DEBUG QUERIES
 // ============================================================================
@@ -0,0 +1,92 @@
+//! `prompt-manager://` URLs other apps and browser bookmarklets use to push prompts in:
+//!
+//! - `prompt-manager://open?id=review.md` shows a prompt
+//! - `prompt-manager://new?text=...&tags=a,b` creates one
+//! - `prompt-manager://import?url=...` imports a prompt shared as a GitHub gist
+
+use crate::error::AppError;
+use tauri_plugin_http::reqwest::Url;
+
+pub const SCHEME: &str = "prompt-manager";
+
+/// What a deep link asks for
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLink {
+    Open { id: String },
+    New { text: String, tags: Vec<String> },
+    Import { url: String },
+}
+
+/// Parse a `prompt-manager://` URL
+pub fn parse(url: &str) -> Result<DeepLink, AppError> {
+    let invalid = |message: &str| AppError::invalid_input(format!("{}: {}", message, url));
+
+    let parsed = Url::parse(url).map_err(|_| invalid("Invalid deep link"))?;
+    if parsed.scheme() != SCHEME {
+        return Err(invalid("Not a prompt-manager link"));
+    }
+    let param = |name: &str| {
+        parsed
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+
+    // `prompt-manager://new?...` has the action as host; also accept `prompt-manager:new?...`
+    let action = parsed
+        .host_str()
+        .unwrap_or_else(|| parsed.path().trim_matches('/'));
+    match action {
+        "open" => Ok(DeepLink::Open {
+            id: param("id").ok_or_else(|| invalid("Missing id"))?,
+        }),
+        "new" => Ok(DeepLink::New {
+            text: param("text").ok_or_else(|| invalid("Missing text"))?,
+            tags: param("tags")
+                .map(|tags| {
+                    tags.split(',')
+                        .map(str::trim)
+                        .filter(|tag| !tag.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default(),
+        }),
+        "import" => Ok(DeepLink::Import {
+            url: param("url").ok_or_else(|| invalid("Missing url"))?,
+        }),
+        _ => Err(invalid("Unknown deep link action")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse("prompt-manager://open?id=review.md").unwrap(),
+            DeepLink::Open {
+                id: "review.md".to_string()
+            }
+        );
+        assert_eq!(
+            parse("prompt-manager://new?text=Summarize%20this&tags=work,%20summary,").unwrap(),
+            DeepLink::New {
+                text: "Summarize this".to_string(),
+                tags: vec!["work".to_string(), "summary".to_string()],
+            }
+        );
+        assert_eq!(
+            parse("prompt-manager://import?url=https%3A%2F%2Fgist.github.com%2Fu%2Fabc").unwrap(),
+            DeepLink::Import {
+                url: "https://gist.github.com/u/abc".to_string()
+            }
+        );
+        assert!(parse("prompt-manager://new?tags=work").is_err());
+        assert!(parse("prompt-manager://delete?id=review.md").is_err());
+        assert!(parse("https://open?id=review.md").is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! Line and word level diffs between prompt texts
+
+use serde::{Deserialize, Serialize};
+use similar::{ChangeTag, TextDiff};
+use specta::Type;
+
+/// Unchanged lines kept around each hunk
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffChange {
+    Equal,
+    Insert,
+    Delete,
+}
+
+/// Part of a line; `changed` marks the words that differ from the other side
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSegment {
+    pub text: String,
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub change: DiffChange,
+    /// 1-based line number on the old side (`None` for inserted lines)
+    pub old_line: Option<usize>,
+    /// 1-based line number on the new side (`None` for deleted lines)
+    pub new_line: Option<usize>,
+    pub text: String,
+    /// Word-level segments of `text` (empty unless requested)
+    pub segments: Vec<DiffSegment>,
+}
+
+/// A run of changed lines with surrounding context
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptDiff {
+    pub hunks: Vec<DiffHunk>,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Diff `old` against `new` line by line, optionally marking changed words within lines
+pub fn diff_text(old: &str, new: &str, word_level: bool) -> PromptDiff {
+    let diff = TextDiff::from_lines(old, new);
+    let mut result = PromptDiff {
+        hunks: Vec::new(),
+        insertions: 0,
+        deletions: 0,
+    };
+
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+
+        let mut lines = Vec::new();
+        for op in &group {
+            if word_level {
+                for change in diff.iter_inline_changes(op) {
+                    let segments: Vec<DiffSegment> = change
+                        .iter_strings_lossy()
+                        .map(|(changed, text)| DiffSegment {
+                            text: strip_newline(&text).to_string(),
+                            changed,
+                        })
+                        .filter(|segment| !segment.text.is_empty())
+                        .collect();
+                    lines.push(DiffLine {
+                        change: change.tag().into(),
+                        old_line: change.old_index().map(|i| i + 1),
+                        new_line: change.new_index().map(|i| i + 1),
+                        text: segments.iter().map(|s| s.text.as_str()).collect(),
+                        segments,
+                    });
+                }
+            } else {
+                for change in diff.iter_changes(op) {
+                    lines.push(DiffLine {
+                        change: change.tag().into(),
+                        old_line: change.old_index().map(|i| i + 1),
+                        new_line: change.new_index().map(|i| i + 1),
+                        text: strip_newline(change.value()).to_string(),
+                        segments: Vec::new(),
+                    });
+                }
+            }
+        }
+
+        for line in &lines {
+            match line.change {
+                DiffChange::Insert => result.insertions += 1,
+                DiffChange::Delete => result.deletions += 1,
+                DiffChange::Equal => {}
+            }
+        }
+        result.hunks.push(DiffHunk {
+            old_start: old_range.start + 1,
+            old_lines: old_range.len(),
+            new_start: new_range.start + 1,
+            new_lines: new_range.len(),
+            lines,
+        });
+    }
+
+    result
+}
+
+impl From<ChangeTag> for DiffChange {
+    fn from(tag: ChangeTag) -> Self {
+        match tag {
+            ChangeTag::Equal => DiffChange::Equal,
+            ChangeTag::Insert => DiffChange::Insert,
+            ChangeTag::Delete => DiffChange::Delete,
+        }
+    }
+}
+
+fn strip_newline(text: &str) -> &str {
+    text.strip_suffix('\n')
+        .map(|text| text.strip_suffix('\r').unwrap_or(text))
+        .unwrap_or(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_diff() {
+        let old = "one\ntwo\nthree\n";
+        let new = "one\n2\nthree\nfour\n";
+        let diff = diff_text(old, new, false);
+
+        assert_eq!(diff.insertions, 2);
+        assert_eq!(diff.deletions, 1);
+        assert_eq!(diff.hunks.len(), 1);
+
+        let hunk = &diff.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines), (1, 3));
+        assert_eq!((hunk.new_start, hunk.new_lines), (1, 4));
+        let lines: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.change, l.text.as_str()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (DiffChange::Equal, "one"),
+                (DiffChange::Delete, "two"),
+                (DiffChange::Insert, "2"),
+                (DiffChange::Equal, "three"),
+                (DiffChange::Insert, "four"),
+            ]
+        );
+        assert!(hunk.lines.iter().all(|l| l.segments.is_empty()));
+    }
+
+    #[test]
+    fn test_word_diff() {
+        let diff = diff_text(
+            "Summarize the text briefly",
+            "Summarize the article briefly",
+            true,
+        );
+        let deleted = diff.hunks[0]
+            .lines
+            .iter()
+            .find(|l| l.change == DiffChange::Delete)
+            .unwrap();
+
+        assert_eq!(deleted.text, "Summarize the text briefly");
+        let changed: Vec<_> = deleted
+            .segments
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(changed, vec!["text"]);
+    }
+
+    #[test]
+    fn test_identical_texts() {
+        let diff = diff_text("same\n", "same\n", true);
+        assert!(diff.hunks.is_empty());
+        assert_eq!((diff.insertions, diff.deletions), (0, 0));
+    }
+}
@@ -0,0 +1,638 @@
+//! Vault health checks: broken prompt files and drift between the vault and its cache
+
+use crate::config::{ContentMode, FrontmatterSettings};
+use crate::promptignore::PromptIgnore;
+use crate::vault::{self, VaultError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value as YamlValue};
+use specta::Type;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthIssueKind {
+    /// Frontmatter that is not valid YAML or has no closing `---`; its fields are ignored
+    MalformedFrontmatter,
+    /// A file that is not valid UTF-8
+    UnreadableFile,
+    /// A file kept in a fence with no ```` ```prompt ```` block, so its prompt reads as empty
+    MissingPromptBlock,
+    /// Cached tags that differ from the file's frontmatter
+    TagMismatch,
+    /// Files with the same `id:` in frontmatter or names that only differ in case
+    DuplicateId,
+    /// A temp file left behind by an interrupted save
+    StrayTempFile,
+    /// A prompt file the cache does not know about
+    UncachedFile,
+    /// A cached prompt whose file is gone
+    MissingFile,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+    pub kind: HealthIssueKind,
+    /// Vault-relative path of the file (or id of the cached prompt)
+    pub path: String,
+    pub message: String,
+    /// How to fix it
+    pub suggestion: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultHealthReport {
+    pub files_checked: usize,
+    /// Issues by path, then kind
+    pub issues: Vec<HealthIssue>,
+}
+
+/// Check the prompt files of the vault and compare them with `cached_tags`, the tags of
+/// every cached prompt by id
+pub fn check_vault(
+    vault_path: &Path,
+    frontmatter_settings: &FrontmatterSettings,
+    cached_tags: &BTreeMap<String, BTreeSet<String>>,
+) -> Result<VaultHealthReport, VaultError> {
+    let names = vault_file_names(vault_path)?;
+    let mut issues = Vec::new();
+    let mut issue = |kind, path: &str, message: String, suggestion: &str| {
+        issues.push(HealthIssue {
+            kind,
+            path: path.to_string(),
+            message,
+            suggestion: suggestion.to_string(),
+        })
+    };
+
+    let mut files = BTreeSet::new();
+    let mut by_lowercase: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    let mut by_frontmatter_id: BTreeMap<String, Vec<&str>> = BTreeMap::new();
+    for name in &names {
+        let path = vault_path.join(name);
+        if vault::is_stray_temp_file(&path, &frontmatter_settings.file_extensions) {
+            issue(
+                HealthIssueKind::StrayTempFile,
+                name,
+                "Temp file left behind by an interrupted save".to_string(),
+                "Delete it; the next full sync also removes it",
+            );
+            continue;
+        }
+        if !vault::has_prompt_extension(name, &frontmatter_settings.file_extensions) {
+            continue;
+        }
+        files.insert(name.as_str());
+        by_lowercase
+            .entry(name.to_lowercase())
+            .or_default()
+            .push(name);
+
+        let Ok(content) = fs::read_to_string(&path) else {
+            issue(
+                HealthIssueKind::UnreadableFile,
+                name,
+                "File is not valid UTF-8 text".to_string(),
+                "Re-save it as UTF-8 in a text editor",
+            );
+            continue;
+        };
+        // Files that are not markdown are raw prompt text without frontmatter
+        let is_markdown = vault::is_markdown(name);
+        let frontmatter = if is_markdown {
+            parse_frontmatter(&content)
+        } else {
+            Ok(None)
+        };
+        match frontmatter {
+            Ok(frontmatter) => {
+                let id = frontmatter
+                    .as_ref()
+                    .and_then(|map| map.get(YamlValue::String("id".to_string())))
+                    .and_then(|id| match id {
+                        YamlValue::String(id) => Some(id.trim().to_string()),
+                        YamlValue::Number(id) => Some(id.to_string()),
+                        _ => None,
+                    })
+                    .filter(|id| !id.is_empty());
+                if let Some(id) = id {
+                    by_frontmatter_id.entry(id).or_default().push(name);
+                }
+            }
+            Err(message) => issue(
+                HealthIssueKind::MalformedFrontmatter,
+                name,
+                format!("Frontmatter can't be read: {}", message),
+                "Repair the frontmatter or fix its YAML; its fields are ignored until then",
+            ),
+        }
+        if is_markdown && vault::lacks_prompt_block(&content, frontmatter_settings) {
+            issue(
+                HealthIssueKind::MissingPromptBlock,
+                name,
+                "No ```prompt block, so the prompt text is empty".to_string(),
+                "Repair the file to wrap its text in a ```prompt block, or set `content_mode: body`",
+            );
+        }
+
+        let file = vault::parse_prompt_file(name, &content, frontmatter_settings);
+        match cached_tags.get(name) {
+            None => issue(
+                HealthIssueKind::UncachedFile,
+                name,
+                "File is not in the cache".to_string(),
+                "Sync the vault",
+            ),
+            Some(cached) => {
+                let tags: BTreeSet<String> = file.tags.into_iter().collect();
+                if &tags != cached {
+                    issue(
+                        HealthIssueKind::TagMismatch,
+                        name,
+                        format!(
+                            "File has tags [{}] but the cache has [{}]",
+                            join(&tags),
+                            join(cached)
+                        ),
+                        "Sync the vault",
+                    );
+                }
+            }
+        }
+    }
+
+    for paths in by_lowercase.values().filter(|paths| paths.len() > 1) {
+        for path in paths {
+            issue(
+                HealthIssueKind::DuplicateId,
+                path,
+                format!(
+                    "{} only differ in case and clash on Windows and macOS",
+                    paths.join(", ")
+                ),
+                "Rename all but one of them",
+            );
+        }
+    }
+    for (id, paths) in by_frontmatter_id
+        .iter()
+        .filter(|(_, paths)| paths.len() > 1)
+    {
+        for path in paths {
+            issue(
+                HealthIssueKind::DuplicateId,
+                path,
+                format!("`id: {}` is also used by {}", id, others(paths, path)),
+                "Remove the `id` field; prompts are identified by file name",
+            );
+        }
+    }
+    for id in cached_tags.keys().filter(|id| !files.contains(id.as_str())) {
+        issue(
+            HealthIssueKind::MissingFile,
+            id,
+            "Cached prompt has no file in the vault".to_string(),
+            "Sync the vault to drop it from the cache",
+        );
+    }
+
+    issues.sort_by(|a, b| (&a.path, a.kind as u8).cmp(&(&b.path, b.kind as u8)));
+    Ok(VaultHealthReport {
+        files_checked: files.len(),
+        issues,
+    })
+}
+
+/// The frontmatter mapping of `content` (`None` without frontmatter), or why it can't be read
+fn parse_frontmatter(content: &str) -> Result<Option<Mapping>, String> {
+    match split_frontmatter(content) {
+        None => Ok(None),
+        Some(frontmatter) if frontmatter.closed => parse_mapping(frontmatter.yaml).map(Some),
+        Some(_) => Err("no closing `---` line".to_string()),
+    }
+}
+
+/// The frontmatter block at the start of a prompt file
+struct Frontmatter<'a> {
+    /// Text between the `---` lines
+    yaml: &'a str,
+    /// Everything after the closing `---` line (nothing when there is none)
+    body: &'a str,
+    closed: bool,
+}
+
+fn split_frontmatter(content: &str) -> Option<Frontmatter<'_>> {
+    let rest = content.trim_start_matches('\u{feff}').strip_prefix("---")?;
+    let (opening, yaml_and_body) = rest.split_once('\n')?;
+    if !opening.trim().is_empty() {
+        return None;
+    }
+    let mut offset = 0;
+    for line in yaml_and_body.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some(Frontmatter {
+                yaml: &yaml_and_body[..offset],
+                body: &yaml_and_body[offset + line.len()..],
+                closed: true,
+            });
+        }
+        offset += line.len();
+    }
+    Some(Frontmatter {
+        yaml: yaml_and_body,
+        body: "",
+        closed: false,
+    })
+}
+
+fn parse_mapping(yaml: &str) -> Result<Mapping, String> {
+    match serde_yaml::from_str::<YamlValue>(yaml) {
+        Ok(YamlValue::Mapping(map)) => Ok(map),
+        Ok(YamlValue::Null) => Ok(Mapping::new()),
+        Ok(_) => Err("it is not a list of `key: value` fields".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Names of the files directly in the vault folder that `.promptignore` does not exclude,
+/// sorted
+fn vault_file_names(vault_path: &Path) -> Result<Vec<String>, VaultError> {
+    if !vault_path.exists() {
+        return Err(VaultError::PathNotFound(vault_path.display().to_string()));
+    }
+    let ignore = PromptIgnore::load(vault_path);
+    let entries = fs::read_dir(vault_path).map_err(|e| VaultError::IoError(e.to_string()))?;
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .filter(|name| !ignore.is_ignored(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+// ============================================================================
+// REPAIR
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairKind {
+    /// Re-write unreadable frontmatter from the fields that still parse; the rest is kept
+    /// in a comment below it
+    Frontmatter,
+    /// Add a missing created date from the file's modification time
+    MissingCreated,
+    /// Turn tags into a list of normalized, distinct tags
+    TagFormat,
+    /// Wrap the text of a file kept in a fence but lacking a ```` ```prompt ```` block
+    PromptBlock,
+}
+
+/// Changes made (or planned, in a dry run) to one prompt file
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRepair {
+    pub path: String,
+    pub fixes: Vec<RepairKind>,
+    /// What each fix did, for people
+    pub notes: Vec<String>,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultRepairReport {
+    pub dry_run: bool,
+    pub files: Vec<FileRepair>,
+}
+
+/// Apply `fixes` to every prompt file of the vault that needs them. A dry run only
+/// reports what would change.
+pub fn repair_vault(
+    vault_path: &Path,
+    frontmatter_settings: &FrontmatterSettings,
+    fixes: &[RepairKind],
+    dry_run: bool,
+) -> Result<VaultRepairReport, VaultError> {
+    let mut files = Vec::new();
+    for name in vault_file_names(vault_path)? {
+        if !name.ends_with(".md") {
+            continue;
+        }
+        let path = vault_path.join(&name);
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let modified = fs::metadata(&path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+        let Some(repair) =
+            repair_prompt_file(&name, &content, modified, fixes, frontmatter_settings)
+        else {
+            continue;
+        };
+        if !dry_run {
+            vault::write_atomic(&path, &repair.after)
+                .map_err(|e| VaultError::IoError(e.to_string()))?;
+            info!("Repaired {}: {:?}", name, repair.fixes);
+        }
+        files.push(repair);
+    }
+    Ok(VaultRepairReport { dry_run, files })
+}
+
+/// Apply `fixes` to the markdown of a prompt file; `None` when none of them is needed.
+/// `modified` is the file's modification time, used for a missing created date.
+fn repair_prompt_file(
+    path: &str,
+    content: &str,
+    modified: Option<DateTime<Utc>>,
+    fixes: &[RepairKind],
+    frontmatter_settings: &FrontmatterSettings,
+) -> Option<FileRepair> {
+    let mut applied = Vec::new();
+    let mut notes = Vec::new();
+    // Lines of broken frontmatter that are kept below the repaired one
+    let mut unreadable = Vec::new();
+
+    let (mut map, header, body) = match split_frontmatter(content) {
+        None => (Mapping::new(), "", content),
+        Some(frontmatter) => {
+            let header = &content[..content.len() - frontmatter.body.len()];
+            match (frontmatter.closed, parse_mapping(frontmatter.yaml)) {
+                (true, Ok(map)) => (map, header, frontmatter.body),
+                _ if fixes.contains(&RepairKind::Frontmatter) => {
+                    // Without a closing line the frontmatter is taken to end at the first blank one
+                    let (yaml, body) = match frontmatter.closed {
+                        true => (frontmatter.yaml, frontmatter.body),
+                        false => match frontmatter.yaml.find("\n\n") {
+                            Some(end) => frontmatter.yaml.split_at(end + 1),
+                            None => (frontmatter.yaml, ""),
+                        },
+                    };
+                    let (map, dropped) = salvage_frontmatter(yaml);
+                    applied.push(RepairKind::Frontmatter);
+                    notes.push(format!(
+                        "Re-wrote the frontmatter ({} fields kept)",
+                        map.len()
+                    ));
+                    if !dropped.is_empty() {
+                        notes.push(format!(
+                            "Kept {} unreadable lines in a comment below the frontmatter",
+                            dropped.len()
+                        ));
+                    }
+                    unreadable = dropped;
+                    (map, "", body)
+                }
+                // Fields can't be touched without losing what the broken frontmatter says
+                _ => return None,
+            }
+        }
+    };
+    let mut map_changed = applied.contains(&RepairKind::Frontmatter);
+
+    let keys = vault::FrontmatterKeys::new(frontmatter_settings);
+    let created_key = YamlValue::String(keys.created.clone());
+    if let (true, None, Some(modified)) = (
+        fixes.contains(&RepairKind::MissingCreated),
+        map.get(&created_key),
+        modified,
+    ) {
+        let created = modified.format("%Y-%m-%dT%H:%M:%S").to_string();
+        notes.push(format!("Added `{}: {}`", keys.created, created));
+        map.insert(created_key, YamlValue::String(created));
+        applied.push(RepairKind::MissingCreated);
+        map_changed = true;
+    }
+
+    let tags_key = YamlValue::String(keys.tags.clone());
+    if fixes.contains(&RepairKind::TagFormat) && map.contains_key(&tags_key) {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in vault::extract_tags(&map, &keys.tags) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        let list = YamlValue::Sequence(tags.iter().cloned().map(YamlValue::String).collect());
+        if map.get(&tags_key) != Some(&list) {
+            notes.push(format!("Normalized tags to [{}]", tags.join(", ")));
+            vault::set_tags(&mut map, &keys.tags, &tags);
+            applied.push(RepairKind::TagFormat);
+            map_changed = true;
+        }
+    }
+
+    let mut body = body.to_string();
+    if fixes.contains(&RepairKind::PromptBlock)
+        && vault::resolve_content_mode(&map, frontmatter_settings) == ContentMode::Fence
+        && !vault::has_prompt_block(&body)
+        && !body.trim().is_empty()
+    {
+        let text = body.trim();
+        let fence = vault::fence_for(text, '`');
+        let leading = &body[..body.len() - body.trim_start().len()];
+        body = format!("{}{}prompt\n{}\n{}\n", leading, fence, text, fence);
+        notes.push("Wrapped the text in a ```prompt block".to_string());
+        applied.push(RepairKind::PromptBlock);
+    }
+
+    if applied.is_empty() {
+        return None;
+    }
+    if !unreadable.is_empty() {
+        body = format!(
+            "<!-- Unreadable frontmatter:\n{}\n-->\n\n{}",
+            unreadable.join("\n"),
+            body.trim_start_matches(['\r', '\n'])
+        );
+    }
+    let after = if map_changed {
+        format!(
+            "{}{}",
+            vault::render_frontmatter(&map).ok()?,
+            body.trim_start_matches(['\r', '\n'])
+        )
+    } else {
+        format!("{}{}", header, body)
+    };
+    (after != content).then(|| FileRepair {
+        path: path.to_string(),
+        fixes: applied,
+        notes,
+        before: content.to_string(),
+        after,
+    })
+}
+
+/// Fields of broken frontmatter that parse on their own, and the entries that don't
+fn salvage_frontmatter(yaml: &str) -> (Mapping, Vec<String>) {
+    // Tabs are the most common culprit; YAML only allows spaces for indentation
+    let yaml = yaml.replace('\t', "  ");
+    if let Ok(map) = parse_mapping(&yaml) {
+        return (map, Vec::new());
+    }
+
+    // An entry is a line at column 0 with the indented or `- item` lines under it
+    let mut entries: Vec<String> = Vec::new();
+    for line in yaml.lines() {
+        match entries.last_mut() {
+            Some(entry) if line.starts_with([' ', '-']) => {
+                entry.push('\n');
+                entry.push_str(line);
+            }
+            _ => entries.push(line.to_string()),
+        }
+    }
+    let mut map = Mapping::new();
+    let mut dropped = Vec::new();
+    for entry in entries.into_iter().filter(|entry| !entry.trim().is_empty()) {
+        match parse_mapping(&entry) {
+            Ok(fields) => map.extend(fields),
+            Err(_) => dropped.push(entry),
+        }
+    }
+    (map, dropped)
+}
+
+fn join(tags: &BTreeSet<String>) -> String {
+    tags.iter().cloned().collect::<Vec<_>>().join(", ")
+}
+
+fn others(paths: &[&str], path: &str) -> String {
+    paths
+        .iter()
+        .filter(|other| **other != path)
+        .copied()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_check_vault() {
+        let vault_path = TempDir::new("doctor");
+        let write = |name: &str, content: &str| fs::write(vault_path.join(name), content).unwrap();
+        write(
+            "healthy.md",
+            "---\ntags:\n- code\n---\n\n```prompt\nReview this\n```\n",
+        );
+        write("broken.md", "---\ntags: [code\n---\n\n```prompt\nHi\n```\n");
+        write("bare.md", "---\nid: shared\n---\n\nJust text\n");
+        write("copy.md", "---\nid: shared\n---\n\n```prompt\nHi\n```\n");
+        write("draft.md.tmp", "partial");
+
+        let cached_tags = BTreeMap::from([
+            (
+                "healthy.md".to_string(),
+                BTreeSet::from(["code".to_string(), "old".to_string()]),
+            ),
+            ("broken.md".to_string(), BTreeSet::new()),
+            ("bare.md".to_string(), BTreeSet::new()),
+            ("gone.md".to_string(), BTreeSet::new()),
+        ]);
+        let report =
+            check_vault(&vault_path, &FrontmatterSettings::default(), &cached_tags).unwrap();
+        let found: Vec<(&str, HealthIssueKind)> = report
+            .issues
+            .iter()
+            .map(|issue| (issue.path.as_str(), issue.kind))
+            .collect();
+
+        assert_eq!(report.files_checked, 4);
+        assert_eq!(
+            found,
+            vec![
+                ("bare.md", HealthIssueKind::MissingPromptBlock),
+                ("bare.md", HealthIssueKind::DuplicateId),
+                ("broken.md", HealthIssueKind::MalformedFrontmatter),
+                ("copy.md", HealthIssueKind::DuplicateId),
+                ("copy.md", HealthIssueKind::UncachedFile),
+                ("draft.md.tmp", HealthIssueKind::StrayTempFile),
+                ("gone.md", HealthIssueKind::MissingFile),
+                ("healthy.md", HealthIssueKind::TagMismatch),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_repair_prompt_file() {
+        let all = [
+            RepairKind::Frontmatter,
+            RepairKind::MissingCreated,
+            RepairKind::TagFormat,
+            RepairKind::PromptBlock,
+        ];
+        let settings = FrontmatterSettings::default();
+        let modified = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).single();
+
+        let repair = repair_prompt_file(
+            "tabs.md",
+            "---\ntitle: Review\ntags: \"#code, writing code\"\nnotes:\n\tsource: web\n---\n\nBody text\n",
+            modified,
+            &all,
+            &settings,
+        )
+        .unwrap();
+        assert_eq!(repair.fixes, all);
+        assert_eq!(
+            repair.after,
+            "---\ntitle: Review\ntags:\n- code\n- writing\nnotes:\n  source: web\n\
+             created: 2024-05-01T12:00:00\n---\n\n```prompt\nBody text\n```\n"
+        );
+
+        // Fields that don't parse are kept in a comment
+        let repair = repair_prompt_file(
+            "broken.md",
+            "---\ntitle: Ok\ntags: [a, b\n---\n```prompt\nHi\n```\n",
+            modified,
+            &[RepairKind::Frontmatter],
+            &settings,
+        )
+        .unwrap();
+        assert_eq!(
+            repair.after,
+            "---\ntitle: Ok\n---\n\n<!-- Unreadable frontmatter:\ntags: [a, b\n-->\n\n\
+             ```prompt\nHi\n```\n"
+        );
+        assert!(repair_prompt_file(
+            "broken.md",
+            "---\ntags: [a, b\n---\n\nHi\n",
+            modified,
+            &[RepairKind::TagFormat, RepairKind::PromptBlock],
+            &settings,
+        )
+        .is_none());
+
+        // A file without frontmatter only gets one when a field is added
+        let repair = repair_prompt_file(
+            "bare.md",
+            "\nJust text\n",
+            modified,
+            &[RepairKind::PromptBlock, RepairKind::TagFormat],
+            &settings,
+        )
+        .unwrap();
+        assert_eq!(repair.after, "\n```prompt\nJust text\n```\n");
+        assert!(
+            repair_prompt_file("done.md", &repair.after, modified, &all[1..], &settings)
+                .unwrap()
+                .after
+                .starts_with("---\ncreated:")
+        );
+    }
+}
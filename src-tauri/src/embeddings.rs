@@ -0,0 +1,300 @@
+//! Vector embeddings of prompt texts for semantic search
+
+use crate::config::{EmbeddingProvider, EmbeddingSettings};
+use crate::db::{queries::*, DbPool};
+use crate::models::{DbError, EmbeddingRow, PromptEmbeddingStateRow};
+use crate::secrets::{self, SecretName};
+use crate::vault;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use tauri_plugin_http::reqwest;
+use tracing::info;
+
+/// Dimensions of the built-in hashed embedding
+const LOCAL_DIMENSIONS: usize = 512;
+
+/// Model id stored with built-in embeddings; bump it whenever `local_embedding` changes
+const LOCAL_MODEL: &str = "local-hash-v1";
+
+/// Weight of character trigrams relative to whole words in the built-in embedding
+const TRIGRAM_WEIGHT: f32 = 0.5;
+
+/// Texts sent to an embedding API per request
+const API_BATCH_SIZE: usize = 32;
+
+/// Embedding errors
+#[derive(Debug, Clone, Serialize, thiserror::Error, specta::Type)]
+pub enum EmbeddingError {
+    #[error("Embedding request failed: {0}")]
+    Request(String),
+    #[error("Invalid embedding response: {0}")]
+    InvalidResponse(String),
+}
+
+impl From<EmbeddingError> for DbError {
+    fn from(e: EmbeddingError) -> Self {
+        DbError::Database(e.to_string())
+    }
+}
+
+/// Identifier stored with each vector, so switching models re-embeds every prompt
+pub fn model_id(settings: &EmbeddingSettings) -> String {
+    match settings.provider {
+        EmbeddingProvider::Local => LOCAL_MODEL.to_string(),
+        EmbeddingProvider::Api => format!("api:{}", settings.api_model),
+    }
+}
+
+/// Embed `texts` with the configured provider; vectors are L2-normalized
+pub async fn embed_texts(
+    settings: &EmbeddingSettings,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    match settings.provider {
+        EmbeddingProvider::Local => Ok(texts.iter().map(|text| local_embedding(text)).collect()),
+        EmbeddingProvider::Api => {
+            let mut vectors = Vec::with_capacity(texts.len());
+            for batch in texts.chunks(API_BATCH_SIZE) {
+                vectors.extend(api_embeddings(settings, batch).await?);
+            }
+            Ok(vectors)
+        }
+    }
+}
+
+/// Embed prompts whose text changed (or that have no vector for the current model yet).
+/// Returns the number of prompts embedded.
+pub async fn refresh_embeddings(
+    pool: &DbPool,
+    settings: &EmbeddingSettings,
+) -> Result<usize, DbError> {
+    let model = model_id(settings);
+    let rows = sqlx::query_as::<_, PromptEmbeddingStateRow>(SELECT_PROMPT_EMBEDDING_STATE)
+        .bind(&model)
+        .fetch_all(pool)
+        .await?;
+
+    let stale: Vec<(String, String, String)> = rows
+        .into_iter()
+        .filter_map(|row| {
+            let hash = vault::compute_file_hash(&row.text);
+            (row.text_hash.as_deref() != Some(hash.as_str())).then_some((row.id, row.text, hash))
+        })
+        .collect();
+    if stale.is_empty() {
+        return Ok(0);
+    }
+
+    let texts: Vec<String> = stale.iter().map(|(_, text, _)| text.clone()).collect();
+    let vectors = embed_texts(settings, &texts).await?;
+
+    let mut tx = pool.begin().await?;
+    for ((id, _, hash), vector) in stale.iter().zip(&vectors) {
+        sqlx::query(UPSERT_PROMPT_EMBEDDING)
+            .bind(id)
+            .bind(&model)
+            .bind(hash)
+            .bind(encode_vector(vector))
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    info!("Embedded {} prompts with {}", stale.len(), model);
+    Ok(stale.len())
+}
+
+/// All stored vectors for the current model, keyed by prompt id
+pub async fn load_embeddings(
+    pool: &DbPool,
+    settings: &EmbeddingSettings,
+) -> Result<Vec<(String, Vec<f32>)>, DbError> {
+    let rows = sqlx::query_as::<_, EmbeddingRow>(SELECT_EMBEDDINGS_FOR_MODEL)
+        .bind(model_id(settings))
+        .fetch_all(pool)
+        .await?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.prompt_id, decode_vector(&row.vector)))
+        .collect())
+}
+
+/// The `top_k` candidates most similar to `query`, best first, skipping `exclude_id`
+pub fn rank(
+    query: &[f32],
+    candidates: &[(String, Vec<f32>)],
+    top_k: usize,
+    exclude_id: Option<&str>,
+) -> Vec<(String, f32)> {
+    let mut scored: Vec<(String, f32)> = candidates
+        .iter()
+        .filter(|(id, vector)| Some(id.as_str()) != exclude_id && vector.len() == query.len())
+        .map(|(id, vector)| (id.clone(), cosine_similarity(query, vector)))
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
+    scored.truncate(top_k);
+    scored
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// Dependency-free embedding: hashed bag of words plus character trigrams.
+/// Catches shared vocabulary and word forms, not synonyms; use an API model for that.
+fn local_embedding(text: &str) -> Vec<f32> {
+    let mut vector = vec![0.0f32; LOCAL_DIMENSIONS];
+    let lowered = text.to_lowercase();
+    let words = lowered
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty());
+
+    for word in words {
+        add_feature(&mut vector, word.as_bytes(), 1.0);
+        let padded: Vec<char> = format!(" {} ", word).chars().collect();
+        for trigram in padded.windows(3) {
+            let trigram: String = trigram.iter().collect();
+            add_feature(&mut vector, trigram.as_bytes(), TRIGRAM_WEIGHT);
+        }
+    }
+
+    normalize(&mut vector);
+    vector
+}
+
+/// Hash a feature into a bucket; the sign bit keeps collisions from only adding up
+fn add_feature(vector: &mut [f32], feature: &[u8], weight: f32) {
+    let hash = fnv1a(feature);
+    let index = (hash % vector.len() as u64) as usize;
+    let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+    vector[index] += sign * weight;
+}
+
+/// Stable across Rust versions, unlike `DefaultHasher`, so stored vectors stay comparable
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|x| *x /= norm);
+    }
+}
+
+#[derive(Serialize)]
+struct ApiRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct ApiResponse {
+    data: Vec<ApiEmbedding>,
+}
+
+#[derive(Deserialize)]
+struct ApiEmbedding {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// Call an OpenAI-compatible `/embeddings` endpoint (OpenAI, Ollama, LM Studio, ...)
+async fn api_embeddings(
+    settings: &EmbeddingSettings,
+    texts: &[String],
+) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+    let mut request = reqwest::Client::new()
+        .post(&settings.api_url)
+        .json(&ApiRequest {
+            model: &settings.api_model,
+            input: texts,
+        });
+    if let Some(key) = secrets::lookup(SecretName::EmbeddingsApiKey) {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| EmbeddingError::Request(e.to_string()))?;
+    let mut body: ApiResponse = response
+        .json()
+        .await
+        .map_err(|e| EmbeddingError::InvalidResponse(e.to_string()))?;
+    if body.data.len() != texts.len() {
+        return Err(EmbeddingError::InvalidResponse(format!(
+            "expected {} embeddings, got {}",
+            texts.len(),
+            body.data.len()
+        )));
+    }
+
+    body.data.sort_by_key(|item| item.index);
+    Ok(body
+        .data
+        .into_iter()
+        .map(|item| {
+            let mut vector = item.embedding;
+            normalize(&mut vector);
+            vector
+        })
+        .collect())
+}
+
+/// Store vectors as little-endian `f32` bytes
+fn encode_vector(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_embedding_similarity() {
+        let review = local_embedding("Review this Rust code for bugs");
+        let reviewing = local_embedding("Please review my rust code and find bugs");
+        let recipe = local_embedding("Write a recipe for banana bread");
+
+        assert!(cosine_similarity(&review, &reviewing) > cosine_similarity(&review, &recipe));
+        assert!((cosine_similarity(&review, &review) - 1.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_rank_skips_excluded_and_mismatched() {
+        let candidates = vec![
+            ("a.md".to_string(), vec![1.0, 0.0]),
+            ("b.md".to_string(), vec![0.6, 0.8]),
+            ("c.md".to_string(), vec![0.0, 1.0]),
+            ("d.md".to_string(), vec![1.0, 0.0, 0.0]),
+        ];
+        let ranked = rank(&[1.0, 0.0], &candidates, 2, Some("a.md"));
+        let ids: Vec<_> = ranked.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["b.md", "c.md"]);
+    }
+
+    #[test]
+    fn test_vector_round_trip() {
+        let vector = vec![0.25, -1.5, 3.0];
+        assert_eq!(decode_vector(&encode_vector(&vector)), vector);
+    }
+}
@@ -0,0 +1,230 @@
+//! Error type returned by commands
+
+use crate::config::ConfigError;
+use crate::crypto::CryptoError;
+use crate::embeddings::EmbeddingError;
+use crate::integrations::gist::GistError;
+use crate::llm::LlmError;
+use crate::models::{DbError, RevisionConflict, SaveConflict};
+use crate::secrets::SecretError;
+use crate::template::TemplateError;
+use crate::vault::VaultError;
+use serde::{Serialize, Serializer};
+use specta::datatype::{reference::Reference, DataType};
+use specta::{Generics, Type, TypeCollection};
+
+/// What went wrong, for the frontend to branch on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ErrorKind {
+    VaultNotConfigured,
+    NotFound,
+    /// The file changed on disk since it was read; see `conflict`
+    Conflict,
+    /// The prompt was saved elsewhere since it was loaded; see `revision_conflict`
+    StaleRevision,
+    AlreadyExists,
+    InvalidInput,
+    Io,
+    Parse,
+    Database,
+    Config,
+    /// A request to an LLM, embedding or remote vault endpoint failed
+    Network,
+    /// Stopped on request, e.g. a sync cancelled through `cancel_sync`
+    Cancelled,
+    /// An encrypted prompt was read or saved before `unlock_vault`
+    Locked,
+    /// Stopped before deleting many files; retry with the confirmation flag set
+    NeedsConfirmation,
+}
+
+/// Any failure of a command. Serialized as `{ kind, message, conflict, revisionConflict }` rather
+/// than by variant, so callers never need to know which module failed.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum AppError {
+    #[error(transparent)]
+    Db(#[from] DbError),
+    #[error(transparent)]
+    Vault(#[from] VaultError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Llm(#[from] LlmError),
+    #[error(transparent)]
+    Embedding(#[from] EmbeddingError),
+    #[error(transparent)]
+    Secret(#[from] SecretError),
+    #[error(transparent)]
+    Crypto(#[from] CryptoError),
+    #[error(transparent)]
+    Template(#[from] TemplateError),
+    #[error(transparent)]
+    Gist(#[from] GistError),
+    #[error("{message}")]
+    Other { kind: ErrorKind, message: String },
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        AppError::Other {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Io, message)
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            AppError::Db(e) => match e {
+                DbError::Database(_) => ErrorKind::Database,
+                DbError::NotFound(_) => ErrorKind::NotFound,
+                DbError::Serialization(_) => ErrorKind::Parse,
+                DbError::InvalidInput(_) => ErrorKind::InvalidInput,
+                DbError::Conflict(_) => ErrorKind::Conflict,
+                DbError::StaleRevision(_) => ErrorKind::StaleRevision,
+                DbError::Cancelled => ErrorKind::Cancelled,
+            },
+            AppError::Vault(e) => match e {
+                VaultError::NotConfigured => ErrorKind::VaultNotConfigured,
+                VaultError::NotFound(_) | VaultError::PathNotFound(_) => ErrorKind::NotFound,
+                VaultError::IoError(_)
+                | VaultError::FileTooLarge { .. }
+                | VaultError::BinaryFile(_) => ErrorKind::Io,
+                VaultError::ParseError(_) | VaultError::SerializeError(_) => ErrorKind::Parse,
+                VaultError::InvalidFilename(_)
+                | VaultError::InvalidFilePath(_)
+                | VaultError::InvalidContent(_) => ErrorKind::InvalidInput,
+                VaultError::FileAlreadyExists(_) => ErrorKind::AlreadyExists,
+                VaultError::Remote(_) => ErrorKind::Network,
+                VaultError::Cancelled => ErrorKind::Cancelled,
+                VaultError::UnconfirmedDeletions(_) => ErrorKind::NeedsConfirmation,
+            },
+            AppError::Config(e) => match e {
+                ConfigError::Invalid(_) => ErrorKind::InvalidInput,
+                ConfigError::PathError(_)
+                | ConfigError::IoError(_)
+                | ConfigError::ParseError(_)
+                | ConfigError::SerializeError(_) => ErrorKind::Config,
+            },
+            AppError::Llm(e) => match e {
+                LlmError::Request(_) => ErrorKind::Network,
+                LlmError::InvalidResponse(_) => ErrorKind::Parse,
+            },
+            AppError::Embedding(e) => match e {
+                EmbeddingError::Request(_) => ErrorKind::Network,
+                EmbeddingError::InvalidResponse(_) => ErrorKind::Parse,
+            },
+            AppError::Secret(SecretError::Keyring(_)) => ErrorKind::Io,
+            AppError::Crypto(e) => match e {
+                CryptoError::Locked => ErrorKind::Locked,
+                CryptoError::WrongPassphrase => ErrorKind::InvalidInput,
+                CryptoError::Invalid(_) => ErrorKind::Parse,
+            },
+            AppError::Template(e) => match e {
+                TemplateError::IncludeNotFound(_) => ErrorKind::NotFound,
+                TemplateError::IncludeCycle(_)
+                | TemplateError::IncludeTooDeep(_)
+                | TemplateError::InvalidDateFormat(_)
+                | TemplateError::EnvNotAllowed(_)
+                | TemplateError::UnknownGlobal(_)
+                | TemplateError::UnmatchedTag(_)
+                | TemplateError::UnclosedBlock(_)
+                | TemplateError::MissingRequired(_)
+                | TemplateError::InvalidOption { .. }
+                | TemplateError::EmptyValueSet(_)
+                | TemplateError::MatrixTooLarge(_)
+                | TemplateError::Unconvertible(_) => ErrorKind::InvalidInput,
+            },
+            AppError::Gist(e) => match e {
+                GistError::MissingToken | GistError::InvalidUrl(_) => ErrorKind::InvalidInput,
+                GistError::Request(_) => ErrorKind::Network,
+                GistError::InvalidResponse(_) => ErrorKind::Parse,
+            },
+            AppError::Other { kind, .. } => *kind,
+        }
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        AppError::Db(e.into())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        AppError::Db(e.into())
+    }
+}
+
+/// Wire format of [`AppError`]
+#[derive(Serialize, Type)]
+#[serde(rename = "AppError", rename_all = "camelCase")]
+struct AppErrorPayload {
+    kind: ErrorKind,
+    message: String,
+    /// Both sides of the save, when `kind` is `conflict`
+    conflict: Option<SaveConflict>,
+    /// Expected and current revision, when `kind` is `staleRevision`
+    revision_conflict: Option<RevisionConflict>,
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let conflict = match self {
+            AppError::Db(DbError::Conflict(conflict)) => Some(conflict.clone()),
+            _ => None,
+        };
+        let revision_conflict = match self {
+            AppError::Db(DbError::StaleRevision(conflict)) => Some(conflict.clone()),
+            _ => None,
+        };
+        AppErrorPayload {
+            kind: self.kind(),
+            message: self.to_string(),
+            conflict,
+            revision_conflict,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl Type for AppError {
+    fn inline(type_map: &mut TypeCollection, generics: Generics) -> DataType {
+        AppErrorPayload::inline(type_map, generics)
+    }
+
+    fn reference(type_map: &mut TypeCollection, generics: &[DataType]) -> Reference {
+        AppErrorPayload::reference(type_map, generics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_kind_and_message() {
+        let error = AppError::from(VaultError::NotConfigured);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({
+                "kind": "vaultNotConfigured",
+                "message": "Vault path not configured",
+                "conflict": null,
+                "revisionConflict": null,
+            })
+        );
+
+        let error = AppError::from(DbError::NotFound("a.md".to_string()));
+        assert_eq!(serde_json::to_value(&error).unwrap()["kind"], "notFound");
+    }
+}
@@ -0,0 +1,131 @@
+use crate::cache::CacheState;
+use crate::models::{Prompt, SyncStats, View};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri::{AppHandle, Manager};
+use tauri_specta::Event;
+use tracing::error;
+
+/// Emitted after the vault was synced into the cache, by a command or by the
+/// backend on its own (e.g. in response to watcher events)
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct SyncCompleted(pub SyncStats);
+
+/// Which part of a full sync `SyncProgress` is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncPhase {
+    /// Reading the vault files
+    Scanning,
+    /// Writing what was read into the cache
+    Caching,
+}
+
+/// Emitted periodically while `sync_vault` runs, so big vaults don't look frozen
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncProgress {
+    pub phase: SyncPhase,
+    /// Files done in this phase
+    pub scanned: usize,
+    pub total: usize,
+    /// File being read or cached, if any
+    pub current: Option<String>,
+}
+
+/// A prompt was created or updated through a command, as now cached
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct PromptSaved(pub Prompt);
+
+/// A prompt was deleted through `delete_prompt`
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptDeleted {
+    pub id: String,
+}
+
+/// A command changed which tags prompts use; carries every tag name, sorted
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct TagChanged {
+    pub tags: Vec<String>,
+}
+
+/// A view was created or updated through `save_view`
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct ViewSaved(pub View);
+
+/// A view was deleted through `delete_view`
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewDeleted {
+    pub id: String,
+}
+
+/// Views were reordered through `reorder_views`; carries every view id in its new order
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewsReordered {
+    pub ids: Vec<String>,
+}
+
+/// A prompt file was renamed through `rename_prompt`; ids are vault-relative paths
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRenamed {
+    pub old_id: String,
+    pub new_id: String,
+}
+
+/// A `prompt-manager://` link opened, created or imported this prompt; the frontend shows it
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+pub struct DeepLinkOpened(pub Prompt);
+
+/// The clipboard watcher saw prompt-like text copied in another app
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureSuggested {
+    pub text: String,
+}
+
+/// Prompt files changed on disk, as relative paths grouped by change kind
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type, Event)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultChanged {
+    pub created: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl VaultChanged {
+    /// All changed paths regardless of kind
+    pub fn paths(&self) -> Vec<String> {
+        self.created
+            .iter()
+            .chain(&self.modified)
+            .chain(&self.removed)
+            .cloned()
+            .collect()
+    }
+}
+
+/// Events announcing a change to cached prompts or tags, which `CacheState` must drop
+const CACHE_INVALIDATING: [&str; 5] = [
+    SyncCompleted::NAME,
+    PromptSaved::NAME,
+    PromptDeleted::NAME,
+    PromptRenamed::NAME,
+    TagChanged::NAME,
+];
+
+/// Emit `event` to all windows; failures are logged, as the change itself already happened
+pub fn emit<E: Event + Serialize + Clone>(app: &AppHandle, event: E) {
+    if CACHE_INVALIDATING.contains(&E::NAME) {
+        if let Some(cache) = app.try_state::<CacheState>() {
+            cache.invalidate();
+        }
+    }
+    if let Err(e) = event.emit(app) {
+        error!("Failed to emit {}: {}", E::NAME, e);
+    }
+}
@@ -0,0 +1,437 @@
+use crate::models::{DbError, Prompt};
+use crate::template::{self, Dialect, TemplateError};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// File format for `export_prompts`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Yaml,
+}
+
+/// Tool `export_prompts_for` shapes its file for
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportTool {
+    /// promptfoo config (YAML) listing every prompt under `prompts`
+    Promptfoo,
+    /// JSON array of LangChain `PromptTemplate`s
+    Langchain,
+}
+
+/// How `import_prompts` handles an item whose id or title matches an existing prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStrategy {
+    Skip,
+    Overwrite,
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportStatus {
+    Created,
+    Overwritten,
+    Duplicated,
+    Skipped,
+    Failed,
+}
+
+/// Outcome of importing a single item
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportItemReport {
+    /// Id of the item in the import file, if it had one
+    pub source_id: Option<String>,
+    pub title: Option<String>,
+    /// Vault file written (or the existing file when skipped)
+    pub file_path: Option<String>,
+    pub status: ImportStatus,
+    pub message: Option<String>,
+}
+
+/// User-facing shape of an exported prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedPrompt {
+    #[serde(default)]
+    pub id: String,
+    pub title: Option<String>,
+    pub text: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created: Option<String>,
+    pub description: Option<String>,
+}
+
+impl From<Prompt> for ExportedPrompt {
+    fn from(prompt: Prompt) -> Self {
+        Self {
+            id: prompt.id,
+            title: prompt.title,
+            text: prompt.text,
+            tags: prompt.tags,
+            created: prompt.created,
+            description: prompt.description,
+        }
+    }
+}
+
+/// Render prompts in the requested export format
+pub fn render_export(prompts: Vec<Prompt>, format: ExportFormat) -> Result<String, DbError> {
+    let prompts: Vec<ExportedPrompt> = prompts.into_iter().map(ExportedPrompt::from).collect();
+
+    match format {
+        ExportFormat::Json => Ok(serde_json::to_string_pretty(&prompts)?),
+        ExportFormat::Yaml => {
+            serde_yaml::to_string(&prompts).map_err(|e| DbError::Serialization(e.to_string()))
+        }
+        ExportFormat::Csv => Ok(render_csv(&prompts)),
+    }
+}
+
+#[derive(Serialize)]
+struct PromptfooConfig {
+    description: String,
+    prompts: Vec<PromptfooPrompt>,
+}
+
+#[derive(Serialize)]
+struct PromptfooPrompt {
+    id: String,
+    label: String,
+    raw: String,
+}
+
+#[derive(Serialize)]
+struct LangchainPrompt {
+    #[serde(rename = "_type")]
+    kind: &'static str,
+    input_variables: Vec<String>,
+    template: String,
+    template_format: &'static str,
+    metadata: LangchainMetadata,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct LangchainMetadata {
+    id: String,
+    title: Option<String>,
+    description: Option<String>,
+}
+
+/// Render prompts (with includes already expanded) in the file format of another tool,
+/// converting their template syntax
+pub fn render_for_tool(prompts: Vec<Prompt>, tool: ExportTool) -> Result<String, DbError> {
+    // Name the prompt a template error comes from
+    let failed = |id: &str, e: TemplateError| DbError::InvalidInput(format!("{}: {}", id, e));
+
+    match tool {
+        ExportTool::Promptfoo => {
+            let prompts = prompts
+                .into_iter()
+                .map(|prompt| {
+                    let converted = template::convert(&prompt.text, Dialect::Nunjucks)
+                        .map_err(|e| failed(&prompt.id, e))?;
+                    Ok(PromptfooPrompt {
+                        label: prompt.title.unwrap_or_else(|| prompt.id.clone()),
+                        id: prompt.id,
+                        raw: converted.text,
+                    })
+                })
+                .collect::<Result<_, DbError>>()?;
+            let config = PromptfooConfig {
+                description: "Prompts exported from Prompt Manager".to_string(),
+                prompts,
+            };
+            serde_yaml::to_string(&config).map_err(|e| DbError::Serialization(e.to_string()))
+        }
+        ExportTool::Langchain => {
+            let prompts = prompts
+                .into_iter()
+                .map(|prompt| {
+                    // f-strings are LangChain's default; blocks and dotted names need mustache
+                    let (converted, template_format) =
+                        match template::convert(&prompt.text, Dialect::FString) {
+                            Ok(converted) => (converted, "f-string"),
+                            Err(TemplateError::Unconvertible(_)) => (
+                                template::convert(&prompt.text, Dialect::Mustache)
+                                    .map_err(|e| failed(&prompt.id, e))?,
+                                "mustache",
+                            ),
+                            Err(e) => return Err(failed(&prompt.id, e)),
+                        };
+                    Ok(LangchainPrompt {
+                        kind: "prompt",
+                        input_variables: converted.variables,
+                        template: converted.text,
+                        template_format,
+                        metadata: LangchainMetadata {
+                            id: prompt.id,
+                            title: prompt.title,
+                            description: prompt.description,
+                        },
+                        tags: prompt.tags,
+                    })
+                })
+                .collect::<Result<Vec<_>, DbError>>()?;
+            Ok(serde_json::to_string_pretty(&prompts)?)
+        }
+    }
+}
+
+fn render_csv(prompts: &[ExportedPrompt]) -> String {
+    let mut out = String::from("id,title,text,tags,created,description\r\n");
+    for prompt in prompts {
+        let fields = [
+            prompt.id.as_str(),
+            prompt.title.as_deref().unwrap_or(""),
+            prompt.text.as_str(),
+            &prompt.tags.join(", "),
+            prompt.created.as_deref().unwrap_or(""),
+            prompt.description.as_deref().unwrap_or(""),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Quote a CSV field if needed (RFC 4180)
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse prompts from a file previously written by `render_export` (or a compatible tool)
+pub fn parse_import(content: &str, format: ExportFormat) -> Result<Vec<ExportedPrompt>, DbError> {
+    let content = content.trim_start_matches('\u{feff}');
+    match format {
+        ExportFormat::Json => Ok(serde_json::from_str(content)?),
+        ExportFormat::Yaml => {
+            serde_yaml::from_str(content).map_err(|e| DbError::Serialization(e.to_string()))
+        }
+        ExportFormat::Csv => parse_csv(content),
+    }
+}
+
+/// Map CSV columns by header name; only `text` is required
+fn parse_csv(content: &str) -> Result<Vec<ExportedPrompt>, DbError> {
+    let mut records = parse_csv_records(content).into_iter();
+    let header: Vec<String> = match records.next() {
+        Some(header) => header.iter().map(|h| h.trim().to_lowercase()).collect(),
+        None => return Ok(Vec::new()),
+    };
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let text_column = column("text")
+        .ok_or_else(|| DbError::Serialization("CSV is missing a \"text\" column".to_string()))?;
+    let (id, title, tags, created, description) = (
+        column("id"),
+        column("title"),
+        column("tags"),
+        column("created"),
+        column("description"),
+    );
+
+    let mut prompts = Vec::new();
+    for record in records {
+        // Skip blank lines
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|i| record.get(i))
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        prompts.push(ExportedPrompt {
+            id: field(id).unwrap_or_default(),
+            title: field(title),
+            text: record.get(text_column).cloned().unwrap_or_default(),
+            tags: field(tags)
+                .map(|tags| {
+                    tags.split(',')
+                        .map(|tag| tag.trim().to_string())
+                        .filter(|tag| !tag.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            created: field(created),
+            description: field(description),
+        });
+    }
+    Ok(prompts)
+}
+
+/// Split CSV text into records of unquoted fields (RFC 4180, CRLF or LF line endings)
+fn parse_csv_records(content: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value as JsonValue;
+
+    #[test]
+    fn test_csv_quoting() {
+        let prompts = vec![ExportedPrompt {
+            id: "a.md".to_string(),
+            title: Some("Say \"hi\"".to_string()),
+            text: "line one\nline two".to_string(),
+            tags: vec!["x".to_string(), "y".to_string()],
+            created: None,
+            description: None,
+        }];
+        assert_eq!(
+            render_csv(&prompts),
+            "id,title,text,tags,created,description\r\n\
+             a.md,\"Say \"\"hi\"\"\",\"line one\nline two\",\"x, y\",,\r\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_round_trip() {
+        let prompts = vec![Prompt {
+            id: "notes/a.md".to_string(),
+            created: Some("2024-05-01T10:00:00".to_string()),
+            text: "Say \"hi\",\r\nthen stop".to_string(),
+            tags: vec!["x".to_string(), "y/z".to_string()],
+            file_path: Some("notes/a.md".to_string()),
+            title: Some("Greeting".to_string()),
+            description: None,
+            file_hash: None,
+            custom_fields: Default::default(),
+            token_count: None,
+            archived: false,
+            updated_at: None,
+            variables: Vec::new(),
+            revision: 0,
+            color: None,
+            icon: None,
+            notes: None,
+            model_targets: Vec::new(),
+            language: None,
+            status: None,
+            char_count: None,
+            word_count: None,
+            line_count: None,
+        }];
+        let csv = render_export(prompts, ExportFormat::Csv).unwrap();
+        let parsed = parse_import(&csv, ExportFormat::Csv).unwrap();
+
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, "notes/a.md");
+        assert_eq!(parsed[0].title.as_deref(), Some("Greeting"));
+        assert_eq!(parsed[0].text, "Say \"hi\",\r\nthen stop");
+        assert_eq!(parsed[0].tags, vec!["x", "y/z"]);
+        assert_eq!(parsed[0].created.as_deref(), Some("2024-05-01T10:00:00"));
+        assert_eq!(parsed[0].description, None);
+    }
+
+    #[test]
+    fn test_render_for_langchain() {
+        let prompt = |id: &str, text: &str| Prompt {
+            id: id.to_string(),
+            created: None,
+            text: text.to_string(),
+            tags: vec!["review".to_string()],
+            file_path: Some(id.to_string()),
+            title: None,
+            description: None,
+            file_hash: None,
+            custom_fields: Default::default(),
+            token_count: None,
+            archived: false,
+            updated_at: None,
+            variables: Vec::new(),
+            revision: 0,
+            color: None,
+            icon: None,
+            notes: None,
+            model_targets: Vec::new(),
+            language: None,
+            status: None,
+            char_count: None,
+            word_count: None,
+            line_count: None,
+        };
+        let json = render_for_tool(
+            vec![
+                prompt("a.md", "Review {{language}} as JSON: {\"ok\": true}"),
+                prompt("b.md", "{{#each files}}{{this}}{{/each}}"),
+            ],
+            ExportTool::Langchain,
+        )
+        .unwrap();
+        let exported: JsonValue = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(exported[0]["_type"], "prompt");
+        assert_eq!(exported[0]["template_format"], "f-string");
+        assert_eq!(
+            exported[0]["template"],
+            "Review {language} as JSON: {{\"ok\": true}}"
+        );
+        assert_eq!(
+            exported[0]["input_variables"],
+            serde_json::json!(["language"])
+        );
+        assert_eq!(exported[0]["metadata"]["id"], "a.md");
+        assert_eq!(exported[1]["template_format"], "mustache");
+        assert_eq!(exported[1]["template"], "{{#files}}{{{.}}}{{/files}}");
+
+        let broken = render_for_tool(vec![prompt("c.md", "{{#if x}}")], ExportTool::Promptfoo);
+        assert!(broken.unwrap_err().to_string().contains("c.md"));
+    }
+
+    #[test]
+    fn test_csv_import_requires_text_column() {
+        assert!(parse_import("id,title\r\na.md,A\r\n", ExportFormat::Csv).is_err());
+        let parsed = parse_import("Title,Text\nA,hello\n\n", ExportFormat::Csv).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].text, "hello");
+        assert!(parsed[0].id.is_empty());
+    }
+}
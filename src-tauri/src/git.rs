@@ -0,0 +1,216 @@
+//! Optional git history for the vault (enabled with `AppConfig::git_history`)
+
+use chrono::{DateTime, Local};
+use git2::{Commit, Index, IndexAddOption, Oid, Repository, Signature, Sort};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+use tracing::info;
+
+/// Author used when git has no `user.name`/`user.email` configured
+const FALLBACK_AUTHOR_NAME: &str = "Prompt Manager";
+const FALLBACK_AUTHOR_EMAIL: &str = "prompt-manager@localhost";
+
+/// A commit that touched a prompt file
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptCommit {
+    pub sha: String,
+    pub message: String,
+    pub author: String,
+    pub timestamp: String,
+}
+
+/// Open the vault repository, initializing one (with a snapshot of existing prompts) on first use
+fn open_or_init(vault_path: &Path) -> Result<Repository, git2::Error> {
+    if let Ok(repo) = Repository::open(vault_path) {
+        return Ok(repo);
+    }
+
+    info!("Initializing git repository in {:?}", vault_path);
+    let repo = Repository::init(vault_path)?;
+    let mut index = repo.index()?;
+    index.add_all(["*.md"], IndexAddOption::DEFAULT, None)?;
+    commit_index(&repo, &mut index, "Snapshot existing prompts")?;
+    Ok(repo)
+}
+
+/// Stage `paths` (vault-relative; missing files are removed from the index) and commit them.
+/// Returns the new commit id, or `None` when nothing changed.
+pub fn commit_paths(
+    vault_path: &Path,
+    paths: &[&str],
+    message: &str,
+) -> Result<Option<String>, git2::Error> {
+    let repo = open_or_init(vault_path)?;
+    let mut index = repo.index()?;
+    for path in paths {
+        let relative = Path::new(path);
+        if vault_path.join(relative).is_file() {
+            index.add_path(relative)?;
+        } else {
+            index.remove_path(relative)?;
+        }
+    }
+    commit_index(&repo, &mut index, message)
+}
+
+/// Commit the staged index on top of HEAD, unless its tree is unchanged
+fn commit_index(
+    repo: &Repository,
+    index: &mut Index,
+    message: &str,
+) -> Result<Option<String>, git2::Error> {
+    index.write()?;
+    let tree_id = index.write_tree()?;
+    let parent = head_commit(repo)?;
+    match &parent {
+        Some(parent) if parent.tree_id() == tree_id => return Ok(None),
+        // Nothing to snapshot in an empty vault
+        None if index.is_empty() => return Ok(None),
+        _ => {}
+    }
+
+    let tree = repo.find_tree(tree_id)?;
+    let signature = repo
+        .signature()
+        .or_else(|_| Signature::now(FALLBACK_AUTHOR_NAME, FALLBACK_AUTHOR_EMAIL))?;
+    let parents: Vec<&Commit> = parent.iter().collect();
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    info!("Committed {} to vault history: {}", oid, message);
+    Ok(Some(oid.to_string()))
+}
+
+/// Commits that changed `relative_path`, newest first
+pub fn file_history(
+    vault_path: &Path,
+    relative_path: &str,
+) -> Result<Vec<PromptCommit>, git2::Error> {
+    let repo = match Repository::open(vault_path) {
+        Ok(repo) => repo,
+        Err(_) => return Ok(Vec::new()),
+    };
+    if head_commit(&repo)?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let mut revwalk = repo.revwalk()?;
+    revwalk.push_head()?;
+    revwalk.set_sorting(Sort::TIME)?;
+
+    let path = Path::new(relative_path);
+    let mut history = Vec::new();
+    for oid in revwalk {
+        let commit = repo.find_commit(oid?)?;
+        let blob = blob_id(&commit, path);
+        let parent_blob = match commit.parent(0) {
+            Ok(parent) => blob_id(&parent, path),
+            Err(_) => None,
+        };
+        if blob != parent_blob {
+            history.push(to_prompt_commit(&commit));
+        }
+    }
+    Ok(history)
+}
+
+/// Contents of `relative_path` as of commit `sha` (`None` if the file did not exist)
+pub fn file_at_commit(
+    vault_path: &Path,
+    relative_path: &str,
+    sha: &str,
+) -> Result<Option<String>, git2::Error> {
+    let repo = Repository::open(vault_path)?;
+    let commit = repo.find_commit(Oid::from_str(sha)?)?;
+    let blob_id = match blob_id(&commit, Path::new(relative_path)) {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    let blob = repo.find_blob(blob_id)?;
+    Ok(Some(String::from_utf8_lossy(blob.content()).to_string()))
+}
+
+fn head_commit(repo: &Repository) -> Result<Option<Commit<'_>>, git2::Error> {
+    match repo.head() {
+        Ok(head) => Ok(Some(head.peel_to_commit()?)),
+        Err(e)
+            if e.code() == git2::ErrorCode::UnbornBranch
+                || e.code() == git2::ErrorCode::NotFound =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn blob_id(commit: &Commit, path: &Path) -> Option<Oid> {
+    commit
+        .tree()
+        .ok()?
+        .get_path(path)
+        .ok()
+        .map(|entry| entry.id())
+}
+
+fn to_prompt_commit(commit: &Commit) -> PromptCommit {
+    let timestamp = DateTime::from_timestamp(commit.time().seconds(), 0)
+        .map(|time| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%dT%H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default();
+    PromptCommit {
+        sha: commit.id().to_string(),
+        message: commit.summary().unwrap_or_default().to_string(),
+        author: commit.author().name().unwrap_or_default().to_string(),
+        timestamp,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::fs;
+
+    #[test]
+    fn test_commit_and_history() {
+        let root = TempDir::new("vault-git");
+        fs::write(root.join("existing.md"), "before history").unwrap();
+        open_or_init(&root).unwrap();
+        assert_eq!(file_history(&root, "existing.md").unwrap().len(), 1);
+
+        fs::write(root.join("a.md"), "first").unwrap();
+        let first = commit_paths(&root, &["a.md"], "Add a.md").unwrap().unwrap();
+        // Committing an unchanged file is a no-op
+        assert_eq!(commit_paths(&root, &["a.md"], "Update a.md").unwrap(), None);
+
+        fs::write(root.join("b.md"), "other").unwrap();
+        commit_paths(&root, &["b.md"], "Add b.md").unwrap();
+        fs::write(root.join("a.md"), "second").unwrap();
+        commit_paths(&root, &["a.md"], "Update a.md").unwrap();
+        fs::remove_file(root.join("a.md")).unwrap();
+        commit_paths(&root, &["a.md"], "Delete a.md").unwrap();
+
+        let history = file_history(&root, "a.md").unwrap();
+        let messages: Vec<_> = history.iter().map(|c| c.message.as_str()).collect();
+        assert_eq!(messages.len(), 3);
+        assert!(messages.contains(&"Add a.md"));
+        assert!(!messages.contains(&"Add b.md"));
+
+        assert_eq!(
+            file_at_commit(&root, "a.md", &first).unwrap().as_deref(),
+            Some("first")
+        );
+        let deleted = history.iter().find(|c| c.message == "Delete a.md").unwrap();
+        assert_eq!(file_at_commit(&root, "a.md", &deleted.sha).unwrap(), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Import user messages from a ChatGPT data export (`conversations.json`)
+
+use super::title_from_text;
+use crate::export::ExportedPrompt;
+use crate::models::DbError;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use specta::Type;
+use std::collections::HashMap;
+
+/// Tag added to every prompt imported from ChatGPT
+pub const CHATGPT_TAG: &str = "imported/chatgpt";
+
+/// Title ChatGPT gives conversations it never named
+const UNTITLED_CONVERSATION: &str = "New chat";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatGptImportOptions {
+    /// Only import the opening user message of each conversation
+    #[serde(default)]
+    pub first_message_only: bool,
+}
+
+#[derive(Deserialize)]
+struct Conversation {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    conversation_id: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    create_time: Option<f64>,
+    #[serde(default)]
+    mapping: HashMap<String, Node>,
+}
+
+#[derive(Deserialize)]
+struct Node {
+    #[serde(default)]
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    author: Author,
+    #[serde(default)]
+    content: Option<Content>,
+    #[serde(default)]
+    create_time: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct Author {
+    role: String,
+}
+
+#[derive(Deserialize)]
+struct Content {
+    #[serde(default)]
+    content_type: Option<String>,
+    #[serde(default)]
+    parts: Vec<JsonValue>,
+}
+
+/// Extract user messages from `conversations.json` as importable prompts,
+/// in conversation order
+pub fn parse_conversations(
+    content: &str,
+    options: &ChatGptImportOptions,
+) -> Result<Vec<ExportedPrompt>, DbError> {
+    let conversations: Vec<Conversation> = serde_json::from_str(content)?;
+    let mut prompts = Vec::new();
+
+    for conversation in conversations {
+        let conversation_id = conversation
+            .id
+            .clone()
+            .or_else(|| conversation.conversation_id.clone())
+            .unwrap_or_default();
+
+        let mut messages: Vec<(f64, &String, String)> = conversation
+            .mapping
+            .iter()
+            .filter_map(|(node_id, node)| {
+                let message = node.message.as_ref()?;
+                let text = user_message_text(message)?;
+                let time = message
+                    .create_time
+                    .or(conversation.create_time)
+                    .unwrap_or(0.0);
+                Some((time, node_id, text))
+            })
+            .collect();
+        // Mapping order is arbitrary; node ids keep ties deterministic
+        messages.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+        if options.first_message_only {
+            messages.truncate(1);
+        }
+
+        let base_title = conversation
+            .title
+            .as_deref()
+            .map(str::trim)
+            .filter(|title| !title.is_empty() && *title != UNTITLED_CONVERSATION)
+            .map(str::to_string);
+
+        for (index, (time, _, text)) in messages.into_iter().enumerate() {
+            let title = match (&base_title, index) {
+                (Some(title), 0) => Some(title.clone()),
+                (Some(title), n) => Some(format!("{} ({})", title, n + 1)),
+                (None, _) => title_from_text(&text),
+            };
+            prompts.push(ExportedPrompt {
+                id: conversation_id.clone(),
+                title,
+                text,
+                tags: vec![CHATGPT_TAG.to_string()],
+                created: format_timestamp(time),
+                description: None,
+            });
+        }
+    }
+
+    Ok(prompts)
+}
+
+/// Text of a user-authored message, ignoring hidden context and non-text parts
+fn user_message_text(message: &Message) -> Option<String> {
+    if message.author.role != "user" {
+        return None;
+    }
+    let content = message.content.as_ref()?;
+    match content.content_type.as_deref() {
+        None | Some("text") | Some("multimodal_text") => {}
+        Some(_) => return None,
+    }
+
+    let text = content
+        .parts
+        .iter()
+        .filter_map(JsonValue::as_str)
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn format_timestamp(seconds: f64) -> Option<String> {
+    if seconds <= 0.0 {
+        return None;
+    }
+    DateTime::from_timestamp(seconds as i64, 0).map(|time| {
+        time.with_timezone(&Local)
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"[
+        {
+            "id": "conv-1",
+            "title": "Refactor help",
+            "create_time": 1700000000.5,
+            "mapping": {
+                "root": { "message": null },
+                "b": {
+                    "message": {
+                        "author": { "role": "assistant" },
+                        "content": { "content_type": "text", "parts": ["Sure!"] },
+                        "create_time": 1700000010.0
+                    }
+                },
+                "c": {
+                    "message": {
+                        "author": { "role": "user" },
+                        "content": { "content_type": "text", "parts": ["Now add tests"] },
+                        "create_time": 1700000020.0
+                    }
+                },
+                "a": {
+                    "message": {
+                        "author": { "role": "user" },
+                        "content": { "content_type": "text", "parts": ["Refactor this function"] },
+                        "create_time": 1700000005.0
+                    }
+                },
+                "ctx": {
+                    "message": {
+                        "author": { "role": "user" },
+                        "content": { "content_type": "user_editable_context", "parts": [] }
+                    }
+                }
+            }
+        },
+        {
+            "id": "conv-2",
+            "title": "New chat",
+            "mapping": {
+                "x": {
+                    "message": {
+                        "author": { "role": "user" },
+                        "content": { "content_type": "multimodal_text", "parts": [{ "asset": "img" }, "Describe this image"] },
+                        "create_time": 1700000100.0
+                    }
+                }
+            }
+        }
+    ]"#;
+
+    #[test]
+    fn test_parse_conversations() {
+        let prompts = parse_conversations(EXPORT, &ChatGptImportOptions::default()).unwrap();
+        let texts: Vec<_> = prompts.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Refactor this function",
+                "Now add tests",
+                "Describe this image"
+            ]
+        );
+
+        let titles: Vec<_> = prompts.iter().map(|p| p.title.as_deref()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                Some("Refactor help"),
+                Some("Refactor help (2)"),
+                Some("Describe this image")
+            ]
+        );
+        assert!(prompts.iter().all(|p| p.tags == vec![CHATGPT_TAG]));
+        assert!(prompts[0].created.is_some());
+    }
+
+    #[test]
+    fn test_first_message_only() {
+        let options = ChatGptImportOptions {
+            first_message_only: true,
+        };
+        let prompts = parse_conversations(EXPORT, &options).unwrap();
+        assert_eq!(prompts.len(), 2);
+        assert_eq!(prompts[0].text, "Refactor this function");
+        assert_eq!(prompts[0].id, "conv-1");
+    }
+}
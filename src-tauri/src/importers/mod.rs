@@ -0,0 +1,41 @@
+//! Converters that turn other tools' data into prompts for the vault
+
+pub mod chatgpt;
+pub mod obsidian;
+
+/// Maximum length of a title generated from prompt text
+const GENERATED_TITLE_MAX_CHARS: usize = 60;
+
+/// Build a title from the first non-empty line of `text`, cut at a word boundary
+pub fn title_from_text(text: &str) -> Option<String> {
+    let line = text.lines().map(str::trim).find(|line| !line.is_empty())?;
+    if line.chars().count() <= GENERATED_TITLE_MAX_CHARS {
+        return Some(line.to_string());
+    }
+
+    let truncated: String = line.chars().take(GENERATED_TITLE_MAX_CHARS).collect();
+    let cut = match truncated.rfind(char::is_whitespace) {
+        Some(index) if index > 0 => &truncated[..index],
+        _ => truncated.as_str(),
+    };
+    Some(format!("{}…", cut.trim_end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_from_text() {
+        assert_eq!(
+            title_from_text("\n  Fix my code\nmore"),
+            Some("Fix my code".to_string())
+        );
+        assert_eq!(title_from_text("   \n"), None);
+
+        let long = "word ".repeat(30);
+        let title = title_from_text(&long).unwrap();
+        assert!(title.ends_with("word…"));
+        assert!(title.chars().count() <= GENERATED_TITLE_MAX_CHARS + 1);
+    }
+}
@@ -0,0 +1,243 @@
+//! Import tagged notes from an Obsidian vault
+
+use crate::config::{ContentMode, FrontmatterSettings};
+use crate::vault::{self, VaultError};
+use chrono::{DateTime, Local};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Obsidian's legacy single-tag frontmatter key
+const LEGACY_TAG_KEY: &str = "tag";
+
+/// Frontmatter key Obsidian templates commonly use instead of `created`
+const DATE_KEY: &str = "date";
+
+/// An Obsidian note converted to this app's conventions
+#[derive(Debug, Clone)]
+pub struct ObsidianNote {
+    /// Path of the note relative to the Obsidian vault
+    pub source_path: String,
+    /// File name to use in the prompt vault
+    pub file_name: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub created: Option<String>,
+    pub tags: Vec<String>,
+    pub text: String,
+    pub custom_fields: HashMap<String, JsonValue>,
+}
+
+/// Find notes under `root` tagged with `tag_filter` (or a tag nested under it),
+/// from either frontmatter or inline `#tags`
+pub fn find_tagged_notes(root: &Path, tag_filter: &str) -> Result<Vec<ObsidianNote>, VaultError> {
+    if !root.is_dir() {
+        return Err(VaultError::PathNotFound(root.display().to_string()));
+    }
+
+    let mut paths = Vec::new();
+    collect_markdown_files(root, &mut paths)?;
+    paths.sort();
+
+    // Read whole note bodies; prompt fences are picked out below
+    let settings = FrontmatterSettings {
+        content_mode: ContentMode::Body,
+        ..FrontmatterSettings::default()
+    };
+
+    let mut notes = Vec::new();
+    for path in paths {
+        let file = match vault::read_prompt_file(root, &path, &settings) {
+            Ok(file) => file,
+            Err(e) => {
+                info!("Skipping note {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        let mut custom_fields = file.custom_fields;
+        let mut tags = file.tags;
+        let extra_tags = custom_fields
+            .remove(LEGACY_TAG_KEY)
+            .map(|value| json_tags(&value))
+            .unwrap_or_default();
+        for tag in extra_tags.into_iter().chain(inline_tags(&file.content)) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        if !tags.iter().any(|tag| vault::tag_matches(tag, tag_filter)) {
+            continue;
+        }
+
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let created = file
+            .created
+            .or_else(|| {
+                custom_fields
+                    .get(DATE_KEY)
+                    .and_then(JsonValue::as_str)
+                    .map(str::to_string)
+            })
+            .or_else(|| modified_time(&path));
+
+        // Notes written for this app keep just their prompt fence
+        let fenced = vault::extract_code_block_content(&file.content, ContentMode::Fence);
+        let text = if fenced.is_empty() {
+            file.content
+        } else {
+            fenced
+        };
+
+        notes.push(ObsidianNote {
+            source_path: file.file_path,
+            file_name: format!("{}.md", stem),
+            title: file.title.or(Some(stem)),
+            description: file.description,
+            created,
+            tags,
+            text,
+            custom_fields,
+        });
+    }
+
+    info!("Found {} Obsidian notes tagged {}", notes.len(), tag_filter);
+    Ok(notes)
+}
+
+/// Recursively collect `.md` files, skipping hidden folders like `.obsidian` and `.trash`
+fn collect_markdown_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), VaultError> {
+    let entries = fs::read_dir(dir).map_err(|e| VaultError::IoError(e.to_string()))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if path.is_dir() {
+            if !hidden {
+                collect_markdown_files(&path, paths)?;
+            }
+        } else if !hidden && path.extension().and_then(|ext| ext.to_str()) == Some("md") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Tags from a frontmatter value that is either a list or a comma/space separated string
+fn json_tags(value: &JsonValue) -> Vec<String> {
+    match value {
+        JsonValue::Array(items) => items
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .filter_map(vault::normalize_tag)
+            .collect(),
+        JsonValue::String(text) => text
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter_map(vault::normalize_tag)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Inline `#tags` in a note body, ignoring code blocks, headings and numbers like `#1`
+fn inline_tags(body: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut fence: Option<String> = None;
+
+    for line in body.lines() {
+        let trimmed = line.trim_start();
+        if let Some(open) = &fence {
+            if trimmed.starts_with(open.as_str()) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            let marker = trimmed.chars().next().unwrap_or('`');
+            let len = trimmed.chars().take_while(|c| *c == marker).count();
+            fence = Some(marker.to_string().repeat(len));
+            continue;
+        }
+
+        let mut previous = ' ';
+        for (index, c) in line.char_indices() {
+            if c == '#' && previous.is_whitespace() {
+                let rest = &line[index + 1..];
+                let end = rest
+                    .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '/')))
+                    .unwrap_or(rest.len());
+                let candidate = &rest[..end];
+                if candidate.chars().any(|c| !c.is_ascii_digit()) {
+                    if let Some(tag) = vault::normalize_tag(candidate) {
+                        if !tags.contains(&tag) {
+                            tags.push(tag);
+                        }
+                    }
+                }
+            }
+            previous = c;
+        }
+    }
+    tags
+}
+
+fn modified_time(path: &Path) -> Option<String> {
+    let modified = fs::metadata(path).and_then(|meta| meta.modified()).ok()?;
+    let time: DateTime<Local> = modified.into();
+    Some(time.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_inline_tags() {
+        let body = "Use this #prompt for #lang/rust reviews.\n\
+                    # Heading\n\
+                    Issue #42 and C#\n\
+                    ```\n#not-a-tag\n```\n\
+                    #writing";
+        assert_eq!(inline_tags(body), vec!["prompt", "lang/rust", "writing"]);
+    }
+
+    #[test]
+    fn test_find_tagged_notes() {
+        let root = TempDir::new("obsidian-import");
+        fs::create_dir_all(root.join("Prompts")).unwrap();
+        fs::create_dir_all(root.join(".obsidian")).unwrap();
+        fs::write(
+            root.join("Prompts/Review.md"),
+            "---\ntags: [prompt/code]\ndate: 2024-01-02\naliases: [CR]\n---\n\nReview this diff.\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("Fenced.md"),
+            "Notes first. #prompt\n\n```prompt\nSummarize the text.\n```\n",
+        )
+        .unwrap();
+        fs::write(root.join("Journal.md"), "Nothing to see #daily\n").unwrap();
+        fs::write(root.join(".obsidian/Hidden.md"), "#prompt\n").unwrap();
+
+        let notes = find_tagged_notes(&root, "#prompt").unwrap();
+
+        assert_eq!(notes.len(), 2);
+        let fenced = &notes[0];
+        assert_eq!(fenced.file_name, "Fenced.md");
+        assert_eq!(fenced.title.as_deref(), Some("Fenced"));
+        assert_eq!(fenced.text, "Summarize the text.");
+        assert_eq!(fenced.tags, vec!["prompt"]);
+
+        let review = &notes[1];
+        assert_eq!(review.source_path.replace('\\', "/"), "Prompts/Review.md");
+        assert_eq!(review.text, "Review this diff.");
+        assert_eq!(review.tags, vec!["prompt/code"]);
+        assert_eq!(review.created.as_deref(), Some("2024-01-02"));
+        assert!(review.custom_fields.contains_key("aliases"));
+    }
+}
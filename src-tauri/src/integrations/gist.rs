@@ -0,0 +1,193 @@
+//! GitHub Gist client: share a prompt file as a gist and fetch one back
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::BTreeMap;
+use tauri_plugin_http::reqwest;
+
+const GISTS_API_URL: &str = "https://api.github.com/gists";
+/// GitHub rejects API requests without a user agent
+const USER_AGENT: &str = "prompt-manager";
+
+/// Gist errors
+#[derive(Debug, Clone, Serialize, thiserror::Error, Type)]
+pub enum GistError {
+    #[error("No GitHub token configured")]
+    MissingToken,
+    #[error("Not a gist URL or id: {0}")]
+    InvalidUrl(String),
+    #[error("Gist request failed: {0}")]
+    Request(String),
+    #[error("Invalid gist: {0}")]
+    InvalidResponse(String),
+}
+
+/// A gist created by `create_gist`
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedGist {
+    pub id: String,
+    /// Page of the gist on gist.github.com
+    pub url: String,
+}
+
+/// A file of a fetched gist
+#[derive(Debug, Clone)]
+pub struct GistFile {
+    pub filename: String,
+    pub content: String,
+}
+
+#[derive(Serialize)]
+struct CreateGistRequest<'a> {
+    description: &'a str,
+    public: bool,
+    files: BTreeMap<&'a str, CreateGistFile<'a>>,
+}
+
+#[derive(Serialize)]
+struct CreateGistFile<'a> {
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct GistResponse {
+    id: String,
+    html_url: String,
+    #[serde(default)]
+    files: BTreeMap<String, GistResponseFile>,
+}
+
+#[derive(Deserialize)]
+struct GistResponseFile {
+    filename: String,
+    #[serde(default)]
+    content: Option<String>,
+    /// Large files are cut short in the API response and must be read from `raw_url`
+    #[serde(default)]
+    truncated: bool,
+    raw_url: String,
+}
+
+/// Gist id in a gist URL (`https://gist.github.com/user/<id>`, a raw file URL) or a bare id
+pub fn gist_id(url: &str) -> Result<String, GistError> {
+    let url = url.trim();
+    let is_id = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_hexdigit());
+    if is_id(url) {
+        return Ok(url.to_string());
+    }
+
+    let parsed = reqwest::Url::parse(url).map_err(|_| GistError::InvalidUrl(url.to_string()))?;
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    let candidate = match parsed.host_str() {
+        // gist.github.com/<id> or gist.github.com/<user>/<id>
+        Some("gist.github.com") => segments.iter().take(2).rev().find(|s| is_id(s)),
+        // gist.githubusercontent.com/<user>/<id>/raw/...
+        Some("gist.githubusercontent.com") => segments.get(1),
+        _ => None,
+    };
+    candidate
+        .filter(|id| is_id(id))
+        .map(|id| id.to_string())
+        .ok_or_else(|| GistError::InvalidUrl(url.to_string()))
+}
+
+fn request(builder: reqwest::RequestBuilder, token: Option<&str>) -> reqwest::RequestBuilder {
+    let builder = builder
+        .header(reqwest::header::USER_AGENT, USER_AGENT)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json");
+    match token.filter(|token| !token.is_empty()) {
+        Some(token) => builder.bearer_auth(token),
+        None => builder,
+    }
+}
+
+/// Create a gist holding a single file
+pub async fn create_gist(
+    token: &str,
+    filename: &str,
+    content: &str,
+    description: &str,
+    public: bool,
+) -> Result<SharedGist, GistError> {
+    let body = CreateGistRequest {
+        description,
+        public,
+        files: BTreeMap::from([(filename, CreateGistFile { content })]),
+    };
+    let response = request(reqwest::Client::new().post(GISTS_API_URL), Some(token))
+        .json(&body)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| GistError::Request(e.to_string()))?;
+    let gist: GistResponse = response
+        .json()
+        .await
+        .map_err(|e| GistError::InvalidResponse(e.to_string()))?;
+    Ok(SharedGist {
+        id: gist.id,
+        url: gist.html_url,
+    })
+}
+
+/// Fetch the files of gist `id`; `token` is only needed for secret gists of rate-limited users
+pub async fn fetch_gist(token: Option<&str>, id: &str) -> Result<Vec<GistFile>, GistError> {
+    let client = reqwest::Client::new();
+    let response = request(client.get(format!("{}/{}", GISTS_API_URL, id)), token)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| GistError::Request(e.to_string()))?;
+    let gist: GistResponse = response
+        .json()
+        .await
+        .map_err(|e| GistError::InvalidResponse(e.to_string()))?;
+
+    let mut files = Vec::with_capacity(gist.files.len());
+    for file in gist.files.into_values() {
+        let content = match file.content {
+            Some(content) if !file.truncated => content,
+            _ => request(client.get(&file.raw_url), token)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| GistError::Request(e.to_string()))?
+                .text()
+                .await
+                .map_err(|e| GistError::InvalidResponse(e.to_string()))?,
+        };
+        files.push(GistFile {
+            filename: file.filename,
+            content,
+        });
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gist_id() {
+        let id = "aa5a315d61ae9438b18d";
+        for url in [
+            id.to_string(),
+            format!("https://gist.github.com/octocat/{}", id),
+            format!("https://gist.github.com/{}#file-review-md", id),
+            format!(
+                "https://gist.githubusercontent.com/octocat/{}/raw/abc123/review.md",
+                id
+            ),
+        ] {
+            assert_eq!(gist_id(&url).unwrap(), id, "{}", url);
+        }
+        assert!(gist_id("https://github.com/octocat/repo").is_err());
+        assert!(gist_id("https://gist.github.com/octocat").is_err());
+        assert!(gist_id("not a gist").is_err());
+    }
+}
@@ -0,0 +1,5 @@
+//! Clients for external services prompts can be shared through or stored on
+
+pub mod gist;
+pub mod s3;
+pub mod webdav;
@@ -0,0 +1,338 @@
+//! S3-compatible client (AWS, MinIO, Backblaze B2) keeping vault files in a bucket,
+//! with requests signed by AWS Signature Version 4
+
+use crate::config::S3Settings;
+use crate::secrets::{self, SecretName};
+use crate::vault::{self, VaultError};
+use crate::vault_backend::{self, BackendFile, VaultBackend};
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use tauri_plugin_http::reqwest::{self, Method, StatusCode, Url};
+
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// A folder in an S3 bucket holding the prompt files of a vault
+pub struct S3Backend {
+    client: reqwest::Client,
+    endpoint: Url,
+    region: String,
+    bucket: String,
+    /// Empty or ending with `/`
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+    /// Prompt file extensions, as in `FrontmatterSettings::file_extensions`
+    extensions: Vec<String>,
+}
+
+impl S3Backend {
+    pub fn new(settings: &S3Settings, extensions: &[String]) -> Result<Self, VaultError> {
+        let endpoint = Url::parse(settings.endpoint.trim())
+            .map_err(|e| VaultError::Remote(format!("Invalid S3 endpoint: {}", e)))?;
+        let bucket = settings.bucket.trim();
+        if bucket.is_empty() {
+            return Err(VaultError::Remote("No S3 bucket configured".to_string()));
+        }
+        let access_key_id = settings
+            .access_key_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+        let (Some(access_key_id), Some(secret_access_key)) = (
+            access_key_id,
+            secrets::lookup(SecretName::S3SecretAccessKey),
+        ) else {
+            return Err(VaultError::Remote(
+                "No S3 credentials configured".to_string(),
+            ));
+        };
+        let prefix = settings.prefix.trim().trim_matches('/');
+
+        Ok(Self {
+            client: reqwest::Client::new(),
+            endpoint,
+            region: settings.region.trim().to_string(),
+            bucket: bucket.to_string(),
+            prefix: if prefix.is_empty() {
+                String::new()
+            } else {
+                format!("{}/", prefix)
+            },
+            access_key_id,
+            secret_access_key,
+            extensions: extensions.to_vec(),
+        })
+    }
+
+    fn key(&self, path: &str) -> Result<String, VaultError> {
+        Ok(format!(
+            "{}{}",
+            self.prefix,
+            vault_backend::store_path(path, &self.extensions)?
+        ))
+    }
+
+    /// Signed request for `key` (the bucket itself when empty). Path-style URLs
+    /// (`{endpoint}/{bucket}/{key}`) work with AWS as well as MinIO and B2.
+    async fn send(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: String,
+    ) -> Result<reqwest::Response, VaultError> {
+        let mut path = format!(
+            "{}/{}",
+            self.endpoint.path().trim_end_matches('/'),
+            uri_encode(&self.bucket, false)
+        );
+        if !key.is_empty() {
+            path = format!("{}/{}", path, uri_encode(key, true));
+        }
+        let query = canonical_query(query);
+        let mut url = self.endpoint.clone();
+        url.set_path(&path);
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(VaultError::Remote("S3 endpoint has no host".to_string())),
+        };
+        let payload_hash = hex_sha256(body.as_bytes());
+        let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+            method, path, query, host, payload_hash, amz_date, SIGNED_HEADERS, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", &amz_date[..8], self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            hex_sha256(canonical_request.as_bytes())
+        );
+        let key = signing_key(&self.secret_access_key, &amz_date[..8], &self.region, "s3");
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key_id, scope, SIGNED_HEADERS, signature
+        );
+
+        self.client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| VaultError::Remote(e.to_string()))
+    }
+}
+
+impl VaultBackend for S3Backend {
+    fn identity(&self) -> String {
+        format!("s3:{}{}/{}", self.endpoint, self.bucket, self.prefix)
+    }
+
+    async fn list(&self) -> Result<Vec<BackendFile>, VaultError> {
+        let mut files = Vec::new();
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", self.prefix.as_str())];
+            if let Some(token) = &continuation {
+                query.push(("continuation-token", token));
+            }
+            let xml = self
+                .send(Method::GET, "", &query, String::new())
+                .await?
+                .error_for_status()
+                .map_err(|e| VaultError::Remote(e.to_string()))?
+                .text()
+                .await
+                .map_err(|e| VaultError::Remote(e.to_string()))?;
+            let (page, next) = parse_list_objects(&xml, &self.prefix, &self.extensions)?;
+            files.extend(page);
+            match next {
+                Some(token) => continuation = Some(token),
+                None => return Ok(files),
+            }
+        }
+    }
+
+    async fn read(&self, path: &str) -> Result<String, VaultError> {
+        let response = self
+            .send(Method::GET, &self.key(path)?, &[], String::new())
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(VaultError::NotFound(path.to_string()));
+        }
+        response
+            .error_for_status()
+            .map_err(|e| VaultError::Remote(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| VaultError::Remote(e.to_string()))
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<(), VaultError> {
+        self.send(Method::PUT, &self.key(path)?, &[], content.to_string())
+            .await?
+            .error_for_status()
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), VaultError> {
+        // S3 answers 204 whether or not the object existed
+        self.send(Method::DELETE, &self.key(path)?, &[], String::new())
+            .await?
+            .error_for_status()
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        Ok(())
+    }
+}
+
+/// Prompt files directly under `prefix` in a ListObjectsV2 response, with the token of
+/// the next page if the listing was cut short. The version is the ETag (an MD5 of the
+/// content for files uploaded in one part).
+fn parse_list_objects(
+    xml: &str,
+    prefix: &str,
+    extensions: &[String],
+) -> Result<(Vec<BackendFile>, Option<String>), VaultError> {
+    let document = roxmltree::Document::parse(xml)
+        .map_err(|e| VaultError::Remote(format!("Invalid S3 listing: {}", e)))?;
+    let child_text = |node: roxmltree::Node, name: &str| {
+        node.children()
+            .find(|child| child.tag_name().name() == name)
+            .and_then(|child| child.text())
+            .map(|text| text.trim().to_string())
+    };
+
+    let root = document.root_element();
+    let files = root
+        .children()
+        .filter(|node| node.tag_name().name() == "Contents")
+        .filter_map(|node| {
+            let key = child_text(node, "Key")?;
+            let name = key.strip_prefix(prefix)?;
+            // Vaults are flat; anything the vault could not hold is left in the bucket
+            if !vault::has_prompt_extension(name, extensions)
+                || vault::normalize_relative_path(name, extensions).is_err()
+            {
+                return None;
+            }
+            Some(BackendFile {
+                path: name.to_string(),
+                version: child_text(node, "ETag").unwrap_or_default(),
+            })
+        })
+        .collect();
+    let next = match child_text(root, "IsTruncated").as_deref() {
+        Some("true") => child_text(root, "NextContinuationToken"),
+        _ => None,
+    };
+    Ok((files, next))
+}
+
+/// AWS URI encoding: everything but unreserved characters (and `/` in paths) is escaped
+fn uri_encode(value: &str, keep_slash: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            b'/' if keep_slash => encoded.push('/'),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Query string with encoded parameters sorted by name, as signed and as sent
+fn canonical_query(params: &[(&str, &str)]) -> String {
+    let mut pairs: Vec<String> = params
+        .iter()
+        .map(|(name, value)| format!("{}={}", uri_encode(name, false), uri_encode(value, false)))
+        .collect();
+    pairs.sort();
+    pairs.join("&")
+}
+
+fn signing_key(secret: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{}", secret).as_bytes(), date.as_bytes());
+    let key = hmac_sha256(&key, region.as_bytes());
+    let key = hmac_sha256(&key, service.as_bytes());
+    hmac_sha256(&key, b"aws4_request")
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn hex_sha256(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signing() {
+        // Example from the AWS Signature Version 4 documentation
+        let key = signing_key(
+            "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+            "20120215",
+            "us-east-1",
+            "iam",
+        );
+        assert_eq!(
+            hex(&key),
+            "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+        );
+        assert_eq!(
+            uri_encode("prompts/code review+1.md", true),
+            "prompts/code%20review%2B1.md"
+        );
+        assert_eq!(
+            canonical_query(&[("prefix", "prompts/"), ("list-type", "2")]),
+            "list-type=2&prefix=prompts%2F"
+        );
+    }
+
+    #[test]
+    fn test_parse_list_objects() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListBucketResult xmlns="http://s3.amazonaws.com/doc/2006-03-01/">
+  <Name>vault</Name>
+  <Prefix>prompts/</Prefix>
+  <IsTruncated>true</IsTruncated>
+  <NextContinuationToken>1ueGcxLPRx1Tr</NextContinuationToken>
+  <Contents><Key>prompts/review.md</Key><ETag>"9b2cf535f27731c9"</ETag></Contents>
+  <Contents><Key>prompts/.prompt-manager-tombstones.json</Key><ETag>"11"</ETag></Contents>
+  <Contents><Key>prompts/archive/old.md</Key><ETag>"22"</ETag></Contents>
+</ListBucketResult>"#;
+
+        let (files, next) = parse_list_objects(xml, "prompts/", vault::MARKDOWN_ONLY).unwrap();
+        assert_eq!(
+            files,
+            vec![BackendFile {
+                path: "review.md".to_string(),
+                version: "\"9b2cf535f27731c9\"".to_string(),
+            }]
+        );
+        assert_eq!(next.as_deref(), Some("1ueGcxLPRx1Tr"));
+    }
+}
@@ -0,0 +1,252 @@
+//! WebDAV client (e.g. Nextcloud) keeping vault files in a server folder
+
+use crate::config::WebDavSettings;
+use crate::secrets::{self, SecretName};
+use crate::vault::{self, VaultError};
+use crate::vault_backend::{self, BackendFile, VaultBackend};
+use percent_encoding::percent_decode_str;
+use tauri_plugin_http::reqwest::{self, Method, StatusCode, Url};
+
+const DAV_NAMESPACE: &str = "DAV:";
+
+/// Properties requested for each file of the folder
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop><d:resourcetype/><d:getetag/><d:getlastmodified/><d:getcontentlength/></d:prop>
+</d:propfind>"#;
+
+/// A WebDAV folder holding the prompt files of a vault
+pub struct WebDavBackend {
+    client: reqwest::Client,
+    /// Always ends with `/` so file names can be joined onto it
+    folder: Url,
+    username: Option<String>,
+    password: Option<String>,
+    /// Prompt file extensions, as in `FrontmatterSettings::file_extensions`
+    extensions: Vec<String>,
+}
+
+impl WebDavBackend {
+    pub fn new(settings: &WebDavSettings, extensions: &[String]) -> Result<Self, VaultError> {
+        let mut folder = Url::parse(settings.url.trim())
+            .map_err(|e| VaultError::Remote(format!("Invalid WebDAV URL: {}", e)))?;
+        if folder.cannot_be_a_base() {
+            return Err(VaultError::Remote(format!(
+                "Invalid WebDAV URL: {}",
+                settings.url
+            )));
+        }
+        if !folder.path().ends_with('/') {
+            folder.set_path(&format!("{}/", folder.path()));
+        }
+        Ok(Self {
+            client: reqwest::Client::new(),
+            folder,
+            username: settings.username.clone().filter(|name| !name.is_empty()),
+            password: secrets::lookup(SecretName::WebDavPassword),
+            extensions: extensions.to_vec(),
+        })
+    }
+
+    fn file_url(&self, path: &str) -> Result<Url, VaultError> {
+        let name = vault_backend::store_path(path, &self.extensions)?;
+        let mut url = self.folder.clone();
+        url.path_segments_mut()
+            .expect("checked in new")
+            .pop_if_empty()
+            .push(&name);
+        Ok(url)
+    }
+
+    fn request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.username {
+            Some(username) => request.basic_auth(username, self.password.as_ref()),
+            None => request,
+        }
+    }
+}
+
+impl VaultBackend for WebDavBackend {
+    fn identity(&self) -> String {
+        format!("webdav:{}", self.folder)
+    }
+
+    async fn list(&self) -> Result<Vec<BackendFile>, VaultError> {
+        let method = Method::from_bytes(b"PROPFIND").expect("valid method");
+        let response = self
+            .request(method, self.folder.clone())
+            .header("Depth", "1")
+            .header(reqwest::header::CONTENT_TYPE, "application/xml")
+            .body(PROPFIND_BODY)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        let xml = response
+            .text()
+            .await
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        parse_multistatus(&xml, &self.extensions)
+    }
+
+    async fn read(&self, path: &str) -> Result<String, VaultError> {
+        let response = self
+            .request(Method::GET, self.file_url(path)?)
+            .send()
+            .await
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(VaultError::NotFound(path.to_string()));
+        }
+        response
+            .error_for_status()
+            .map_err(|e| VaultError::Remote(e.to_string()))?
+            .text()
+            .await
+            .map_err(|e| VaultError::Remote(e.to_string()))
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<(), VaultError> {
+        self.request(Method::PUT, self.file_url(path)?)
+            .body(content.to_string())
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), VaultError> {
+        let response = self
+            .request(Method::DELETE, self.file_url(path)?)
+            .send()
+            .await
+            .map_err(|e| VaultError::Remote(e.to_string()))?;
+        if response.status() != StatusCode::NOT_FOUND {
+            response
+                .error_for_status()
+                .map_err(|e| VaultError::Remote(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Prompt files in a `207 Multi-Status` PROPFIND response. The version is the ETag, or
+/// the modification time and size on servers that send none.
+fn parse_multistatus(xml: &str, extensions: &[String]) -> Result<Vec<BackendFile>, VaultError> {
+    let document = roxmltree::Document::parse(xml)
+        .map_err(|e| VaultError::Remote(format!("Invalid PROPFIND response: {}", e)))?;
+    let responses: Vec<_> = document
+        .descendants()
+        .filter(|node| node.has_tag_name((DAV_NAMESPACE, "response")))
+        .collect();
+    // Even an empty folder lists itself
+    if responses.is_empty() {
+        return Err(VaultError::Remote(
+            "Invalid PROPFIND response: no entries".to_string(),
+        ));
+    }
+    let mut files = Vec::new();
+    for response in responses {
+        let is_folder = dav_child(response, "resourcetype")
+            .is_some_and(|node| dav_child(node, "collection").is_some());
+        let href = dav_text(response, "href");
+        let name = href
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default();
+        let Ok(name) = percent_decode_str(name).decode_utf8() else {
+            continue;
+        };
+        // Vaults are flat; anything the vault could not hold is left on the server
+        if is_folder
+            || !vault::has_prompt_extension(&name, extensions)
+            || vault::normalize_relative_path(&name, extensions).is_err()
+        {
+            continue;
+        }
+        let etag = dav_text(response, "getetag");
+        let version = if etag.is_empty() {
+            format!(
+                "{}:{}",
+                dav_text(response, "getlastmodified"),
+                dav_text(response, "getcontentlength")
+            )
+        } else {
+            etag
+        };
+        files.push(BackendFile {
+            path: name.into_owned(),
+            version,
+        });
+    }
+    Ok(files)
+}
+
+/// First descendant of `node` named `name` in the DAV namespace, whatever its prefix
+fn dav_child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.descendants()
+        .find(|child| child.has_tag_name((DAV_NAMESPACE, name)))
+}
+
+fn dav_text(node: roxmltree::Node, name: &str) -> String {
+    dav_child(node, name)
+        .and_then(|child| child.text())
+        .map(|text| text.trim().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multistatus() {
+        let xml = r#"<?xml version="1.0"?>
+<d:multistatus xmlns:d="DAV:" xmlns:oc="http://owncloud.org/ns">
+  <d:response>
+    <d:href>/remote.php/dav/files/me/prompts/</d:href>
+    <d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype></d:prop></d:propstat>
+  </d:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/me/prompts/code%20review.md</d:href>
+    <d:propstat><d:prop><d:resourcetype/><d:getetag>"5f2a"</d:getetag></d:prop></d:propstat>
+  </d:response>
+  <D:response xmlns:D="DAV:">
+    <D:href>/remote.php/dav/files/me/prompts/notes.txt</D:href>
+    <D:propstat><D:prop><D:getetag>"77"</D:getetag></D:prop></D:propstat>
+  </D:response>
+  <d:response>
+    <d:href>/remote.php/dav/files/me/prompts/summary.md</d:href>
+    <d:propstat><d:prop>
+      <d:getlastmodified>Wed, 01 May 2024 10:00:00 GMT</d:getlastmodified>
+      <d:getcontentlength>42</d:getcontentlength>
+    </d:prop></d:propstat>
+  </d:response>
+</d:multistatus>"#;
+
+        let files = parse_multistatus(xml, vault::MARKDOWN_ONLY).unwrap();
+        assert_eq!(
+            files,
+            vec![
+                BackendFile {
+                    path: "code review.md".to_string(),
+                    version: "\"5f2a\"".to_string(),
+                },
+                BackendFile {
+                    path: "summary.md".to_string(),
+                    version: "Wed, 01 May 2024 10:00:00 GMT:42".to_string(),
+                },
+            ]
+        );
+        let extensions = vec!["md".to_string(), "txt".to_string()];
+        let files = parse_multistatus(xml, &extensions).unwrap();
+        assert_eq!(files[1].path, "notes.txt");
+        assert!(parse_multistatus("not xml", vault::MARKDOWN_ONLY).is_err());
+    }
+}
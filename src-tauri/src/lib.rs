@@ -1,44 +1,188 @@
+mod autotag;
+mod backup;
+mod cache;
+mod clipboard_watcher;
 mod commands;
 pub mod config;
+pub mod crypto;
 pub mod db;
-mod models;
+mod deep_link;
+mod diff;
+mod doctor;
+mod embeddings;
+pub mod error;
+mod events;
+mod export;
+pub mod git;
+mod importers;
+mod integrations;
+mod lint;
+mod llm;
+mod logging;
+pub mod models;
+mod promptignore;
+mod promptpack;
+pub mod prompts;
+mod sample_vault;
+pub mod search;
+mod secrets;
+mod serve;
+pub mod store;
+pub mod sync;
+pub mod template;
+#[cfg(test)]
+mod test_support;
+mod tokens;
+pub mod undo;
 pub mod vault;
+mod vault_backend;
 pub mod vault_watcher;
 
-use log::info;
 use tauri::Manager;
-use tauri_specta::{collect_commands, Builder};
+use tauri_plugin_deep_link::DeepLinkExt;
+use tauri_specta::{collect_commands, collect_events, Builder};
+use tracing::info;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Build the specta command registry
-    let builder = Builder::<tauri::Wry>::new().commands(collect_commands![
-        commands::get_prompts,
-        commands::save_prompt,
-        commands::delete_prompt,
-        commands::duplicate_prompt,
-        commands::get_views,
-        commands::get_view_by_id,
-        commands::save_view,
-        commands::delete_view,
-        commands::get_all_tags,
-        commands::get_table_names,
-        commands::get_table_info,
-        commands::get_table_rows,
-        commands::clear_table,
-        commands::export_database_as_json,
-        commands::get_database_path,
-        // Config
-        commands::get_config,
-        commands::save_config,
-        // Vault
-        commands::scan_vault,
-        commands::read_prompt_file,
-        commands::write_prompt_file,
-        commands::delete_prompt_file,
-        commands::sync_vault,
-        commands::start_vault_watch,
-    ]);
+    let builder = Builder::<tauri::Wry>::new()
+        .commands(collect_commands![
+            commands::get_prompts,
+            commands::get_prompt_summaries,
+            commands::get_prompt_groups,
+            commands::get_prompt,
+            commands::search_prompts,
+            commands::get_recent_prompts,
+            commands::get_stale_prompts,
+            commands::fuzzy_search_prompts,
+            commands::save_prompt,
+            commands::save_prompts,
+            commands::delete_prompt,
+            commands::duplicate_prompt,
+            commands::rename_prompt,
+            commands::set_archived,
+            commands::set_prompt_status,
+            commands::split_prompt,
+            commands::list_recently_deleted,
+            commands::recover_deleted_prompt,
+            commands::undo_last_operation,
+            commands::redo,
+            commands::get_undo_status,
+            commands::get_views,
+            commands::get_view_by_id,
+            commands::save_view,
+            commands::delete_view,
+            commands::reorder_views,
+            commands::get_all_tags,
+            commands::get_tag_tree,
+            commands::get_tag_graph,
+            commands::bulk_update_tags,
+            commands::link_prompts,
+            commands::unlink_prompts,
+            commands::get_linked_prompts,
+            commands::get_table_names,
+            commands::get_table_info,
+            commands::get_table_rows,
+            commands::clear_table,
+            commands::export_database_as_json,
+            commands::export_prompts,
+            commands::export_prompts_for,
+            commands::import_prompts,
+            commands::export_promptpack,
+            commands::import_promptpack,
+            commands::share_prompt_as_gist,
+            commands::import_prompt_from_gist,
+            commands::import_chatgpt_export,
+            commands::import_obsidian,
+            commands::get_database_path,
+            commands::reload_database,
+            commands::get_schema_version,
+            // Config
+            commands::get_config,
+            commands::save_config,
+            commands::validate_config,
+            commands::list_profiles,
+            commands::switch_profile,
+            commands::set_secret,
+            commands::has_secret,
+            commands::delete_secret,
+            commands::unlock_vault,
+            commands::lock_vault,
+            commands::is_vault_unlocked,
+            commands::decrypt_prompt,
+            // Vault
+            commands::scan_vault,
+            commands::read_prompt_file,
+            commands::write_prompt_file,
+            commands::delete_prompt_file,
+            commands::get_prompt_git_history,
+            commands::restore_prompt_from_commit,
+            commands::diff_prompts,
+            commands::diff_prompt_versions,
+            commands::semantic_search,
+            commands::find_similar_prompts,
+            commands::render_prompt,
+            commands::render_prompt_matrix,
+            commands::save_template_preset,
+            commands::list_template_presets,
+            commands::delete_template_preset,
+            commands::set_global_variable,
+            commands::get_global_variables,
+            commands::delete_global_variable,
+            commands::lint_prompt,
+            commands::lint_vault,
+            commands::check_vault_health,
+            commands::repair_vault,
+            commands::count_tokens,
+            commands::copy_prompt_to_clipboard,
+            commands::quick_capture,
+            commands::run_prompt,
+            commands::get_prompt_runs,
+            commands::delete_prompt_run,
+            commands::save_prompt_test,
+            commands::list_prompt_tests,
+            commands::delete_prompt_test,
+            commands::run_prompt_tests,
+            commands::suggest_tags_for_prompt,
+            commands::suggest_tags_for_untagged,
+            commands::sync_vault,
+            commands::cancel_sync,
+            commands::sync_paths,
+            commands::start_vault_watch,
+            commands::stop_vault_watch,
+            commands::get_vault_watch_status,
+            commands::sync_remote_vault,
+            commands::push_vault,
+            commands::pull_vault,
+            commands::set_clipboard_watch,
+            commands::get_clipboard_watch_status,
+            commands::create_backup_now,
+            commands::list_backups,
+            commands::restore_backup,
+            commands::create_sample_vault,
+            // HTTP API
+            commands::start_api_server,
+            commands::stop_api_server,
+            commands::get_api_server_status,
+            // Logs
+            commands::get_recent_logs,
+            commands::set_log_level,
+        ])
+        .events(collect_events![
+            events::SyncCompleted,
+            events::SyncProgress,
+            events::VaultChanged,
+            events::PromptRenamed,
+            events::PromptSaved,
+            events::PromptDeleted,
+            events::TagChanged,
+            events::ViewSaved,
+            events::ViewDeleted,
+            events::ViewsReordered,
+            events::DeepLinkOpened,
+            events::CaptureSuggested
+        ]);
 
     // Export TypeScript bindings in debug builds
     #[cfg(debug_assertions)]
@@ -52,29 +196,96 @@ pub fn run() {
         .expect("Failed to export TypeScript bindings");
 
     tauri::Builder::default()
+        // Must come first: a second launch (e.g. by a deep link) hands its URL to this one
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.unminimize();
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_http::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
-        .setup(|app| {
+        .invoke_handler(builder.invoke_handler())
+        .setup(move |app| {
+            builder.mount_events(app);
+
+            // Logging comes first so setup failures end up in the log file
+            match logging::log_dir(app.handle()).and_then(|dir| logging::init(&dir)) {
+                Ok(state) => {
+                    app.manage(state);
+                }
+                Err(e) => eprintln!("Failed to initialize logging: {}", e),
+            }
+
+            // Read the config file once; commands and watchers share the copy in managed state
+            let app_config = config::load_config(app.handle()).unwrap_or_else(|e| {
+                tracing::error!("Failed to load config, using defaults: {}", e);
+                config::AppConfig::default()
+            });
+            let watch_clipboard = app_config.capture.watch_clipboard;
+            app.manage(config::ConfigState::new(app_config));
+            app.manage(crypto::VaultKey::default());
+            app.manage(undo::UndoState::default());
+
             // Initialize database
             let handle = app.handle().clone();
             tauri::async_runtime::block_on(async move {
                 match db::init_db(&handle).await {
                     Ok(pool) => {
                         info!("Database initialized successfully");
-                        handle.manage(pool);
+                        handle.manage(db::DbState::new(pool));
+                        handle.manage(cache::CacheState::default());
                         handle.manage(vault_watcher::VaultWatcherState::default());
+                        handle.manage(sync::SyncCancellation::default());
+                        handle.manage(serve::ApiServerState::default());
+                        handle.manage(clipboard_watcher::ClipboardWatcherState::default());
                     }
                     Err(e) => {
-                        log::error!("Failed to initialize database: {}", e);
+                        tracing::error!("Failed to initialize database: {}", e);
                         panic!("Database initialization failed: {}", e);
                     }
                 }
             });
+
+            // Resume the clipboard watcher if it was left on
+            if watch_clipboard {
+                let state = app.state::<clipboard_watcher::ClipboardWatcherState>();
+                if let Err(e) =
+                    clipboard_watcher::start_clipboard_watch(app.handle().clone(), &state)
+                {
+                    tracing::error!("Failed to start clipboard watcher: {}", e);
+                }
+            }
+
+            backup::start_backup_scheduler(app.handle().clone());
+
+            // prompt-manager:// links, both the one the app was launched with and later ones
+            #[cfg(any(windows, target_os = "linux"))]
+            app.deep_link().register_all()?;
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                open_deep_links(&handle, event.urls());
+            });
+            if let Some(urls) = app.deep_link().get_current()? {
+                open_deep_links(app.handle(), urls);
+            }
             Ok(())
         })
-        .invoke_handler(builder.invoke_handler())
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
+
+fn open_deep_links(app: &tauri::AppHandle, urls: Vec<tauri::Url>) {
+    for url in urls {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = commands::handle_deep_link(&app, url.as_str()).await {
+                tracing::error!("Failed to open deep link {}: {}", url, e);
+            }
+        });
+    }
+}
@@ -0,0 +1,262 @@
+//! Tidiness checks for prompt files
+
+use crate::config::LintSettings;
+use crate::template::{self, Builtins, TemplateError};
+use crate::tokens;
+use crate::vault::PromptFile;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LintKind {
+    /// A placeholder or include nothing fills in
+    UnresolvedPlaceholder,
+    /// Unbalanced `{{#if}}`/`{{#each}}` blocks
+    InvalidTemplate,
+    /// A declared variable the prompt never uses
+    UnusedVariable,
+    TrailingWhitespace,
+    MissingTitle,
+    DuplicateTag,
+    LongLine,
+    OverTokenBudget,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LintWarning {
+    pub kind: LintKind,
+    pub message: String,
+    /// 1-based line in the prompt text, for line-level warnings
+    pub line: Option<usize>,
+}
+
+/// Warnings for one prompt
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptLint {
+    pub id: String,
+    pub warnings: Vec<LintWarning>,
+}
+
+/// What prompts are checked against besides their own file
+pub struct LintContext<'a> {
+    pub settings: &'a LintSettings,
+    /// Built-ins and globals available when rendering
+    pub builtins: &'a Builtins,
+    /// Ids of every prompt, to resolve includes
+    pub prompt_ids: &'a HashSet<String>,
+}
+
+/// Check a prompt file; file-level warnings come first, then line-level ones in line order
+pub fn lint(file: &PromptFile, context: &LintContext) -> PromptLint {
+    let text = &file.content;
+    let mut warnings = Vec::new();
+    let mut warn = |kind: LintKind, message: String, line: Option<usize>| {
+        warnings.push(LintWarning {
+            kind,
+            message,
+            line,
+        })
+    };
+
+    if file
+        .title
+        .as_deref()
+        .is_none_or(|title| title.trim().is_empty())
+    {
+        warn(
+            LintKind::MissingTitle,
+            "Prompt has no title".to_string(),
+            None,
+        );
+    }
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut reported: HashSet<String> = HashSet::new();
+    for tag in &file.tags {
+        let key = tag.to_lowercase();
+        if !seen.insert(key.clone()) && reported.insert(key) {
+            warn(
+                LintKind::DuplicateTag,
+                format!("Tag {} appears more than once", tag),
+                None,
+            );
+        }
+    }
+
+    for path in template::includes(text) {
+        if !context.prompt_ids.contains(&path) {
+            warn(
+                LintKind::UnresolvedPlaceholder,
+                format!("Included prompt not found: {}", path),
+                None,
+            );
+        }
+    }
+    for problem in template::check(text, context.builtins) {
+        let kind = match problem {
+            TemplateError::UnmatchedTag(_) | TemplateError::UnclosedBlock(_) => {
+                LintKind::InvalidTemplate
+            }
+            _ => LintKind::UnresolvedPlaceholder,
+        };
+        warn(kind, problem.to_string(), None);
+    }
+
+    // Without declarations every variable is expected to be filled in at render time
+    if !file.variables.is_empty() {
+        for name in template::undeclared(text, &file.variables) {
+            warn(
+                LintKind::UnresolvedPlaceholder,
+                format!("{{{{{}}}}} is not declared in variables", name),
+                None,
+            );
+        }
+        let used = template::variables(text);
+        for variable in &file.variables {
+            if !used.contains(&variable.name) {
+                warn(
+                    LintKind::UnusedVariable,
+                    format!("Declared variable {} is never used", variable.name),
+                    None,
+                );
+            }
+        }
+    }
+
+    let tokens = tokens::count(text, None);
+    if tokens > context.settings.token_budget {
+        warn(
+            LintKind::OverTokenBudget,
+            format!(
+                "About {} tokens (budget {})",
+                tokens, context.settings.token_budget
+            ),
+            None,
+        );
+    }
+
+    for (index, line) in text.lines().enumerate() {
+        if line.ends_with([' ', '\t']) {
+            warn(
+                LintKind::TrailingWhitespace,
+                "Trailing whitespace".to_string(),
+                Some(index + 1),
+            );
+        }
+        let length = line.chars().count();
+        if length > context.settings.max_line_length {
+            warn(
+                LintKind::LongLine,
+                format!(
+                    "Line is {} characters long (limit {})",
+                    length, context.settings.max_line_length
+                ),
+                Some(index + 1),
+            );
+        }
+    }
+
+    PromptLint {
+        id: file.id.clone(),
+        warnings,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FrontmatterSettings, TemplateSettings};
+    use crate::vault::parse_prompt_file;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_lint() {
+        let content = "---\ntags: [rust, Rust, review]\nvariables: [language, unused]\n---\n\
+                       ```prompt\n{{include:blocks/tone.md}} {{include:gone.md}}\n\
+                       Review {{language}} code {{focus}} {{global:team}}  \n\
+                       {{#each files}}{{path}}{{/each}}\n\
+                       0123456789abc\n```\n";
+        let file = parse_prompt_file("a.md", content, &FrontmatterSettings::default());
+        let settings = LintSettings {
+            max_line_length: 12,
+            token_budget: 5,
+        };
+        let builtins = Builtins::new(&TemplateSettings::default(), None, HashMap::new());
+        let prompt_ids = HashSet::from(["a.md".to_string(), "blocks/tone.md".to_string()]);
+        let context = LintContext {
+            settings: &settings,
+            builtins: &builtins,
+            prompt_ids: &prompt_ids,
+        };
+
+        let report = lint(&file, &context);
+        let found: Vec<(LintKind, &str, Option<usize>)> = report
+            .warnings
+            .iter()
+            .map(|w| (w.kind, w.message.as_str(), w.line))
+            .collect();
+        assert_eq!(
+            found[..7],
+            [
+                (LintKind::MissingTitle, "Prompt has no title", None),
+                (
+                    LintKind::DuplicateTag,
+                    "Tag Rust appears more than once",
+                    None
+                ),
+                (
+                    LintKind::UnresolvedPlaceholder,
+                    "Included prompt not found: gone.md",
+                    None
+                ),
+                (
+                    LintKind::UnresolvedPlaceholder,
+                    "Global variable team is not set",
+                    None
+                ),
+                (
+                    LintKind::UnresolvedPlaceholder,
+                    "{{focus}} is not declared in variables",
+                    None
+                ),
+                (
+                    LintKind::UnresolvedPlaceholder,
+                    "{{files}} is not declared in variables",
+                    None
+                ),
+                (
+                    LintKind::UnusedVariable,
+                    "Declared variable unused is never used",
+                    None
+                ),
+            ]
+        );
+        assert_eq!(found[7].0, LintKind::OverTokenBudget);
+        assert_eq!(
+            found[8],
+            (
+                LintKind::LongLine,
+                "Line is 46 characters long (limit 12)",
+                Some(1)
+            )
+        );
+        assert_eq!(
+            found[9],
+            (LintKind::TrailingWhitespace, "Trailing whitespace", Some(2))
+        );
+        assert_eq!(found[10].0, LintKind::LongLine);
+        assert_eq!(
+            found[12],
+            (
+                LintKind::LongLine,
+                "Line is 13 characters long (limit 12)",
+                Some(4)
+            )
+        );
+        assert_eq!(found.len(), 13);
+    }
+}
@@ -0,0 +1,136 @@
+//! Minimal client for OpenAI-compatible chat completion endpoints (Ollama, LM Studio, OpenAI, ...)
+
+use crate::config::LlmSettings;
+use crate::models::DbError;
+use crate::secrets::{self, SecretName};
+use serde::{Deserialize, Serialize};
+use tauri_plugin_http::reqwest;
+
+/// LLM errors
+#[derive(Debug, Clone, Serialize, thiserror::Error, specta::Type)]
+pub enum LlmError {
+    #[error("LLM request failed: {0}")]
+    Request(String),
+    #[error("Invalid LLM response: {0}")]
+    InvalidResponse(String),
+}
+
+impl From<LlmError> for DbError {
+    fn from(e: LlmError) -> Self {
+        DbError::Database(e.to_string())
+    }
+}
+
+/// A completion with the token usage reported by the endpoint (if any)
+#[derive(Debug, Clone)]
+pub struct Completion {
+    pub text: String,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<ChatMessage<'a>>,
+    stream: bool,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Deserialize)]
+struct ChatResponseMessage {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatUsage {
+    prompt_tokens: Option<i64>,
+    completion_tokens: Option<i64>,
+}
+
+/// Name recorded for the configured endpoint (its host, e.g. `localhost:11434`)
+pub fn provider_name(settings: &LlmSettings) -> String {
+    reqwest::Url::parse(&settings.api_url)
+        .ok()
+        .and_then(|url| {
+            url.host_str().map(|host| match url.port() {
+                Some(port) => format!("{}:{}", host, port),
+                None => host.to_string(),
+            })
+        })
+        .unwrap_or_else(|| settings.api_url.clone())
+}
+
+/// Send `prompt` as a single user message (after an optional system message) to `model`
+pub async fn complete(
+    settings: &LlmSettings,
+    model: &str,
+    system: Option<&str>,
+    prompt: &str,
+) -> Result<Completion, LlmError> {
+    let mut messages = Vec::new();
+    if let Some(system) = system {
+        messages.push(ChatMessage {
+            role: "system",
+            content: system,
+        });
+    }
+    messages.push(ChatMessage {
+        role: "user",
+        content: prompt,
+    });
+
+    let mut request = reqwest::Client::new()
+        .post(&settings.api_url)
+        .json(&ChatRequest {
+            model,
+            messages,
+            stream: false,
+        });
+    if let Some(key) = secrets::lookup(SecretName::LlmApiKey) {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| LlmError::Request(e.to_string()))?;
+    let body: ChatResponse = response
+        .json()
+        .await
+        .map_err(|e| LlmError::InvalidResponse(e.to_string()))?;
+
+    let text = body
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.message.content)
+        .ok_or_else(|| LlmError::InvalidResponse("no choices returned".to_string()))?;
+    Ok(Completion {
+        text,
+        prompt_tokens: body.usage.as_ref().and_then(|usage| usage.prompt_tokens),
+        completion_tokens: body
+            .usage
+            .as_ref()
+            .and_then(|usage| usage.completion_tokens),
+    })
+}
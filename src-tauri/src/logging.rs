@@ -0,0 +1,267 @@
+//! Tracing setup: events go to stderr and to a daily JSON log file in the app data dir,
+//! which `get_recent_logs` reads back so users can attach it to bug reports
+
+use crate::error::{AppError, ErrorKind};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+/// Log files are `prompt-manager.{date}.log`; other files in the log dir are ignored
+const FILE_PREFIX: &str = "prompt-manager";
+const FILE_SUFFIX: &str = "log";
+
+/// Days of logs kept before the oldest file is deleted
+const MAX_LOG_FILES: usize = 7;
+
+const DEFAULT_LEVEL: LogLevel = LogLevel::Info;
+
+/// Severity of a log entry, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// Parse the upper-case level names written by the JSON formatter
+    fn from_tracing(name: &str) -> Option<Self> {
+        match name {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            "TRACE" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// One line of the log file
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// Module that logged the entry, e.g. `prompt_manager_lib::sync`
+    pub target: String,
+    pub message: String,
+    /// Innermost span the entry was logged in, usually the command being run
+    pub span: Option<String>,
+}
+
+/// A log file line as written by `fmt::layer().json()`
+#[derive(Deserialize)]
+struct RawEntry {
+    timestamp: String,
+    level: String,
+    target: String,
+    #[serde(default)]
+    fields: RawFields,
+    span: Option<RawSpan>,
+}
+
+#[derive(Default, Deserialize)]
+struct RawFields {
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct RawSpan {
+    name: String,
+}
+
+/// Managed as Tauri state once `init` installed the subscriber
+pub struct LoggingState {
+    log_dir: PathBuf,
+    level: reload::Handle<LevelFilter, Registry>,
+    /// Flushes the file writer when the app exits
+    _guard: WorkerGuard,
+}
+
+impl LoggingState {
+    pub fn log_dir(&self) -> &Path {
+        &self.log_dir
+    }
+
+    /// Change which entries are recorded from now on (not persisted across restarts)
+    pub fn set_level(&self, level: LogLevel) -> Result<(), AppError> {
+        self.level
+            .modify(|filter| *filter = level.into())
+            .map_err(|e| AppError::new(ErrorKind::Config, e.to_string()))
+    }
+}
+
+/// `logs` in the app data dir
+pub fn log_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("logs"))
+        .map_err(|e| AppError::io(e.to_string()))
+}
+
+/// Install the global subscriber, also receiving records from dependencies using `log`
+pub fn init(log_dir: &Path) -> Result<LoggingState, AppError> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(FILE_PREFIX)
+        .filename_suffix(FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir)
+        .map_err(|e| AppError::io(format!("Failed to open log file: {}", e)))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    let (level, handle) = reload::Layer::new(LevelFilter::from(DEFAULT_LEVEL));
+
+    tracing_subscriber::registry()
+        .with(level)
+        .with(fmt::layer().with_writer(io::stderr))
+        .with(
+            fmt::layer()
+                .json()
+                .with_span_list(false)
+                .with_writer(writer),
+        )
+        .try_init()
+        .map_err(|e| AppError::new(ErrorKind::Config, e.to_string()))?;
+
+    Ok(LoggingState {
+        log_dir: log_dir.to_path_buf(),
+        level: handle,
+        _guard: guard,
+    })
+}
+
+fn is_log_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            name.starts_with(FILE_PREFIX) && name.ends_with(&format!(".{}", FILE_SUFFIX))
+        })
+}
+
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let raw: RawEntry = serde_json::from_str(line).ok()?;
+    Some(LogEntry {
+        timestamp: raw.timestamp,
+        level: LogLevel::from_tracing(&raw.level)?,
+        target: raw.target,
+        message: raw.fields.message,
+        span: raw.span.map(|span| span.name),
+    })
+}
+
+/// The newest `limit` entries at `level` or more severe, oldest first. Lines that are not
+/// log entries (e.g. cut off by a crash) are skipped.
+pub fn recent_logs(
+    log_dir: &Path,
+    level: LogLevel,
+    limit: usize,
+) -> Result<Vec<LogEntry>, AppError> {
+    let mut files: Vec<PathBuf> = match fs::read_dir(log_dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| is_log_file(path))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::io(format!("Failed to read log dir: {}", e))),
+    };
+    // Dated names sort chronologically
+    files.sort();
+
+    let mut entries: Vec<LogEntry> = Vec::new();
+    for file in files.iter().rev() {
+        if entries.len() >= limit {
+            break;
+        }
+        let content = fs::read_to_string(file)
+            .map_err(|e| AppError::io(format!("Failed to read {}: {}", file.display(), e)))?;
+        let mut older: Vec<LogEntry> = content
+            .lines()
+            .filter_map(parse_line)
+            .filter(|entry| entry.level <= level)
+            .collect();
+        let skip = older.len().saturating_sub(limit - entries.len());
+        older.drain(..skip);
+        older.append(&mut entries);
+        entries = older;
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    fn line(timestamp: &str, level: &str, message: &str) -> String {
+        format!(
+            r#"{{"timestamp":"{}","level":"{}","fields":{{"message":"{}"}},"target":"prompt_manager_lib::sync","span":{{"name":"sync_vault"}}}}"#,
+            timestamp, level, message
+        )
+    }
+
+    #[test]
+    fn test_recent_logs() {
+        let dir = TempDir::new("prompt-manager-logs");
+        fs::write(
+            dir.join("prompt-manager.2024-05-01.log"),
+            [line("1", "INFO", "a"), line("2", "ERROR", "b")].join("\n"),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("prompt-manager.2024-05-02.log"),
+            [
+                line("3", "DEBUG", "c"),
+                "{\"timestamp\":\"cut".to_string(),
+                line("4", "WARN", "d"),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), line("5", "ERROR", "e")).unwrap();
+
+        let messages = |level, limit| {
+            recent_logs(&dir, level, limit)
+                .unwrap()
+                .into_iter()
+                .map(|entry| entry.message)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(messages(LogLevel::Trace, 10), vec!["a", "b", "c", "d"]);
+        assert_eq!(messages(LogLevel::Info, 10), vec!["a", "b", "d"]);
+        // The limit keeps the newest entries, across files
+        assert_eq!(messages(LogLevel::Warn, 2), vec!["b", "d"]);
+
+        let entry = recent_logs(&dir, LogLevel::Warn, 1).unwrap().remove(0);
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.span.as_deref(), Some("sync_vault"));
+
+        let _ = fs::remove_dir_all(&dir);
+        assert!(recent_logs(&dir, LogLevel::Info, 10).unwrap().is_empty());
+    }
+}
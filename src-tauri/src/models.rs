@@ -1,4 +1,7 @@
+use crate::error::AppError;
+use crate::template::TemplateVariable;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use specta::Type;
 use sqlx::FromRow;
 use std::collections::HashMap;
@@ -17,6 +20,55 @@ pub struct PromptRow {
     pub description: Option<String>,
     pub file_path: Option<String>,
     pub file_hash: Option<String>,
+    /// Custom frontmatter fields as a JSON object
+    pub custom_fields: Option<String>,
+    /// Tokens in `text` for the default tokenizer (NULL until the next sync after upgrading)
+    pub token_count: Option<i64>,
+    pub archived: bool,
+    /// Last time the cached file content changed (NULL for prompts cached before upgrading)
+    pub updated_at: Option<String>,
+    /// Declared template variables as a JSON array
+    pub variables: Option<String>,
+    /// Bumped whenever the cached file content changes
+    pub revision: i64,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    pub notes: Option<String>,
+    /// Targeted models as a JSON array
+    pub model_targets: Option<String>,
+    pub language: Option<String>,
+    pub status: Option<String>,
+    /// Counts of `text` (NULL until the next sync after upgrading), see `tokens::text_counts`
+    pub char_count: Option<i64>,
+    pub word_count: Option<i64>,
+    pub line_count: Option<i64>,
+}
+
+impl PromptRow {
+    pub fn parse_custom_fields(&self) -> HashMap<String, JsonValue> {
+        self.custom_fields
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn parse_variables(&self) -> Vec<TemplateVariable> {
+        self.variables
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn parse_model_targets(&self) -> Vec<String> {
+        self.model_targets
+            .as_deref()
+            .and_then(|raw| serde_json::from_str(raw).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn parse_status(&self) -> Option<PromptStatus> {
+        self.status.as_deref().and_then(PromptStatus::parse)
+    }
 }
 
 /// Tag row from database
@@ -37,12 +89,101 @@ pub struct ViewRow {
     pub created: String,
 }
 
+/// Prompt id row (for pruning the cache)
+#[derive(Debug, Clone, FromRow)]
+pub struct PromptIdRow {
+    pub id: String,
+}
+
+/// Global template variable row
+#[derive(Debug, Clone, FromRow)]
+pub struct GlobalRow {
+    pub name: String,
+    pub value: String,
+}
+
+/// Template preset row; `values_json` is a JSON object of variable values
+#[derive(Debug, Clone, FromRow)]
+pub struct TemplatePresetRow {
+    pub prompt_id: String,
+    pub name: String,
+    pub values_json: String,
+    pub updated: String,
+}
+
+/// Prompt test row; `values_json` is a JSON object of variable values, `last_passed` the
+/// outcome of the latest run (if any)
+#[derive(Debug, Clone, FromRow)]
+pub struct PromptTestRow {
+    pub id: String,
+    pub prompt_id: String,
+    pub name: String,
+    pub values_json: String,
+    pub assertion: String,
+    pub is_regex: bool,
+    pub updated: String,
+    pub last_passed: Option<bool>,
+}
+
+/// Prompt link row; `target_id` is a prompt id or, for wikilinks, the link text
+#[derive(Debug, Clone, FromRow)]
+pub struct PromptLinkRow {
+    pub source_id: String,
+    pub target_id: String,
+    pub relation: String,
+}
+
+/// Tag usage row (tag name with the id of a prompt using it)
+#[derive(Debug, Clone, FromRow)]
+pub struct TagUsageRow {
+    pub name: String,
+    pub prompt_id: Option<String>,
+}
+
+/// Tag with the number of prompts using it
+#[derive(Debug, Clone, FromRow)]
+pub struct TagCountRow {
+    pub name: String,
+    pub prompt_count: i64,
+}
+
+/// A prompt in one group of `group_prompts`, with the size of the group
+#[derive(Debug, Clone, FromRow)]
+pub struct PromptGroupRow {
+    pub key: Option<String>,
+    pub prompt_id: String,
+    pub count: i64,
+}
+
+/// Two tags with the number of prompts carrying both
+#[derive(Debug, Clone, FromRow)]
+pub struct TagPairRow {
+    pub source: String,
+    pub target: String,
+    pub weight: i64,
+}
+
 /// Tag name row (for simple queries)
 #[derive(Debug, Clone, FromRow)]
 pub struct TagNameRow {
     pub name: String,
 }
 
+/// Stored embedding vector (little-endian `f32` bytes)
+#[derive(Debug, Clone, FromRow)]
+pub struct EmbeddingRow {
+    pub prompt_id: String,
+    pub vector: Vec<u8>,
+}
+
+/// Prompt text with the hash of the text its embedding was computed from
+#[derive(Debug, Clone, FromRow)]
+pub struct PromptEmbeddingStateRow {
+    pub id: String,
+    pub text: String,
+    pub text_hash: Option<String>,
+}
+
 // ============================================================================
 // API TYPES (for Tauri commands with Specta)
 // ============================================================================
@@ -58,6 +199,303 @@ pub struct Prompt {
     pub file_path: Option<String>,
     pub title: Option<String>,
     pub description: Option<String>,
+    #[serde(default)]
+    pub file_hash: Option<String>,
+    #[serde(default)]
+    pub custom_fields: HashMap<String, JsonValue>,
+    /// Estimated tokens in `text`, see `count_tokens` for other models
+    #[serde(default)]
+    pub token_count: Option<i64>,
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    /// Template variables declared in the prompt's frontmatter
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+    /// Bumped on every write; pass it back as `PromptInput::expected_revision`
+    #[serde(default)]
+    pub revision: i64,
+    /// Color views show the prompt with, e.g. `#e11d48` or `red`
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Icon name or emoji views show the prompt with
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Private working notes; unlike the description, left out of shares and exports
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Models the prompt is tuned for, e.g. `gpt-4o`
+    #[serde(default)]
+    pub model_targets: Vec<String>,
+    /// Language the prompt is written in
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Lifecycle stage of the prompt
+    #[serde(default)]
+    pub status: Option<PromptStatus>,
+    /// Characters, words and lines in `text`, counted when the file was cached
+    #[serde(default)]
+    pub char_count: Option<i64>,
+    #[serde(default)]
+    pub word_count: Option<i64>,
+    #[serde(default)]
+    pub line_count: Option<i64>,
+}
+
+/// Lifecycle stage of a prompt (`status` in frontmatter), in workflow order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum PromptStatus {
+    Draft,
+    Testing,
+    Production,
+    Deprecated,
+}
+
+impl PromptStatus {
+    /// How the status is written in frontmatter and stored in the cache
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PromptStatus::Draft => "draft",
+            PromptStatus::Testing => "testing",
+            PromptStatus::Production => "production",
+            PromptStatus::Deprecated => "deprecated",
+        }
+    }
+
+    /// A status as written in frontmatter (ignoring case); `None` for unknown values
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            PromptStatus::Draft,
+            PromptStatus::Testing,
+            PromptStatus::Production,
+            PromptStatus::Deprecated,
+        ]
+        .into_iter()
+        .find(|status| status.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// A recorded execution of a prompt against an LLM
+#[derive(Debug, Clone, Serialize, Deserialize, Type, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptRun {
+    pub id: String,
+    pub prompt_id: String,
+    /// Hash of the prompt file at run time, to tell revisions apart
+    pub prompt_hash: Option<String>,
+    pub rendered_text: String,
+    /// Endpoint host, e.g. `localhost:11434`
+    pub provider: String,
+    pub model: String,
+    pub response: String,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub latency_ms: i64,
+    pub created: String,
+}
+
+/// A deleted prompt whose last file contents can still be recovered
+#[derive(Debug, Clone, Serialize, Deserialize, Type, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedPrompt {
+    /// Vault-relative path the prompt had, and is recovered to
+    pub id: String,
+    pub title: Option<String>,
+    pub deleted_at: String,
+}
+
+/// Named set of template variable values saved for a prompt (e.g. `python`, `rust`)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplatePreset {
+    pub prompt_id: String,
+    pub name: String,
+    pub values: HashMap<String, JsonValue>,
+    pub updated: String,
+}
+
+impl TryFrom<TemplatePresetRow> for TemplatePreset {
+    type Error = DbError;
+
+    fn try_from(row: TemplatePresetRow) -> Result<Self, Self::Error> {
+        Ok(TemplatePreset {
+            prompt_id: row.prompt_id,
+            name: row.name,
+            values: serde_json::from_str(&row.values_json)?,
+            updated: row.updated,
+        })
+    }
+}
+
+/// A regression check for a prompt: the values to render it with and what the LLM response
+/// must contain (or, with `is_regex`, match)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTest {
+    pub id: String,
+    pub prompt_id: String,
+    pub name: String,
+    pub values: HashMap<String, JsonValue>,
+    pub assertion: String,
+    pub is_regex: bool,
+    pub updated: String,
+    /// Outcome of the latest run, `None` if never run
+    pub last_passed: Option<bool>,
+}
+
+impl TryFrom<PromptTestRow> for PromptTest {
+    type Error = DbError;
+
+    fn try_from(row: PromptTestRow) -> Result<Self, Self::Error> {
+        Ok(PromptTest {
+            id: row.id,
+            prompt_id: row.prompt_id,
+            name: row.name,
+            values: serde_json::from_str(&row.values_json)?,
+            assertion: row.assertion,
+            is_regex: row.is_regex,
+            updated: row.updated,
+            last_passed: row.last_passed,
+        })
+    }
+}
+
+impl PromptTest {
+    /// Whether an LLM response satisfies the assertion
+    pub fn passes(&self, response: &str) -> Result<bool, AppError> {
+        if self.is_regex {
+            let regex = regex::Regex::new(&self.assertion)
+                .map_err(|e| AppError::invalid_input(format!("Invalid assertion regex: {}", e)))?;
+            Ok(regex.is_match(response))
+        } else {
+            Ok(response.contains(&self.assertion))
+        }
+    }
+}
+
+/// Input for saving a prompt test; without an `id` a new test is created
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTestInput {
+    #[serde(default)]
+    pub id: Option<String>,
+    pub prompt_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub values: HashMap<String, JsonValue>,
+    pub assertion: String,
+    #[serde(default)]
+    pub is_regex: bool,
+}
+
+impl PromptTestInput {
+    /// Reject blank names and assertions and regexes that don't compile
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::invalid_input("Test name cannot be empty"));
+        }
+        if self.assertion.is_empty() {
+            return Err(AppError::invalid_input("Test assertion cannot be empty"));
+        }
+        if self.is_regex {
+            regex::Regex::new(&self.assertion)
+                .map_err(|e| AppError::invalid_input(format!("Invalid assertion regex: {}", e)))?;
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of running one prompt test; `run` is also kept in the prompt's run history
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptTestResult {
+    pub test_id: String,
+    pub name: String,
+    pub passed: bool,
+    pub run: PromptRun,
+}
+
+/// Prompt returned by semantic search, with its cosine similarity to the query
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SemanticMatch {
+    pub prompt: Prompt,
+    pub score: f32,
+}
+
+/// Result of syncing the vault into the cache
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct SyncStats {
+    pub found: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    /// Files that could not be read and were left out of the cache
+    #[serde(default)]
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// A vault file a scan or sync left out, and why
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedFile {
+    pub path: String,
+    pub reason: SkipReason,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// Over the configured maximum file size
+    TooLarge,
+    /// Looks like binary data rather than text
+    Binary,
+    /// Could not be read, e.g. not valid UTF-8 or no permission
+    Unreadable,
+    /// Not a valid vault path, e.g. `..` in the name
+    InvalidPath,
+}
+
+/// Node in the hierarchical tag tree (`lang/rust` nests `rust` under `lang`)
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagNode {
+    /// Last path segment, e.g. `rust`
+    pub name: String,
+    /// Full tag path, e.g. `lang/rust`
+    pub path: String,
+    /// Number of prompts tagged with this exact path or any descendant
+    pub prompt_count: i64,
+    pub children: Vec<TagNode>,
+}
+
+/// Tag co-occurrence graph: tags as nodes, shared prompts as weighted edges
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagGraph {
+    pub nodes: Vec<TagGraphNode>,
+    pub edges: Vec<TagGraphEdge>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagGraphNode {
+    pub name: String,
+    /// Number of prompts tagged with exactly this tag
+    pub prompt_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagGraphEdge {
+    pub source: String,
+    pub target: String,
+    /// Number of prompts tagged with both `source` and `target`
+    pub weight: i64,
+    /// Jaccard similarity of the two tags' prompt sets; close to 1 hints at synonyms
+    pub similarity: f64,
 }
 
 /// Input for saving a prompt (legacy, for cache-based operations)
@@ -72,6 +510,60 @@ pub struct PromptInput {
     pub previous_file_path: Option<String>,
     pub title: Option<String>,
     pub description: Option<String>,
+    /// `None` keeps the file's current color; a blank one removes it
+    #[serde(default)]
+    pub color: Option<String>,
+    /// `None` keeps the file's current icon; a blank one removes it
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// `None` keeps the file's current notes; blank ones remove them
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// `None` keeps the file's current model targets; an empty list removes them
+    #[serde(default)]
+    pub model_targets: Option<Vec<String>>,
+    /// `None` keeps the file's current language; a blank one removes it
+    #[serde(default)]
+    pub language: Option<String>,
+    /// `None` keeps the file's current status
+    #[serde(default)]
+    pub status: Option<PromptStatus>,
+    /// Hash of the file as last read by the cache; a save is rejected if the file changed since
+    #[serde(default)]
+    pub file_hash: Option<String>,
+    /// Custom frontmatter fields; `None` keeps the ones already in the file
+    #[serde(default)]
+    pub custom_fields: Option<HashMap<String, JsonValue>>,
+    /// Name of the ```` ```prompt:name ```` block `text` is saved to (the main block if unset)
+    #[serde(default)]
+    pub variant: Option<String>,
+    /// `None` keeps the file's current archived state
+    #[serde(default)]
+    pub archived: Option<bool>,
+    /// Revision the prompt was loaded at; the save fails with `StaleRevision` if it was saved since
+    #[serde(default)]
+    pub expected_revision: Option<i64>,
+}
+
+/// Where `split_prompt` cuts a prompt into parts
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SplitBy {
+    /// 0-based lines of the prompt text where a new part starts
+    Lines(Vec<u32>),
+    /// The text of each part, e.g. as edited before splitting
+    Sections(Vec<String>),
+}
+
+/// Outcome of one prompt in a `save_prompts` batch
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SavePromptResult {
+    /// Id of the input
+    pub id: String,
+    /// Vault file written, when the save succeeded
+    pub file_path: Option<String>,
+    pub error: Option<AppError>,
 }
 
 /// View configuration for filtering and sorting
@@ -82,24 +574,281 @@ pub struct ViewConfig {
     pub filter: Option<FilterConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sort: Option<SortConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<ViewLayout>,
+}
+
+/// Layout modes a `ViewLayout` can use
+pub const LAYOUT_MODES: &[&str] = &["card", "list", "board"];
+/// Prompt details a `ViewLayout` can show
+pub const LAYOUT_COLUMNS: &[&str] = &["title", "tags", "created", "updated", "tokens", "words"];
+/// Most lines of prompt text a `ViewLayout` preview can show
+pub const MAX_PREVIEW_LENGTH: u32 = 50;
+
+/// How a view displays its prompts; unset fields fall back to the global `ViewSettings`
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewLayout {
+    #[serde(default = "default_layout_mode")]
+    pub mode: String, // "card" | "list" | "board" (columns by status)
+    /// Prompt details to show, in order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<Vec<String>>,
+    /// Lines of prompt text to preview
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview_length: Option<u32>,
+    /// Show prompts in sections by their first tag
+    #[serde(default)]
+    pub group_by_tag: bool,
+}
+
+fn default_layout_mode() -> String {
+    "card".to_string()
+}
+
+impl ViewLayout {
+    /// Reject modes and columns the frontend can't display
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !LAYOUT_MODES.contains(&self.mode.as_str()) {
+            return Err(AppError::invalid_input(format!(
+                "Unknown layout mode: {}",
+                self.mode
+            )));
+        }
+        if let Some(columns) = &self.columns {
+            for (index, column) in columns.iter().enumerate() {
+                if !LAYOUT_COLUMNS.contains(&column.as_str()) {
+                    return Err(AppError::invalid_input(format!(
+                        "Unknown layout column: {}",
+                        column
+                    )));
+                }
+                if columns[..index].contains(column) {
+                    return Err(AppError::invalid_input(format!(
+                        "Duplicate layout column: {}",
+                        column
+                    )));
+                }
+            }
+        }
+        if let Some(length) = self.preview_length {
+            if length == 0 || length > MAX_PREVIEW_LENGTH {
+                return Err(AppError::invalid_input(format!(
+                    "Preview length must be between 1 and {} lines",
+                    MAX_PREVIEW_LENGTH
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How the tags of a `FilterConfig` combine; excluded tags are always all excluded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum TagMode {
+    #[default]
+    And,
+    Or,
+}
+
+/// A search query and how its words and phrases match
+#[derive(Debug, Clone, Serialize, Deserialize, Type, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchConfig {
+    /// `tag:rust -tag:draft title:"code review" /regex/i` plus plain words, see `SearchQuery`
+    pub query: String,
+    /// Words, phrases and `title:` values match only with the same case
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Words, phrases and `title:` values match only as whole words, so `ai` skips `said`
+    #[serde(default)]
+    pub whole_word: bool,
+    /// Where plain words and phrases are looked for; empty means the title and text
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<SearchField>,
+}
+
+impl From<String> for SearchConfig {
+    fn from(query: String) -> Self {
+        SearchConfig {
+            query,
+            ..SearchConfig::default()
+        }
+    }
+}
+
+/// A part of a prompt plain search words are looked for in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    Title,
+    Description,
+    Text,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterConfig {
+    /// Tags to filter on, nested tags included; `-tag` excludes
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
+    /// Whether prompts need all of the `tags` or any one of them (defaults to all)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag_mode: Option<TagMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub search: Option<String>,
+    pub search: Option<SearchConfig>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub favorite: Option<bool>,
+    /// Only prompts without any tags
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub untagged: Option<bool>,
+    /// Only prompts without a title (or with a blank one)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub missing_title: Option<bool>,
+    /// Only prompts created at or after this date (`YYYY-MM-DD`) or time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_after: Option<String>,
+    /// Only prompts created before this date (`YYYY-MM-DD`) or time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_before: Option<String>,
+    /// Only prompts updated at or after this date (`YYYY-MM-DD`) or time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_after: Option<String>,
+    /// `true` lists only archived prompts; archived prompts are excluded otherwise
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archived: Option<bool>,
+    /// Custom frontmatter fields that must match (an empty value only requires the key)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fields: Option<HashMap<String, String>>,
+    /// Only prompts listing this model in their `model_targets` (ignoring case)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_target: Option<String>,
+    /// Only prompts in this language (ignoring case)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Only prompts at this lifecycle stage
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<PromptStatus>,
+    /// Only prompts with at least this many words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_words: Option<u32>,
+    /// Only prompts with at most this many words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_words: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct SortConfig {
-    pub by: String,    // "created" | "title" | "usage_count"
+    // "created" | "updated" | "title" | "usage_count" | "last_used" | "char_count" | "word_count"
+    // | "line_count" | "token_count"
+    pub by: String,
     pub order: String, // "asc" | "desc"
+    /// Tie-breakers applied in order when `by` is equal
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub then: Vec<SortKey>,
+}
+
+/// Which quick list `get_recent_prompts` returns
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum RecentKind {
+    /// Most recently copied
+    Used,
+    /// Most recently changed on disk
+    Edited,
+}
+
+/// Which page of a list to return; pages are zero-based
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PageRequest {
+    pub page: u32,
+    pub page_size: u32,
+}
+
+/// A prompt as shown in lists: metadata and the start of its text, see `get_prompt`
+/// for the full prompt
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSummary {
+    pub id: String,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub created: Option<String>,
+    /// Start of the text with whitespace collapsed, `…` when cut
+    pub excerpt: String,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+}
+
+/// What `get_prompt_groups` groups prompts by
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    /// Each tag; a prompt with several tags is in each of their groups
+    Tag,
+    /// `YYYY-MM` of the created date
+    CreatedMonth,
+    /// Each of `model_targets`
+    ModelTarget,
+    /// Folder of the prompt file, relative to the vault. Subfolders aren't supported yet, so
+    /// this is a single group of prompts at the vault root.
+    Folder,
+    /// Lifecycle stage, in workflow order
+    Status,
+}
+
+/// Prompts sharing a group key, in the requested sort order
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptGroup {
+    /// Tag, month, model, folder or status; `None` for prompts without one (no tags, the vault
+    /// root...)
+    pub key: Option<String>,
+    pub count: u32,
+    pub prompts: Vec<Prompt>,
+}
+
+/// One page of `get_prompt_summaries`
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptSummaryPage {
+    pub items: Vec<PromptSummary>,
+    /// Prompts matching the filter across all pages
+    pub total: usize,
+}
+
+/// Relation of links read from `[[wikilinks]]` during sync (replaced on every sync)
+pub const WIKILINK_RELATION: &str = "wikilink";
+
+/// Which end of a link a `LinkedPrompt` is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkDirection {
+    /// The prompt links to the linked prompt
+    Outgoing,
+    /// The linked prompt links to the prompt
+    Incoming,
+}
+
+/// A prompt linked to another one, manually or by a wikilink
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedPrompt {
+    pub prompt: Prompt,
+    /// e.g. `related`, `follow-up` or `wikilink`
+    pub relation: String,
+    pub direction: LinkDirection,
+}
+
+/// A secondary sort key of a `SortConfig`
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SortKey {
+    pub by: String,
+    pub order: String,
 }
 
 /// View - returned to frontend
@@ -138,6 +887,38 @@ pub enum DbError {
     NotFound(String),
     #[error("Serialization error: {0}")]
     Serialization(String),
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+    #[error("File changed on disk: {}", .0.file_path)]
+    Conflict(SaveConflict),
+    #[error("Prompt was saved elsewhere since it was loaded: {}", .0.id)]
+    StaleRevision(RevisionConflict),
+    #[error("Sync cancelled")]
+    Cancelled,
+}
+
+/// Both sides of a save rejected because the file was modified outside the app
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveConflict {
+    pub file_path: String,
+    pub expected_hash: String,
+    /// `None` if the file was deleted on disk
+    pub current_hash: Option<String>,
+    /// Prompt text the save tried to write
+    pub local_text: String,
+    /// Prompt text currently on disk
+    pub disk_text: Option<String>,
+}
+
+/// A save rejected because the prompt got a newer revision after the caller loaded it
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionConflict {
+    pub id: String,
+    pub expected_revision: i64,
+    /// `None` if the prompt was deleted or moved since
+    pub current_revision: Option<i64>,
 }
 
 impl From<sqlx::Error> for DbError {
@@ -183,6 +964,16 @@ impl TableRow {
     }
 }
 
+/// Schema version of the cache database
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaVersion {
+    /// Version recorded in the database
+    pub current: i64,
+    /// Latest version known to this build
+    pub latest: i64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportedTable {
@@ -195,3 +986,52 @@ pub struct ExportedTable {
 pub struct ExportedDatabase {
     pub tables: HashMap<String, ExportedTable>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_view_layout_validate() {
+        let layout: ViewLayout = serde_json::from_str(r#"{"columns":["title","tags"]}"#).unwrap();
+        assert_eq!(layout.mode, "card");
+        assert!(layout.validate().is_ok());
+
+        let invalid = [
+            r#"{"mode":"grid"}"#,
+            r#"{"columns":["title","author"]}"#,
+            r#"{"columns":["tags","tags"]}"#,
+            r#"{"previewLength":0}"#,
+            r#"{"previewLength":51}"#,
+        ];
+        for json in invalid {
+            let layout: ViewLayout = serde_json::from_str(json).unwrap();
+            assert!(layout.validate().is_err(), "{} should be rejected", json);
+        }
+    }
+
+    #[test]
+    fn test_prompt_test_passes() {
+        let mut test = PromptTest {
+            id: "t".to_string(),
+            prompt_id: "a.md".to_string(),
+            name: "mentions borrowing".to_string(),
+            values: HashMap::new(),
+            assertion: "borrow".to_string(),
+            is_regex: false,
+            updated: String::new(),
+            last_passed: None,
+        };
+        assert!(test.passes("The borrow checker rejects this").unwrap());
+        assert!(!test.passes("Looks fine").unwrap());
+
+        test.assertion = r"(?i)^looks (fine|good)\b".to_string();
+        assert!(!test.passes("Looks fine").unwrap());
+        test.is_regex = true;
+        assert!(test.passes("Looks fine").unwrap());
+        assert!(!test.passes("It looks fine").unwrap());
+
+        test.assertion = "(".to_string();
+        assert!(test.passes("anything").is_err());
+    }
+}
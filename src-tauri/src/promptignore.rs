@@ -0,0 +1,194 @@
+//! `.promptignore` at the vault root: files matching its gitignore-style patterns are
+//! left out of scans, syncs and the watcher
+
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+pub const PROMPTIGNORE_FILE: &str = ".promptignore";
+
+struct Rule {
+    regex: Regex,
+    /// `!pattern`: re-include what an earlier rule ignored
+    negate: bool,
+    /// `pattern/`: only matches folders
+    dir_only: bool,
+}
+
+/// Compiled `.promptignore` patterns; empty when the vault has none
+#[derive(Default)]
+pub struct PromptIgnore {
+    rules: Vec<Rule>,
+}
+
+impl PromptIgnore {
+    /// Rules of the vault's `.promptignore`. A missing file ignores nothing; an unreadable
+    /// one is logged and ignores nothing as well.
+    pub fn load(vault_path: &Path) -> Self {
+        match fs::read_to_string(vault_path.join(PROMPTIGNORE_FILE)) {
+            Ok(text) => Self::parse(&text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                info!("Failed to read {}: {}", PROMPTIGNORE_FILE, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse gitignore syntax: `#` comments, `!` negation, `/` anchoring, a trailing `/`
+    /// for folders, and `*`, `?`, `**` and `[...]` wildcards
+    pub fn parse(text: &str) -> Self {
+        let rules = text
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+                let (negate, pattern) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, pattern) = match pattern.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, pattern),
+                };
+                // A slash anywhere but the end anchors the pattern to the vault root
+                let anchored = pattern.contains('/');
+                let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+                if pattern.is_empty() {
+                    return None;
+                }
+                let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+                match Regex::new(&format!("{}{}$", prefix, glob_to_regex(pattern))) {
+                    Ok(regex) => Some(Rule {
+                        regex,
+                        negate,
+                        dir_only,
+                    }),
+                    Err(e) => {
+                        info!("Skipping {} pattern {:?}: {}", PROMPTIGNORE_FILE, line, e);
+                        None
+                    }
+                }
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// Whether the file at vault-relative `path` is ignored, itself or through one of its
+    /// folders. The `.promptignore` file is never a prompt, so it is always ignored.
+    pub fn is_ignored(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        if path == PROMPTIGNORE_FILE {
+            return true;
+        }
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+        (1..=components.len()).any(|depth| {
+            let is_dir = depth < components.len();
+            self.matches(&components[..depth].join("/"), is_dir)
+        })
+    }
+
+    /// The last rule matching `path` decides, as in git
+    fn matches(&self, path: &str, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| (is_dir || !rule.dir_only) && rule.regex.is_match(path))
+            .is_some_and(|rule| !rule.negate)
+    }
+}
+
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => {
+                let mut class = String::from("[");
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    class.push('^');
+                }
+                let mut closed = false;
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        closed = true;
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        class.push('\\');
+                    }
+                    class.push(c);
+                }
+                if closed {
+                    class.push(']');
+                    regex.push_str(&class);
+                } else {
+                    // An unclosed bracket is a literal character
+                    regex.push_str(&regex::escape("["));
+                    regex.push_str(&regex::escape(&class[1..]));
+                }
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    regex.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_promptignore_patterns() {
+        let ignore = PromptIgnore::parse(
+            "# Obsidian and templates\n\
+             .obsidian/\n\
+             /templates/\n\
+             *-draft.md\n\
+             !keep-draft.md\n\
+             scratch?.md\n\
+             notes/**/old.md\n\
+             [Aa]rchive*.md\n",
+        );
+
+        assert!(ignore.is_ignored(".obsidian/workspace.md"));
+        assert!(ignore.is_ignored("templates/daily.md"));
+        assert!(!ignore.is_ignored("prompts/templates.md"));
+        assert!(ignore.is_ignored("email-draft.md"));
+        assert!(!ignore.is_ignored("keep-draft.md"));
+        assert!(ignore.is_ignored("scratch1.md"));
+        assert!(!ignore.is_ignored("scratch12.md"));
+        assert!(ignore.is_ignored("notes/old.md"));
+        assert!(ignore.is_ignored("notes/2023/q1/old.md"));
+        assert!(ignore.is_ignored("archive-2023.md"));
+        assert!(ignore.is_ignored("Archive.md"));
+        assert!(!ignore.is_ignored("review.md"));
+        assert!(ignore.is_ignored(PROMPTIGNORE_FILE));
+
+        // Folder-only patterns do not match files of the same name
+        let ignore = PromptIgnore::parse("drafts/\n");
+        assert!(!ignore.is_ignored("drafts"));
+        assert!(ignore.is_ignored("drafts/idea.md"));
+        assert!(!PromptIgnore::default().is_ignored("drafts/idea.md"));
+    }
+}
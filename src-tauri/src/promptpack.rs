@@ -0,0 +1,206 @@
+//! `.promptpack` bundles: a zip of prompt files and the snippets they include, with a
+//! manifest, for exchanging prompt sets
+
+use crate::error::AppError;
+use crate::vault;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+/// Bumped when the layout of a pack changes; newer packs are refused
+pub const PACK_SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+/// Prompt files are stored under this folder by vault-relative path
+const PROMPTS_DIR: &str = "prompts/";
+
+/// `manifest.json` of a pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackManifest {
+    pub schema_version: u32,
+    pub created: String,
+    /// Prompts picked for the pack
+    pub prompts: Vec<String>,
+    /// Prompts included by those with `{{include:...}}` that were not picked themselves
+    #[serde(default)]
+    pub snippets: Vec<String>,
+    /// Tags of the packed prompts
+    #[serde(default)]
+    pub tags: Vec<PackTag>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackTag {
+    pub name: String,
+    /// Packed prompts using the tag
+    pub prompt_count: usize,
+}
+
+/// A prompt file in a pack, stored as is (frontmatter included)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PackFile {
+    /// Vault-relative path
+    pub path: String,
+    pub content: String,
+}
+
+/// Tags of `files` with the number of files using each, by name
+pub fn tag_counts<'a>(tags: impl IntoIterator<Item = &'a [String]>) -> Vec<PackTag> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for file_tags in tags {
+        for tag in file_tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(name, prompt_count)| PackTag {
+            name: name.to_string(),
+            prompt_count,
+        })
+        .collect()
+}
+
+/// Write a pack with `manifest` and `files` to `writer`
+pub fn write_pack<W: Write + Seek>(
+    writer: W,
+    manifest: &PackManifest,
+    files: &[PackFile],
+) -> Result<(), AppError> {
+    let failed = |e: zip::result::ZipError| AppError::io(format!("Failed to write pack: {}", e));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut zip = ZipWriter::new(writer);
+    zip.start_file(MANIFEST_NAME, options).map_err(failed)?;
+    zip.write_all(serde_json::to_string_pretty(manifest)?.as_bytes())
+        .map_err(|e| AppError::io(format!("Failed to write pack: {}", e)))?;
+    for file in files {
+        zip.start_file(format!("{}{}", PROMPTS_DIR, file.path), options)
+            .map_err(failed)?;
+        zip.write_all(file.content.as_bytes())
+            .map_err(|e| AppError::io(format!("Failed to write pack: {}", e)))?;
+    }
+    zip.finish().map_err(failed)?;
+    Ok(())
+}
+
+/// Read a pack written by `write_pack`. Files are returned in manifest order (prompts,
+/// then snippets); entries outside the manifest are ignored.
+pub fn read_pack<R: Read + Seek>(reader: R) -> Result<(PackManifest, Vec<PackFile>), AppError> {
+    let invalid =
+        |message: String| AppError::invalid_input(format!("Invalid prompt pack: {}", message));
+
+    let mut zip = ZipArchive::new(reader).map_err(|e| invalid(e.to_string()))?;
+    let manifest: PackManifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| invalid(format!("{} is missing", MANIFEST_NAME)))?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| invalid(e.to_string()))?;
+        serde_json::from_str(&content).map_err(|e| invalid(e.to_string()))?
+    };
+    if manifest.schema_version > PACK_SCHEMA_VERSION {
+        return Err(invalid(format!(
+            "schema version {} is newer than this app supports ({})",
+            manifest.schema_version, PACK_SCHEMA_VERSION
+        )));
+    }
+
+    let mut files = Vec::new();
+    for id in manifest.prompts.iter().chain(&manifest.snippets) {
+        // Paths come from an untrusted file; never let one escape the vault
+        let path = vault::normalize_relative_path(id, vault::MARKDOWN_ONLY)
+            .map_err(|e| invalid(format!("{}: {}", id, e)))?;
+        let mut entry = zip
+            .by_name(&format!("{}{}", PROMPTS_DIR, id))
+            .map_err(|_| invalid(format!("{} is missing", id)))?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| invalid(format!("{}: {}", id, e)))?;
+        files.push(PackFile { path, content });
+    }
+    Ok((manifest, files))
+}
+
+/// `path`, or the first of `name-2.md`, `name-3.md`, ... not taken in `vault_path` or by
+/// `reserved`
+pub fn available_path(vault_path: &Path, path: &str, reserved: &[String]) -> String {
+    let is_free = |candidate: &str| {
+        !vault_path.join(candidate).exists() && !reserved.iter().any(|p| p == candidate)
+    };
+    if is_free(path) {
+        return path.to_string();
+    }
+    let stem = path.strip_suffix(".md").unwrap_or(path);
+    (2..)
+        .map(|n| format!("{}-{}.md", stem, n))
+        .find(|candidate| is_free(candidate))
+        .expect("unbounded counter")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_pack_round_trip() {
+        let files = vec![
+            PackFile {
+                path: "review.md".to_string(),
+                content: "---\ntags: [code]\n---\n{{include:tone-block.md}} Review".to_string(),
+            },
+            PackFile {
+                path: "tone-block.md".to_string(),
+                content: "Be kind.".to_string(),
+            },
+        ];
+        let manifest = PackManifest {
+            schema_version: PACK_SCHEMA_VERSION,
+            created: "2024-05-01T10:00:00".to_string(),
+            prompts: vec!["review.md".to_string()],
+            snippets: vec!["tone-block.md".to_string()],
+            tags: tag_counts([vec!["code".to_string()].as_slice()]),
+        };
+
+        let mut buffer = Cursor::new(Vec::new());
+        write_pack(&mut buffer, &manifest, &files).unwrap();
+        let (read_manifest, read_files) = read_pack(Cursor::new(buffer.into_inner())).unwrap();
+        assert_eq!(read_manifest.snippets, vec!["tone-block.md"]);
+        assert_eq!(read_manifest.tags[0].prompt_count, 1);
+        assert_eq!(read_files, files);
+
+        let mut escaping = manifest.clone();
+        escaping.prompts = vec!["../outside.md".to_string()];
+        let mut buffer = Cursor::new(Vec::new());
+        write_pack(&mut buffer, &escaping, &files[..1]).unwrap();
+        assert!(read_pack(Cursor::new(buffer.into_inner())).is_err());
+
+        let mut newer = manifest;
+        newer.schema_version = PACK_SCHEMA_VERSION + 1;
+        let mut buffer = Cursor::new(Vec::new());
+        write_pack(&mut buffer, &newer, &files).unwrap();
+        assert!(read_pack(Cursor::new(buffer.into_inner())).is_err());
+    }
+
+    #[test]
+    fn test_available_path() {
+        let dir = TempDir::new("promptpack");
+        std::fs::write(dir.join("tone-block.md"), "").unwrap();
+
+        assert_eq!(available_path(&dir, "review.md", &[]), "review.md");
+        assert_eq!(
+            available_path(&dir, "tone-block.md", &["tone-block-2.md".to_string()]),
+            "tone-block-3.md"
+        );
+    }
+}
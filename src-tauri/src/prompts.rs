@@ -0,0 +1,1226 @@
+//! Reading prompts from the cache (shared by the commands, the HTTP API and the CLI)
+
+use crate::crypto::VaultKey;
+use crate::db::{queries::*, DbPool};
+use crate::error::AppError;
+use crate::models::{
+    DbError, FilterConfig, GlobalRow, GroupBy, LinkDirection, LinkedPrompt, PageRequest, Prompt,
+    PromptGroup, PromptGroupRow, PromptIdRow, PromptLinkRow, PromptRow, PromptSummary,
+    PromptSummaryPage, RecentKind, SortConfig, TagCountRow, TagGraph, TagGraphEdge, TagGraphNode,
+    TagMode, TagNameRow, TagPairRow,
+};
+use crate::search::SearchQuery;
+use crate::template;
+use crate::vault;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::{HashMap, HashSet};
+
+/// Newest first when no sort is given
+const DEFAULT_ORDER_BY: &str = "p.created DESC";
+
+/// Characters of prompt text kept in a `PromptSummary` excerpt
+const EXCERPT_CHARS: usize = 200;
+
+/// Filter and sort cached prompts
+pub async fn query_prompts(
+    pool: &DbPool,
+    filter: Option<FilterConfig>,
+    sort: Option<SortConfig>,
+) -> Result<Vec<Prompt>, DbError> {
+    // Fetch all prompts from cache, sorted in SQL (in-memory filters keep the order)
+    let order_by = sort
+        .as_ref()
+        .map(order_by_clause)
+        .unwrap_or_else(|| DEFAULT_ORDER_BY.to_string());
+    let (conditions, values) = where_clause(filter.as_ref())?;
+    let query = format!(
+        "{}{} ORDER BY {}, p.id",
+        SELECT_ALL_PROMPTS, conditions, order_by
+    );
+    let mut prompt_query = sqlx::query_as::<_, PromptRow>(&query);
+    for value in values {
+        prompt_query = prompt_query.bind(value);
+    }
+    let prompt_rows = prompt_query.fetch_all(pool).await?;
+
+    // Build prompts with tags
+    let mut prompts = Vec::new();
+    for row in prompt_rows {
+        prompts.push(with_tags(pool, row).await?);
+    }
+
+    // Archived prompts only show up when asked for
+    let archived = filter
+        .as_ref()
+        .and_then(|filter| filter.archived)
+        .unwrap_or(false);
+    prompts.retain(|p| p.archived == archived);
+
+    // Apply filters in memory
+    if let Some(filter) = filter {
+        // Filter by search query (`tag:`, `title:`, `/regex/`, words; `-` negates)
+        if let Some(search) = &filter.search {
+            let query = SearchQuery::from_config(search)?;
+            if !query.is_empty() {
+                prompts.retain(|p| query.matches(p));
+            }
+        }
+
+        // Filter by custom frontmatter fields (all must match)
+        if let Some(fields) = &filter.fields {
+            prompts.retain(|p| {
+                fields.iter().all(|(key, expected)| {
+                    p.custom_fields
+                        .get(key)
+                        .is_some_and(|value| custom_field_matches(value, expected))
+                })
+            });
+        }
+
+        // Filter by targeted model and language
+        if let Some(model) = non_blank(&filter.model_target) {
+            prompts.retain(|p| {
+                p.model_targets
+                    .iter()
+                    .any(|target| target.eq_ignore_ascii_case(model))
+            });
+        }
+        if let Some(language) = non_blank(&filter.language) {
+            prompts.retain(|p| {
+                p.language
+                    .as_deref()
+                    .is_some_and(|value| value.trim().eq_ignore_ascii_case(language))
+            });
+        }
+    }
+
+    Ok(prompts)
+}
+
+fn non_blank(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// Summaries of the prompts `query_prompts` returns, only `page` of them when given
+pub async fn prompt_summaries(
+    pool: &DbPool,
+    filter: Option<FilterConfig>,
+    sort: Option<SortConfig>,
+    page: Option<PageRequest>,
+) -> Result<PromptSummaryPage, DbError> {
+    summary_page(query_prompts(pool, filter, sort).await?, page)
+}
+
+/// One page of `prompts` as summaries, with the total across all pages
+pub fn summary_page(
+    prompts: Vec<Prompt>,
+    page: Option<PageRequest>,
+) -> Result<PromptSummaryPage, DbError> {
+    let total = prompts.len();
+    let (skip, take) = match page {
+        Some(page) if page.page_size == 0 => {
+            return Err(DbError::InvalidInput("Page size must be positive".into()));
+        }
+        Some(page) => (
+            page.page as usize * page.page_size as usize,
+            page.page_size as usize,
+        ),
+        None => (0, total),
+    };
+    Ok(PromptSummaryPage {
+        items: prompts
+            .into_iter()
+            .skip(skip)
+            .take(take)
+            .map(summarize)
+            .collect(),
+        total,
+    })
+}
+
+/// The prompts `query_prompts` returns, grouped by `group_by` with the group sizes counted
+/// in SQL. Groups come sorted by key (newest month first, statuses in workflow order), prompts
+/// keep the requested order.
+pub async fn group_prompts(
+    pool: &DbPool,
+    filter: Option<FilterConfig>,
+    sort: Option<SortConfig>,
+    group_by: GroupBy,
+) -> Result<Vec<PromptGroup>, DbError> {
+    let prompts = query_prompts(pool, filter, sort).await?;
+    let ids: Vec<&str> = prompts.iter().map(|p| p.id.as_str()).collect();
+    let query = match group_by {
+        GroupBy::Tag => SELECT_PROMPT_GROUPS_BY_TAG,
+        GroupBy::CreatedMonth => SELECT_PROMPT_GROUPS_BY_CREATED_MONTH,
+        GroupBy::ModelTarget => SELECT_PROMPT_GROUPS_BY_MODEL_TARGET,
+        GroupBy::Folder => SELECT_PROMPT_GROUPS_BY_FOLDER,
+        GroupBy::Status => SELECT_PROMPT_GROUPS_BY_STATUS,
+    };
+    let rows = sqlx::query_as::<_, PromptGroupRow>(query)
+        .bind(serde_json::to_string(&ids)?)
+        .fetch_all(pool)
+        .await?;
+
+    let mut members: HashMap<Option<String>, HashSet<String>> = HashMap::new();
+    let mut groups: Vec<PromptGroup> = Vec::new();
+    for row in rows {
+        if !members.contains_key(&row.key) {
+            groups.push(PromptGroup {
+                key: row.key.clone(),
+                count: row.count as u32,
+                prompts: Vec::new(),
+            });
+        }
+        members.entry(row.key).or_default().insert(row.prompt_id);
+    }
+    for group in &mut groups {
+        let ids = &members[&group.key];
+        group.prompts = prompts
+            .iter()
+            .filter(|p| ids.contains(&p.id))
+            .cloned()
+            .collect();
+    }
+    Ok(groups)
+}
+
+/// `prompt` without its full text
+pub fn summarize(prompt: Prompt) -> PromptSummary {
+    let mut words = prompt.text.split_whitespace();
+    let mut excerpt = words.next().unwrap_or_default().to_string();
+    for word in words {
+        excerpt.push(' ');
+        excerpt.push_str(word);
+        if excerpt.chars().count() > EXCERPT_CHARS {
+            break;
+        }
+    }
+    if excerpt.chars().count() > EXCERPT_CHARS {
+        excerpt = excerpt.chars().take(EXCERPT_CHARS).collect::<String>();
+        excerpt.truncate(excerpt.trim_end().len());
+        excerpt.push('…');
+    }
+    PromptSummary {
+        id: prompt.id,
+        title: prompt.title,
+        tags: prompt.tags,
+        created: prompt.created,
+        excerpt,
+        color: prompt.color,
+        icon: prompt.icon,
+    }
+}
+
+/// `WHERE` clause for the filters evaluated in SQL (empty when none apply) and the
+/// values to bind to its placeholders
+fn where_clause(filter: Option<&FilterConfig>) -> Result<(String, Vec<String>), DbError> {
+    let mut conditions: Vec<String> = Vec::new();
+    let mut values: Vec<String> = Vec::new();
+    if let Some(filter) = filter {
+        // Tags are hierarchical: filtering on `lang` also matches `lang/rust`
+        let (included, excluded) = split_tag_filters(filter.tags.as_deref().unwrap_or_default());
+        if !included.is_empty() {
+            match filter.tag_mode.unwrap_or_default() {
+                TagMode::And => {
+                    for tag in included {
+                        conditions.push(PROMPT_HAS_ANY_TAG.to_string());
+                        values.push(serde_json::to_string(&[tag])?);
+                    }
+                }
+                TagMode::Or => {
+                    conditions.push(PROMPT_HAS_ANY_TAG.to_string());
+                    values.push(serde_json::to_string(&included)?);
+                }
+            }
+        }
+        if !excluded.is_empty() {
+            conditions.push(format!("NOT {}", PROMPT_HAS_ANY_TAG));
+            values.push(serde_json::to_string(&excluded)?);
+        }
+
+        let date_filters = [
+            (&filter.created_after, "p.created >= ?"),
+            (&filter.created_before, "p.created < ?"),
+            (
+                &filter.updated_after,
+                "COALESCE(p.updated_at, p.created) >= ?",
+            ),
+        ];
+        for (value, condition) in date_filters {
+            if let Some(value) = value.as_deref().filter(|v| !v.trim().is_empty()) {
+                conditions.push(condition.to_string());
+                values.push(parse_filter_date(value)?);
+            }
+        }
+        if filter.untagged == Some(true) {
+            conditions.push(
+                "NOT EXISTS (SELECT 1 FROM prompt_tags pt WHERE pt.prompt_id = p.id)".to_string(),
+            );
+        }
+        if filter.missing_title == Some(true) {
+            conditions.push("NULLIF(TRIM(p.title), '') IS NULL".to_string());
+        }
+        if let Some(status) = filter.status {
+            conditions.push("p.status = ?".to_string());
+            values.push(status.as_str().to_string());
+        }
+        let word_filters = [
+            (filter.min_words, "p.word_count >= CAST(? AS INTEGER)"),
+            (filter.max_words, "p.word_count <= CAST(? AS INTEGER)"),
+        ];
+        for (value, condition) in word_filters {
+            if let Some(value) = value {
+                conditions.push(condition.to_string());
+                values.push(value.to_string());
+            }
+        }
+    }
+    if conditions.is_empty() {
+        Ok((String::new(), values))
+    } else {
+        Ok((format!(" WHERE {}", conditions.join(" AND ")), values))
+    }
+}
+
+/// Tag names to include and to exclude (`-tag`), blank ones dropped
+fn split_tag_filters(tags: &[String]) -> (Vec<&str>, Vec<&str>) {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        let (list, name) = match tag.strip_prefix('-') {
+            Some(name) => (&mut excluded, vault::tag_filter_name(name)),
+            None => (&mut included, vault::tag_filter_name(tag)),
+        };
+        if !name.is_empty() {
+            list.push(name);
+        }
+    }
+    (included, excluded)
+}
+
+/// A filter date as the local `YYYY-MM-DDTHH:MM:SS` timestamps prompts are created with;
+/// a bare date means its midnight
+fn parse_filter_date(value: &str) -> Result<String, DbError> {
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+    let value = value.trim();
+    let parsed = if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        date.and_time(NaiveTime::MIN)
+    } else if let Ok(time) = NaiveDateTime::parse_from_str(value, FORMAT) {
+        time
+    } else if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        time.with_timezone(&Local).naive_local()
+    } else {
+        return Err(DbError::InvalidInput(format!("Invalid date: {}", value)));
+    };
+    Ok(parsed.format(FORMAT).to_string())
+}
+
+/// `ORDER BY` terms for `sort` and its tie-breakers (only whitelisted expressions reach SQL)
+fn order_by_clause(sort: &SortConfig) -> String {
+    let mut terms = vec![order_term(&sort.by, &sort.order)];
+    terms.extend(sort.then.iter().map(|key| order_term(&key.by, &key.order)));
+    terms.join(", ")
+}
+
+fn order_term(by: &str, order: &str) -> String {
+    let direction = if order == "desc" { "DESC" } else { "ASC" };
+    format!("{} {}", sort_expression(by), direction)
+}
+
+/// SQL expression for a `SortConfig::by` key; unknown keys sort by `created`
+fn sort_expression(by: &str) -> &'static str {
+    match by {
+        "title" => "COALESCE(NULLIF(TRIM(p.title), ''), p.text) COLLATE NOCASE",
+        "updated" | "updated_at" => "COALESCE(p.updated_at, p.created)",
+        "usage_count" => "COALESCE(u.use_count, 0)",
+        "last_used" => "u.last_used_at",
+        "char_count" => "p.char_count",
+        "word_count" => "p.word_count",
+        "line_count" => "p.line_count",
+        "token_count" => "p.token_count",
+        _ => "p.created",
+    }
+}
+
+/// A single cached prompt with its tags
+pub async fn load_prompt(pool: &DbPool, id: &str) -> Result<Option<Prompt>, DbError> {
+    let Some(row) = sqlx::query_as::<_, PromptRow>(SELECT_PROMPT_BY_ID)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?
+    else {
+        return Ok(None);
+    };
+    Ok(Some(with_tags(pool, row).await?))
+}
+
+/// Values for `{{global:name}}` placeholders, by name
+pub async fn global_variables(pool: &DbPool) -> Result<HashMap<String, String>, DbError> {
+    let rows = sqlx::query_as::<_, GlobalRow>(SELECT_GLOBALS)
+        .fetch_all(pool)
+        .await?;
+    Ok(rows.into_iter().map(|row| (row.name, row.value)).collect())
+}
+
+/// Text of a prompt with its `{{include:path.md}}` directives expanded from the cache.
+/// Encrypted includes are opened with `key`, failing with `CryptoError::Locked` while the
+/// vault is locked.
+pub async fn expand_includes(
+    pool: &DbPool,
+    prompt: &Prompt,
+    key: &VaultKey,
+) -> Result<String, AppError> {
+    // Load every prompt reachable through includes, one level at a time
+    let mut texts = HashMap::new();
+    let mut pending = template::includes(&prompt.text);
+    for _ in 0..=template::MAX_INCLUDE_DEPTH {
+        let mut next = Vec::new();
+        for path in pending {
+            if path == prompt.id || texts.contains_key(&path) {
+                continue;
+            }
+            let row = sqlx::query_as::<_, PromptRow>(SELECT_PROMPT_BY_ID)
+                .bind(&path)
+                .fetch_optional(pool)
+                .await?;
+            if let Some(row) = row {
+                let text = key.open(&row.text)?;
+                next.extend(template::includes(&text));
+                texts.insert(path, text);
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        pending = next;
+    }
+
+    Ok(template::expand_includes(&prompt.text, &prompt.id, &texts)?)
+}
+
+/// Up to `limit` unarchived prompts, most recently used or edited first
+pub async fn recent_prompts(
+    pool: &DbPool,
+    kind: RecentKind,
+    limit: u32,
+) -> Result<Vec<Prompt>, DbError> {
+    let query = match kind {
+        RecentKind::Used => SELECT_RECENTLY_USED_PROMPTS,
+        RecentKind::Edited => SELECT_RECENTLY_EDITED_PROMPTS,
+    };
+    let rows = sqlx::query_as::<_, PromptRow>(query)
+        .bind(limit)
+        .fetch_all(pool)
+        .await?;
+
+    let mut prompts = Vec::with_capacity(rows.len());
+    for row in rows {
+        prompts.push(with_tags(pool, row).await?);
+    }
+    Ok(prompts)
+}
+
+/// Unarchived prompts neither used nor edited in the last `days` days, stalest first
+pub async fn stale_prompts(pool: &DbPool, days: u32) -> Result<Vec<Prompt>, DbError> {
+    let cutoff = (Local::now() - chrono::Duration::days(days.into()))
+        .format("%Y-%m-%dT%H:%M:%S")
+        .to_string();
+    let rows = sqlx::query_as::<_, PromptRow>(SELECT_STALE_PROMPTS)
+        .bind(cutoff)
+        .fetch_all(pool)
+        .await?;
+
+    let mut prompts = Vec::with_capacity(rows.len());
+    for row in rows {
+        prompts.push(with_tags(pool, row).await?);
+    }
+    Ok(prompts)
+}
+
+async fn with_tags(pool: &DbPool, row: PromptRow) -> Result<Prompt, DbError> {
+    let tags = get_tags_for_prompt(pool, &row.id).await?;
+    let custom_fields = row.parse_custom_fields();
+    let variables = row.parse_variables();
+    let model_targets = row.parse_model_targets();
+    let status = row.parse_status();
+    Ok(Prompt {
+        id: row.id,
+        created: row.created,
+        text: row.text,
+        tags,
+        file_path: row.file_path,
+        title: row.title,
+        description: row.description,
+        file_hash: row.file_hash,
+        custom_fields,
+        token_count: row.token_count,
+        archived: row.archived,
+        updated_at: row.updated_at,
+        variables,
+        revision: row.revision,
+        color: row.color,
+        icon: row.icon,
+        notes: row.notes,
+        model_targets,
+        language: row.language,
+        status,
+        char_count: row.char_count,
+        word_count: row.word_count,
+        line_count: row.line_count,
+    })
+}
+
+/// Tag names of a cached prompt, sorted
+pub async fn get_tags_for_prompt(pool: &DbPool, prompt_id: &str) -> Result<Vec<String>, DbError> {
+    let rows = sqlx::query_as::<_, TagNameRow>(SELECT_TAGS_FOR_PROMPT)
+        .bind(prompt_id)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(rows.into_iter().map(|r| r.name).collect())
+}
+
+/// Tags with their prompt counts, linked by how many prompts they share
+pub async fn tag_graph(pool: &DbPool) -> Result<TagGraph, DbError> {
+    let counts = sqlx::query_as::<_, TagCountRow>(SELECT_TAG_COUNTS)
+        .fetch_all(pool)
+        .await?;
+    let pairs = sqlx::query_as::<_, TagPairRow>(SELECT_TAG_PAIRS)
+        .fetch_all(pool)
+        .await?;
+
+    let count_of = |name: &str| {
+        counts
+            .binary_search_by(|row| row.name.as_str().cmp(name))
+            .map(|index| counts[index].prompt_count)
+            .unwrap_or(0)
+    };
+    let edges = pairs
+        .iter()
+        .map(|pair| {
+            // |A ∩ B| / |A ∪ B|
+            let union = count_of(&pair.source) + count_of(&pair.target) - pair.weight;
+            TagGraphEdge {
+                source: pair.source.clone(),
+                target: pair.target.clone(),
+                weight: pair.weight,
+                similarity: if union > 0 {
+                    pair.weight as f64 / union as f64
+                } else {
+                    0.0
+                },
+            }
+        })
+        .collect();
+    let nodes = counts
+        .into_iter()
+        .map(|row| TagGraphNode {
+            name: row.name,
+            prompt_count: row.prompt_count,
+        })
+        .collect();
+
+    Ok(TagGraph { nodes, edges })
+}
+
+/// Prompts a prompt links to and prompts linking to it, in link creation order.
+/// Links whose other end is not in the cache are skipped.
+pub async fn linked_prompts(pool: &DbPool, id: &str) -> Result<Vec<LinkedPrompt>, DbError> {
+    let outgoing = sqlx::query_as::<_, PromptLinkRow>(SELECT_OUTGOING_LINKS)
+        .bind(id)
+        .fetch_all(pool)
+        .await?;
+    let [name, without_extension, stem] = link_names(id);
+    let incoming = sqlx::query_as::<_, PromptLinkRow>(SELECT_INCOMING_LINKS)
+        .bind(name)
+        .bind(without_extension)
+        .bind(stem)
+        .fetch_all(pool)
+        .await?;
+
+    // Shortest paths first, so an ambiguous file stem resolves like Obsidian does
+    let mut ids: Vec<String> = sqlx::query_as::<_, PromptIdRow>(SELECT_PROMPT_IDS)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|row| row.id)
+        .collect();
+    ids.sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+
+    let links = outgoing
+        .into_iter()
+        .map(|row| (row, LinkDirection::Outgoing))
+        .chain(
+            incoming
+                .into_iter()
+                .map(|row| (row, LinkDirection::Incoming)),
+        );
+    let mut seen = HashSet::new();
+    let mut linked = Vec::new();
+    for (row, direction) in links {
+        let Some(target) = resolve_link(&ids, &row.target_id) else {
+            continue;
+        };
+        let other = match direction {
+            LinkDirection::Outgoing => target,
+            // A stem match may have resolved to another prompt with the same file name
+            LinkDirection::Incoming if target == id => row.source_id.as_str(),
+            LinkDirection::Incoming => continue,
+        };
+        if other == id || !seen.insert((other.to_string(), row.relation.clone(), direction)) {
+            continue;
+        }
+        if let Some(prompt) = load_prompt(pool, other).await? {
+            linked.push(LinkedPrompt {
+                prompt,
+                relation: row.relation,
+                direction,
+            });
+        }
+    }
+    Ok(linked)
+}
+
+/// Names a link can use for a prompt: its id, the id without `.md` and the file stem
+fn link_names(id: &str) -> [String; 3] {
+    let without_extension = id.strip_suffix(".md").unwrap_or(id);
+    let stem = without_extension
+        .rsplit('/')
+        .next()
+        .unwrap_or(without_extension);
+    [
+        id.to_string(),
+        without_extension.to_string(),
+        stem.to_string(),
+    ]
+}
+
+/// The cached prompt a link target names: an exact id, else the first id (of `ids`) it is a
+/// link name of
+fn resolve_link<'a>(ids: &'a [String], target: &str) -> Option<&'a str> {
+    ids.iter()
+        .find(|id| *id == target)
+        .or_else(|| {
+            ids.iter()
+                .find(|id| link_names(id).iter().any(|name| name == target))
+        })
+        .map(String::as_str)
+}
+
+/// Whether a custom field value matches a filter value (case-insensitive, any list item)
+fn custom_field_matches(value: &serde_json::Value, expected: &str) -> bool {
+    let expected = expected.trim();
+    if expected.is_empty() {
+        return true;
+    }
+    match value {
+        serde_json::Value::String(s) => s.eq_ignore_ascii_case(expected),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .any(|item| !item.is_array() && custom_field_matches(item, expected)),
+        serde_json::Value::Null => false,
+        other => other.to_string().eq_ignore_ascii_case(expected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::migrations;
+    use crate::models::SortKey;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    #[tokio::test]
+    async fn test_untagged_and_missing_title_filters() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        for (id, title) in [
+            ("a.md", Some("Tagged")),
+            ("b.md", None),
+            ("c.md", Some(" ")),
+        ] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, title, file_path) VALUES (?, '2024-01-01', 'x', ?, ?)",
+            )
+            .bind(id)
+            .bind(title)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(INSERT_TAG)
+            .bind("t1")
+            .bind("rust")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO prompt_tags (prompt_id, tag_id) VALUES ('a.md', 't1')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let ids = |prompts: Vec<Prompt>| prompts.into_iter().map(|p| p.id).collect::<Vec<_>>();
+        let sort = || {
+            Some(SortConfig {
+                by: "title".to_string(),
+                order: "asc".to_string(),
+                then: Vec::new(),
+            })
+        };
+
+        let untagged = FilterConfig {
+            untagged: Some(true),
+            ..FilterConfig::default()
+        };
+        let found = query_prompts(&pool, Some(untagged), sort()).await.unwrap();
+        assert_eq!(ids(found), vec!["b.md", "c.md"]);
+
+        let missing_title = FilterConfig {
+            missing_title: Some(true),
+            tags: Some(vec!["-rust".to_string()]),
+            ..FilterConfig::default()
+        };
+        let found = query_prompts(&pool, Some(missing_title), sort())
+            .await
+            .unwrap();
+        assert_eq!(ids(found), vec!["b.md", "c.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_tag_modes() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let tags = [
+            ("t1", "writing"),
+            ("t2", "blogging/drafts"),
+            ("t3", "archive"),
+            ("t4", "writingtips"),
+        ];
+        for (id, name) in tags {
+            sqlx::query(INSERT_TAG)
+                .bind(id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (id, tag_ids) in [
+            ("a.md", vec!["t1"]),
+            ("b.md", vec!["t2"]),
+            ("c.md", vec!["t1", "t2"]),
+            ("d.md", vec!["t1", "t3"]),
+            ("e.md", vec!["t4"]),
+        ] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, file_path) VALUES (?, '2024-01-01', 'x', ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            for tag_id in tag_ids {
+                sqlx::query("INSERT INTO prompt_tags (prompt_id, tag_id) VALUES (?, ?)")
+                    .bind(id)
+                    .bind(tag_id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let found = |tags: &[&str], tag_mode| {
+            let filter = FilterConfig {
+                tags: Some(tags.iter().map(|tag| tag.to_string()).collect()),
+                tag_mode,
+                ..FilterConfig::default()
+            };
+            let pool = pool.clone();
+            async move {
+                let mut ids: Vec<String> = query_prompts(&pool, Some(filter), None)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|p| p.id)
+                    .collect();
+                ids.sort();
+                ids
+            }
+        };
+
+        // Nested tags count, but not tags that only start with the same letters
+        assert_eq!(found(&["writing", "#blogging"], None).await, vec!["c.md"]);
+        assert_eq!(
+            found(&["writing", "blogging/"], Some(TagMode::Or)).await,
+            vec!["a.md", "b.md", "c.md", "d.md"]
+        );
+        assert_eq!(
+            found(&["writing", "blogging", "-archive"], Some(TagMode::Or)).await,
+            vec!["a.md", "b.md", "c.md"]
+        );
+        assert_eq!(
+            found(&["-writing", "-blogging"], Some(TagMode::Or)).await,
+            vec!["e.md"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_group_prompts() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        for (id, created, targets) in [
+            (
+                "a.md",
+                "2024-01-05T10:00:00",
+                Some(r#"["gpt-4o","claude-3.5"]"#),
+            ),
+            ("b.md", "2024-02-01T10:00:00", Some(r#"["gpt-4o"]"#)),
+            ("c.md", "2024-01-20T10:00:00", None),
+        ] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, file_path, model_targets) VALUES (?, ?, 'x', ?, ?)",
+            )
+            .bind(id)
+            .bind(created)
+            .bind(id)
+            .bind(targets)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(INSERT_TAG)
+            .bind("t1")
+            .bind("rust")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO prompt_tags (prompt_id, tag_id) VALUES ('a.md', 't1'), ('b.md', 't1')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Newest first within each group, as sorted
+        let sort = || {
+            Some(SortConfig {
+                by: "created".to_string(),
+                order: "desc".to_string(),
+                then: Vec::new(),
+            })
+        };
+        let groups = |groups: Vec<PromptGroup>| {
+            groups
+                .into_iter()
+                .map(|group| {
+                    let ids: Vec<String> = group.prompts.into_iter().map(|p| p.id).collect();
+                    assert_eq!(ids.len(), group.count as usize);
+                    (group.key, ids)
+                })
+                .collect::<Vec<_>>()
+        };
+        let key = |key: &str| Some(key.to_string());
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+
+        let by_tag = group_prompts(&pool, None, sort(), GroupBy::Tag)
+            .await
+            .unwrap();
+        assert_eq!(
+            groups(by_tag),
+            vec![
+                (key("rust"), ids(&["b.md", "a.md"])),
+                (None, ids(&["c.md"])),
+            ]
+        );
+
+        let by_month = group_prompts(&pool, None, sort(), GroupBy::CreatedMonth)
+            .await
+            .unwrap();
+        assert_eq!(
+            groups(by_month),
+            vec![
+                (key("2024-02"), ids(&["b.md"])),
+                (key("2024-01"), ids(&["c.md", "a.md"])),
+            ]
+        );
+
+        let by_model = group_prompts(&pool, None, sort(), GroupBy::ModelTarget)
+            .await
+            .unwrap();
+        assert_eq!(
+            groups(by_model),
+            vec![
+                (key("claude-3.5"), ids(&["a.md"])),
+                (key("gpt-4o"), ids(&["b.md", "a.md"])),
+                (None, ids(&["c.md"])),
+            ]
+        );
+
+        // Only prompts matching the filter are grouped
+        let filter = FilterConfig {
+            model_target: Some("gpt-4o".to_string()),
+            ..FilterConfig::default()
+        };
+        let by_month = group_prompts(&pool, Some(filter), sort(), GroupBy::CreatedMonth)
+            .await
+            .unwrap();
+        assert_eq!(
+            groups(by_month),
+            vec![
+                (key("2024-02"), ids(&["b.md"])),
+                (key("2024-01"), ids(&["a.md"])),
+            ]
+        );
+
+        // Every prompt is at the vault root
+        let by_folder = group_prompts(&pool, None, sort(), GroupBy::Folder)
+            .await
+            .unwrap();
+        assert_eq!(
+            groups(by_folder),
+            vec![(None, ids(&["b.md", "c.md", "a.md"]))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_model_target_and_language_filters() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        for (id, targets, language) in [
+            ("a.md", Some(r#"["gpt-4o","claude-3.5"]"#), Some("en")),
+            ("b.md", Some(r#"["claude-3.5"]"#), Some("de")),
+            ("c.md", None, Some("EN")),
+        ] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, file_path, model_targets, language) VALUES (?, '2024-01-01', 'x', ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .bind(targets)
+            .bind(language)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let ids = |prompts: Vec<Prompt>| {
+            let mut ids: Vec<String> = prompts.into_iter().map(|p| p.id).collect();
+            ids.sort();
+            ids
+        };
+        let filter = |model: Option<&str>, language: Option<&str>| {
+            Some(FilterConfig {
+                model_target: model.map(str::to_string),
+                language: language.map(str::to_string),
+                ..FilterConfig::default()
+            })
+        };
+
+        let found = query_prompts(&pool, filter(Some("Claude-3.5"), None), None)
+            .await
+            .unwrap();
+        assert_eq!(ids(found), vec!["a.md", "b.md"]);
+        let found = query_prompts(&pool, filter(None, Some("en")), None)
+            .await
+            .unwrap();
+        assert_eq!(ids(found), vec!["a.md", "c.md"]);
+        let found = query_prompts(&pool, filter(Some("claude-3.5"), Some("en")), None)
+            .await
+            .unwrap();
+        assert_eq!(ids(found), vec!["a.md"]);
+        // Blank filters match everything
+        let found = query_prompts(&pool, filter(Some(" "), None), None)
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_tag_graph() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        for (tag_id, name) in [
+            ("t1", "ai"),
+            ("t2", "llm"),
+            ("t3", "rust"),
+            ("t4", "unused"),
+        ] {
+            sqlx::query(INSERT_TAG)
+                .bind(tag_id)
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for (id, tag_ids) in [
+            ("a.md", vec!["t1", "t2"]),
+            ("b.md", vec!["t1", "t2", "t3"]),
+            ("c.md", vec!["t1"]),
+        ] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, file_path) VALUES (?, '2024-01-01', 'x', ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+            for tag_id in tag_ids {
+                sqlx::query("INSERT INTO prompt_tags (prompt_id, tag_id) VALUES (?, ?)")
+                    .bind(id)
+                    .bind(tag_id)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let graph = tag_graph(&pool).await.unwrap();
+        let nodes: Vec<_> = graph
+            .nodes
+            .iter()
+            .map(|node| (node.name.as_str(), node.prompt_count))
+            .collect();
+        assert_eq!(nodes, vec![("ai", 3), ("llm", 2), ("rust", 1)]);
+
+        let edges: Vec<_> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.source.as_str(), edge.target.as_str(), edge.weight))
+            .collect();
+        assert_eq!(
+            edges,
+            vec![("ai", "llm", 2), ("ai", "rust", 1), ("llm", "rust", 1)]
+        );
+        assert!((graph.edges[0].similarity - 2.0 / 3.0).abs() < 1e-9);
+        assert!((graph.edges[2].similarity - 0.5).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_stale_prompts() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        let recent = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+        for (id, created, updated_at, archived) in [
+            ("old.md", "2020-01-01", None, false),
+            ("edited.md", "2020-01-01", Some(recent.as_str()), false),
+            ("used.md", "2020-01-01", None, false),
+            ("archived.md", "2020-01-01", None, true),
+            (
+                "older.md",
+                "2019-06-01T08:00:00",
+                Some("2019-07-01T08:00:00"),
+                false,
+            ),
+        ] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, file_path, updated_at, archived) VALUES (?, ?, 'x', ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(created)
+            .bind(id)
+            .bind(updated_at)
+            .bind(archived)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query(RECORD_PROMPT_USAGE)
+            .bind("used.md")
+            .bind(&recent)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stale = stale_prompts(&pool, 30).await.unwrap();
+        let ids: Vec<_> = stale.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["older.md", "old.md"]);
+    }
+
+    #[tokio::test]
+    async fn test_linked_prompts() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        for id in ["base.md", "system/base.md", "follow.md", "notes/other.md"] {
+            sqlx::query(
+                "INSERT INTO prompts (id, created, text, file_path) VALUES (?, '2024-01-01', 'x', ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        for (source, target, relation) in [
+            ("follow.md", "base", "wikilink"),
+            ("follow.md", "system/base", "wikilink"),
+            ("follow.md", "missing", "wikilink"),
+            ("notes/other.md", "follow.md", "follow-up"),
+            ("follow.md", "follow", "wikilink"),
+        ] {
+            sqlx::query(INSERT_PROMPT_LINK)
+                .bind(source)
+                .bind(target)
+                .bind(relation)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let summary = |linked: Vec<LinkedPrompt>| {
+            linked
+                .into_iter()
+                .map(|link| (link.prompt.id, link.relation, link.direction))
+                .collect::<Vec<_>>()
+        };
+        let mut found = summary(linked_prompts(&pool, "follow.md").await.unwrap());
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            found,
+            vec![
+                (
+                    "base.md".to_string(),
+                    "wikilink".to_string(),
+                    LinkDirection::Outgoing
+                ),
+                (
+                    "notes/other.md".to_string(),
+                    "follow-up".to_string(),
+                    LinkDirection::Incoming
+                ),
+                (
+                    "system/base.md".to_string(),
+                    "wikilink".to_string(),
+                    LinkDirection::Outgoing
+                ),
+            ]
+        );
+
+        // `[[base]]` resolves to the shorter `base.md`, not `system/base.md`
+        let found = summary(linked_prompts(&pool, "system/base.md").await.unwrap());
+        assert_eq!(
+            found,
+            vec![(
+                "follow.md".to_string(),
+                "wikilink".to_string(),
+                LinkDirection::Incoming
+            )]
+        );
+    }
+
+    #[test]
+    fn test_parse_filter_date() {
+        assert_eq!(
+            parse_filter_date("2024-03-01").unwrap(),
+            "2024-03-01T00:00:00"
+        );
+        assert_eq!(
+            parse_filter_date(" 2024-03-01T12:30:00 ").unwrap(),
+            "2024-03-01T12:30:00"
+        );
+        assert!(parse_filter_date("2024-03-01T12:30:00+02:00").is_ok());
+        assert!(parse_filter_date("last week").is_err());
+    }
+
+    #[test]
+    fn test_order_by_clause() {
+        let sort = SortConfig {
+            by: "usage_count".to_string(),
+            order: "desc".to_string(),
+            then: vec![
+                SortKey {
+                    by: "title".to_string(),
+                    order: "asc".to_string(),
+                },
+                SortKey {
+                    by: "id; DROP TABLE prompts".to_string(),
+                    order: "sideways".to_string(),
+                },
+            ],
+        };
+        assert_eq!(
+            order_by_clause(&sort),
+            "COALESCE(u.use_count, 0) DESC, \
+             COALESCE(NULLIF(TRIM(p.title), ''), p.text) COLLATE NOCASE ASC, \
+             p.created ASC"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prompt_summaries() {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        migrations::run_migrations(&pool).await.unwrap();
+        for i in 0..5 {
+            sqlx::query("INSERT INTO prompts (id, created, text, file_path) VALUES (?, ?, ?, ?)")
+                .bind(format!("p{}.md", i))
+                .bind(format!("2024-01-0{}", i + 1))
+                .bind(format!("Prompt {}\n\n{}", i, "word ".repeat(100)))
+                .bind(format!("p{}.md", i))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let page = PageRequest {
+            page: 1,
+            page_size: 2,
+        };
+        let summaries = prompt_summaries(&pool, None, None, Some(page))
+            .await
+            .unwrap();
+        assert_eq!(summaries.total, 5);
+        let ids: Vec<&str> = summaries.items.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["p2.md", "p1.md"]);
+        let excerpt = &summaries.items[0].excerpt;
+        assert!(excerpt.starts_with("Prompt 2 word word"));
+        assert!(excerpt.ends_with('…'));
+        assert_eq!(excerpt.chars().count(), EXCERPT_CHARS + 1);
+
+        let all = prompt_summaries(&pool, None, None, None).await.unwrap();
+        assert_eq!(all.items.len(), 5);
+        let empty = PageRequest {
+            page: 0,
+            page_size: 0,
+        };
+        assert!(prompt_summaries(&pool, None, None, Some(empty))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,310 @@
+//! Starter prompts and views for a new vault, so first-time users have something to explore
+
+use crate::config::FrontmatterSettings;
+use crate::error::AppError;
+use crate::template::TemplateVariable;
+use crate::vault::{self, PromptFile};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use specta::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Which starter prompts `create_sample_vault` writes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SamplePack {
+    /// Every starter prompt
+    Starter,
+    Coding,
+    Writing,
+    Summarization,
+}
+
+impl SamplePack {
+    fn includes(self, topic: SamplePack) -> bool {
+        self == SamplePack::Starter || self == topic
+    }
+}
+
+struct SamplePrompt {
+    file: &'static str,
+    topic: SamplePack,
+    title: &'static str,
+    description: &'static str,
+    tags: &'static [&'static str],
+    /// Declared template variables: name, default and description
+    variables: &'static [(&'static str, Option<&'static str>, &'static str)],
+    content: &'static str,
+}
+
+const SAMPLE_PROMPTS: &[SamplePrompt] = &[
+    SamplePrompt {
+        file: "code-review.md",
+        topic: SamplePack::Coding,
+        title: "Code Review",
+        description: "Review a change for bugs, readability and missing tests",
+        tags: &["coding", "coding/review"],
+        variables: &[
+            ("language", Some("Rust"), "Language of the code"),
+            ("code", None, "The code or diff to review"),
+        ],
+        content: "You are a senior {{language}} engineer reviewing a pull request.
+
+Review the code below. List, in order of importance:
+1. Bugs and edge cases that are not handled
+2. Code that is hard to read or does more than it needs to
+3. Tests that are missing
+
+Quote the relevant lines for every point and suggest a fix.
+
+```
+{{code}}
+```",
+    },
+    SamplePrompt {
+        file: "explain-error.md",
+        topic: SamplePack::Coding,
+        title: "Explain an Error",
+        description: "Find the cause of an error message and how to fix it",
+        tags: &["coding", "coding/debugging"],
+        variables: &[
+            ("error", None, "The error message or stack trace"),
+            (
+                "context",
+                Some("(none)"),
+                "What you were doing when it happened",
+            ),
+        ],
+        content:
+            "Explain what causes the error below in plain words, then give the most likely fix \
+first and other possible causes after it.
+
+Error:
+{{error}}
+
+Context: {{context}}",
+    },
+    SamplePrompt {
+        file: "write-tests.md",
+        topic: SamplePack::Coding,
+        title: "Write Unit Tests",
+        description: "Generate unit tests covering normal and edge cases",
+        tags: &["coding", "coding/testing"],
+        variables: &[
+            (
+                "framework",
+                Some("the project's usual test framework"),
+                "Test framework to use",
+            ),
+            ("code", None, "The function or module to test"),
+        ],
+        content: "Write unit tests for the code below using {{framework}}. Cover the normal case, \
+boundaries and invalid input, one behavior per test, with descriptive test names.
+
+```
+{{code}}
+```",
+    },
+    SamplePrompt {
+        file: "improve-writing.md",
+        topic: SamplePack::Writing,
+        title: "Improve Writing",
+        description: "Tighten a draft without changing its meaning",
+        tags: &["writing", "writing/editing"],
+        variables: &[
+            ("tone", Some("clear and friendly"), "Tone of the result"),
+            ("text", None, "The draft"),
+        ],
+        content:
+            "Edit the text below to be {{tone}}. Keep the meaning and the author's voice, cut \
+filler words, split long sentences and fix grammar. Return the edited text, then a short list \
+of the most important changes.
+
+{{text}}",
+    },
+    SamplePrompt {
+        file: "email-reply.md",
+        topic: SamplePack::Writing,
+        title: "Reply to an Email",
+        description: "Draft a reply that answers every question in an email",
+        tags: &["writing", "writing/email"],
+        variables: &[
+            ("email", None, "The email to reply to"),
+            ("points", Some("(none)"), "What the reply should say"),
+        ],
+        content: "Draft a reply to the email below. Answer every question it asks, keep it under \
+150 words and end with a clear next step.
+
+Points to make: {{points}}
+
+Email:
+{{email}}",
+    },
+    SamplePrompt {
+        file: "summarize.md",
+        topic: SamplePack::Summarization,
+        title: "Summarize",
+        description: "Summarize a document into key points",
+        tags: &["summarization"],
+        variables: &[
+            (
+                "length",
+                Some("5 bullet points"),
+                "How long the summary should be",
+            ),
+            ("text", None, "The text to summarize"),
+        ],
+        content: "Summarize the text below in {{length}}. Start with the main conclusion, keep \
+numbers and names exact and don't add anything the text does not say.
+
+{{text}}",
+    },
+    SamplePrompt {
+        file: "meeting-notes.md",
+        topic: SamplePack::Summarization,
+        title: "Meeting Notes",
+        description: "Turn a meeting transcript into decisions and action items",
+        tags: &["summarization", "summarization/meetings"],
+        variables: &[("transcript", None, "The meeting transcript or rough notes")],
+        content: "Turn the meeting transcript below into notes with three sections:
+- Decisions made
+- Action items, each with an owner and a due date if one was mentioned
+- Open questions
+
+{{transcript}}",
+    },
+];
+
+/// Views created along with a sample vault: pack, id, name and view config
+const SAMPLE_VIEWS: &[(SamplePack, &str, &str, &str)] = &[
+    (
+        SamplePack::Coding,
+        "sample-coding",
+        "Coding",
+        r#"{"filter":{"tags":["coding"]},"sort":{"by":"title","order":"asc"}}"#,
+    ),
+    (
+        SamplePack::Writing,
+        "sample-writing",
+        "Writing",
+        r#"{"filter":{"tags":["writing"]},"sort":{"by":"title","order":"asc"}}"#,
+    ),
+    (
+        SamplePack::Summarization,
+        "sample-summaries",
+        "Summaries",
+        r#"{"filter":{"tags":["summarization"]},"sort":{"by":"title","order":"asc"}}"#,
+    ),
+];
+
+/// Write the prompts of `pack` into `vault_path` (created if needed), which must not hold
+/// prompt files yet. Returns the written file names.
+pub fn create_sample_vault(
+    vault_path: &Path,
+    pack: SamplePack,
+    frontmatter_settings: &FrontmatterSettings,
+) -> Result<Vec<String>, AppError> {
+    fs::create_dir_all(vault_path)
+        .map_err(|e| AppError::io(format!("Failed to create vault folder: {}", e)))?;
+    let has_prompts = fs::read_dir(vault_path)
+        .map_err(|e| AppError::io(format!("Failed to read vault folder: {}", e)))?
+        .flatten()
+        .any(|entry| entry.path().extension().and_then(|ext| ext.to_str()) == Some("md"));
+    if has_prompts {
+        return Err(AppError::invalid_input(format!(
+            "{} already contains prompts",
+            vault_path.display()
+        )));
+    }
+
+    let created = Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let mut written = Vec::new();
+    for sample in SAMPLE_PROMPTS
+        .iter()
+        .filter(|sample| pack.includes(sample.topic))
+    {
+        let prompt = PromptFile {
+            id: sample.file.to_string(),
+            file_path: sample.file.to_string(),
+            tags: sample.tags.iter().map(|tag| tag.to_string()).collect(),
+            created: Some(created.clone()),
+            content: sample.content.to_string(),
+            file_hash: None,
+            title: Some(sample.title.to_string()),
+            description: Some(sample.description.to_string()),
+            color: None,
+            icon: None,
+            notes: None,
+            model_targets: Vec::new(),
+            language: None,
+            status: None,
+            custom_fields: HashMap::new(),
+            variants: Vec::new(),
+            archived: false,
+            links: Vec::new(),
+            variables: sample
+                .variables
+                .iter()
+                .map(|(name, default, description)| TemplateVariable {
+                    name: name.to_string(),
+                    default: default.map(|value| JsonValue::String(value.to_string())),
+                    required: default.is_none(),
+                    options: Vec::new(),
+                    description: Some(description.to_string()),
+                })
+                .collect(),
+        };
+        vault::write_prompt_file(vault_path, &prompt, frontmatter_settings)?;
+        written.push(sample.file.to_string());
+    }
+    Ok(written)
+}
+
+/// Views to create with a sample vault of `pack`: id, name and view config JSON
+pub fn sample_views(pack: SamplePack) -> Vec<(&'static str, &'static str, &'static str)> {
+    SAMPLE_VIEWS
+        .iter()
+        .filter(|(topic, ..)| pack.includes(*topic))
+        .map(|(_, id, name, config)| (*id, *name, *config))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ViewConfig;
+    use crate::template;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_create_sample_vault() {
+        let vault_path = TempDir::new("sample");
+        let settings = FrontmatterSettings::default();
+
+        let written = create_sample_vault(&vault_path, SamplePack::Coding, &settings).unwrap();
+        assert_eq!(written.len(), 3);
+        for name in &written {
+            let file =
+                vault::read_prompt_file(&vault_path, &vault_path.join(name), &settings).unwrap();
+            assert!(file.tags.iter().any(|tag| tag == "coding"));
+            assert!(file.title.is_some());
+            assert!(template::undeclared(&file.content, &file.variables).is_empty());
+        }
+        assert!(create_sample_vault(&vault_path, SamplePack::Writing, &settings).is_err());
+
+        // Every sample prompt uses all of its variables, and every view config parses
+        for sample in SAMPLE_PROMPTS {
+            let used = template::variables(sample.content);
+            for (name, ..) in sample.variables {
+                assert!(used.iter().any(|used| used == name), "{}", sample.file);
+            }
+        }
+        assert_eq!(sample_views(SamplePack::Starter).len(), SAMPLE_VIEWS.len());
+        for (_, _, config) in sample_views(SamplePack::Starter) {
+            serde_json::from_str::<ViewConfig>(config).unwrap();
+        }
+    }
+}
@@ -0,0 +1,788 @@
+//! Search query language: `tag:rust -tag:draft title:"code review" /regex/i` plus plain words
+
+use crate::models::{DbError, Prompt, SearchConfig, SearchField};
+use crate::vault;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::path::Path;
+
+/// Search query errors
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SearchError {
+    #[error("Unterminated {0} in search")]
+    Unterminated(String),
+    #[error("Invalid regex in search: {0}")]
+    InvalidRegex(String),
+}
+
+impl From<SearchError> for DbError {
+    fn from(e: SearchError) -> Self {
+        DbError::InvalidInput(e.to_string())
+    }
+}
+
+/// What a single search predicate looks at
+#[derive(Debug, Clone)]
+pub enum Term {
+    /// Word or `"quoted phrase"` in the title or text, or the fields the search is limited to
+    Text(String),
+    /// `tag:name`, including nested tags
+    Tag(String),
+    /// `title:word` or `title:"quoted phrase"`
+    Title(String),
+    /// `/pattern/flags` matched against the text
+    Regex(Regex),
+}
+
+/// A term, possibly negated with a leading `-`
+#[derive(Debug, Clone)]
+pub struct Predicate {
+    pub term: Term,
+    pub negated: bool,
+}
+
+/// A parsed search; a prompt matches when every predicate does. Words, phrases and `title:`
+/// values ignore case and match anywhere unless the `SearchConfig` says otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct SearchQuery {
+    pub predicates: Vec<Predicate>,
+    case_sensitive: bool,
+    whole_word: bool,
+    fields: Vec<SearchField>,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Result<Self, SearchError> {
+        Self::parse_with_case(input, false)
+    }
+
+    /// Parse `config.query` to match the way `config` asks for
+    pub fn from_config(config: &SearchConfig) -> Result<Self, SearchError> {
+        let mut query = Self::parse_with_case(&config.query, config.case_sensitive)?;
+        query.whole_word = config.whole_word;
+        query.fields = config.fields.clone();
+        Ok(query)
+    }
+
+    /// Values are lowercased as they are read unless `case_sensitive`
+    fn parse_with_case(input: &str, case_sensitive: bool) -> Result<Self, SearchError> {
+        let mut scanner = Scanner {
+            chars: input.chars().collect(),
+            pos: 0,
+            case_sensitive,
+        };
+        let mut predicates = Vec::new();
+
+        loop {
+            scanner.skip_whitespace();
+            let Some(first) = scanner.peek(0) else {
+                break;
+            };
+            let negated = first == '-' && scanner.peek(1).is_some_and(|c| !c.is_whitespace());
+            if negated {
+                scanner.pos += 1;
+            }
+
+            let term = match scanner.peek(0) {
+                Some('/') => scanner.read_regex()?,
+                Some('"') => {
+                    let phrase = scanner.read_quoted()?;
+                    Term::Text(scanner.fold_case(phrase))
+                }
+                _ => scanner.read_field()?,
+            };
+            let empty = match &term {
+                Term::Text(value) | Term::Tag(value) | Term::Title(value) => value.is_empty(),
+                Term::Regex(_) => false,
+            };
+            if !empty {
+                predicates.push(Predicate { term, negated });
+            }
+        }
+
+        Ok(SearchQuery {
+            predicates,
+            case_sensitive,
+            ..SearchQuery::default()
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.predicates.is_empty()
+    }
+
+    pub fn matches(&self, prompt: &Prompt) -> bool {
+        self.predicates
+            .iter()
+            .all(|predicate| predicate.term.matches(prompt, self) != predicate.negated)
+    }
+
+    /// Whether plain words and phrases are looked for in `field`
+    fn searches(&self, field: SearchField) -> bool {
+        if self.fields.is_empty() {
+            field != SearchField::Description
+        } else {
+            self.fields.contains(&field)
+        }
+    }
+
+    /// Whether `value` (as read by the scanner) occurs in `haystack`
+    fn contains(&self, haystack: &str, value: &str) -> bool {
+        if self.whole_word {
+            let chars: Vec<char> = haystack.chars().collect();
+            !self.find_all(&chars, value).is_empty()
+        } else if self.case_sensitive {
+            haystack.contains(value)
+        } else {
+            haystack.to_lowercase().contains(value)
+        }
+    }
+
+    fn find_all(&self, haystack: &[char], value: &str) -> Vec<MatchRange> {
+        find_all(haystack, value, self.case_sensitive, self.whole_word)
+    }
+
+    /// Where the words, titles and regexes of the query (not negated ones) hit `prompt`
+    pub fn highlight(&self, prompt: Prompt) -> SearchHit {
+        let title: Vec<char> = prompt.title.as_deref().unwrap_or("").chars().collect();
+        let text: Vec<char> = prompt.text.chars().collect();
+        let mut title_ranges = Vec::new();
+        let mut text_ranges = Vec::new();
+
+        for predicate in self.predicates.iter().filter(|p| !p.negated) {
+            match &predicate.term {
+                Term::Text(value) => {
+                    if self.searches(SearchField::Title) {
+                        title_ranges.extend(self.find_all(&title, value));
+                    }
+                    if self.searches(SearchField::Text) {
+                        text_ranges.extend(self.find_all(&text, value));
+                    }
+                }
+                Term::Title(value) => title_ranges.extend(self.find_all(&title, value)),
+                Term::Regex(regex) => {
+                    text_ranges.extend(regex.find_iter(&prompt.text).filter_map(|m| {
+                        let start = prompt.text[..m.start()].chars().count();
+                        let end = start + m.as_str().chars().count();
+                        (end > start).then(|| MatchRange::new(start, end))
+                    }))
+                }
+                Term::Tag(_) => {}
+            }
+        }
+
+        let text_ranges = merge_ranges(text_ranges);
+        SearchHit {
+            title_ranges: merge_ranges(title_ranges),
+            excerpt: excerpt(&text, &text_ranges),
+            text_ranges,
+            prompt,
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, prompt: &Prompt, query: &SearchQuery) -> bool {
+        let title = prompt.title.as_deref().unwrap_or("");
+        match self {
+            Term::Text(value) => [
+                (SearchField::Title, Some(title)),
+                (SearchField::Description, prompt.description.as_deref()),
+                (SearchField::Text, Some(prompt.text.as_str())),
+            ]
+            .into_iter()
+            .any(|(field, haystack)| {
+                query.searches(field) && haystack.is_some_and(|h| query.contains(h, value))
+            }),
+            Term::Tag(value) => prompt.tags.iter().any(|tag| vault::tag_matches(tag, value)),
+            Term::Title(value) => query.contains(title, value),
+            Term::Regex(regex) => regex.is_match(&prompt.text),
+        }
+    }
+}
+
+struct Scanner {
+    chars: Vec<char>,
+    pos: usize,
+    case_sensitive: bool,
+}
+
+impl Scanner {
+    fn fold_case(&self, value: String) -> String {
+        if self.case_sensitive {
+            value
+        } else {
+            value.to_lowercase()
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek(0).is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Characters up to the next whitespace
+    fn read_word(&mut self) -> String {
+        let start = self.pos;
+        while self.peek(0).is_some_and(|c| !c.is_whitespace()) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    /// A `"..."` string starting at the current quote; `\"` escapes a quote
+    fn read_quoted(&mut self) -> Result<String, SearchError> {
+        self.pos += 1;
+        let mut value = String::new();
+        while let Some(c) = self.peek(0) {
+            self.pos += 1;
+            match c {
+                '"' => return Ok(value),
+                '\\' if self.peek(0) == Some('"') => {
+                    value.push('"');
+                    self.pos += 1;
+                }
+                c => value.push(c),
+            }
+        }
+        Err(SearchError::Unterminated("quote".to_string()))
+    }
+
+    /// A `/pattern/flags` regex starting at the current slash; `\/` escapes a slash
+    fn read_regex(&mut self) -> Result<Term, SearchError> {
+        self.pos += 1;
+        let mut pattern = String::new();
+        loop {
+            let Some(c) = self.peek(0) else {
+                return Err(SearchError::Unterminated("regex".to_string()));
+            };
+            self.pos += 1;
+            match c {
+                '/' => break,
+                '\\' if self.peek(0) == Some('/') => {
+                    pattern.push('/');
+                    self.pos += 1;
+                }
+                c => pattern.push(c),
+            }
+        }
+
+        let mut builder = RegexBuilder::new(&pattern);
+        for flag in self.read_word().chars() {
+            match flag {
+                'i' => builder.case_insensitive(true),
+                'm' => builder.multi_line(true),
+                's' => builder.dot_matches_new_line(true),
+                other => {
+                    return Err(SearchError::InvalidRegex(format!(
+                        "unknown flag '{}'",
+                        other
+                    )))
+                }
+            };
+        }
+        builder
+            .build()
+            .map(Term::Regex)
+            .map_err(|e| SearchError::InvalidRegex(e.to_string()))
+    }
+
+    /// `tag:value`, `title:value` or a plain word
+    fn read_field(&mut self) -> Result<Term, SearchError> {
+        let start = self.pos;
+        while self.peek(0).is_some_and(char::is_alphabetic) {
+            self.pos += 1;
+        }
+        let key = self.chars[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .to_lowercase();
+        if self.peek(0) == Some(':') && matches!(key.as_str(), "tag" | "title") {
+            self.pos += 1;
+            let value = if self.peek(0) == Some('"') {
+                self.read_quoted()?
+            } else {
+                self.read_word()
+            };
+            return Ok(if key == "tag" {
+                Term::Tag(value)
+            } else {
+                Term::Title(self.fold_case(value))
+            });
+        }
+
+        self.pos = start;
+        let word = self.read_word();
+        Ok(Term::Text(self.fold_case(word)))
+    }
+}
+
+// ============================================================================
+// HIGHLIGHTING
+// ============================================================================
+
+/// Chars of context kept before the first hit in an excerpt
+const EXCERPT_CONTEXT: usize = 60;
+/// Most chars in an excerpt
+const EXCERPT_LENGTH: usize = 200;
+
+/// Char offsets of a hit (`end` exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+pub struct MatchRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl MatchRange {
+    fn new(start: usize, end: usize) -> Self {
+        MatchRange {
+            start: start as u32,
+            end: end as u32,
+        }
+    }
+}
+
+/// The part of a prompt's text around its first hit
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Excerpt {
+    pub text: String,
+    /// Char offset of the excerpt in the prompt text
+    pub start: u32,
+    /// Whether text follows the excerpt
+    pub truncated: bool,
+    /// Hits within the excerpt, relative to its start
+    pub ranges: Vec<MatchRange>,
+}
+
+/// A prompt matched by `search_prompts` and where the query hit it
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub prompt: Prompt,
+    pub title_ranges: Vec<MatchRange>,
+    pub text_ranges: Vec<MatchRange>,
+    /// Absent when the text itself wasn't hit
+    pub excerpt: Option<Excerpt>,
+}
+
+/// Occurrences of `needle` (already lowercased unless `case_sensitive`); with `whole_word`,
+/// only those not touching a letter, digit or `_` on either side
+fn find_all(
+    haystack: &[char],
+    needle: &str,
+    case_sensitive: bool,
+    whole_word: bool,
+) -> Vec<MatchRange> {
+    let fold = move |c: char| {
+        if case_sensitive {
+            c
+        } else {
+            lowercase_char(c)
+        }
+    };
+    let needle: Vec<char> = needle.chars().map(fold).collect();
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return Vec::new();
+    }
+    let is_word_char = |i: usize| {
+        haystack
+            .get(i)
+            .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+    };
+    let folded: Vec<char> = haystack.iter().copied().map(fold).collect();
+    folded
+        .windows(needle.len())
+        .enumerate()
+        .filter(|(_, window)| *window == needle.as_slice())
+        .map(|(start, _)| (start, start + needle.len()))
+        .filter(|&(start, end)| {
+            !whole_word || !(start > 0 && is_word_char(start - 1) || is_word_char(end))
+        })
+        .map(|(start, end)| MatchRange::new(start, end))
+        .collect()
+}
+
+/// One char per char, so offsets in the lowercased text are offsets in the original
+fn lowercase_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Sort ranges and join the ones that overlap or touch
+fn merge_ranges(mut ranges: Vec<MatchRange>) -> Vec<MatchRange> {
+    ranges.sort_by_key(|range| (range.start, range.end));
+    let mut merged: Vec<MatchRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Up to `EXCERPT_LENGTH` chars starting a little before the first hit, at a word start
+fn excerpt(text: &[char], ranges: &[MatchRange]) -> Option<Excerpt> {
+    let first = ranges.first()?.start as usize;
+    let mut start = first.saturating_sub(EXCERPT_CONTEXT);
+    while start > 0 && start < first && !text[start - 1].is_whitespace() {
+        start += 1;
+    }
+    let end = (start + EXCERPT_LENGTH).min(text.len());
+    Some(Excerpt {
+        text: text[start..end].iter().collect(),
+        start: start as u32,
+        truncated: end < text.len(),
+        ranges: ranges
+            .iter()
+            .filter(|range| (range.start as usize) < end)
+            .map(|range| {
+                MatchRange::new(
+                    range.start as usize - start,
+                    (range.end as usize).min(end) - start,
+                )
+            })
+            .collect(),
+    })
+}
+
+// ============================================================================
+// FUZZY MATCHING
+// ============================================================================
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_CAMEL_CASE: i64 = 6;
+const BONUS_CONSECUTIVE: i64 = 4;
+const PENALTY_GAP_START: i64 = 3;
+const PENALTY_GAP_EXTENSION: i64 = 1;
+
+/// A prompt matched by `fuzzy_search`, with the matched characters of each field
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FuzzyPromptMatch {
+    pub prompt: Prompt,
+    pub score: i64,
+    /// Char indices matched in the title (empty if the title didn't match)
+    pub title_indices: Vec<u32>,
+    /// Char indices matched in the file name without `.md` (empty if it didn't match)
+    pub file_name_indices: Vec<u32>,
+}
+
+/// Score and matched char indices of a pattern found in a string
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub indices: Vec<u32>,
+}
+
+/// Up to `limit` prompts whose title or file name fuzzy-matches `query`, best first
+pub fn fuzzy_search(prompts: Vec<Prompt>, query: &str, limit: usize) -> Vec<FuzzyPromptMatch> {
+    let mut matches: Vec<FuzzyPromptMatch> = prompts
+        .into_iter()
+        .filter_map(|prompt| {
+            let title = prompt
+                .title
+                .as_deref()
+                .and_then(|title| fuzzy_match(query, title));
+            let file_name = Path::new(prompt.file_path.as_deref().unwrap_or(&prompt.id))
+                .file_stem()
+                .and_then(|stem| fuzzy_match(query, &stem.to_string_lossy()));
+            let score = title
+                .iter()
+                .chain(file_name.iter())
+                .map(|m| m.score)
+                .max()?;
+            Some(FuzzyPromptMatch {
+                prompt,
+                score,
+                title_indices: title.map(|m| m.indices).unwrap_or_default(),
+                file_name_indices: file_name.map(|m| m.indices).unwrap_or_default(),
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.prompt.id.cmp(&b.prompt.id))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// Skim-style fuzzy match: every pattern character must appear in order (ignoring case
+/// and whitespace in the pattern). Matches at word starts and consecutive runs score
+/// higher; gaps score lower.
+pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if pattern.is_empty() {
+        return None;
+    }
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    // Earliest position where the whole pattern has been seen
+    let mut matched = 0;
+    let mut end = None;
+    for (i, c) in lower.iter().enumerate() {
+        if *c == pattern[matched] {
+            matched += 1;
+            if matched == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    // Walk back from there to the latest start, for the shortest window
+    let mut remaining = pattern.len();
+    let mut start = 0;
+    for i in (0..=end).rev() {
+        if lower[i] == pattern[remaining - 1] {
+            remaining -= 1;
+            if remaining == 0 {
+                start = i;
+                break;
+            }
+        }
+    }
+
+    let mut indices = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for (i, c) in lower.iter().enumerate().take(end + 1).skip(start) {
+        if indices.len() == pattern.len() || *c != pattern[indices.len()] {
+            continue;
+        }
+        let mut bonus = position_bonus(&chars, i);
+        if previous.is_none() {
+            bonus *= 2;
+        }
+        score += SCORE_MATCH + bonus;
+        match previous {
+            Some(p) if p + 1 == i => score += BONUS_CONSECUTIVE,
+            Some(p) => score -= PENALTY_GAP_START + PENALTY_GAP_EXTENSION * (i - p - 2) as i64,
+            None => {}
+        }
+        previous = Some(i);
+        indices.push(i as u32);
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+/// Bonus for a match at `i`: the start of a word or a camelCase hump
+fn position_bonus(chars: &[char], i: usize) -> i64 {
+    let Some(previous) = i.checked_sub(1).map(|p| chars[p]) else {
+        return BONUS_BOUNDARY;
+    };
+    let current = chars[i];
+    if !previous.is_alphanumeric() && current.is_alphanumeric() {
+        BONUS_BOUNDARY
+    } else if previous.is_lowercase() && current.is_uppercase() {
+        BONUS_CAMEL_CASE
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(title: &str, text: &str, tags: &[&str]) -> Prompt {
+        Prompt {
+            id: "p.md".to_string(),
+            created: Some("2024-01-01T00:00:00".to_string()),
+            text: text.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            file_path: None,
+            title: Some(title.to_string()),
+            description: None,
+            file_hash: None,
+            custom_fields: Default::default(),
+            token_count: None,
+            archived: false,
+            updated_at: None,
+            variables: Vec::new(),
+            revision: 0,
+            color: None,
+            icon: None,
+            notes: None,
+            model_targets: Vec::new(),
+            language: None,
+            status: None,
+            char_count: None,
+            word_count: None,
+            line_count: None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let query =
+            SearchQuery::parse(r#"tag:rust -tag:draft title:"Code Review" /fn\s+\w+/i - word"#)
+                .unwrap();
+        let summary: Vec<String> = query
+            .predicates
+            .iter()
+            .map(|p| {
+                let term = match &p.term {
+                    Term::Text(v) => format!("text:{}", v),
+                    Term::Tag(v) => format!("tag:{}", v),
+                    Term::Title(v) => format!("title:{}", v),
+                    Term::Regex(r) => format!("regex:{}", r.as_str()),
+                };
+                format!("{}{}", if p.negated { "-" } else { "" }, term)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                "tag:rust",
+                "-tag:draft",
+                "title:code review",
+                r"regex:fn\s+\w+",
+                "text:-",
+                "text:word",
+            ]
+        );
+
+        assert!(SearchQuery::parse("title:\"open").is_err());
+        assert!(SearchQuery::parse("/unclosed").is_err());
+        assert!(SearchQuery::parse("/(/").is_err());
+        assert!(SearchQuery::parse("/a/x").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let review = prompt(
+            "Code review",
+            "Review this function:\nfn main() {}",
+            &["lang/rust"],
+        );
+        let draft = prompt("Draft", "Summarize the text", &["lang/rust", "draft"]);
+
+        let query = SearchQuery::parse(r"tag:lang -tag:draft /FN\s+main/i").unwrap();
+        assert!(query.matches(&review));
+        assert!(!query.matches(&draft));
+
+        let query = SearchQuery::parse("title:\"code review\" function").unwrap();
+        assert!(query.matches(&review));
+        assert!(!query.matches(&draft));
+
+        assert!(SearchQuery::parse("summarize").unwrap().matches(&draft));
+        assert!(SearchQuery::parse("").unwrap().matches(&draft));
+    }
+
+    #[test]
+    fn test_search_config() {
+        let mut said = prompt("Notes", "He said AI_tools are fine. Test: AI-ready", &[]);
+        said.description = Some("Testing helper".to_string());
+        let search = |query: &str, case_sensitive, whole_word, fields: &[SearchField]| {
+            SearchQuery::from_config(&SearchConfig {
+                query: query.to_string(),
+                case_sensitive,
+                whole_word,
+                fields: fields.to_vec(),
+            })
+            .unwrap()
+        };
+
+        // Whole words skip `said` and `AI_tools` but not `AI-ready`
+        let query = search("ai", false, true, &[]);
+        assert!(query.matches(&said));
+        let hit = query.highlight(said.clone());
+        assert_eq!(hit.text_ranges, vec![MatchRange::new(33, 35)]);
+        assert!(!search("sai", false, true, &[]).matches(&said));
+        assert!(search("sai", false, false, &[]).matches(&said));
+
+        assert!(search("Test", true, false, &[]).matches(&said));
+        assert!(!search("test", true, false, &[]).matches(&said));
+        assert!(!search("title:notes", true, false, &[]).matches(&said));
+        assert!(search("title:notes", false, true, &[]).matches(&said));
+
+        // The description is only searched when asked for
+        assert!(!search("helper", false, false, &[]).matches(&said));
+        let description = search("helper", false, false, &[SearchField::Description]);
+        assert!(description.matches(&said));
+        assert!(!search("said", false, false, &[SearchField::Title]).matches(&said));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let text = format!("{}Review the CODE below. /* code */", "x ".repeat(50));
+        let review = prompt("Code review", &text, &["lang/rust"]);
+        let hit = SearchQuery::parse(r"code -below tag:lang /re\w+/i")
+            .unwrap()
+            .highlight(review);
+
+        // Regexes only look at the text
+        assert_eq!(hit.title_ranges, vec![MatchRange::new(0, 4)]);
+        assert_eq!(
+            hit.text_ranges,
+            vec![
+                MatchRange::new(100, 106),
+                MatchRange::new(111, 115),
+                MatchRange::new(126, 130)
+            ]
+        );
+
+        let excerpt = hit.excerpt.unwrap();
+        assert_eq!(excerpt.start, 40);
+        assert!(excerpt.text.starts_with("x x"));
+        assert!(!excerpt.truncated);
+        assert_eq!(excerpt.ranges[0], MatchRange::new(60, 66));
+
+        let hit = SearchQuery::parse("title:code").unwrap().highlight(prompt(
+            "Code",
+            "no match here",
+            &[],
+        ));
+        assert!(hit.text_ranges.is_empty());
+        assert!(hit.excerpt.is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_match() {
+        let review = fuzzy_match("cr", "Code review").unwrap();
+        assert_eq!(review.indices, vec![0, 5]);
+        let scratch = fuzzy_match("cr", "scratch").unwrap();
+        assert!(review.score > scratch.score);
+
+        // The shortest window wins over the first occurrence
+        assert_eq!(fuzzy_match("ab", "a-x-ab").unwrap().indices, vec![4, 5]);
+        assert_eq!(fuzzy_match("eR", "codeReview").unwrap().indices, vec![3, 4]);
+        assert!(fuzzy_match("rc", "Code review").is_none());
+        assert!(fuzzy_match(" ", "anything").is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_search() {
+        let mut summarize = prompt("Summarize", "text", &[]);
+        summarize.id = "notes/summary.md".to_string();
+        let mut review = prompt("Code review", "text", &[]);
+        review.id = "review.md".to_string();
+
+        let matches = fuzzy_search(vec![summarize, review], "sum", 10);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].prompt.id, "notes/summary.md");
+        assert_eq!(matches[0].title_indices, vec![0, 1, 2]);
+        assert_eq!(matches[0].file_name_indices, vec![0, 1, 2]);
+    }
+}
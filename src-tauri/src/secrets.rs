@@ -0,0 +1,155 @@
+//! API keys and tokens, kept in the OS keyring (macOS Keychain, Windows Credential Manager,
+//! Secret Service on Linux) instead of the config file
+
+use crate::config::APP_IDENTIFIER;
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tracing::{error, info};
+
+/// A secret the app stores; each is one keyring entry under the app identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub enum SecretName {
+    /// Bearer token for the chat completions endpoint
+    LlmApiKey,
+    /// Bearer token for the embeddings endpoint
+    EmbeddingsApiKey,
+    /// GitHub personal access token with the `gist` scope
+    GithubToken,
+    /// Password or app token for the WebDAV vault folder
+    WebDavPassword,
+    /// Secret access key for the S3 vault bucket
+    S3SecretAccessKey,
+}
+
+impl SecretName {
+    /// Keyring account name
+    fn account(self) -> &'static str {
+        match self {
+            SecretName::LlmApiKey => "llm-api-key",
+            SecretName::EmbeddingsApiKey => "embeddings-api-key",
+            SecretName::GithubToken => "github-token",
+            SecretName::WebDavPassword => "webdav-password",
+            SecretName::S3SecretAccessKey => "s3-secret-access-key",
+        }
+    }
+}
+
+/// Keyring errors
+#[derive(Debug, Clone, Serialize, thiserror::Error, Type)]
+pub enum SecretError {
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+}
+
+impl From<keyring::Error> for SecretError {
+    fn from(e: keyring::Error) -> Self {
+        SecretError::Keyring(e.to_string())
+    }
+}
+
+fn entry(name: SecretName) -> Result<Entry, SecretError> {
+    Ok(Entry::new(APP_IDENTIFIER, name.account())?)
+}
+
+/// The stored value, `None` when it was never set
+pub fn get_secret(name: SecretName) -> Result<Option<String>, SecretError> {
+    match entry(name)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Store `value`, replacing any previous one; a blank value deletes the secret
+pub fn set_secret(name: SecretName, value: &str) -> Result<(), SecretError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return delete_secret(name);
+    }
+    entry(name)?.set_password(value)?;
+    info!("Stored secret {:?}", name);
+    Ok(())
+}
+
+pub fn has_secret(name: SecretName) -> Result<bool, SecretError> {
+    Ok(get_secret(name)?.is_some())
+}
+
+/// Remove the secret; removing one that was never set is not an error
+pub fn delete_secret(name: SecretName) -> Result<(), SecretError> {
+    match entry(name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The secret to send with a request, or `None` when it is unset or the keyring can't be
+/// read (logged, so the request goes out unauthenticated rather than failing here)
+pub fn lookup(name: SecretName) -> Option<String> {
+    match get_secret(name) {
+        Ok(value) => value.filter(|value| !value.trim().is_empty()),
+        Err(e) => {
+            error!("Failed to read {:?} from the keyring: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Plaintext keys older config files kept: the section, the key in it and the secret it
+/// moves to
+const CONFIG_KEYS: [(&[&str], &str, SecretName); 5] = [
+    (&["llm"], "apiKey", SecretName::LlmApiKey),
+    (&["embeddings"], "apiKey", SecretName::EmbeddingsApiKey),
+    (&["integrations"], "githubToken", SecretName::GithubToken),
+    (
+        &["remote", "webdav"],
+        "password",
+        SecretName::WebDavPassword,
+    ),
+    (
+        &["remote", "s3"],
+        "secretAccessKey",
+        SecretName::S3SecretAccessKey,
+    ),
+];
+
+/// The non-blank value of `key` in the `path` section of a config table
+fn config_key<'a>(table: &'a toml::Table, path: &[&str], key: &str) -> Option<&'a str> {
+    let section = path
+        .iter()
+        .try_fold(table, |table, part| table.get(*part)?.as_table())?;
+    section
+        .get(key)?
+        .as_str()
+        .filter(|value| !value.trim().is_empty())
+}
+
+/// Config migration step: move the keys older config files kept in plaintext into the
+/// keyring. A key the keyring refuses stays in the table (see `has_config_keys`). Keys
+/// already moved are no longer in the table, so the step can run again.
+pub(crate) fn move_config_keys(table: &mut toml::Table) {
+    for (path, key, name) in CONFIG_KEYS {
+        let Some(value) = config_key(table, path, key).map(str::to_string) else {
+            continue;
+        };
+        if let Err(e) = set_secret(name, &value) {
+            error!("Failed to move {:?} into the keyring: {}", name, e);
+            continue;
+        }
+        let section = path.iter().try_fold(&mut *table, |table, part| {
+            table.get_mut(*part)?.as_table_mut()
+        });
+        if let Some(section) = section {
+            section.remove(key);
+        }
+    }
+}
+
+/// Whether `table` still has a plaintext key, i.e. `move_config_keys` couldn't store it
+pub(crate) fn has_config_keys(table: &toml::Table) -> bool {
+    CONFIG_KEYS
+        .iter()
+        .any(|(path, key, _)| config_key(table, path, key).is_some())
+}
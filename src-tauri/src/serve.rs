@@ -0,0 +1,328 @@
+//! Opt-in local HTTP API so scripts and editors can pull prompts without the GUI.
+//!
+//! Endpoints (JSON, bound to 127.0.0.1 only, each needing `Authorization: Bearer <token>`):
+//! - `GET /api/prompts?tags=a,-b&q=text&model=gpt-4o&lang=en` - list prompts, filtered like views
+//! - `GET /api/prompts/{id}` - a single prompt
+//! - `GET /api/search?q=text&limit=10` - semantic search
+//! - `POST /api/prompts/{id}/render` with `{"values": {...}}` - fill template variables
+
+use crate::commands;
+use crate::config;
+use crate::crypto;
+use crate::db::{DbPool, DbState};
+use crate::error::{AppError, ErrorKind};
+use crate::models::{DbError, FilterConfig, SearchConfig};
+use crate::prompts;
+use crate::template;
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use specta::Type;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+use tracing::{error, info};
+use uuid::Uuid;
+
+/// Results returned by `/api/search` when no `limit` is given
+const DEFAULT_SEARCH_LIMIT: usize = 10;
+
+struct RunningServer {
+    port: u16,
+    token: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Default)]
+pub struct ApiServerState {
+    server: Mutex<Option<RunningServer>>,
+}
+
+/// Current state of the HTTP API - returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiServerStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Token requests must send as `Authorization: Bearer <token>`; generated when the
+    /// server was started without one
+    pub token: Option<String>,
+}
+
+impl ApiServerState {
+    /// Shut the server down (no-op when it is not running)
+    pub fn stop(&self) -> Result<(), String> {
+        let mut guard = self
+            .server
+            .lock()
+            .map_err(|_| "Server lock poisoned".to_string())?;
+        if let Some(server) = guard.take() {
+            let _ = server.shutdown.send(());
+            info!("Stopped API server on port {}", server.port);
+        }
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<ApiServerStatus, String> {
+        let guard = self
+            .server
+            .lock()
+            .map_err(|_| "Server lock poisoned".to_string())?;
+        Ok(ApiServerStatus {
+            running: guard.is_some(),
+            port: guard.as_ref().map(|server| server.port),
+            token: guard.as_ref().map(|server| server.token.clone()),
+        })
+    }
+}
+
+#[derive(Clone)]
+struct ApiContext {
+    app: AppHandle,
+    /// Bound port; requests must name it in their `Host` header
+    port: u16,
+    /// Required as `Authorization: Bearer <token>`
+    token: String,
+}
+
+impl ApiContext {
+    fn pool(&self) -> DbPool {
+        self.app.state::<DbState>().pool()
+    }
+}
+
+/// Start serving on `127.0.0.1:port` (port 0 picks a free one), replacing a running server.
+/// Without a `token` a random one is generated. Returns the bound port.
+pub async fn start_api_server(
+    app: AppHandle,
+    state: &ApiServerState,
+    port: u16,
+    token: Option<String>,
+) -> Result<u16, String> {
+    state.stop()?;
+
+    let listener = tokio::net::TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+        .await
+        .map_err(|e| format!("Failed to bind port {}: {}", port, e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let token = token
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+    let context = ApiContext {
+        app,
+        port,
+        token: token.clone(),
+    };
+    let router = Router::new()
+        .route("/api/prompts", get(list_prompts))
+        .route("/api/prompts/{id}", get(get_prompt))
+        .route("/api/prompts/{id}/render", post(render_prompt))
+        .route("/api/search", get(search_prompts))
+        .layer(middleware::from_fn_with_state(
+            context.clone(),
+            require_token,
+        ))
+        .with_state(context);
+
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    tauri::async_runtime::spawn(async move {
+        let result = axum::serve(listener, router)
+            .with_graceful_shutdown(async {
+                let _ = shutdown_rx.await;
+            })
+            .await;
+        if let Err(e) = result {
+            error!("API server stopped with error: {}", e);
+        }
+    });
+
+    let mut guard = state
+        .server
+        .lock()
+        .map_err(|_| "Server lock poisoned".to_string())?;
+    *guard = Some(RunningServer {
+        port,
+        token,
+        shutdown,
+    });
+    info!("API server listening on 127.0.0.1:{}", port);
+    Ok(port)
+}
+
+/// Reject requests without the bearer token, and requests for another host, which is how
+/// a web page reaches the API through DNS rebinding
+async fn require_token(
+    State(context): State<ApiContext>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let header_value = |name: header::HeaderName| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+    };
+    let local_host = header_value(header::HOST).is_some_and(|host| {
+        host == format!("127.0.0.1:{}", context.port)
+            || host == format!("localhost:{}", context.port)
+    });
+    if !local_host {
+        return ApiError(StatusCode::FORBIDDEN, "Unexpected Host header".into()).into_response();
+    }
+    let authorized = header_value(header::AUTHORIZATION)
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|provided| tokens_match(provided, &context.token));
+    if !authorized {
+        return ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid token".into())
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Compare tokens in time that doesn't depend on where they first differ
+fn tokens_match(provided: &str, token: &str) -> bool {
+    provided.len() == token.len()
+        && provided
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    /// Comma separated; `-tag` excludes
+    tags: Option<String>,
+    /// Search query, as in the app's search box
+    q: Option<String>,
+    /// Only prompts targeting this model
+    model: Option<String>,
+    /// Only prompts in this language
+    lang: Option<String>,
+}
+
+async fn list_prompts(
+    State(context): State<ApiContext>,
+    Query(query): Query<ListQuery>,
+) -> Result<Response, ApiError> {
+    let filter = FilterConfig {
+        tags: query
+            .tags
+            .map(|tags| tags.split(',').map(str::to_string).collect()),
+        search: query.q.map(SearchConfig::from),
+        model_target: query.model,
+        language: query.lang,
+        ..FilterConfig::default()
+    };
+    let prompts = prompts::query_prompts(&context.pool(), Some(filter), None).await?;
+    Ok(Json(prompts).into_response())
+}
+
+async fn get_prompt(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+) -> Result<Response, ApiError> {
+    let prompt = prompts::load_prompt(&context.pool(), &id)
+        .await?
+        .ok_or(DbError::NotFound(id))?;
+    Ok(Json(prompt).into_response())
+}
+
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+    limit: Option<usize>,
+}
+
+async fn search_prompts(
+    State(context): State<ApiContext>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response, ApiError> {
+    let config = config::current(&context.app);
+    let matches = commands::semantic_matches(
+        &context.pool(),
+        &config.embeddings,
+        query.q,
+        query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT),
+    )
+    .await?;
+    Ok(Json(matches).into_response())
+}
+
+#[derive(Deserialize, Default)]
+struct RenderBody {
+    #[serde(default)]
+    values: HashMap<String, JsonValue>,
+}
+
+async fn render_prompt(
+    State(context): State<ApiContext>,
+    Path(id): Path<String>,
+    body: Option<Json<RenderBody>>,
+) -> Result<Response, ApiError> {
+    let prompt = prompts::load_prompt(&context.pool(), &id)
+        .await?
+        .ok_or(DbError::NotFound(id))?;
+    // The API has no passphrase, so encrypted prompts are never rendered
+    if crypto::is_sealed(&prompt.text) {
+        return Err(ApiError(
+            StatusCode::FORBIDDEN,
+            format!(
+                "{} is encrypted and can't be rendered through the API",
+                prompt.id
+            ),
+        ));
+    }
+    let Json(mut body) = body.unwrap_or_default();
+    template::apply_declared(&prompt.variables, &mut body.values).map_err(AppError::from)?;
+    let config = config::current(&context.app);
+    // Likewise encrypted includes fail as locked
+    let text =
+        prompts::expand_includes(&context.pool(), &prompt, &crypto::VaultKey::default()).await?;
+    // API clients never get to read the desktop clipboard
+    let globals = prompts::global_variables(&context.pool()).await?;
+    let builtins = template::Builtins::new(&config.templates, None, globals);
+    let rendered = template::render(&text, &body.values, &builtins).map_err(AppError::from)?;
+    Ok(Json(rendered).into_response())
+}
+
+/// Error response with a `{"error": "..."}` body
+struct ApiError(StatusCode, String);
+
+impl From<DbError> for ApiError {
+    fn from(e: DbError) -> Self {
+        let status = match e {
+            DbError::NotFound(_) => StatusCode::NOT_FOUND,
+            DbError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        let status = match e.kind() {
+            ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+            ErrorKind::Locked => StatusCode::FORBIDDEN,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
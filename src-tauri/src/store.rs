@@ -0,0 +1,899 @@
+//! Vault-first prompt writes: the file is written (Master), then the cache follows. Commands
+//! add Tauri state and events around these; the integration tests call them directly.
+
+use crate::config::AppConfig;
+use crate::crypto::{self, VaultKey};
+use crate::db::{queries::*, DbPool};
+use crate::error::AppError;
+use crate::git;
+use crate::models::{
+    DbError, DeletedPrompt, PromptInput, PromptRow, PromptStatus, RevisionConflict, SaveConflict,
+    SavePromptResult, SplitBy, SyncStats,
+};
+use crate::prompts::{get_tags_for_prompt, load_prompt};
+use crate::sync::{self, get_or_create_tag};
+use crate::tokens;
+use crate::vault::{self, PromptFile, VaultError};
+use crate::vault_watcher::SelfWrites;
+use chrono::{Duration, Utc};
+use serde_json::Value as JsonValue;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+
+/// The vault prompt writes go to
+#[derive(Clone)]
+pub struct VaultContext {
+    pub path: PathBuf,
+    pub config: AppConfig,
+    /// Told about every write so the watcher skips the app's own changes
+    pub self_writes: SelfWrites,
+    /// Seals the text of prompts marked `encrypted` (locked unless set after `new`)
+    pub key: VaultKey,
+}
+
+impl VaultContext {
+    /// The vault of `config`; `NotConfigured` without one
+    pub fn new(config: &AppConfig, self_writes: SelfWrites) -> Result<Self, VaultError> {
+        let path = config.vault_path.clone().ok_or(VaultError::NotConfigured)?;
+        Ok(VaultContext {
+            path: PathBuf::from(path),
+            config: config.clone(),
+            self_writes,
+            key: VaultKey::default(),
+        })
+    }
+}
+
+/// Run `f` with the vault folder, the config and the self-write record on the blocking
+/// thread pool (see `vault::blocking`)
+pub async fn vault_io<T, F>(vault: &VaultContext, f: F) -> Result<T, AppError>
+where
+    F: FnOnce(&Path, &AppConfig, &SelfWrites) -> Result<T, AppError> + Send + 'static,
+    T: Send + 'static,
+{
+    let vault = vault.clone();
+    vault::blocking(move || f(&vault.path, &vault.config, &vault.self_writes)).await
+}
+
+/// Give a prompt that creates a new file (one without a previous path) the default tags and
+/// frontmatter from the config
+pub fn apply_new_prompt_defaults(prompt: &mut PromptInput, config: &AppConfig) {
+    if prompt
+        .previous_file_path
+        .as_deref()
+        .is_some_and(|path| !path.trim().is_empty())
+    {
+        return;
+    }
+    for tag in &config.default_tags {
+        if !prompt.tags.contains(tag) {
+            prompt.tags.push(tag.clone());
+        }
+    }
+    // A default status is a managed field, not a custom one
+    if prompt.status.is_none() {
+        prompt.status = config
+            .default_frontmatter
+            .get(vault::STATUS_KEY)
+            .and_then(JsonValue::as_str)
+            .and_then(PromptStatus::parse);
+    }
+    if !config.default_frontmatter.is_empty() {
+        let fields = prompt.custom_fields.get_or_insert_with(Default::default);
+        for (key, value) in &config.default_frontmatter {
+            fields.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+}
+
+/// Save one prompt: check its revision, write the file (sealing the text of an encrypted
+/// prompt), then cache it
+pub async fn save_prompt(
+    pool: &DbPool,
+    vault: &VaultContext,
+    mut prompt: PromptInput,
+) -> Result<WrittenPrompt, AppError> {
+    check_revision(pool, &prompt).await?;
+    apply_new_prompt_defaults(&mut prompt, &vault.config);
+    let key = vault.key.clone();
+    let written = vault_io(vault, move |vault_path, config, self_writes| {
+        write_prompt_input(vault_path, config, self_writes, &key, &prompt)
+    })
+    .await?;
+    cache_written(pool, vault, std::slice::from_ref(&written)).await?;
+    Ok(written)
+}
+
+/// Save many prompts: every file is written first, then the cache is updated in a single
+/// transaction. A prompt that fails to save is reported without stopping the rest.
+pub async fn save_prompts(
+    pool: &DbPool,
+    vault: &VaultContext,
+    mut prompts: Vec<PromptInput>,
+) -> Result<(Vec<SavePromptResult>, Vec<WrittenPrompt>), AppError> {
+    let mut checks = Vec::with_capacity(prompts.len());
+    for prompt in &mut prompts {
+        apply_new_prompt_defaults(prompt, &vault.config);
+        checks.push(check_revision(pool, prompt).await);
+    }
+    let key = vault.key.clone();
+    let (results, written) = vault_io(vault, move |vault_path, config, self_writes| {
+        let mut results = Vec::with_capacity(prompts.len());
+        let mut written = Vec::with_capacity(prompts.len());
+        for (prompt, check) in prompts.iter().zip(checks) {
+            let saved = check
+                .and_then(|()| write_prompt_input(vault_path, config, self_writes, &key, prompt));
+            match saved {
+                Ok(file) => {
+                    results.push(SavePromptResult {
+                        id: prompt.id.clone(),
+                        file_path: Some(file.file_path.clone()),
+                        error: None,
+                    });
+                    written.push(file);
+                }
+                Err(e) => {
+                    error!("Failed to save prompt {}: {}", prompt.id, e);
+                    results.push(SavePromptResult {
+                        id: prompt.id.clone(),
+                        file_path: None,
+                        error: Some(e),
+                    });
+                }
+            }
+        }
+        Ok((results, written))
+    })
+    .await?;
+
+    cache_written(pool, vault, &written).await?;
+    Ok((results, written))
+}
+
+/// Delete a prompt file and its cache row. A file already gone from the vault still has its
+/// row removed. The file contents are kept in `deleted_prompts` for `recover_deleted_prompt`.
+/// Returns the tags the prompt had.
+pub async fn delete_prompt(
+    pool: &DbPool,
+    vault: &VaultContext,
+    id: &str,
+) -> Result<Vec<String>, AppError> {
+    // We try to delete, but if file is already gone, we proceed to ensure DB is clean
+    let row = sqlx::query_as::<_, PromptRow>(SELECT_PROMPT_BY_ID)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    let title = row.as_ref().and_then(|r| r.title.clone());
+    let file_path = row
+        .and_then(|r| r.file_path)
+        .unwrap_or_else(|| id.to_string());
+
+    let prompt_id = id.to_string();
+    let content = vault_io(vault, move |vault_path, config, self_writes| {
+        let content = std::fs::read_to_string(vault_path.join(&file_path)).ok();
+        self_writes.record(&file_path, None);
+        if let Err(e) = vault::delete_prompt_file(vault_path, &file_path, &config.frontmatter) {
+            match e {
+                VaultError::PathNotFound(_) => {
+                    info!(
+                        "File for prompt {} not found in vault, proceeding to delete from DB",
+                        prompt_id
+                    );
+                }
+                _ => return Err(e.into()),
+            }
+        }
+
+        if config.git_history {
+            commit_vault_history(
+                vault_path,
+                &[&file_path],
+                &format!("Delete prompt {}", file_path),
+            );
+        }
+        Ok(content)
+    })
+    .await?;
+
+    let tags = get_tags_for_prompt(pool, id).await?;
+    let mut tx = pool.begin().await?;
+    sqlx::query(DELETE_PROMPT)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+    if let Some(content) = content {
+        sqlx::query(UPSERT_DELETED_PROMPT)
+            .bind(id)
+            .bind(&title)
+            .bind(&content)
+            .bind(Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(tags)
+}
+
+/// Deleted prompts that can still be recovered, newest first. Ones older than
+/// `retention_days` (unless 0) are dropped first.
+pub async fn list_recently_deleted(
+    pool: &DbPool,
+    retention_days: u32,
+) -> Result<Vec<DeletedPrompt>, AppError> {
+    if retention_days > 0 {
+        let cutoff = Utc::now() - Duration::days(i64::from(retention_days));
+        sqlx::query(PURGE_DELETED_PROMPTS)
+            .bind(cutoff.to_rfc3339())
+            .execute(pool)
+            .await?;
+    }
+    Ok(sqlx::query_as::<_, DeletedPrompt>(SELECT_DELETED_PROMPTS)
+        .fetch_all(pool)
+        .await?)
+}
+
+/// Write the kept contents of deleted prompt `id` back to its path and cache it again.
+/// Fails with `AlreadyExists` when a file has taken that path since.
+pub async fn recover_deleted_prompt(
+    pool: &DbPool,
+    vault: &VaultContext,
+    id: &str,
+) -> Result<SyncStats, AppError> {
+    let content: Option<String> = sqlx::query_scalar(SELECT_DELETED_PROMPT_CONTENT)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    let content = content.ok_or_else(|| DbError::NotFound(format!("No deleted prompt {}", id)))?;
+
+    let file_path = vault::normalize_relative_path(id, &vault.config.frontmatter.file_extensions)?;
+    let write_path = file_path.clone();
+    vault_io(vault, move |vault_path, config, self_writes| {
+        let full_path = vault_path.join(&write_path);
+        if full_path.exists() {
+            return Err(VaultError::FileAlreadyExists(write_path).into());
+        }
+        vault::write_atomic(&full_path, &content)
+            .map_err(|e| AppError::io(format!("Failed to write file: {}", e)))?;
+        self_writes.record(
+            &write_path,
+            vault::compute_file_hash_from_path(&full_path).ok(),
+        );
+        if config.git_history {
+            commit_vault_history(
+                vault_path,
+                &[&write_path],
+                &format!("Recover prompt {}", write_path),
+            );
+        }
+        Ok(())
+    })
+    .await?;
+
+    let stats =
+        sync::sync_paths(pool, &vault.path, &vault.config.frontmatter, &[file_path]).await?;
+    sqlx::query(DELETE_DELETED_PROMPT)
+        .bind(id)
+        .execute(pool)
+        .await?;
+    Ok(stats)
+}
+
+/// Rename (move) a prompt file within the vault
+/// 1. Validate the target path
+/// 2. Re-key the cache rows in a transaction
+/// 3. Move the file, then commit (moving it back if the commit fails)
+///
+/// Returns the normalized old and new ids, or `None` when the path did not change.
+pub async fn rename_prompt(
+    pool: &DbPool,
+    vault: &VaultContext,
+    id: &str,
+    new_path: &str,
+) -> Result<Option<(String, String)>, AppError> {
+    let old_path = vault::normalize_relative_path(id, &vault.config.frontmatter.file_extensions)?;
+    let new_path =
+        vault::normalize_relative_path(new_path, &vault.config.frontmatter.file_extensions)?;
+    if old_path == new_path {
+        return Ok(None);
+    }
+
+    let source = vault.path.join(&old_path);
+    let target = vault.path.join(&new_path);
+    let (source_exists, target_exists) = {
+        let (source, target) = (source.clone(), target.clone());
+        vault::blocking(move || Ok::<_, AppError>((source.is_file(), target.exists()))).await?
+    };
+    if !source_exists {
+        return Err(DbError::NotFound(old_path).into());
+    }
+    if target_exists {
+        return Err(VaultError::FileAlreadyExists(new_path).into());
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query(COPY_PROMPT_TO_ID)
+        .bind(&new_path)
+        .bind(&new_path)
+        .bind(&old_path)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(UPDATE_PROMPT_TAGS_PROMPT_ID)
+        .bind(&new_path)
+        .bind(&old_path)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(UPDATE_PROMPT_USAGE_PROMPT_ID)
+        .bind(&new_path)
+        .bind(&old_path)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(UPDATE_PROMPT_RUNS_PROMPT_ID)
+        .bind(&new_path)
+        .bind(&old_path)
+        .execute(&mut *tx)
+        .await?;
+    for query in [
+        UPDATE_TEMPLATE_PRESETS_PROMPT_ID,
+        UPDATE_PROMPT_TESTS_PROMPT_ID,
+        UPDATE_PROMPT_LINKS_SOURCE_ID,
+        UPDATE_PROMPT_LINKS_TARGET_ID,
+    ] {
+        sqlx::query(query)
+            .bind(&new_path)
+            .bind(&old_path)
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query(DELETE_PROMPT)
+        .bind(&old_path)
+        .execute(&mut *tx)
+        .await?;
+
+    let (from, to) = (old_path.clone(), new_path.clone());
+    let (moved_source, moved_target) = (source.clone(), target.clone());
+    vault_io(vault, move |_, _, self_writes| {
+        if let Some(parent) = moved_target.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| AppError::io(format!("Failed to create folder: {}", e)))?;
+        }
+        self_writes.record(&from, None);
+        std::fs::rename(&moved_source, &moved_target)
+            .map_err(|e| AppError::io(format!("Failed to rename file: {}", e)))?;
+        self_writes.record(&to, vault::compute_file_hash_from_path(&moved_target).ok());
+        Ok(())
+    })
+    .await?;
+
+    if let Err(e) = tx.commit().await {
+        let _ = vault::blocking(move || {
+            std::fs::rename(&target, &source).map_err(|e| VaultError::IoError(e.to_string()))
+        })
+        .await;
+        return Err(e.into());
+    }
+
+    if vault.config.git_history {
+        let (from, to) = (old_path.clone(), new_path.clone());
+        vault_io(vault, move |vault_path, _, _| {
+            commit_vault_history(
+                vault_path,
+                &[&from, &to],
+                &format!("Rename prompt {} to {}", from, to),
+            );
+            Ok(())
+        })
+        .await?;
+    }
+
+    Ok(Some((old_path, new_path)))
+}
+
+/// Cut `text` into the parts `split` describes, trimmed and without empty ones
+pub fn split_text(text: &str, split: &SplitBy) -> Vec<String> {
+    let parts = match split {
+        SplitBy::Lines(points) => {
+            let lines: Vec<&str> = text.lines().collect();
+            let mut starts: Vec<usize> = points
+                .iter()
+                .map(|&line| line as usize)
+                .filter(|&line| line > 0 && line < lines.len())
+                .collect();
+            starts.sort_unstable();
+            starts.dedup();
+            starts.insert(0, 0);
+            starts.push(lines.len());
+            starts
+                .windows(2)
+                .map(|range| lines[range[0]..range[1]].join("\n"))
+                .collect()
+        }
+        SplitBy::Sections(sections) => sections.clone(),
+    };
+    parts
+        .into_iter()
+        .map(|part| part.trim().to_string())
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
+/// Split prompt `id` into new files, one per part of its text, each with the tags and
+/// created date of the original; the original is archived. Returns the files written, the
+/// new ones first.
+pub async fn split_prompt(
+    pool: &DbPool,
+    vault: &VaultContext,
+    id: &str,
+    split: SplitBy,
+) -> Result<Vec<WrittenPrompt>, AppError> {
+    let file_path = vault::normalize_relative_path(id, &vault.config.frontmatter.file_extensions)?;
+    let key = vault.key.clone();
+    let written = vault_io(vault, move |vault_path, config, self_writes| {
+        let file = vault::read_prompt_file(
+            vault_path,
+            &vault_path.join(&file_path),
+            &config.frontmatter,
+        )?;
+        if crypto::is_sealed(&file.content) {
+            return Err(AppError::invalid_input("Encrypted prompts can't be split"));
+        }
+        let parts = split_text(&file.content, &split);
+        if parts.len() < 2 {
+            return Err(AppError::invalid_input(
+                "Splitting needs at least two non-empty parts",
+            ));
+        }
+
+        let mut written = Vec::with_capacity(parts.len() + 1);
+        for text in parts {
+            let part = PromptInput {
+                id: String::new(),
+                created: file.created.clone(),
+                text,
+                tags: file.tags.clone(),
+                file_path: None,
+                previous_file_path: None,
+                title: None,
+                description: None,
+                color: None,
+                icon: None,
+                notes: None,
+                model_targets: None,
+                language: None,
+                status: None,
+                file_hash: None,
+                custom_fields: None,
+                variant: None,
+                archived: None,
+                expected_revision: None,
+            };
+            written.push(write_prompt_input(
+                vault_path,
+                config,
+                self_writes,
+                &key,
+                &part,
+            )?);
+        }
+        let original = PromptInput {
+            archived: Some(true),
+            ..rewrite_input(file_path, file)
+        };
+        written.push(write_prompt_input(
+            vault_path,
+            config,
+            self_writes,
+            &key,
+            &original,
+        )?);
+        Ok(written)
+    })
+    .await?;
+    cache_written(pool, vault, &written).await?;
+    Ok(written)
+}
+
+/// Copy a prompt to a new file named by the filename template, with a new created date.
+/// The source file is copied first so the save keeps its variants and variables. `None` when
+/// the prompt is not in the cache.
+pub async fn duplicate_prompt(
+    pool: &DbPool,
+    vault: &VaultContext,
+    id: &str,
+) -> Result<Option<WrittenPrompt>, AppError> {
+    let Some(prompt) = load_prompt(pool, id).await? else {
+        return Ok(None);
+    };
+    let source_path = vault::normalize_relative_path(
+        prompt.file_path.as_deref().unwrap_or(&prompt.id),
+        &vault.config.frontmatter.file_extensions,
+    )?;
+    let created = chrono::Local::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let key = vault.key.clone();
+    let written = vault_io(vault, move |vault_path, config, self_writes| {
+        let source = vault_path.join(&source_path);
+        let file = vault::read_prompt_file(vault_path, &source, &config.frontmatter)?;
+        let file_path = vault::generate_unique_file_path(
+            vault_path,
+            &config.filename_template,
+            file.title.as_deref(),
+        )?;
+        let content =
+            fs::read(&source).map_err(|e| AppError::io(format!("Failed to read file: {}", e)))?;
+        vault::write_atomic(&vault_path.join(&file_path), content)
+            .map_err(|e| AppError::io(format!("Failed to write file: {}", e)))?;
+
+        let input = PromptInput {
+            created: Some(created),
+            archived: Some(false),
+            ..rewrite_input(file_path, file)
+        };
+        let mut written = write_prompt_input(vault_path, config, self_writes, &key, &input)?;
+        // A new prompt, not an update of the copy
+        written.previous_tags = None;
+        Ok(written)
+    })
+    .await?;
+    cache_written(pool, vault, std::slice::from_ref(&written)).await?;
+    Ok(Some(written))
+}
+
+/// Input that saves `file` back to `file_path` unchanged, for edits of a single field
+pub fn rewrite_input(file_path: String, file: PromptFile) -> PromptInput {
+    PromptInput {
+        id: file_path.clone(),
+        created: file.created,
+        text: file.content,
+        tags: file.tags,
+        file_path: Some(file_path.clone()),
+        previous_file_path: Some(file_path),
+        title: file.title,
+        description: file.description,
+        color: None,
+        icon: None,
+        notes: None,
+        model_targets: None,
+        language: None,
+        status: None,
+        file_hash: None,
+        custom_fields: None,
+        variant: None,
+        archived: None,
+        expected_revision: None,
+    }
+}
+
+/// A prompt file written by `write_prompt_input`, not yet in the cache until `cache_written`
+pub struct WrittenPrompt {
+    pub file_path: String,
+    /// Previous path when the save renamed the file; deleted once the cache is updated
+    pub renamed_from: Option<String>,
+    pub file: PromptFile,
+    pub file_hash: Option<String>,
+    /// Tags in the file before this save (`None` for a new file)
+    pub previous_tags: Option<Vec<String>>,
+}
+
+/// Reject `prompt` if the cached prompt it was loaded from got a newer revision since
+pub async fn check_revision(pool: &DbPool, prompt: &PromptInput) -> Result<(), AppError> {
+    let Some(expected_revision) = prompt.expected_revision else {
+        return Ok(());
+    };
+    let id = prompt
+        .previous_file_path
+        .as_deref()
+        .filter(|path| !path.trim().is_empty())
+        .unwrap_or(&prompt.id);
+    let current_revision = sqlx::query_scalar::<_, i64>(SELECT_PROMPT_REVISION)
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    if current_revision == Some(expected_revision) {
+        return Ok(());
+    }
+    Err(DbError::StaleRevision(RevisionConflict {
+        id: id.to_string(),
+        expected_revision,
+        current_revision,
+    })
+    .into())
+}
+
+/// Validate `prompt` and write it to the vault (step 2 of `save_prompt`). The text is sealed
+/// when the file's frontmatter, after merging, asks for encryption.
+pub fn write_prompt_input(
+    vault_path: &Path,
+    config: &AppConfig,
+    self_writes: &SelfWrites,
+    key: &VaultKey,
+    prompt: &PromptInput,
+) -> Result<WrittenPrompt, AppError> {
+    // Prepare PromptFile for vault write
+    let file_path_raw = match prompt.file_path.clone() {
+        Some(path) if !path.trim().is_empty() => path,
+        _ => vault::generate_unique_file_path(
+            vault_path,
+            &config.filename_template,
+            prompt.title.as_deref(),
+        )?,
+    };
+    let file_path =
+        vault::normalize_relative_path(&file_path_raw, &config.frontmatter.file_extensions)?;
+
+    let previous_file_path = prompt
+        .previous_file_path
+        .clone()
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| vault::normalize_relative_path(&p, &config.frontmatter.file_extensions))
+        .transpose()?;
+
+    if let Some(prev_path) = &previous_file_path {
+        if prev_path != &file_path {
+            let target_path = vault_path.join(&file_path);
+            if target_path.exists() {
+                return Err(VaultError::FileAlreadyExists(file_path).into());
+            }
+        }
+    } else if vault_path.join(&file_path).exists() {
+        return Err(VaultError::FileAlreadyExists(file_path).into());
+    }
+
+    // Refuse to overwrite edits made outside the app since the cache last read the file
+    if let Some(expected_hash) = &prompt.file_hash {
+        let source_path = previous_file_path.as_ref().unwrap_or(&file_path);
+        let source = vault_path.join(source_path);
+        let current_hash = vault::compute_file_hash_from_path(&source).ok();
+        if current_hash.as_ref() != Some(expected_hash) {
+            let disk_text = vault::read_prompt_file(vault_path, &source, &config.frontmatter)
+                .ok()
+                .map(|file| file.content);
+            return Err(AppError::Db(DbError::Conflict(SaveConflict {
+                file_path: source_path.clone(),
+                expected_hash: expected_hash.clone(),
+                current_hash,
+                local_text: prompt.text.clone(),
+                disk_text,
+            })));
+        }
+    }
+
+    let source_path = previous_file_path.as_ref().unwrap_or(&file_path);
+    let existing_file = vault::read_prompt_file(
+        vault_path,
+        &vault_path.join(source_path),
+        &config.frontmatter,
+    )
+    .ok();
+
+    // Without explicit custom fields, carry over the ones already in the file
+    let custom_fields = match prompt.custom_fields.clone() {
+        Some(fields) => fields,
+        None => existing_file
+            .as_ref()
+            .map(|file| file.custom_fields.clone())
+            .unwrap_or_default(),
+    };
+    // Decided on the merged fields, so a save that leaves them out keeps the file sealed
+    let encrypted = crypto::wants_encryption(&custom_fields);
+
+    // Keep existing variants (needed on rename); a named variant save only replaces that block
+    let mut variants = existing_file
+        .as_ref()
+        .map(|file| file.variants.clone())
+        .unwrap_or_default();
+    let content = match prompt.variant.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            if encrypted {
+                return Err(AppError::invalid_input(
+                    "Encrypted prompts can't have variants",
+                ));
+            }
+            variants.retain(|v| v.name != name);
+            variants.push(vault::PromptVariant {
+                name: name.to_string(),
+                content: prompt.text.clone(),
+            });
+            existing_file
+                .as_ref()
+                .map(|file| file.content.clone())
+                .unwrap_or_default()
+        }
+        _ if encrypted => crypto::seal_text(&prompt.text, key)?,
+        _ => prompt.text.clone(),
+    };
+
+    let prompt_file = vault::PromptFile {
+        id: file_path.clone(),
+        // We calculate relative path just for completeness, but write_prompt_file uses ID for filename
+        file_path: file_path.clone(),
+        tags: prompt.tags.clone(),
+        created: prompt.created.clone(),
+        content,
+        file_hash: None,
+        title: prompt.title.clone(),
+        description: prompt.description.clone(),
+        color: keep_or_set(
+            &prompt.color,
+            existing_file.as_ref().map(|file| &file.color),
+        ),
+        icon: keep_or_set(&prompt.icon, existing_file.as_ref().map(|file| &file.icon)),
+        notes: keep_or_set(
+            &prompt.notes,
+            existing_file.as_ref().map(|file| &file.notes),
+        ),
+        model_targets: match &prompt.model_targets {
+            Some(targets) => targets.clone(),
+            None => existing_file
+                .as_ref()
+                .map(|file| file.model_targets.clone())
+                .unwrap_or_default(),
+        },
+        language: keep_or_set(
+            &prompt.language,
+            existing_file.as_ref().map(|file| &file.language),
+        ),
+        custom_fields,
+        variants,
+        status: prompt
+            .status
+            .or_else(|| existing_file.as_ref().and_then(|file| file.status)),
+        archived: prompt
+            .archived
+            .unwrap_or_else(|| existing_file.as_ref().is_some_and(|file| file.archived)),
+        links: Vec::new(),
+        variables: existing_file
+            .as_ref()
+            .map(|file| file.variables.clone())
+            .unwrap_or_default(),
+    };
+
+    // Write to Filesystem
+    vault::write_prompt_file(vault_path, &prompt_file, &config.frontmatter)?;
+
+    let file_hash = vault::compute_file_hash_from_path(&vault_path.join(&file_path)).ok();
+    self_writes.record(&file_path, file_hash.clone());
+
+    Ok(WrittenPrompt {
+        renamed_from: previous_file_path.filter(|prev_path| prev_path != &file_path),
+        file_path,
+        file: prompt_file,
+        file_hash,
+        previous_tags: existing_file.map(|file| file.tags),
+    })
+}
+
+/// A metadata field of a save: `None` keeps the file's current value, a blank one clears it
+fn keep_or_set(value: &Option<String>, existing: Option<&Option<String>>) -> Option<String> {
+    match value {
+        Some(value) => Some(value.trim().to_string()).filter(|value| !value.is_empty()),
+        None => existing.cloned().flatten(),
+    }
+}
+
+/// Cache prompts written by `write_prompt_input` in one transaction (step 3 of `save_prompt`),
+/// then remove renamed files and record history
+pub async fn cache_written(
+    pool: &DbPool,
+    vault: &VaultContext,
+    written: &[WrittenPrompt],
+) -> Result<(), AppError> {
+    if written.is_empty() {
+        return Ok(());
+    }
+
+    // Use a transaction for atomicity
+    let mut tx = pool.begin().await?;
+    for prompt in written {
+        // Remove old prompt row if file was renamed, carrying its revision over
+        let mut previous_revision = None;
+        if let Some(prev_path) = &prompt.renamed_from {
+            previous_revision = sqlx::query_scalar::<_, i64>(SELECT_PROMPT_REVISION)
+                .bind(prev_path)
+                .fetch_optional(&mut *tx)
+                .await?;
+            sqlx::query(DELETE_PROMPT)
+                .bind(prev_path)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Upsert the prompt
+        let counts = tokens::text_counts(&prompt.file.content);
+        sqlx::query(UPSERT_PROMPT)
+            .bind(&prompt.file_path)
+            .bind(prompt.file.created.clone())
+            .bind(&prompt.file.content)
+            .bind(prompt.file.title.clone())
+            .bind(prompt.file.description.clone())
+            .bind(Some(prompt.file_path.clone())) // Store the relative path
+            .bind(prompt.file_hash.clone())
+            .bind(sync::custom_fields_json(&prompt.file.custom_fields)?)
+            .bind(tokens::count(&prompt.file.content, None) as i64)
+            .bind(prompt.file.archived)
+            .bind(sync::variables_json(&prompt.file.variables)?)
+            .bind(prompt.file.color.clone())
+            .bind(prompt.file.icon.clone())
+            .bind(prompt.file.notes.clone())
+            .bind(sync::list_json(&prompt.file.model_targets)?)
+            .bind(prompt.file.language.clone())
+            .bind(prompt.file.status.map(PromptStatus::as_str))
+            .bind(counts.chars)
+            .bind(counts.words)
+            .bind(counts.lines)
+            .execute(&mut *tx)
+            .await?;
+        if let Some(revision) = previous_revision {
+            sqlx::query(UPDATE_PROMPT_REVISION)
+                .bind(revision + 1)
+                .bind(&prompt.file_path)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // Delete existing tags
+        sqlx::query(DELETE_PROMPT_TAGS)
+            .bind(&prompt.file_path)
+            .execute(&mut *tx)
+            .await?;
+
+        // Insert new tags
+        for tag_name in &prompt.file.tags {
+            let tag_id = get_or_create_tag(&mut tx, tag_name).await?;
+            sqlx::query(INSERT_PROMPT_TAG)
+                .bind(&prompt.file_path)
+                .bind(&tag_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+    tx.commit().await?;
+
+    let renamed: Vec<String> = written
+        .iter()
+        .filter_map(|prompt| prompt.renamed_from.clone())
+        .collect();
+    let history = match written {
+        _ if !vault.config.git_history => None,
+        [prompt] => {
+            let file_path = prompt.file_path.clone();
+            Some(match &prompt.renamed_from {
+                Some(prev_path) => (
+                    format!("Rename prompt {} to {}", prev_path, file_path),
+                    vec![prev_path.clone(), file_path],
+                ),
+                None if prompt.previous_tags.is_some() => {
+                    (format!("Update prompt {}", file_path), vec![file_path])
+                }
+                None => (format!("Add prompt {}", file_path), vec![file_path]),
+            })
+        }
+        _ => Some((
+            format!("Save {} prompts", written.len()),
+            written
+                .iter()
+                .flat_map(|prompt| {
+                    std::iter::once(prompt.file_path.clone()).chain(prompt.renamed_from.clone())
+                })
+                .collect(),
+        )),
+    };
+    vault_io(vault, move |vault_path, config, self_writes| {
+        for prev_path in &renamed {
+            self_writes.record(prev_path, None);
+            let _ = vault::delete_prompt_file(vault_path, prev_path, &config.frontmatter);
+        }
+        if let Some((message, paths)) = history {
+            let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+            commit_vault_history(vault_path, &paths, &message);
+        }
+        Ok(())
+    })
+    .await
+}
+
+/// Commit changed vault files to git history; failures are logged, never fatal to the write
+pub fn commit_vault_history(vault_path: &Path, paths: &[&str], message: &str) {
+    if let Err(e) = git::commit_paths(vault_path, paths, message) {
+        error!("Failed to record vault history ({}): {}", message, e);
+    }
+}
@@ -0,0 +1,578 @@
+use crate::config::FrontmatterSettings;
+use crate::db::{queries::*, DbPool};
+use crate::events::{SyncPhase, SyncProgress};
+use crate::models::{DbError, PromptIdRow, PromptStatus, SyncStats, TagRow, WIKILINK_RELATION};
+use crate::promptignore::PromptIgnore;
+use crate::template::TemplateVariable;
+use crate::tokens;
+use crate::vault::{self, PromptFile, VaultError};
+use sqlx::{QueryBuilder, Sqlite};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+use uuid::Uuid;
+
+/// Rows per multi-row statement, keeping bound parameters well under SQLite's limit
+const BATCH_ROWS: usize = 400;
+
+/// Least time between two `SyncProgress` reports of one phase
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancels running full syncs through `cancel_sync`; managed as Tauri state
+#[derive(Default)]
+pub struct SyncCancellation {
+    token: Mutex<CancellationToken>,
+}
+
+impl SyncCancellation {
+    /// Token for a sync that is starting, cancelled by the next `cancel`
+    pub fn token(&self) -> CancellationToken {
+        self.token
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .child_token()
+    }
+
+    /// Cancel every sync running now; later syncs are not affected
+    pub fn cancel(&self) {
+        std::mem::take(&mut *self.token.lock().unwrap_or_else(PoisonError::into_inner)).cancel();
+    }
+}
+
+/// Progress reporting and cancellation for `sync_vault_with`
+#[derive(Clone)]
+pub struct SyncControl {
+    pub cancel: CancellationToken,
+    /// Called with throttled progress, also from the blocking pool during the scan
+    pub on_progress: Arc<dyn Fn(SyncProgress) + Send + Sync>,
+}
+
+impl Default for SyncControl {
+    fn default() -> Self {
+        Self {
+            cancel: CancellationToken::new(),
+            on_progress: Arc::new(|_| {}),
+        }
+    }
+}
+
+/// Reports at most one `SyncProgress` per `PROGRESS_INTERVAL`, plus the first and last
+struct ProgressThrottle {
+    on_progress: Arc<dyn Fn(SyncProgress) + Send + Sync>,
+    phase: SyncPhase,
+    last_report: Option<Instant>,
+}
+
+impl ProgressThrottle {
+    fn new(control: &SyncControl, phase: SyncPhase) -> Self {
+        Self {
+            on_progress: control.on_progress.clone(),
+            phase,
+            last_report: None,
+        }
+    }
+
+    fn report(&mut self, scanned: usize, total: usize, current: Option<&str>) {
+        let due = self
+            .last_report
+            .is_none_or(|last| last.elapsed() >= PROGRESS_INTERVAL);
+        if !due && scanned < total {
+            return;
+        }
+        self.last_report = Some(Instant::now());
+        (self.on_progress)(SyncProgress {
+            phase: self.phase,
+            scanned,
+            total,
+            current: current.map(str::to_string),
+        });
+    }
+}
+
+/// Sync vault files to database cache
+/// STRICT VAULT-FIRST:
+/// 1. Scan filesystem
+/// 2. Upsert all found files to DB
+/// 3. Remove DB entries that are not in the scan
+pub async fn sync_vault(
+    pool: &DbPool,
+    vault_path: &Path,
+    frontmatter_settings: &FrontmatterSettings,
+) -> Result<SyncStats, DbError> {
+    sync_vault_with(
+        pool,
+        vault_path,
+        frontmatter_settings,
+        &SyncControl::default(),
+    )
+    .await
+}
+
+/// `sync_vault` reporting progress through `control`. Once `control.cancel` is cancelled
+/// it stops between files with `DbError::Cancelled`, leaving the cache as it was.
+pub async fn sync_vault_with(
+    pool: &DbPool,
+    vault_path: &Path,
+    frontmatter_settings: &FrontmatterSettings,
+    control: &SyncControl,
+) -> Result<SyncStats, DbError> {
+    // 1. Scan Vault
+    let scan = {
+        let (vault_path, frontmatter_settings) =
+            (vault_path.to_path_buf(), frontmatter_settings.clone());
+        let cancel = control.cancel.clone();
+        let mut progress = ProgressThrottle::new(control, SyncPhase::Scanning);
+        vault::blocking(move || {
+            vault::scan_vault_with_progress(
+                &vault_path,
+                &frontmatter_settings,
+                &cancel,
+                |scanned, total, name| progress.report(scanned, total, Some(name)),
+            )
+        })
+        .await
+        .map_err(|e| match e {
+            VaultError::Cancelled => DbError::Cancelled,
+            e => DbError::Database(format!("Failed to scan vault: {}", e)),
+        })?
+    };
+
+    let mut tx = pool.begin().await?;
+    let mut found_ids = HashSet::new();
+    let found_count = scan.prompts.len();
+
+    // 2. Upsert all files
+    let mut progress = ProgressThrottle::new(control, SyncPhase::Caching);
+    for (cached, file) in scan.prompts.iter().enumerate() {
+        if control.cancel.is_cancelled() {
+            return Err(DbError::Cancelled);
+        }
+        progress.report(cached, found_count, Some(&file.file_path));
+        found_ids.insert(file.file_path.clone());
+        upsert_prompt_file(&mut tx, file).await?;
+    }
+    replace_prompt_tags(&mut tx, &scan.prompts).await?;
+    progress.report(found_count, found_count, None);
+
+    // 3. Prune DB entries not in Vault
+    let cached_ids = sqlx::query_as::<_, PromptIdRow>(SELECT_PROMPT_IDS)
+        .fetch_all(&mut *tx)
+        .await?;
+
+    let mut deleted_count = 0;
+    for row in cached_ids {
+        if !found_ids.contains(&row.id) {
+            sqlx::query(DELETE_PROMPT)
+                .bind(&row.id)
+                .execute(&mut *tx)
+                .await?;
+            deleted_count += 1;
+        }
+    }
+
+    tx.commit().await?;
+
+    info!(
+        "sync_vault completed. Found: {}, Deleted: {}",
+        found_count, deleted_count
+    );
+
+    Ok(SyncStats {
+        found: found_count,
+        updated: found_count, // Effectively all found are "updated" via upsert
+        deleted: deleted_count,
+        skipped: scan.skipped,
+    })
+}
+
+/// Re-read only the given vault-relative paths into the cache.
+/// Paths whose file no longer exists, or that `.promptignore` matches, are removed from
+/// the cache. Paths that are not valid vault paths are reported in `skipped` and the rest
+/// are still synced; when none is valid the sync fails with `DbError::InvalidInput`.
+pub async fn sync_paths(
+    pool: &DbPool,
+    vault_path: &Path,
+    frontmatter_settings: &FrontmatterSettings,
+    paths: &[String],
+) -> Result<SyncStats, DbError> {
+    let mut invalid = Vec::new();
+    let mut relative_paths = Vec::with_capacity(paths.len());
+    for path in paths {
+        match vault::normalize_relative_path(path, &frontmatter_settings.file_extensions) {
+            Ok(relative_path) => relative_paths.push(relative_path),
+            Err(e) => {
+                info!("Skipping invalid path {}: {}", path, e);
+                invalid.push(vault::skipped_file(path, &e));
+            }
+        }
+    }
+    if relative_paths.is_empty() && !invalid.is_empty() {
+        let messages: Vec<String> = invalid.iter().map(|file| file.message.clone()).collect();
+        return Err(DbError::InvalidInput(messages.join("; ")));
+    }
+
+    // Read every file before touching the cache; `None` for files that are gone or ignored
+    let reads = {
+        let (vault_path, frontmatter_settings) =
+            (vault_path.to_path_buf(), frontmatter_settings.clone());
+        vault::blocking(move || {
+            let ignore = PromptIgnore::load(&vault_path);
+            let reads: Vec<_> = relative_paths
+                .into_iter()
+                .map(|relative_path| {
+                    let file_path = vault_path.join(&relative_path);
+                    let read =
+                        (file_path.is_file() && !ignore.is_ignored(&relative_path)).then(|| {
+                            vault::read_prompt_file(&vault_path, &file_path, &frontmatter_settings)
+                        });
+                    (relative_path, read)
+                })
+                .collect();
+            Ok::<_, VaultError>(reads)
+        })
+        .await
+        .map_err(|e| DbError::Database(format!("Failed to read vault files: {}", e)))?
+    };
+
+    let mut tx = pool.begin().await?;
+    let mut stats = SyncStats {
+        found: 0,
+        updated: 0,
+        deleted: 0,
+        skipped: invalid,
+    };
+
+    let mut upserted = Vec::new();
+    for (relative_path, read) in reads {
+        let Some(read) = read else {
+            let result = sqlx::query(DELETE_PROMPT)
+                .bind(&relative_path)
+                .execute(&mut *tx)
+                .await?;
+            stats.deleted += result.rows_affected() as usize;
+            continue;
+        };
+
+        stats.found += 1;
+        match read {
+            Ok(file) => {
+                upsert_prompt_file(&mut tx, &file).await?;
+                upserted.push(file);
+                stats.updated += 1;
+            }
+            Err(e) => {
+                info!("Skipping file {}: {}", relative_path, e);
+                stats.skipped.push(vault::skipped_file(&relative_path, &e));
+            }
+        }
+    }
+    replace_prompt_tags(&mut tx, &upserted).await?;
+
+    tx.commit().await?;
+
+    info!(
+        "sync_paths completed. Found: {}, Updated: {}, Deleted: {}",
+        stats.found, stats.updated, stats.deleted
+    );
+    Ok(stats)
+}
+
+/// Upsert a parsed vault file and replace its wikilinks in the cache; its tags are
+/// replaced afterwards, together with the rest of the batch, by `replace_prompt_tags`
+async fn upsert_prompt_file(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    file: &PromptFile,
+) -> Result<(), DbError> {
+    let counts = tokens::text_counts(&file.content);
+    sqlx::query(UPSERT_PROMPT)
+        .bind(&file.file_path)
+        .bind(file.created.clone())
+        .bind(&file.content)
+        .bind(file.title.clone())
+        .bind(file.description.clone())
+        .bind(Some(&file.file_path))
+        .bind(file.file_hash.clone())
+        .bind(custom_fields_json(&file.custom_fields)?)
+        .bind(tokens::count(&file.content, None) as i64)
+        .bind(file.archived)
+        .bind(variables_json(&file.variables)?)
+        .bind(file.color.clone())
+        .bind(file.icon.clone())
+        .bind(file.notes.clone())
+        .bind(list_json(&file.model_targets)?)
+        .bind(file.language.clone())
+        .bind(file.status.map(PromptStatus::as_str))
+        .bind(counts.chars)
+        .bind(counts.words)
+        .bind(counts.lines)
+        .execute(&mut **tx)
+        .await?;
+
+    // Replace links found in the file
+    sqlx::query(DELETE_PROMPT_LINKS_BY_RELATION)
+        .bind(&file.file_path)
+        .bind(WIKILINK_RELATION)
+        .execute(&mut **tx)
+        .await?;
+
+    for target in &file.links {
+        sqlx::query(INSERT_PROMPT_LINK)
+            .bind(&file.file_path)
+            .bind(target)
+            .bind(WIKILINK_RELATION)
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Replace the cached tags of `files` with a handful of statements whatever the batch size:
+/// every tag is loaded once, missing tags and all prompt tags are inserted with
+/// multi-row inserts
+async fn replace_prompt_tags(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    files: &[PromptFile],
+) -> Result<(), DbError> {
+    if files.is_empty() {
+        return Ok(());
+    }
+
+    let mut tag_ids: HashMap<String, String> = sqlx::query_as::<_, TagRow>(SELECT_ALL_TAGS)
+        .fetch_all(&mut **tx)
+        .await?
+        .into_iter()
+        .map(|tag| (tag.name, tag.id))
+        .collect();
+
+    let mut missing: Vec<(String, String)> = Vec::new();
+    for tag_name in files.iter().flat_map(|file| &file.tags) {
+        if !tag_ids.contains_key(tag_name) {
+            let id = Uuid::new_v4().to_string();
+            tag_ids.insert(tag_name.clone(), id.clone());
+            missing.push((id, tag_name.clone()));
+        }
+    }
+    for chunk in missing.chunks(BATCH_ROWS) {
+        QueryBuilder::<Sqlite>::new("INSERT INTO tags (id, name) ")
+            .push_values(chunk, |mut row, (id, name)| {
+                row.push_bind(id).push_bind(name);
+            })
+            .build()
+            .execute(&mut **tx)
+            .await?;
+    }
+
+    for chunk in files.chunks(BATCH_ROWS) {
+        let mut query = QueryBuilder::<Sqlite>::new("DELETE FROM prompt_tags WHERE prompt_id IN (");
+        let mut ids = query.separated(", ");
+        for file in chunk {
+            ids.push_bind(&file.file_path);
+        }
+        query.push(")").build().execute(&mut **tx).await?;
+    }
+
+    let prompt_tags: Vec<(&str, &str)> = files
+        .iter()
+        .flat_map(|file| {
+            let tag_ids = &tag_ids;
+            file.tags
+                .iter()
+                .map(move |tag_name| (file.file_path.as_str(), tag_ids[tag_name].as_str()))
+        })
+        .collect();
+    for chunk in prompt_tags.chunks(BATCH_ROWS) {
+        QueryBuilder::<Sqlite>::new("INSERT INTO prompt_tags (prompt_id, tag_id) ")
+            .push_values(chunk, |mut row, (prompt_id, tag_id)| {
+                row.push_bind(*prompt_id).push_bind(*tag_id);
+            })
+            .push(" ON CONFLICT DO NOTHING")
+            .build()
+            .execute(&mut **tx)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Serialize custom frontmatter fields for the cache (`None` when there are none)
+pub fn custom_fields_json(
+    fields: &HashMap<String, serde_json::Value>,
+) -> Result<Option<String>, DbError> {
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(fields)?))
+}
+
+/// A list of strings as a JSON array, `None` when empty
+pub fn list_json(items: &[String]) -> Result<Option<String>, DbError> {
+    if items.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(items)?))
+}
+
+/// Serialize declared template variables for the cache (`None` when there are none)
+pub fn variables_json(variables: &[TemplateVariable]) -> Result<Option<String>, DbError> {
+    if variables.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::to_string(variables)?))
+}
+
+pub async fn get_or_create_tag<'c>(
+    tx: &mut sqlx::Transaction<'c, sqlx::Sqlite>,
+    tag_name: &str,
+) -> Result<String, DbError> {
+    // Try to find existing tag
+    let existing = sqlx::query_as::<_, TagRow>(SELECT_TAG_BY_NAME)
+        .bind(tag_name)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+    if let Some(tag) = existing {
+        return Ok(tag.id);
+    }
+
+    // Create new tag
+    let id = Uuid::new_v4().to_string();
+    sqlx::query(INSERT_TAG)
+        .bind(&id)
+        .bind(tag_name)
+        .execute(&mut **tx)
+        .await?;
+
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use crate::models::SkipReason;
+    use crate::test_support::TempDir;
+    use sqlx::Row;
+
+    async fn tag_counts(pool: &DbPool) -> Vec<(String, i64)> {
+        sqlx::query(SELECT_TAG_COUNTS)
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| (row.get("name"), row.get("prompt_count")))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_sync_batches_tags() {
+        let dir = TempDir::new("prompt-manager");
+        let vault_path = dir.join("vault");
+        std::fs::create_dir_all(&vault_path).unwrap();
+        // More prompts than one batch, all sharing a tag
+        for i in 0..BATCH_ROWS + 5 {
+            std::fs::write(
+                vault_path.join(format!("p{}.md", i)),
+                format!("---\ntags: [shared, own-{}]\n---\nPrompt {}\n", i % 3, i),
+            )
+            .unwrap();
+        }
+        let pool = db::connect(&dir.join("cache.db")).await.unwrap();
+        let settings = FrontmatterSettings::default();
+
+        sync_vault(&pool, &vault_path, &settings).await.unwrap();
+        let total = (BATCH_ROWS + 5) as i64;
+        let own = |i: i64| (total + 2 - i) / 3;
+        assert_eq!(
+            tag_counts(&pool).await,
+            vec![
+                ("own-0".to_string(), own(0)),
+                ("own-1".to_string(), own(1)),
+                ("own-2".to_string(), own(2)),
+                ("shared".to_string(), total),
+            ]
+        );
+
+        // Re-syncing a changed file replaces its tags instead of adding to them
+        std::fs::write(
+            vault_path.join("p0.md"),
+            "---\ntags: [solo]\n---\nPrompt 0\n",
+        )
+        .unwrap();
+        sync_paths(&pool, &vault_path, &settings, &["p0.md".to_string()])
+            .await
+            .unwrap();
+        let counts = tag_counts(&pool).await;
+        assert!(counts.contains(&("own-0".to_string(), own(0) - 1)));
+        assert!(counts.contains(&("shared".to_string(), total - 1)));
+        assert!(counts.contains(&("solo".to_string(), 1)));
+
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_bumps_revision_on_change() {
+        let dir = TempDir::new("prompt-manager");
+        let vault_path = dir.join("vault");
+        std::fs::create_dir_all(&vault_path).unwrap();
+        std::fs::write(vault_path.join("a.md"), "First\n").unwrap();
+        let pool = db::connect(&dir.join("cache.db")).await.unwrap();
+        let settings = FrontmatterSettings::default();
+        let revision = || async {
+            sqlx::query_scalar::<_, i64>(SELECT_PROMPT_REVISION)
+                .bind("a.md")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+
+        sync_vault(&pool, &vault_path, &settings).await.unwrap();
+        assert_eq!(revision().await, 1);
+
+        // Unchanged content keeps the revision, a rewrite bumps it
+        sync_vault(&pool, &vault_path, &settings).await.unwrap();
+        assert_eq!(revision().await, 1);
+        std::fs::write(vault_path.join("a.md"), "Second\n").unwrap();
+        sync_paths(&pool, &vault_path, &settings, &["a.md".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(revision().await, 2);
+
+        pool.close().await;
+    }
+
+    #[tokio::test]
+    async fn test_sync_paths_skips_invalid_paths() {
+        let dir = TempDir::new("prompt-manager");
+        let vault_path = dir.join("vault");
+        std::fs::create_dir_all(&vault_path).unwrap();
+        std::fs::write(vault_path.join("a.md"), "First\n").unwrap();
+        std::fs::write(vault_path.join("v1..2.md"), "Second\n").unwrap();
+        let pool = db::connect(&dir.join("cache.db")).await.unwrap();
+        let settings = FrontmatterSettings::default();
+
+        // The valid path is synced, the invalid one reported
+        let paths = ["v1..2.md".to_string(), "a.md".to_string()];
+        let stats = sync_paths(&pool, &vault_path, &settings, &paths)
+            .await
+            .unwrap();
+        assert_eq!(stats.updated, 1);
+        assert_eq!(stats.skipped.len(), 1);
+        assert_eq!(stats.skipped[0].path, "v1..2.md");
+        assert_eq!(stats.skipped[0].reason, SkipReason::InvalidPath);
+        let cached = sqlx::query_scalar::<_, i64>(SELECT_PROMPT_REVISION)
+            .bind("a.md")
+            .fetch_optional(&pool)
+            .await
+            .unwrap();
+        assert!(cached.is_some());
+
+        // A batch of nothing but invalid paths is rejected
+        let result = sync_paths(&pool, &vault_path, &settings, &paths[..1]).await;
+        assert!(matches!(result, Err(DbError::InvalidInput(_))));
+
+        pool.close().await;
+    }
+}
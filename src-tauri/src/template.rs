@@ -0,0 +1,1297 @@
+//! Prompt templates with `{{variable}}` placeholders, `{{#if}}`/`{{#each}}` blocks,
+//! `{{include:path.md}}` directives and built-ins like `{{date:%Y-%m-%d}}`, `{{uuid}}`,
+//! `{{clipboard}}` and `{{env:VAR}}`
+
+use crate::config::{CopySettings, TemplateSettings};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+
+/// Deepest chain of nested includes before expanding gives up
+pub const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Most combinations one matrix render may produce
+pub const MAX_MATRIX_SIZE: usize = 1000;
+
+/// Format used by `{{date}}` without one
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Why a template could not be expanded or rendered
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum TemplateError {
+    #[error("Included prompt not found: {0}")]
+    IncludeNotFound(String),
+    #[error("Include cycle: {0}")]
+    IncludeCycle(String),
+    #[error("Includes nested more than {MAX_INCLUDE_DEPTH} deep at {0}")]
+    IncludeTooDeep(String),
+    #[error("Invalid date format: {0}")]
+    InvalidDateFormat(String),
+    #[error("Environment variable {0} is not in the template env allowlist")]
+    EnvNotAllowed(String),
+    #[error("Global variable {0} is not set")]
+    UnknownGlobal(String),
+    #[error("Unmatched {0}")]
+    UnmatchedTag(String),
+    #[error("{0} is never closed")]
+    UnclosedBlock(String),
+    #[error("Missing required variables: {}", .0.join(", "))]
+    MissingRequired(Vec<String>),
+    #[error("Invalid value for {name}: {value} (expected one of {})", .options.join(", "))]
+    InvalidOption {
+        name: String,
+        value: String,
+        options: Vec<String>,
+    },
+    #[error("No values given for {0}")]
+    EmptyValueSet(String),
+    #[error("Matrix has {0} combinations (at most {MAX_MATRIX_SIZE})")]
+    MatrixTooLarge(usize),
+    #[error("{0} has no equivalent in the target template syntax")]
+    Unconvertible(String),
+}
+
+/// A prompt with its template variables filled in
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedPrompt {
+    pub text: String,
+    /// Variables without a value; their placeholders are left in `text`
+    pub missing: Vec<String>,
+}
+
+/// One combination of a matrix render
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MatrixOutput {
+    /// The combination as `name=value` pairs, e.g. `language=rust, tone=formal`
+    pub label: String,
+    pub values: BTreeMap<String, JsonValue>,
+    pub rendered: RenderedPrompt,
+}
+
+/// A variable declared in a prompt's `variables` frontmatter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateVariable {
+    pub name: String,
+    /// Used when no value is given
+    #[serde(default)]
+    pub default: Option<JsonValue>,
+    /// Rendering fails when there is neither a value nor a default
+    #[serde(default)]
+    pub required: bool,
+    /// Allowed values; anything goes when empty
+    #[serde(default)]
+    pub options: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// What built-in functions evaluate to for one render
+#[derive(Debug, Clone)]
+pub struct Builtins {
+    pub now: DateTime<Local>,
+    /// Clipboard text for `{{clipboard}}`; left as a missing placeholder when `None`
+    pub clipboard: Option<String>,
+    /// Allowlisted environment variables (unset ones as empty strings)
+    pub env: HashMap<String, String>,
+    /// Global variables for `{{global:name}}`; unknown names are left as missing placeholders
+    pub globals: HashMap<String, String>,
+}
+
+impl Builtins {
+    /// Built-ins as of now, reading the allowlisted environment variables
+    pub fn new(
+        settings: &TemplateSettings,
+        clipboard: Option<String>,
+        globals: HashMap<String, String>,
+    ) -> Self {
+        let env = settings
+            .env_allowlist
+            .iter()
+            .map(|name| (name.clone(), std::env::var(name).unwrap_or_default()))
+            .collect();
+        Self {
+            now: Local::now(),
+            clipboard,
+            env,
+            globals,
+        }
+    }
+
+    /// Value of the built-in `name`, or `None` when it is not one
+    fn value(&self, name: &str) -> Option<Result<String, TemplateError>> {
+        if let Some(var) = name.strip_prefix("env:") {
+            let var = var.trim();
+            return Some(
+                self.env
+                    .get(var)
+                    .cloned()
+                    .ok_or_else(|| TemplateError::EnvNotAllowed(var.to_string())),
+            );
+        }
+        if let Some(global) = name.strip_prefix("global:") {
+            return self.globals.get(global.trim()).cloned().map(Ok);
+        }
+        let format = match name {
+            "uuid" => return Some(Ok(uuid::Uuid::new_v4().to_string())),
+            "clipboard" => return self.clipboard.clone().map(Ok),
+            "date" => DEFAULT_DATE_FORMAT,
+            _ => name.strip_prefix("date:")?,
+        };
+        let items: Vec<Item> = StrftimeItems::new(format).collect();
+        if items.iter().any(|item| matches!(item, Item::Error)) {
+            return Some(Err(TemplateError::InvalidDateFormat(format.to_string())));
+        }
+        Some(Ok(self
+            .now
+            .format_with_items(items.into_iter())
+            .to_string()))
+    }
+}
+
+/// Problems rendering `text` runs into whatever the variable values: malformed blocks,
+/// unset globals, env variables outside the allowlist and invalid date formats
+pub fn check(text: &str, builtins: &Builtins) -> Vec<TemplateError> {
+    if let Err(e) = parse(text) {
+        return vec![e];
+    }
+    let mut problems: Vec<TemplateError> = Vec::new();
+    for (_, tag) in tags(text) {
+        let Tag::Value(name) = tag else { continue };
+        let problem = match builtins.value(name) {
+            Some(Err(e)) => e,
+            None => match name.strip_prefix("global:") {
+                Some(global) => TemplateError::UnknownGlobal(global.trim().to_string()),
+                None => continue,
+            },
+            Some(Ok(_)) => continue,
+        };
+        if !problems
+            .iter()
+            .any(|p| p.to_string() == problem.to_string())
+        {
+            problems.push(problem);
+        }
+    }
+    problems
+}
+
+/// Whether `text` uses `{{clipboard}}`, so the clipboard only gets read when needed
+pub fn uses_clipboard(text: &str) -> bool {
+    tags(text)
+        .iter()
+        .any(|(_, tag)| *tag == Tag::Value("clipboard"))
+}
+
+/// Names of the variables used in `text`, including block conditions, in order of first
+/// use (built-ins and loop items excluded)
+pub fn variables(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    for (_, tag) in tags(text) {
+        let name = match tag {
+            Tag::Value(name) | Tag::Open(_, name) => name,
+            Tag::Else | Tag::Close(_) => continue,
+        };
+        if is_builtin(name) || is_loop_name(name) {
+            continue;
+        }
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Variables used outside `{{#each}}` blocks (inside them names may be item fields) that
+/// `declared` does not list, in order of first use
+pub fn undeclared(text: &str, declared: &[TemplateVariable]) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut loops = 0usize;
+    for (_, tag) in tags(text) {
+        let (name, depth) = match tag {
+            Tag::Open(Block::Each, name) => {
+                loops += 1;
+                (name, loops - 1)
+            }
+            Tag::Close(Block::Each) => {
+                loops = loops.saturating_sub(1);
+                continue;
+            }
+            Tag::Value(name) | Tag::Open(Block::If, name) => (name, loops),
+            Tag::Else | Tag::Close(Block::If) => continue,
+        };
+        let root = name.split('.').next().unwrap_or(name);
+        let is_declared = declared.iter().any(|v| v.name == name || v.name == root);
+        if depth > 0 || is_declared || is_builtin(name) || is_loop_name(name) {
+            continue;
+        }
+        if !names.iter().any(|existing| existing == name) {
+            names.push(name.to_string());
+        }
+    }
+    names
+}
+
+/// Replace `{{name}}` placeholders with `values`, falling back to `builtins`, and evaluate
+/// `{{#if name}}...{{else}}...{{/if}}` and `{{#each name}}...{{/each}}` blocks
+pub fn render(
+    text: &str,
+    values: &HashMap<String, JsonValue>,
+    builtins: &Builtins,
+) -> Result<RenderedPrompt, TemplateError> {
+    let nodes = parse(text)?;
+    let mut renderer = Renderer {
+        values,
+        builtins,
+        output: String::with_capacity(text.len()),
+        missing: Vec::new(),
+    };
+    renderer.render(&nodes, &mut Vec::new())?;
+
+    Ok(RenderedPrompt {
+        text: renderer.output,
+        missing: renderer.missing,
+    })
+}
+
+/// Render `text` once per combination of `value_sets` (cartesian product, the last variable
+/// by name varying fastest), applying `declared` to each combination
+pub fn render_matrix(
+    text: &str,
+    declared: &[TemplateVariable],
+    value_sets: &BTreeMap<String, Vec<JsonValue>>,
+    builtins: &Builtins,
+) -> Result<Vec<MatrixOutput>, TemplateError> {
+    let mut size = 1usize;
+    for (name, set) in value_sets {
+        if set.is_empty() {
+            return Err(TemplateError::EmptyValueSet(name.clone()));
+        }
+        size = size.saturating_mul(set.len());
+    }
+    if size > MAX_MATRIX_SIZE {
+        return Err(TemplateError::MatrixTooLarge(size));
+    }
+
+    let mut combinations = vec![BTreeMap::new()];
+    for (name, set) in value_sets {
+        combinations = combinations
+            .into_iter()
+            .flat_map(|combination| {
+                set.iter().map(move |value| {
+                    let mut next = combination.clone();
+                    next.insert(name.clone(), value.clone());
+                    next
+                })
+            })
+            .collect();
+    }
+
+    combinations
+        .into_iter()
+        .map(|values: BTreeMap<String, JsonValue>| {
+            let mut merged: HashMap<String, JsonValue> = values
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            apply_declared(declared, &mut merged)?;
+            let rendered = render(text, &merged, builtins)?;
+            let label = values
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value_text(value)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Ok(MatrixOutput {
+                label,
+                values,
+                rendered,
+            })
+        })
+        .collect()
+}
+
+/// Fill in defaults of `declared` variables without a value and check required and
+/// allowed values. `null` and empty strings count as no value.
+pub fn apply_declared(
+    declared: &[TemplateVariable],
+    values: &mut HashMap<String, JsonValue>,
+) -> Result<(), TemplateError> {
+    let mut missing = Vec::new();
+    for variable in declared {
+        if !values.get(&variable.name).is_some_and(has_value) {
+            match &variable.default {
+                Some(default) => {
+                    values.insert(variable.name.clone(), default.clone());
+                }
+                None => {
+                    if variable.required {
+                        missing.push(variable.name.clone());
+                    }
+                    continue;
+                }
+            }
+        }
+        let value = value_text(&values[&variable.name]);
+        if !variable.options.is_empty() && !variable.options.contains(&value) {
+            return Err(TemplateError::InvalidOption {
+                name: variable.name.clone(),
+                value,
+                options: variable.options.clone(),
+            });
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(TemplateError::MissingRequired(missing))
+    }
+}
+
+fn has_value(value: &JsonValue) -> bool {
+    !matches!(value, JsonValue::Null) && value.as_str() != Some("")
+}
+
+/// Block helpers: `{{#if}}` and `{{#each}}`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Block {
+    If,
+    Each,
+}
+
+impl Block {
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "if" => Some(Block::If),
+            "each" => Some(Block::Each),
+            _ => None,
+        }
+    }
+
+    fn keyword(self) -> &'static str {
+        match self {
+            Block::If => "if",
+            Block::Each => "each",
+        }
+    }
+}
+
+/// A `{{...}}` tag the renderer understands
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Tag<'a> {
+    /// `{{name}}`: a variable, loop item or built-in
+    Value(&'a str),
+    /// `{{#if name}}` or `{{#each name}}`
+    Open(Block, &'a str),
+    Else,
+    /// `{{/if}}` or `{{/each}}`
+    Close(Block),
+}
+
+/// Parsed template
+#[derive(Debug)]
+enum Node<'a> {
+    Text(&'a str),
+    /// Placeholder name and its source, kept as is when there is no value
+    Value(&'a str, &'a str),
+    Block {
+        block: Block,
+        name: &'a str,
+        body: Vec<Node<'a>>,
+        otherwise: Vec<Node<'a>>,
+    },
+}
+
+/// Block still waiting for its closing tag while parsing
+struct OpenBlock<'a> {
+    block: Block,
+    name: &'a str,
+    body: Vec<Node<'a>>,
+    /// Started by `{{else}}`
+    otherwise: Option<Vec<Node<'a>>>,
+}
+
+impl<'a> OpenBlock<'a> {
+    fn nodes(&mut self) -> &mut Vec<Node<'a>> {
+        match &mut self.otherwise {
+            Some(otherwise) => otherwise,
+            None => &mut self.body,
+        }
+    }
+}
+
+fn parse(text: &str) -> Result<Vec<Node<'_>>, TemplateError> {
+    let mut root: Vec<Node> = Vec::new();
+    let mut open: Vec<OpenBlock> = Vec::new();
+    let mut last = 0;
+
+    for (range, tag) in tags(text) {
+        let source = &text[range.clone()];
+        let nodes = match open.last_mut() {
+            Some(block) => block.nodes(),
+            None => &mut root,
+        };
+        if range.start > last {
+            nodes.push(Node::Text(&text[last..range.start]));
+        }
+        last = range.end;
+
+        match tag {
+            Tag::Value(name) => nodes.push(Node::Value(name, source)),
+            // Outside a block `else` is an ordinary variable
+            Tag::Else => match open.last_mut() {
+                Some(block) if block.otherwise.is_none() => block.otherwise = Some(Vec::new()),
+                Some(_) => return Err(TemplateError::UnmatchedTag(source.to_string())),
+                None => root.push(Node::Value("else", source)),
+            },
+            Tag::Open(block, name) => open.push(OpenBlock {
+                block,
+                name,
+                body: Vec::new(),
+                otherwise: None,
+            }),
+            Tag::Close(block) => {
+                let closed = open
+                    .pop()
+                    .filter(|open| open.block == block)
+                    .ok_or_else(|| TemplateError::UnmatchedTag(source.to_string()))?;
+                let node = Node::Block {
+                    block,
+                    name: closed.name,
+                    body: closed.body,
+                    otherwise: closed.otherwise.unwrap_or_default(),
+                };
+                match open.last_mut() {
+                    Some(parent) => parent.nodes().push(node),
+                    None => root.push(node),
+                }
+            }
+        }
+    }
+    if let Some(block) = open.pop() {
+        return Err(TemplateError::UnclosedBlock(format!(
+            "{{{{#{} {}}}}}",
+            block.block.keyword(),
+            block.name
+        )));
+    }
+    if last < text.len() {
+        root.push(Node::Text(&text[last..]));
+    }
+    Ok(root)
+}
+
+/// Item of an enclosing `{{#each}}`
+struct LoopScope<'v> {
+    item: &'v JsonValue,
+    index: usize,
+}
+
+struct Renderer<'v> {
+    values: &'v HashMap<String, JsonValue>,
+    builtins: &'v Builtins,
+    output: String,
+    missing: Vec<String>,
+}
+
+impl<'v> Renderer<'v> {
+    fn render(
+        &mut self,
+        nodes: &[Node],
+        scopes: &mut Vec<LoopScope<'v>>,
+    ) -> Result<(), TemplateError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.output.push_str(text),
+                Node::Value(name, source) => {
+                    let value = match (*name, scopes.last()) {
+                        ("@index", Some(scope)) => Some(scope.index.to_string()),
+                        _ => match self.lookup(name, scopes) {
+                            Some(value) => Some(value_text(value)),
+                            None => self.builtins.value(name).transpose()?,
+                        },
+                    };
+                    match value {
+                        Some(value) => self.output.push_str(&value),
+                        None => {
+                            self.output.push_str(source);
+                            if !self.missing.iter().any(|existing| existing == name) {
+                                self.missing.push(name.to_string());
+                            }
+                        }
+                    }
+                }
+                Node::Block {
+                    block: Block::If,
+                    name,
+                    body,
+                    otherwise,
+                } => {
+                    let branch = if self.lookup(name, scopes).is_some_and(is_truthy) {
+                        body
+                    } else {
+                        otherwise
+                    };
+                    self.render(branch, scopes)?;
+                }
+                Node::Block {
+                    block: Block::Each,
+                    name,
+                    body,
+                    otherwise,
+                } => {
+                    let items = match self.lookup(name, scopes) {
+                        Some(JsonValue::Array(items)) if !items.is_empty() => items,
+                        _ => {
+                            self.render(otherwise, scopes)?;
+                            continue;
+                        }
+                    };
+                    for (index, item) in items.iter().enumerate() {
+                        scopes.push(LoopScope { item, index });
+                        let rendered = self.render(body, scopes);
+                        scopes.pop();
+                        rendered?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Value of `name`: `this` is the current loop item, other names are looked up in
+    /// loop items from the innermost out, then in `values`; dots reach into objects and arrays
+    fn lookup(&self, name: &str, scopes: &[LoopScope<'v>]) -> Option<&'v JsonValue> {
+        if name == "this" {
+            return scopes.last().map(|scope| scope.item);
+        }
+        if let Some(path) = name.strip_prefix("this.") {
+            return scopes.last().and_then(|scope| field(scope.item, path));
+        }
+        if let Some(value) = scopes
+            .iter()
+            .rev()
+            .find_map(|scope| field(scope.item, name))
+        {
+            return Some(value);
+        }
+        // Flat names with dots (`user.name`) win over paths into objects
+        self.values.get(name).or_else(|| {
+            let (first, rest) = name.split_once('.')?;
+            field(self.values.get(first)?, rest)
+        })
+    }
+}
+
+/// Value at a dot-separated path of object keys and array indices in `value`
+fn field<'v>(value: &'v JsonValue, path: &str) -> Option<&'v JsonValue> {
+    path.split('.').try_fold(value, |value, key| match value {
+        JsonValue::Object(map) => map.get(key),
+        JsonValue::Array(items) => items.get(key.parse::<usize>().ok()?),
+        _ => None,
+    })
+}
+
+/// Text a value renders as: strings as is, `null` as nothing, anything else as JSON
+fn value_text(value: &JsonValue) -> String {
+    match value {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Whether `{{#if}}` takes its first branch: not `false`, `null`, `0`, `""`, `[]` or `{}`
+fn is_truthy(value: &JsonValue) -> bool {
+    match value {
+        JsonValue::Null => false,
+        JsonValue::Bool(value) => *value,
+        JsonValue::Number(number) => number.as_f64() != Some(0.0),
+        JsonValue::String(text) => !text.is_empty(),
+        JsonValue::Array(items) => !items.is_empty(),
+        JsonValue::Object(map) => !map.is_empty(),
+    }
+}
+
+/// Template syntax of another tool that prompts can be converted to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dialect {
+    /// `{{ name }}` with `{% if %}` and `{% for %}` blocks (Nunjucks, used by promptfoo)
+    Nunjucks,
+    /// Python `{name}` placeholders (LangChain's default); no blocks or dotted names
+    FString,
+    /// `{{{name}}}` with `{{#name}}` sections (LangChain's mustache format); no `@index`
+    Mustache,
+}
+
+/// A template rewritten in another syntax, with the variables it expects
+#[derive(Debug, Clone, PartialEq)]
+pub struct Converted {
+    pub text: String,
+    /// Variables used outside loops (the first segment of dotted names), in order of first use
+    pub variables: Vec<String>,
+}
+
+/// Rewrite `text` in another template syntax. Built-ins and globals become ordinary
+/// variables with characters those tools don't accept replaced by `_`
+/// (`{{global:author}}` becomes `global_author`).
+pub fn convert(text: &str, dialect: Dialect) -> Result<Converted, TemplateError> {
+    let nodes = parse(text)?;
+    let mut converter = Converter {
+        dialect,
+        output: String::with_capacity(text.len()),
+        variables: Vec::new(),
+    };
+    converter.convert(&nodes, 0)?;
+    Ok(Converted {
+        text: converter.output,
+        variables: converter.variables,
+    })
+}
+
+struct Converter {
+    dialect: Dialect,
+    output: String,
+    variables: Vec<String>,
+}
+
+impl Converter {
+    /// `depth` is the number of enclosing `{{#each}}` blocks
+    fn convert(&mut self, nodes: &[Node], depth: usize) -> Result<(), TemplateError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.text(text),
+                Node::Value(name, source) => {
+                    let name = self.name(name, depth, source)?;
+                    let tag = match self.dialect {
+                        Dialect::Nunjucks => format!("{{{{ {} }}}}", name),
+                        Dialect::FString => format!("{{{}}}", name),
+                        Dialect::Mustache => format!("{{{{{{{}}}}}}}", name),
+                    };
+                    self.output.push_str(&tag);
+                }
+                Node::Block {
+                    block,
+                    name,
+                    body,
+                    otherwise,
+                } => {
+                    let source = format!("{{{{#{} {}}}}}", block.keyword(), name);
+                    if self.dialect == Dialect::FString {
+                        return Err(TemplateError::Unconvertible(source));
+                    }
+                    let target = self.name(name, depth, &source)?;
+                    let inner = match block {
+                        Block::If => depth,
+                        Block::Each => depth + 1,
+                    };
+                    if self.dialect == Dialect::Nunjucks {
+                        let (open, close) = match block {
+                            Block::If => (format!("{{% if {} %}}", target), "{% endif %}"),
+                            Block::Each => (
+                                format!("{{% for {} in {} %}}", loop_item(inner), target),
+                                "{% endfor %}",
+                            ),
+                        };
+                        self.output.push_str(&open);
+                        self.convert(body, inner)?;
+                        if !otherwise.is_empty() {
+                            self.output.push_str("{% else %}");
+                            self.convert(otherwise, depth)?;
+                        }
+                        self.output.push_str(close);
+                    } else {
+                        self.output.push_str(&format!("{{{{#{}}}}}", target));
+                        self.convert(body, inner)?;
+                        self.output.push_str(&format!("{{{{/{}}}}}", target));
+                        if !otherwise.is_empty() {
+                            self.output.push_str(&format!("{{{{^{}}}}}", target));
+                            self.convert(otherwise, depth)?;
+                            self.output.push_str(&format!("{{{{/{}}}}}", target));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn text(&mut self, text: &str) {
+        match self.dialect {
+            Dialect::Nunjucks if ["{{", "{%", "{#"].iter().any(|s| text.contains(s)) => {
+                self.output.push_str("{% raw %}");
+                self.output.push_str(text);
+                self.output.push_str("{% endraw %}");
+            }
+            Dialect::FString => self
+                .output
+                .push_str(&text.replace('{', "{{").replace('}', "}}")),
+            _ => self.output.push_str(text),
+        }
+    }
+
+    /// `name` as the target syntax refers to it, recording variables used outside loops
+    fn name(&mut self, name: &str, depth: usize, source: &str) -> Result<String, TemplateError> {
+        if depth > 0 && is_loop_name(name) {
+            return match self.dialect {
+                Dialect::Nunjucks if name == "@index" => Ok("loop.index0".to_string()),
+                Dialect::Nunjucks => Ok(name.replacen("this", &loop_item(depth), 1)),
+                Dialect::Mustache if name == "this" => Ok(".".to_string()),
+                Dialect::Mustache if name != "@index" => Ok(name["this.".len()..].to_string()),
+                _ => Err(TemplateError::Unconvertible(source.to_string())),
+            };
+        }
+
+        let id: String = name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, '_' | '.') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if self.dialect == Dialect::FString && id.contains('.') {
+            return Err(TemplateError::Unconvertible(source.to_string()));
+        }
+        if depth == 0 {
+            let root = id.split('.').next().unwrap_or(&id);
+            if !self.variables.iter().any(|existing| existing == root) {
+                self.variables.push(root.to_string());
+            }
+            return Ok(id);
+        }
+        // Inside loops names are looked up in the items first, innermost out
+        Ok(match self.dialect {
+            Dialect::Nunjucks => (1..=depth).fold(id.clone(), |fallback, level| {
+                let field = format!("{}.{}", loop_item(level), id);
+                format!("({} if {} is defined else {})", field, field, fallback)
+            }),
+            _ => id,
+        })
+    }
+}
+
+/// Nunjucks loop variable of the `{{#each}}` at `depth`
+fn loop_item(depth: usize) -> String {
+    match depth {
+        1 => "item".to_string(),
+        _ => format!("item{}", depth),
+    }
+}
+
+/// Vault-relative paths of the prompts `text` includes directly, in order of first use
+pub fn includes(text: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for (_, path) in include_directives(text) {
+        if !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Replace `{{include:path.md}}` directives in the text of prompt `id` with the text of
+/// the included prompts (looked up by vault-relative path in `texts`), recursively
+pub fn expand_includes(
+    text: &str,
+    id: &str,
+    texts: &HashMap<String, String>,
+) -> Result<String, TemplateError> {
+    let mut chain = vec![id.to_string()];
+    expand(text, texts, &mut chain)
+}
+
+fn expand(
+    text: &str,
+    texts: &HashMap<String, String>,
+    chain: &mut Vec<String>,
+) -> Result<String, TemplateError> {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for (range, path) in include_directives(text) {
+        output.push_str(&text[last..range.start]);
+        if chain.contains(&path) {
+            return Err(TemplateError::IncludeCycle(format!(
+                "{} -> {}",
+                chain.join(" -> "),
+                path
+            )));
+        }
+        if chain.len() > MAX_INCLUDE_DEPTH {
+            return Err(TemplateError::IncludeTooDeep(path));
+        }
+        let included = texts
+            .get(&path)
+            .ok_or_else(|| TemplateError::IncludeNotFound(path.clone()))?;
+        chain.push(path);
+        output.push_str(&expand(included, texts, chain)?);
+        chain.pop();
+        last = range.end;
+    }
+    output.push_str(&text[last..]);
+    Ok(output)
+}
+
+/// Point `{{include:...}}` directives at new paths, for included prompts that were moved
+/// (`renames` maps old to new vault-relative paths)
+pub fn rename_includes(text: &str, renames: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut last = 0;
+    for (range, path) in include_directives(text) {
+        if let Some(renamed) = renames.get(&path) {
+            output.push_str(&text[last..range.start]);
+            output.push_str(&format!("{{{{include:{}}}}}", renamed));
+            last = range.end;
+        }
+    }
+    output.push_str(&text[last..]);
+    output
+}
+
+/// Apply the whitespace and wrapping preferences for copied prompts
+pub fn format_for_copy(text: &str, settings: &CopySettings) -> String {
+    let body = if settings.trim_whitespace {
+        let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+        lines.join("\n").trim_matches('\n').to_string()
+    } else {
+        text.to_string()
+    };
+    format!("{}{}{}", settings.wrap_prefix, body, settings.wrap_suffix)
+}
+
+/// Byte range of every well-formed tag
+fn tags(text: &str) -> Vec<(std::ops::Range<usize>, Tag<'_>)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|i| offset + i) {
+        let Some(end) = text[start + 2..].find("}}").map(|i| start + 2 + i) else {
+            break;
+        };
+        match parse_tag(text[start + 2..end].trim()) {
+            Some(tag) => {
+                found.push((start..end + 2, tag));
+                offset = end + 2;
+            }
+            None => offset = start + 2,
+        }
+    }
+    found
+}
+
+fn parse_tag(inner: &str) -> Option<Tag<'_>> {
+    if inner == "else" {
+        return Some(Tag::Else);
+    }
+    if let Some(rest) = inner.strip_prefix('#') {
+        let (keyword, name) = rest.split_once(char::is_whitespace)?;
+        let name = name.trim();
+        let block = Block::from_keyword(keyword)?;
+        return is_variable_name(name).then_some(Tag::Open(block, name));
+    }
+    if let Some(keyword) = inner.strip_prefix('/') {
+        return Block::from_keyword(keyword.trim()).map(Tag::Close);
+    }
+    (is_variable_name(inner) || is_builtin(inner) || inner == "@index").then_some(Tag::Value(inner))
+}
+
+/// Byte range and normalized path of every `{{include:path}}` directive
+fn include_directives(text: &str) -> Vec<(std::ops::Range<usize>, String)> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("{{").map(|i| offset + i) {
+        let Some(end) = text[start + 2..].find("}}").map(|i| start + 2 + i) else {
+            break;
+        };
+        let directive = text[start + 2..end].trim();
+        match directive.strip_prefix("include:") {
+            Some(path) if !path.contains('\n') => {
+                found.push((start..end + 2, include_path(path)));
+                offset = end + 2;
+            }
+            _ => offset = start + 2,
+        }
+    }
+    found
+}
+
+/// Vault-relative id an include path refers to (leading `./` dropped, backslashes as `/`,
+/// `.md` implied)
+fn include_path(path: &str) -> String {
+    let path = path.trim().replace('\\', "/");
+    let path = path.trim_start_matches("./");
+    if path.ends_with(".md") {
+        path.to_string()
+    } else {
+        format!("{}.md", path)
+    }
+}
+
+fn is_builtin(name: &str) -> bool {
+    matches!(name, "date" | "uuid" | "clipboard")
+        || ["date:", "env:", "global:"].iter().any(|prefix| {
+            name.strip_prefix(prefix)
+                .is_some_and(|rest| !rest.contains('\n'))
+        })
+}
+
+/// `this`, `this.field` and `@index`, which only have values inside `{{#each}}`
+fn is_loop_name(name: &str) -> bool {
+    name == "this" || name == "@index" || name.starts_with("this.")
+}
+
+/// Whether `name` can be used as `{{name}}` (letters, digits, `_`, `-` and `.`)
+pub fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render() {
+        let values = HashMap::from([("language".to_string(), JsonValue::from("Rust"))]);
+        let rendered = render(
+            "Review this {{language}} code for {{ focus }}. {{language}}! {{not a var}}",
+            &values,
+            &builtins(),
+        )
+        .unwrap();
+        assert_eq!(
+            rendered.text,
+            "Review this Rust code for {{ focus }}. Rust! {{not a var}}"
+        );
+        assert_eq!(rendered.missing, vec!["focus"]);
+    }
+
+    #[test]
+    fn test_format_for_copy() {
+        let mut settings = CopySettings::default();
+        assert_eq!(
+            format_for_copy("\n\n  Indented  \nnext\t\n\n", &settings),
+            "  Indented\nnext"
+        );
+
+        settings.trim_whitespace = false;
+        settings.wrap_prefix = "```\n".to_string();
+        settings.wrap_suffix = "```".to_string();
+        assert_eq!(format_for_copy("text\n", &settings), "```\ntext\n```");
+    }
+
+    #[test]
+    fn test_expand_includes() {
+        let texts = HashMap::from([
+            ("blocks/tone.md".to_string(), "Be {{tone}}.".to_string()),
+            (
+                "blocks/rules.md".to_string(),
+                "Rules:\n{{ include: ./blocks/tone.md }}".to_string(),
+            ),
+            ("loop/a.md".to_string(), "{{include:loop/b}}".to_string()),
+            ("loop/b.md".to_string(), "{{include:loop/a.md}}".to_string()),
+        ]);
+        let text = "System.\n{{include:blocks/rules.md}}\n{{include:blocks/tone.md}}";
+        assert_eq!(
+            includes(text),
+            vec!["blocks/rules.md".to_string(), "blocks/tone.md".to_string()]
+        );
+        assert_eq!(
+            expand_includes(text, "system.md", &texts).unwrap(),
+            "System.\nRules:\nBe {{tone}}.\nBe {{tone}}."
+        );
+
+        let renames =
+            HashMap::from([("blocks/tone.md".to_string(), "blocks/tone-2.md".to_string())]);
+        assert_eq!(
+            rename_includes(text, &renames),
+            "System.\n{{include:blocks/rules.md}}\n{{include:blocks/tone-2.md}}"
+        );
+
+        let error = expand_includes("{{include:loop/a.md}}", "main.md", &texts).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Include cycle: main.md -> loop/a.md -> loop/b.md -> loop/a.md"
+        );
+        assert!(matches!(
+            expand_includes("{{include:missing.md}}", "main.md", &texts),
+            Err(TemplateError::IncludeNotFound(path)) if path == "missing.md"
+        ));
+
+        // A chain of distinct prompts deeper than the limit
+        let mut deep = HashMap::new();
+        for level in 0..=MAX_INCLUDE_DEPTH {
+            deep.insert(
+                format!("{}.md", level),
+                format!("{{{{include:{}.md}}}}", level + 1),
+            );
+        }
+        assert!(matches!(
+            expand_includes("{{include:0.md}}", "main.md", &deep),
+            Err(TemplateError::IncludeTooDeep(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_builtins() {
+        let rendered = render(
+            "{{date}} {{ date:%H:%M }} {{env:HOME}} {{global:author}} {{global:team}} {{clipboard}} {{uuid}}",
+            &HashMap::new(),
+            &builtins(),
+        )
+        .unwrap();
+        let (fixed, uuid) = rendered.text.rsplit_once(' ').unwrap();
+        assert_eq!(
+            fixed,
+            "2024-03-05 14:07 /home/me Ann {{global:team}} {{clipboard}}"
+        );
+        assert!(uuid::Uuid::parse_str(uuid).is_ok());
+        assert_eq!(rendered.missing, vec!["global:team", "clipboard"]);
+        assert!(uses_clipboard("Summarize: {{ clipboard }}"));
+
+        // Explicit values win over built-ins
+        let values = HashMap::from([("date".to_string(), JsonValue::from("yesterday"))]);
+        assert_eq!(
+            render("{{date}}", &values, &builtins()).unwrap().text,
+            "yesterday"
+        );
+
+        assert!(matches!(
+            render("{{env:SECRET}}", &HashMap::new(), &builtins()),
+            Err(TemplateError::EnvNotAllowed(name)) if name == "SECRET"
+        ));
+        assert!(matches!(
+            render("{{date:%Q}}", &HashMap::new(), &builtins()),
+            Err(TemplateError::InvalidDateFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_render_blocks() {
+        let values: HashMap<String, JsonValue> = serde_json::from_str(
+            r#"{
+                "strict": true,
+                "tests": false,
+                "files": [{"path": "a.rs", "lines": 3}, {"path": "b.rs", "lines": 0}],
+                "user": {"name": "Ann"},
+                "user.name": "flat"
+            }"#,
+        )
+        .unwrap();
+        let text = "{{#if strict}}Be strict.{{else}}Be kind.{{/if}}\
+            {{#if tests}} Add tests.{{else}} No tests.{{/if}}\
+            {{#each files}} {{@index}}:{{path}}{{#if lines}}({{this.lines}}){{/if}}{{/each}}\
+            {{#each none}}x{{else}} empty{{/each}} {{user.name}} {{user.missing}}";
+        let rendered = render(text, &values, &builtins()).unwrap();
+        assert_eq!(
+            rendered.text,
+            "Be strict. No tests. 0:a.rs(3) 1:b.rs empty flat {{user.missing}}"
+        );
+        assert_eq!(rendered.missing, vec!["user.missing"]);
+        assert_eq!(
+            variables(text),
+            vec![
+                "strict",
+                "tests",
+                "files",
+                "path",
+                "lines",
+                "none",
+                "user.name",
+                "user.missing"
+            ]
+        );
+
+        // Outside a block `else` is just a variable
+        let rendered = render("{{else}}", &HashMap::new(), &builtins()).unwrap();
+        assert_eq!(rendered.missing, vec!["else"]);
+
+        assert!(matches!(
+            render("{{#if a}}{{#each b}}{{/if}}", &values, &builtins()),
+            Err(TemplateError::UnmatchedTag(tag)) if tag == "{{/if}}"
+        ));
+        assert_eq!(
+            render("{{#each files}}", &values, &builtins())
+                .unwrap_err()
+                .to_string(),
+            "{{#each files}} is never closed"
+        );
+    }
+
+    #[test]
+    fn test_apply_declared() {
+        let declared: Vec<TemplateVariable> = serde_json::from_str(
+            r#"[
+                {"name": "language", "default": "rust", "options": ["rust", "python"]},
+                {"name": "code", "required": true},
+                {"name": "focus"}
+            ]"#,
+        )
+        .unwrap();
+
+        let mut values = HashMap::from([
+            ("code".to_string(), JsonValue::from("fn main() {}")),
+            ("language".to_string(), JsonValue::from("")),
+        ]);
+        apply_declared(&declared, &mut values).unwrap();
+        assert_eq!(values["language"], JsonValue::from("rust"));
+        assert!(!values.contains_key("focus"));
+
+        values.insert("language".to_string(), JsonValue::from("go"));
+        assert_eq!(
+            apply_declared(&declared, &mut values)
+                .unwrap_err()
+                .to_string(),
+            "Invalid value for language: go (expected one of rust, python)"
+        );
+
+        let mut values = HashMap::from([("code".to_string(), JsonValue::Null)]);
+        assert!(matches!(
+            apply_declared(&declared, &mut values),
+            Err(TemplateError::MissingRequired(names)) if names == vec!["code"]
+        ));
+    }
+
+    #[test]
+    fn test_render_matrix() {
+        let declared: Vec<TemplateVariable> =
+            serde_json::from_str(r#"[{"name": "tone", "default": "neutral"}]"#).unwrap();
+        let value_sets = BTreeMap::from([
+            (
+                "language".to_string(),
+                vec![JsonValue::from("Rust"), JsonValue::from("Go")],
+            ),
+            (
+                "level".to_string(),
+                vec![JsonValue::from(1), JsonValue::from(2)],
+            ),
+        ]);
+        let outputs = render_matrix(
+            "{{language}} {{level}} {{tone}}",
+            &declared,
+            &value_sets,
+            &builtins(),
+        )
+        .unwrap();
+        assert_eq!(
+            outputs
+                .iter()
+                .map(|o| (o.label.as_str(), o.rendered.text.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("language=Rust, level=1", "Rust 1 neutral"),
+                ("language=Rust, level=2", "Rust 2 neutral"),
+                ("language=Go, level=1", "Go 1 neutral"),
+                ("language=Go, level=2", "Go 2 neutral"),
+            ]
+        );
+        assert_eq!(outputs[3].values["level"], JsonValue::from(2));
+
+        let empty = BTreeMap::from([("language".to_string(), Vec::new())]);
+        assert!(matches!(
+            render_matrix("{{language}}", &[], &empty, &builtins()),
+            Err(TemplateError::EmptyValueSet(name)) if name == "language"
+        ));
+        let huge: BTreeMap<String, Vec<JsonValue>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|name| (name.to_string(), (0..10).map(JsonValue::from).collect()))
+            .collect();
+        assert!(matches!(
+            render_matrix("", &[], &huge, &builtins()),
+            Err(TemplateError::MatrixTooLarge(10000))
+        ));
+    }
+
+    #[test]
+    fn test_convert() {
+        let text = "Review {{language}} {code} by {{global:author}}\n\
+                    {{#each files}}{{@index}}: {{this.path}} ({{language}}){{else}}none{{/each}}\
+                    {{#if notes}}Notes: {{notes.text}}{{/if}}";
+
+        let nunjucks = convert(text, Dialect::Nunjucks).unwrap();
+        assert_eq!(
+            nunjucks.text,
+            "Review {{ language }} {code} by {{ global_author }}\n\
+             {% for item in files %}{{ loop.index0 }}: {{ item.path }} \
+             ({{ (item.language if item.language is defined else language) }})\
+             {% else %}none{% endfor %}{% if notes %}Notes: {{ notes.text }}{% endif %}"
+        );
+        assert_eq!(
+            nunjucks.variables,
+            vec!["language", "global_author", "files", "notes"]
+        );
+
+        assert!(matches!(
+            convert(text, Dialect::Mustache),
+            Err(TemplateError::Unconvertible(tag)) if tag == "{{@index}}"
+        ));
+        let mustache = convert(
+            "{{#each files}}{{this}} {{this.path}}{{/each}}",
+            Dialect::Mustache,
+        );
+        assert_eq!(
+            mustache.unwrap().text,
+            "{{#files}}{{{.}}} {{{path}}}{{/files}}"
+        );
+
+        let fstring = convert(
+            "Review {{language}} {code} as {{user-name}}",
+            Dialect::FString,
+        );
+        assert_eq!(
+            fstring.unwrap(),
+            Converted {
+                text: "Review {language} {{code}} as {user_name}".to_string(),
+                variables: vec!["language".to_string(), "user_name".to_string()],
+            }
+        );
+        assert!(convert(text, Dialect::FString).is_err());
+        assert!(convert("{{user.name}}", Dialect::FString).is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let problems = check(
+            "{{global:author}} {{global:team}} {{env:SECRET}} {{date:%Q}} {{global:team}} {{x}}",
+            &builtins(),
+        );
+        assert_eq!(
+            problems.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+            vec![
+                "Global variable team is not set",
+                "Environment variable SECRET is not in the template env allowlist",
+                "Invalid date format: %Q",
+            ]
+        );
+        assert!(matches!(
+            check("{{#if a}}", &builtins()).as_slice(),
+            [TemplateError::UnclosedBlock(_)]
+        ));
+    }
+
+    #[test]
+    fn test_variables() {
+        assert_eq!(
+            variables("{{a}} {{ b }} {{a}} {{}} {{c {{uuid}} {{date:%Y}}"),
+            vec!["a".to_string(), "b".to_string()]
+        );
+    }
+
+    fn builtins() -> Builtins {
+        Builtins {
+            now: Local.with_ymd_and_hms(2024, 3, 5, 14, 7, 0).unwrap(),
+            clipboard: None,
+            env: HashMap::from([("HOME".to_string(), "/home/me".to_string())]),
+            globals: HashMap::from([("author".to_string(), "Ann".to_string())]),
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Token counts for prompt texts, using OpenAI's BPE tokenizers, and their plain character,
+//! word and line counts
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tiktoken_rs::tokenizer::{self, Tokenizer};
+use tiktoken_rs::CoreBPE;
+
+/// Model assumed when none is given (and for the counts stored during sync)
+pub const DEFAULT_MODEL: &str = "gpt-4";
+
+/// Token count of a text for a given model - returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenCount {
+    pub tokens: u32,
+    pub model: String,
+    /// Context window of the model, in tokens
+    pub context_window: u32,
+}
+
+/// Number of tokens in `text` for `model`.
+/// Models tiktoken does not know (Llama, Claude, ...) fall back to `cl100k_base`, which is close enough for a warning.
+pub fn count(text: &str, model: Option<&str>) -> usize {
+    encoder(model.unwrap_or(DEFAULT_MODEL))
+        .encode_with_special_tokens(text)
+        .len()
+}
+
+/// Count tokens and report them against the model's context window
+pub fn count_for_model(text: &str, model: Option<&str>) -> TokenCount {
+    let model = model
+        .map(str::trim)
+        .filter(|model| !model.is_empty())
+        .unwrap_or(DEFAULT_MODEL);
+    TokenCount {
+        tokens: count(text, Some(model)) as u32,
+        model: model.to_string(),
+        context_window: tiktoken_rs::model::get_context_size(model) as u32,
+    }
+}
+
+/// Characters, words and lines of a text, as stored in the cache during sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextCounts {
+    pub chars: i64,
+    /// Runs of non-whitespace
+    pub words: i64,
+    /// A final line break does not start another line
+    pub lines: i64,
+}
+
+pub fn text_counts(text: &str) -> TextCounts {
+    TextCounts {
+        chars: text.chars().count() as i64,
+        words: text.split_whitespace().count() as i64,
+        lines: text.lines().count() as i64,
+    }
+}
+
+fn encoder(model: &str) -> &'static CoreBPE {
+    match tokenizer::get_tokenizer(model) {
+        Some(Tokenizer::O200kBase) => tiktoken_rs::o200k_base_singleton(),
+        Some(Tokenizer::P50kBase) => tiktoken_rs::p50k_base_singleton(),
+        Some(Tokenizer::P50kEdit) => tiktoken_rs::p50k_edit_singleton(),
+        Some(Tokenizer::R50kBase | Tokenizer::Gpt2) => tiktoken_rs::r50k_base_singleton(),
+        Some(Tokenizer::Cl100kBase) | None => tiktoken_rs::cl100k_base_singleton(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count() {
+        assert_eq!(count("", None), 0);
+        assert_eq!(count("hello world", None), 2);
+        assert_eq!(count("hello world", Some("gpt-4o")), 2);
+        assert_eq!(count("hello world", Some("llama3.2")), 2);
+    }
+
+    #[test]
+    fn test_count_for_model() {
+        let result = count_for_model("hello world", Some(" gpt-4o "));
+        assert_eq!(result.model, "gpt-4o");
+        assert_eq!(result.context_window, 128_000);
+
+        assert_eq!(count_for_model("hi", None).model, DEFAULT_MODEL);
+    }
+
+    #[test]
+    fn test_text_counts() {
+        let counts = |text| {
+            let counts = text_counts(text);
+            (counts.chars, counts.words, counts.lines)
+        };
+        assert_eq!(counts(""), (0, 0, 0));
+        assert_eq!(counts("Summarize  this:\n\n{{text}}\n"), (27, 3, 3));
+        assert_eq!(counts("héllo wörld"), (11, 2, 1));
+        assert_eq!(counts("a\r\nb"), (4, 2, 2));
+    }
+}
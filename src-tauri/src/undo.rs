@@ -0,0 +1,287 @@
+//! Undo and redo for prompt file changes. Saves, deletes and bulk edits record the files they
+//! touched with their contents before and after, so `undo_last_operation` can write the old
+//! contents back (and `redo` the new ones), then resync those files into the cache.
+
+use crate::db::{queries::DELETE_DELETED_PROMPT, DbPool};
+use crate::error::{AppError, ErrorKind};
+use crate::models::SyncStats;
+use crate::store::{commit_vault_history, vault_io, VaultContext};
+use crate::sync;
+use crate::vault;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, PoisonError};
+use tracing::{error, info};
+
+/// Operations kept for undo; older ones are dropped with their file contents
+const HISTORY_LIMIT: usize = 50;
+
+/// One file an operation touched; `None` when the file did not exist
+#[derive(Debug, Clone)]
+struct FileChange {
+    path: String,
+    before: Option<Vec<u8>>,
+    after: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone)]
+struct Operation {
+    label: String,
+    changes: Vec<FileChange>,
+}
+
+/// What `undo_last_operation` or `redo` put back
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoneOperation {
+    /// e.g. `Delete prompt notes.md`
+    pub label: String,
+    /// Vault-relative files rewritten or removed
+    pub paths: Vec<String>,
+    pub stats: SyncStats,
+}
+
+/// Labels of the operations `undo_last_operation` and `redo` would apply next
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoStatus {
+    pub undo: Option<String>,
+    pub redo: Option<String>,
+}
+
+#[derive(Default)]
+struct History {
+    undo: VecDeque<Operation>,
+    redo: Vec<Operation>,
+}
+
+/// Recorded operations of the running app (not kept across restarts)
+#[derive(Default)]
+pub struct UndoState(Mutex<History>);
+
+impl UndoState {
+    fn history(&self) -> std::sync::MutexGuard<'_, History> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add a new operation; anything undone before it can no longer be redone
+    fn record(&self, operation: Operation) {
+        let mut history = self.history();
+        history.redo.clear();
+        history.undo.push_back(operation);
+        while history.undo.len() > HISTORY_LIMIT {
+            history.undo.pop_front();
+        }
+    }
+
+    pub fn status(&self) -> UndoStatus {
+        let history = self.history();
+        UndoStatus {
+            undo: history.undo.back().map(|op| op.label.clone()),
+            redo: history.redo.last().map(|op| op.label.clone()),
+        }
+    }
+}
+
+fn read_file(vault_path: &Path, path: &str) -> Option<Vec<u8>> {
+    fs::read(vault_path.join(path)).ok()
+}
+
+/// The files an operation is about to change, read before it runs
+pub struct Snapshot {
+    label: String,
+    before: BTreeMap<String, Option<Vec<u8>>>,
+}
+
+impl Snapshot {
+    /// Read `paths` (vault-relative; ones that don't normalize are skipped) before `label` runs
+    pub async fn take(
+        vault: &VaultContext,
+        label: impl Into<String>,
+        paths: impl IntoIterator<Item = String>,
+    ) -> Result<Self, AppError> {
+        let extensions = &vault.config.frontmatter.file_extensions;
+        let paths: Vec<String> = paths
+            .into_iter()
+            .filter_map(|path| vault::normalize_relative_path(&path, extensions).ok())
+            .collect();
+        let label = label.into();
+        vault_io(vault, move |vault_path, _, _| {
+            let before = paths
+                .into_iter()
+                .map(|path| {
+                    let content = read_file(vault_path, &path);
+                    (path, content)
+                })
+                .collect();
+            Ok(Snapshot { label, before })
+        })
+        .await
+    }
+
+    /// Record the operation once it ran. `created` are files it wrote that were not known up
+    /// front (e.g. a name picked by the filename template). Files left as they were are not
+    /// recorded, and an operation that changed nothing is dropped.
+    pub async fn finish(self, vault: &VaultContext, state: &UndoState, created: Vec<String>) {
+        let Snapshot { label, mut before } = self;
+        for path in created {
+            before.entry(path).or_insert(None);
+        }
+        let recorded = vault_io(vault, move |vault_path, _, _| {
+            let changes: Vec<FileChange> = before
+                .into_iter()
+                .filter_map(|(path, before)| {
+                    let after = read_file(vault_path, &path);
+                    (after != before).then_some(FileChange {
+                        path,
+                        before,
+                        after,
+                    })
+                })
+                .collect();
+            Ok(Operation { label, changes })
+        })
+        .await;
+        match recorded {
+            Ok(operation) if !operation.changes.is_empty() => state.record(operation),
+            Ok(_) => {}
+            Err(e) => error!("Failed to record operation for undo: {}", e),
+        }
+    }
+}
+
+/// Write back the contents from before the newest operation, `None` when there is nothing to
+/// undo. Fails without touching anything if one of its files was changed since.
+pub async fn undo_last_operation(
+    pool: &DbPool,
+    vault: &VaultContext,
+    state: &UndoState,
+) -> Result<Option<UndoneOperation>, AppError> {
+    let Some(operation) = state.history().undo.pop_back() else {
+        return Ok(None);
+    };
+    match apply(pool, vault, &operation, false).await {
+        Ok(undone) => {
+            state.history().redo.push(operation);
+            Ok(Some(undone))
+        }
+        Err(e) => {
+            state.history().undo.push_back(operation);
+            Err(e)
+        }
+    }
+}
+
+/// Apply the last undone operation again, `None` when there is nothing to redo
+pub async fn redo(
+    pool: &DbPool,
+    vault: &VaultContext,
+    state: &UndoState,
+) -> Result<Option<UndoneOperation>, AppError> {
+    let Some(operation) = state.history().redo.pop() else {
+        return Ok(None);
+    };
+    match apply(pool, vault, &operation, true).await {
+        Ok(redone) => {
+            state.history().undo.push_back(operation);
+            Ok(Some(redone))
+        }
+        Err(e) => {
+            state.history().redo.push(operation);
+            Err(e)
+        }
+    }
+}
+
+/// Move the files of `operation` to their `after` contents (`forward`) or back to `before`,
+/// then resync them
+async fn apply(
+    pool: &DbPool,
+    vault: &VaultContext,
+    operation: &Operation,
+    forward: bool,
+) -> Result<UndoneOperation, AppError> {
+    let (verb, done) = if forward {
+        ("Redo", "redone")
+    } else {
+        ("Undo", "undone")
+    };
+    let changes = operation.changes.clone();
+    let message = format!("{} {}", verb, operation.label);
+    let paths = vault_io(vault, move |vault_path, config, self_writes| {
+        for change in &changes {
+            let expected = if forward {
+                &change.before
+            } else {
+                &change.after
+            };
+            if read_file(vault_path, &change.path) != *expected {
+                return Err(AppError::new(
+                    ErrorKind::Conflict,
+                    format!("{} changed since; nothing was {}", change.path, done),
+                ));
+            }
+        }
+
+        for change in &changes {
+            let target = if forward {
+                &change.after
+            } else {
+                &change.before
+            };
+            let file_path = vault_path.join(&change.path);
+            match target {
+                Some(content) => {
+                    if let Some(parent) = file_path.parent() {
+                        fs::create_dir_all(parent)
+                            .map_err(|e| AppError::io(format!("Failed to create folder: {}", e)))?;
+                    }
+                    vault::write_atomic(&file_path, content)
+                        .map_err(|e| AppError::io(format!("Failed to write file: {}", e)))?;
+                    self_writes.record(
+                        &change.path,
+                        vault::compute_file_hash_from_path(&file_path).ok(),
+                    );
+                }
+                None => {
+                    self_writes.record(&change.path, None);
+                    fs::remove_file(&file_path)
+                        .map_err(|e| AppError::io(format!("Failed to delete file: {}", e)))?;
+                }
+            }
+        }
+
+        let paths: Vec<String> = changes.iter().map(|change| change.path.clone()).collect();
+        if config.git_history {
+            let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+            commit_vault_history(vault_path, &refs, &message);
+        }
+        Ok(paths)
+    })
+    .await?;
+
+    let stats = sync::sync_paths(pool, &vault.path, &vault.config.frontmatter, &paths).await?;
+    // A deleted prompt that is back on disk no longer belongs in the recovery list
+    for change in &operation.changes {
+        let (from, to) = if forward {
+            (&change.before, &change.after)
+        } else {
+            (&change.after, &change.before)
+        };
+        if from.is_none() && to.is_some() {
+            sqlx::query(DELETE_DELETED_PROMPT)
+                .bind(&change.path)
+                .execute(pool)
+                .await?;
+        }
+    }
+    info!("{} {}: {} files", verb, operation.label, paths.len());
+    Ok(UndoneOperation {
+        label: operation.label.clone(),
+        paths,
+        stats,
+    })
+}
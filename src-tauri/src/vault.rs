@@ -1,14 +1,153 @@
+use crate::config::{ContentMode, FrontmatterSettings};
+use crate::models::{PromptStatus, SkipReason, SkippedFile};
+use crate::promptignore::PromptIgnore;
+use crate::template::TemplateVariable;
 use chrono::{Local, Utc};
-use crate::config::FrontmatterSettings;
 use gray_matter::{engine::YAML, Matter};
-use log::info;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 use serde_yaml::{Mapping, Value as YamlValue};
 use sha2::{Digest, Sha256};
 use specta::Type;
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs;
+use std::io::Write;
 use std::path::Path;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
 use uuid::Uuid;
+
+/// Prompt file extensions for paths that must be markdown (prompt packs, backups and
+/// remote vaults): only `.md`
+pub const MARKDOWN_ONLY: &[String] = &[];
+
+/// How much of a file is searched for NUL bytes to tell binary files from text
+const BINARY_SNIFF_LEN: usize = 8192;
+
+/// Suffix of the sibling file a prompt is written to before being renamed into place
+const TEMP_FILE_SUFFIX: &str = ".tmp";
+
+/// Byte order mark some Windows editors put at the start of UTF-8 files
+const BOM: char = '\u{feff}';
+
+/// Frontmatter keys the app manages itself (the configured keys are reserved too)
+const RESERVED_FRONTMATTER_KEYS: &[&str] = &[
+    "id",
+    "created",
+    "tags",
+    "title",
+    "description",
+    CONTENT_MODE_KEY,
+    ARCHIVED_KEY,
+    VARIABLES_KEY,
+    COLOR_KEY,
+    ICON_KEY,
+    NOTES_KEY,
+    MODEL_TARGETS_KEY,
+    LANGUAGE_KEY,
+    STATUS_KEY,
+];
+
+/// Per-file override of `FrontmatterSettings::content_mode` (`fence` or `body`)
+const CONTENT_MODE_KEY: &str = "content_mode";
+
+/// `archived: true` hides a prompt from views that do not ask for archived prompts
+const ARCHIVED_KEY: &str = "archived";
+
+/// Declared template variables: a list of names or `{name, default, required, options}`
+const VARIABLES_KEY: &str = "variables";
+
+/// Color a prompt is shown with, e.g. `#e11d48` or `red`
+const COLOR_KEY: &str = "color";
+
+/// Icon shown with a prompt: an icon name or an emoji
+const ICON_KEY: &str = "icon";
+
+/// Private working notes; left out when a prompt is shared or exported
+pub const NOTES_KEY: &str = "notes";
+
+/// Models a prompt is tuned for: a list (or comma separated string) such as `gpt-4o`
+const MODEL_TARGETS_KEY: &str = "model_targets";
+
+/// Language a prompt is written in, e.g. `en` or `German`
+const LANGUAGE_KEY: &str = "language";
+
+/// Lifecycle stage: `draft`, `testing`, `production` or `deprecated`
+pub const STATUS_KEY: &str = "status";
+
+/// Frontmatter keys resolved from `FrontmatterSettings`
+pub(crate) struct FrontmatterKeys {
+    pub(crate) tags: String,
+    pub(crate) created: String,
+    title: String,
+    description: String,
+}
+
+impl FrontmatterKeys {
+    pub(crate) fn new(settings: &FrontmatterSettings) -> Self {
+        Self {
+            tags: normalize_frontmatter_key(&settings.prompt_tags_property, "tags"),
+            created: normalize_frontmatter_key(&settings.created_property, "created"),
+            title: normalize_frontmatter_key(&settings.title_property, "title"),
+            description: normalize_frontmatter_key(&settings.description_property, "description"),
+        }
+    }
+
+    fn is_reserved(&self, key: &str) -> bool {
+        key == self.tags
+            || key == self.created
+            || key == self.title
+            || key == self.description
+            || RESERVED_FRONTMATTER_KEYS.contains(&key)
+    }
+}
+
+/// Reject frontmatter settings that would mangle files: two fields sharing a key, a field
+/// stored under a key the app manages itself, or a file extension with a path in it
+pub(crate) fn validate_frontmatter_settings(settings: &FrontmatterSettings) -> Result<(), String> {
+    let keys = FrontmatterKeys::new(settings);
+    let fields = [
+        ("tags", &keys.tags),
+        ("created", &keys.created),
+        ("title", &keys.title),
+        ("description", &keys.description),
+    ];
+    for (i, (field, key)) in fields.iter().enumerate() {
+        if let Some((other, _)) = fields[..i].iter().find(|(_, other_key)| other_key == key) {
+            return Err(format!(
+                "the {} and {} properties both use the `{}` key",
+                other, field, key
+            ));
+        }
+        let managed = [
+            CONTENT_MODE_KEY,
+            ARCHIVED_KEY,
+            VARIABLES_KEY,
+            COLOR_KEY,
+            ICON_KEY,
+            NOTES_KEY,
+            MODEL_TARGETS_KEY,
+            LANGUAGE_KEY,
+            STATUS_KEY,
+            "id",
+        ];
+        if managed.contains(&key.as_str()) {
+            return Err(format!(
+                "the {} property cannot use the `{}` key",
+                field, key
+            ));
+        }
+    }
+    if let Some(extension) = settings
+        .file_extensions
+        .iter()
+        .find(|extension| extension.contains(['/', '\\']))
+    {
+        return Err(format!("`{}` is not a file extension", extension));
+    }
+    Ok(())
+}
 
 /// A prompt file representation (parsed from markdown)
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -31,6 +170,48 @@ pub struct PromptFile {
     pub title: Option<String>,
     /// Optional prompt description from frontmatter
     pub description: Option<String>,
+    /// Color views show the prompt with (`color` in frontmatter)
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Icon name or emoji views show the prompt with (`icon` in frontmatter)
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Private working notes (`notes` in frontmatter), not shared with the prompt
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Models the prompt is tuned for (`model_targets` in frontmatter)
+    #[serde(default)]
+    pub model_targets: Vec<String>,
+    /// Language the prompt is written in (`language` in frontmatter)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Lifecycle stage (`status` in frontmatter); unknown values read as `None` and are
+    /// left in the file
+    #[serde(default)]
+    pub status: Option<PromptStatus>,
+    /// Frontmatter fields not managed by the app (e.g. `model`, `author`, `source`)
+    #[serde(default)]
+    pub custom_fields: HashMap<String, JsonValue>,
+    /// Named ```` ```prompt:name ```` blocks; written blocks are updated or appended by name
+    #[serde(default)]
+    pub variants: Vec<PromptVariant>,
+    /// Archived prompts are kept but hidden from views by default
+    #[serde(default)]
+    pub archived: bool,
+    /// Targets of `[[wikilinks]]` in the file body (only read when `parse_wikilinks` is on)
+    #[serde(default)]
+    pub links: Vec<String>,
+    /// Template variables declared in frontmatter; when empty, a file's declaration is kept
+    #[serde(default)]
+    pub variables: Vec<TemplateVariable>,
+}
+
+/// An alternative version of a prompt stored in its own named block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptVariant {
+    pub name: String,
+    pub content: String,
 }
 
 /// Vault operation errors
@@ -56,37 +237,128 @@ pub enum VaultError {
     FileAlreadyExists(String),
     #[error("Invalid prompt content: {0}")]
     InvalidContent(String),
+    #[error("Remote vault error: {0}")]
+    Remote(String),
+    #[error("File is {size} bytes, over the {limit} byte limit: {path}")]
+    FileTooLarge { path: String, size: u64, limit: u64 },
+    #[error("File looks binary, not text: {0}")]
+    BinaryFile(String),
+    #[error("Cancelled")]
+    Cancelled,
+    #[error("Sync would delete {0} local files")]
+    UnconfirmedDeletions(usize),
+}
+
+/// Run blocking vault file IO on the blocking thread pool. Async callers go through this
+/// so a slow disk or network share never stalls the runtime serving every other command.
+pub async fn blocking<T, E, F>(f: F) -> Result<T, E>
+where
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+    T: Send + 'static,
+    E: From<VaultError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| VaultError::IoError(format!("File task failed: {}", e)))?
+}
+
+/// Prompt files found by `scan_vault`
+#[derive(Debug, Clone, Default)]
+pub struct VaultScan {
+    pub prompts: Vec<PromptFile>,
+    /// Files with a prompt extension that could not be read
+    pub skipped: Vec<SkippedFile>,
 }
 
-/// Scan vault directory and return all prompt files
+/// Scan vault directory and return all prompt files. Files that can't be read (too large,
+/// binary, not UTF-8) are reported in `skipped` rather than failing the scan.
 pub fn scan_vault(
     vault_path: &Path,
     frontmatter_settings: &FrontmatterSettings,
-) -> Result<Vec<PromptFile>, VaultError> {
+) -> Result<VaultScan, VaultError> {
+    scan_vault_with_progress(
+        vault_path,
+        frontmatter_settings,
+        &CancellationToken::new(),
+        |_, _, _| {},
+    )
+}
+
+/// `scan_vault` that calls `progress(scanned, total, file name)` before reading each
+/// prompt file, and stops with `VaultError::Cancelled` once `cancel` is cancelled
+pub fn scan_vault_with_progress(
+    vault_path: &Path,
+    frontmatter_settings: &FrontmatterSettings,
+    cancel: &CancellationToken,
+    mut progress: impl FnMut(usize, usize, &str),
+) -> Result<VaultScan, VaultError> {
     if !vault_path.exists() {
         return Err(VaultError::PathNotFound(vault_path.display().to_string()));
     }
 
-    let mut prompts = Vec::new();
+    let mut scan = VaultScan::default();
+    let ignore = PromptIgnore::load(vault_path);
 
-    let entries = fs::read_dir(vault_path)
-        .map_err(|e| VaultError::IoError(e.to_string()))?;
+    let entries = fs::read_dir(vault_path).map_err(|e| VaultError::IoError(e.to_string()))?;
 
+    // List the prompt files first so progress has a total
+    let mut files = Vec::new();
     for entry in entries.flatten() {
         let path = entry.path();
-        if path.extension().and_then(|ext| ext.to_str()) != Some("md") {
+        if is_stray_temp_file(&path, &frontmatter_settings.file_extensions) {
+            // Left behind by a write that was interrupted before the rename
+            match fs::remove_file(&path) {
+                Ok(()) => info!("Removed stray temp file {:?}", path),
+                Err(e) => info!("Failed to remove stray temp file {:?}: {}", path, e),
+            }
             continue;
         }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !has_prompt_extension(&name, &frontmatter_settings.file_extensions)
+            || !path.is_file()
+            || ignore.is_ignored(&name)
+        {
+            continue;
+        }
+        files.push((name, path));
+    }
+
+    let total = files.len();
+    for (scanned, (name, path)) in files.into_iter().enumerate() {
+        if cancel.is_cancelled() {
+            return Err(VaultError::Cancelled);
+        }
+        progress(scanned, total, &name);
         match read_prompt_file(vault_path, &path, frontmatter_settings) {
-            Ok(prompt) => prompts.push(prompt),
+            Ok(prompt) => scan.prompts.push(prompt),
             Err(e) => {
                 info!("Skipping file {:?}: {}", path, e);
+                scan.skipped.push(skipped_file(&name, &e));
             }
         }
     }
 
-    info!("Scanned vault, found {} prompts", prompts.len());
-    Ok(prompts)
+    info!(
+        "Scanned vault, found {} prompts, skipped {} files",
+        scan.prompts.len(),
+        scan.skipped.len()
+    );
+    Ok(scan)
+}
+
+/// Why reading the prompt file at vault-relative `path` failed, for sync results
+pub fn skipped_file(path: &str, error: &VaultError) -> SkippedFile {
+    let reason = match error {
+        VaultError::FileTooLarge { .. } => SkipReason::TooLarge,
+        VaultError::BinaryFile(_) => SkipReason::Binary,
+        VaultError::InvalidFilePath(_) => SkipReason::InvalidPath,
+        _ => SkipReason::Unreadable,
+    };
+    SkippedFile {
+        path: path.to_string(),
+        reason,
+        message: error.to_string(),
+    }
 }
 
 pub fn find_prompt_by_id(
@@ -98,98 +370,190 @@ pub fn find_prompt_by_id(
         return Err(VaultError::PathNotFound(vault_path.display().to_string()));
     }
 
-    let relative_path = normalize_relative_path(id)?;
+    let relative_path = normalize_relative_path(id, &frontmatter_settings.file_extensions)?;
     let file_path = vault_path.join(&relative_path);
     read_prompt_file(vault_path, &file_path, frontmatter_settings)
         .map_err(|_| VaultError::NotFound(id.to_string()))
 }
 
-/// Read and parse a single prompt markdown file
+/// Read and parse a single prompt file
 pub fn read_prompt_file(
     vault_path: &Path,
     file_path: &Path,
     frontmatter_settings: &FrontmatterSettings,
 ) -> Result<PromptFile, VaultError> {
-    // Read file content
-    let content = fs::read_to_string(file_path).map_err(|e| VaultError::IoError(e.to_string()))?;
-    let file_hash = Some(compute_file_hash(&content));
+    let content = read_text_file(file_path, frontmatter_settings.max_file_size_kb)?;
+
+    // Get relative path
+    let relative_path = file_path
+        .strip_prefix(vault_path)
+        .unwrap_or(file_path)
+        .display()
+        .to_string();
+
+    Ok(parse_prompt_file(
+        &relative_path,
+        &content,
+        frontmatter_settings,
+    ))
+}
+
+/// Read a file as text, refusing files over `max_file_size_kb` (unless 0) before reading
+/// them and files with NUL bytes near the start, which text never has
+fn read_text_file(file_path: &Path, max_file_size_kb: u64) -> Result<String, VaultError> {
+    let display = file_path.display().to_string();
+    let size = fs::metadata(file_path)
+        .map_err(|e| VaultError::IoError(e.to_string()))?
+        .len();
+    let limit = max_file_size_kb.saturating_mul(1024);
+    if limit > 0 && size > limit {
+        return Err(VaultError::FileTooLarge {
+            path: display,
+            size,
+            limit,
+        });
+    }
+
+    let bytes = fs::read(file_path).map_err(|e| VaultError::IoError(e.to_string()))?;
+    if bytes.iter().take(BINARY_SNIFF_LEN).any(|&byte| byte == 0) {
+        return Err(VaultError::BinaryFile(display));
+    }
+    String::from_utf8(bytes)
+        .map_err(|_| VaultError::IoError(format!("File is not valid UTF-8: {}", display)))
+}
+
+/// Parse the markdown of a prompt file (e.g. an older version from history). Files that
+/// are not markdown hold nothing but the prompt text.
+pub fn parse_prompt_file(
+    relative_path: &str,
+    content: &str,
+    frontmatter_settings: &FrontmatterSettings,
+) -> PromptFile {
+    let file_hash = Some(compute_file_hash(content));
+    let normalized = apply_line_endings(content, frontmatter_settings);
+    let content = normalized.as_ref();
+    if !is_markdown(relative_path) {
+        return PromptFile {
+            id: relative_path.to_string(),
+            file_path: relative_path.to_string(),
+            tags: Vec::new(),
+            created: None,
+            content: extract_code_block_content(content, ContentMode::Body),
+            file_hash,
+            title: None,
+            description: None,
+            color: None,
+            icon: None,
+            notes: None,
+            model_targets: Vec::new(),
+            language: None,
+            status: None,
+            custom_fields: HashMap::new(),
+            variants: Vec::new(),
+            archived: false,
+            links: Vec::new(),
+            variables: Vec::new(),
+        };
+    }
 
     // Parse frontmatter
     let matter = Matter::<YAML>::new();
-    let parsed = matter.parse(&content);
+    let parsed = matter.parse(content);
 
     let frontmatter_map: Mapping = parsed
         .data
         .and_then(|d| d.deserialize().ok())
         .unwrap_or_else(Mapping::new);
 
-    let prompt_tags_property = normalize_frontmatter_key(&frontmatter_settings.prompt_tags_property);
-    let tags = extract_tags(&frontmatter_map, &prompt_tags_property);
-    let created = extract_string(&frontmatter_map, "created");
-    let title = extract_string(&frontmatter_map, "title");
-    let description = extract_string(&frontmatter_map, "description");
+    let keys = FrontmatterKeys::new(frontmatter_settings);
+    let tags = extract_tags(&frontmatter_map, &keys.tags);
+    let created = extract_string(&frontmatter_map, &keys.created);
+    let title = extract_string(&frontmatter_map, &keys.title);
+    let description = extract_string(&frontmatter_map, &keys.description);
+    let color = extract_string(&frontmatter_map, COLOR_KEY);
+    let icon = extract_string(&frontmatter_map, ICON_KEY);
+    let notes = extract_string(&frontmatter_map, NOTES_KEY);
+    let model_targets = extract_list(&frontmatter_map, MODEL_TARGETS_KEY);
+    let language = extract_string(&frontmatter_map, LANGUAGE_KEY);
+    let status =
+        extract_string(&frontmatter_map, STATUS_KEY).and_then(|value| PromptStatus::parse(&value));
+    let custom_fields = extract_custom_fields(&frontmatter_map, &keys);
+    let variables = extract_variables(&frontmatter_map);
+    let content_mode = resolve_content_mode(&frontmatter_map, frontmatter_settings);
+    let archived = frontmatter_map
+        .get(YamlValue::String(ARCHIVED_KEY.to_string()))
+        .and_then(YamlValue::as_bool)
+        .unwrap_or(false);
 
-    // Extract content from code block
-    let prompt_content = extract_code_block_content(&parsed.content);
-
-    // Get relative path
-    let relative_path = file_path
-        .strip_prefix(vault_path)
-        .unwrap_or(file_path)
-        .display()
-        .to_string();
+    // Extract content from code block (or the whole body)
+    let prompt_content = extract_code_block_content(&parsed.content, content_mode);
+    let variants = extract_variants(&parsed.content, content_mode);
+    let links = if frontmatter_settings.parse_wikilinks {
+        extract_wikilinks(&parsed.content)
+    } else {
+        Vec::new()
+    };
 
-    Ok(PromptFile {
-        id: relative_path.clone(),
-        file_path: relative_path,
+    PromptFile {
+        id: relative_path.to_string(),
+        file_path: relative_path.to_string(),
         tags,
         created,
         content: prompt_content,
         file_hash,
         title,
         description,
-    })
+        color,
+        icon,
+        notes,
+        model_targets,
+        language,
+        status,
+        custom_fields,
+        variants,
+        archived,
+        links,
+        variables,
+    }
 }
 
-/// Write a prompt to a markdown file
+/// Write a prompt to its file. Files that are not markdown only get the prompt text;
+/// tags and other metadata have nowhere to go.
 pub fn write_prompt_file(
     vault_path: &Path,
     prompt: &PromptFile,
     frontmatter_settings: &FrontmatterSettings,
 ) -> Result<(), VaultError> {
-    if prompt.content.contains("```") || prompt.content.contains("~~~") {
-        return Err(VaultError::InvalidContent(
-            "Prompt content cannot include ``` or ~~~".to_string(),
-        ));
-    }
-
-    let relative_path = normalize_relative_path(&prompt.file_path)?;
+    let relative_path =
+        normalize_relative_path(&prompt.file_path, &frontmatter_settings.file_extensions)?;
     let file_path = vault_path.join(&relative_path);
+    if !is_markdown(&relative_path) {
+        let content = format!("{}\n", prompt.content.trim_end());
+        write_atomic(
+            &file_path,
+            &apply_line_endings(&content, frontmatter_settings),
+        )
+        .map_err(|e| VaultError::IoError(e.to_string()))?;
+        info!("Wrote prompt file: {:?}", file_path);
+        return Ok(());
+    }
 
-    let existing = fs::read_to_string(&file_path).ok();
+    let existing = fs::read_to_string(&file_path)
+        .ok()
+        .map(|existing| apply_line_endings(&existing, frontmatter_settings).into_owned());
     let (mut frontmatter_map, existing_body) = parse_existing_prompt(&existing)?;
+    let content_mode = resolve_content_mode(&frontmatter_map, frontmatter_settings);
 
     // Build frontmatter
+    let keys = FrontmatterKeys::new(frontmatter_settings);
     let created = prompt
         .created
         .clone()
-        .or_else(|| {
-            frontmatter_map
-                .get(&YamlValue::String("created".to_string()))
-                .and_then(|v| v.as_str().map(|s| s.to_string()))
-        })
+        .or_else(|| extract_string(&frontmatter_map, &keys.created))
         .unwrap_or_else(|| Utc::now().format("%Y-%m-%dT%H:%M:%S").to_string());
 
-    frontmatter_map.insert(
-        YamlValue::String("created".to_string()),
-        YamlValue::String(created),
-    );
-    let prompt_tags_property = normalize_frontmatter_key(&frontmatter_settings.prompt_tags_property);
-    set_tags(
-        &mut frontmatter_map,
-        &prompt_tags_property,
-        &prompt.tags,
-    );
+    set_string(&mut frontmatter_map, &keys.created, Some(created));
+    set_tags(&mut frontmatter_map, &keys.tags, &prompt.tags);
     if frontmatter_settings.add_prompts_tag_to_tags {
         let mut existing_tags = extract_tags(&frontmatter_map, "tags");
         if !existing_tags.iter().any(|t| t == "prompts") {
@@ -197,41 +561,142 @@ pub fn write_prompt_file(
         }
         set_tags(&mut frontmatter_map, "tags", &existing_tags);
     }
-    if let Some(title) = prompt.title.clone().filter(|t| !t.trim().is_empty()) {
-        frontmatter_map.insert(
-            YamlValue::String("title".to_string()),
-            YamlValue::String(title),
+    set_string(
+        &mut frontmatter_map,
+        &keys.title,
+        prompt.title.clone().filter(|t| !t.trim().is_empty()),
+    );
+    set_string(
+        &mut frontmatter_map,
+        &keys.description,
+        prompt.description.clone().filter(|d| !d.trim().is_empty()),
+    );
+    for (key, value) in [
+        (COLOR_KEY, &prompt.color),
+        (ICON_KEY, &prompt.icon),
+        (NOTES_KEY, &prompt.notes),
+        (LANGUAGE_KEY, &prompt.language),
+    ] {
+        set_string(
+            &mut frontmatter_map,
+            key,
+            value.clone().filter(|v| !v.trim().is_empty()),
         );
+    }
+    set_list(
+        &mut frontmatter_map,
+        MODEL_TARGETS_KEY,
+        &prompt.model_targets,
+    );
+    if let Some(status) = prompt.status {
+        set_string(
+            &mut frontmatter_map,
+            STATUS_KEY,
+            Some(status.as_str().to_string()),
+        );
+    }
+
+    set_custom_fields(&mut frontmatter_map, &keys, &prompt.custom_fields)?;
+    let archived_key = YamlValue::String(ARCHIVED_KEY.to_string());
+    if prompt.archived {
+        frontmatter_map.insert(archived_key, YamlValue::Bool(true));
     } else {
-        frontmatter_map.remove(&YamlValue::String("title".to_string()));
+        frontmatter_map.remove(&archived_key);
     }
-    if let Some(description) = prompt
-        .description
-        .clone()
-        .filter(|d| !d.trim().is_empty())
-    {
+    if !prompt.variables.is_empty() {
+        let variables = prompt
+            .variables
+            .iter()
+            .map(variable_yaml)
+            .collect::<Result<Vec<_>, _>>()?;
         frontmatter_map.insert(
-            YamlValue::String("description".to_string()),
-            YamlValue::String(description),
+            YamlValue::String(VARIABLES_KEY.to_string()),
+            YamlValue::Sequence(variables),
         );
-    } else {
-        frontmatter_map.remove(&YamlValue::String("description".to_string()));
     }
 
     frontmatter_map.remove(&YamlValue::String("id".to_string()));
     let frontmatter = render_frontmatter(&frontmatter_map)?;
-    let updated_body = update_prompt_block(&existing_body, &prompt.content);
+    let mut updated_body = update_prompt_block(&existing_body, &prompt.content, content_mode);
+    if content_mode == ContentMode::Fence {
+        for variant in &prompt.variants {
+            updated_body = update_named_block(&updated_body, Some(&variant.name), &variant.content);
+        }
+    }
     let content = format!("{}{}", frontmatter, updated_body);
+    let content = apply_line_endings(&content, frontmatter_settings);
 
-    fs::write(&file_path, content).map_err(|e| VaultError::IoError(e.to_string()))?;
+    write_atomic(&file_path, &content).map_err(|e| VaultError::IoError(e.to_string()))?;
 
     info!("Wrote prompt file: {:?}", file_path);
     Ok(())
 }
 
+/// The text of a prompt file with its private `notes` removed, for sharing it outside the
+/// vault. Files without notes are returned as they are.
+pub fn strip_notes(content: &str) -> Result<String, VaultError> {
+    let normalized = normalize_line_endings(content);
+    let parsed = Matter::<YAML>::new().parse(normalized.as_ref());
+    let Some(mut frontmatter_map) = parsed.data.and_then(|d| d.deserialize::<Mapping>().ok())
+    else {
+        return Ok(content.to_string());
+    };
+    if frontmatter_map
+        .remove(YamlValue::String(NOTES_KEY.to_string()))
+        .is_none()
+    {
+        return Ok(content.to_string());
+    }
+
+    let body = parsed.content.trim_start_matches('\n');
+    if frontmatter_map.is_empty() {
+        return Ok(body.to_string());
+    }
+    Ok(format!("{}{}", render_frontmatter(&frontmatter_map)?, body))
+}
+
+/// Write `content` to a temp sibling, fsync it and rename it over `file_path`,
+/// so a crash mid-write never leaves a truncated prompt file behind
+pub(crate) fn write_atomic(file_path: &Path, content: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let mut temp_name = file_path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(TEMP_FILE_SUFFIX);
+    let temp_path = file_path.with_file_name(temp_name);
+
+    let result = (|| {
+        let mut file = fs::File::create(&temp_path)?;
+        file.write_all(content.as_ref())?;
+        file.sync_all()?;
+        fs::rename(&temp_path, file_path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+        return result;
+    }
+
+    // Persist the rename itself; directories can't be opened for syncing on Windows
+    #[cfg(unix)]
+    if let Some(parent) = file_path.parent() {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Whether `path` is a temp file left over from an interrupted `write_atomic` of a prompt
+/// file, i.e. named `<name>.<prompt extension>.tmp`
+pub(crate) fn is_stray_temp_file(path: &Path, extensions: &[String]) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .and_then(|name| name.strip_suffix(TEMP_FILE_SUFFIX))
+        .is_some_and(|name| has_prompt_extension(name, extensions))
+}
+
 /// Delete a prompt file
-pub fn delete_prompt_file(vault_path: &Path, id: &str) -> Result<(), VaultError> {
-    let relative_path = normalize_relative_path(id)?;
+pub fn delete_prompt_file(
+    vault_path: &Path,
+    id: &str,
+    frontmatter_settings: &FrontmatterSettings,
+) -> Result<(), VaultError> {
+    let relative_path = normalize_relative_path(id, &frontmatter_settings.file_extensions)?;
     let file_path = vault_path.join(relative_path);
 
     if !file_path.exists() {
@@ -245,40 +710,186 @@ pub fn delete_prompt_file(vault_path: &Path, id: &str) -> Result<(), VaultError>
 }
 
 /// Extract content from a markdown code block with language "prompt"
-fn extract_code_block_content(markdown: &str) -> String {
-    let lines: Vec<&str> = markdown.lines().collect();
-    let mut in_block = false;
-    let mut fence = "";
-    let mut content_lines = Vec::new();
-
-    for line in lines {
-        let trimmed = line.trim_start();
-        if trimmed.starts_with("```prompt") || trimmed.starts_with("~~~prompt") {
-            in_block = true;
-            fence = if trimmed.starts_with("~~~") { "~~~" } else { "```" };
+pub(crate) fn extract_code_block_content(markdown: &str, mode: ContentMode) -> String {
+    if mode == ContentMode::Body {
+        return markdown
+            .trim_start_matches(['\r', '\n'])
+            .trim_end()
+            .to_string();
+    }
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    find_prompt_blocks(&lines)
+        .iter()
+        .find(|block| block.name.is_none())
+        .map(|block| block.content(&lines))
+        .unwrap_or_default()
+}
+
+/// Whether a prompt file kept in a fence has no unnamed ```` ```prompt ```` block to read
+pub(crate) fn lacks_prompt_block(
+    content: &str,
+    frontmatter_settings: &FrontmatterSettings,
+) -> bool {
+    let (frontmatter_map, body) = parse_existing_prompt(&Some(content.to_string()))
+        .unwrap_or_else(|_| (Mapping::new(), content.to_string()));
+    if resolve_content_mode(&frontmatter_map, frontmatter_settings) == ContentMode::Body {
+        return false;
+    }
+    !has_prompt_block(&body)
+}
+
+/// Whether `markdown` has an unnamed ```` ```prompt ```` block
+pub(crate) fn has_prompt_block(markdown: &str) -> bool {
+    let lines: Vec<&str> = markdown.lines().collect();
+    find_prompt_blocks(&lines)
+        .iter()
+        .any(|block| block.name.is_none())
+}
+
+/// Distinct `[[target]]` link targets in order of appearance; aliases (`|alias`) and
+/// heading or block references (`#heading`) are dropped
+pub(crate) fn extract_wikilinks(markdown: &str) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut rest = markdown;
+    while let Some(start) = rest.find("[[") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("]]") else {
+            break;
+        };
+        let inner = &rest[..end];
+        rest = &rest[end + 2..];
+        if inner.contains('\n') {
             continue;
         }
-        if in_block && line.trim_start().starts_with(fence) {
-            break;
-        }
-        if in_block {
-            content_lines.push(line);
+        let target = inner.split(['|', '#']).next().unwrap_or_default().trim();
+        if !target.is_empty() && !links.iter().any(|link| link == target) {
+            links.push(target.to_string());
         }
     }
+    links
+}
+
+/// Named ```` ```prompt:name ```` blocks of a markdown body (none in body mode)
+fn extract_variants(markdown: &str, mode: ContentMode) -> Vec<PromptVariant> {
+    if mode == ContentMode::Body {
+        return Vec::new();
+    }
+
+    let lines: Vec<&str> = markdown.lines().collect();
+    find_prompt_blocks(&lines)
+        .into_iter()
+        .filter_map(|block| {
+            let content = block.content(&lines);
+            block.name.map(|name| PromptVariant { name, content })
+        })
+        .collect()
+}
+
+/// Location of a ```` ```prompt ```` code block within a list of lines
+struct PromptBlock {
+    name: Option<String>,
+    fence_char: char,
+    fence_len: usize,
+    start: usize,
+    /// Index of the closing fence (`None` if the block runs to the end of the file)
+    end: Option<usize>,
+}
+
+impl PromptBlock {
+    fn content(&self, lines: &[&str]) -> String {
+        let end = self.end.unwrap_or(lines.len());
+        lines[self.start + 1..end].join("\n")
+    }
+}
+
+fn find_prompt_blocks(lines: &[&str]) -> Vec<PromptBlock> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        if let Some((fence_char, fence_len, name)) = parse_prompt_fence_open(lines[i]) {
+            let end =
+                (i + 1..lines.len()).find(|&j| is_fence_close(lines[j], fence_char, fence_len));
+            blocks.push(PromptBlock {
+                name,
+                fence_char,
+                fence_len,
+                start: i,
+                end,
+            });
+            match end {
+                Some(end) => i = end,
+                None => break,
+            }
+        }
+        i += 1;
+    }
+    blocks
+}
+
+/// Split a fence line into its fence character, fence length and info string
+fn parse_fence(line: &str) -> Option<(char, usize, &str)> {
+    let trimmed = line.trim_start();
+    let ch = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == ch).count();
+    if len < 3 {
+        return None;
+    }
+    Some((ch, len, &trimmed[len..]))
+}
+
+/// Fence character, length and optional variant name of a ```` ```prompt[:name] ```` opening line
+fn parse_prompt_fence_open(line: &str) -> Option<(char, usize, Option<String>)> {
+    let (ch, len, info) = parse_fence(line)?;
+    let rest = info.trim_start().strip_prefix("prompt")?;
+    if let Some(name) = rest.strip_prefix(':') {
+        let name = name.split_whitespace().next()?;
+        return Some((ch, len, Some(name.to_string())));
+    }
+    if rest.is_empty() || rest.starts_with(char::is_whitespace) {
+        return Some((ch, len, None));
+    }
+    None
+}
 
-    content_lines.join("\n")
+/// A closing fence uses the same character, is at least as long and has no info string
+fn is_fence_close(line: &str, ch: char, len: usize) -> bool {
+    parse_fence(line).is_some_and(|(c, n, rest)| c == ch && n >= len && rest.trim().is_empty())
+}
+
+/// Shortest fence of `ch` that no fence inside `content` can close
+pub(crate) fn fence_for(content: &str, ch: char) -> String {
+    let longest = content
+        .lines()
+        .filter_map(parse_fence)
+        .filter(|(c, _, _)| *c == ch)
+        .map(|(_, len, _)| len)
+        .max()
+        .unwrap_or(0);
+    ch.to_string().repeat((longest + 1).max(3))
 }
 
-pub fn generate_unique_file_path(vault_path: &Path) -> Result<String, VaultError> {
-    let date = Local::now().format("%Y-%m-%d").to_string();
-    for _ in 0..20 {
-        let random = Uuid::new_v4()
-            .simple()
-            .to_string()
-            .chars()
-            .take(6)
-            .collect::<String>();
-        let candidate = format!("{}-{}.md", date, random);
+/// Generate an unused file name for a new prompt from `template`
+/// (e.g. `{title}-{date}` -> `summarize-pr-2024-05-01.md`)
+pub fn generate_unique_file_path(
+    vault_path: &Path,
+    template: &str,
+    title: Option<&str>,
+) -> Result<String, VaultError> {
+    let template = match template.trim() {
+        "" => "{date}-{random}",
+        template => template.trim_end_matches(".md"),
+    };
+    let has_random = template.contains("{random}") || template.contains("{uuid}");
+
+    for attempt in 0..20 {
+        let stem = render_filename_template(template, title);
+        // Random tokens get re-rolled on collision; otherwise number the duplicates
+        let candidate = if attempt == 0 || has_random {
+            format!("{}.md", stem)
+        } else {
+            format!("{}-{}.md", stem, attempt + 1)
+        };
         let path = vault_path.join(&candidate);
         if !path.exists() {
             return Ok(candidate);
@@ -289,8 +900,76 @@ pub fn generate_unique_file_path(vault_path: &Path) -> Result<String, VaultError
     ))
 }
 
-pub fn normalize_relative_path(path: &str) -> Result<String, VaultError> {
-    let trimmed = path.trim();
+/// Tokens `render_filename_template` fills in
+const FILENAME_TOKENS: [&str; 5] = ["title", "date", "time", "uuid", "random"];
+
+/// Reject a file name template with an unknown `{token}` or an unclosed brace, which would
+/// end up verbatim in file names
+pub(crate) fn check_filename_template(template: &str) -> Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return Err(format!("`{}` has an unclosed `{{`", template));
+        };
+        let token = &rest[start + 1..start + len];
+        if !FILENAME_TOKENS.contains(&token) {
+            return Err(format!(
+                "`{{{}}}` is not a token; use {}",
+                token,
+                FILENAME_TOKENS
+                    .map(|token| format!("`{{{}}}`", token))
+                    .join(", ")
+            ));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    Ok(())
+}
+
+fn render_filename_template(template: &str, title: Option<&str>) -> String {
+    let now = Local::now();
+    let uuid = Uuid::new_v4().simple().to_string();
+    let title = title.map(slugify).filter(|s| !s.is_empty());
+
+    let rendered = template
+        .replace("{title}", title.as_deref().unwrap_or("untitled"))
+        .replace("{date}", &now.format("%Y-%m-%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{uuid}", &uuid)
+        .replace("{random}", &uuid[..6]);
+
+    // Tokens must not introduce path separators or leave stray separators behind
+    let stem = rendered
+        .replace(['/', '\\', ':'], "-")
+        .trim_matches(|c: char| c == '-' || c == '.' || c.is_whitespace())
+        .to_string();
+    if stem.is_empty() {
+        uuid[..6].to_string()
+    } else {
+        stem
+    }
+}
+
+/// Lowercase slug (`Summarize PR #12` -> `summarize-pr-12`)
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Validate a vault-relative prompt path, adding `.md` unless it already ends in one of
+/// the prompt file `extensions`
+pub fn normalize_relative_path(path: &str, extensions: &[String]) -> Result<String, VaultError> {
+    // Windows drops trailing dots and spaces from file names, so they never reach the disk
+    let trimmed = path
+        .trim_start()
+        .trim_end_matches(|c: char| c == '.' || c.is_whitespace());
     if trimmed.is_empty() {
         return Err(VaultError::InvalidFilePath("empty path".to_string()));
     }
@@ -305,8 +984,13 @@ pub fn normalize_relative_path(path: &str) -> Result<String, VaultError> {
             "subfolders are not supported".to_string(),
         ));
     }
+    // `C:name` is drive-relative and `name:stream` an NTFS stream on Windows; elsewhere
+    // `:` is an ordinary character that existing files may use
+    if (cfg!(windows) && trimmed.contains(':')) || trimmed.chars().any(char::is_control) {
+        return Err(VaultError::InvalidFilePath("invalid character".to_string()));
+    }
 
-    let with_ext = if trimmed.ends_with(".md") {
+    let with_ext = if trimmed.ends_with(".md") || has_prompt_extension(trimmed, extensions) {
         trimmed.to_string()
     } else {
         format!("{}.md", trimmed)
@@ -315,6 +999,50 @@ pub fn normalize_relative_path(path: &str) -> Result<String, VaultError> {
     Ok(with_ext)
 }
 
+/// Whether the file `name` ends in one of the prompt file `extensions` (`md` when there
+/// are none)
+pub fn has_prompt_extension(name: &str, extensions: &[String]) -> bool {
+    let name = name.to_ascii_lowercase();
+    let mut extensions: Vec<String> = extensions
+        .iter()
+        .map(|extension| {
+            extension
+                .trim()
+                .trim_start_matches('.')
+                .to_ascii_lowercase()
+        })
+        .filter(|extension| !extension.is_empty())
+        .collect();
+    if extensions.is_empty() {
+        extensions.push("md".to_string());
+    }
+    extensions.iter().any(|extension| {
+        name.strip_suffix(extension.as_str())
+            .and_then(|stem| stem.strip_suffix('.'))
+            .is_some_and(|stem| !stem.is_empty())
+    })
+}
+
+/// Whether the prompt file at `path` is markdown, with frontmatter
+pub(crate) fn is_markdown(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".md")
+}
+
+/// Content mode for a file: its `content_mode` frontmatter key, else the vault setting
+pub(crate) fn resolve_content_mode(
+    map: &Mapping,
+    frontmatter_settings: &FrontmatterSettings,
+) -> ContentMode {
+    match extract_string(map, CONTENT_MODE_KEY)
+        .as_deref()
+        .map(str::trim)
+    {
+        Some("body") => ContentMode::Body,
+        Some("fence") => ContentMode::Fence,
+        _ => frontmatter_settings.content_mode,
+    }
+}
+
 fn parse_existing_prompt(existing: &Option<String>) -> Result<(Mapping, String), VaultError> {
     if let Some(content) = existing {
         let matter = Matter::<YAML>::new();
@@ -329,8 +1057,9 @@ fn parse_existing_prompt(existing: &Option<String>) -> Result<(Mapping, String),
     }
 }
 
-fn render_frontmatter(map: &Mapping) -> Result<String, VaultError> {
-    let mut yaml = serde_yaml::to_string(map).map_err(|e| VaultError::SerializeError(e.to_string()))?;
+pub(crate) fn render_frontmatter(map: &Mapping) -> Result<String, VaultError> {
+    let mut yaml =
+        serde_yaml::to_string(map).map_err(|e| VaultError::SerializeError(e.to_string()))?;
     if yaml.starts_with("---") {
         yaml = yaml.trim_start_matches("---\n").to_string();
     }
@@ -340,29 +1069,76 @@ fn render_frontmatter(map: &Mapping) -> Result<String, VaultError> {
     Ok(format!("---\n{}---\n\n", yaml))
 }
 
-fn normalize_frontmatter_key(key: &str) -> String {
+fn normalize_frontmatter_key(key: &str, default: &str) -> String {
     let trimmed = key.trim();
     if trimmed.is_empty() {
-        return "tags".to_string();
+        return default.to_string();
     }
     trimmed.to_string()
 }
 
-fn normalize_tag(tag: &str) -> Option<String> {
-    let normalized = tag.trim().trim_start_matches('#').trim();
-    if normalized.is_empty() {
+/// Normalize a tag, collapsing hierarchical separators (`lang//rust/` -> `lang/rust`).
+/// Every leading `#` goes, even behind a separator, so normalizing twice changes nothing.
+pub(crate) fn normalize_tag(tag: &str) -> Option<String> {
+    let normalized = tag.trim_start_matches(|c: char| c == '#' || c == '/' || c.is_whitespace());
+    let segments: Vec<&str> = normalized
+        .split('/')
+        .map(|segment| segment.trim())
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    if segments.is_empty() {
         None
     } else {
-        Some(normalized.to_string())
+        Some(segments.join("/"))
     }
 }
 
+/// The tag a filter like `#lang/` names
+pub fn tag_filter_name(filter: &str) -> &str {
+    filter.trim().trim_start_matches('#').trim_end_matches('/')
+}
+
+/// Check whether `tag` equals `filter` or is nested under it (`lang` matches `lang/rust`)
+pub fn tag_matches(tag: &str, filter: &str) -> bool {
+    let filter = tag_filter_name(filter);
+    tag == filter
+        || tag
+            .strip_prefix(filter)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Read a string value; list values (e.g. Obsidian `aliases`) yield their first item
 fn extract_string(map: &Mapping, key: &str) -> Option<String> {
-    map.get(&YamlValue::String(key.to_string()))
-        .and_then(|v| v.as_str().map(|s| s.to_string()))
+    match map.get(&YamlValue::String(key.to_string()))? {
+        YamlValue::Sequence(seq) => seq.first().and_then(|v| v.as_str().map(|s| s.to_string())),
+        value => value.as_str().map(|s| s.to_string()),
+    }
 }
 
-fn extract_tags(map: &Mapping, key: &str) -> Vec<String> {
+/// Write a string value (`None` removes the key); list values keep their other items
+fn set_string(map: &mut Mapping, key: &str, value: Option<String>) {
+    let key = YamlValue::String(key.to_string());
+    match (map.get_mut(&key), value) {
+        (Some(YamlValue::Sequence(seq)), Some(value)) => {
+            if seq.is_empty() {
+                seq.push(YamlValue::String(value));
+            } else {
+                seq[0] = YamlValue::String(value);
+            }
+        }
+        (Some(YamlValue::Sequence(seq)), None) if seq.len() > 1 => {
+            seq.remove(0);
+        }
+        (_, Some(value)) => {
+            map.insert(key, YamlValue::String(value));
+        }
+        (_, None) => {
+            map.remove(&key);
+        }
+    }
+}
+
+pub(crate) fn extract_tags(map: &Mapping, key: &str) -> Vec<String> {
     let key_value = YamlValue::String(key.to_string());
     let value = match map.get(&key_value) {
         Some(val) => val,
@@ -394,7 +1170,141 @@ fn extract_tags(map: &Mapping, key: &str) -> Vec<String> {
     tags
 }
 
-fn set_tags(map: &mut Mapping, key: &str, tags: &[String]) {
+/// Read a list of strings, also accepting one comma separated string; items are trimmed,
+/// blank and repeated ones dropped
+fn extract_list(map: &Mapping, key: &str) -> Vec<String> {
+    let items: Vec<&str> = match map.get(YamlValue::String(key.to_string())) {
+        Some(YamlValue::Sequence(seq)) => seq.iter().filter_map(YamlValue::as_str).collect(),
+        Some(YamlValue::String(text)) => text.split(',').collect(),
+        _ => Vec::new(),
+    };
+    let mut list: Vec<String> = Vec::new();
+    for item in items.into_iter().map(str::trim) {
+        if !item.is_empty() && !list.iter().any(|existing| existing == item) {
+            list.push(item.to_string());
+        }
+    }
+    list
+}
+
+/// Write a list of strings (see `extract_list`); an empty one removes the key
+fn set_list(map: &mut Mapping, key: &str, items: &[String]) {
+    let items: Vec<YamlValue> = items
+        .iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| YamlValue::String(item.to_string()))
+        .collect();
+    let key = YamlValue::String(key.to_string());
+    if items.is_empty() {
+        map.remove(&key);
+    } else {
+        map.insert(key, YamlValue::Sequence(items));
+    }
+}
+
+fn extract_custom_fields(map: &Mapping, keys: &FrontmatterKeys) -> HashMap<String, JsonValue> {
+    map.iter()
+        .filter_map(|(key, value)| {
+            let key = key.as_str()?;
+            if keys.is_reserved(key) {
+                return None;
+            }
+            // Values with non-string mapping keys have no JSON form and are left untouched
+            let value = serde_json::to_value(value).ok()?;
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/// Declared template variables; malformed entries are skipped
+fn extract_variables(map: &Mapping) -> Vec<TemplateVariable> {
+    let Some(YamlValue::Sequence(entries)) = map.get(YamlValue::String(VARIABLES_KEY.to_string()))
+    else {
+        return Vec::new();
+    };
+    let mut variables: Vec<TemplateVariable> = Vec::new();
+    for entry in entries {
+        let variable = match entry {
+            YamlValue::String(name) => TemplateVariable {
+                name: name.clone(),
+                default: None,
+                required: false,
+                options: Vec::new(),
+                description: None,
+            },
+            other => match serde_json::to_value(other).and_then(serde_json::from_value) {
+                Ok(variable) => variable,
+                Err(e) => {
+                    info!("Skipping malformed variable declaration: {}", e);
+                    continue;
+                }
+            },
+        };
+        let name = variable.name.trim();
+        if !name.is_empty() && !variables.iter().any(|v| v.name == name) {
+            variables.push(TemplateVariable {
+                name: name.to_string(),
+                ..variable
+            });
+        }
+    }
+    variables
+}
+
+/// Frontmatter form of a declared variable, leaving out unset fields
+fn variable_yaml(variable: &TemplateVariable) -> Result<YamlValue, VaultError> {
+    let mut map = Mapping::new();
+    map.insert("name".into(), variable.name.clone().into());
+    if let Some(default) = &variable.default {
+        let default =
+            serde_yaml::to_value(default).map_err(|e| VaultError::SerializeError(e.to_string()))?;
+        map.insert("default".into(), default);
+    }
+    if variable.required {
+        map.insert("required".into(), true.into());
+    }
+    if !variable.options.is_empty() {
+        let options = variable
+            .options
+            .iter()
+            .cloned()
+            .map(YamlValue::String)
+            .collect();
+        map.insert("options".into(), YamlValue::Sequence(options));
+    }
+    if let Some(description) = &variable.description {
+        map.insert("description".into(), description.clone().into());
+    }
+    Ok(YamlValue::Mapping(map))
+}
+
+/// Replace the custom fields in `map`, keeping the position of keys that already exist
+fn set_custom_fields(
+    map: &mut Mapping,
+    keys: &FrontmatterKeys,
+    fields: &HashMap<String, JsonValue>,
+) -> Result<(), VaultError> {
+    let stale: Vec<YamlValue> = extract_custom_fields(map, keys)
+        .into_keys()
+        .filter(|key| !fields.contains_key(key))
+        .map(YamlValue::String)
+        .collect();
+    for key in stale {
+        map.remove(&key);
+    }
+
+    let mut names: Vec<&String> = fields.keys().filter(|key| !keys.is_reserved(key)).collect();
+    names.sort();
+    for key in names {
+        let value = serde_yaml::to_value(&fields[key])
+            .map_err(|e| VaultError::SerializeError(e.to_string()))?;
+        map.insert(YamlValue::String(key.clone()), value);
+    }
+    Ok(())
+}
+
+pub(crate) fn set_tags(map: &mut Mapping, key: &str, tags: &[String]) {
     let normalized_tags: Vec<YamlValue> = tags
         .iter()
         .filter_map(|tag| normalize_tag(tag))
@@ -406,52 +1316,113 @@ fn set_tags(map: &mut Mapping, key: &str, tags: &[String]) {
     );
 }
 
-fn update_prompt_block(body: &str, new_content: &str) -> String {
+fn update_prompt_block(body: &str, new_content: &str, mode: ContentMode) -> String {
+    if mode == ContentMode::Body {
+        return format!("{}\n", new_content.trim_end());
+    }
+    update_named_block(body, None, new_content)
+}
+
+/// Replace the content of the prompt block called `name` (the unnamed block for `None`),
+/// appending a new block if there is none
+fn update_named_block(body: &str, name: Option<&str>, new_content: &str) -> String {
     let mut lines: Vec<String> = body.lines().map(|l| l.to_string()).collect();
-    let mut start = None;
-    let mut end = None;
-    let mut fence = "```";
-
-    for (i, line) in lines.iter().enumerate() {
-        let trimmed = line.trim_start();
-        if trimmed.starts_with("```prompt") || trimmed.starts_with("~~~prompt") {
-            start = Some(i);
-            fence = if trimmed.starts_with("~~~") { "~~~" } else { "```" };
-            break;
-        }
+    let block = {
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        find_prompt_blocks(&lines)
+            .pop()
+            .filter(|block| block.end.is_none())
+    };
+    // A block left open runs to the end of the file and would swallow an appended one
+    if let Some(open) = block {
+        let open_line = &lines[open.start];
+        let indent = &open_line[..open_line.len() - open_line.trim_start().len()];
+        let close = format!(
+            "{}{}",
+            indent,
+            open.fence_char.to_string().repeat(open.fence_len)
+        );
+        lines.push(close);
     }
+    let block = {
+        let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+        find_prompt_blocks(&lines)
+            .into_iter()
+            .find(|block| block.name.as_deref() == name)
+    };
 
-    if let Some(start_idx) = start {
-        for i in (start_idx + 1)..lines.len() {
-            if lines[i].trim_start().starts_with(fence) {
-                end = Some(i);
-                break;
-            }
+    if let Some(PromptBlock {
+        fence_char: ch,
+        fence_len: len,
+        start: start_idx,
+        end: Some(end_idx),
+        ..
+    }) = block
+    {
+        // Lengthen the existing fence if the new content contains a fence that would close it
+        let fence = fence_for(new_content, ch);
+        if fence.len() > len {
+            let open = lines[start_idx].clone();
+            let indent = &open[..open.len() - open.trim_start().len()];
+            let info = &open.trim_start()[len..];
+            lines[start_idx] = format!("{}{}{}", indent, fence, info);
+            lines[end_idx] = format!("{}{}", indent, fence);
         }
 
-        if let Some(end_idx) = end {
-            let mut replacement: Vec<String> = Vec::new();
-            if !new_content.is_empty() {
-                replacement = new_content.lines().map(|l| l.to_string()).collect();
-            }
-            lines.splice((start_idx + 1)..end_idx, replacement);
-            return lines.join("\n");
+        let mut replacement: Vec<String> = Vec::new();
+        if !new_content.is_empty() {
+            // Like `lines`, but a trailing newline keeps its empty line, as when appending
+            replacement = new_content
+                .split('\n')
+                .map(|l| l.strip_suffix('\r').unwrap_or(l).to_string())
+                .collect();
         }
+        lines.splice((start_idx + 1)..end_idx, replacement);
+        return lines.join("\n");
     }
 
-    let mut output = body.trim_end().to_string();
+    let fence = fence_for(new_content, '`');
+    let info = match name {
+        Some(name) => format!("prompt:{}", name),
+        None => "prompt".to_string(),
+    };
+    let mut output = lines.join("\n").trim_end().to_string();
     if !output.is_empty() {
         output.push_str("\n\n");
     }
-    output.push_str("```prompt\n");
+    output.push_str(&format!("{}{}\n", fence, info));
     output.push_str(new_content);
-    output.push_str("\n```\n");
+    output.push_str(&format!("\n{}\n", fence));
     output
 }
 
-fn compute_file_hash(content: &str) -> String {
+/// `content` without a leading byte order mark and with CRLF line endings turned into LF
+pub(crate) fn normalize_line_endings(content: &str) -> Cow<'_, str> {
+    let content = content.strip_prefix(BOM).unwrap_or(content);
+    if content.contains("\r\n") {
+        Cow::Owned(content.replace("\r\n", "\n"))
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
+/// `normalize_line_endings` when the vault asks for it, else `content` as it is
+fn apply_line_endings<'a>(
+    content: &'a str,
+    frontmatter_settings: &FrontmatterSettings,
+) -> Cow<'a, str> {
+    if frontmatter_settings.normalize_line_endings {
+        normalize_line_endings(content)
+    } else {
+        Cow::Borrowed(content)
+    }
+}
+
+/// Hash of a file's text, ignoring a byte order mark and CRLF line endings so a file
+/// re-saved by an editor with other line endings keeps its hash
+pub(crate) fn compute_file_hash(content: &str) -> String {
     let mut hasher = Sha256::new();
-    hasher.update(content.as_bytes());
+    hasher.update(normalize_line_endings(content).as_bytes());
     format!("{:x}", hasher.finalize())
 }
 
@@ -463,6 +1434,8 @@ pub fn compute_file_hash_from_path(file_path: &Path) -> Result<String, VaultErro
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::TempDir;
+    use proptest::prelude::*;
 
     #[test]
     fn test_extract_code_block() {
@@ -475,7 +1448,780 @@ with multiple lines
 
 More text"#;
 
-        let content = extract_code_block_content(markdown);
+        let content = extract_code_block_content(markdown, ContentMode::Fence);
         assert_eq!(content, "This is the prompt content\nwith multiple lines");
     }
+
+    #[test]
+    fn test_hierarchical_tags() {
+        let mut map = Mapping::new();
+        map.insert(
+            YamlValue::String("tags".to_string()),
+            YamlValue::String("#lang//rust/, tools".to_string()),
+        );
+        assert_eq!(extract_tags(&map, "tags"), vec!["lang/rust", "tools"]);
+
+        assert!(tag_matches("lang/rust", "lang"));
+        assert!(tag_matches("lang", "lang"));
+        assert!(!tag_matches("language", "lang"));
+        assert!(!tag_matches("lang", "lang/rust"));
+    }
+
+    #[test]
+    fn test_custom_fields_round_trip() {
+        let vault = TempDir::new("prompt-manager");
+        fs::write(
+            vault.join("prompt.md"),
+            "---\nmodel: gpt-4o\nauthor: me\ntags: [a]\n---\n\n```prompt\nhi\n```\n",
+        )
+        .unwrap();
+
+        let settings = FrontmatterSettings::default();
+        let mut file = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert_eq!(file.custom_fields["model"], JsonValue::from("gpt-4o"));
+        assert!(!file.custom_fields.contains_key("tags"));
+
+        file.custom_fields.remove("author");
+        file.custom_fields
+            .insert("source".to_string(), JsonValue::from(vec!["x", "y"]));
+        write_prompt_file(&vault, &file, &settings).unwrap();
+
+        let reread = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert_eq!(reread.custom_fields, file.custom_fields);
+    }
+
+    #[test]
+    fn test_archived_round_trip() {
+        let vault = TempDir::new("prompt-manager");
+        fs::write(
+            vault.join("prompt.md"),
+            "---\narchived: true\n---\n\n```prompt\nold\n```\n",
+        )
+        .unwrap();
+
+        let settings = FrontmatterSettings::default();
+        let mut file = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert!(file.archived);
+        assert!(file.custom_fields.is_empty());
+
+        file.archived = false;
+        write_prompt_file(&vault, &file, &settings).unwrap();
+        let written = fs::read_to_string(vault.join("prompt.md")).unwrap();
+        assert!(!written.contains("archived"));
+    }
+
+    #[test]
+    fn test_strip_notes() {
+        let settings = FrontmatterSettings::default();
+        let content = "---\ntitle: Review\nnotes: |-\n  works better with\n  examples\n---\n\n```prompt\nReview this\n```\n";
+        let file = parse_prompt_file("review.md", content, &settings);
+        assert_eq!(file.notes.as_deref(), Some("works better with\nexamples"));
+        assert!(file.custom_fields.is_empty());
+
+        let shared = strip_notes(content).unwrap();
+        assert!(!shared.contains("notes") && !shared.contains("examples"));
+        let reread = parse_prompt_file("review.md", &shared, &settings);
+        assert_eq!(reread.title.as_deref(), Some("Review"));
+        assert_eq!(reread.content, "Review this");
+        assert_eq!(reread.notes, None);
+        let windows = format!("\u{feff}{}", content.replace('\n', "\r\n"));
+        assert_eq!(strip_notes(&windows).unwrap(), shared);
+
+        // Nothing else left in the frontmatter, and nothing to strip
+        let shared = strip_notes("---\nnotes: draft\n---\n\nHello\n").unwrap();
+        assert_eq!(shared, "Hello\n");
+        assert_eq!(
+            strip_notes("---\ntitle: A\n---\nB").unwrap(),
+            "---\ntitle: A\n---\nB"
+        );
+    }
+
+    #[test]
+    fn test_validate_frontmatter_settings() {
+        assert!(validate_frontmatter_settings(&FrontmatterSettings::default()).is_ok());
+        let settings = |update: fn(&mut FrontmatterSettings)| {
+            let mut settings = FrontmatterSettings::default();
+            update(&mut settings);
+            validate_frontmatter_settings(&settings)
+        };
+        // A blank key falls back to the default one
+        assert!(settings(|s| s.title_property = " ".to_string()).is_ok());
+        assert!(settings(|s| s.title_property = "aliases".to_string()).is_ok());
+        assert!(settings(|s| s.title_property = "tags".to_string()).is_err());
+        assert!(settings(|s| s.prompt_tags_property = " created ".to_string()).is_err());
+        assert!(settings(|s| s.description_property = "archived".to_string()).is_err());
+        assert!(settings(|s| s.file_extensions = vec!["md/../x".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_check_filename_template() {
+        assert!(check_filename_template("{title}-{date}").is_ok());
+        assert!(check_filename_template("notes").is_ok());
+        assert!(check_filename_template("{title}-{slug}").is_err());
+        assert!(check_filename_template("{date").is_err());
+    }
+
+    #[test]
+    fn test_frontmatter_key_mapping() {
+        let vault = TempDir::new("prompt-manager");
+        fs::write(
+            vault.join("prompt.md"),
+            "---\ndate: 2024-01-02\naliases: [Greeting, Hello]\n---\n\n```prompt\nhi\n```\n",
+        )
+        .unwrap();
+
+        let settings = FrontmatterSettings {
+            created_property: "date".to_string(),
+            title_property: "aliases".to_string(),
+            ..FrontmatterSettings::default()
+        };
+        let mut file = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert_eq!(file.created.as_deref(), Some("2024-01-02"));
+        assert_eq!(file.title.as_deref(), Some("Greeting"));
+        assert!(file.custom_fields.is_empty());
+
+        file.title = Some("Hi".to_string());
+        write_prompt_file(&vault, &file, &settings).unwrap();
+
+        let written = fs::read_to_string(vault.join("prompt.md")).unwrap();
+        assert!(written.contains("date: 2024-01-02"));
+        assert!(!written.contains("created:"));
+        assert!(!written.contains("title:"));
+        let reread = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert_eq!(reread.title.as_deref(), Some("Hi"));
+    }
+
+    #[test]
+    fn test_nested_code_fences() {
+        let content = "Explain this:\n```rust\nfn main() {}\n```";
+        let body = update_prompt_block("", content, ContentMode::Fence);
+        assert!(body.starts_with("````prompt\n"));
+        assert_eq!(
+            extract_code_block_content(&body, ContentMode::Fence),
+            content
+        );
+
+        // An existing block is lengthened in place, keeping the text around it
+        let existing = "Intro\n\n```prompt\nold\n```\n\nOutro";
+        let updated = update_prompt_block(existing, content, ContentMode::Fence);
+        assert_eq!(
+            updated,
+            format!("Intro\n\n````prompt\n{}\n````\n\nOutro", content)
+        );
+        assert_eq!(
+            extract_code_block_content(&updated, ContentMode::Fence),
+            content
+        );
+    }
+
+    #[test]
+    fn test_filename_template() {
+        assert_eq!(slugify("  Summarize PR #12: fast!"), "summarize-pr-12-fast");
+
+        let vault = TempDir::new("prompt-manager");
+
+        let first = generate_unique_file_path(&vault, "{title}", Some("Summarize PR")).unwrap();
+        assert_eq!(first, "summarize-pr.md");
+        fs::write(vault.join(&first), "").unwrap();
+        let second = generate_unique_file_path(&vault, "{title}", Some("Summarize PR")).unwrap();
+        assert_eq!(second, "summarize-pr-2.md");
+        assert_eq!(
+            generate_unique_file_path(&vault, "{title}", None).unwrap(),
+            "untitled.md"
+        );
+    }
+
+    #[test]
+    fn test_prompt_variants() {
+        let markdown = "```prompt\nmain\n```\n\n```prompt:short\nbrief\n```\n";
+        assert_eq!(
+            extract_code_block_content(markdown, ContentMode::Fence),
+            "main"
+        );
+        assert_eq!(
+            extract_variants(markdown, ContentMode::Fence),
+            vec![PromptVariant {
+                name: "short".to_string(),
+                content: "brief".to_string(),
+            }]
+        );
+
+        let updated = update_named_block(markdown, Some("short"), "briefer");
+        assert_eq!(
+            updated,
+            "```prompt\nmain\n```\n\n```prompt:short\nbriefer\n```"
+        );
+        let added = update_named_block(&updated, Some("long"), "verbose");
+        assert_eq!(extract_variants(&added, ContentMode::Fence).len(), 2);
+        assert_eq!(
+            extract_code_block_content(&added, ContentMode::Fence),
+            "main"
+        );
+    }
+
+    #[test]
+    fn test_body_content_mode() {
+        let markdown = "\nJust a note body\n\n```js\nconsole.log(1)\n```\n";
+        assert_eq!(
+            extract_code_block_content(markdown, ContentMode::Body),
+            "Just a note body\n\n```js\nconsole.log(1)\n```"
+        );
+        assert_eq!(
+            update_prompt_block(markdown, "Replaced", ContentMode::Body),
+            "Replaced\n"
+        );
+
+        let mut map = Mapping::new();
+        map.insert(
+            YamlValue::String(CONTENT_MODE_KEY.to_string()),
+            YamlValue::String("body".to_string()),
+        );
+        let settings = FrontmatterSettings::default();
+        assert_eq!(resolve_content_mode(&map, &settings), ContentMode::Body);
+        assert_eq!(
+            resolve_content_mode(&Mapping::new(), &settings),
+            ContentMode::Fence
+        );
+    }
+
+    #[test]
+    fn test_crlf_and_bom_normalization() {
+        let vault = TempDir::new("prompt-manager");
+        let lf = "---\ntags:\n- a\n---\n\n```prompt\nhi\nthere\n```\n";
+        let windows = format!("\u{feff}{}", lf.replace('\n', "\r\n"));
+        fs::write(vault.join("prompt.md"), &windows).unwrap();
+        assert_eq!(compute_file_hash(&windows), compute_file_hash(lf));
+
+        let settings = FrontmatterSettings {
+            normalize_line_endings: true,
+            ..FrontmatterSettings::default()
+        };
+        let mut file = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert_eq!(file.tags, vec!["a"]);
+        assert_eq!(file.content, "hi\nthere");
+
+        // Saving rewrites the whole file with LF endings, keeping its single frontmatter
+        file.content = "hi\r\nagain".to_string();
+        write_prompt_file(&vault, &file, &settings).unwrap();
+        let written = fs::read_to_string(vault.join("prompt.md")).unwrap();
+        assert!(!written.contains('\r') && !written.starts_with(BOM));
+        assert_eq!(written.matches("---\n").count(), 2);
+        let reread = read_prompt_file(&vault, &vault.join("prompt.md"), &settings).unwrap();
+        assert_eq!(reread.tags, vec!["a"]);
+        assert_eq!(reread.content, "hi\nagain");
+    }
+
+    #[test]
+    fn test_atomic_write_and_temp_cleanup() {
+        let vault = TempDir::new("prompt-manager");
+
+        let file_path = vault.join("prompt.md");
+        write_atomic(&file_path, "first").unwrap();
+        write_atomic(&file_path, "second").unwrap();
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "second");
+        assert!(!vault.join("prompt.md.tmp").exists());
+
+        fs::write(vault.join("stray.md.tmp"), "partial").unwrap();
+        scan_vault(&vault, &FrontmatterSettings::default()).unwrap();
+        assert!(!vault.join("stray.md.tmp").exists());
+
+        // Only temp files of the configured prompt extensions are cleaned up
+        let settings = FrontmatterSettings {
+            file_extensions: vec!["txt".to_string()],
+            ..FrontmatterSettings::default()
+        };
+        fs::write(vault.join("stray.txt.tmp"), "partial").unwrap();
+        fs::write(vault.join("mine.md.tmp"), "keep").unwrap();
+        scan_vault(&vault, &settings).unwrap();
+        assert!(!vault.join("stray.txt.tmp").exists());
+        assert!(vault.join("mine.md.tmp").exists());
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_colon_in_existing_file_name() {
+        let vault = TempDir::new("prompt-manager");
+        fs::write(vault.join("a:b.md"), "```prompt\nhi\n```\n").unwrap();
+
+        let settings = FrontmatterSettings::default();
+        assert_eq!(
+            normalize_relative_path("a:b", MARKDOWN_ONLY).unwrap(),
+            "a:b.md"
+        );
+        delete_prompt_file(&vault, "a:b.md", &settings).unwrap();
+        assert!(!vault.join("a:b.md").exists());
+    }
+
+    #[test]
+    fn test_file_extensions() {
+        let settings = FrontmatterSettings {
+            file_extensions: vec![
+                "md".to_string(),
+                ".txt".to_string(),
+                "prompt.md".to_string(),
+            ],
+            ..FrontmatterSettings::default()
+        };
+        let extensions = &settings.file_extensions;
+        assert!(has_prompt_extension("notes.TXT", extensions));
+        assert!(has_prompt_extension("review.prompt.md", extensions));
+        assert!(!has_prompt_extension("data.json", extensions));
+        assert!(!has_prompt_extension(".txt", extensions));
+        assert!(!has_prompt_extension("notes.txt", MARKDOWN_ONLY));
+        assert_eq!(
+            normalize_relative_path("notes.txt", extensions).unwrap(),
+            "notes.txt"
+        );
+        assert_eq!(
+            normalize_relative_path("notes.txt", MARKDOWN_ONLY).unwrap(),
+            "notes.txt.md"
+        );
+
+        let vault = TempDir::new("prompt-manager");
+        fs::write(vault.join("raw.txt"), "---\ntags: [a]\n---\nSay hi\n").unwrap();
+        fs::write(vault.join("data.json"), "{}").unwrap();
+        fs::write(vault.join("plain.md"), "```prompt\nHello\n```\n").unwrap();
+
+        let mut names: Vec<String> = scan_vault(&vault, &settings)
+            .unwrap()
+            .prompts
+            .into_iter()
+            .map(|prompt| prompt.file_path)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["plain.md", "raw.txt"]);
+
+        // Files that are not markdown are all prompt text, frontmatter-looking lines included
+        let mut raw = find_prompt_by_id(&vault, "raw.txt", &settings).unwrap();
+        assert!(raw.tags.is_empty());
+        assert_eq!(raw.content, "---\ntags: [a]\n---\nSay hi");
+        raw.content = "Say bye".to_string();
+        raw.tags = vec!["ignored".to_string()];
+        write_prompt_file(&vault, &raw, &settings).unwrap();
+        assert_eq!(
+            fs::read_to_string(vault.join("raw.txt")).unwrap(),
+            "Say bye\n"
+        );
+    }
+
+    #[test]
+    fn test_skip_large_and_binary_files() {
+        let vault = TempDir::new("prompt-manager");
+        fs::write(vault.join("ok.md"), "```prompt\nHello\n```\n").unwrap();
+        fs::write(vault.join("huge.md"), "x".repeat(2048)).unwrap();
+        fs::write(vault.join("image.md"), b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR").unwrap();
+        fs::write(vault.join("latin1.md"), b"caf\xe9").unwrap();
+
+        let settings = FrontmatterSettings {
+            max_file_size_kb: 1,
+            ..FrontmatterSettings::default()
+        };
+        let scan = scan_vault(&vault, &settings).unwrap();
+        assert_eq!(scan.prompts.len(), 1);
+        let mut skipped: Vec<(String, SkipReason)> = scan
+            .skipped
+            .into_iter()
+            .map(|file| (file.path, file.reason))
+            .collect();
+        skipped.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            skipped,
+            vec![
+                ("huge.md".to_string(), SkipReason::TooLarge),
+                ("image.md".to_string(), SkipReason::Binary),
+                ("latin1.md".to_string(), SkipReason::Unreadable),
+            ]
+        );
+
+        // No limit
+        let settings = FrontmatterSettings {
+            max_file_size_kb: 0,
+            ..FrontmatterSettings::default()
+        };
+        assert_eq!(scan_vault(&vault, &settings).unwrap().prompts.len(), 2);
+    }
+
+    #[test]
+    fn test_scan_progress_and_cancel() {
+        let vault = TempDir::new("prompt-manager");
+        for name in ["a.md", "b.md", "c.md"] {
+            fs::write(vault.join(name), "Hello").unwrap();
+        }
+        fs::write(vault.join("notes.txt"), "not a prompt").unwrap();
+        let settings = FrontmatterSettings::default();
+
+        let mut reports = Vec::new();
+        let cancel = CancellationToken::new();
+        let scan = scan_vault_with_progress(&vault, &settings, &cancel, |scanned, total, name| {
+            reports.push((scanned, total, name.to_string()))
+        })
+        .unwrap();
+        assert_eq!(scan.prompts.len(), 3);
+        reports.sort();
+        assert_eq!(
+            reports.iter().map(|r| (r.0, r.1)).collect::<Vec<_>>(),
+            vec![(0, 3), (1, 3), (2, 3)]
+        );
+
+        // Cancelling mid-scan stops before the next file
+        let mut read = 0;
+        let result = scan_vault_with_progress(&vault, &settings, &cancel, |_, _, _| {
+            read += 1;
+            cancel.cancel();
+        });
+        assert!(matches!(result, Err(VaultError::Cancelled)));
+        assert_eq!(read, 1);
+    }
+
+    #[test]
+    fn test_declared_variables() {
+        let content = r#"---
+variables:
+  - name: language
+    default: rust
+    required: true
+    options: [rust, python]
+  - focus
+  - {required: true}
+---
+```prompt
+{{language}} {{focus}}
+```
+"#;
+        let file = parse_prompt_file("a.md", content, &FrontmatterSettings::default());
+        assert!(file.custom_fields.is_empty());
+        assert_eq!(
+            file.variables
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["language", "focus"]
+        );
+        assert_eq!(file.variables[0].default, Some(JsonValue::from("rust")));
+        assert!(file.variables[0].required);
+        assert_eq!(file.variables[0].options, vec!["rust", "python"]);
+        assert!(!file.variables[1].required);
+
+        // Saving under a new name carries the declaration over
+        let vault = TempDir::new("prompt-manager");
+        let renamed = PromptFile {
+            id: "b.md".to_string(),
+            file_path: "b.md".to_string(),
+            ..file.clone()
+        };
+        write_prompt_file(&vault, &renamed, &FrontmatterSettings::default()).unwrap();
+        let reread =
+            read_prompt_file(&vault, &vault.join("b.md"), &FrontmatterSettings::default()).unwrap();
+        assert_eq!(reread.variables, file.variables);
+    }
+
+    #[test]
+    fn test_extract_wikilinks() {
+        let body = "See [[system/base]] and [[Follow up|the follow-up]].\n\
+                    Again [[system/base#Rules]], not [[ ]] or [[broken\n]] [[tail";
+        assert_eq!(
+            extract_wikilinks(body),
+            vec!["system/base".to_string(), "Follow up".to_string()]
+        );
+
+        let content = "---\ntags: []\n---\nUses [[base]].\n```prompt\nHello\n```\n";
+        let file = parse_prompt_file("a.md", content, &FrontmatterSettings::default());
+        assert!(file.links.is_empty());
+        let settings = FrontmatterSettings {
+            parse_wikilinks: true,
+            ..FrontmatterSettings::default()
+        };
+        let file = parse_prompt_file("a.md", content, &settings);
+        assert_eq!(file.links, vec!["base".to_string()]);
+    }
+
+    /// Text with awkward characters for YAML: quotes, colons, comment markers, unicode
+    fn yaml_text() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "\\PC{1,24}",
+            Just("yes".to_string()),
+            Just("null".to_string()),
+            Just("~".to_string()),
+            Just("1e3".to_string()),
+            Just("2024-01-02".to_string()),
+            Just("key: value".to_string()),
+            Just("- item".to_string()),
+            Just("# not a comment".to_string()),
+            Just("'single' \"double\"".to_string()),
+            Just("  padded  ".to_string()),
+            Just("line one\nline two\n---\n".to_string()),
+        ]
+    }
+
+    /// Prompt text: printable lines, including fences and frontmatter markers
+    fn prompt_text() -> impl Strategy<Value = String> {
+        let line = prop_oneof![
+            "\\PC{0,30}",
+            Just("```".to_string()),
+            Just("~~~".to_string()),
+            Just("```prompt".to_string()),
+            Just("---".to_string()),
+            Just(String::new()),
+        ];
+        prop::collection::vec(line, 0..6).prop_map(|lines| lines.join("\n"))
+    }
+
+    fn json_value() -> impl Strategy<Value = JsonValue> {
+        let leaf = prop_oneof![
+            Just(JsonValue::Null),
+            any::<bool>().prop_map(JsonValue::from),
+            any::<i64>().prop_map(JsonValue::from),
+            (-1e6..1e6f64).prop_map(JsonValue::from),
+            yaml_text().prop_map(JsonValue::from),
+        ];
+        leaf.prop_recursive(2, 8, 4, |inner| {
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 0..4).prop_map(JsonValue::from),
+                prop::collection::hash_map("[a-z]{1,6}", inner, 0..4)
+                    .prop_map(|map| JsonValue::Object(map.into_iter().collect())),
+            ]
+        })
+    }
+
+    fn custom_fields() -> impl Strategy<Value = HashMap<String, JsonValue>> {
+        let keys = FrontmatterKeys::new(&FrontmatterSettings::default());
+        prop::collection::hash_map("[a-z][a-z0-9_]{0,10}", json_value(), 0..4).prop_map(
+            move |fields| {
+                fields
+                    .into_iter()
+                    .filter(|(key, _)| !keys.is_reserved(key))
+                    .collect()
+            },
+        )
+    }
+
+    fn prompt_file() -> impl Strategy<Value = PromptFile> {
+        (
+            prop::collection::vec(prop_oneof!["[\\pL0-9_-]{1,12}", yaml_text()], 0..5),
+            yaml_text(),
+            prop::option::of(yaml_text()),
+            prop::option::of(yaml_text()),
+            prompt_text(),
+            custom_fields(),
+            prop::collection::btree_map("[a-z0-9-]{1,8}", prompt_text(), 0..3),
+            any::<bool>(),
+            prop::option::of(yaml_text()),
+            prop::option::of(yaml_text()),
+            (
+                prop::option::of(yaml_text()),
+                prop::collection::btree_set("[a-z0-9][a-z0-9.-]{0,11}", 0..3),
+                prop::option::of(yaml_text()),
+                prop::option::of(prop_oneof![
+                    Just(PromptStatus::Draft),
+                    Just(PromptStatus::Testing),
+                    Just(PromptStatus::Production),
+                    Just(PromptStatus::Deprecated),
+                ]),
+            ),
+        )
+            .prop_map(
+                |(
+                    tags,
+                    created,
+                    title,
+                    description,
+                    content,
+                    custom_fields,
+                    variants,
+                    archived,
+                    color,
+                    icon,
+                    (notes, model_targets, language, status),
+                )| {
+                    PromptFile {
+                        id: "prompt.md".to_string(),
+                        file_path: "prompt.md".to_string(),
+                        tags,
+                        created: Some(created),
+                        content,
+                        file_hash: None,
+                        title,
+                        description,
+                        color,
+                        icon,
+                        notes,
+                        model_targets: model_targets.into_iter().collect(),
+                        language,
+                        status,
+                        custom_fields,
+                        variants: variants
+                            .into_iter()
+                            .map(|(name, content)| PromptVariant { name, content })
+                            .collect(),
+                        archived,
+                        links: Vec::new(),
+                        variables: Vec::new(),
+                    }
+                },
+            )
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(128))]
+
+        /// Whatever `write_prompt_file` writes, `read_prompt_file` reads back unchanged
+        /// (tags come back normalized, blank titles and descriptions are dropped)
+        #[test]
+        fn test_frontmatter_round_trip(file in prompt_file()) {
+            let vault = TempDir::new("prompt-manager");
+            let settings = FrontmatterSettings::default();
+            write_prompt_file(&vault, &file, &settings).unwrap();
+            let reread = read_prompt_file(&vault, &vault.join("prompt.md"), &settings);
+            let reread = reread.unwrap();
+
+            let tags: Vec<String> = file.tags.iter().filter_map(|tag| normalize_tag(tag)).collect();
+            let non_blank = |text: &Option<String>| text.clone().filter(|t| !t.trim().is_empty());
+            prop_assert_eq!(reread.tags, tags);
+            prop_assert_eq!(reread.created, file.created);
+            prop_assert_eq!(reread.title, non_blank(&file.title));
+            prop_assert_eq!(reread.description, non_blank(&file.description));
+            prop_assert_eq!(reread.color, non_blank(&file.color));
+            prop_assert_eq!(reread.icon, non_blank(&file.icon));
+            prop_assert_eq!(reread.notes, non_blank(&file.notes));
+            prop_assert_eq!(reread.model_targets, file.model_targets);
+            prop_assert_eq!(reread.language, non_blank(&file.language));
+            prop_assert_eq!(reread.status, file.status);
+            prop_assert_eq!(reread.content, file.content);
+            prop_assert_eq!(reread.custom_fields, file.custom_fields);
+            prop_assert_eq!(reread.variants, file.variants);
+            prop_assert_eq!(reread.archived, file.archived);
+        }
+
+        /// Normalizing a tag twice changes nothing, so saved tags never drift across saves
+        #[test]
+        fn test_normalize_tag_idempotent(tag in "[#/ a-zé]{0,12}") {
+            if let Some(normalized) = normalize_tag(&tag) {
+                prop_assert_eq!(normalize_tag(&normalized), Some(normalized.clone()));
+                prop_assert!(!normalized.starts_with('#') && !normalized.contains("//"));
+            }
+        }
+    }
+
+    /// Path-like input: Windows drives and UNC prefixes, separators, dots, unicode, controls
+    fn path_input() -> impl Strategy<Value = String> {
+        let piece = prop_oneof![
+            "[\\pL0-9 _-]{1,8}",
+            Just(".".to_string()),
+            Just("..".to_string()),
+            Just("/".to_string()),
+            Just("\\".to_string()),
+            Just("C:".to_string()),
+            Just("\\\\server\\share".to_string()),
+            Just(".md".to_string()),
+            Just(".txt".to_string()),
+            Just("\0".to_string()),
+            Just("\r\n".to_string()),
+            Just("%2e%2e".to_string()),
+        ];
+        prop::collection::vec(piece, 0..6).prop_map(|pieces| pieces.concat())
+    }
+
+    /// Markdown around prompt blocks: plain text, other fences, named and unnamed blocks
+    /// (possibly left open), with LF or CRLF line endings
+    fn markdown_body() -> impl Strategy<Value = String> {
+        let line = prop_oneof![
+            "\\PC{0,20}",
+            Just("```".to_string()),
+            Just("````".to_string()),
+            Just("~~~".to_string()),
+            Just("```rust".to_string()),
+            Just("```prompt".to_string()),
+            Just("````prompt".to_string()),
+            Just("  ```prompt".to_string()),
+            Just("```prompt:alt".to_string()),
+            Just("~~~prompt:other".to_string()),
+            Just("```promptly".to_string()),
+        ];
+        (prop::collection::vec(line, 0..12), any::<bool>())
+            .prop_map(|(lines, crlf)| lines.join(if crlf { "\r\n" } else { "\n" }))
+    }
+
+    /// Prompt text that may contain fences, with LF or CRLF line endings
+    fn block_content() -> impl Strategy<Value = String> {
+        (prompt_text(), any::<bool>()).prop_map(|(text, crlf)| {
+            if crlf {
+                text.replace('\n', "\r\n")
+            } else {
+                text
+            }
+        })
+    }
+
+    proptest! {
+        /// Accepted paths name a single file directly in the vault, on any platform, and
+        /// normalizing them again changes nothing
+        #[test]
+        fn test_fuzz_normalize_relative_path(path in path_input(), txt in any::<bool>()) {
+            let extensions: Vec<String> = if txt {
+                vec!["md".to_string(), "txt".to_string()]
+            } else {
+                MARKDOWN_ONLY.to_vec()
+            };
+            if let Ok(normalized) = normalize_relative_path(&path, &extensions) {
+                prop_assert!(!normalized.is_empty());
+                prop_assert!(!normalized.contains(['/', '\\']));
+                prop_assert!(!cfg!(windows) || !normalized.contains(':'));
+                prop_assert!(!normalized.contains(".."));
+                prop_assert!(!normalized.chars().any(char::is_control));
+                prop_assert!(
+                    normalized.ends_with(".md") || has_prompt_extension(&normalized, &extensions)
+                );
+                let mut components = Path::new(&normalized).components();
+                prop_assert!(matches!(
+                    components.next(),
+                    Some(std::path::Component::Normal(_))
+                ));
+                prop_assert!(components.next().is_none());
+                prop_assert_eq!(
+                    normalize_relative_path(&normalized, &extensions).ok(),
+                    Some(normalized.clone())
+                );
+            }
+        }
+
+        /// Whatever surrounds it, the text written by `update_prompt_block` is what
+        /// `extract_code_block_content` reads back, and the variants are left alone
+        #[test]
+        fn test_fuzz_prompt_block_round_trip(body in markdown_body(), content in block_content()) {
+            let expected = content
+                .split('\n')
+                .map(|l| l.strip_suffix('\r').unwrap_or(l))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let updated = update_prompt_block(&body, &content, ContentMode::Fence);
+            prop_assert_eq!(
+                extract_code_block_content(&updated, ContentMode::Fence),
+                expected.clone()
+            );
+            prop_assert_eq!(
+                extract_variants(&updated, ContentMode::Fence),
+                extract_variants(&body, ContentMode::Fence)
+            );
+
+            // Saving again over the updated file reads back the same
+            let again = update_prompt_block(&updated, &content, ContentMode::Fence);
+            prop_assert_eq!(extract_code_block_content(&again, ContentMode::Fence), expected);
+
+            let updated = update_prompt_block(&body, &content, ContentMode::Body);
+            prop_assert_eq!(
+                extract_code_block_content(&updated, ContentMode::Body),
+                content.trim_start_matches(['\r', '\n']).trim_end()
+            );
+        }
+
+        /// Extraction never panics on arbitrary input and never returns more than it was given
+        #[test]
+        fn test_fuzz_extract_code_block(markdown in "\\PC{0,200}|(\\PC{0,10}\r?\n){0,10}") {
+            for mode in [ContentMode::Fence, ContentMode::Body] {
+                let content = extract_code_block_content(&markdown, mode);
+                prop_assert!(content.len() <= markdown.len());
+                extract_variants(&markdown, mode);
+            }
+        }
+    }
 }
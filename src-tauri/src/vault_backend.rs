@@ -0,0 +1,689 @@
+//! Storage for vault files. Prompts are always read from and written to the vault
+//! folder; a remote backend is synced with that folder, which doubles as its offline cache.
+
+use crate::config::{AppConfig, VaultBackendKind};
+use crate::integrations::s3::S3Backend;
+use crate::integrations::webdav::WebDavBackend;
+use crate::promptpack;
+use crate::vault::{self, VaultError};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Versions of both sides after the last sync, kept in the vault folder
+const SYNC_STATE_FILE: &str = ".prompt-manager-sync.json";
+/// Local files a sync may delete before it asks for confirmation
+const MAX_UNCONFIRMED_DELETIONS: usize = 10;
+/// Paths deleted by a sync, kept on the server next to the prompt files
+pub const TOMBSTONES_FILE: &str = ".prompt-manager-tombstones.json";
+
+/// A prompt file in a backend
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackendFile {
+    /// Vault-relative path
+    pub path: String,
+    /// Changes whenever the file does (a content hash or an ETag)
+    pub version: String,
+}
+
+/// A store of prompt files, addressed by vault-relative path (see `store_path`). Futures are `Send` so
+/// commands can await them.
+pub trait VaultBackend {
+    /// Backend and location of the store, e.g. `webdav:https://host/folder/`
+    fn identity(&self) -> String;
+    /// Prompt files (those with one of the vault's file extensions) in the store
+    fn list(&self) -> impl Future<Output = Result<Vec<BackendFile>, VaultError>> + Send;
+    /// Fails with `VaultError::NotFound` when the file does not exist
+    fn read(&self, path: &str) -> impl Future<Output = Result<String, VaultError>> + Send;
+    fn write(
+        &self,
+        path: &str,
+        content: &str,
+    ) -> impl Future<Output = Result<(), VaultError>> + Send;
+    /// Delete a file; deleting a missing file is not an error
+    fn delete(&self, path: &str) -> impl Future<Output = Result<(), VaultError>> + Send;
+}
+
+/// `path` checked to be a prompt file of a flat vault, with one of the prompt file
+/// `extensions`, or the tombstone list
+pub fn store_path(path: &str, extensions: &[String]) -> Result<String, VaultError> {
+    if path == TOMBSTONES_FILE {
+        return Ok(path.to_string());
+    }
+    vault::normalize_relative_path(path, extensions)
+}
+
+/// The vault folder on this machine
+pub struct LocalBackend {
+    root: PathBuf,
+    /// Prompt file extensions, as in `FrontmatterSettings::file_extensions`
+    extensions: Vec<String>,
+}
+
+impl LocalBackend {
+    pub fn new(root: impl Into<PathBuf>, extensions: &[String]) -> Self {
+        Self {
+            root: root.into(),
+            extensions: extensions.to_vec(),
+        }
+    }
+
+    fn file_path(&self, path: &str) -> Result<PathBuf, VaultError> {
+        Ok(self.root.join(store_path(path, &self.extensions)?))
+    }
+}
+
+impl VaultBackend for LocalBackend {
+    fn identity(&self) -> String {
+        format!("local:{}", self.root.display())
+    }
+
+    async fn list(&self) -> Result<Vec<BackendFile>, VaultError> {
+        let (root, extensions) = (self.root.clone(), self.extensions.clone());
+        vault::blocking(move || {
+            let entries = fs::read_dir(&root).map_err(|e| VaultError::IoError(e.to_string()))?;
+            let mut files = Vec::new();
+            for entry in entries.flatten() {
+                let file_path = entry.path();
+                let Some(name) = file_path.file_name().and_then(|name| name.to_str()) else {
+                    continue;
+                };
+                if !vault::has_prompt_extension(name, &extensions) || !file_path.is_file() {
+                    continue;
+                }
+                files.push(BackendFile {
+                    path: name.to_string(),
+                    version: vault::compute_file_hash_from_path(&file_path)?,
+                });
+            }
+            Ok(files)
+        })
+        .await
+    }
+
+    async fn read(&self, path: &str) -> Result<String, VaultError> {
+        let file_path = self.file_path(path)?;
+        let path = path.to_string();
+        vault::blocking(move || {
+            fs::read_to_string(file_path).map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => VaultError::NotFound(path),
+                _ => VaultError::IoError(e.to_string()),
+            })
+        })
+        .await
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<(), VaultError> {
+        let file_path = self.file_path(path)?;
+        let content = content.to_string();
+        vault::blocking(move || {
+            vault::write_atomic(&file_path, &content)
+                .map_err(|e| VaultError::IoError(e.to_string()))
+        })
+        .await
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), VaultError> {
+        let file_path = self.file_path(path)?;
+        vault::blocking(move || match fs::remove_file(file_path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(VaultError::IoError(e.to_string()))
+            }
+            _ => Ok(()),
+        })
+        .await
+    }
+}
+
+/// The server a vault is kept on
+pub enum RemoteBackend {
+    WebDav(WebDavBackend),
+    S3(S3Backend),
+}
+
+impl RemoteBackend {
+    /// The backend configured in `config`, or `None` for a local-only vault
+    pub fn from_config(config: &AppConfig) -> Result<Option<Self>, VaultError> {
+        let extensions = &config.frontmatter.file_extensions;
+        match config.remote.backend {
+            VaultBackendKind::Local => Ok(None),
+            VaultBackendKind::WebDav => Ok(Some(RemoteBackend::WebDav(WebDavBackend::new(
+                &config.remote.webdav,
+                extensions,
+            )?))),
+            VaultBackendKind::S3 => Ok(Some(RemoteBackend::S3(S3Backend::new(
+                &config.remote.s3,
+                extensions,
+            )?))),
+        }
+    }
+}
+
+impl VaultBackend for RemoteBackend {
+    fn identity(&self) -> String {
+        match self {
+            RemoteBackend::WebDav(backend) => backend.identity(),
+            RemoteBackend::S3(backend) => backend.identity(),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<BackendFile>, VaultError> {
+        match self {
+            RemoteBackend::WebDav(backend) => backend.list().await,
+            RemoteBackend::S3(backend) => backend.list().await,
+        }
+    }
+
+    async fn read(&self, path: &str) -> Result<String, VaultError> {
+        match self {
+            RemoteBackend::WebDav(backend) => backend.read(path).await,
+            RemoteBackend::S3(backend) => backend.read(path).await,
+        }
+    }
+
+    async fn write(&self, path: &str, content: &str) -> Result<(), VaultError> {
+        match self {
+            RemoteBackend::WebDav(backend) => backend.write(path, content).await,
+            RemoteBackend::S3(backend) => backend.write(path, content).await,
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), VaultError> {
+        match self {
+            RemoteBackend::WebDav(backend) => backend.delete(path).await,
+            RemoteBackend::S3(backend) => backend.delete(path).await,
+        }
+    }
+}
+
+/// Outcome of `sync_remote`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteSyncStats {
+    /// Vault files written or deleted to match the server
+    pub pulled: Vec<String>,
+    /// Server files written or deleted to match the vault
+    pub pushed: Vec<String>,
+    /// Copies of local edits to files that also changed on the server, which wins
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    /// `VaultBackend::identity` of the server the versions were recorded against
+    #[serde(default)]
+    remote: String,
+    files: HashMap<String, SyncedVersions>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SyncedVersions {
+    local: String,
+    remote: String,
+}
+
+/// Which way `sync_remote` copies changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncDirection {
+    /// Both ways; a file changed on both sides becomes a conflict
+    Both,
+    /// Only upload vault changes and deletions to the server
+    Push,
+    /// Only apply server changes and deletions to the vault
+    Pull,
+}
+
+/// A file deleted from the server by a sync
+#[derive(Debug, Serialize, Deserialize)]
+struct Tombstone {
+    /// Local version (content hash) of the file when it was deleted
+    hash: String,
+    deleted: String,
+}
+
+/// What to do with a path that differs from the last sync
+enum SyncAction {
+    Pull,
+    Push,
+    /// Changed on both sides
+    Merge,
+    /// Gone from both sides
+    Forget,
+}
+
+/// Sync the vault folder with `remote` in `direction`. Changes on one side since the
+/// last sync are copied to the other; a file changed on both keeps the server version
+/// and the local one is saved next to it as `name-conflict.md` (with the file's own
+/// extension). Only files with one of the prompt file `extensions` are synced. Deletions
+/// are recorded in a tombstone list on the server, so a device that never synced a
+/// deleted file drops its identical copy instead of uploading it again.
+///
+/// The recorded versions belong to one server; after switching servers the first sync
+/// treats every file as new. A server listing nothing although files were synced (and
+/// not deleted through a sync) fails rather than deleting them locally, and deleting more than
+/// `MAX_UNCONFIRMED_DELETIONS` local files fails with `VaultError::UnconfirmedDeletions`
+/// unless `allow_deletions` is set.
+pub async fn sync_remote(
+    vault_path: &Path,
+    remote: &impl VaultBackend,
+    extensions: &[String],
+    direction: SyncDirection,
+    allow_deletions: bool,
+) -> Result<RemoteSyncStats, VaultError> {
+    let local = LocalBackend::new(vault_path, extensions);
+    let state_path = vault_path.join(SYNC_STATE_FILE);
+    let identity = remote.identity();
+    let mut state: SyncState = {
+        let state_path = state_path.clone();
+        vault::blocking(move || Ok::<_, VaultError>(fs::read_to_string(state_path).ok()))
+            .await?
+            .and_then(|content| serde_json::from_str::<SyncState>(&content).ok())
+            .filter(|state| state.remote == identity)
+            .unwrap_or_else(|| SyncState {
+                remote: identity,
+                files: HashMap::new(),
+            })
+    };
+    let mut tombstones: BTreeMap<String, Tombstone> = match remote.read(TOMBSTONES_FILE).await {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| VaultError::ParseError(format!("{}: {}", TOMBSTONES_FILE, e)))?,
+        Err(VaultError::NotFound(_)) => BTreeMap::new(),
+        Err(e) => return Err(e),
+    };
+    let tombstone_count = tombstones.len();
+
+    let local_files = versions(local.list().await?);
+    let remote_files = versions(remote.list().await?);
+    // Syncs that delete files leave tombstones; an empty listing without them is a broken server
+    let unexplained = state
+        .files
+        .keys()
+        .filter(|path| !tombstones.contains_key(*path))
+        .count();
+    if remote_files.is_empty() && unexplained > 0 {
+        return Err(VaultError::Remote(format!(
+            "The server lists no prompt files, but {} were synced before",
+            unexplained
+        )));
+    }
+    let paths: BTreeSet<String> = local_files
+        .keys()
+        .chain(remote_files.keys())
+        .chain(state.files.keys())
+        .cloned()
+        .collect();
+
+    let mut actions = Vec::new();
+    for path in paths {
+        let synced = state.files.get(&path);
+        let local_version = local_files.get(&path);
+        let remote_version = remote_files.get(&path);
+        let local_changed = local_version != synced.map(|s| &s.local);
+        let remote_changed = remote_version != synced.map(|s| &s.remote);
+
+        let action = match (local_changed, remote_changed, local_version, remote_version) {
+            (false, false, _, _) => continue,
+            (_, _, None, None) => SyncAction::Forget,
+            (false, true, _, _) | (true, true, None, Some(_)) => SyncAction::Pull,
+            (true, false, Some(hash), None)
+                if synced.is_none() && tombstones.get(&path).is_some_and(|t| &t.hash == hash) =>
+            {
+                SyncAction::Pull
+            }
+            (true, false, _, _) | (true, true, Some(_), None) => SyncAction::Push,
+            (true, true, Some(_), Some(_)) => SyncAction::Merge,
+        };
+        actions.push((path, action));
+    }
+
+    let deletions = actions
+        .iter()
+        .filter(|(path, action)| {
+            matches!(action, SyncAction::Pull)
+                && direction != SyncDirection::Push
+                && !remote_files.contains_key(path)
+        })
+        .count();
+    if deletions > MAX_UNCONFIRMED_DELETIONS && !allow_deletions {
+        return Err(VaultError::UnconfirmedDeletions(deletions));
+    }
+
+    let mut stats = RemoteSyncStats::default();
+    // Paths whose synced versions must be recorded again
+    let mut touched = Vec::new();
+    for (path, action) in actions {
+        let synced = state.files.get(&path);
+        let local_version = local_files.get(&path);
+        let remote_version = remote_files.get(&path);
+        match action {
+            SyncAction::Forget => {}
+            SyncAction::Pull if direction != SyncDirection::Push => {
+                copy_file(remote, &local, &path, remote_version.is_some()).await?;
+                stats.pulled.push(path.clone());
+            }
+            SyncAction::Push if direction != SyncDirection::Pull => {
+                copy_file(&local, remote, &path, local_version.is_some()).await?;
+                match (local_version, synced) {
+                    (None, Some(synced)) => {
+                        let tombstone = Tombstone {
+                            hash: synced.local.clone(),
+                            deleted: Utc::now().to_rfc3339(),
+                        };
+                        tombstones.insert(path.clone(), tombstone);
+                    }
+                    _ => {
+                        tombstones.remove(&path);
+                    }
+                }
+                stats.pushed.push(path.clone());
+            }
+            SyncAction::Merge if direction != SyncDirection::Push => {
+                let local_content = local.read(&path).await?;
+                let remote_content = remote.read(&path).await?;
+                if local_content != remote_content {
+                    let reserved: Vec<String> = remote_files.keys().cloned().collect();
+                    let (stem, extension) = path.rsplit_once('.').unwrap_or((path.as_str(), "md"));
+                    let conflict = promptpack::available_path(
+                        vault_path,
+                        &format!("{}-conflict.{}", stem, extension),
+                        &reserved,
+                    );
+                    local.write(&conflict, &local_content).await?;
+                    local.write(&path, &remote_content).await?;
+                    if direction == SyncDirection::Both {
+                        remote.write(&conflict, &local_content).await?;
+                        stats.pushed.push(conflict.clone());
+                        touched.push(conflict.clone());
+                    }
+                    stats.pulled.push(path.clone());
+                    stats.conflicts.push(conflict);
+                }
+            }
+            // Left for a sync in the other direction
+            _ => continue,
+        }
+        touched.push(path);
+    }
+
+    if !stats.pushed.is_empty() || tombstones.len() != tombstone_count {
+        let content = serde_json::to_string_pretty(&tombstones)
+            .map_err(|e| VaultError::SerializeError(e.to_string()))?;
+        remote.write(TOMBSTONES_FILE, &content).await?;
+    }
+
+    // Record the touched paths as they are now; uploads get a new version on the server
+    let local_files = versions(local.list().await?);
+    let remote_files = if stats.pushed.is_empty() {
+        remote_files
+    } else {
+        versions(remote.list().await?)
+    };
+    for path in touched {
+        match (local_files.get(&path), remote_files.get(&path)) {
+            (Some(local), Some(remote)) => {
+                let synced = SyncedVersions {
+                    local: local.clone(),
+                    remote: remote.clone(),
+                };
+                state.files.insert(path, synced);
+            }
+            _ => {
+                state.files.remove(&path);
+            }
+        }
+    }
+    let state =
+        serde_json::to_string(&state).map_err(|e| VaultError::SerializeError(e.to_string()))?;
+    vault::blocking(move || {
+        vault::write_atomic(&state_path, &state).map_err(|e| VaultError::IoError(e.to_string()))
+    })
+    .await?;
+
+    info!(
+        "Synced vault with remote ({:?}): {} pulled, {} pushed, {} conflicts",
+        direction,
+        stats.pulled.len(),
+        stats.pushed.len(),
+        stats.conflicts.len()
+    );
+    Ok(stats)
+}
+
+fn versions(files: Vec<BackendFile>) -> HashMap<String, String> {
+    files
+        .into_iter()
+        .map(|file| (file.path, file.version))
+        .collect()
+}
+
+/// Copy `path` from `from` to `to`, or delete it from `to` when `from` no longer has it
+async fn copy_file(
+    from: &impl VaultBackend,
+    to: &impl VaultBackend,
+    path: &str,
+    exists: bool,
+) -> Result<(), VaultError> {
+    if exists {
+        to.write(path, &from.read(path).await?).await
+    } else {
+        to.delete(path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    /// `sync_remote` for a markdown-only vault
+    async fn sync(
+        vault_path: &Path,
+        server: &LocalBackend,
+        direction: SyncDirection,
+        allow_deletions: bool,
+    ) -> Result<RemoteSyncStats, VaultError> {
+        sync_remote(
+            vault_path,
+            server,
+            vault::MARKDOWN_ONLY,
+            direction,
+            allow_deletions,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_sync_remote() {
+        let root = TempDir::new("vault-backend");
+        let (vault_path, server_path) = (root.join("vault"), root.join("server"));
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::create_dir_all(&server_path).unwrap();
+        let server = LocalBackend::new(&server_path, vault::MARKDOWN_ONLY);
+
+        fs::write(vault_path.join("local.md"), "local").unwrap();
+        fs::write(server_path.join("remote.md"), "remote").unwrap();
+        let stats = sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert_eq!(stats.pulled, vec!["remote.md"]);
+        assert_eq!(stats.pushed, vec!["local.md"]);
+        assert_eq!(
+            fs::read_to_string(server_path.join("local.md")).unwrap(),
+            "local"
+        );
+
+        // Deletions travel too, and an unchanged vault syncs to nothing
+        fs::remove_file(server_path.join("remote.md")).unwrap();
+        let stats = sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert_eq!(stats.pulled, vec!["remote.md"]);
+        assert!(!vault_path.join("remote.md").exists());
+        let stats = sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert!(stats.pulled.is_empty() && stats.pushed.is_empty());
+
+        // Edited on both sides: the server wins, the local edit is kept aside
+        fs::write(vault_path.join("local.md"), "edited here").unwrap();
+        fs::write(server_path.join("local.md"), "edited there").unwrap();
+        let stats = sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert_eq!(stats.conflicts, vec!["local-conflict.md"]);
+        assert_eq!(
+            fs::read_to_string(vault_path.join("local.md")).unwrap(),
+            "edited there"
+        );
+        assert_eq!(
+            fs::read_to_string(server_path.join("local-conflict.md")).unwrap(),
+            "edited here"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sync_remote_file_extensions() {
+        let root = TempDir::new("vault-backend");
+        let (vault_path, server_path) = (root.join("vault"), root.join("server"));
+        fs::create_dir_all(&vault_path).unwrap();
+        fs::create_dir_all(&server_path).unwrap();
+        let extensions = vec!["md".to_string(), "txt".to_string()];
+        let server = LocalBackend::new(&server_path, &extensions);
+
+        fs::write(vault_path.join("notes.txt"), "notes").unwrap();
+        fs::write(vault_path.join("data.json"), "{}").unwrap();
+        let stats = sync_remote(
+            &vault_path,
+            &server,
+            &extensions,
+            SyncDirection::Both,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(stats.pushed, vec!["notes.txt"]);
+
+        fs::write(vault_path.join("notes.txt"), "edited here").unwrap();
+        fs::write(server_path.join("notes.txt"), "edited there").unwrap();
+        let stats = sync_remote(
+            &vault_path,
+            &server,
+            &extensions,
+            SyncDirection::Both,
+            false,
+        )
+        .await
+        .unwrap();
+        assert_eq!(stats.conflicts, vec!["notes-conflict.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_push_pull_tombstones() {
+        let root = TempDir::new("vault-backend");
+        let (laptop, desktop, stale) = (
+            root.join("laptop"),
+            root.join("desktop"),
+            root.join("stale"),
+        );
+        let server_path = root.join("server");
+        for dir in [&laptop, &desktop, &stale, &server_path] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let server = LocalBackend::new(&server_path, vault::MARKDOWN_ONLY);
+
+        fs::write(laptop.join("draft.md"), "draft").unwrap();
+        fs::write(stale.join("draft.md"), "draft").unwrap();
+        // Pulling never uploads, pushing never downloads
+        let stats = sync(&laptop, &server, SyncDirection::Pull, false)
+            .await
+            .unwrap();
+        assert!(stats.pushed.is_empty());
+        sync(&laptop, &server, SyncDirection::Push, false)
+            .await
+            .unwrap();
+        let stats = sync(&desktop, &server, SyncDirection::Push, false)
+            .await
+            .unwrap();
+        assert!(stats.pulled.is_empty() && !desktop.join("draft.md").exists());
+        sync(&desktop, &server, SyncDirection::Pull, false)
+            .await
+            .unwrap();
+        assert!(desktop.join("draft.md").exists());
+
+        // A pushed deletion reaches devices that synced the file...
+        fs::remove_file(laptop.join("draft.md")).unwrap();
+        let stats = sync(&laptop, &server, SyncDirection::Push, false)
+            .await
+            .unwrap();
+        assert_eq!(stats.pushed, vec!["draft.md"]);
+        sync(&desktop, &server, SyncDirection::Pull, false)
+            .await
+            .unwrap();
+        assert!(!desktop.join("draft.md").exists());
+
+        // ...and, through the tombstone, ones that only ever had the same copy
+        sync(&stale, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert!(!stale.join("draft.md").exists());
+        assert!(!server_path.join("draft.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_sync_remote_safeguards() {
+        let root = TempDir::new("vault-backend");
+        let (vault_path, server_path, other_path) =
+            (root.join("vault"), root.join("server"), root.join("other"));
+        for dir in [&vault_path, &server_path, &other_path] {
+            fs::create_dir_all(dir).unwrap();
+        }
+        let (server, other) = (
+            LocalBackend::new(&server_path, vault::MARKDOWN_ONLY),
+            LocalBackend::new(&other_path, vault::MARKDOWN_ONLY),
+        );
+        for i in 0..=MAX_UNCONFIRMED_DELETIONS {
+            fs::write(vault_path.join(format!("p{}.md", i)), "text").unwrap();
+        }
+        sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+
+        // Another server starts from scratch instead of reading the vault as deleted there
+        fs::write(other_path.join("other.md"), "other").unwrap();
+        let stats = sync(&vault_path, &other, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert_eq!(stats.pulled, vec!["other.md"]);
+        assert_eq!(stats.pushed.len(), MAX_UNCONFIRMED_DELETIONS + 1);
+        sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .unwrap();
+        assert!(vault_path.join("p0.md").exists());
+
+        // A server that lists nothing deletes nothing
+        fs::remove_dir_all(&server_path).unwrap();
+        fs::create_dir_all(&server_path).unwrap();
+        assert!(sync(&vault_path, &server, SyncDirection::Both, false)
+            .await
+            .is_err());
+        assert!(vault_path.join("p0.md").exists());
+
+        // Deleting many local files waits for confirmation
+        fs::write(server_path.join("other.md"), "other").unwrap();
+        let result = sync(&vault_path, &server, SyncDirection::Pull, false).await;
+        assert!(matches!(
+            result,
+            Err(VaultError::UnconfirmedDeletions(n)) if n == MAX_UNCONFIRMED_DELETIONS + 1
+        ));
+        assert!(vault_path.join("p0.md").exists());
+        sync(&vault_path, &server, SyncDirection::Pull, true)
+            .await
+            .unwrap();
+        assert!(!vault_path.join("p0.md").exists());
+    }
+}
@@ -1,26 +1,148 @@
-use notify::{Event, RecommendedWatcher, RecursiveMode, Result as NotifyResult, Watcher};
-use std::path::Path;
+use crate::config;
+use crate::db::DbState;
+use crate::events::{self, SyncCompleted, VaultChanged};
+use crate::models::{DbError, SyncStats};
+use crate::promptignore::{PromptIgnore, PROMPTIGNORE_FILE};
+use crate::sync;
+use crate::vault;
+use chrono::Utc;
+use notify::event::ModifyKind;
+use notify::{
+    Event, EventKind, RecommendedWatcher, RecursiveMode, Result as NotifyResult, Watcher,
+};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tauri::AppHandle;
-use tauri::Emitter;
+use tauri::{AppHandle, Manager};
+use tokio::sync::mpsc;
+use tracing::{error, info};
 
+/// Quiet period after the last filesystem event before the vault is synced
+const SYNC_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How long a write made by the app itself suppresses matching watcher events
+const SELF_WRITE_TTL: Duration = Duration::from_secs(2);
+
+/// A file the app wrote (or deleted, when `hash` is `None`) itself
+struct SelfWrite {
+    hash: Option<String>,
+    at: Instant,
+}
+
+/// Files the app wrote (or deleted) itself, shared with the watcher's sync task so it skips
+/// them. Clones share the same record, so one can be moved onto the blocking pool.
+#[derive(Clone, Default)]
+pub struct SelfWrites(Arc<Mutex<HashMap<String, SelfWrite>>>);
+
+impl SelfWrites {
+    /// Remember that the app itself wrote `relative_path` (with the resulting file hash,
+    /// or `None` for a deletion) so the watcher does not resync its own changes
+    pub fn record(&self, relative_path: &str, hash: Option<String>) {
+        if let Ok(mut writes) = self.0.lock() {
+            writes.insert(
+                relative_path.to_string(),
+                SelfWrite {
+                    hash,
+                    at: Instant::now(),
+                },
+            );
+        }
+    }
+
+    /// Whether the file still matches what the app itself recently wrote (or deleted)
+    fn contains(&self, root: &Path, relative_path: &str) -> bool {
+        let mut writes = match self.0.lock() {
+            Ok(writes) => writes,
+            Err(_) => return false,
+        };
+        writes.retain(|_, write| write.at.elapsed() < SELF_WRITE_TTL);
+
+        match writes.get(relative_path) {
+            Some(write) => {
+                let current = vault::compute_file_hash_from_path(&root.join(relative_path)).ok();
+                current == write.hash
+            }
+            None => false,
+        }
+    }
+}
+
+#[derive(Default)]
 pub struct VaultWatcherState {
     pub watcher: Mutex<Option<RecommendedWatcher>>,
     pub path: Mutex<Option<String>>,
-    pub last_emit: Arc<Mutex<Instant>>,
+    /// Timestamp (RFC 3339) of the last prompt file change seen by the watcher
+    pub last_event: Arc<Mutex<Option<String>>>,
+    self_writes: SelfWrites,
+    /// Prompt file extensions, refreshed from the config whenever watching is (re)started
+    extensions: Arc<Mutex<Vec<String>>>,
 }
 
-impl Default for VaultWatcherState {
-    fn default() -> Self {
-        Self {
-            watcher: Mutex::new(None),
-            path: Mutex::new(None),
-            last_emit: Arc::new(Mutex::new(Instant::now() - Duration::from_secs(60))),
+/// Current state of the vault watcher - returned to frontend
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultWatchStatus {
+    pub running: bool,
+    pub path: Option<String>,
+    pub last_event_at: Option<String>,
+}
+
+impl VaultWatcherState {
+    /// Where the app records its own writes for this watcher
+    pub fn self_writes(&self) -> SelfWrites {
+        self.self_writes.clone()
+    }
+
+    /// Stop watching; dropping the watcher also ends its auto-sync task
+    pub fn stop(&self) -> Result<(), String> {
+        let mut watcher_guard = self
+            .watcher
+            .lock()
+            .map_err(|_| "Watcher lock poisoned".to_string())?;
+        let mut path_guard = self
+            .path
+            .lock()
+            .map_err(|_| "Path lock poisoned".to_string())?;
+
+        if let Some(path) = path_guard.take() {
+            info!("Stopped watching vault: {}", path);
         }
+        *watcher_guard = None;
+        Ok(())
+    }
+
+    pub fn status(&self) -> Result<VaultWatchStatus, String> {
+        let watcher_guard = self
+            .watcher
+            .lock()
+            .map_err(|_| "Watcher lock poisoned".to_string())?;
+        let path_guard = self
+            .path
+            .lock()
+            .map_err(|_| "Path lock poisoned".to_string())?;
+        let last_event = self
+            .last_event
+            .lock()
+            .map_err(|_| "Last event lock poisoned".to_string())?;
+
+        Ok(VaultWatchStatus {
+            running: watcher_guard.is_some(),
+            path: path_guard.clone(),
+            last_event_at: last_event.clone(),
+        })
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
 pub fn start_vault_watch(
     app: AppHandle,
     state: &VaultWatcherState,
@@ -35,26 +157,34 @@ pub fn start_vault_watch(
         .lock()
         .map_err(|_| "Path lock poisoned".to_string())?;
 
+    if let Ok(mut extensions) = state.extensions.lock() {
+        *extensions = config::current(&app).frontmatter.file_extensions;
+    }
     if path_guard.as_deref() == Some(&vault_path) && watcher_guard.is_some() {
         return Ok(());
     }
 
-    let last_emit = state.last_emit.clone();
-    let app_handle = app.clone();
+    let (tx, rx) = mpsc::unbounded_channel();
+    let root = PathBuf::from(&vault_path);
+    let last_event = state.last_event.clone();
+    let extensions = state.extensions.clone();
 
     let mut watcher = notify::recommended_watcher(move |res: NotifyResult<Event>| {
-        if res.is_err() {
-            return;
-        }
-        let mut last = match last_emit.lock() {
-            Ok(lock) => lock,
+        let event = match res {
+            Ok(event) => event,
             Err(_) => return,
         };
-        if last.elapsed() < Duration::from_millis(250) {
+        let Ok(extensions) = extensions.lock() else {
             return;
+        };
+        for path in &event.paths {
+            if let Some(change) = classify_change(&root, &extensions, &event.kind, path) {
+                if let Ok(mut last) = last_event.lock() {
+                    *last = Some(Utc::now().to_rfc3339());
+                }
+                let _ = tx.send(change);
+            }
         }
-        *last = Instant::now();
-        let _ = app_handle.emit("vault-changed", ());
     })
     .map_err(|e| e.to_string())?;
 
@@ -62,8 +192,132 @@ pub fn start_vault_watch(
         .watch(Path::new(&vault_path), RecursiveMode::NonRecursive)
         .map_err(|e| e.to_string())?;
 
+    // Replacing the watcher drops the previous sender, which ends its sync task
+    tauri::async_runtime::spawn(auto_sync(
+        app,
+        PathBuf::from(&vault_path),
+        state.self_writes.clone(),
+        rx,
+    ));
+
     *path_guard = Some(vault_path);
     *watcher_guard = Some(watcher);
 
     Ok(())
 }
+
+/// Map a raw notify event to a change on a prompt file (or the `.promptignore`) relative
+/// to the vault root
+fn classify_change(
+    root: &Path,
+    extensions: &[String],
+    kind: &EventKind,
+    path: &Path,
+) -> Option<(String, ChangeKind)> {
+    let name = path.file_name()?.to_str()?;
+    if name != PROMPTIGNORE_FILE && !vault::has_prompt_extension(name, extensions) {
+        return None;
+    }
+    let relative = path.strip_prefix(root).ok()?.to_str()?.to_string();
+
+    let change = match kind {
+        EventKind::Access(_) => return None,
+        EventKind::Create(_) => ChangeKind::Created,
+        EventKind::Remove(_) => ChangeKind::Removed,
+        // Renames report both ends; whichever side still exists was created
+        EventKind::Modify(ModifyKind::Name(_)) if path.exists() => ChangeKind::Created,
+        EventKind::Modify(ModifyKind::Name(_)) => ChangeKind::Removed,
+        _ => ChangeKind::Modified,
+    };
+    Some((relative, change))
+}
+
+/// Debounce watcher events, then emit the batched changes and sync only those files
+async fn auto_sync(
+    app: AppHandle,
+    root: PathBuf,
+    self_writes: SelfWrites,
+    mut rx: mpsc::UnboundedReceiver<(String, ChangeKind)>,
+) {
+    while let Some(first) = rx.recv().await {
+        let mut pending = BTreeMap::new();
+        merge_change(&mut pending, first);
+        loop {
+            match tokio::time::timeout(SYNC_DEBOUNCE, rx.recv()).await {
+                Ok(Some(change)) => merge_change(&mut pending, change),
+                Ok(None) => return,
+                Err(_) => break,
+            }
+        }
+
+        pending.retain(|path, _| !self_writes.contains(&root, path));
+        if pending.remove(PROMPTIGNORE_FILE).is_some() {
+            // Different files may be ignored now, so the whole vault is re-read
+            match resync_vault(&app).await {
+                Ok(stats) => {
+                    info!(
+                        "{} changed, vault re-synced: {:?}",
+                        PROMPTIGNORE_FILE, stats
+                    );
+                    events::emit(&app, SyncCompleted(stats));
+                }
+                Err(e) => error!("Re-sync after {} change failed: {}", PROMPTIGNORE_FILE, e),
+            }
+            continue;
+        }
+        let ignore = PromptIgnore::load(&root);
+        pending.retain(|path, _| !ignore.is_ignored(path));
+        if pending.is_empty() {
+            continue;
+        }
+
+        let mut changed = VaultChanged::default();
+        for (path, kind) in pending {
+            match kind {
+                ChangeKind::Created => changed.created.push(path),
+                ChangeKind::Modified => changed.modified.push(path),
+                ChangeKind::Removed => changed.removed.push(path),
+            }
+        }
+        events::emit(&app, changed.clone());
+
+        match sync_changes(&app, &changed.paths()).await {
+            Ok(stats) => {
+                info!("Auto-sync completed: {:?}", stats);
+                events::emit(&app, SyncCompleted(stats));
+            }
+            Err(e) => error!("Auto-sync failed: {}", e),
+        }
+    }
+}
+
+/// A file created within the batch stays "created" even if it was modified afterwards
+fn merge_change(pending: &mut BTreeMap<String, ChangeKind>, (path, kind): (String, ChangeKind)) {
+    let merged = match (pending.get(&path), kind) {
+        (Some(ChangeKind::Created), ChangeKind::Modified) => ChangeKind::Created,
+        _ => kind,
+    };
+    pending.insert(path, merged);
+}
+
+async fn sync_changes(app: &AppHandle, paths: &[String]) -> Result<SyncStats, DbError> {
+    let config = config::current(app);
+
+    let vault_path = config
+        .vault_path
+        .ok_or_else(|| DbError::Database("Vault path not configured".to_string()))?;
+
+    let pool = app.state::<DbState>().pool();
+    sync::sync_paths(&pool, Path::new(&vault_path), &config.frontmatter, paths).await
+}
+
+async fn resync_vault(app: &AppHandle) -> Result<SyncStats, DbError> {
+    let config = config::current(app);
+
+    let vault_path = config
+        .vault_path
+        .ok_or_else(|| DbError::Database("Vault path not configured".to_string()))?;
+
+    let pool = app.state::<DbState>().pool();
+    sync::sync_vault(&pool, Path::new(&vault_path), &config.frontmatter).await
+}
@@ -0,0 +1,719 @@
+//! Sync, save, delete and rename against a temporary vault, checking after every step that
+//! the cache matches the files on disk
+
+use prompt_manager_lib::config::AppConfig;
+use prompt_manager_lib::crypto;
+use prompt_manager_lib::db::{self, DbPool};
+use prompt_manager_lib::error::ErrorKind;
+use prompt_manager_lib::models::{
+    FilterConfig, GroupBy, Prompt, PromptInput, PromptStatus, SortConfig, SplitBy,
+};
+use prompt_manager_lib::prompts::{expand_includes, group_prompts, load_prompt, query_prompts};
+use prompt_manager_lib::store::{self, VaultContext};
+use prompt_manager_lib::sync;
+use prompt_manager_lib::undo::{self, Snapshot, UndoState};
+use prompt_manager_lib::vault;
+use prompt_manager_lib::vault_watcher::SelfWrites;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A prompt file as written by hand, with the text in a `prompt` fence
+fn prompt_file(text: &str) -> String {
+    format!("```prompt\n{}\n```\n", text)
+}
+
+/// A vault folder and cache database under the temp dir, removed on drop
+struct TestVault {
+    root: PathBuf,
+    pool: DbPool,
+    vault: VaultContext,
+}
+
+impl TestVault {
+    async fn new() -> Self {
+        let root =
+            std::env::temp_dir().join(format!("prompt-manager-flows-{}", uuid::Uuid::new_v4()));
+        let vault_path = root.join("vault");
+        fs::create_dir_all(&vault_path).unwrap();
+        let pool = db::connect(&root.join("cache.db")).await.unwrap();
+        let config = AppConfig {
+            vault_path: Some(vault_path.to_string_lossy().into_owned()),
+            ..Default::default()
+        };
+        let vault = VaultContext::new(&config, SelfWrites::default()).unwrap();
+        TestVault { root, pool, vault }
+    }
+
+    fn path(&self) -> &Path {
+        &self.vault.path
+    }
+
+    async fn sync(&self) {
+        sync::sync_vault(&self.pool, self.path(), &self.vault.config.frontmatter)
+            .await
+            .unwrap();
+    }
+
+    /// Every file in the vault (archived ones too) is cached with the same text, tags and hash,
+    /// and nothing else is
+    async fn assert_consistent(&self) {
+        let scan = vault::scan_vault(self.path(), &self.vault.config.frontmatter).unwrap();
+        let mut files = scan.prompts;
+        files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        let archived = FilterConfig {
+            archived: Some(true),
+            ..Default::default()
+        };
+        let mut cached = query_prompts(&self.pool, None, None).await.unwrap();
+        cached.extend(
+            query_prompts(&self.pool, Some(archived), None)
+                .await
+                .unwrap(),
+        );
+        cached.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let ids = |paths: Vec<&String>| paths.into_iter().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            ids(files.iter().map(|file| &file.file_path).collect()),
+            ids(cached.iter().map(|prompt| &prompt.id).collect()),
+        );
+        for (file, prompt) in files.iter().zip(&cached) {
+            assert_eq!(prompt.text, file.content, "text of {}", prompt.id);
+            let mut file_tags = file.tags.clone();
+            file_tags.sort();
+            let mut tags = prompt.tags.clone();
+            tags.sort();
+            assert_eq!(tags, file_tags, "tags of {}", prompt.id);
+            let hash = vault::compute_file_hash_from_path(&self.path().join(&file.file_path)).ok();
+            assert_eq!(prompt.file_hash, hash, "hash of {}", prompt.id);
+        }
+    }
+}
+
+impl Drop for TestVault {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn input(file_path: &str, text: &str, tags: &[&str]) -> PromptInput {
+    PromptInput {
+        id: file_path.to_string(),
+        created: None,
+        text: text.to_string(),
+        tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        file_path: Some(file_path.to_string()),
+        previous_file_path: None,
+        title: None,
+        description: None,
+        color: None,
+        icon: None,
+        notes: None,
+        model_targets: None,
+        language: None,
+        status: None,
+        file_hash: None,
+        custom_fields: None,
+        variant: None,
+        archived: None,
+        expected_revision: None,
+    }
+}
+
+#[tokio::test]
+async fn test_sync_picks_up_external_edits() {
+    let t = TestVault::new().await;
+    fs::write(
+        t.path().join("hello.md"),
+        format!(
+            "---\ntags:\n  - greeting\n---\n{}",
+            prompt_file("Hello there")
+        ),
+    )
+    .unwrap();
+    fs::write(t.path().join("bye.md"), prompt_file("Goodbye")).unwrap();
+    t.sync().await;
+    t.assert_consistent().await;
+
+    fs::write(t.path().join("hello.md"), prompt_file("Hello again")).unwrap();
+    fs::remove_file(t.path().join("bye.md")).unwrap();
+    t.sync().await;
+    t.assert_consistent().await;
+    let hello = load_prompt(&t.pool, "hello.md").await.unwrap().unwrap();
+    assert_eq!(hello.text, "Hello again");
+    assert!(hello.tags.is_empty());
+}
+
+#[tokio::test]
+async fn test_save_writes_file_then_cache() {
+    let t = TestVault::new().await;
+    let written = store::save_prompt(&t.pool, &t.vault, input("a.md", "First", &["x"]))
+        .await
+        .unwrap();
+    assert_eq!(written.file_path, "a.md");
+    assert!(t.path().join("a.md").is_file());
+    t.assert_consistent().await;
+
+    // Updating keeps one file and one row
+    let saved = load_prompt(&t.pool, "a.md").await.unwrap().unwrap();
+    let mut update = input("a.md", "Second", &["y"]);
+    update.previous_file_path = Some("a.md".to_string());
+    update.file_hash = saved.file_hash.clone();
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+    t.assert_consistent().await;
+
+    // A batch reports each prompt; the one that fails leaves the others saved
+    let (results, written) = store::save_prompts(
+        &t.pool,
+        &t.vault,
+        vec![input("b.md", "B", &[]), input("a.md", "Clash", &[])],
+    )
+    .await
+    .unwrap();
+    assert_eq!(written.len(), 1);
+    assert!(results[0].error.is_none());
+    assert_eq!(
+        results[1].error.as_ref().map(|e| e.kind()),
+        Some(ErrorKind::AlreadyExists)
+    );
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_rename_moves_file_and_row() {
+    let t = TestVault::new().await;
+    store::save_prompt(&t.pool, &t.vault, input("old.md", "Text", &["keep"]))
+        .await
+        .unwrap();
+
+    let renamed = store::rename_prompt(&t.pool, &t.vault, "old.md", "new")
+        .await
+        .unwrap();
+    assert_eq!(renamed, Some(("old.md".to_string(), "new.md".to_string())));
+    assert!(!t.path().join("old.md").exists());
+    t.assert_consistent().await;
+    assert_eq!(
+        store::rename_prompt(&t.pool, &t.vault, "new.md", "new.md")
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Saving under a new path removes the previous file and row
+    let mut moved = input("moved.md", "Text", &["keep"]);
+    moved.previous_file_path = Some("new.md".to_string());
+    store::save_prompt(&t.pool, &t.vault, moved).await.unwrap();
+    assert!(!t.path().join("new.md").exists());
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_delete_removes_file_and_row() {
+    let t = TestVault::new().await;
+    store::save_prompt(&t.pool, &t.vault, input("gone.md", "Text", &["tag"]))
+        .await
+        .unwrap();
+    let tags = store::delete_prompt(&t.pool, &t.vault, "gone.md")
+        .await
+        .unwrap();
+    assert_eq!(tags, vec!["tag"]);
+    assert!(!t.path().join("gone.md").exists());
+    t.assert_consistent().await;
+
+    // A file deleted outside the app still has its row cleaned up
+    store::save_prompt(&t.pool, &t.vault, input("external.md", "Text", &[]))
+        .await
+        .unwrap();
+    fs::remove_file(t.path().join("external.md")).unwrap();
+    store::delete_prompt(&t.pool, &t.vault, "external.md")
+        .await
+        .unwrap();
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_stale_revision_is_rejected() {
+    let t = TestVault::new().await;
+    store::save_prompt(&t.pool, &t.vault, input("p.md", "One", &[]))
+        .await
+        .unwrap();
+    let loaded = load_prompt(&t.pool, "p.md").await.unwrap().unwrap();
+
+    // Changed outside the app and synced: the revision loaded before is now stale
+    fs::write(t.path().join("p.md"), prompt_file("Two")).unwrap();
+    t.sync().await;
+
+    let mut save = input("p.md", "Three", &[]);
+    save.previous_file_path = Some("p.md".to_string());
+    save.expected_revision = Some(loaded.revision);
+    let error = store::save_prompt(&t.pool, &t.vault, save)
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::StaleRevision);
+    assert_eq!(
+        fs::read_to_string(t.path().join("p.md")).unwrap(),
+        prompt_file("Two")
+    );
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_new_prompts_get_defaults() {
+    let mut t = TestVault::new().await;
+    t.vault.config.default_tags = vec!["prompts".to_string()];
+    t.vault
+        .config
+        .default_frontmatter
+        .insert("status".to_string(), "draft".into());
+    let read = |t: &TestVault| {
+        vault::read_prompt_file(
+            t.path(),
+            &t.path().join("d.md"),
+            &t.vault.config.frontmatter,
+        )
+        .unwrap()
+    };
+
+    store::save_prompt(&t.pool, &t.vault, input("d.md", "Text", &["x"]))
+        .await
+        .unwrap();
+    let file = read(&t);
+    assert_eq!(file.tags, vec!["x", "prompts"]);
+    assert_eq!(file.status, Some(PromptStatus::Draft));
+    assert!(file.custom_fields.is_empty());
+    t.assert_consistent().await;
+
+    // Updates keep what the prompt says, even without the defaults
+    let saved = load_prompt(&t.pool, "d.md").await.unwrap().unwrap();
+    let mut update = input("d.md", "Text", &["x"]);
+    update.previous_file_path = Some("d.md".to_string());
+    update.file_hash = saved.file_hash.clone();
+    update.custom_fields = Some(Default::default());
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+    let file = read(&t);
+    assert_eq!(file.tags, vec!["x"]);
+    assert_eq!(file.status, Some(PromptStatus::Draft));
+    assert!(file.custom_fields.is_empty());
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_encrypted_prompt_is_sealed_on_disk_and_in_cache() {
+    let t = TestVault::new().await;
+    let mut secret = input("secret.md", "Client: ACME", &["client"]);
+    secret.custom_fields = Some([("encrypted".to_string(), true.into())].into());
+
+    // Locked: nothing is written
+    let error = store::save_prompt(&t.pool, &t.vault, secret.clone())
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::Locked);
+    assert!(!t.path().join("secret.md").exists());
+
+    t.vault.key.unlock("passphrase".to_string());
+    store::save_prompt(&t.pool, &t.vault, secret).await.unwrap();
+    let file = fs::read_to_string(t.path().join("secret.md")).unwrap();
+    assert!(!file.contains("ACME"));
+    let cached = load_prompt(&t.pool, "secret.md").await.unwrap().unwrap();
+    assert!(crypto::is_sealed(&cached.text));
+    assert_eq!(t.vault.key.open(&cached.text).unwrap(), "Client: ACME");
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_save_without_custom_fields_keeps_encrypted_prompt_sealed() {
+    let t = TestVault::new().await;
+    t.vault.key.unlock("passphrase".to_string());
+    let mut secret = input("secret.md", "Client: ACME", &["client"]);
+    secret.custom_fields = Some([("encrypted".to_string(), true.into())].into());
+    let saved = store::save_prompt(&t.pool, &t.vault, secret).await.unwrap();
+
+    // The editor sends the opened text back without the frontmatter fields
+    let mut update = input("secret.md", "Client: Globex", &["client"]);
+    update.previous_file_path = Some("secret.md".to_string());
+    update.file_hash = saved.file_hash;
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+
+    let file = fs::read_to_string(t.path().join("secret.md")).unwrap();
+    assert!(!file.contains("Globex"));
+    let cached = load_prompt(&t.pool, "secret.md").await.unwrap().unwrap();
+    assert!(crypto::is_sealed(&cached.text));
+    assert_eq!(t.vault.key.open(&cached.text).unwrap(), "Client: Globex");
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_encrypted_include_is_opened_when_expanded() {
+    let t = TestVault::new().await;
+    t.vault.key.unlock("passphrase".to_string());
+    let mut secret = input("secret.md", "Client: ACME", &[]);
+    secret.custom_fields = Some([("encrypted".to_string(), true.into())].into());
+    store::save_prompt(&t.pool, &t.vault, secret).await.unwrap();
+    let brief = input("brief.md", "{{include:secret.md}} Draft a reply", &[]);
+    store::save_prompt(&t.pool, &t.vault, brief).await.unwrap();
+    let brief = load_prompt(&t.pool, "brief.md").await.unwrap().unwrap();
+
+    let text = expand_includes(&t.pool, &brief, &t.vault.key)
+        .await
+        .unwrap();
+    assert_eq!(text, "Client: ACME Draft a reply");
+
+    t.vault.key.lock();
+    let error = expand_includes(&t.pool, &brief, &t.vault.key)
+        .await
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Locked);
+}
+
+#[tokio::test]
+async fn test_undo_and_redo_restore_files_and_cache() {
+    let t = TestVault::new().await;
+    let state = UndoState::default();
+    let read = |t: &TestVault| fs::read_to_string(t.path().join("u.md")).ok();
+
+    let snapshot = Snapshot::take(&t.vault, "Save prompt u.md", ["u.md".to_string()])
+        .await
+        .unwrap();
+    store::save_prompt(&t.pool, &t.vault, input("u.md", "One", &["x"]))
+        .await
+        .unwrap();
+    snapshot.finish(&t.vault, &state, Vec::new()).await;
+    let saved = read(&t);
+
+    let snapshot = Snapshot::take(&t.vault, "Delete prompt u.md", ["u.md".to_string()])
+        .await
+        .unwrap();
+    store::delete_prompt(&t.pool, &t.vault, "u.md")
+        .await
+        .unwrap();
+    snapshot.finish(&t.vault, &state, Vec::new()).await;
+    assert_eq!(state.status().undo.as_deref(), Some("Delete prompt u.md"));
+    assert_eq!(
+        store::list_recently_deleted(&t.pool, 30)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // Undoing the delete brings back the file and its row, and drops it from recovery
+    let undone = undo::undo_last_operation(&t.pool, &t.vault, &state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(undone.paths, vec!["u.md"]);
+    assert_eq!(read(&t), saved);
+    t.assert_consistent().await;
+    assert!(store::list_recently_deleted(&t.pool, 30)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Undoing the save removes the new file again
+    undo::undo_last_operation(&t.pool, &t.vault, &state)
+        .await
+        .unwrap();
+    assert_eq!(read(&t), None);
+    t.assert_consistent().await;
+    assert!(undo::undo_last_operation(&t.pool, &t.vault, &state)
+        .await
+        .unwrap()
+        .is_none());
+
+    undo::redo(&t.pool, &t.vault, &state).await.unwrap();
+    assert_eq!(read(&t), saved);
+    t.assert_consistent().await;
+
+    // A file changed since is left alone, and the redo stays available
+    fs::write(t.path().join("u.md"), prompt_file("Edited")).unwrap();
+    let error = undo::redo(&t.pool, &t.vault, &state).await.err().unwrap();
+    assert_eq!(error.kind(), ErrorKind::Conflict);
+    assert_eq!(read(&t), Some(prompt_file("Edited")));
+    assert_eq!(state.status().redo.as_deref(), Some("Delete prompt u.md"));
+}
+
+#[tokio::test]
+async fn test_deleted_prompt_can_be_recovered() {
+    let t = TestVault::new().await;
+    store::save_prompt(&t.pool, &t.vault, input("kept.md", "Text", &["tag"]))
+        .await
+        .unwrap();
+    let saved = fs::read_to_string(t.path().join("kept.md")).unwrap();
+    store::delete_prompt(&t.pool, &t.vault, "kept.md")
+        .await
+        .unwrap();
+
+    let deleted = store::list_recently_deleted(&t.pool, 30).await.unwrap();
+    assert_eq!(deleted.len(), 1);
+    assert_eq!(deleted[0].id, "kept.md");
+
+    // A file that took the path since is not overwritten
+    fs::write(t.path().join("kept.md"), prompt_file("Other")).unwrap();
+    let error = store::recover_deleted_prompt(&t.pool, &t.vault, "kept.md")
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::AlreadyExists);
+    fs::remove_file(t.path().join("kept.md")).unwrap();
+
+    store::recover_deleted_prompt(&t.pool, &t.vault, "kept.md")
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(t.path().join("kept.md")).unwrap(), saved);
+    t.assert_consistent().await;
+    assert!(store::list_recently_deleted(&t.pool, 30)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn test_split_prompt_into_new_files() {
+    let t = TestVault::new().await;
+    let mut dump = input(
+        "dump.md",
+        "First prompt\n\nSecond prompt\nstill second",
+        &["imported"],
+    );
+    dump.created = Some("2024-01-02T03:04:05".to_string());
+    store::save_prompt(&t.pool, &t.vault, dump).await.unwrap();
+
+    let error = store::split_prompt(&t.pool, &t.vault, "dump.md", SplitBy::Lines(vec![0]))
+        .await
+        .err()
+        .unwrap();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let written = store::split_prompt(&t.pool, &t.vault, "dump.md", SplitBy::Lines(vec![2]))
+        .await
+        .unwrap();
+    assert_eq!(written.len(), 3);
+    let texts: Vec<&str> = written[..2]
+        .iter()
+        .map(|w| w.file.content.as_str())
+        .collect();
+    assert_eq!(texts, vec!["First prompt", "Second prompt\nstill second"]);
+    for part in &written[..2] {
+        let cached = load_prompt(&t.pool, &part.file_path)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.tags, vec!["imported"]);
+        assert_eq!(cached.created.as_deref(), Some("2024-01-02T03:04:05"));
+    }
+    let original = load_prompt(&t.pool, "dump.md").await.unwrap().unwrap();
+    assert!(original.archived);
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_duplicate_prompt_keeps_variants_and_can_be_undone() {
+    let t = TestVault::new().await;
+    let state = UndoState::default();
+    let mut original = input("orig.md", "Main", &["x"]);
+    original.title = Some("Orig".to_string());
+    original.created = Some("2024-01-02T03:04:05".to_string());
+    store::save_prompt(&t.pool, &t.vault, original.clone())
+        .await
+        .unwrap();
+    let short = PromptInput {
+        text: "Short".to_string(),
+        variant: Some("short".to_string()),
+        ..original
+    };
+    store::save_prompt(&t.pool, &t.vault, short).await.unwrap();
+
+    let snapshot = Snapshot::take(&t.vault, "Duplicate prompt orig.md", Vec::new())
+        .await
+        .unwrap();
+    let written = store::duplicate_prompt(&t.pool, &t.vault, "orig.md")
+        .await
+        .unwrap()
+        .unwrap();
+    snapshot
+        .finish(&t.vault, &state, vec![written.file_path.clone()])
+        .await;
+    assert_ne!(written.file_path, "orig.md");
+    let copy = load_prompt(&t.pool, &written.file_path)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(copy.text, "Main");
+    assert_eq!(copy.tags, vec!["x"]);
+    assert_ne!(copy.created.as_deref(), Some("2024-01-02T03:04:05"));
+    let file = fs::read_to_string(t.path().join(&written.file_path)).unwrap();
+    assert!(file.contains("```prompt:short\nShort"));
+    t.assert_consistent().await;
+
+    undo::undo_last_operation(&t.pool, &t.vault, &state)
+        .await
+        .unwrap();
+    assert!(!t.path().join(&written.file_path).exists());
+    t.assert_consistent().await;
+
+    assert!(store::duplicate_prompt(&t.pool, &t.vault, "missing.md")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn test_color_and_icon_are_kept_until_cleared() {
+    let t = TestVault::new().await;
+    let mut styled = input("s.md", "Text", &[]);
+    styled.color = Some("#e11d48".to_string());
+    styled.icon = Some("🛡️".to_string());
+    store::save_prompt(&t.pool, &t.vault, styled).await.unwrap();
+    let cached = load_prompt(&t.pool, "s.md").await.unwrap().unwrap();
+    assert_eq!(cached.color.as_deref(), Some("#e11d48"));
+    assert_eq!(cached.icon.as_deref(), Some("🛡️"));
+    assert!(cached.custom_fields.is_empty());
+
+    // Saves that don't set them keep them; a blank value removes them
+    let mut update = input("s.md", "Changed", &[]);
+    update.previous_file_path = Some("s.md".to_string());
+    update.color = Some(String::new());
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+    let cached = load_prompt(&t.pool, "s.md").await.unwrap().unwrap();
+    assert_eq!(cached.color, None);
+    assert_eq!(cached.icon.as_deref(), Some("🛡️"));
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_notes_are_cached_but_not_shared() {
+    let t = TestVault::new().await;
+    let mut noted = input("n.md", "Text", &[]);
+    noted.description = Some("Summarize a thread".to_string());
+    noted.notes = Some("Drop the last paragraph for short threads".to_string());
+    store::save_prompt(&t.pool, &t.vault, noted).await.unwrap();
+    let cached = load_prompt(&t.pool, "n.md").await.unwrap().unwrap();
+    assert_eq!(
+        cached.notes.as_deref(),
+        Some("Drop the last paragraph for short threads")
+    );
+    assert!(cached.custom_fields.is_empty());
+
+    let content = fs::read_to_string(t.path().join("n.md")).unwrap();
+    let shared = vault::strip_notes(&content).unwrap();
+    assert!(shared.contains("Summarize a thread"));
+    assert!(!shared.contains("short threads"));
+
+    // Saves that don't set them keep them
+    let mut update = input("n.md", "Changed", &[]);
+    update.previous_file_path = Some("n.md".to_string());
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+    let cached = load_prompt(&t.pool, "n.md").await.unwrap().unwrap();
+    assert!(cached.notes.is_some());
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_status_is_filtered_grouped_and_kept() {
+    let t = TestVault::new().await;
+    for (path, status) in [
+        ("a.md", PromptStatus::Testing),
+        ("b.md", PromptStatus::Draft),
+        ("c.md", PromptStatus::Testing),
+    ] {
+        let mut staged = input(path, "Text", &[]);
+        staged.status = Some(status);
+        store::save_prompt(&t.pool, &t.vault, staged).await.unwrap();
+    }
+    // Unknown statuses read as none and stay in the file
+    fs::write(
+        t.path().join("d.md"),
+        format!("---\nstatus: wip\n---\n{}", prompt_file("Text")),
+    )
+    .unwrap();
+    t.sync().await;
+
+    let testing = FilterConfig {
+        status: Some(PromptStatus::Testing),
+        ..Default::default()
+    };
+    let mut found: Vec<String> = query_prompts(&t.pool, Some(testing), None)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| p.id)
+        .collect();
+    found.sort();
+    assert_eq!(found, vec!["a.md", "c.md"]);
+
+    let groups = group_prompts(&t.pool, None, None, GroupBy::Status)
+        .await
+        .unwrap();
+    let keys: Vec<(Option<String>, u32)> = groups.into_iter().map(|g| (g.key, g.count)).collect();
+    assert_eq!(
+        keys,
+        vec![
+            (Some("draft".to_string()), 1),
+            (Some("testing".to_string()), 2),
+            (None, 1),
+        ]
+    );
+
+    // Saves that don't set a status keep the one in the file
+    let mut update = input("d.md", "Changed", &[]);
+    update.previous_file_path = Some("d.md".to_string());
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+    assert!(fs::read_to_string(t.path().join("d.md"))
+        .unwrap()
+        .contains("status: wip"));
+    let mut update = input("a.md", "Changed", &[]);
+    update.previous_file_path = Some("a.md".to_string());
+    store::save_prompt(&t.pool, &t.vault, update).await.unwrap();
+    let cached = load_prompt(&t.pool, "a.md").await.unwrap().unwrap();
+    assert_eq!(cached.status, Some(PromptStatus::Testing));
+    t.assert_consistent().await;
+}
+
+#[tokio::test]
+async fn test_counts_are_cached_and_sortable() {
+    let t = TestVault::new().await;
+    fs::write(
+        t.path().join("long.md"),
+        prompt_file("Review this code.\nList every bug you find."),
+    )
+    .unwrap();
+    t.sync().await;
+    store::save_prompt(&t.pool, &t.vault, input("short.md", "Be brief.", &[]))
+        .await
+        .unwrap();
+
+    let long = load_prompt(&t.pool, "long.md").await.unwrap().unwrap();
+    assert_eq!(
+        (long.char_count, long.word_count, long.line_count),
+        (Some(42), Some(8), Some(2))
+    );
+    let short = load_prompt(&t.pool, "short.md").await.unwrap().unwrap();
+    assert_eq!(short.word_count, Some(2));
+
+    let longest = SortConfig {
+        by: "word_count".to_string(),
+        order: "desc".to_string(),
+        then: Vec::new(),
+    };
+    let ids = |prompts: Vec<Prompt>| -> Vec<String> { prompts.into_iter().map(|p| p.id).collect() };
+    let found = query_prompts(&t.pool, None, Some(longest)).await.unwrap();
+    assert_eq!(ids(found), vec!["long.md", "short.md"]);
+
+    let filter = |min_words, max_words| {
+        Some(FilterConfig {
+            min_words,
+            max_words,
+            ..Default::default()
+        })
+    };
+    let found = query_prompts(&t.pool, filter(Some(3), None), None)
+        .await
+        .unwrap();
+    assert_eq!(ids(found), vec!["long.md"]);
+    let found = query_prompts(&t.pool, filter(None, Some(2)), None)
+        .await
+        .unwrap();
+    assert_eq!(ids(found), vec!["short.md"]);
+}
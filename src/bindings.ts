@@ -10,7 +10,7 @@ export const commands = {
 /**
  * Get all prompts with their tags from cache
  */
-async getPrompts(filter: FilterConfig | null, sort: SortConfig | null) : Promise<Result<Prompt[], DbError>> {
+async getPrompts(filter: FilterConfig | null, sort: SortConfig | null) : Promise<Result<Prompt[], AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("get_prompts", { filter, sort }) };
 } catch (e) {
@@ -18,6 +18,86 @@ async getPrompts(filter: FilterConfig | null, sort: SortConfig | null) : Promise
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Like `get_prompts` without the full texts, for lists of large vaults; `page` limits the
+ * result to one page of summaries
+ */
+async getPromptSummaries(filter: FilterConfig | null, sort: SortConfig | null, page: PageRequest | null) : Promise<Result<PromptSummaryPage, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_prompt_summaries", { filter, sort, page }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Like `get_prompts`, split into groups by tag, created month, model target or folder, each
+ * with its number of prompts
+ */
+async getPromptGroups(filter: FilterConfig | null, sort: SortConfig | null, groupBy: GroupBy) : Promise<Result<PromptGroup[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_prompt_groups", { filter, sort, groupBy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * A single cached prompt with its full text
+ */
+async getPrompt(id: string) : Promise<Result<Prompt | null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_prompt", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Like `get_prompts`, with where the search query hit each prompt for highlighting
+ */
+async searchPrompts(filter: FilterConfig, sort: SortConfig | null) : Promise<Result<SearchHit[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("search_prompts", { filter, sort }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Quick list of the prompts used or edited most recently
+ */
+async getRecentPrompts(kind: RecentKind, limit: number) : Promise<Result<Prompt[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recent_prompts", { kind, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Unarchived prompts neither used nor edited in the last `days` days, stalest first, for
+ * periodic review
+ */
+async getStalePrompts(days: number) : Promise<Result<Prompt[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_stale_prompts", { days }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fuzzy-match titles and file names for the quick picker, best match first
+ */
+async fuzzySearchPrompts(query: string, limit: number) : Promise<Result<FuzzyPromptMatch[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("fuzzy_search_prompts", { query, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Save a prompt to cache (upsert)
  * STRICT VAULT-FIRST:
@@ -25,7 +105,7 @@ async getPrompts(filter: FilterConfig | null, sort: SortConfig | null) : Promise
  * 2. Write to filesystem (Master)
  * 3. Update database (Cache)
  */
-async savePrompt(prompt: PromptInput) : Promise<Result<null, DbError>> {
+async savePrompt(prompt: PromptInput) : Promise<Result<null, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("save_prompt", { prompt }) };
 } catch (e) {
@@ -33,6 +113,18 @@ async savePrompt(prompt: PromptInput) : Promise<Result<null, DbError>> {
     else return { status: "error", error: e  as any };
 }
 },
+/**
+ * Save many prompts at once: every file is written first, then the cache is updated
+ * in a single transaction. A prompt that fails to write is reported without stopping the rest.
+ */
+async savePrompts(prompts: PromptInput[]) : Promise<Result<SavePromptResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_prompts", { prompts }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
  * Delete a prompt from cache
  * STRICT VAULT-FIRST:
@@ -40,7 +132,7 @@ async savePrompt(prompt: PromptInput) : Promise<Result<null, DbError>> {
  * 2. Delete from filesystem (Master)
  * 3. Delete from database (Cache)
  */
-async deletePrompt(id: string) : Promise<Result<null, DbError>> {
+async deletePrompt(id: string) : Promise<Result<null, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("delete_prompt", { id }) };
 } catch (e) {
@@ -55,7 +147,7 @@ async deletePrompt(id: string) : Promise<Result<null, DbError>> {
  * 2. Write new file to filesystem (Master)
  * 3. Update database (Cache)
  */
-async duplicatePrompt(id: string) : Promise<Result<Prompt | null, DbError>> {
+async duplicatePrompt(id: string) : Promise<Result<Prompt | null, AppError>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("duplicate_prompt", { id }) };
 } catch (e) {
@@ -64,311 +156,2499 @@ async duplicatePrompt(id: string) : Promise<Result<Prompt | null, DbError>> {
 }
 },
 /**
- * Get all views
+ * Rename (move) a prompt file within the vault
+ * 1. Validate the target path
+ * 2. Re-key the cache rows in a transaction
+ * 3. Move the file, then commit (moving it back if the commit fails)
  */
-async getViews() : Promise<Result<View[], DbError>> {
+async renamePrompt(id: string, newPath: string) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_views") };
+    return { status: "ok", data: await TAURI_INVOKE("rename_prompt", { id, newPath }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get a view by ID
+ * Archive or unarchive a prompt; archived prompts are hidden from views unless
+ * their filter sets `archived`
  */
-async getViewById(id: string) : Promise<Result<View | null, DbError>> {
+async setArchived(id: string, archived: boolean) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_view_by_id", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("set_archived", { id, archived }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Save a view (upsert)
+ * Move a prompt to another lifecycle stage (`status` in its frontmatter)
  */
-async saveView(view: ViewInput) : Promise<Result<null, DbError>> {
+async setPromptStatus(id: string, status: PromptStatus) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("save_view", { view }) };
+    return { status: "ok", data: await TAURI_INVOKE("set_prompt_status", { id, status }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Delete a view
+ * Split a prompt into new files (e.g. a list of prompts pasted into one), each keeping its
+ * tags and created date, and archive the original. Returns the new prompt ids.
  */
-async deleteView(id: string) : Promise<Result<null, DbError>> {
+async splitPrompt(id: string, split: SplitBy) : Promise<Result<string[], AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_view", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("split_prompt", { id, split }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get all tag names
+ * Deleted prompts whose contents can still be recovered, newest first
  */
-async getAllTags() : Promise<Result<string[], DbError>> {
+async listRecentlyDeleted() : Promise<Result<DeletedPrompt[], AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_all_tags") };
+    return { status: "ok", data: await TAURI_INVOKE("list_recently_deleted") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get all table names (for debugging)
+ * Re-create the vault file of a deleted prompt from its kept contents
  */
-async getTableNames() : Promise<Result<string[], DbError>> {
+async recoverDeletedPrompt(id: string) : Promise<Result<Prompt | null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_table_names") };
+    return { status: "ok", data: await TAURI_INVOKE("recover_deleted_prompt", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get table schema information
+ * Put back the files of the last save, delete, rename or bulk edit and resync them.
+ * Returns `None` when there is nothing to undo.
  */
-async getTableInfo(tableName: string) : Promise<Result<TableColumn[], DbError>> {
+async undoLastOperation() : Promise<Result<UndoneOperation | null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_table_info", { tableName }) };
+    return { status: "ok", data: await TAURI_INVOKE("undo_last_operation") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get all rows from a table (for debugging)
+ * Apply the last undone operation again. Returns `None` when there is nothing to redo.
  */
-async getTableRows(tableName: string) : Promise<Result<TableRow[], DbError>> {
+async redo() : Promise<Result<UndoneOperation | null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_table_rows", { tableName }) };
+    return { status: "ok", data: await TAURI_INVOKE("redo") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Clear all rows from a table (for debugging)
+ * Labels of the operations undo and redo would apply next
  */
-async clearTable(tableName: string) : Promise<Result<null, DbError>> {
+async getUndoStatus() : Promise<Result<UndoStatus, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("clear_table", { tableName }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_undo_status") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Export entire database as JSON (for debugging)
+ * Get all views
  */
-async exportDatabaseAsJson() : Promise<Result<ExportedDatabase, DbError>> {
+async getViews() : Promise<Result<View[], AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("export_database_as_json") };
+    return { status: "ok", data: await TAURI_INVOKE("get_views") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get the database file path
+ * Get a view by ID
  */
-async getDatabasePath() : Promise<Result<string, DbError>> {
+async getViewById(id: string) : Promise<Result<View | null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_database_path") };
+    return { status: "ok", data: await TAURI_INVOKE("get_view_by_id", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Get application configuration
+ * Save a view (upsert)
  */
-async getConfig() : Promise<Result<AppConfig, ConfigError>> {
+async saveView(view: ViewInput) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("get_config") };
+    return { status: "ok", data: await TAURI_INVOKE("save_view", { view }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Save application configuration
+ * Delete a view
  */
-async saveConfig(config: AppConfig) : Promise<Result<null, ConfigError>> {
+async deleteView(id: string) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("save_config", { config }) };
+    return { status: "ok", data: await TAURI_INVOKE("delete_view", { id }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Scan vault and return all prompt files
+ * Put views in the order of `ids`; views not listed keep their position after them
  */
-async scanVault() : Promise<Result<PromptFile[], VaultError>> {
+async reorderViews(ids: string[]) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("scan_vault") };
+    return { status: "ok", data: await TAURI_INVOKE("reorder_views", { ids }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Read a single prompt file by ID
+ * Get all tag names
  */
-async readPromptFile(id: string) : Promise<Result<PromptFile, VaultError>> {
+async getAllTags() : Promise<Result<string[], AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("read_prompt_file", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_all_tags") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Write a prompt file
+ * Get all tags as a nested tree built from `/`-separated tag paths
  */
-async writePromptFile(prompt: PromptFile) : Promise<Result<null, VaultError>> {
+async getTagTree() : Promise<Result<TagNode[], AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("write_prompt_file", { prompt }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_tag_tree") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Delete a prompt file
+ * Get tags as a co-occurrence graph: nodes are tags with prompt counts, edges link
+ * tags that appear on the same prompts
  */
-async deletePromptFile(id: string) : Promise<Result<null, VaultError>> {
+async getTagGraph() : Promise<Result<TagGraph, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("delete_prompt_file", { id }) };
+    return { status: "ok", data: await TAURI_INVOKE("get_tag_graph") };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Sync vault files to database cache
- * STRICT VAULT-FIRST:
- * 1. Scan filesystem
- * 2. Upsert all found files to DB
- * 3. Remove DB entries that are not in the scan
+ * Add and remove tags on many prompts: each vault file is rewritten, then the cache is
+ * updated in one transaction. Prompts whose tags would not change are left untouched.
  */
-async syncVault() : Promise<Result<SyncStats, DbError>> {
+async bulkUpdateTags(promptIds: string[], addTags: string[], removeTags: string[]) : Promise<Result<SavePromptResult[], AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("sync_vault") };
+    return { status: "ok", data: await TAURI_INVOKE("bulk_update_tags", { promptIds, addTags, removeTags }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
 },
 /**
- * Start watching the vault for external changes
+ * Link prompt `source` to prompt `target` (e.g. a system prompt to its follow-ups).
+ * Linking the same pair with the same relation again is a no-op.
  */
-async startVaultWatch() : Promise<Result<null, VaultError>> {
+async linkPrompts(source: string, target: string, relation: string) : Promise<Result<null, AppError>> {
     try {
-    return { status: "ok", data: await TAURI_INVOKE("start_vault_watch") };
+    return { status: "ok", data: await TAURI_INVOKE("link_prompts", { source, target, relation }) };
 } catch (e) {
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
-}
-}
-
-/** user-defined events **/
-
-
-
-/** user-defined constants **/
-
-
-
-/** user-defined types **/
-
+},
 /**
- * Application configuration stored in TOML format
+ * Remove a link made with `link_prompts`
  */
-export type AppConfig = { 
+async unlinkPrompts(source: string, target: string, relation: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlink_prompts", { source, target, relation }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Path to the vault directory containing prompt markdown files (as string for TypeScript)
+ * Prompts linked to or from a prompt, by `link_prompts` or by `[[wikilinks]]` in its file
  */
-vaultPath: string | null; 
+async getLinkedPrompts(id: string) : Promise<Result<LinkedPrompt[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_linked_prompts", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * UI theme name
+ * Get all table names (for debugging)
  */
-theme?: string; 
+async getTableNames() : Promise<Result<string[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_table_names") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * View preferences
+ * Get table schema information
  */
-view?: ViewSettings; 
+async getTableInfo(tableName: string) : Promise<Result<TableColumn[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_table_info", { tableName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Frontmatter preferences
+ * Get all rows from a table (for debugging)
  */
-frontmatter?: FrontmatterSettings }
+async getTableRows(tableName: string) : Promise<Result<TableRow[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_table_rows", { tableName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Configuration errors
+ * Clear all rows from a table (for debugging)
  */
-export type ConfigError = { PathError: string } | { IoError: string } | { ParseError: string } | { SerializeError: string }
-export type DbError = { Database: string } | { NotFound: string } | { Serialization: string }
-export type ExportedDatabase = { tables: Partial<{ [key in string]: ExportedTable }> }
-export type ExportedTable = { schema: TableColumn[]; rows: TableRow[] }
-export type FilterConfig = { tags?: string[] | null; search?: string | null; favorite?: boolean | null }
-export type FrontmatterSettings = { promptTagsProperty?: string; addPromptsTagToTags?: boolean }
+async clearTable(tableName: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_table", { tableName }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Prompt with tags - returned to frontend (legacy, for cache-based queries)
+ * Export entire database as JSON (for debugging)
  */
-export type Prompt = { id: string; created: string | null; text: string; tags: string[]; filePath: string | null; title: string | null; description: string | null }
+async exportDatabaseAsJson() : Promise<Result<ExportedDatabase, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_database_as_json") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * A prompt file representation (parsed from markdown)
+ * Export prompts matching `filter` to a JSON, CSV or YAML file; returns the number exported
  */
-export type PromptFile = { 
+async exportPrompts(format: ExportFormat, filter: FilterConfig | null, destinationPath: string) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_prompts", { format, filter, destinationPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * File identifier (relative file path)
+ * Export prompts matching `filter` for another tool (promptfoo config or LangChain
+ * templates), with includes expanded and placeholders converted; returns the number exported
  */
-id: string; 
+async exportPromptsFor(tool: ExportTool, filter: FilterConfig | null, destinationPath: string) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_prompts_for", { tool, filter, destinationPath }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * File path relative to vault root
+ * Import prompts from a JSON, CSV or YAML export into the vault.
+ * `strategy` decides what happens when an item's id or title matches an existing prompt.
  */
-filePath: string; 
+async importPrompts(path: string, format: ExportFormat, strategy: ImportStrategy) : Promise<Result<ImportItemReport[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_prompts", { path, format, strategy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Tags from frontmatter
+ * Export the prompts `ids`, the prompts they include and their tags as a `.promptpack`
+ * archive at `path` (private notes left out unless `share_notes` is on); returns the number
+ * of files packed
  */
-tags: string[]; 
+async exportPromptpack(ids: string[], path: string) : Promise<Result<number, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_promptpack", { ids, path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Created timestamp from frontmatter (ISO string)
+ * Import the prompts and snippets of a `.promptpack` archive into the vault.
+ * `strategy` decides what happens when a file already exists; duplicated files get a
+ * numbered name and includes of them are updated to match.
  */
-created: string | null; 
+async importPromptpack(path: string, strategy: ImportStrategy) : Promise<Result<ImportItemReport[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_promptpack", { path, strategy }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * The prompt content (from code block)
+ * Share the markdown file of prompt `id` (frontmatter included, private notes left out unless
+ * `share_notes` is on) as a GitHub gist, using the token from settings
  */
-content: string; 
+async sharePromptAsGist(id: string, public: boolean) : Promise<Result<SharedGist, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("share_prompt_as_gist", { id, public }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Hash of the full file contents
+ * Import the markdown file of a gist (its first `.md` file, else its first file) as a new
+ * prompt; a numbered name is used when the file name is taken
  */
-fileHash?: string | null; 
+async importPromptFromGist(url: string) : Promise<Result<ImportItemReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_prompt_from_gist", { url }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Optional prompt title from frontmatter
+ * Import user messages from a ChatGPT `conversations.json` export as new prompts
  */
-title: string | null; 
+async importChatgptExport(path: string, options: ChatGptImportOptions) : Promise<Result<ImportItemReport[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_chatgpt_export", { path, options }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 /**
- * Optional prompt description from frontmatter
+ * Copy notes tagged `tag_filter` from an Obsidian vault into the prompt vault.
+ * Notes whose file name is already taken are skipped.
  */
-description: string | null }
-/**
+async importObsidian(path: string, tagFilter: string) : Promise<Result<ImportItemReport[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_obsidian", { path, tagFilter }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the database file path
+ */
+async getDatabasePath() : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_database_path") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Reopen the cache at the path the config names now (e.g. after `databasePath` changed)
+ * and return that path; a new cache is empty until the next sync
+ */
+async reloadDatabase() : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("reload_database") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the current and latest schema versions (for debugging)
+ */
+async getSchemaVersion() : Promise<Result<SchemaVersion, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_schema_version") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get application configuration
+ */
+async getConfig() : Promise<Result<AppConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save application configuration
+ */
+async saveConfig(config: AppConfig) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check a configuration without saving it, for inline feedback in settings
+ */
+async validateConfig(config: AppConfig) : Promise<Result<ConfigIssue[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("validate_config", { config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the saved settings profiles
+ */
+async listProfiles() : Promise<Result<Profile[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_profiles") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Switch to another settings profile and return the config it results in; the frontend
+ * then restarts the vault watcher and syncs the cache as after any vault change
+ */
+async switchProfile(name: string) : Promise<Result<AppConfig, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("switch_profile", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Store an API key or token in the OS keyring; a blank value removes it
+ */
+async setSecret(name: SecretName, value: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_secret", { name, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether a secret is stored; its value never goes back to the frontend
+ */
+async hasSecret(name: SecretName) : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("has_secret", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove an API key or token from the OS keyring; removing one that isn't stored is not an
+ * error
+ */
+async deleteSecret(name: SecretName) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_secret", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Keep `passphrase` in memory to read and save prompts marked `encrypted`. It is checked
+ * against an encrypted prompt in the cache, if there is one.
+ */
+async unlockVault(passphrase: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("unlock_vault", { passphrase }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Forget the passphrase; encrypted prompts can't be read or saved until the next unlock
+ */
+async lockVault() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lock_vault") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Whether a passphrase is set, so encrypted prompts can be opened
+ */
+async isVaultUnlocked() : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("is_vault_unlocked") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The text of an encrypted prompt for the editor; plain prompts are returned as they are
+ */
+async decryptPrompt(id: string) : Promise<Result<string, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("decrypt_prompt", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Scan vault and return all prompt files
+ */
+async scanVault() : Promise<Result<PromptFile[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("scan_vault") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Read a single prompt file by ID
+ */
+async readPromptFile(id: string) : Promise<Result<PromptFile, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("read_prompt_file", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Write a prompt file
+ */
+async writePromptFile(prompt: PromptFile) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("write_prompt_file", { prompt }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a prompt file
+ */
+async deletePromptFile(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_prompt_file", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the git history of a prompt file (empty when git history was never enabled)
+ */
+async getPromptGitHistory(id: string) : Promise<Result<PromptCommit[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_prompt_git_history", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Restore a prompt file to its contents at commit `sha` and refresh it in the cache
+ */
+async restorePromptFromCommit(id: string, sha: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_prompt_from_commit", { id, sha }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Diff the text of two prompts (e.g. near-duplicates)
+ */
+async diffPrompts(idA: string, idB: string, wordLevel: boolean | null) : Promise<Result<PromptDiff, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_prompts", { idA, idB, wordLevel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Diff a prompt's text between two commits of its git history.
+ * A missing version (`None`) means the current file in the vault.
+ */
+async diffPromptVersions(id: string, v1: string | null, v2: string | null, wordLevel: boolean | null) : Promise<Result<PromptDiff, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("diff_prompt_versions", { id, v1, v2, wordLevel }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Find prompts by meaning rather than exact words.
+ * Embeddings of new or edited prompts are computed first.
+ */
+async semanticSearch(query: string, topK: number | null) : Promise<Result<SemanticMatch[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("semantic_search", { query, topK }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Prompts whose embedding is closest to the given prompt's
+ */
+async findSimilarPrompts(id: string, topK: number | null) : Promise<Result<SemanticMatch[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("find_similar_prompts", { id, topK }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Fill a prompt's `{{variable}}` placeholders and built-ins after expanding its `{{include:...}}`s.
+ * With a `preset`, its saved values are used where `values` has none. Declared variables
+ * supply defaults and fail the render when required ones are missing or a value isn't allowed.
+ */
+async renderPrompt(id: string, values: Partial<{ [key in string]: JsonValue }>, preset: string | null) : Promise<Result<RenderedPrompt, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("render_prompt", { id, values, preset }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Render a prompt once per combination of `value_sets` (cartesian product), e.g. to
+ * build a benchmark suite from one base prompt
+ */
+async renderPromptMatrix(id: string, valueSets: Partial<{ [key in string]: JsonValue[] }>) : Promise<Result<MatrixOutput[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("render_prompt_matrix", { id, valueSets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save a named set of variable values for a prompt, replacing a preset of the same name
+ */
+async saveTemplatePreset(promptId: string, name: string, values: Partial<{ [key in string]: JsonValue }>) : Promise<Result<TemplatePreset, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_template_preset", { promptId, name, values }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Presets saved for a prompt, by name
+ */
+async listTemplatePresets(promptId: string) : Promise<Result<TemplatePreset[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_template_presets", { promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a saved preset
+ */
+async deleteTemplatePreset(promptId: string, name: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_template_preset", { promptId, name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Set a value every prompt can use as `{{global:name}}`
+ */
+async setGlobalVariable(name: string, value: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_global_variable", { name, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Global variables by name
+ */
+async getGlobalVariables() : Promise<Result<Partial<{ [key in string]: string }>, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_global_variables") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Remove a global variable; prompts using it render its placeholder as missing
+ */
+async deleteGlobalVariable(name: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_global_variable", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check one prompt file for tidiness problems
+ */
+async lintPrompt(id: string) : Promise<Result<PromptLint, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lint_prompt", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check every prompt in the vault; only prompts with warnings are returned, sorted by id
+ */
+async lintVault() : Promise<Result<PromptLint[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("lint_vault") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check the vault for broken prompt files and for drift between the files and the cache
+ */
+async checkVaultHealth() : Promise<Result<VaultHealthReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_vault_health") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Apply `fixes` to the prompt files that need them and re-sync those files. A dry run
+ * only returns the planned changes.
+ */
+async repairVault(fixes: RepairKind[], dryRun: boolean) : Promise<Result<VaultRepairReport, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("repair_vault", { fixes, dryRun }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Estimate how many tokens `text` takes up for `model` (defaults to GPT-4's tokenizer)
+ */
+async countTokens(text: string, model: string | null) : Promise<TokenCount> {
+    return await TAURI_INVOKE("count_tokens", { text, model });
+},
+/**
+ * Render a prompt, format it per the copy settings, put it on the clipboard and
+ * count the use. Returns what was copied.
+ */
+async copyPromptToClipboard(id: string, values: Partial<{ [key in string]: JsonValue }> | null) : Promise<Result<RenderedPrompt, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("copy_prompt_to_clipboard", { id, values }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Save `text` (the clipboard when omitted) as a new prompt, with the capture tags from
+ * settings added to `tags`; meant for a shortcut that grabs prompts on the fly
+ */
+async quickCapture(text: string | null, title: string | null, tags: string[] | null) : Promise<Result<Prompt, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("quick_capture", { text, title, tags }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Render a prompt, send it to the configured LLM endpoint and record the run
+ */
+async runPrompt(id: string, values: Partial<{ [key in string]: JsonValue }> | null, model: string | null) : Promise<Result<PromptRun, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_prompt", { id, values, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Recorded runs of a prompt, newest first
+ */
+async getPromptRuns(id: string) : Promise<Result<PromptRun[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_prompt_runs", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a single recorded run
+ */
+async deletePromptRun(runId: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_prompt_run", { runId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Create or update a prompt test
+ */
+async savePromptTest(test: PromptTestInput) : Promise<Result<PromptTest, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_prompt_test", { test }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Tests of a prompt with the outcome of their latest run, by name
+ */
+async listPromptTests(promptId: string) : Promise<Result<PromptTest[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_prompt_tests", { promptId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Delete a prompt test and its recorded results
+ */
+async deletePromptTest(id: string) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("delete_prompt_test", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Run every test of a prompt against the configured LLM endpoint (with `model`, or the
+ * configured one) and record whether each response passed its assertion
+ */
+async runPromptTests(promptId: string, model: string | null) : Promise<Result<PromptTestResult[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("run_prompt_tests", { promptId, model }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Ask the configured LLM for tags for a prompt, preferring existing tags
+ */
+async suggestTagsForPrompt(id: string) : Promise<Result<TagSuggestions, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("suggest_tags_for_prompt", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Suggest tags for every prompt without tags. Prompts the LLM fails on are
+ * logged and skipped so one bad reply does not lose the rest of the batch.
+ */
+async suggestTagsForUntagged() : Promise<Result<TagSuggestions[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("suggest_tags_for_untagged") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Sync vault files to database cache (full resync of every file)
+ */
+async syncVault() : Promise<Result<SyncStats, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_vault") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop running `sync_vault` calls between files; a cancelled sync leaves the cache as it
+ * was and fails with a `cancelled` error
+ */
+async cancelSync() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_sync") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Re-read only the given vault-relative paths into the cache
+ */
+async syncPaths(paths: string[]) : Promise<Result<SyncStats, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_paths", { paths }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start watching the vault for external changes
+ */
+async startVaultWatch() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_vault_watch") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop watching the vault (e.g. before switching to another vault path)
+ */
+async stopVaultWatch() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_vault_watch") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get the watched path, running flag and last change timestamp
+ */
+async getVaultWatchStatus() : Promise<Result<VaultWatchStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_vault_watch_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Two-way sync of the vault folder with the configured server, then re-read what it changed.
+ * Fails with `needsConfirmation` before deleting many local files unless `confirm_deletions`.
+ */
+async syncRemoteVault(confirmDeletions: boolean) : Promise<Result<RemoteSyncStats, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("sync_remote_vault", { confirmDeletions }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Upload vault changes and deletions to the configured server
+ */
+async pushVault() : Promise<Result<RemoteSyncStats, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("push_vault") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Apply changes and deletions from the configured server to the vault; see `sync_remote_vault`
+ * for `confirm_deletions`
+ */
+async pullVault(confirmDeletions: boolean) : Promise<Result<RemoteSyncStats, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("pull_vault", { confirmDeletions }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turn the clipboard watcher on or off and remember the choice in settings
+ */
+async setClipboardWatch(enabled: boolean) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_clipboard_watch", { enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get whether the clipboard watcher is running
+ */
+async getClipboardWatchStatus() : Promise<Result<boolean, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_clipboard_watch_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Snapshot the vault into the backup folder now, pruning old snapshots like the scheduler
+ */
+async createBackupNow() : Promise<Result<BackupInfo, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_backup_now") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Snapshots in the backup folder, newest first
+ */
+async listBackups() : Promise<Result<BackupInfo[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_backups") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Replace the vault contents with the snapshot at `path`, then re-sync the cache. The
+ * current vault is snapshotted first so the restore itself can be undone.
+ */
+async restoreBackup(path: string) : Promise<Result<SyncStats, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restore_backup", { path }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Scaffold a new vault at `path` with the starter prompts of `pack` and views for them.
+ * The vault is not opened; the caller points the config at `path` afterwards.
+ */
+async createSampleVault(path: string, pack: SamplePack) : Promise<Result<string[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("create_sample_vault", { path, pack }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Start the local HTTP API on `port` (0 picks a free port); requests must send
+ * `Authorization: Bearer <token>`, with a random token generated when none is given
+ */
+async startApiServer(port: number, token: string | null) : Promise<Result<ApiServerStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_api_server", { port, token }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop the local HTTP API
+ */
+async stopApiServer() : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_api_server") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Get whether the HTTP API is running and on which port
+ */
+async getApiServerStatus() : Promise<Result<ApiServerStatus, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_api_server_status") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The newest `limit` log entries at `level` or more severe, oldest first, for bug reports
+ */
+async getRecentLogs(level: LogLevel, limit: number) : Promise<Result<LogEntry[], AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_recent_logs", { level, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Record entries at `level` or more severe from now on, until the app restarts
+ */
+async setLogLevel(level: LogLevel) : Promise<Result<null, AppError>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_log_level", { level }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+export const events = __makeEvents__<{
+captureSuggested: CaptureSuggested,
+deepLinkOpened: DeepLinkOpened,
+promptDeleted: PromptDeleted,
+promptRenamed: PromptRenamed,
+promptSaved: PromptSaved,
+syncCompleted: SyncCompleted,
+syncProgress: SyncProgress,
+tagChanged: TagChanged,
+vaultChanged: VaultChanged,
+viewDeleted: ViewDeleted,
+viewSaved: ViewSaved,
+viewsReordered: ViewsReordered
+}>({
+captureSuggested: "capture-suggested",
+deepLinkOpened: "deep-link-opened",
+promptDeleted: "prompt-deleted",
+promptRenamed: "prompt-renamed",
+promptSaved: "prompt-saved",
+syncCompleted: "sync-completed",
+syncProgress: "sync-progress",
+tagChanged: "tag-changed",
+vaultChanged: "vault-changed",
+viewDeleted: "view-deleted",
+viewSaved: "view-saved",
+viewsReordered: "views-reordered"
+})
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * Current state of the HTTP API - returned to frontend
+ */
+export type ApiServerStatus = { running: boolean; port: number | null; 
+/**
+ * Token requests must send as `Authorization: Bearer <token>`; generated when the
+ * server was started without one
+ */
+token: string | null }
+/**
+ * Application configuration stored in TOML format
+ */
+export type AppConfig = { 
+/**
+ * Layout version of the file (`CONFIG_VERSION` when saved); files without one are version 0
+ */
+version?: number; 
+/**
+ * Path to the vault directory containing prompt markdown files (as string for TypeScript)
+ */
+vaultPath: string | null; 
+/**
+ * UI theme name
+ */
+theme?: string; 
+/**
+ * View preferences
+ */
+view?: ViewSettings; 
+/**
+ * Frontmatter preferences
+ */
+frontmatter?: FrontmatterSettings; 
+/**
+ * File name for new prompts; supports `{title}`, `{date}`, `{time}`, `{uuid}` and `{random}`
+ */
+filenameTemplate?: string; 
+/**
+ * Tags every new prompt file starts with
+ */
+defaultTags?: string[]; 
+/**
+ * Frontmatter fields every new prompt file starts with (e.g. `status = "draft"`); a
+ * field the prompt sets itself wins
+ */
+defaultFrontmatter?: Partial<{ [key in string]: JsonValue }>; 
+/**
+ * Commit every prompt file change to a git repository in the vault
+ */
+gitHistory?: boolean; 
+/**
+ * Days a deleted prompt can be recovered from the cache; 0 keeps it until recovered
+ */
+deletedRetentionDays?: number; 
+/**
+ * Keep private prompt notes in shared gists and prompt packs (other exports never have them)
+ */
+shareNotes?: boolean; 
+/**
+ * How prompt embeddings for semantic search are computed
+ */
+embeddings?: EmbeddingSettings; 
+/**
+ * How prompts are prepared when copied to the clipboard
+ */
+copy?: CopySettings; 
+/**
+ * Chat completion endpoint used to run prompts
+ */
+llm?: LlmSettings; 
+/**
+ * Built-in template functions like `{{env:VAR}}`
+ */
+templates?: TemplateSettings; 
+/**
+ * Thresholds used when linting prompts
+ */
+lint?: LintSettings; 
+/**
+ * How `quick_capture` saves prompts
+ */
+capture?: CaptureSettings; 
+/**
+ * Server the vault is kept on; `vault_path` then holds its local copy
+ */
+remote?: RemoteVaultSettings; 
+/**
+ * Scheduled zip snapshots of the vault
+ */
+backup?: BackupSettings; 
+/**
+ * Named vault setups to switch between, e.g. "work" and "personal"
+ */
+profiles?: Profile[]; 
+/**
+ * Profile in use; `vault_path`, `frontmatter` and `view` hold its settings
+ */
+activeProfile?: string | null; 
+/**
+ * Cache database file (or a folder to keep `cache.db` in) instead of the app data
+ * folder, e.g. on a RAM disk; applied by `reload_database` or on the next start
+ */
+databasePath?: string | null }
+/**
+ * Wire format of [`AppError`]
+ */
+export type AppError = { kind: ErrorKind; message: string; 
+/**
+ * Both sides of the save, when `kind` is `conflict`
+ */
+conflict: SaveConflict | null; 
+/**
+ * Expected and current revision, when `kind` is `staleRevision`
+ */
+revisionConflict: RevisionConflict | null }
+/**
+ * A vault snapshot in the backup folder
+ */
+export type BackupInfo = { path: string; 
+/**
+ * When the snapshot was taken (RFC 3339)
+ */
+created: string; sizeBytes: number; promptCount: number }
+export type BackupSettings = { 
+/**
+ * Take snapshots in the background
+ */
+enabled?: boolean; 
+/**
+ * Folder the snapshots are written to; `backups` in the app data directory when unset
+ */
+directory?: string | null; 
+/**
+ * Hours between scheduled snapshots
+ */
+intervalHours?: number; 
+/**
+ * Number of snapshots kept; older ones are deleted, 0 keeps them all
+ */
+retention?: number }
+export type CaptureSettings = { 
+/**
+ * Tags added to every captured prompt (e.g. `inbox`)
+ */
+tags?: string[]; 
+/**
+ * Suggest saving prompt-like text copied in other apps
+ */
+watchClipboard?: boolean; 
+/**
+ * Copies shorter than this many characters are not suggested
+ */
+minLength?: number; 
+/**
+ * Copies must contain one of these (case-insensitive) to be suggested; empty accepts any
+ */
+keywords?: string[] }
+/**
+ * The clipboard watcher saw prompt-like text copied in another app
+ */
+export type CaptureSuggested = { text: string }
+export type ChatGptImportOptions = { 
+/**
+ * Only import the opening user message of each conversation
+ */
+firstMessageOnly?: boolean }
+/**
+ * A problem with one setting, for the settings page to show next to it
+ */
+export type ConfigIssue = { severity: ConfigIssueSeverity; 
+/**
+ * Setting the issue is about, named as in the config file (`vaultPath`, `frontmatter`)
+ */
+field: string; message: string }
+export type ConfigIssueSeverity = 
+/**
+ * The config saves, but part of the app will not work with it
+ */
+"warning" | 
+/**
+ * `save_config` refuses the config
+ */
+"error"
+/**
+ * Where the prompt text is stored in a markdown file
+ */
+export type ContentMode = 
+/**
+ * Inside a ```` ```prompt ```` code fence
+ */
+"fence" | 
+/**
+ * The whole body after the frontmatter
+ */
+"body"
+export type CopySettings = { 
+/**
+ * Drop leading/trailing blank lines and trailing spaces on each line
+ */
+trimWhitespace?: boolean; 
+/**
+ * Text added before the copied prompt (e.g. an opening code fence)
+ */
+wrapPrefix?: string; 
+/**
+ * Text added after the copied prompt
+ */
+wrapSuffix?: string }
+/**
+ * A `prompt-manager://` link opened, created or imported this prompt; the frontend shows it
+ */
+export type DeepLinkOpened = Prompt
+/**
+ * A deleted prompt whose last file contents can still be recovered
+ */
+export type DeletedPrompt = { 
+/**
+ * Vault-relative path the prompt had, and is recovered to
+ */
+id: string; title: string | null; deletedAt: string }
+export type DiffChange = "equal" | "insert" | "delete"
+/**
+ * A run of changed lines with surrounding context
+ */
+export type DiffHunk = { oldStart: number; oldLines: number; newStart: number; newLines: number; lines: DiffLine[] }
+export type DiffLine = { change: DiffChange; 
+/**
+ * 1-based line number on the old side (`None` for inserted lines)
+ */
+oldLine: number | null; 
+/**
+ * 1-based line number on the new side (`None` for deleted lines)
+ */
+newLine: number | null; text: string; 
+/**
+ * Word-level segments of `text` (empty unless requested)
+ */
+segments: DiffSegment[] }
+/**
+ * Part of a line; `changed` marks the words that differ from the other side
+ */
+export type DiffSegment = { text: string; changed: boolean }
+/**
+ * Where prompt embeddings come from
+ */
+export type EmbeddingProvider = 
+/**
+ * Built-in hashed word embedding, no model download or network needed
+ */
+"local" | 
+/**
+ * An embeddings API such as a local Ollama server or OpenAI
+ */
+"api"
+export type EmbeddingSettings = { provider?: EmbeddingProvider; 
+/**
+ * OpenAI-compatible embeddings endpoint (used by the `api` provider)
+ */
+apiUrl?: string; apiModel?: string }
+/**
+ * What went wrong, for the frontend to branch on
+ */
+export type ErrorKind = "vaultNotConfigured" | "notFound" | 
+/**
+ * The file changed on disk since it was read; see `conflict`
+ */
+"conflict" | 
+/**
+ * The prompt was saved elsewhere since it was loaded; see `revision_conflict`
+ */
+"staleRevision" | "alreadyExists" | "invalidInput" | "io" | "parse" | "database" | "config" | 
+/**
+ * A request to an LLM, embedding or remote vault endpoint failed
+ */
+"network" | 
+/**
+ * Stopped on request, e.g. a sync cancelled through `cancel_sync`
+ */
+"cancelled" | 
+/**
+ * An encrypted prompt was read or saved before `unlock_vault`
+ */
+"locked" | 
+/**
+ * Stopped before deleting many files; retry with the confirmation flag set
+ */
+"needsConfirmation"
+/**
+ * The part of a prompt's text around its first hit
+ */
+export type Excerpt = { text: string; 
+/**
+ * Char offset of the excerpt in the prompt text
+ */
+start: number; 
+/**
+ * Whether text follows the excerpt
+ */
+truncated: boolean; 
+/**
+ * Hits within the excerpt, relative to its start
+ */
+ranges: MatchRange[] }
+/**
+ * File format for `export_prompts`
+ */
+export type ExportFormat = "json" | "csv" | "yaml"
+/**
+ * Tool `export_prompts_for` shapes its file for
+ */
+export type ExportTool = 
+/**
+ * promptfoo config (YAML) listing every prompt under `prompts`
+ */
+"promptfoo" | 
+/**
+ * JSON array of LangChain `PromptTemplate`s
+ */
+"langchain"
+export type ExportedDatabase = { tables: Partial<{ [key in string]: ExportedTable }> }
+export type ExportedTable = { schema: TableColumn[]; rows: TableRow[] }
+/**
+ * Changes made (or planned, in a dry run) to one prompt file
+ */
+export type FileRepair = { path: string; fixes: RepairKind[]; 
+/**
+ * What each fix did, for people
+ */
+notes: string[]; before: string; after: string }
+export type FilterConfig = { 
+/**
+ * Tags to filter on, nested tags included; `-tag` excludes
+ */
+tags?: string[] | null; 
+/**
+ * Whether prompts need all of the `tags` or any one of them (defaults to all)
+ */
+tagMode?: TagMode | null; search?: SearchConfig | null; favorite?: boolean | null; 
+/**
+ * Only prompts without any tags
+ */
+untagged?: boolean | null; 
+/**
+ * Only prompts without a title (or with a blank one)
+ */
+missingTitle?: boolean | null; 
+/**
+ * Only prompts created at or after this date (`YYYY-MM-DD`) or time
+ */
+createdAfter?: string | null; 
+/**
+ * Only prompts created before this date (`YYYY-MM-DD`) or time
+ */
+createdBefore?: string | null; 
+/**
+ * Only prompts updated at or after this date (`YYYY-MM-DD`) or time
+ */
+updatedAfter?: string | null; 
+/**
+ * `true` lists only archived prompts; archived prompts are excluded otherwise
+ */
+archived?: boolean | null; 
+/**
+ * Custom frontmatter fields that must match (an empty value only requires the key)
+ */
+fields?: Partial<{ [key in string]: string }> | null; 
+/**
+ * Only prompts listing this model in their `model_targets` (ignoring case)
+ */
+modelTarget?: string | null; 
+/**
+ * Only prompts in this language (ignoring case)
+ */
+language?: string | null; 
+/**
+ * Only prompts at this lifecycle stage
+ */
+status?: PromptStatus | null; 
+/**
+ * Only prompts with at least this many words
+ */
+minWords?: number | null; 
+/**
+ * Only prompts with at most this many words
+ */
+maxWords?: number | null }
+export type FrontmatterSettings = { promptTagsProperty?: string; addPromptsTagToTags?: boolean; 
+/**
+ * Frontmatter key holding the created timestamp (e.g. `date`)
+ */
+createdProperty?: string; 
+/**
+ * Frontmatter key holding the title (e.g. `aliases`; lists use their first item)
+ */
+titleProperty?: string; 
+/**
+ * Frontmatter key holding the description
+ */
+descriptionProperty?: string; 
+/**
+ * Where the prompt text lives in a file (a `content_mode` frontmatter key overrides it)
+ */
+contentMode?: ContentMode; 
+/**
+ * Index Obsidian-style `[[wikilinks]]` in prompt files as links to other prompts
+ */
+parseWikilinks?: boolean; 
+/**
+ * Extensions (without the leading dot) of the files treated as prompts, e.g. `txt` or
+ * `prompt.md`; names without one of them get `.md`. Files that do not end in `.md`
+ * are read and written as raw text without frontmatter.
+ */
+fileExtensions?: string[]; 
+/**
+ * Larger files are skipped by scans and syncs instead of being read (0 for no limit)
+ */
+maxFileSizeKb?: number; 
+/**
+ * Read and write prompt files with LF line endings and no byte order mark, as Windows
+ * editors sometimes leave them
+ */
+normalizeLineEndings?: boolean }
+/**
+ * A prompt matched by `fuzzy_search`, with the matched characters of each field
+ */
+export type FuzzyPromptMatch = { prompt: Prompt; score: number; 
+/**
+ * Char indices matched in the title (empty if the title didn't match)
+ */
+titleIndices: number[]; 
+/**
+ * Char indices matched in the file name without `.md` (empty if it didn't match)
+ */
+fileNameIndices: number[] }
+/**
+ * What `get_prompt_groups` groups prompts by
+ */
+export type GroupBy = 
+/**
+ * Each tag; a prompt with several tags is in each of their groups
+ */
+"tag" | 
+/**
+ * `YYYY-MM` of the created date
+ */
+"createdMonth" | 
+/**
+ * Each of `model_targets`
+ */
+"modelTarget" | 
+/**
+ * Folder of the prompt file, relative to the vault. Subfolders aren't supported yet, so
+ * this is a single group of prompts at the vault root.
+ */
+"folder" | 
+/**
+ * Lifecycle stage, in workflow order
+ */
+"status"
+export type HealthIssue = { kind: HealthIssueKind; 
+/**
+ * Vault-relative path of the file (or id of the cached prompt)
+ */
+path: string; message: string; 
+/**
+ * How to fix it
+ */
+suggestion: string }
+export type HealthIssueKind = 
+/**
+ * Frontmatter that is not valid YAML or has no closing `---`; its fields are ignored
+ */
+"malformedFrontmatter" | 
+/**
+ * A file that is not valid UTF-8
+ */
+"unreadableFile" | 
+/**
+ * A file kept in a fence with no ```` ```prompt ```` block, so its prompt reads as empty
+ */
+"missingPromptBlock" | 
+/**
+ * Cached tags that differ from the file's frontmatter
+ */
+"tagMismatch" | 
+/**
+ * Files with the same `id:` in frontmatter or names that only differ in case
+ */
+"duplicateId" | 
+/**
+ * A temp file left behind by an interrupted save
+ */
+"strayTempFile" | 
+/**
+ * A prompt file the cache does not know about
+ */
+"uncachedFile" | 
+/**
+ * A cached prompt whose file is gone
+ */
+"missingFile"
+/**
+ * Outcome of importing a single item
+ */
+export type ImportItemReport = { 
+/**
+ * Id of the item in the import file, if it had one
+ */
+sourceId: string | null; title: string | null; 
+/**
+ * Vault file written (or the existing file when skipped)
+ */
+filePath: string | null; status: ImportStatus; message: string | null }
+export type ImportStatus = "created" | "overwritten" | "duplicated" | "skipped" | "failed"
+/**
+ * How `import_prompts` handles an item whose id or title matches an existing prompt
+ */
+export type ImportStrategy = "skip" | "overwrite" | "duplicate"
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * Which end of a link a `LinkedPrompt` is on
+ */
+export type LinkDirection = 
+/**
+ * The prompt links to the linked prompt
+ */
+"outgoing" | 
+/**
+ * The linked prompt links to the prompt
+ */
+"incoming"
+/**
+ * A prompt linked to another one, manually or by a wikilink
+ */
+export type LinkedPrompt = { prompt: Prompt; 
+/**
+ * e.g. `related`, `follow-up` or `wikilink`
+ */
+relation: string; direction: LinkDirection }
+export type LintKind = 
+/**
+ * A placeholder or include nothing fills in
+ */
+"unresolvedPlaceholder" | 
+/**
+ * Unbalanced `{{#if}}`/`{{#each}}` blocks
+ */
+"invalidTemplate" | 
+/**
+ * A declared variable the prompt never uses
+ */
+"unusedVariable" | "trailingWhitespace" | "missingTitle" | "duplicateTag" | "longLine" | "overTokenBudget"
+export type LintSettings = { 
+/**
+ * Prompt lines longer than this many characters are flagged
+ */
+maxLineLength?: number; 
+/**
+ * Prompts estimated at more tokens than this are flagged
+ */
+tokenBudget?: number }
+export type LintWarning = { kind: LintKind; message: string; 
+/**
+ * 1-based line in the prompt text, for line-level warnings
+ */
+line: number | null }
+export type LlmSettings = { 
+/**
+ * OpenAI-compatible chat completions endpoint
+ */
+apiUrl?: string; 
+/**
+ * Model used when a run does not name one
+ */
+model?: string }
+/**
+ * One line of the log file
+ */
+export type LogEntry = { timestamp: string; level: LogLevel; 
+/**
+ * Module that logged the entry, e.g. `prompt_manager_lib::sync`
+ */
+target: string; message: string; 
+/**
+ * Innermost span the entry was logged in, usually the command being run
+ */
+span: string | null }
+/**
+ * Severity of a log entry, most severe first
+ */
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace"
+/**
+ * Char offsets of a hit (`end` exclusive)
+ */
+export type MatchRange = { start: number; end: number }
+/**
+ * One combination of a matrix render
+ */
+export type MatrixOutput = { 
+/**
+ * The combination as `name=value` pairs, e.g. `language=rust, tone=formal`
+ */
+label: string; values: Partial<{ [key in string]: JsonValue }>; rendered: RenderedPrompt }
+/**
+ * Which page of a list to return; pages are zero-based
+ */
+export type PageRequest = { page: number; pageSize: number }
+/**
+ * The per-vault part of the config, saved under a name
+ */
+export type Profile = { name: string; vaultPath: string | null; frontmatter?: FrontmatterSettings; 
+/**
+ * How prompt lists look in this profile
+ */
+view?: ViewSettings }
+/**
+ * Prompt with tags - returned to frontend (legacy, for cache-based queries)
+ */
+export type Prompt = { id: string; created: string | null; text: string; tags: string[]; filePath: string | null; title: string | null; description: string | null; fileHash?: string | null; customFields?: Partial<{ [key in string]: JsonValue }>; 
+/**
+ * Estimated tokens in `text`, see `count_tokens` for other models
+ */
+tokenCount?: number | null; archived?: boolean; updatedAt?: string | null; 
+/**
+ * Template variables declared in the prompt's frontmatter
+ */
+variables?: TemplateVariable[]; 
+/**
+ * Bumped on every write; pass it back as `PromptInput::expected_revision`
+ */
+revision?: number; 
+/**
+ * Color views show the prompt with, e.g. `#e11d48` or `red`
+ */
+color?: string | null; 
+/**
+ * Icon name or emoji views show the prompt with
+ */
+icon?: string | null; 
+/**
+ * Private working notes; unlike the description, left out of shares and exports
+ */
+notes?: string | null; 
+/**
+ * Models the prompt is tuned for, e.g. `gpt-4o`
+ */
+modelTargets?: string[]; 
+/**
+ * Language the prompt is written in
+ */
+language?: string | null; 
+/**
+ * Lifecycle stage of the prompt
+ */
+status?: PromptStatus | null; 
+/**
+ * Characters, words and lines in `text`, counted when the file was cached
+ */
+charCount?: number | null; wordCount?: number | null; lineCount?: number | null }
+/**
+ * A commit that touched a prompt file
+ */
+export type PromptCommit = { sha: string; message: string; author: string; timestamp: string }
+/**
+ * A prompt was deleted through `delete_prompt`
+ */
+export type PromptDeleted = { id: string }
+export type PromptDiff = { hunks: DiffHunk[]; insertions: number; deletions: number }
+/**
+ * A prompt file representation (parsed from markdown)
+ */
+export type PromptFile = { 
+/**
+ * File identifier (relative file path)
+ */
+id: string; 
+/**
+ * File path relative to vault root
+ */
+filePath: string; 
+/**
+ * Tags from frontmatter
+ */
+tags: string[]; 
+/**
+ * Created timestamp from frontmatter (ISO string)
+ */
+created: string | null; 
+/**
+ * The prompt content (from code block)
+ */
+content: string; 
+/**
+ * Hash of the full file contents
+ */
+fileHash?: string | null; 
+/**
+ * Optional prompt title from frontmatter
+ */
+title: string | null; 
+/**
+ * Optional prompt description from frontmatter
+ */
+description: string | null; 
+/**
+ * Color views show the prompt with (`color` in frontmatter)
+ */
+color?: string | null; 
+/**
+ * Icon name or emoji views show the prompt with (`icon` in frontmatter)
+ */
+icon?: string | null; 
+/**
+ * Private working notes (`notes` in frontmatter), not shared with the prompt
+ */
+notes?: string | null; 
+/**
+ * Models the prompt is tuned for (`model_targets` in frontmatter)
+ */
+modelTargets?: string[]; 
+/**
+ * Language the prompt is written in (`language` in frontmatter)
+ */
+language?: string | null; 
+/**
+ * Lifecycle stage (`status` in frontmatter); unknown values read as `None` and are
+ * left in the file
+ */
+status?: PromptStatus | null; 
+/**
+ * Frontmatter fields not managed by the app (e.g. `model`, `author`, `source`)
+ */
+customFields?: Partial<{ [key in string]: JsonValue }>; 
+/**
+ * Named ```` ```prompt:name ```` blocks; written blocks are updated or appended by name
+ */
+variants?: PromptVariant[]; 
+/**
+ * Archived prompts are kept but hidden from views by default
+ */
+archived?: boolean; 
+/**
+ * Targets of `[[wikilinks]]` in the file body (only read when `parse_wikilinks` is on)
+ */
+links?: string[]; 
+/**
+ * Template variables declared in frontmatter; when empty, a file's declaration is kept
+ */
+variables?: TemplateVariable[] }
+/**
+ * Prompts sharing a group key, in the requested sort order
+ */
+export type PromptGroup = { 
+/**
+ * Tag, month, model, folder or status; `None` for prompts without one (no tags, the vault
+ * root...)
+ */
+key: string | null; count: number; prompts: Prompt[] }
+/**
  * Input for saving a prompt (legacy, for cache-based operations)
  */
-export type PromptInput = { id: string; created: string | null; text: string; tags: string[]; filePath: string | null; previousFilePath: string | null; title: string | null; description: string | null }
-export type SortConfig = { by: string; order: string }
-export type SyncStats = { found: number; updated: number; deleted: number }
+export type PromptInput = { id: string; created: string | null; text: string; tags: string[]; filePath: string | null; previousFilePath: string | null; title: string | null; description: string | null; 
+/**
+ * `None` keeps the file's current color; a blank one removes it
+ */
+color?: string | null; 
+/**
+ * `None` keeps the file's current icon; a blank one removes it
+ */
+icon?: string | null; 
+/**
+ * `None` keeps the file's current notes; blank ones remove them
+ */
+notes?: string | null; 
+/**
+ * `None` keeps the file's current model targets; an empty list removes them
+ */
+modelTargets?: string[] | null; 
+/**
+ * `None` keeps the file's current language; a blank one removes it
+ */
+language?: string | null; 
+/**
+ * `None` keeps the file's current status
+ */
+status?: PromptStatus | null; 
+/**
+ * Hash of the file as last read by the cache; a save is rejected if the file changed since
+ */
+fileHash?: string | null; 
+/**
+ * Custom frontmatter fields; `None` keeps the ones already in the file
+ */
+customFields?: Partial<{ [key in string]: JsonValue }> | null; 
+/**
+ * Name of the ```` ```prompt:name ```` block `text` is saved to (the main block if unset)
+ */
+variant?: string | null; 
+/**
+ * `None` keeps the file's current archived state
+ */
+archived?: boolean | null; 
+/**
+ * Revision the prompt was loaded at; the save fails with `StaleRevision` if it was saved since
+ */
+expectedRevision?: number | null }
+/**
+ * Warnings for one prompt
+ */
+export type PromptLint = { id: string; warnings: LintWarning[] }
+/**
+ * A prompt file was renamed through `rename_prompt`; ids are vault-relative paths
+ */
+export type PromptRenamed = { oldId: string; newId: string }
+/**
+ * A recorded execution of a prompt against an LLM
+ */
+export type PromptRun = { id: string; promptId: string; 
+/**
+ * Hash of the prompt file at run time, to tell revisions apart
+ */
+promptHash: string | null; renderedText: string; 
+/**
+ * Endpoint host, e.g. `localhost:11434`
+ */
+provider: string; model: string; response: string; promptTokens: number | null; completionTokens: number | null; latencyMs: number; created: string }
+/**
+ * A prompt was created or updated through a command, as now cached
+ */
+export type PromptSaved = Prompt
+/**
+ * Lifecycle stage of a prompt (`status` in frontmatter), in workflow order
+ */
+export type PromptStatus = "draft" | "testing" | "production" | "deprecated"
+/**
+ * A prompt as shown in lists: metadata and the start of its text, see `get_prompt`
+ * for the full prompt
+ */
+export type PromptSummary = { id: string; title: string | null; tags: string[]; created: string | null; 
+/**
+ * Start of the text with whitespace collapsed, `…` when cut
+ */
+excerpt: string; color: string | null; icon: string | null }
+/**
+ * One page of `get_prompt_summaries`
+ */
+export type PromptSummaryPage = { items: PromptSummary[]; 
+/**
+ * Prompts matching the filter across all pages
+ */
+total: number }
+/**
+ * A regression check for a prompt: the values to render it with and what the LLM response
+ * must contain (or, with `is_regex`, match)
+ */
+export type PromptTest = { id: string; promptId: string; name: string; values: Partial<{ [key in string]: JsonValue }>; assertion: string; isRegex: boolean; updated: string; 
+/**
+ * Outcome of the latest run, `None` if never run
+ */
+lastPassed: boolean | null }
+/**
+ * Input for saving a prompt test; without an `id` a new test is created
+ */
+export type PromptTestInput = { id?: string | null; promptId: string; name: string; values?: Partial<{ [key in string]: JsonValue }>; assertion: string; isRegex?: boolean }
+/**
+ * Outcome of running one prompt test; `run` is also kept in the prompt's run history
+ */
+export type PromptTestResult = { testId: string; name: string; passed: boolean; run: PromptRun }
+/**
+ * An alternative version of a prompt stored in its own named block
+ */
+export type PromptVariant = { name: string; content: string }
+/**
+ * Which quick list `get_recent_prompts` returns
+ */
+export type RecentKind = 
+/**
+ * Most recently copied
+ */
+"used" | 
+/**
+ * Most recently changed on disk
+ */
+"edited"
+/**
+ * Outcome of `sync_remote`
+ */
+export type RemoteSyncStats = { 
+/**
+ * Vault files written or deleted to match the server
+ */
+pulled: string[]; 
+/**
+ * Server files written or deleted to match the vault
+ */
+pushed: string[]; 
+/**
+ * Copies of local edits to files that also changed on the server, which wins
+ */
+conflicts: string[] }
+export type RemoteVaultSettings = { backend?: VaultBackendKind; webdav?: WebDavSettings; s3?: S3Settings }
+/**
+ * A prompt with its template variables filled in
+ */
+export type RenderedPrompt = { text: string; 
+/**
+ * Variables without a value; their placeholders are left in `text`
+ */
+missing: string[] }
+export type RepairKind = 
+/**
+ * Re-write unreadable frontmatter from the fields that still parse; the rest is kept
+ * in a comment below it
+ */
+"frontmatter" | 
+/**
+ * Add a missing created date from the file's modification time
+ */
+"missingCreated" | 
+/**
+ * Turn tags into a list of normalized, distinct tags
+ */
+"tagFormat" | 
+/**
+ * Wrap the text of a file kept in a fence but lacking a ```` ```prompt ```` block
+ */
+"promptBlock"
+/**
+ * A save rejected because the prompt got a newer revision after the caller loaded it
+ */
+export type RevisionConflict = { id: string; expectedRevision: number; 
+/**
+ * `None` if the prompt was deleted or moved since
+ */
+currentRevision: number | null }
+export type S3Settings = { 
+/**
+ * Service URL, e.g. `https://s3.eu-west-1.amazonaws.com` or `http://localhost:9000`
+ */
+endpoint?: string; region?: string; bucket?: string; 
+/**
+ * Folder in the bucket holding the prompt files (e.g. `prompts/`)
+ */
+prefix?: string; 
+/**
+ * Paired with the secret key stored as `SecretName::S3SecretAccessKey`
+ */
+accessKeyId?: string | null }
+/**
+ * Which starter prompts `create_sample_vault` writes
+ */
+export type SamplePack = 
+/**
+ * Every starter prompt
+ */
+"starter" | "coding" | "writing" | "summarization"
+/**
+ * Both sides of a save rejected because the file was modified outside the app
+ */
+export type SaveConflict = { filePath: string; expectedHash: string; 
+/**
+ * `None` if the file was deleted on disk
+ */
+currentHash: string | null; 
+/**
+ * Prompt text the save tried to write
+ */
+localText: string; 
+/**
+ * Prompt text currently on disk
+ */
+diskText: string | null }
+/**
+ * Outcome of one prompt in a `save_prompts` batch
+ */
+export type SavePromptResult = { 
+/**
+ * Id of the input
+ */
+id: string; 
+/**
+ * Vault file written, when the save succeeded
+ */
+filePath: string | null; error: AppError | null }
+/**
+ * Schema version of the cache database
+ */
+export type SchemaVersion = { 
+/**
+ * Version recorded in the database
+ */
+current: number; 
+/**
+ * Latest version known to this build
+ */
+latest: number }
+/**
+ * A search query and how its words and phrases match
+ */
+export type SearchConfig = { 
+/**
+ * `tag:rust -tag:draft title:"code review" /regex/i` plus plain words, see `SearchQuery`
+ */
+query: string; 
+/**
+ * Words, phrases and `title:` values match only with the same case
+ */
+caseSensitive?: boolean; 
+/**
+ * Words, phrases and `title:` values match only as whole words, so `ai` skips `said`
+ */
+wholeWord?: boolean; 
+/**
+ * Where plain words and phrases are looked for; empty means the title and text
+ */
+fields: SearchField[] }
+/**
+ * A part of a prompt plain search words are looked for in
+ */
+export type SearchField = "title" | "description" | "text"
+/**
+ * A prompt matched by `search_prompts` and where the query hit it
+ */
+export type SearchHit = { prompt: Prompt; titleRanges: MatchRange[]; textRanges: MatchRange[]; 
+/**
+ * Absent when the text itself wasn't hit
+ */
+excerpt: Excerpt | null }
+/**
+ * A secret the app stores; each is one keyring entry under the app identifier
+ */
+export type SecretName = 
+/**
+ * Bearer token for the chat completions endpoint
+ */
+"llmApiKey" | 
+/**
+ * Bearer token for the embeddings endpoint
+ */
+"embeddingsApiKey" | 
+/**
+ * GitHub personal access token with the `gist` scope
+ */
+"githubToken" | 
+/**
+ * Password or app token for the WebDAV vault folder
+ */
+"webDavPassword" | 
+/**
+ * Secret access key for the S3 vault bucket
+ */
+"s3SecretAccessKey"
+/**
+ * Prompt returned by semantic search, with its cosine similarity to the query
+ */
+export type SemanticMatch = { prompt: Prompt; score: number }
+/**
+ * A gist created by `create_gist`
+ */
+export type SharedGist = { id: string; 
+/**
+ * Page of the gist on gist.github.com
+ */
+url: string }
+export type SkipReason = 
+/**
+ * Over the configured maximum file size
+ */
+"tooLarge" | 
+/**
+ * Looks like binary data rather than text
+ */
+"binary" | 
+/**
+ * Could not be read, e.g. not valid UTF-8 or no permission
+ */
+"unreadable" | 
+/**
+ * Not a valid vault path, e.g. `..` in the name
+ */
+"invalidPath"
+/**
+ * A vault file a scan or sync left out, and why
+ */
+export type SkippedFile = { path: string; reason: SkipReason; message: string }
+export type SortConfig = { by: string; order: string; 
+/**
+ * Tie-breakers applied in order when `by` is equal
+ */
+then: SortKey[] }
+/**
+ * A secondary sort key of a `SortConfig`
+ */
+export type SortKey = { by: string; order: string }
+/**
+ * Where `split_prompt` cuts a prompt into parts
+ */
+export type SplitBy = 
+/**
+ * 0-based lines of the prompt text where a new part starts
+ */
+{ lines: number[] } | 
+/**
+ * The text of each part, e.g. as edited before splitting
+ */
+{ sections: string[] }
+/**
+ * Emitted after the vault was synced into the cache, by a command or by the
+ * backend on its own (e.g. in response to watcher events)
+ */
+export type SyncCompleted = SyncStats
+/**
+ * Which part of a full sync `SyncProgress` is about
+ */
+export type SyncPhase = 
+/**
+ * Reading the vault files
+ */
+"scanning" | 
+/**
+ * Writing what was read into the cache
+ */
+"caching"
+/**
+ * Emitted periodically while `sync_vault` runs, so big vaults don't look frozen
+ */
+export type SyncProgress = { phase: SyncPhase; 
+/**
+ * Files done in this phase
+ */
+scanned: number; total: number; 
+/**
+ * File being read or cached, if any
+ */
+current: string | null }
+/**
+ * Result of syncing the vault into the cache
+ */
+export type SyncStats = { found: number; updated: number; deleted: number; 
+/**
+ * Files that could not be read and were left out of the cache
+ */
+skipped?: SkippedFile[] }
 export type TableColumn = { cid: number; name: string; type: string; notnull: number; dfltValue?: string | null; pk: number }
 export type TableRow = (Partial<{ [key in string]: string }>)
 /**
- * Vault operation errors
+ * A command changed which tags prompts use; carries every tag name, sorted
+ */
+export type TagChanged = { tags: string[] }
+/**
+ * Tag co-occurrence graph: tags as nodes, shared prompts as weighted edges
+ */
+export type TagGraph = { nodes: TagGraphNode[]; edges: TagGraphEdge[] }
+export type TagGraphEdge = { source: string; target: string; 
+/**
+ * Number of prompts tagged with both `source` and `target`
+ */
+weight: number; 
+/**
+ * Jaccard similarity of the two tags' prompt sets; close to 1 hints at synonyms
+ */
+similarity: number }
+export type TagGraphNode = { name: string; 
+/**
+ * Number of prompts tagged with exactly this tag
+ */
+promptCount: number }
+/**
+ * How the tags of a `FilterConfig` combine; excluded tags are always all excluded
+ */
+export type TagMode = "and" | "or"
+/**
+ * Node in the hierarchical tag tree (`lang/rust` nests `rust` under `lang`)
+ */
+export type TagNode = { 
+/**
+ * Last path segment, e.g. `rust`
+ */
+name: string; 
+/**
+ * Full tag path, e.g. `lang/rust`
  */
-export type VaultError = "NotConfigured" | { NotFound: string } | { PathNotFound: string } | { IoError: string } | { ParseError: string } | { SerializeError: string } | { InvalidFilename: string } | { InvalidFilePath: string } | { FileAlreadyExists: string } | { InvalidContent: string }
+path: string; 
+/**
+ * Number of prompts tagged with this exact path or any descendant
+ */
+promptCount: number; children: TagNode[] }
+/**
+ * Suggested tags for a prompt - returned to frontend
+ */
+export type TagSuggestions = { promptId: string; 
+/**
+ * Suggestions already used elsewhere in the vault
+ */
+existing: string[]; 
+/**
+ * Suggestions not in the vault yet
+ */
+new: string[] }
+/**
+ * Named set of template variable values saved for a prompt (e.g. `python`, `rust`)
+ */
+export type TemplatePreset = { promptId: string; name: string; values: Partial<{ [key in string]: JsonValue }>; updated: string }
+export type TemplateSettings = { 
+/**
+ * Environment variables `{{env:VAR}}` may read; any other name is rejected
+ */
+envAllowlist?: string[] }
+/**
+ * A variable declared in a prompt's `variables` frontmatter
+ */
+export type TemplateVariable = { name: string; 
+/**
+ * Used when no value is given
+ */
+default?: JsonValue | null; 
+/**
+ * Rendering fails when there is neither a value nor a default
+ */
+required?: boolean; 
+/**
+ * Allowed values; anything goes when empty
+ */
+options?: string[]; description?: string | null }
+/**
+ * Token count of a text for a given model - returned to frontend
+ */
+export type TokenCount = { tokens: number; model: string; 
+/**
+ * Context window of the model, in tokens
+ */
+contextWindow: number }
+/**
+ * Labels of the operations `undo_last_operation` and `redo` would apply next
+ */
+export type UndoStatus = { undo: string | null; redo: string | null }
+/**
+ * What `undo_last_operation` or `redo` put back
+ */
+export type UndoneOperation = { 
+/**
+ * e.g. `Delete prompt notes.md`
+ */
+label: string; 
+/**
+ * Vault-relative files rewritten or removed
+ */
+paths: string[]; stats: SyncStats }
+/**
+ * Where the vault files are stored
+ */
+export type VaultBackendKind = 
+/**
+ * Only in the vault folder
+ */
+"local" | 
+/**
+ * In a WebDAV folder (e.g. Nextcloud), synced with the vault folder
+ */
+"webDav" | 
+/**
+ * In an S3-compatible bucket (AWS, MinIO, Backblaze B2), synced with the vault folder
+ */
+"s3"
+/**
+ * Prompt files changed on disk, as relative paths grouped by change kind
+ */
+export type VaultChanged = { created: string[]; modified: string[]; removed: string[] }
+export type VaultHealthReport = { filesChecked: number; 
+/**
+ * Issues by path, then kind
+ */
+issues: HealthIssue[] }
+export type VaultRepairReport = { dryRun: boolean; files: FileRepair[] }
+/**
+ * Current state of the vault watcher - returned to frontend
+ */
+export type VaultWatchStatus = { running: boolean; path: string | null; lastEventAt: string | null }
 /**
  * View - returned to frontend
  */
@@ -376,12 +2656,50 @@ export type View = { id: string; name: string; type: string; config: ViewConfig;
 /**
  * View configuration for filtering and sorting
  */
-export type ViewConfig = { filter?: FilterConfig | null; sort?: SortConfig | null }
+export type ViewConfig = { filter?: FilterConfig | null; sort?: SortConfig | null; layout?: ViewLayout | null }
+/**
+ * A view was deleted through `delete_view`
+ */
+export type ViewDeleted = { id: string }
 /**
  * Input for saving a view
  */
 export type ViewInput = { id: string; name: string; type: string; config: ViewConfig; created: string }
+/**
+ * How a view displays its prompts; unset fields fall back to the global `ViewSettings`
+ */
+export type ViewLayout = { mode?: string; 
+/**
+ * Prompt details to show, in order
+ */
+columns?: string[] | null; 
+/**
+ * Lines of prompt text to preview
+ */
+previewLength?: number | null; 
+/**
+ * Show prompts in sections by their first tag
+ */
+groupByTag?: boolean }
+/**
+ * A view was created or updated through `save_view`
+ */
+export type ViewSaved = View
 export type ViewSettings = { showPromptTitles?: boolean; showFullPrompt?: boolean; showPromptTags?: boolean; showCreatedDate?: boolean }
+/**
+ * Views were reordered through `reorder_views`; carries every view id in its new order
+ */
+export type ViewsReordered = { ids: string[] }
+export type WebDavSettings = { 
+/**
+ * Folder holding the prompt files, e.g.
+ * `https://cloud.example.com/remote.php/dav/files/me/prompts/`
+ */
+url?: string; 
+/**
+ * Sent with the password or app token stored as `SecretName::WebDavPassword`
+ */
+username?: string | null }
 
 /** tauri-specta globals **/
 
@@ -0,0 +1,125 @@
+import { save } from "@tauri-apps/plugin-dialog";
+import { useState } from "react";
+import { FiCheck, FiPackage, FiX } from "react-icons/fi";
+import { TagInput } from "@/components/tags/TagInput.tsx";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+interface BulkTagBarProps {
+  selectedIds: string[];
+  allTags: string[];
+  onApplied: () => void;
+  onClear: () => void;
+}
+
+// Add tags to (or, as `-tag`, remove them from) every selected prompt
+export function BulkTagBar({
+  selectedIds,
+  allTags,
+  onApplied,
+  onClear,
+}: BulkTagBarProps) {
+  const { pushToast } = useToast();
+  const [tags, setTags] = useState<string[]>([]);
+  const [isApplying, setIsApplying] = useState(false);
+
+  const handleApply = async () => {
+    const addTags = tags.filter((tag) => !tag.startsWith("-"));
+    const removeTags = tags
+      .filter((tag) => tag.startsWith("-"))
+      .map((tag) => tag.slice(1));
+    setIsApplying(true);
+    try {
+      const results = await promptManagerService.bulkUpdateTags(
+        selectedIds,
+        addTags,
+        removeTags,
+      );
+      const failed = results.filter((result) => result.error);
+      if (failed.length > 0) {
+        pushToast({
+          title: `Failed to update ${failed.length} prompts`,
+          description: failed[0].error?.message,
+          variant: "error",
+        });
+      }
+      setTags([]);
+      onApplied();
+    } catch (error) {
+      pushToast({
+        title: "Bulk tag update failed",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    } finally {
+      setIsApplying(false);
+    }
+  };
+
+  // Share the selection (with the prompts it includes) as a .promptpack archive
+  const handleExportPack = async () => {
+    const path = await save({
+      title: "Export Prompt Pack",
+      defaultPath: "prompts.promptpack",
+      filters: [{ name: "Prompt pack", extensions: ["promptpack"] }],
+    });
+    if (!path) return;
+
+    try {
+      const count = await promptManagerService.exportPromptpack(
+        selectedIds,
+        path,
+      );
+      pushToast({ title: `Packed ${count} prompts`, variant: "info" });
+    } catch (error) {
+      pushToast({
+        title: "Pack export failed",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  return (
+    <div className="mb-4 flex items-center gap-2 border border-panel-border bg-panel p-2">
+      <span className="shrink-0 text-neutral-600 text-xs dark:text-neutral-400">
+        {selectedIds.length} selected
+      </span>
+      <div className="min-w-0 flex-1">
+        <TagInput
+          tags={tags}
+          onChange={setTags}
+          suggestions={allTags}
+          placeholder="Tags to add, -tag to remove..."
+          enableNegativeTags
+        />
+      </div>
+      <button
+        type="button"
+        onClick={handleApply}
+        disabled={isApplying || tags.length === 0}
+        className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-400 dark:hover:bg-neutral-700"
+      >
+        <FiCheck size={12} />
+        {isApplying ? "Applying..." : "Apply"}
+      </button>
+      <button
+        type="button"
+        onClick={handleExportPack}
+        title="Export the selected prompts as a .promptpack"
+        className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+      >
+        <FiPackage size={12} />
+        Pack
+      </button>
+      <button
+        type="button"
+        onClick={onClear}
+        className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+      >
+        <FiX size={12} />
+        Clear
+      </button>
+    </div>
+  );
+}
@@ -0,0 +1,116 @@
+import { DragEvent, useMemo, useState } from "react";
+import { Prompt, PromptStatus } from "@/schemas/schemas.ts";
+import { PromptCard } from "./PromptCard.tsx";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+
+interface PromptBoardProps {
+  prompts: Prompt[];
+  onEdit: (prompt: Prompt) => void;
+  onDelete: (prompt: Prompt) => void;
+  onArchive?: (prompt: Prompt) => void;
+  onStatusChange: (prompt: Prompt, status: PromptStatus) => void;
+  showTitles: boolean;
+}
+
+const COLUMNS: { status: PromptStatus; label: string }[] = [
+  { status: "draft", label: "Draft" },
+  { status: "testing", label: "Testing" },
+  { status: "production", label: "Production" },
+  { status: "deprecated", label: "Deprecated" },
+];
+
+// Columns of prompts by lifecycle stage; drop a card on a column to move it
+// there. Prompts without a status are listed last and can't be dropped back.
+export function PromptBoard({
+  prompts,
+  onEdit,
+  onDelete,
+  onArchive,
+  onStatusChange,
+  showTitles,
+}: PromptBoardProps) {
+  const [dropTarget, setDropTarget] = useState<PromptStatus | null>(null);
+
+  const columns = useMemo(() => {
+    const byStatus = new Map<PromptStatus | null, Prompt[]>();
+    for (const prompt of prompts) {
+      const status = prompt.status ?? null;
+      byStatus.set(status, [...(byStatus.get(status) ?? []), prompt]);
+    }
+    const unsorted = byStatus.get(null) ?? [];
+    return [
+      ...COLUMNS.map((column) => ({
+        ...column,
+        prompts: byStatus.get(column.status) ?? [],
+      })),
+      ...(unsorted.length > 0
+        ? [{ status: null, label: "No status", prompts: unsorted }]
+        : []),
+    ];
+  }, [prompts]);
+
+  const handleDrop = (event: DragEvent, status: PromptStatus) => {
+    event.preventDefault();
+    setDropTarget(null);
+    const id = event.dataTransfer.getData("text/plain");
+    const prompt = prompts.find((p) => p.id === id);
+    if (prompt && prompt.status !== status) onStatusChange(prompt, status);
+  };
+
+  return (
+    <div className="flex gap-3 overflow-x-auto pb-2">
+      {columns.map((column) => {
+        const status = column.status;
+        return (
+          <div
+            key={status ?? "none"}
+            onDragOver={status
+              ? (event) => {
+                event.preventDefault();
+                setDropTarget(status);
+              }
+              : undefined}
+            onDragLeave={() => setDropTarget(null)}
+            onDrop={status ? (event) => handleDrop(event, status) : undefined}
+            className={`flex w-64 shrink-0 flex-col gap-2 rounded-xl border p-2 ${
+              status && dropTarget === status
+                ? "border-blue-400 bg-blue-50 dark:bg-blue-900/20"
+                : "border-panel-border bg-panel"
+            }`}
+          >
+            <div className="px-1 pt-1 text-neutral-500 text-xs font-medium uppercase">
+              {column.label}{" "}
+              <span className="text-neutral-400">
+                ({column.prompts.length})
+              </span>
+            </div>
+            {column.prompts.map((prompt) => (
+              <div
+                key={prompt.id}
+                draggable
+                onDragStart={(event) =>
+                  event.dataTransfer.setData("text/plain", prompt.id)}
+                className="cursor-grab"
+              >
+                <PromptCard
+                  prompt={prompt}
+                  onDoubleClick={() => onEdit(prompt)}
+                  onCopy={() =>
+                    promptManagerService.copyPromptToClipboard(prompt.id)
+                      .catch((e) => console.error("Copy failed", e))}
+                  onDelete={() => onDelete(prompt)}
+                  onArchive={onArchive && (() => onArchive(prompt))}
+                  showTitle={showTitles}
+                  showFullPrompt={false}
+                  showTags={false}
+                  showCreatedDate={false}
+                  compact
+                />
+              </div>
+            ))}
+          </div>
+        );
+      })}
+    </div>
+  );
+}
@@ -2,16 +2,31 @@ import React from "react";
 import { Prompt } from "@/schemas/schemas.ts";
 import { ContextMenu } from "radix-ui";
 import { FiCopy } from "react-icons/fi";
+import { SearchHit } from "@/services/PromptManagerService.ts";
+import { HighlightedText } from "@/components/ui/HighlightedText.tsx";
 
 interface PromptCardProps {
   prompt: Prompt;
   onDoubleClick: () => void;
   onCopy: () => void;
   onDelete: () => void;
+  onArchive?: () => void;
   showTitle: boolean;
   showFullPrompt: boolean;
   showTags: boolean;
   showCreatedDate: boolean;
+  showUpdatedDate?: boolean;
+  showTokenCount?: boolean;
+  /** Word and line counts stored with the prompt */
+  showWordCount?: boolean;
+  /** Lines of text to preview; overrides `showFullPrompt` */
+  previewLines?: number;
+  /** Single-line row for the list layout */
+  compact?: boolean;
+  /** Where the current search hit this prompt; the preview shows the hit */
+  searchHit?: SearchHit;
+  selected?: boolean;
+  onToggleSelect?: () => void;
 }
 
 export function PromptCard({
@@ -19,21 +34,41 @@ export function PromptCard({
   onDoubleClick,
   onCopy,
   onDelete,
+  onArchive,
   showTitle,
   showFullPrompt,
   showTags,
   showCreatedDate,
+  showUpdatedDate = false,
+  showTokenCount = false,
+  showWordCount = false,
+  previewLines,
+  compact = false,
+  searchHit,
+  selected = false,
+  onToggleSelect,
 }: PromptCardProps) {
   const handleCopy = (e: React.MouseEvent) => {
     e.stopPropagation();
     onCopy();
   };
+  // Ctrl/Cmd-click adds the prompt to the selection
+  const handleClick = (e: React.MouseEvent) => {
+    if (onToggleSelect && (e.ctrlKey || e.metaKey)) {
+      e.preventDefault();
+      onToggleSelect();
+    }
+  };
   const title = prompt.title?.trim() || "";
   const description = prompt.description?.trim() || "";
 
   const createdLabel = prompt.created
     ? new Date(prompt.created).toLocaleString()
     : "Unknown";
+  const updatedLabel = prompt.updatedAt
+    ? new Date(prompt.updatedAt).toLocaleString()
+    : createdLabel;
+  const lineClamp = previewLines ?? (compact ? 1 : showFullPrompt ? null : 3);
   const tooltipLines = [
     `File: ${prompt.filePath ?? prompt.id}`,
     `Created: ${createdLabel}`,
@@ -46,9 +81,19 @@ export function PromptCard({
     <ContextMenu.Root>
       <ContextMenu.Trigger asChild>
         <div
+          onClick={handleClick}
           onDoubleClick={onDoubleClick}
           title={tooltipLines.join("\n")}
-          className="group relative cursor-pointer border border-panel-border bg-panel p-4 transition-all hover:border-neutral-400 hover:shadow-md dark:hover:border-neutral-500"
+          style={prompt.color
+            ? { borderLeft: `4px solid ${prompt.color}` }
+            : undefined}
+          className={`group relative cursor-pointer border bg-panel ${
+            compact ? "px-3 py-2" : "p-4"
+          } transition-all hover:border-neutral-400 hover:shadow-md dark:hover:border-neutral-500 ${
+            selected
+              ? "border-neutral-900 dark:border-neutral-100"
+              : "border-panel-border"
+          }`}
         >
           <div className="absolute right-3 top-3 flex gap-1 opacity-0 transition-opacity group-hover:opacity-100">
             <button
@@ -67,11 +112,20 @@ export function PromptCard({
             {title
               ? (
                 <h3 className="mb-1 truncate font-semibold text-neutral-900 dark:text-neutral-100">
-                  {title}
+                  {prompt.icon && (
+                    <span className="mr-1.5">{prompt.icon}</span>
+                  )}
+                  <HighlightedText
+                    text={prompt.title ?? ""}
+                    ranges={searchHit?.titleRanges ?? []}
+                  />
                 </h3>
               )
               : (
                 <h3 className="mb-1 truncate font-semibold text-neutral-500 dark:text-neutral-400 italic">
+                  {prompt.icon && (
+                    <span className="mr-1.5">{prompt.icon}</span>
+                  )}
                   Untitled Prompt
                 </h3>
               )}
@@ -81,16 +135,44 @@ export function PromptCard({
 
       {/* Text preview */}
       <p
-        className={`whitespace-pre-wrap font-mono text-neutral-700 text-sm dark:text-neutral-300 ${
-          showFullPrompt ? "" : "line-clamp-3"
-        }`}
+        className="whitespace-pre-wrap font-mono text-neutral-700 text-sm dark:text-neutral-300"
+        style={lineClamp === null ? undefined : {
+          display: "-webkit-box",
+          WebkitBoxOrient: "vertical",
+          WebkitLineClamp: lineClamp,
+          overflow: "hidden",
+        }}
       >
-        {prompt.text}
+        {searchHit?.excerpt
+          ? (
+            <>
+              {searchHit.excerpt.start > 0 && "…"}
+              <HighlightedText
+                text={searchHit.excerpt.text}
+                ranges={searchHit.excerpt.ranges}
+              />
+              {searchHit.excerpt.truncated && "…"}
+            </>
+          )
+          : prompt.customFields?.encrypted === true
+          ? <span className="italic text-neutral-500">Encrypted prompt</span>
+          : prompt.text}
       </p>
 
-      {showCreatedDate && (
-        <div className="mt-2 text-xs text-neutral-500 dark:text-neutral-400">
-          Created: {createdLabel}
+      {(showCreatedDate || showUpdatedDate ||
+        (showTokenCount && prompt.tokenCount != null) ||
+        (showWordCount && prompt.wordCount != null)) && (
+        <div className="mt-2 flex gap-3 text-xs text-neutral-500 dark:text-neutral-400">
+          {showCreatedDate && <span>Created: {createdLabel}</span>}
+          {showUpdatedDate && <span>Updated: {updatedLabel}</span>}
+          {showTokenCount && prompt.tokenCount != null && (
+            <span>~{prompt.tokenCount} tokens</span>
+          )}
+          {showWordCount && prompt.wordCount != null && (
+            <span>
+              {prompt.wordCount} words, {prompt.lineCount ?? 0} lines
+            </span>
+          )}
         </div>
       )}
 
@@ -116,6 +198,14 @@ export function PromptCard({
       </ContextMenu.Trigger>
       <ContextMenu.Portal>
         <ContextMenu.Content className="w-40 rounded-md border border-panel-border bg-panel p-1 shadow-lg">
+          {onArchive && (
+            <ContextMenu.Item
+              onSelect={onArchive}
+              className="cursor-pointer rounded-sm px-2 py-1.5 text-sm text-neutral-700 hover:bg-neutral-100 focus:outline-none dark:text-neutral-300 dark:hover:bg-neutral-700"
+            >
+              {prompt.archived ? "Unarchive" : "Archive"}
+            </ContextMenu.Item>
+          )}
           <ContextMenu.Item
             onSelect={onDelete}
             className="cursor-pointer rounded-sm px-2 py-1.5 text-sm text-red-500 hover:bg-red-500/10 focus:outline-none"
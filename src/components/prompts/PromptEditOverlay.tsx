@@ -40,6 +40,7 @@ export function PromptEditOverlay({
     setTags,
     filePath,
     setFilePath,
+    initialFilePath,
     title,
     setTitle,
     isModified,
@@ -74,15 +75,6 @@ export function PromptEditOverlay({
   }, [isModified, onClose]);
 
   const handleSave = () => {
-    if (text.includes("```") || text.includes("~~~")) {
-      pushToast({
-        title: "Invalid prompt content",
-        description: "Prompt content can't include ``` or ~~~.",
-        variant: "error",
-      });
-      return;
-    }
-
     const normalizedFilePath = filePath
       .trim()
       .replace(/\\/g, "/")
@@ -127,13 +119,18 @@ export function PromptEditOverlay({
       )
     }T${pad(now.getHours())}:${pad(now.getMinutes())}:${pad(now.getSeconds())}`;
 
+    // An untouched suggested name lets the backend apply the filename template
+    const useTemplateName = isNew && filePath === initialFilePath;
+
     const updatedPrompt: Prompt = {
       id: filePathWithExt,
       created: prompt?.created || createdStr,
       text: text,
       tags,
-      filePath: filePathWithExt,
+      filePath: useTemplateName ? null : filePathWithExt,
       previousFilePath: prompt?.filePath || prompt?.id || null,
+      fileHash: prompt?.fileHash ?? null,
+      revision: prompt?.revision,
       title: title.trim() ? title.trim() : null,
     };
     onSave(updatedPrompt);
@@ -1,16 +1,34 @@
 import { useEffect, useMemo, useRef, useState } from "react";
-import { Prompt } from "@/schemas/schemas.ts";
+import { Prompt, PromptStatus } from "@/schemas/schemas.ts";
 import { TagInput } from "@/components/tags/TagInput.tsx";
 import {
   FiCopy,
   FiCopy as FiDuplicate,
   FiCheck,
+  FiLock,
   FiSave,
+  FiScissors,
+  FiShare2,
   FiTrash2,
   FiX,
+  FiZap,
 } from "react-icons/fi";
 import { usePromptEditForm } from "@/hooks/usePromptEditForm.ts";
 import { useToast } from "@/components/ui/ToastProvider.tsx";
+import { PromptHistory } from "@/components/prompts/PromptHistory.tsx";
+import { PromptRuns } from "@/components/prompts/PromptRuns.tsx";
+import { PromptTests } from "@/components/prompts/PromptTests.tsx";
+import { PromptLinks } from "@/components/prompts/PromptLinks.tsx";
+import { usePromptManager } from "@/contexts/PromptManagerContext.tsx";
+import { TokenCount } from "@/bindings.ts";
+import {
+  CommandError,
+  promptManagerService,
+} from "@/services/PromptManagerService.ts";
+
+// Share of the context window a prompt may take before the editor warns,
+// leaving room for the input pasted alongside it and the response
+const TOKEN_WARNING_SHARE = 0.5;
 
 interface PromptEditorProps {
   prompt: Prompt | null;
@@ -33,6 +51,14 @@ export function PromptEditor({
   onClose,
   initialTags = [],
 }: PromptEditorProps) {
+  const wasEncrypted = prompt?.customFields?.encrypted === true;
+  // Text of an encrypted prompt once decrypted; the form compares edits to it
+  const [plainText, setPlainText] = useState<string | null>(null);
+  const editablePrompt = useMemo(
+    () =>
+      prompt && plainText !== null ? { ...prompt, text: plainText } : prompt,
+    [prompt, plainText],
+  );
   const {
     text,
     setText,
@@ -40,13 +66,26 @@ export function PromptEditor({
     setTags,
     filePath,
     setFilePath,
+    initialFilePath,
     title,
     setTitle,
     description,
     setDescription,
+    color,
+    setColor,
+    icon,
+    setIcon,
+    notes,
+    setNotes,
+    modelTargets,
+    setModelTargets,
+    language,
+    setLanguage,
+    status,
+    setStatus,
     isModified,
   } = usePromptEditForm({
-    prompt,
+    prompt: editablePrompt,
     initialTags,
     isNew,
     initialFilePath: useMemo(() => {
@@ -62,8 +101,62 @@ export function PromptEditor({
   });
 
   const { pushToast } = useToast();
+  const { config } = usePromptManager();
   const [confirmDelete, setConfirmDelete] = useState(false);
+  const [tokenCount, setTokenCount] = useState<TokenCount | null>(null);
+  const [suggestedTags, setSuggestedTags] = useState<string[]>([]);
+  const [isSuggesting, setIsSuggesting] = useState(false);
   const textAreaRef = useRef<HTMLTextAreaElement | null>(null);
+  const [encrypted, setEncrypted] = useState(wasEncrypted);
+  const [isLocked, setIsLocked] = useState(false);
+  const [passphrase, setPassphrase] = useState("");
+
+  const revealText = async (id: string) => {
+    try {
+      const decrypted = await promptManagerService.decryptPrompt(id);
+      setPlainText(decrypted);
+      setText(decrypted);
+      setIsLocked(false);
+    } catch (error) {
+      if (error instanceof CommandError && error.kind === "locked") {
+        setIsLocked(true);
+        return;
+      }
+      pushToast({
+        title: "Decryption failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  useEffect(() => {
+    setEncrypted(wasEncrypted);
+    setPlainText(null);
+    setIsLocked(false);
+    if (!isNew && prompt && wasEncrypted) revealText(prompt.id);
+  }, [prompt?.id, isNew]);
+
+  const handleUnlock = async () => {
+    try {
+      await promptManagerService.unlockVault(passphrase);
+      setPassphrase("");
+      if (prompt) await revealText(prompt.id);
+    } catch (error) {
+      pushToast({
+        title: "Unlock failed",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  const handleLock = async () => {
+    await promptManagerService.lockVault();
+    setPlainText(null);
+    setText(prompt?.text ?? "");
+    setIsLocked(true);
+  };
 
   // Handle ESC to close if not modified
   useEffect(() => {
@@ -86,16 +179,35 @@ export function PromptEditor({
     resizeTextArea();
   }, [text]);
 
-  const handleSave = () => {
-    if (text.includes("```") || text.includes("~~~")) {
+  useEffect(() => {
+    const timer = setTimeout(() => {
+      promptManagerService.countTokens(text, config?.llm.model)
+        .then(setTokenCount)
+        .catch((error) => console.error("Failed to count tokens", error));
+    }, 300);
+    return () => clearTimeout(timer);
+  }, [text, config?.llm.model]);
+
+  const handleSuggestTags = async () => {
+    if (!prompt) return;
+    setIsSuggesting(true);
+    try {
+      const result = await promptManagerService.suggestTagsForPrompt(
+        prompt.id,
+      );
+      setSuggestedTags([...result.existing, ...result.new]);
+    } catch (error) {
       pushToast({
-        title: "Invalid prompt content",
-        description: "Prompt content can't include ``` or ~~~.",
+        title: "Tag suggestions failed",
+        description: String(error),
         variant: "error",
       });
-      return;
+    } finally {
+      setIsSuggesting(false);
     }
+  };
 
+  const handleSave = () => {
     const normalizedFilePath = filePath
       .trim()
       .replace(/\\/g, "/")
@@ -140,16 +252,34 @@ export function PromptEditor({
       )
     }T${pad(now.getHours())}:${pad(now.getMinutes())}:${pad(now.getSeconds())}`;
 
+    // An untouched suggested name lets the backend apply the filename template
+    const useTemplateName = isNew && filePath === initialFilePath;
+
     const updatedPrompt: Prompt = {
       id: filePathWithExt,
       created: prompt?.created || createdStr,
       text: text,
       tags,
-      filePath: filePathWithExt,
+      filePath: useTemplateName ? null : filePathWithExt,
       previousFilePath: prompt?.filePath || prompt?.id || null,
+      fileHash: prompt?.fileHash ?? null,
+      revision: prompt?.revision,
       title: title.trim() ? title.trim() : null,
       description: description.trim() ? description.trim() : null,
+      color: color.trim(),
+      icon: icon.trim(),
+      notes: notes.trim(),
+      modelTargets: modelTargets.split(",").map((target) => target.trim())
+        .filter(Boolean),
+      language: language.trim(),
+      status: status || null,
     };
+    if (encrypted || wasEncrypted) {
+      const { encrypted: _, ...fields } = prompt?.customFields ?? {};
+      updatedPrompt.customFields = encrypted
+        ? { ...fields, encrypted: true }
+        : fields;
+    }
     onSave(updatedPrompt);
   };
 
@@ -157,6 +287,65 @@ export function PromptEditor({
     navigator.clipboard.writeText(text);
   };
 
+  // Split the text at blank lines into new prompts; the original is archived
+  const handleSplit = async () => {
+    const sections = text.split(/\n\s*\n/).map((section) => section.trim())
+      .filter(Boolean);
+    if (sections.length < 2) {
+      pushToast({
+        title: "Nothing to split",
+        description: "Separate the prompts with a blank line",
+        variant: "info",
+      });
+      return;
+    }
+    if (
+      !confirm(
+        `Split into ${sections.length} new prompts and archive this one?`,
+      )
+    ) {
+      return;
+    }
+    try {
+      const ids = await promptManagerService.splitPrompt(prompt!.id, {
+        sections,
+      });
+      pushToast({
+        title: `Split into ${ids.length} prompts`,
+        variant: "info",
+      });
+      onClose();
+    } catch (error) {
+      pushToast({
+        title: "Split failed",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  // Share the saved file as a secret gist and copy its URL
+  const handleShareGist = async () => {
+    try {
+      const gist = await promptManagerService.sharePromptAsGist(
+        prompt!.id,
+        false,
+      );
+      await navigator.clipboard.writeText(gist.url);
+      pushToast({
+        title: "Gist created",
+        description: `${gist.url} copied to clipboard`,
+        variant: "info",
+      });
+    } catch (error) {
+      pushToast({
+        title: "Sharing failed",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    }
+  };
+
   return (
     <div className="flex w-full flex-col overflow-hidden border border-panel-border bg-panel shadow-md">
       {/* Header */}
@@ -209,6 +398,28 @@ export function PromptEditor({
               Duplicate
             </button>
           )}
+          {!isNew && !wasEncrypted && (
+            <button
+              type="button"
+              onClick={handleSplit}
+              title="Split at blank lines into new prompts"
+              className="flex items-center gap-1.5 px-2.5 py-1.5 text-neutral-600 text-xs transition-colors hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+            >
+              <FiScissors size={12} />
+              Split
+            </button>
+          )}
+          {!isNew && (
+            <button
+              type="button"
+              onClick={handleShareGist}
+              title="Share as a secret GitHub gist"
+              className="flex items-center gap-1.5 px-2.5 py-1.5 text-neutral-600 text-xs transition-colors hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+            >
+              <FiShare2 size={12} />
+              Share
+            </button>
+          )}
           <button
             type="button"
             onClick={handleCopy}
@@ -240,15 +451,49 @@ export function PromptEditor({
       <div className="flex-1 space-y-4 overflow-y-auto p-4">
         {/* Tags */}
         <div>
-          <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
-            Tags
-          </label>
+          <div className="mb-1 flex items-center justify-between">
+            <label className="block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Tags
+            </label>
+            {!isNew && prompt && (
+              <button
+                type="button"
+                onClick={handleSuggestTags}
+                disabled={isSuggesting}
+                className="flex items-center gap-1 text-neutral-600 text-xs hover:text-neutral-900 disabled:opacity-50 dark:text-neutral-400 dark:hover:text-neutral-100"
+              >
+                <FiZap size={12} />
+                {isSuggesting ? "Suggesting..." : "Suggest"}
+              </button>
+            )}
+          </div>
           <TagInput
             tags={tags}
             onChange={setTags}
             suggestions={allTags}
             placeholder="Add tags..."
           />
+          {suggestedTags.some((tag) => !tags.includes(tag)) && (
+            <div className="mt-1 flex flex-wrap gap-1">
+              {suggestedTags.filter((tag) => !tags.includes(tag)).map((
+                tag,
+              ) => (
+                <button
+                  key={tag}
+                  type="button"
+                  onClick={() => setTags([...tags, tag])}
+                  className={`border border-dashed px-2 py-0.5 text-xs ${
+                    allTags.includes(tag)
+                      ? "border-blue-400 text-blue-700 dark:text-blue-300"
+                      : "border-neutral-400 text-neutral-600 dark:text-neutral-400"
+                  }`}
+                  title={allTags.includes(tag) ? "Existing tag" : "New tag"}
+                >
+                  + {tag}
+                </button>
+              ))}
+            </div>
+          )}
         </div>
 
         <div className="grid gap-4 md:grid-cols-2">
@@ -298,12 +543,154 @@ export function PromptEditor({
           />
         </div>
 
+        {/* Color and icon */}
+        <div className="grid gap-4 md:grid-cols-2">
+          <div>
+            <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Color
+            </label>
+            <div className="flex items-center gap-2">
+              <input
+                type="color"
+                value={/^#[0-9a-f]{6}$/i.test(color) ? color : "#000000"}
+                onChange={(e) => setColor(e.target.value)}
+                className="h-9 w-9 shrink-0 cursor-pointer border border-panel-border bg-panel-2"
+              />
+              <input
+                type="text"
+                value={color}
+                onChange={(e) => setColor(e.target.value)}
+                placeholder="None"
+                className="w-full border border-panel-border bg-panel-2 px-3 py-2 text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+              />
+            </div>
+          </div>
+          <div>
+            <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Icon
+            </label>
+            <input
+              type="text"
+              value={icon}
+              onChange={(e) => setIcon(e.target.value)}
+              placeholder="Emoji or icon name"
+              className="w-full border border-panel-border bg-panel-2 px-3 py-2 text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+            />
+          </div>
+        </div>
+
+        {/* Model targets, language and status */}
+        <div className="grid gap-4 md:grid-cols-3">
+          <div>
+            <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Models
+            </label>
+            <input
+              type="text"
+              value={modelTargets}
+              onChange={(e) => setModelTargets(e.target.value)}
+              placeholder="e.g. gpt-4o, claude-3.5"
+              className="w-full border border-panel-border bg-panel-2 px-3 py-2 text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Language
+            </label>
+            <input
+              type="text"
+              value={language}
+              onChange={(e) => setLanguage(e.target.value)}
+              placeholder="e.g. en"
+              className="w-full border border-panel-border bg-panel-2 px-3 py-2 text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+            />
+          </div>
+          <div>
+            <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Status
+            </label>
+            <select
+              value={status}
+              onChange={(e) => setStatus(e.target.value as PromptStatus | "")}
+              className="w-full border border-panel-border bg-panel-2 px-3 py-2 text-neutral-900 text-sm focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+            >
+              {/* A saved status can be changed but not cleared */}
+              {!prompt?.status && <option value="">No status</option>}
+              <option value="draft">Draft</option>
+              <option value="testing">Testing</option>
+              <option value="production">Production</option>
+              <option value="deprecated">Deprecated</option>
+            </select>
+          </div>
+        </div>
+
+        {/* Notes */}
+        <div>
+          <label className="mb-1 block font-medium text-neutral-700 text-sm dark:text-neutral-300">
+            Notes
+          </label>
+          <textarea
+            value={notes}
+            onChange={(e) => setNotes(e.target.value)}
+            placeholder="Private notes, left out when the prompt is shared"
+            rows={2}
+            className="w-full resize-y border border-panel-border bg-panel-2 px-3 py-2 text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+          />
+        </div>
+
         {/* Text */}
         <div className="flex flex-col gap-1">
-          <label className="font-medium text-neutral-700 text-sm dark:text-neutral-300">
-            Prompt Content
-          </label>
+          <div className="flex items-center justify-between">
+            <label className="font-medium text-neutral-700 text-sm dark:text-neutral-300">
+              Prompt Content
+            </label>
+            <div className="flex items-center gap-3">
+              {encrypted && plainText !== null && (
+                <button
+                  type="button"
+                  onClick={handleLock}
+                  title="Forget the passphrase until the next unlock"
+                  className="flex items-center gap-1 text-neutral-600 text-xs hover:text-neutral-900 dark:text-neutral-400 dark:hover:text-neutral-100"
+                >
+                  <FiLock size={12} />
+                  Lock
+                </button>
+              )}
+              <label className="flex items-center gap-1.5 text-neutral-600 text-xs dark:text-neutral-400">
+                <input
+                  type="checkbox"
+                  checked={encrypted}
+                  disabled={isLocked}
+                  onChange={(e) => setEncrypted(e.target.checked)}
+                  className="h-3.5 w-3.5 accent-blue-600"
+                />
+                Encrypted
+              </label>
+            </div>
+          </div>
+          {isLocked && (
+            <div className="flex items-center gap-2 border border-panel-border bg-panel-2 p-3">
+              <FiLock size={14} className="shrink-0 text-neutral-500" />
+              <input
+                type="password"
+                value={passphrase}
+                onChange={(e) => setPassphrase(e.target.value)}
+                onKeyDown={(e) => e.key === "Enter" && handleUnlock()}
+                placeholder="Passphrase to decrypt this prompt"
+                className="flex-1 border border-panel-border bg-panel px-3 py-1.5 text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
+              />
+              <button
+                type="button"
+                onClick={handleUnlock}
+                disabled={!passphrase}
+                className="bg-neutral-800 px-3 py-1.5 text-xs text-white transition-colors hover:bg-neutral-700 disabled:opacity-50 dark:bg-neutral-200 dark:text-black dark:hover:bg-neutral-300"
+              >
+                Unlock
+              </button>
+            </div>
+          )}
           <textarea
+            hidden={isLocked}
             ref={textAreaRef}
             autoFocus
             value={text}
@@ -314,7 +701,28 @@ export function PromptEditor({
             placeholder="Enter your prompt text..."
             className="min-h-[200px] w-full resize-none overflow-hidden border border-panel-border bg-panel-2 px-3 py-2 font-mono text-neutral-900 text-sm placeholder-neutral-400 focus:outline-none focus:ring-2 focus:ring-blue-500/50 dark:text-neutral-100"
           />
+          {tokenCount && (
+            <span
+              className={`self-end text-xs ${
+                tokenCount.tokens >
+                    tokenCount.contextWindow * TOKEN_WARNING_SHARE
+                  ? "text-amber-600 dark:text-amber-400"
+                  : "text-neutral-500"
+              }`}
+              title={`${tokenCount.model} context window: ${tokenCount.contextWindow} tokens`}
+            >
+              ~{tokenCount.tokens} tokens
+              {tokenCount.tokens >
+                  tokenCount.contextWindow * TOKEN_WARNING_SHARE &&
+                ` (over half of ${tokenCount.model}'s context window)`}
+            </span>
+          )}
         </div>
+
+        {!isNew && prompt && <PromptRuns promptId={prompt.id} />}
+        {!isNew && prompt && <PromptTests promptId={prompt.id} />}
+        {!isNew && prompt && <PromptLinks promptId={prompt.id} />}
+        {!isNew && prompt && <PromptHistory promptId={prompt.id} />}
       </div>
     </div>
   );
@@ -0,0 +1,178 @@
+import { useEffect, useState } from "react";
+import { FiClock, FiColumns, FiRotateCcw } from "react-icons/fi";
+import { PromptCommit, PromptDiff } from "@/bindings.ts";
+import { usePromptManager } from "@/contexts/PromptManagerContext.tsx";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+interface PromptHistoryProps {
+  promptId: string;
+}
+
+// Git history of a prompt file, shown when vault history is enabled
+export function PromptHistory({ promptId }: PromptHistoryProps) {
+  const { config, refresh } = usePromptManager();
+  const { pushToast } = useToast();
+  const [isOpen, setIsOpen] = useState(false);
+  const [commits, setCommits] = useState<PromptCommit[]>([]);
+  const [diff, setDiff] = useState<{ sha: string; diff: PromptDiff } | null>(
+    null,
+  );
+
+  useEffect(() => {
+    if (!isOpen) return;
+    promptManagerService.getPromptGitHistory(promptId)
+      .then(setCommits)
+      .catch((error) => {
+        console.error("Failed to load prompt history", error);
+      });
+  }, [isOpen, promptId]);
+
+  if (!config?.gitHistory) return null;
+
+  const handleToggleDiff = async (commit: PromptCommit) => {
+    if (diff?.sha === commit.sha) {
+      setDiff(null);
+      return;
+    }
+    try {
+      const result = await promptManagerService.diffPromptVersions(
+        promptId,
+        commit.sha,
+        null,
+        true,
+      );
+      setDiff({ sha: commit.sha, diff: result });
+    } catch (error) {
+      pushToast({
+        title: "Diff failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  const handleRestore = async (commit: PromptCommit) => {
+    try {
+      await promptManagerService.restorePromptFromCommit(promptId, commit.sha);
+      await refresh({ skipSync: true });
+      pushToast({
+        title: "Prompt restored",
+        description: `Restored version from ${commit.timestamp}.`,
+      });
+    } catch (error) {
+      pushToast({
+        title: "Restore failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  return (
+    <div className="border-t border-panel-border pt-3">
+      <button
+        type="button"
+        onClick={() => setIsOpen(!isOpen)}
+        className="flex items-center gap-1.5 text-neutral-600 text-xs hover:text-neutral-900 dark:text-neutral-400 dark:hover:text-neutral-100"
+      >
+        <FiClock size={12} />
+        {isOpen ? "Hide history" : "Show history"}
+      </button>
+      {isOpen && (
+        <ul className="mt-2 space-y-1">
+          {commits.length === 0 && (
+            <li className="text-neutral-500 text-xs">No history yet.</li>
+          )}
+          {commits.map((commit, index) => (
+            <li key={commit.sha}>
+              <div className="flex items-center justify-between gap-2 text-xs text-neutral-700 dark:text-neutral-300">
+                <span className="truncate">
+                  <span className="font-mono text-neutral-500">
+                    {commit.sha.slice(0, 7)}
+                  </span>{" "}
+                  {commit.message}
+                  <span className="ml-2 text-neutral-500">
+                    {commit.timestamp}
+                  </span>
+                </span>
+                {index > 0 && (
+                  <span className="flex shrink-0 items-center">
+                    <button
+                      type="button"
+                      onClick={() => handleToggleDiff(commit)}
+                      className="flex items-center gap-1 px-2 py-1 text-neutral-600 hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+                    >
+                      <FiColumns size={12} />
+                      Diff
+                    </button>
+                    <button
+                      type="button"
+                      onClick={() => handleRestore(commit)}
+                      className="flex items-center gap-1 px-2 py-1 text-neutral-600 hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+                    >
+                      <FiRotateCcw size={12} />
+                      Restore
+                    </button>
+                  </span>
+                )}
+              </div>
+              {diff?.sha === commit.sha && <DiffView diff={diff.diff} />}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
+
+// Hunks of a version diff against the current text, with changed words highlighted
+function DiffView({ diff }: { diff: PromptDiff }) {
+  if (diff.hunks.length === 0) {
+    return (
+      <p className="mt-1 text-neutral-500 text-xs">
+        Identical to the current version.
+      </p>
+    );
+  }
+
+  return (
+    <div className="mt-1 overflow-x-auto border border-panel-border font-mono text-xs">
+      {diff.hunks.map((hunk) => (
+        <div key={`${hunk.oldStart}-${hunk.newStart}`}>
+          <div className="bg-neutral-100 px-2 text-neutral-500 dark:bg-neutral-800">
+            @@ -{hunk.oldStart},{hunk.oldLines} +{hunk.newStart},{hunk.newLines} @@
+          </div>
+          {hunk.lines.map((line, index) => (
+            <div
+              key={index}
+              className={`whitespace-pre-wrap px-2 ${
+                line.change === "insert"
+                  ? "bg-green-50 text-green-800 dark:bg-green-950 dark:text-green-300"
+                  : line.change === "delete"
+                  ? "bg-red-50 text-red-800 dark:bg-red-950 dark:text-red-300"
+                  : "text-neutral-600 dark:text-neutral-400"
+              }`}
+            >
+              {line.change === "insert"
+                ? "+ "
+                : line.change === "delete"
+                ? "- "
+                : "  "}
+              {line.segments.length > 0
+                ? line.segments.map((segment, i) => (
+                  <span
+                    key={i}
+                    className={segment.changed ? "font-semibold underline" : ""}
+                  >
+                    {segment.text}
+                  </span>
+                ))
+                : line.text}
+            </div>
+          ))}
+        </div>
+      ))}
+    </div>
+  );
+}
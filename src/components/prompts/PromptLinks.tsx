@@ -0,0 +1,155 @@
+import { useEffect, useState } from "react";
+import { FiArrowLeft, FiArrowRight, FiLink, FiX } from "react-icons/fi";
+import { usePromptManager } from "@/contexts/PromptManagerContext.tsx";
+import {
+  LinkedPrompt,
+  promptManagerService,
+} from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+import { Prompt } from "@/schemas/schemas.ts";
+
+const WIKILINK_RELATION = "wikilink";
+const DEFAULT_RELATION = "related";
+
+interface PromptLinksProps {
+  promptId: string;
+}
+
+function promptLabel(prompt: Prompt): string {
+  return prompt.title?.trim() || prompt.text.split("\n")[0].trim() ||
+    prompt.id;
+}
+
+// Prompts linked to or from a prompt; wikilinks come from the file and can't be removed here
+export function PromptLinks({ promptId }: PromptLinksProps) {
+  const { prompts } = usePromptManager();
+  const { pushToast } = useToast();
+  const [isOpen, setIsOpen] = useState(false);
+  const [links, setLinks] = useState<LinkedPrompt[]>([]);
+  const [target, setTarget] = useState("");
+  const [relation, setRelation] = useState(DEFAULT_RELATION);
+
+  const loadLinks = () =>
+    promptManagerService.getLinkedPrompts(promptId)
+      .then(setLinks)
+      .catch((error) => {
+        console.error("Failed to load linked prompts", error);
+      });
+
+  useEffect(() => {
+    if (!isOpen) return;
+    loadLinks();
+  }, [isOpen, promptId]);
+
+  const handleLink = async () => {
+    try {
+      await promptManagerService.linkPrompts(promptId, target, relation);
+      setTarget("");
+      await loadLinks();
+    } catch (error) {
+      pushToast({
+        title: "Link failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  const handleUnlink = async (link: LinkedPrompt) => {
+    try {
+      await promptManagerService.unlinkPrompts(
+        promptId,
+        link.prompt.id,
+        link.relation,
+      );
+      setLinks((prev) => prev.filter((item) => item !== link));
+    } catch (error) {
+      pushToast({
+        title: "Unlink failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  return (
+    <div className="border-t border-panel-border pt-3">
+      <button
+        type="button"
+        onClick={() => setIsOpen(!isOpen)}
+        className="flex items-center gap-1.5 text-neutral-600 text-xs hover:text-neutral-900 dark:text-neutral-400 dark:hover:text-neutral-100"
+      >
+        <FiLink size={12} />
+        {isOpen ? "Hide links" : "Show links"}
+      </button>
+      {isOpen && (
+        <div className="mt-2 space-y-2">
+          <ul className="space-y-1">
+            {links.length === 0 && (
+              <li className="text-neutral-500 text-xs">No linked prompts.</li>
+            )}
+            {links.map((link) => (
+              <li
+                key={`${link.direction}:${link.relation}:${link.prompt.id}`}
+                className="flex items-center justify-between gap-2 text-neutral-700 text-xs dark:text-neutral-300"
+              >
+                <span className="flex min-w-0 items-center gap-1.5">
+                  {link.direction === "outgoing"
+                    ? <FiArrowRight size={12} className="shrink-0" />
+                    : <FiArrowLeft size={12} className="shrink-0" />}
+                  <span className="truncate">{promptLabel(link.prompt)}</span>
+                  <span className="shrink-0 text-neutral-500">
+                    {link.relation}
+                  </span>
+                </span>
+                {link.direction === "outgoing" &&
+                  link.relation !== WIKILINK_RELATION && (
+                  <button
+                    type="button"
+                    onClick={() => handleUnlink(link)}
+                    className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+                  >
+                    <FiX size={12} />
+                    Unlink
+                  </button>
+                )}
+              </li>
+            ))}
+          </ul>
+          <div className="flex items-center gap-2">
+            <select
+              value={target}
+              onChange={(event) => setTarget(event.target.value)}
+              className="min-w-0 flex-1 border border-panel-border bg-panel px-2 py-1 text-neutral-700 text-xs dark:text-neutral-300"
+            >
+              <option value="">Link to prompt...</option>
+              {prompts
+                .filter((prompt) => prompt.id !== promptId)
+                .map((prompt) => (
+                  <option key={prompt.id} value={prompt.id}>
+                    {promptLabel(prompt)}
+                  </option>
+                ))}
+            </select>
+            <input
+              type="text"
+              value={relation}
+              onChange={(event) => setRelation(event.target.value)}
+              placeholder={DEFAULT_RELATION}
+              className="w-28 border border-panel-border bg-panel px-2 py-1 text-neutral-700 text-xs dark:text-neutral-300"
+            />
+            <button
+              type="button"
+              onClick={handleLink}
+              disabled={!target || !relation.trim()}
+              className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-400 dark:hover:bg-neutral-700"
+            >
+              <FiLink size={12} />
+              Link
+            </button>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
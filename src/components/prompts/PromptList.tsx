@@ -1,6 +1,10 @@
-import { RefObject, useLayoutEffect, useRef, useState } from "react";
+import { RefObject, useLayoutEffect, useMemo, useRef, useState } from "react";
 import { Prompt } from "@/schemas/schemas.ts";
 import { PromptCard } from "./PromptCard.tsx";
+import {
+  promptManagerService,
+  SearchHit,
+} from "@/services/PromptManagerService.ts";
 import { useVirtualizer } from "@tanstack/react-virtual";
 import { ReactNode } from "react";
 
@@ -9,11 +13,46 @@ interface PromptListProps {
   parentRef: RefObject<HTMLDivElement | null>;
   onEdit: (prompt: Prompt) => void;
   onDelete: (prompt: Prompt) => void;
+  onArchive?: (prompt: Prompt) => void;
   renderPrompt?: (prompt: Prompt) => ReactNode | null;
   showTitles: boolean;
   showFullPrompt: boolean;
   showTags: boolean;
   showCreatedDate: boolean;
+  showUpdatedDate?: boolean;
+  showTokenCount?: boolean;
+  showWordCount?: boolean;
+  previewLines?: number;
+  compact?: boolean;
+  groupByTag?: boolean;
+  searchHits?: Map<string, SearchHit>;
+  selectedIds?: Set<string>;
+  onToggleSelect?: (prompt: Prompt) => void;
+}
+
+type ListRow =
+  | { kind: "header"; tag: string; count: number }
+  | { kind: "prompt"; prompt: Prompt };
+
+const UNTAGGED_GROUP = "Untagged";
+
+// Sections by first tag (alphabetical, untagged last), keeping the prompt order within each
+function groupRows(prompts: Prompt[]): ListRow[] {
+  const groups = new Map<string, Prompt[]>();
+  for (const prompt of prompts) {
+    const tag = prompt.tags[0] ?? UNTAGGED_GROUP;
+    groups.set(tag, [...(groups.get(tag) ?? []), prompt]);
+  }
+  const tags = [...groups.keys()].sort((a, b) =>
+    a === UNTAGGED_GROUP ? 1 : b === UNTAGGED_GROUP ? -1 : a.localeCompare(b)
+  );
+  return tags.flatMap((tag) => {
+    const members = groups.get(tag) ?? [];
+    return [
+      { kind: "header", tag, count: members.length } as ListRow,
+      ...members.map((prompt) => ({ kind: "prompt", prompt }) as ListRow),
+    ];
+  });
 }
 
 export function PromptList({
@@ -21,11 +60,21 @@ export function PromptList({
   parentRef,
   onEdit,
   onDelete,
+  onArchive,
   renderPrompt,
   showTitles,
   showFullPrompt,
   showTags,
   showCreatedDate,
+  showUpdatedDate,
+  showTokenCount,
+  showWordCount,
+  previewLines,
+  compact = false,
+  groupByTag = false,
+  searchHits,
+  selectedIds,
+  onToggleSelect,
 }: PromptListProps) {
   const containerRef = useRef<HTMLDivElement>(null);
   const [scrollMargin, setScrollMargin] = useState(0);
@@ -36,10 +85,19 @@ export function PromptList({
     }
   }, []);
 
+  const rows = useMemo<ListRow[]>(
+    () =>
+      groupByTag
+        ? groupRows(prompts)
+        : prompts.map((prompt) => ({ kind: "prompt", prompt })),
+    [prompts, groupByTag],
+  );
+
   const rowVirtualizer = useVirtualizer({
-    count: prompts.length,
+    count: rows.length,
     getScrollElement: () => parentRef.current,
-    estimateSize: () => 140,
+    estimateSize: (index) =>
+      rows[index].kind === "header" ? 32 : compact ? 60 : 140,
     overscan: 10,
     scrollMargin,
   });
@@ -62,7 +120,30 @@ export function PromptList({
       }}
     >
       {rowVirtualizer.getVirtualItems().map((virtualItem) => {
-        const prompt = prompts[virtualItem.index];
+        const row = rows[virtualItem.index];
+        if (row.kind === "header") {
+          return (
+            <div
+              key={virtualItem.key}
+              data-index={virtualItem.index}
+              ref={rowVirtualizer.measureElement}
+              style={{
+                position: "absolute",
+                top: 0,
+                left: 0,
+                width: "100%",
+                transform: `translateY(${
+                  virtualItem.start - rowVirtualizer.options.scrollMargin
+                }px)`,
+              }}
+              className="pb-2 pt-1 text-neutral-500 text-xs font-medium uppercase"
+            >
+              {row.tag}{" "}
+              <span className="text-neutral-400">({row.count})</span>
+            </div>
+          );
+        }
+        const prompt = row.prompt;
         const customRender = renderPrompt ? renderPrompt(prompt) : null;
         return (
           <div
@@ -78,18 +159,29 @@ export function PromptList({
                 virtualItem.start - rowVirtualizer.options.scrollMargin
               }px)`,
             }}
-            className="pb-4"
+            className={compact ? "pb-2" : "pb-4"}
           >
             {customRender || (
               <PromptCard
                 prompt={prompt}
                 onDoubleClick={() => onEdit(prompt)}
-                onCopy={() => navigator.clipboard.writeText(prompt.text)}
+                onCopy={() =>
+                  promptManagerService.copyPromptToClipboard(prompt.id)
+                    .catch((e) => console.error("Copy failed", e))}
                 onDelete={() => onDelete(prompt)}
+                onArchive={onArchive && (() => onArchive(prompt))}
                 showTitle={showTitles}
                 showFullPrompt={showFullPrompt}
                 showTags={showTags}
                 showCreatedDate={showCreatedDate}
+                showUpdatedDate={showUpdatedDate}
+                showTokenCount={showTokenCount}
+                showWordCount={showWordCount}
+                previewLines={previewLines}
+                compact={compact}
+                searchHit={searchHits?.get(prompt.id)}
+                selected={selectedIds?.has(prompt.id) ?? false}
+                onToggleSelect={onToggleSelect && (() => onToggleSelect(prompt))}
               />
             )}
           </div>
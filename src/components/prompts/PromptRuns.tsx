@@ -0,0 +1,124 @@
+import { useEffect, useState } from "react";
+import { FiPlay, FiTrash2, FiZap } from "react-icons/fi";
+import { PromptRun } from "@/bindings.ts";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+interface PromptRunsProps {
+  promptId: string;
+}
+
+// Runs of a prompt against the configured LLM, newest first
+export function PromptRuns({ promptId }: PromptRunsProps) {
+  const { pushToast } = useToast();
+  const [isOpen, setIsOpen] = useState(false);
+  const [isRunning, setIsRunning] = useState(false);
+  const [runs, setRuns] = useState<PromptRun[]>([]);
+  const [expanded, setExpanded] = useState<string | null>(null);
+
+  useEffect(() => {
+    if (!isOpen) return;
+    promptManagerService.getPromptRuns(promptId)
+      .then(setRuns)
+      .catch((error) => {
+        console.error("Failed to load prompt runs", error);
+      });
+  }, [isOpen, promptId]);
+
+  const handleRun = async () => {
+    setIsRunning(true);
+    try {
+      const run = await promptManagerService.runPrompt(promptId);
+      setRuns((prev) => [run, ...prev]);
+      setExpanded(run.id);
+      setIsOpen(true);
+    } catch (error) {
+      pushToast({
+        title: "Run failed",
+        description: String(error),
+        variant: "error",
+      });
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  const handleDelete = async (run: PromptRun) => {
+    try {
+      await promptManagerService.deletePromptRun(run.id);
+      setRuns((prev) => prev.filter((item) => item.id !== run.id));
+    } catch (error) {
+      pushToast({
+        title: "Delete failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  return (
+    <div className="border-t border-panel-border pt-3">
+      <div className="flex items-center gap-3">
+        <button
+          type="button"
+          onClick={() => setIsOpen(!isOpen)}
+          className="flex items-center gap-1.5 text-neutral-600 text-xs hover:text-neutral-900 dark:text-neutral-400 dark:hover:text-neutral-100"
+        >
+          <FiZap size={12} />
+          {isOpen ? "Hide runs" : "Show runs"}
+        </button>
+        <button
+          type="button"
+          onClick={handleRun}
+          disabled={isRunning}
+          className="flex items-center gap-1.5 text-neutral-600 text-xs hover:text-neutral-900 disabled:opacity-50 dark:text-neutral-400 dark:hover:text-neutral-100"
+        >
+          <FiPlay size={12} />
+          {isRunning ? "Running..." : "Run"}
+        </button>
+      </div>
+      {isOpen && (
+        <ul className="mt-2 space-y-1">
+          {runs.length === 0 && (
+            <li className="text-neutral-500 text-xs">No runs yet.</li>
+          )}
+          {runs.map((run) => (
+            <li key={run.id}>
+              <div className="flex items-center justify-between gap-2 text-xs text-neutral-700 dark:text-neutral-300">
+                <button
+                  type="button"
+                  onClick={() =>
+                    setExpanded(expanded === run.id ? null : run.id)}
+                  className="truncate text-left"
+                >
+                  <span className="font-mono text-neutral-500">
+                    {run.model}
+                  </span>{" "}
+                  {run.created}
+                  <span className="ml-2 text-neutral-500">
+                    {run.latencyMs} ms
+                    {run.completionTokens !== null &&
+                      ` · ${run.promptTokens ?? "?"}/${run.completionTokens} tokens`}
+                  </span>
+                </button>
+                <button
+                  type="button"
+                  onClick={() => handleDelete(run)}
+                  className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+                >
+                  <FiTrash2 size={12} />
+                  Delete
+                </button>
+              </div>
+              {expanded === run.id && (
+                <div className="mt-1 whitespace-pre-wrap border border-panel-border px-2 py-1 font-mono text-neutral-700 text-xs dark:text-neutral-300">
+                  {run.response}
+                </div>
+              )}
+            </li>
+          ))}
+        </ul>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,203 @@
+import { useEffect, useState } from "react";
+import { FiCheckSquare, FiPlay, FiPlus, FiTrash2 } from "react-icons/fi";
+import { JsonValue, PromptTest } from "@/bindings.ts";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+interface PromptTestsProps {
+  promptId: string;
+}
+
+function statusLabel(test: PromptTest): string {
+  if (test.lastPassed === null) return "not run";
+  return test.lastPassed ? "passed" : "failed";
+}
+
+// Regression tests of a prompt: values to render it with and what the response must contain
+export function PromptTests({ promptId }: PromptTestsProps) {
+  const { pushToast } = useToast();
+  const [isOpen, setIsOpen] = useState(false);
+  const [isRunning, setIsRunning] = useState(false);
+  const [tests, setTests] = useState<PromptTest[]>([]);
+  const [name, setName] = useState("");
+  const [values, setValues] = useState("{}");
+  const [assertion, setAssertion] = useState("");
+  const [isRegex, setIsRegex] = useState(false);
+
+  const loadTests = () =>
+    promptManagerService.listPromptTests(promptId)
+      .then(setTests)
+      .catch((error) => {
+        console.error("Failed to load prompt tests", error);
+      });
+
+  useEffect(() => {
+    if (!isOpen) return;
+    loadTests();
+  }, [isOpen, promptId]);
+
+  const handleAdd = async () => {
+    try {
+      await promptManagerService.savePromptTest({
+        promptId,
+        name,
+        values: JSON.parse(values || "{}") as Record<string, JsonValue>,
+        assertion,
+        isRegex,
+      });
+      setName("");
+      setAssertion("");
+      await loadTests();
+    } catch (error) {
+      pushToast({
+        title: "Saving test failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  const handleRun = async () => {
+    setIsRunning(true);
+    try {
+      const results = await promptManagerService.runPromptTests(promptId);
+      const failed = results.filter((result) => !result.passed).length;
+      pushToast({
+        title: failed === 0
+          ? `All ${results.length} tests passed`
+          : `${failed} of ${results.length} tests failed`,
+        variant: failed === 0 ? "info" : "error",
+      });
+      setIsOpen(true);
+      await loadTests();
+    } catch (error) {
+      pushToast({
+        title: "Running tests failed",
+        description: String(error),
+        variant: "error",
+      });
+    } finally {
+      setIsRunning(false);
+    }
+  };
+
+  const handleDelete = async (test: PromptTest) => {
+    try {
+      await promptManagerService.deletePromptTest(test.id);
+      setTests((prev) => prev.filter((item) => item.id !== test.id));
+    } catch (error) {
+      pushToast({
+        title: "Delete failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  return (
+    <div className="border-t border-panel-border pt-3">
+      <div className="flex items-center gap-3">
+        <button
+          type="button"
+          onClick={() => setIsOpen(!isOpen)}
+          className="flex items-center gap-1.5 text-neutral-600 text-xs hover:text-neutral-900 dark:text-neutral-400 dark:hover:text-neutral-100"
+        >
+          <FiCheckSquare size={12} />
+          {isOpen ? "Hide tests" : "Show tests"}
+        </button>
+        <button
+          type="button"
+          onClick={handleRun}
+          disabled={isRunning}
+          className="flex items-center gap-1.5 text-neutral-600 text-xs hover:text-neutral-900 disabled:opacity-50 dark:text-neutral-400 dark:hover:text-neutral-100"
+        >
+          <FiPlay size={12} />
+          {isRunning ? "Running..." : "Run tests"}
+        </button>
+      </div>
+      {isOpen && (
+        <div className="mt-2 space-y-2">
+          <ul className="space-y-1">
+            {tests.length === 0 && (
+              <li className="text-neutral-500 text-xs">No tests yet.</li>
+            )}
+            {tests.map((test) => (
+              <li
+                key={test.id}
+                className="flex items-center justify-between gap-2 text-neutral-700 text-xs dark:text-neutral-300"
+              >
+                <span className="min-w-0 truncate">
+                  <span
+                    className={test.lastPassed === false
+                      ? "text-red-600 dark:text-red-400"
+                      : test.lastPassed
+                      ? "text-green-600 dark:text-green-400"
+                      : "text-neutral-500"}
+                  >
+                    {statusLabel(test)}
+                  </span>{" "}
+                  {test.name}
+                  <span className="ml-2 font-mono text-neutral-500">
+                    {test.isRegex ? `/${test.assertion}/` : test.assertion}
+                  </span>
+                </span>
+                <button
+                  type="button"
+                  onClick={() => handleDelete(test)}
+                  className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+                >
+                  <FiTrash2 size={12} />
+                  Delete
+                </button>
+              </li>
+            ))}
+          </ul>
+          <div className="space-y-1">
+            <div className="flex items-center gap-2">
+              <input
+                type="text"
+                value={name}
+                onChange={(event) => setName(event.target.value)}
+                placeholder="Test name"
+                className="w-40 border border-panel-border bg-panel px-2 py-1 text-neutral-700 text-xs dark:text-neutral-300"
+              />
+              <input
+                type="text"
+                value={assertion}
+                onChange={(event) => setAssertion(event.target.value)}
+                placeholder={isRegex ? "Regex to match" : "Text to contain"}
+                className="min-w-0 flex-1 border border-panel-border bg-panel px-2 py-1 font-mono text-neutral-700 text-xs dark:text-neutral-300"
+              />
+              <label className="flex shrink-0 items-center gap-1 text-neutral-600 text-xs dark:text-neutral-400">
+                <input
+                  type="checkbox"
+                  checked={isRegex}
+                  onChange={(event) => setIsRegex(event.target.checked)}
+                />
+                Regex
+              </label>
+            </div>
+            <div className="flex items-start gap-2">
+              <textarea
+                rows={2}
+                value={values}
+                onChange={(event) => setValues(event.target.value)}
+                placeholder='{"language": "rust"}'
+                className="min-w-0 flex-1 border border-panel-border bg-panel px-2 py-1 font-mono text-neutral-700 text-xs dark:text-neutral-300"
+              />
+              <button
+                type="button"
+                onClick={handleAdd}
+                disabled={!name.trim() || !assertion}
+                className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-400 dark:hover:bg-neutral-700"
+              >
+                <FiPlus size={12} />
+                Add test
+              </button>
+            </div>
+          </div>
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,116 @@
+import { useEffect, useState } from "react";
+import { FiPlus, FiX } from "react-icons/fi";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+// Values every prompt can use as {{global:name}}
+export function GlobalVariables() {
+  const { pushToast } = useToast();
+  const [globals, setGlobals] = useState<Record<string, string>>({});
+  const [name, setName] = useState("");
+  const [value, setValue] = useState("");
+
+  const loadGlobals = () =>
+    promptManagerService.getGlobalVariables()
+      .then(setGlobals)
+      .catch((error) => {
+        console.error("Failed to load global variables", error);
+      });
+
+  useEffect(() => {
+    loadGlobals();
+  }, []);
+
+  const handleSet = async (globalName: string, globalValue: string) => {
+    try {
+      await promptManagerService.setGlobalVariable(globalName, globalValue);
+      await loadGlobals();
+      return true;
+    } catch (error) {
+      pushToast({
+        title: "Saving global variable failed",
+        description: String(error),
+        variant: "error",
+      });
+      return false;
+    }
+  };
+
+  const handleAdd = async () => {
+    if (await handleSet(name.trim(), value)) {
+      setName("");
+      setValue("");
+    }
+  };
+
+  const handleDelete = async (globalName: string) => {
+    try {
+      await promptManagerService.deleteGlobalVariable(globalName);
+      await loadGlobals();
+    } catch (error) {
+      pushToast({
+        title: "Deleting global variable failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  const names = Object.keys(globals).sort();
+
+  return (
+    <div className="space-y-2">
+      <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+        Global Variables
+      </label>
+      {names.map((globalName) => (
+        <div key={globalName} className="flex items-center gap-2">
+          <span className="w-40 shrink-0 truncate font-mono text-neutral-700 text-xs dark:text-neutral-300">
+            {`{{global:${globalName}}}`}
+          </span>
+          <input
+            type="text"
+            defaultValue={globals[globalName]}
+            onBlur={(event) =>
+              event.target.value !== globals[globalName] &&
+              handleSet(globalName, event.target.value)}
+            className="min-w-0 flex-1 rounded-lg border border-panel-border bg-panel px-3 py-1 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+          />
+          <button
+            type="button"
+            onClick={() => handleDelete(globalName)}
+            title="Delete"
+            className="shrink-0 p-1 text-neutral-500 hover:bg-neutral-100 hover:text-neutral-900 dark:hover:bg-neutral-700 dark:hover:text-neutral-100"
+          >
+            <FiX size={14} />
+          </button>
+        </div>
+      ))}
+      <div className="flex items-center gap-2">
+        <input
+          type="text"
+          value={name}
+          onChange={(event) => setName(event.target.value)}
+          placeholder="author"
+          className="w-40 shrink-0 rounded-lg border border-panel-border bg-panel px-3 py-1 font-mono text-xs text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+        />
+        <input
+          type="text"
+          value={value}
+          onChange={(event) => setValue(event.target.value)}
+          placeholder="Value"
+          className="min-w-0 flex-1 rounded-lg border border-panel-border bg-panel px-3 py-1 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+        />
+        <button
+          type="button"
+          onClick={handleAdd}
+          disabled={!name.trim()}
+          title="Add"
+          className="shrink-0 p-1 text-neutral-500 hover:bg-neutral-100 hover:text-neutral-900 disabled:opacity-50 dark:hover:bg-neutral-700 dark:hover:text-neutral-100"
+        >
+          <FiPlus size={14} />
+        </button>
+      </div>
+    </div>
+  );
+}
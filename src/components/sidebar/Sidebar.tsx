@@ -6,7 +6,15 @@ import { useViewConfig } from "@/contexts/ViewConfigContext.tsx";
 import { useViewManagement } from "@/hooks/useViewManagement.ts";
 import { Link, useRouterState } from "@tanstack/react-router";
 import { useMemo, useState } from "react";
-import { FiGrid, FiTag, FiEye, FiTerminal } from "react-icons/fi";
+import {
+  FiAlertTriangle,
+  FiClock,
+  FiEye,
+  FiGrid,
+  FiShare2,
+  FiTag,
+  FiTerminal,
+} from "react-icons/fi";
 
 export function Sidebar() {
   const { isOpen } = useSidebar();
@@ -175,6 +183,27 @@ export function Sidebar() {
                     <FiGrid />
                     All Prompts
                   </Link>
+                  <Link
+                    to="/tag_graph"
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-2 text-neutral-700 hover:bg-neutral-100 dark:text-neutral-300 dark:hover:bg-neutral-800 [&.active]:bg-blue-50 [&.active]:text-blue-600 dark:[&.active]:bg-blue-900/20 dark:[&.active]:text-blue-400"
+                  >
+                    <FiShare2 />
+                    Tag Graph
+                  </Link>
+                  <Link
+                    to="/stale"
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-2 text-neutral-700 hover:bg-neutral-100 dark:text-neutral-300 dark:hover:bg-neutral-800 [&.active]:bg-blue-50 [&.active]:text-blue-600 dark:[&.active]:bg-blue-900/20 dark:[&.active]:text-blue-400"
+                  >
+                    <FiClock />
+                    Stale Prompts
+                  </Link>
+                  <Link
+                    to="/lint"
+                    className="flex w-full items-center gap-2 rounded-md px-2 py-2 text-neutral-700 hover:bg-neutral-100 dark:text-neutral-300 dark:hover:bg-neutral-800 [&.active]:bg-blue-50 [&.active]:text-blue-600 dark:[&.active]:bg-blue-900/20 dark:[&.active]:text-blue-400"
+                  >
+                    <FiAlertTriangle />
+                    Lint
+                  </Link>
                   <Link
                     to="/debug"
                     className="flex w-full items-center gap-2 rounded-md px-2 py-2 text-neutral-700 hover:bg-neutral-100 dark:text-neutral-300 dark:hover:bg-neutral-800 [&.active]:bg-blue-50 [&.active]:text-blue-600 dark:[&.active]:bg-blue-900/20 dark:[&.active]:text-blue-400"
@@ -7,8 +7,9 @@ import { formatViewConfig } from "@/utils/viewUtils.ts";
 import { useViewConfig } from "@/contexts/ViewConfigContext.tsx";
 
 export function SidebarViews() {
-  const { views, addView, removeView } = usePromptManager();
+  const { views, addView, removeView, reorderViews } = usePromptManager();
   const [deletingViewId, setDeletingViewId] = useState<string | null>(null);
+  const [draggingViewId, setDraggingViewId] = useState<string | null>(null);
   const navigate = useNavigate();
   const location = useRouterState({ select: (state) => state.location });
   const { systemConfig } = useViewConfig();
@@ -69,6 +70,21 @@ export function SidebarViews() {
     setDeletingViewId(null);
   };
 
+  // Move the dragged view to the position of the view it was dropped on
+  const handleDrop = async (e: React.DragEvent, targetId: string) => {
+    e.preventDefault();
+    const draggedId = draggingViewId;
+    setDraggingViewId(null);
+    if (!draggedId || draggedId === targetId) return;
+
+    const ids = views.map((view) => view.id);
+    const movingDown = ids.indexOf(draggedId) < ids.indexOf(targetId);
+    const reordered = ids.filter((id) => id !== draggedId);
+    const targetIndex = reordered.indexOf(targetId);
+    reordered.splice(movingDown ? targetIndex + 1 : targetIndex, 0, draggedId);
+    await reorderViews(reordered);
+  };
+
   return (
     <div className="mb-6">
       <Link
@@ -96,52 +112,61 @@ export function SidebarViews() {
             key={view.id}
             to="/main_view"
             search={{ viewId: view.id }}
-            className="group flex w-full items-center justify-between rounded-md px-2 py-2 text-neutral-700 hover:bg-neutral-100 dark:text-neutral-300 dark:hover:bg-neutral-800 [&.active]:bg-blue-50 [&.active]:text-blue-600 dark:[&.active]:bg-blue-900/20 dark:[&.active]:text-blue-400"
+            draggable
+            onDragStart={() => setDraggingViewId(view.id)}
+            onDragEnd={() => setDraggingViewId(null)}
+            onDragOver={(e) => e.preventDefault()}
+            onDrop={(e) => handleDrop(e, view.id)}
+            className={`${
+              draggingViewId === view.id ? "opacity-50 " : ""
+            }group flex w-full items-center justify-between rounded-md px-2 py-2 text-neutral-700 hover:bg-neutral-100 dark:text-neutral-300 dark:hover:bg-neutral-800 [&.active]:bg-blue-50 [&.active]:text-blue-600 dark:[&.active]:bg-blue-900/20 dark:[&.active]:text-blue-400`}
           >
             <span className="truncate">
               {view.name || formatViewConfig(view.config)}
             </span>
 
-            {/* Delete button or Confirmation */}
-            <div
-              className={`transition-opacity ${
-                deletingViewId === view.id
-                  ? "opacity-100"
-                  : "opacity-0 group-hover:opacity-100"
-              }`}
-            >
-              {deletingViewId === view.id
-                ? (
-                  <div className="flex items-center gap-1">
-                    <button
-                      type="button"
-                      onClick={(e) => handleConfirmDelete(e, view.id)}
-                      className="rounded p-1 text-green-600 hover:bg-green-100 dark:text-green-400 dark:hover:bg-green-900/30"
-                      title="Confirm deletion"
-                    >
-                      <FiCheck size={12} />
-                    </button>
+            {/* Delete button or Confirmation (system views cannot be deleted) */}
+            {view.type === "custom" && (
+              <div
+                className={`transition-opacity ${
+                  deletingViewId === view.id
+                    ? "opacity-100"
+                    : "opacity-0 group-hover:opacity-100"
+                }`}
+              >
+                {deletingViewId === view.id
+                  ? (
+                    <div className="flex items-center gap-1">
+                      <button
+                        type="button"
+                        onClick={(e) => handleConfirmDelete(e, view.id)}
+                        className="rounded p-1 text-green-600 hover:bg-green-100 dark:text-green-400 dark:hover:bg-green-900/30"
+                        title="Confirm deletion"
+                      >
+                        <FiCheck size={12} />
+                      </button>
+                      <button
+                        type="button"
+                        onClick={(e) => handleCancelDelete(e)}
+                        className="rounded p-1 text-red-600 hover:bg-red-100 dark:text-red-400 dark:hover:bg-red-900/30"
+                        title="Cancel"
+                      >
+                        <FiX size={12} />
+                      </button>
+                    </div>
+                  )
+                  : (
                     <button
                       type="button"
-                      onClick={(e) => handleCancelDelete(e)}
-                      className="rounded p-1 text-red-600 hover:bg-red-100 dark:text-red-400 dark:hover:bg-red-900/30"
-                      title="Cancel"
+                      onClick={(e) => handleTrashClick(e, view.id)}
+                      className="rounded p-1 text-neutral-400 hover:bg-neutral-200 hover:text-red-500 dark:hover:bg-neutral-700"
+                      title="Delete view"
                     >
-                      <FiX size={12} />
+                      <FiTrash2 size={12} />
                     </button>
-                  </div>
-                )
-                : (
-                  <button
-                    type="button"
-                    onClick={(e) => handleTrashClick(e, view.id)}
-                    className="rounded p-1 text-neutral-400 hover:bg-neutral-200 hover:text-red-500 dark:hover:bg-neutral-700"
-                    title="Delete view"
-                  >
-                    <FiTrash2 size={12} />
-                  </button>
-                )}
-            </div>
+                  )}
+              </div>
+            )}
           </Link>
         ))}
       </div>
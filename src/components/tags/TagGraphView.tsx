@@ -0,0 +1,138 @@
+import { useMemo, useState } from "react";
+import {
+  forceCenter,
+  forceCollide,
+  forceLink,
+  forceManyBody,
+  forceSimulation,
+  type SimulationLinkDatum,
+  type SimulationNodeDatum,
+} from "d3";
+import { TagGraph } from "@/bindings.ts";
+
+const WIDTH = 800;
+const HEIGHT = 560;
+/** Ticks run up front so the graph renders already settled */
+const LAYOUT_TICKS = 300;
+
+interface LayoutNode extends SimulationNodeDatum {
+  name: string;
+  promptCount: number;
+  radius: number;
+}
+
+interface LayoutLink extends SimulationLinkDatum<LayoutNode> {
+  weight: number;
+  similarity: number;
+}
+
+interface TagGraphViewProps {
+  graph: TagGraph;
+}
+
+// Force-directed tag co-occurrence graph; hovering a tag highlights its neighbours
+export function TagGraphView({ graph }: TagGraphViewProps) {
+  const [hovered, setHovered] = useState<string | null>(null);
+
+  const { nodes, links } = useMemo(() => {
+    const maxCount = Math.max(1, ...graph.nodes.map((n) => n.promptCount));
+    const nodes: LayoutNode[] = graph.nodes.map((node) => ({
+      name: node.name,
+      promptCount: node.promptCount,
+      radius: 4 + 12 * Math.sqrt(node.promptCount / maxCount),
+    }));
+    const links: LayoutLink[] = graph.edges.map((edge) => ({
+      source: edge.source,
+      target: edge.target,
+      weight: edge.weight,
+      similarity: edge.similarity,
+    }));
+    forceSimulation(nodes)
+      .force(
+        "link",
+        forceLink<LayoutNode, LayoutLink>(links)
+          .id((node) => node.name)
+          .distance((link) => 120 - 80 * link.similarity),
+      )
+      .force("charge", forceManyBody().strength(-120))
+      .force("center", forceCenter(WIDTH / 2, HEIGHT / 2))
+      .force(
+        "collide",
+        forceCollide<LayoutNode>().radius((node) => node.radius + 2),
+      )
+      .stop()
+      .tick(LAYOUT_TICKS);
+    return { nodes, links };
+  }, [graph]);
+
+  const neighbours = useMemo(() => {
+    if (!hovered) return null;
+    const names = new Set([hovered]);
+    for (const edge of graph.edges) {
+      if (edge.source === hovered) names.add(edge.target);
+      if (edge.target === hovered) names.add(edge.source);
+    }
+    return names;
+  }, [graph, hovered]);
+
+  if (nodes.length === 0) {
+    return <div className="text-neutral-500 text-sm">No tags yet.</div>;
+  }
+
+  const endpoint = (end: LayoutLink["source"]) => end as LayoutNode;
+
+  return (
+    <svg
+      viewBox={`0 0 ${WIDTH} ${HEIGHT}`}
+      className="h-auto w-full rounded-2xl border border-panel-border bg-panel"
+    >
+      <g className="stroke-neutral-400 dark:stroke-neutral-600">
+        {links.map((link) => {
+          const source = endpoint(link.source);
+          const target = endpoint(link.target);
+          const isActive = !hovered || source.name === hovered ||
+            target.name === hovered;
+          return (
+            <line
+              key={`${source.name}\u0000${target.name}`}
+              x1={source.x}
+              y1={source.y}
+              x2={target.x}
+              y2={target.y}
+              strokeWidth={Math.min(6, link.weight)}
+              strokeOpacity={isActive ? 0.3 + 0.7 * link.similarity : 0.05}
+            >
+              <title>
+                {`${source.name} + ${target.name}: ${link.weight} prompts (${
+                  Math.round(link.similarity * 100)
+                }% overlap)`}
+              </title>
+            </line>
+          );
+        })}
+      </g>
+      {nodes.map((node) => {
+        const isActive = !neighbours || neighbours.has(node.name);
+        return (
+          <g
+            key={node.name}
+            transform={`translate(${node.x},${node.y})`}
+            opacity={isActive ? 1 : 0.2}
+            onMouseEnter={() => setHovered(node.name)}
+            onMouseLeave={() => setHovered(null)}
+          >
+            <circle r={node.radius} className="fill-blue-500" />
+            <text
+              x={node.radius + 3}
+              y={4}
+              className="fill-neutral-700 text-[11px] dark:fill-neutral-300"
+            >
+              {node.name}
+            </text>
+            <title>{`${node.name}: ${node.promptCount} prompts`}</title>
+          </g>
+        );
+      })}
+    </svg>
+  );
+}
@@ -0,0 +1,67 @@
+import { useEffect, useState } from "react";
+import { FiInbox, FiX } from "react-icons/fi";
+import { events } from "@/bindings.ts";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+// Offers to save prompt-like text copied to the clipboard while the watcher is on
+export function CaptureSuggestion() {
+  const { pushToast } = useToast();
+  const [text, setText] = useState<string | null>(null);
+  const [isSaving, setIsSaving] = useState(false);
+
+  useEffect(() => {
+    const listener = events.captureSuggested.listen(({ payload }) => {
+      setText(payload.text);
+    });
+    return () => {
+      listener.then((stop) => stop());
+    };
+  }, []);
+
+  if (text === null) return null;
+
+  const handleSave = async () => {
+    setIsSaving(true);
+    try {
+      const prompt = await promptManagerService.quickCapture(text);
+      pushToast({ title: "Prompt captured", description: prompt.id });
+      setText(null);
+    } catch (error) {
+      pushToast({
+        title: "Capture failed",
+        description: String(error),
+        variant: "error",
+      });
+    } finally {
+      setIsSaving(false);
+    }
+  };
+
+  return (
+    <div className="fixed bottom-4 left-4 z-[200] w-80 rounded-lg border border-panel-border bg-panel px-4 py-3 text-neutral-900 shadow-lg dark:text-neutral-200">
+      <div className="flex items-start justify-between gap-3">
+        <span className="text-sm font-semibold">Save copied prompt?</span>
+        <button
+          type="button"
+          onClick={() => setText(null)}
+          className="rounded p-1 text-neutral-400 hover:bg-neutral-100 hover:text-neutral-700 dark:hover:bg-neutral-800 dark:hover:text-neutral-200"
+        >
+          <FiX size={14} />
+        </button>
+      </div>
+      <p className="mt-1 line-clamp-3 whitespace-pre-wrap font-mono text-xs text-neutral-600 dark:text-neutral-400">
+        {text}
+      </p>
+      <button
+        type="button"
+        onClick={handleSave}
+        disabled={isSaving}
+        className="mt-2 flex items-center gap-1.5 rounded bg-blue-600 px-2 py-1 text-xs text-white hover:bg-blue-500 disabled:opacity-50"
+      >
+        <FiInbox size={12} />
+        {isSaving ? "Saving..." : "Save as prompt"}
+      </button>
+    </div>
+  );
+}
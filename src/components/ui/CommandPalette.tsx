@@ -4,12 +4,105 @@ import { useTheme } from "@/contexts/ThemeContext.tsx";
 import { useSidebar } from "@/contexts/SidebarContext.tsx";
 import { LuMoon, LuSun } from "react-icons/lu";
 import { Dialog } from "radix-ui";
+import { Prompt } from "@/schemas/schemas.ts";
+import {
+  FuzzyPromptMatch,
+  promptManagerService,
+} from "@/services/PromptManagerService.ts";
+import { UndoStatus } from "@/bindings.ts";
+import {
+  HighlightedText,
+  indicesToRanges,
+} from "@/components/ui/HighlightedText.tsx";
 import "./CommandPalette.css";
+
+const RECENT_LIMIT = 5;
+const FUZZY_LIMIT = 10;
+
+function fileStem(prompt: Prompt): string {
+  const name = (prompt.filePath ?? prompt.id).split(/[\\/]/).pop() ?? "";
+  return name.replace(/\.md$/i, "");
+}
+
+function promptLabel(prompt: Prompt): string {
+  return prompt.title?.trim() || prompt.text.split("\n")[0].trim() ||
+    prompt.id;
+}
 
 export default function CommandPaletteComponent() {
   const [open, setOpen] = useState(false);
   const { theme, toggleTheme } = useTheme();
   const { toggle: toggleSidebar } = useSidebar();
+  const [recentlyUsed, setRecentlyUsed] = useState<Prompt[]>([]);
+  const [recentlyEdited, setRecentlyEdited] = useState<Prompt[]>([]);
+  const [search, setSearch] = useState("");
+  const [matches, setMatches] = useState<FuzzyPromptMatch[]>([]);
+  const [undoStatus, setUndoStatus] = useState<UndoStatus>({
+    undo: null,
+    redo: null,
+  });
+
+  useEffect(() => {
+    if (!open || !search.trim()) {
+      setMatches([]);
+      return;
+    }
+    let cancelled = false;
+    promptManagerService.fuzzySearchPrompts(search, FUZZY_LIMIT)
+      .then((found) => {
+        if (!cancelled) setMatches(found);
+      })
+      .catch((error) => console.error("Fuzzy search failed", error));
+    return () => {
+      cancelled = true;
+    };
+  }, [open, search]);
+
+  useEffect(() => {
+    if (!open) return;
+    Promise.all([
+      promptManagerService.getRecentPrompts("used", RECENT_LIMIT),
+      promptManagerService.getRecentPrompts("edited", RECENT_LIMIT),
+    ])
+      .then(([used, edited]) => {
+        setRecentlyUsed(used);
+        setRecentlyEdited(edited);
+      })
+      .catch((error) => console.error("Failed to load recent prompts", error));
+    promptManagerService.getUndoStatus()
+      .then(setUndoStatus)
+      .catch((error) => console.error("Failed to load undo status", error));
+  }, [open]);
+
+  const undoLast = () => {
+    setOpen(false);
+    promptManagerService.undoLastOperation()
+      .catch((error) => {
+        console.error("Undo failed", error);
+        alert("Undo failed: " + error);
+      });
+  };
+
+  const redoLast = () => {
+    setOpen(false);
+    promptManagerService.redo()
+      .catch((error) => {
+        console.error("Redo failed", error);
+        alert("Redo failed: " + error);
+      });
+  };
+
+  const captureClipboard = () => {
+    setOpen(false);
+    promptManagerService.quickCapture()
+      .catch((error) => console.error("Capture failed", error));
+  };
+
+  const copyPrompt = (prompt: Prompt) => {
+    setOpen(false);
+    promptManagerService.copyPromptToClipboard(prompt.id)
+      .catch((error) => console.error("Copy failed", error));
+  };
 
   // Handle Ctrl+K
   useEffect(() => {
@@ -31,10 +124,93 @@ export default function CommandPaletteComponent() {
       className="command-palette"
     >
       <Dialog.Title className="sr-only">Command Palette</Dialog.Title>
-      <Command.Input placeholder="Type a command or search..." autoFocus />
+      <Command.Input
+        placeholder="Type a command or search..."
+        value={search}
+        onValueChange={setSearch}
+        autoFocus
+      />
       <Command.List>
         <Command.Empty>No results found.</Command.Empty>
 
+        {/* Already ranked by the backend; the search keyword keeps cmdk from hiding them */}
+        {matches.length > 0 && (
+          <Command.Group heading="Prompts">
+            {matches.map((match) => (
+              <Command.Item
+                key={match.prompt.id}
+                value={`prompt ${match.prompt.id}`}
+                keywords={[search]}
+                onSelect={() => copyPrompt(match.prompt)}
+              >
+                <span className="truncate">
+                  {match.prompt.title?.trim()
+                    ? (
+                      <HighlightedText
+                        text={match.prompt.title}
+                        ranges={indicesToRanges(match.titleIndices)}
+                      />
+                    )
+                    : promptLabel(match.prompt)}
+                </span>
+                <span className="ml-2 truncate text-neutral-500 text-xs">
+                  <HighlightedText
+                    text={fileStem(match.prompt)}
+                    ranges={indicesToRanges(match.fileNameIndices)}
+                  />
+                </span>
+              </Command.Item>
+            ))}
+          </Command.Group>
+        )}
+
+        {recentlyUsed.length > 0 && (
+          <Command.Group heading="Recently Used">
+            {recentlyUsed.map((prompt) => (
+              <Command.Item
+                key={prompt.id}
+                value={`used ${prompt.id} ${promptLabel(prompt)}`}
+                onSelect={() => copyPrompt(prompt)}
+              >
+                <span className="truncate">{promptLabel(prompt)}</span>
+              </Command.Item>
+            ))}
+          </Command.Group>
+        )}
+        {recentlyEdited.length > 0 && (
+          <Command.Group heading="Recently Edited">
+            {recentlyEdited.map((prompt) => (
+              <Command.Item
+                key={prompt.id}
+                value={`edited ${prompt.id} ${promptLabel(prompt)}`}
+                onSelect={() => copyPrompt(prompt)}
+              >
+                <span className="truncate">{promptLabel(prompt)}</span>
+              </Command.Item>
+            ))}
+          </Command.Group>
+        )}
+
+        {(undoStatus.undo || undoStatus.redo) && (
+          <Command.Group heading="Edit">
+            {undoStatus.undo && (
+              <Command.Item onSelect={undoLast}>
+                <span className="truncate">Undo {undoStatus.undo}</span>
+              </Command.Item>
+            )}
+            {undoStatus.redo && (
+              <Command.Item onSelect={redoLast}>
+                <span className="truncate">Redo {undoStatus.redo}</span>
+              </Command.Item>
+            )}
+          </Command.Group>
+        )}
+
+        <Command.Group heading="Capture">
+          <Command.Item onSelect={captureClipboard}>
+            <span>Capture Clipboard as Prompt</span>
+          </Command.Item>
+        </Command.Group>
         <Command.Group heading="Appearance">
           <Command.Item
             onSelect={toggleTheme}
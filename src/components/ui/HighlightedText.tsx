@@ -0,0 +1,35 @@
+import { MatchRange } from "@/bindings.ts";
+
+interface HighlightedTextProps {
+  text: string;
+  /** Char (code point) ranges to emphasize, sorted and not overlapping */
+  ranges: MatchRange[];
+}
+
+// Text with search hits emphasized
+export function HighlightedText({ text, ranges }: HighlightedTextProps) {
+  if (ranges.length === 0) return <>{text}</>;
+
+  const chars = Array.from(text);
+  const parts: React.ReactNode[] = [];
+  let last = 0;
+  for (const range of ranges) {
+    if (range.start > last) parts.push(chars.slice(last, range.start).join(""));
+    parts.push(
+      <mark
+        key={range.start}
+        className="bg-transparent font-semibold text-blue-600 dark:text-blue-400"
+      >
+        {chars.slice(range.start, range.end).join("")}
+      </mark>,
+    );
+    last = range.end;
+  }
+  if (last < chars.length) parts.push(chars.slice(last).join(""));
+  return <>{parts}</>;
+}
+
+/** Ranges covering single matched characters, e.g. fuzzy match indices */
+export function indicesToRanges(indices: number[]): MatchRange[] {
+  return indices.map((index) => ({ start: index, end: index + 1 }));
+}
@@ -1,6 +1,93 @@
-import { ViewConfig } from "@/schemas/schemas.ts";
-import { FiArrowDown, FiArrowUp, FiFilter, FiSearch } from "react-icons/fi";
+import { save } from "@tauri-apps/plugin-dialog";
+import {
+  PromptStatus,
+  SearchConfig,
+  SearchField,
+  ViewConfig,
+} from "@/schemas/schemas.ts";
+import { ExportTool } from "@/bindings.ts";
+import {
+  FiArchive,
+  FiArrowDown,
+  FiArrowUp,
+  FiColumns,
+  FiDownload,
+  FiFilter,
+  FiGrid,
+  FiLayers,
+  FiList,
+  FiSearch,
+} from "react-icons/fi";
 import { TagInput } from "@/components/tags/TagInput.tsx";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+
+type SortKey = NonNullable<ViewConfig["sort"]>["by"];
+
+const SORT_OPTIONS: { by: SortKey; label: string; title: string }[] = [
+  { by: "created", label: "Date", title: "Sort by Date" },
+  { by: "updated", label: "Updated", title: "Sort by Last Update" },
+  { by: "title", label: "Title", title: "Sort by Title" },
+  { by: "usage_count", label: "Usage", title: "Sort by Times Copied" },
+  { by: "last_used", label: "Used", title: "Sort by Last Copied" },
+  { by: "word_count", label: "Length", title: "Sort by Word Count" },
+];
+
+const TRIAGE_FILTERS: {
+  key: "untagged" | "missingTitle";
+  label: string;
+  title: string;
+}[] = [
+  { key: "untagged", label: "Untagged", title: "Only prompts without tags" },
+  {
+    key: "missingTitle",
+    label: "No title",
+    title: "Only prompts without a title",
+  },
+];
+
+const STATUS_OPTIONS: { status: PromptStatus; label: string }[] = [
+  { status: "draft", label: "Draft" },
+  { status: "testing", label: "Testing" },
+  { status: "production", label: "Production" },
+  { status: "deprecated", label: "Deprecated" },
+];
+
+// Where plain search words are looked for; the first option is the default
+const SEARCH_FIELD_OPTIONS: {
+  value: string;
+  label: string;
+  fields: SearchField[];
+}[] = [
+  { value: "", label: "Title & text", fields: [] },
+  { value: "title", label: "Title", fields: ["title"] },
+  { value: "description", label: "Description", fields: ["description"] },
+  { value: "text", label: "Text", fields: ["text"] },
+  {
+    value: "all",
+    label: "Everywhere",
+    fields: ["title", "description", "text"],
+  },
+];
+
+const EXPORT_TOOLS: {
+  tool: ExportTool;
+  label: string;
+  defaultPath: string;
+  extension: string;
+}[] = [
+  {
+    tool: "promptfoo",
+    label: "promptfoo",
+    defaultPath: "promptfooconfig.yaml",
+    extension: "yaml",
+  },
+  {
+    tool: "langchain",
+    label: "LangChain",
+    defaultPath: "prompts.langchain.json",
+    extension: "json",
+  },
+];
 
 interface ViewControlsProps {
   config: ViewConfig;
@@ -15,13 +102,19 @@ export function ViewControls({
   allTags,
   onNewPrompt,
 }: ViewControlsProps) {
-  const handleSearchChange = (val: string) => {
+  const search: SearchConfig = config.filter?.search ?? { query: "" };
+
+  const handleSearchChange = (changes: Partial<SearchConfig>) => {
     onChange({
       ...config,
-      filter: { ...config.filter, search: val },
+      filter: { ...config.filter, search: { ...search, ...changes } },
     });
   };
 
+  const searchFieldsValue = SEARCH_FIELD_OPTIONS.find((option) =>
+    option.fields.join() === (search.fields ?? []).join()
+  )?.value ?? "";
+
   const handleTagsChange = (tags: string[]) => {
     onChange({
       ...config,
@@ -29,7 +122,106 @@ export function ViewControls({
     });
   };
 
-  const handleSortChange = (by: "created") => {
+  // Prompts need all filter tags unless switched to any of them
+  const handleTagModeToggle = () => {
+    onChange({
+      ...config,
+      filter: {
+        ...config.filter,
+        tagMode: config.filter?.tagMode === "or" ? undefined : "or",
+      },
+    });
+  };
+
+  // Archived prompts are hidden unless the filter asks for them
+  const handleArchivedToggle = () => {
+    onChange({
+      ...config,
+      filter: { ...config.filter, archived: !config.filter?.archived },
+    });
+  };
+
+  // Layout changes are stored on the view, starting from the card layout
+  const layout = config.layout ?? { mode: "card", groupByTag: false };
+
+  const handleLayoutModeToggle = () => {
+    onChange({
+      ...config,
+      layout: { ...layout, mode: layout.mode === "card" ? "list" : "card" },
+    });
+  };
+
+  // The board shows columns by status; leaving it goes back to cards
+  const handleBoardToggle = () => {
+    onChange({
+      ...config,
+      layout: { ...layout, mode: layout.mode === "board" ? "card" : "board" },
+    });
+  };
+
+  const handleStatusChange = (status: string) => {
+    onChange({
+      ...config,
+      filter: {
+        ...config.filter,
+        status: (status || undefined) as PromptStatus | undefined,
+      },
+    });
+  };
+
+  const handleGroupByTagToggle = () => {
+    onChange({
+      ...config,
+      layout: { ...layout, groupByTag: !layout.groupByTag },
+    });
+  };
+
+  // Dates are sent as `YYYY-MM-DD`; an empty input clears the bound
+  const handleDateChange = (
+    key: "createdAfter" | "createdBefore",
+    value: string,
+  ) => {
+    onChange({
+      ...config,
+      filter: { ...config.filter, [key]: value || undefined },
+    });
+  };
+
+  // Free-text filters; an empty input clears them
+  const handleTextFilterChange = (
+    key: "modelTarget" | "language",
+    value: string,
+  ) => {
+    onChange({
+      ...config,
+      filter: { ...config.filter, [key]: value || undefined },
+    });
+  };
+
+  // Word count bounds; an empty or invalid input clears them
+  const handleWordBoundChange = (
+    key: "minWords" | "maxWords",
+    value: string,
+  ) => {
+    const words = Number.parseInt(value, 10);
+    onChange({
+      ...config,
+      filter: {
+        ...config.filter,
+        [key]: Number.isNaN(words) || words < 0 ? undefined : words,
+      },
+    });
+  };
+
+  // "Needs triage" filters for prompts without tags or a title
+  const handleTriageToggle = (key: "untagged" | "missingTitle") => {
+    onChange({
+      ...config,
+      filter: { ...config.filter, [key]: !config.filter?.[key] },
+    });
+  };
+
+  const handleSortChange = (by: SortKey) => {
     // Toggle order if clicking same sort field
     const currentOrder = config.sort?.order || "desc";
     // Safe check for config.sort
@@ -38,10 +230,67 @@ export function ViewControls({
 
     onChange({
       ...config,
-      sort: { by, order: newOrder },
+      // Newest first among prompts that tie on the chosen key
+      sort: {
+        by,
+        order: newOrder,
+        then: by === "created" ? [] : [{ by: "created", order: "desc" }],
+      },
     });
   };
 
+  // Export the prompts matching the current filter; format follows the extension
+  const handleExport = async () => {
+    const path = await save({
+      title: "Export Prompts",
+      defaultPath: "prompts.json",
+      filters: [
+        { name: "JSON", extensions: ["json"] },
+        { name: "CSV", extensions: ["csv"] },
+        { name: "YAML", extensions: ["yaml", "yml"] },
+      ],
+    });
+    if (!path) return;
+
+    const extension = path.split(".").pop()?.toLowerCase();
+    const format = extension === "csv"
+      ? "csv"
+      : extension === "yaml" || extension === "yml"
+      ? "yaml"
+      : "json";
+    try {
+      await promptManagerService.exportPrompts(
+        format,
+        config.filter ?? null,
+        path,
+      );
+    } catch (e) {
+      console.error("Export failed", e);
+    }
+  };
+
+  // Export the prompts matching the current filter in another tool's format
+  const handleExportFor = async (tool: ExportTool) => {
+    const target = EXPORT_TOOLS.find((item) => item.tool === tool);
+    if (!target) return;
+    const path = await save({
+      title: `Export Prompts for ${target.label}`,
+      defaultPath: target.defaultPath,
+      filters: [{ name: target.label, extensions: [target.extension] }],
+    });
+    if (!path) return;
+
+    try {
+      await promptManagerService.exportPromptsFor(
+        tool,
+        config.filter ?? null,
+        path,
+      );
+    } catch (e) {
+      console.error("Export failed", e);
+    }
+  };
+
   return (
     <div className="flex flex-col gap-2 border-b border-panel-border bg-panel-2 px-3 py-2">
       {/* Top Row: Search and some actions */}
@@ -51,28 +300,141 @@ export function ViewControls({
           <input
             type="text"
             placeholder="Search prompts..."
-            value={config.filter?.search || ""}
-            onChange={(e) => handleSearchChange(e.target.value)}
+            title='Words, tag:name, title:"phrase" and /regex/i; prefix with - to exclude'
+            value={search.query}
+            onChange={(e) => handleSearchChange({ query: e.target.value })}
             className="w-full rounded-md border border-panel-border bg-panel px-9 py-1.5 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:bg-neutral-800 dark:text-neutral-100 dark:focus:border-blue-400"
           />
         </div>
+        {/* Search options */}
+        <div className="flex gap-1 rounded-md border border-panel-border bg-panel p-1 dark:bg-neutral-800">
+          {(
+            [
+              ["caseSensitive", "Aa", "Match case"],
+              ["wholeWord", "ab", "Match whole words only"],
+            ] as const
+          ).map(([key, label, title]) => (
+            <button
+              key={key}
+              type="button"
+              onClick={() => handleSearchChange({ [key]: !search[key] })}
+              className={`rounded px-2 py-1 text-xs font-medium ${
+                search[key]
+                  ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
+                  : "text-neutral-500 hover:bg-neutral-100 dark:hover:bg-neutral-700"
+              } ${key === "wholeWord" ? "underline" : ""}`}
+              title={title}
+            >
+              {label}
+            </button>
+          ))}
+          <select
+            value={searchFieldsValue}
+            onChange={(e) =>
+              handleSearchChange({
+                fields: SEARCH_FIELD_OPTIONS.find((option) =>
+                  option.value === e.target.value
+                )?.fields ?? [],
+              })}
+            className="rounded bg-transparent px-1 text-xs text-neutral-500"
+            title="Where to look for search words"
+          >
+            {SEARCH_FIELD_OPTIONS.map((option) => (
+              <option key={option.value} value={option.value}>
+                {option.label}
+              </option>
+            ))}
+          </select>
+        </div>
         {/* Sort Controls */}
         <div className="flex gap-1 rounded-md border border-panel-border bg-panel p-1 dark:bg-neutral-800">
-          <button
-            onClick={() => handleSortChange("created")}
-            className={`flex items-center gap-1 rounded px-2 py-1 text-xs font-medium transition-colors ${
-              config.sort?.by === "created"
-                ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
-                : "text-neutral-500 hover:bg-neutral-100 dark:hover:bg-neutral-700"
-            }`}
-            title="Sort by Date"
-          >
-            Date
-            {config.sort?.by === "created" && (
-              config.sort.order === "asc" ? <FiArrowUp /> : <FiArrowDown />
-            )}
-          </button>
+          {SORT_OPTIONS.map((option) => (
+            <button
+              key={option.by}
+              onClick={() => handleSortChange(option.by)}
+              className={`flex items-center gap-1 rounded px-2 py-1 text-xs font-medium transition-colors ${
+                config.sort?.by === option.by
+                  ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
+                  : "text-neutral-500 hover:bg-neutral-100 dark:hover:bg-neutral-700"
+              }`}
+              title={option.title}
+            >
+              {option.label}
+              {config.sort?.by === option.by && (
+                config.sort.order === "asc" ? <FiArrowUp /> : <FiArrowDown />
+              )}
+            </button>
+          ))}
         </div>
+        <button
+          type="button"
+          onClick={handleArchivedToggle}
+          className={`rounded-md border border-panel-border p-2 ${
+            config.filter?.archived
+              ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
+              : "bg-panel text-neutral-500 hover:bg-neutral-100 dark:bg-neutral-800 dark:hover:bg-neutral-700"
+          }`}
+          title={config.filter?.archived
+            ? "Showing archived prompts"
+            : "Show archived prompts"}
+        >
+          <FiArchive />
+        </button>
+        <button
+          type="button"
+          onClick={handleLayoutModeToggle}
+          className="rounded-md border border-panel-border bg-panel p-2 text-neutral-500 hover:bg-neutral-100 dark:bg-neutral-800 dark:hover:bg-neutral-700"
+          title={layout.mode === "card" ? "Show as list" : "Show as cards"}
+        >
+          {layout.mode === "card" ? <FiList /> : <FiGrid />}
+        </button>
+        <button
+          type="button"
+          onClick={handleBoardToggle}
+          className={`rounded-md border border-panel-border p-2 ${
+            layout.mode === "board"
+              ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
+              : "bg-panel text-neutral-500 hover:bg-neutral-100 dark:bg-neutral-800 dark:hover:bg-neutral-700"
+          }`}
+          title={layout.mode === "board"
+            ? "Showing a board by status"
+            : "Show as a board by status"}
+        >
+          <FiColumns />
+        </button>
+        <button
+          type="button"
+          onClick={handleGroupByTagToggle}
+          className={`rounded-md border border-panel-border p-2 ${
+            layout.groupByTag
+              ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
+              : "bg-panel text-neutral-500 hover:bg-neutral-100 dark:bg-neutral-800 dark:hover:bg-neutral-700"
+          }`}
+          title={layout.groupByTag ? "Grouped by tag" : "Group by tag"}
+        >
+          <FiLayers />
+        </button>
+        <button
+          type="button"
+          onClick={handleExport}
+          className="rounded-md border border-panel-border bg-panel p-2 text-neutral-500 hover:bg-neutral-100 dark:bg-neutral-800 dark:hover:bg-neutral-700"
+          title="Export prompts in this view"
+        >
+          <FiDownload />
+        </button>
+        <select
+          value=""
+          onChange={(e) => handleExportFor(e.target.value as ExportTool)}
+          className="rounded-md border border-panel-border bg-panel px-1 py-1.5 text-neutral-500 text-xs dark:bg-neutral-800"
+          title="Export prompts in this view for another tool"
+        >
+          <option value="">Export for...</option>
+          {EXPORT_TOOLS.map((item) => (
+            <option key={item.tool} value={item.tool}>
+              {item.label}
+            </option>
+          ))}
+        </select>
         <button
           type="button"
           onClick={onNewPrompt}
@@ -97,6 +459,93 @@ export function ViewControls({
             enableNegativeTags={true}
           />
         </div>
+        <button
+          type="button"
+          onClick={handleTagModeToggle}
+          className="rounded-md border border-panel-border bg-panel px-2 py-1 text-xs font-medium text-neutral-700 hover:bg-neutral-100 dark:bg-neutral-800 dark:text-neutral-300 dark:hover:bg-neutral-700"
+          title={config.filter?.tagMode === "or"
+            ? "Showing prompts with any of the tags"
+            : "Showing prompts with all of the tags"}
+        >
+          {config.filter?.tagMode === "or" ? "Any" : "All"}
+        </button>
+        <input
+          type="date"
+          value={config.filter?.createdAfter ?? ""}
+          onChange={(e) => handleDateChange("createdAfter", e.target.value)}
+          className="rounded-md border border-panel-border bg-panel px-2 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Created on or after"
+        />
+        <input
+          type="date"
+          value={config.filter?.createdBefore ?? ""}
+          onChange={(e) => handleDateChange("createdBefore", e.target.value)}
+          className="rounded-md border border-panel-border bg-panel px-2 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Created before"
+        />
+        <input
+          type="text"
+          value={config.filter?.modelTarget ?? ""}
+          onChange={(e) =>
+            handleTextFilterChange("modelTarget", e.target.value)}
+          placeholder="Model"
+          className="w-24 rounded-md border border-panel-border bg-panel px-2 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Only prompts targeting this model"
+        />
+        <input
+          type="text"
+          value={config.filter?.language ?? ""}
+          onChange={(e) => handleTextFilterChange("language", e.target.value)}
+          placeholder="Language"
+          className="w-20 rounded-md border border-panel-border bg-panel px-2 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Only prompts in this language"
+        />
+        <select
+          value={config.filter?.status ?? ""}
+          onChange={(e) => handleStatusChange(e.target.value)}
+          className="rounded-md border border-panel-border bg-panel px-1 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Only prompts with this status"
+        >
+          <option value="">Any status</option>
+          {STATUS_OPTIONS.map((option) => (
+            <option key={option.status} value={option.status}>
+              {option.label}
+            </option>
+          ))}
+        </select>
+        <input
+          type="number"
+          min={0}
+          value={config.filter?.minWords ?? ""}
+          onChange={(e) => handleWordBoundChange("minWords", e.target.value)}
+          placeholder="Min words"
+          className="w-24 rounded-md border border-panel-border bg-panel px-2 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Only prompts with at least this many words"
+        />
+        <input
+          type="number"
+          min={0}
+          value={config.filter?.maxWords ?? ""}
+          onChange={(e) => handleWordBoundChange("maxWords", e.target.value)}
+          placeholder="Max words"
+          className="w-24 rounded-md border border-panel-border bg-panel px-2 py-1 text-xs text-neutral-700 dark:bg-neutral-800 dark:text-neutral-300"
+          title="Only prompts with at most this many words"
+        />
+        {TRIAGE_FILTERS.map((option) => (
+          <button
+            key={option.key}
+            type="button"
+            onClick={() => handleTriageToggle(option.key)}
+            className={`rounded px-2 py-1 text-xs font-medium ${
+              config.filter?.[option.key]
+                ? "bg-blue-100 text-blue-700 dark:bg-blue-900/40 dark:text-blue-300"
+                : "text-neutral-500 hover:bg-neutral-100 dark:hover:bg-neutral-700"
+            }`}
+            title={option.title}
+          >
+            {option.label}
+          </button>
+        ))}
       </div>
     </div>
   );
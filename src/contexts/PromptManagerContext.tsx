@@ -6,7 +6,7 @@ import {
   useEffect,
   useState,
 } from "react";
-import { listen } from "@tauri-apps/api/event";
+import { events } from "@/bindings.ts";
 import { AppConfig, Prompt, View } from "@/schemas/schemas.ts";
 import { promptManagerService } from "@/services/PromptManagerService.ts";
 
@@ -14,6 +14,7 @@ interface PromptManagerContextType {
   // Config
   config: AppConfig | null;
   saveConfig: (config: AppConfig) => Promise<void>;
+  switchProfile: (name: string) => Promise<void>;
 
   // Prompts
   prompts: Prompt[]; // All prompts
@@ -27,6 +28,7 @@ interface PromptManagerContextType {
   addView: (view: View) => Promise<void>;
   updateView: (view: View) => Promise<void>;
   removeView: (id: string) => Promise<void>;
+  reorderViews: (ids: string[]) => Promise<void>;
   getViewById: (id: string) => Promise<View | undefined>;
 
   // Tags
@@ -44,10 +46,22 @@ interface PromptManagerContextType {
   scanVault: () => Promise<void>;
 }
 
+// Edits are uploaded to a remote vault once they have settled for this long
+const REMOTE_SYNC_DELAY_MS = 3000;
+
 const PromptManagerContext = createContext<
   PromptManagerContextType | undefined
 >(undefined);
 
+// Views in the order of `ids`, followed by any views not listed
+function sortViews(views: View[], ids: string[]): View[] {
+  const rank = (view: View) => {
+    const index = ids.indexOf(view.id);
+    return index === -1 ? ids.length : index;
+  };
+  return [...views].sort((a, b) => rank(a) - rank(b));
+}
+
 export function PromptManagerProvider({ children }: { children: ReactNode }) {
   const [config, setConfig] = useState<AppConfig | null>(null);
   const [prompts, setPrompts] = useState<Prompt[]>([]);
@@ -109,16 +123,76 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
     refresh();
   }, [refresh]);
 
+  // Apply changes made through commands (in this or any other window) as they happen
+  useEffect(() => {
+    const byCreatedDesc = (a: Prompt, b: Prompt) =>
+      (b.created || "").localeCompare(a.created || "");
+    const listeners = [
+      events.promptSaved.listen(({ payload }) => {
+        const saved = promptManagerService.mapPromptFromRust(payload);
+        setPrompts((prev) =>
+          [saved, ...prev.filter((p) => p.id !== saved.id)].sort(
+            byCreatedDesc,
+          )
+        );
+      }),
+      events.promptDeleted.listen(({ payload }) => {
+        setPrompts((prev) => prev.filter((p) => p.id !== payload.id));
+      }),
+      events.promptRenamed.listen(({ payload }) => {
+        setPrompts((prev) =>
+          prev.map((p) =>
+            p.id === payload.oldId
+              ? { ...p, id: payload.newId, filePath: payload.newId }
+              : p
+          )
+        );
+      }),
+      events.tagChanged.listen(({ payload }) => {
+        setAllTags(payload.tags);
+      }),
+      events.viewSaved.listen(({ payload }) => {
+        const saved = promptManagerService.mapViewFromRust(payload);
+        setViews((prev) =>
+          prev.some((v) => v.id === saved.id)
+            ? prev.map((v) => v.id === saved.id ? saved : v)
+            : [...prev, saved]
+        );
+      }),
+      events.viewDeleted.listen(({ payload }) => {
+        setViews((prev) => prev.filter((v) => v.id !== payload.id));
+      }),
+      events.viewsReordered.listen(({ payload }) => {
+        setViews((prev) => sortViews(prev, payload.ids));
+      }),
+    ];
+    return () => {
+      listeners.forEach((listener) => listener.then((stop) => stop()));
+    };
+  }, []);
+
   // Config
   const saveConfig = async (newConfig: AppConfig) => {
     const previousConfig = config;
     await promptManagerService.saveConfig(newConfig);
     setConfig(newConfig);
+    const frontmatterKeys = [
+      "promptTagsProperty",
+      "addPromptsTagToTags",
+      "createdProperty",
+      "titleProperty",
+      "descriptionProperty",
+      "contentMode",
+      "parseWikilinks",
+      "maxFileSizeKb",
+      "normalizeLineEndings",
+    ] as const;
     const frontmatterChanged = !previousConfig ||
-      previousConfig.frontmatter?.promptTagsProperty !==
-        newConfig.frontmatter.promptTagsProperty ||
-      previousConfig.frontmatter?.addPromptsTagToTags !==
-        newConfig.frontmatter.addPromptsTagToTags;
+      frontmatterKeys.some((key) =>
+        previousConfig.frontmatter?.[key] !== newConfig.frontmatter[key]
+      ) ||
+      previousConfig.frontmatter?.fileExtensions?.join(",") !==
+        newConfig.frontmatter.fileExtensions.join(",");
     const shouldRefreshVault = !previousConfig ||
       previousConfig.vaultPath !== newConfig.vaultPath ||
       frontmatterChanged;
@@ -127,9 +201,16 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
     }
   };
 
+  // A profile brings its own vault, so the cache is always resynced
+  const switchProfile = async (name: string) => {
+    const newConfig = await promptManagerService.switchProfile(name);
+    setConfig(newConfig);
+    await refresh({ overrideConfig: newConfig });
+  };
+
   const syncVaultNow = useCallback(async () => {
     if (!config?.vaultPath) {
-      return { found: 0, updated: 0, deleted: 0 };
+      return { found: 0, updated: 0, deleted: 0, skipped: [] };
     }
     const stats = await promptManagerService.syncVault();
     setLastSyncAt(new Date().toISOString());
@@ -138,33 +219,57 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
   }, [config, refresh]);
 
   useEffect(() => {
-    if (!config?.vaultPath) return;
+    if (!config?.vaultPath) {
+      promptManagerService.stopVaultWatch().catch((error) => {
+        console.error("Failed to stop vault watcher", error);
+      });
+      return;
+    }
 
     let unlisten: (() => void) | null = null;
-    let debounceTimer: number | null = null;
 
     promptManagerService.startVaultWatch().catch((error) => {
       console.error("Failed to start vault watcher", error);
     });
 
-    listen("vault-changed", () => {
-      if (debounceTimer) {
-        window.clearTimeout(debounceTimer);
-      }
-      debounceTimer = window.setTimeout(() => {
-        syncVaultNow().catch((error) => {
-          console.error("Failed to sync vault after change", error);
-        });
-      }, 300);
+    // The backend debounces watcher events and syncs the cache itself
+    events.syncCompleted.listen(() => {
+      setLastSyncAt(new Date().toISOString());
+      refresh({ overrideConfig: config, skipSync: true }).catch((error) => {
+        console.error("Failed to refresh after vault sync", error);
+      });
     }).then((stop) => {
       unlisten = stop;
     });
 
     return () => {
-      if (debounceTimer) window.clearTimeout(debounceTimer);
       if (unlisten) unlisten();
     };
-  }, [config?.vaultPath, syncVaultNow]);
+  }, [config, refresh]);
+
+  const remoteBackend = config?.vaultPath ? config.remote.backend : "local";
+  useEffect(() => {
+    if (remoteBackend === "local") return;
+
+    let timer: ReturnType<typeof setTimeout> | undefined;
+    const scheduleRemoteSync = () => {
+      clearTimeout(timer);
+      timer = setTimeout(() => {
+        promptManagerService.syncRemoteVault().catch((error) => {
+          console.error("Failed to sync remote vault", error);
+        });
+      }, REMOTE_SYNC_DELAY_MS);
+    };
+    const listeners = [
+      events.promptSaved.listen(scheduleRemoteSync),
+      events.promptDeleted.listen(scheduleRemoteSync),
+      events.promptRenamed.listen(scheduleRemoteSync),
+    ];
+    return () => {
+      clearTimeout(timer);
+      listeners.forEach((listener) => listener.then((stop) => stop()));
+    };
+  }, [remoteBackend]);
 
   // Vault
   const scanVault = async () => {
@@ -179,6 +284,12 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
   };
 
   const updatePrompt = async (prompt: Prompt) => {
+    const previousPath = prompt.previousFilePath;
+    if (previousPath && prompt.filePath && previousPath !== prompt.filePath) {
+      // Move the file first so the save only updates its contents
+      await promptManagerService.renamePrompt(previousPath, prompt.filePath);
+      prompt = { ...prompt, previousFilePath: prompt.filePath };
+    }
     await promptManagerService.savePrompt(prompt);
     await refresh();
   };
@@ -210,6 +321,11 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
     await refresh();
   };
 
+  const reorderViews = async (ids: string[]) => {
+    setViews((prev) => sortViews(prev, ids));
+    await promptManagerService.reorderViews(ids);
+  };
+
   const getViewById = async (id: string) => {
     const found = views.find((v) => v.id === id);
     if (found) return found;
@@ -221,6 +337,7 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
       value={{
         config,
         saveConfig,
+        switchProfile,
         prompts,
         addPrompt,
         updatePrompt,
@@ -230,6 +347,7 @@ export function PromptManagerProvider({ children }: { children: ReactNode }) {
         addView,
         updateView,
         removeView,
+        reorderViews,
         getViewById,
         allTags,
         isLoading,
@@ -1,5 +1,5 @@
 import { useEffect, useMemo, useState } from "react";
-import { Prompt } from "@/schemas/schemas.ts";
+import { Prompt, PromptStatus } from "@/schemas/schemas.ts";
 
 interface UsePromptEditFormProps {
   prompt: Prompt | null;
@@ -27,6 +27,17 @@ export function usePromptEditForm(
   const [description, setDescription] = useState<string>(
     prompt?.description || "",
   );
+  const [color, setColor] = useState<string>(prompt?.color || "");
+  const [icon, setIcon] = useState<string>(prompt?.icon || "");
+  const [notes, setNotes] = useState<string>(prompt?.notes || "");
+  // Comma separated in the form, a list on the prompt
+  const [modelTargets, setModelTargets] = useState<string>(
+    (prompt?.modelTargets ?? []).join(", "),
+  );
+  const [language, setLanguage] = useState<string>(prompt?.language || "");
+  const [status, setStatus] = useState<PromptStatus | "">(
+    prompt?.status ?? "",
+  );
 
   // Reset form when prompt changes
   useEffect(() => {
@@ -36,12 +47,24 @@ export function usePromptEditForm(
       setFilePath(prompt.filePath || prompt.id || "");
       setTitle(prompt.title || "");
       setDescription(prompt.description || "");
+      setColor(prompt.color || "");
+      setIcon(prompt.icon || "");
+      setNotes(prompt.notes || "");
+      setModelTargets((prompt.modelTargets ?? []).join(", "));
+      setLanguage(prompt.language || "");
+      setStatus(prompt.status ?? "");
     } else {
       setText("");
       setTags(initialTags || []);
       setFilePath(initialFilePath);
       setTitle("");
       setDescription("");
+      setColor("");
+      setIcon("");
+      setNotes("");
+      setModelTargets("");
+      setLanguage("");
+      setStatus("");
     }
   }, [prompt?.id, isNew, initialFilePath]);
 
@@ -52,7 +75,13 @@ export function usePromptEditForm(
         !areArraysEqual(tags, prompt.tags) ||
         (filePath || "") !== (prompt.filePath || "") ||
         (title || "") !== (prompt.title || "") ||
-        (description || "") !== (prompt.description || "")
+        (description || "") !== (prompt.description || "") ||
+        color !== (prompt.color || "") ||
+        icon !== (prompt.icon || "") ||
+        notes !== (prompt.notes || "") ||
+        modelTargets !== (prompt.modelTargets ?? []).join(", ") ||
+        language !== (prompt.language || "") ||
+        status !== (prompt.status ?? "")
       );
     } else {
       return (
@@ -60,7 +89,13 @@ export function usePromptEditForm(
         !areArraysEqual(tags, initialTags) ||
         (filePath || "") !== (initialFilePath || "") ||
         title !== "" ||
-        description !== ""
+        description !== "" ||
+        color !== "" ||
+        icon !== "" ||
+        notes !== "" ||
+        modelTargets !== "" ||
+        language !== "" ||
+        status !== ""
       );
     }
   }, [
@@ -72,6 +107,12 @@ export function usePromptEditForm(
     initialFilePath,
     title,
     description,
+    color,
+    icon,
+    notes,
+    modelTargets,
+    language,
+    status,
   ]);
 
   return {
@@ -85,6 +126,18 @@ export function usePromptEditForm(
     setTitle,
     description,
     setDescription,
+    color,
+    setColor,
+    icon,
+    setIcon,
+    notes,
+    setNotes,
+    modelTargets,
+    setModelTargets,
+    language,
+    setLanguage,
+    status,
+    setStatus,
     isModified,
   };
 }
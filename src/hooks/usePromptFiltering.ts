@@ -10,9 +10,10 @@ export function usePromptFiltering(
     const { filter, sort } = config;
 
     if (filter) {
-      if (filter.search) {
-        const lower = filter.search.toLowerCase();
-        result = result.filter((p) => p.text.toLowerCase().includes(lower));
+      if (filter.search?.query) {
+        const { query, caseSensitive } = filter.search;
+        const fold = (s: string) => caseSensitive ? s : s.toLowerCase();
+        result = result.filter((p) => fold(p.text).includes(fold(query)));
       }
       if (filter.tags && filter.tags.length > 0) {
         const positiveTags = filter.tags.filter((t) => !t.startsWith("-"));
@@ -20,12 +21,39 @@ export function usePromptFiltering(
           (t) => t.slice(1),
         );
 
+        // Tags are hierarchical: `lang` also matches `lang/rust`
+        const hasTag = (p: Prompt, filterTag: string) =>
+          p.tags.some((t) => t === filterTag || t.startsWith(`${filterTag}/`));
+
         result = result.filter((p) => {
-          const hasAllPositive = positiveTags.every((t) => p.tags.includes(t));
-          const hasNoNegative = negativeTags.every((t) => !p.tags.includes(t));
-          return hasAllPositive && hasNoNegative;
+          const hasPositive = positiveTags.length === 0 ||
+            (filter.tagMode === "or"
+              ? positiveTags.some((t) => hasTag(p, t))
+              : positiveTags.every((t) => hasTag(p, t)));
+          const hasNoNegative = negativeTags.every((t) => !hasTag(p, t));
+          return hasPositive && hasNoNegative;
         });
       }
+      if (filter.fields) {
+        // Custom frontmatter fields: case-insensitive, any list item, "" = key present
+        const matches = (value: unknown, expected: string): boolean => {
+          if (expected === "") return true;
+          if (Array.isArray(value)) {
+            return value.some((item) =>
+              !Array.isArray(item) && matches(item, expected)
+            );
+          }
+          if (value === null || value === undefined) return false;
+          return String(value).toLowerCase() === expected.toLowerCase();
+        };
+        const fields = Object.entries(filter.fields);
+        result = result.filter((p) =>
+          fields.every(([key, expected]) =>
+            p.customFields !== undefined && key in p.customFields &&
+            matches(p.customFields[key], expected.trim())
+          )
+        );
+      }
     }
 
     if (sort) {
@@ -9,16 +9,34 @@
 // Additionally, you should also exclude this file from your linter and/or formatter to prevent it from being checked or modified.
 
 import { Route as rootRouteImport } from './routes/__root'
+import { Route as Tag_graphRouteImport } from './routes/tag_graph'
+import { Route as StaleRouteImport } from './routes/stale'
 import { Route as SettingsRouteImport } from './routes/settings'
+import { Route as LintRouteImport } from './routes/lint'
 import { Route as Main_viewRouteImport } from './routes/main_view'
 import { Route as DebugRouteImport } from './routes/debug'
 import { Route as IndexRouteImport } from './routes/index'
 
+const Tag_graphRoute = Tag_graphRouteImport.update({
+  id: '/tag_graph',
+  path: '/tag_graph',
+  getParentRoute: () => rootRouteImport,
+} as any)
+const StaleRoute = StaleRouteImport.update({
+  id: '/stale',
+  path: '/stale',
+  getParentRoute: () => rootRouteImport,
+} as any)
 const SettingsRoute = SettingsRouteImport.update({
   id: '/settings',
   path: '/settings',
   getParentRoute: () => rootRouteImport,
 } as any)
+const LintRoute = LintRouteImport.update({
+  id: '/lint',
+  path: '/lint',
+  getParentRoute: () => rootRouteImport,
+} as any)
 const Main_viewRoute = Main_viewRouteImport.update({
   id: '/main_view',
   path: '/main_view',
@@ -38,39 +56,87 @@ const IndexRoute = IndexRouteImport.update({
 export interface FileRoutesByFullPath {
   '/': typeof IndexRoute
   '/debug': typeof DebugRoute
+  '/lint': typeof LintRoute
   '/main_view': typeof Main_viewRoute
   '/settings': typeof SettingsRoute
+  '/stale': typeof StaleRoute
+  '/tag_graph': typeof Tag_graphRoute
 }
 export interface FileRoutesByTo {
   '/': typeof IndexRoute
   '/debug': typeof DebugRoute
+  '/lint': typeof LintRoute
   '/main_view': typeof Main_viewRoute
   '/settings': typeof SettingsRoute
+  '/stale': typeof StaleRoute
+  '/tag_graph': typeof Tag_graphRoute
 }
 export interface FileRoutesById {
   __root__: typeof rootRouteImport
   '/': typeof IndexRoute
   '/debug': typeof DebugRoute
+  '/lint': typeof LintRoute
   '/main_view': typeof Main_viewRoute
   '/settings': typeof SettingsRoute
+  '/stale': typeof StaleRoute
+  '/tag_graph': typeof Tag_graphRoute
 }
 export interface FileRouteTypes {
   fileRoutesByFullPath: FileRoutesByFullPath
-  fullPaths: '/' | '/debug' | '/main_view' | '/settings'
+  fullPaths:
+    | '/'
+    | '/debug'
+    | '/lint'
+    | '/main_view'
+    | '/settings'
+    | '/stale'
+    | '/tag_graph'
   fileRoutesByTo: FileRoutesByTo
-  to: '/' | '/debug' | '/main_view' | '/settings'
-  id: '__root__' | '/' | '/debug' | '/main_view' | '/settings'
+  to:
+    | '/'
+    | '/debug'
+    | '/lint'
+    | '/main_view'
+    | '/settings'
+    | '/stale'
+    | '/tag_graph'
+  id:
+    | '__root__'
+    | '/'
+    | '/debug'
+    | '/lint'
+    | '/main_view'
+    | '/settings'
+    | '/stale'
+    | '/tag_graph'
   fileRoutesById: FileRoutesById
 }
 export interface RootRouteChildren {
   IndexRoute: typeof IndexRoute
   DebugRoute: typeof DebugRoute
+  LintRoute: typeof LintRoute
   Main_viewRoute: typeof Main_viewRoute
   SettingsRoute: typeof SettingsRoute
+  StaleRoute: typeof StaleRoute
+  Tag_graphRoute: typeof Tag_graphRoute
 }
 
 declare module '@tanstack/react-router' {
   interface FileRoutesByPath {
+    '/tag_graph': {
+      id: '/tag_graph'
+      path: '/tag_graph'
+      fullPath: '/tag_graph'
+      preLoaderRoute: typeof Tag_graphRouteImport
+      parentRoute: typeof rootRouteImport
+    }
+    '/stale': {
+      id: '/stale'
+      path: '/stale'
+      fullPath: '/stale'
+      preLoaderRoute: typeof StaleRouteImport
+      parentRoute: typeof rootRouteImport
+    }
     '/settings': {
       id: '/settings'
       path: '/settings'
@@ -78,6 +144,13 @@ declare module '@tanstack/react-router' {
       preLoaderRoute: typeof SettingsRouteImport
       parentRoute: typeof rootRouteImport
     }
+    '/lint': {
+      id: '/lint'
+      path: '/lint'
+      fullPath: '/lint'
+      preLoaderRoute: typeof LintRouteImport
+      parentRoute: typeof rootRouteImport
+    }
     '/main_view': {
       id: '/main_view'
       path: '/main_view'
@@ -105,8 +178,11 @@ declare module '@tanstack/react-router' {
 const rootRouteChildren: RootRouteChildren = {
   IndexRoute: IndexRoute,
   DebugRoute: DebugRoute,
+  LintRoute: LintRoute,
   Main_viewRoute: Main_viewRoute,
   SettingsRoute: SettingsRoute,
+  StaleRoute: StaleRoute,
+  Tag_graphRoute: Tag_graphRoute,
 }
 export const routeTree = rootRouteImport
   ._addFileChildren(rootRouteChildren)
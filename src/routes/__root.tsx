@@ -1,5 +1,6 @@
 import { createRootRoute, Outlet } from "@tanstack/react-router";
 import CommandPalette from "@/components/ui/CommandPalette.tsx";
+import { CaptureSuggestion } from "@/components/ui/CaptureSuggestion.tsx";
 // import { RightSidebar } from "./main_view.tsx";
 import { Sidebar as LeftSidebar } from "@/components/sidebar/Sidebar.tsx";
 import { TitleBar } from "@/components/TitleBar.tsx";
@@ -27,6 +28,7 @@ function RootComponent() {
                 <ViewHeaderProvider>
                   <ToastProvider>
                     <CommandPalette />
+                    <CaptureSuggestion />
 
                     <div className="flex h-screen flex-col overflow-hidden">
                       <TitleBar />
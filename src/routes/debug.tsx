@@ -1,8 +1,8 @@
 import { createFileRoute } from "@tanstack/react-router";
 import { useCallback, useEffect, useState } from "react";
 import {
+  type AppError,
   commands,
-  type DbError,
   type TableColumn,
   type TableRow,
 } from "@/bindings";
@@ -32,7 +32,7 @@ function DebugPage() {
       const result = await commands.getTableNames();
       if (result.status === "error") {
         throw new Error(
-          getDbErrorMessage(result.error) || "Failed to load tables",
+          getErrorMessage(result.error) || "Failed to load tables",
         );
       }
       const tableNames = result.data;
@@ -43,7 +43,7 @@ function DebugPage() {
           const rowsResult = await commands.getTableRows(name);
           if (rowsResult.status === "error") {
             throw new Error(
-              getDbErrorMessage(rowsResult.error) ||
+              getErrorMessage(rowsResult.error) ||
                 "Failed to load table rows",
             );
           }
@@ -71,12 +71,12 @@ function DebugPage() {
 
         if (rowsResult.status === "error") {
           throw new Error(
-            getDbErrorMessage(rowsResult.error) || "Failed to load table rows",
+            getErrorMessage(rowsResult.error) || "Failed to load table rows",
           );
         }
         if (columnsResult.status === "error") {
           throw new Error(
-            getDbErrorMessage(columnsResult.error) ||
+            getErrorMessage(columnsResult.error) ||
               "Failed to load table info",
           );
         }
@@ -102,12 +102,12 @@ function DebugPage() {
 
       if (rowsResult.status === "error") {
         throw new Error(
-          getDbErrorMessage(rowsResult.error) || "Failed to load table rows",
+          getErrorMessage(rowsResult.error) || "Failed to load table rows",
         );
       }
       if (columnsResult.status === "error") {
         throw new Error(
-          getDbErrorMessage(columnsResult.error) || "Failed to load table info",
+          getErrorMessage(columnsResult.error) || "Failed to load table info",
         );
       }
 
@@ -159,7 +159,7 @@ function DebugPage() {
       const result = await commands.clearTable(selectedTable);
       if (result.status === "error") {
         throw new Error(
-          getDbErrorMessage(result.error) || "Failed to clear table",
+          getErrorMessage(result.error) || "Failed to clear table",
         );
       }
       await loadTableData(selectedTable);
@@ -179,7 +179,7 @@ function DebugPage() {
       const result = await commands.exportDatabaseAsJson();
       if (result.status === "error") {
         throw new Error(
-          getDbErrorMessage(result.error) || "Failed to export database",
+          getErrorMessage(result.error) || "Failed to export database",
         );
       }
       const data = result.data;
@@ -367,9 +367,9 @@ function formatCellValue(value: unknown): string {
   return String(value);
 }
 
-function getDbErrorMessage(error: DbError): string {
-  if ("Database" in error) return error.Database;
-  if ("NotFound" in error) return error.NotFound;
-  if ("Serialization" in error) return error.Serialization;
-  return "Unknown error";
+function getErrorMessage(error: AppError): string {
+  if (error.kind === "conflict" && error.conflict) {
+    return `File changed on disk: ${error.conflict.filePath}`;
+  }
+  return error.message || "Unknown error";
 }
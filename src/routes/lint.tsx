@@ -0,0 +1,248 @@
+import { createFileRoute } from "@tanstack/react-router";
+import { useEffect, useState } from "react";
+import { FiRefreshCw } from "react-icons/fi";
+import {
+  PromptLint,
+  RepairKind,
+  VaultHealthReport,
+  VaultRepairReport,
+} from "@/bindings.ts";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+
+export const Route = createFileRoute("/lint")({
+  component: LintPage,
+});
+
+const REPAIR_FIXES: RepairKind[] = [
+  "frontmatter",
+  "missingCreated",
+  "tagFormat",
+  "promptBlock",
+];
+
+function LintPage() {
+  const [reports, setReports] = useState<PromptLint[]>([]);
+  const [health, setHealth] = useState<VaultHealthReport | null>(null);
+  const [repairPlan, setRepairPlan] = useState<VaultRepairReport | null>(
+    null,
+  );
+  const [isRepairing, setIsRepairing] = useState(false);
+  const [isLoading, setIsLoading] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  const loadReports = () => {
+    setIsLoading(true);
+    setError(null);
+    Promise.all([
+      promptManagerService.lintVault(),
+      promptManagerService.checkVaultHealth(),
+    ])
+      .then(([lints, report]) => {
+        setReports(lints);
+        setHealth(report);
+      })
+      .catch((err) => {
+        setError(err instanceof Error ? err.message : String(err));
+      })
+      .finally(() => setIsLoading(false));
+  };
+
+  useEffect(() => {
+    loadReports();
+  }, []);
+
+  const handleRepair = async (dryRun: boolean) => {
+    setIsRepairing(true);
+    setError(null);
+    try {
+      const report = await promptManagerService.repairVault(
+        REPAIR_FIXES,
+        dryRun,
+      );
+      setRepairPlan(dryRun ? report : null);
+      if (!dryRun) loadReports();
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    } finally {
+      setIsRepairing(false);
+    }
+  };
+
+  const handleRecheck = async (id: string) => {
+    try {
+      const report = await promptManagerService.lintPrompt(id);
+      setReports((prev) =>
+        report.warnings.length === 0
+          ? prev.filter((item) => item.id !== id)
+          : prev.map((item) => item.id === id ? report : item)
+      );
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  return (
+    <div className="flex min-h-full w-full flex-col bg-main-background">
+      <div className="mx-auto w-full max-w-5xl space-y-6 p-8 pb-12">
+        <div className="flex flex-wrap items-end justify-between gap-4">
+          <div>
+            <p className="text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+              Review
+            </p>
+            <h1 className="text-3xl font-semibold text-neutral-900 dark:text-neutral-100">
+              Lint
+            </h1>
+            <p className="mt-2 text-sm text-neutral-600 dark:text-neutral-500">
+              Broken files, cache drift, unresolved placeholders, unused
+              variables, untidy lines and prompts over the token budget set in
+              Settings.
+            </p>
+          </div>
+          <button
+            type="button"
+            onClick={loadReports}
+            disabled={isLoading}
+            className="flex items-center gap-1.5 rounded-md border border-panel-border px-3 py-1 text-neutral-700 text-sm hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-300 dark:hover:bg-neutral-800"
+          >
+            <FiRefreshCw size={14} />
+            {isLoading ? "Checking..." : "Check Again"}
+          </button>
+        </div>
+
+        {error && (
+          <div className="rounded-lg border border-red-200 bg-red-50 p-3 text-red-700 text-sm dark:border-red-800 dark:bg-red-900/20 dark:text-red-400">
+            {error}
+          </div>
+        )}
+
+        {health && health.issues.length > 0 && (
+          <section className="rounded-2xl border border-panel-border bg-panel p-6">
+            <h2 className="font-semibold text-neutral-900 text-sm dark:text-neutral-100">
+              Vault Health
+            </h2>
+            <p className="mt-1 text-neutral-500 text-xs">
+              {health.issues.length} issues in {health.filesChecked} files
+            </p>
+            <ul className="mt-3 space-y-2">
+              {health.issues.map((issue, index) => (
+                <li
+                  key={index}
+                  className="flex items-baseline gap-2 text-neutral-700 text-sm dark:text-neutral-300"
+                >
+                  <span className="w-40 shrink-0 truncate font-mono text-xs">
+                    {issue.path}
+                  </span>
+                  <span className="min-w-0 flex-1">
+                    {issue.message}
+                    <span className="block text-neutral-500 text-xs">
+                      {issue.suggestion}
+                    </span>
+                  </span>
+                  <span className="shrink-0 text-neutral-500 text-xs">
+                    {issue.kind}
+                  </span>
+                </li>
+              ))}
+            </ul>
+          </section>
+        )}
+
+        <section className="rounded-2xl border border-panel-border bg-panel p-6">
+          <div className="flex flex-wrap items-center justify-between gap-2">
+            <div>
+              <h2 className="font-semibold text-neutral-900 text-sm dark:text-neutral-100">
+                Repair Vault
+              </h2>
+              <p className="mt-1 text-neutral-500 text-xs">
+                Re-write broken frontmatter, add missing created dates,
+                normalize tags and wrap bare text in a prompt block.
+              </p>
+            </div>
+            <div className="flex gap-2">
+              <button
+                type="button"
+                onClick={() => handleRepair(true)}
+                disabled={isRepairing}
+                className="rounded-md border border-panel-border px-3 py-1 text-neutral-700 text-sm hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-300 dark:hover:bg-neutral-800"
+              >
+                Preview Repairs
+              </button>
+              {repairPlan && repairPlan.files.length > 0 && (
+                <button
+                  type="button"
+                  onClick={() => handleRepair(false)}
+                  disabled={isRepairing}
+                  className="rounded-md bg-blue-600 px-3 py-1 text-sm text-white hover:bg-blue-500 disabled:opacity-50"
+                >
+                  Apply {repairPlan.files.length} Repairs
+                </button>
+              )}
+            </div>
+          </div>
+          {repairPlan && (
+            <ul className="mt-3 space-y-2">
+              {repairPlan.files.length === 0 && (
+                <li className="text-neutral-500 text-sm">Nothing to repair.</li>
+              )}
+              {repairPlan.files.map((file) => (
+                <li
+                  key={file.path}
+                  className="text-neutral-700 text-sm dark:text-neutral-300"
+                >
+                  <span className="font-mono text-xs">{file.path}</span>
+                  <ul className="ml-4 list-disc text-neutral-500 text-xs">
+                    {file.notes.map((note, index) => (
+                      <li key={index}>{note}</li>
+                    ))}
+                  </ul>
+                </li>
+              ))}
+            </ul>
+          )}
+        </section>
+
+        {!isLoading && !error && reports.length === 0 &&
+          health?.issues.length === 0 && (
+          <p className="text-neutral-500 text-sm">No warnings.</p>
+        )}
+
+        {reports.map((report) => (
+          <section
+            key={report.id}
+            className="rounded-2xl border border-panel-border bg-panel p-6"
+          >
+            <div className="flex items-center justify-between gap-2">
+              <h2 className="truncate font-mono text-neutral-900 text-sm dark:text-neutral-100">
+                {report.id}
+              </h2>
+              <button
+                type="button"
+                onClick={() => handleRecheck(report.id)}
+                className="flex shrink-0 items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+              >
+                <FiRefreshCw size={12} />
+                Recheck
+              </button>
+            </div>
+            <ul className="mt-3 space-y-1">
+              {report.warnings.map((warning, index) => (
+                <li
+                  key={index}
+                  className="flex items-baseline gap-2 text-neutral-700 text-sm dark:text-neutral-300"
+                >
+                  <span className="w-16 shrink-0 text-neutral-500 text-xs tabular-nums">
+                    {warning.line !== null ? `Line ${warning.line}` : ""}
+                  </span>
+                  <span className="min-w-0 flex-1">{warning.message}</span>
+                  <span className="shrink-0 text-neutral-500 text-xs">
+                    {warning.kind}
+                  </span>
+                </li>
+              ))}
+            </ul>
+          </section>
+        ))}
+      </div>
+    </div>
+  );
+}
@@ -1,16 +1,23 @@
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { createFileRoute, useSearch } from "@tanstack/react-router";
 import { usePromptManager } from "@/contexts/PromptManagerContext.tsx";
-import { Prompt, ViewConfig } from "@/schemas/schemas.ts";
+import { Prompt, PromptStatus, ViewConfig } from "@/schemas/schemas.ts";
 import { ViewControls } from "@/components/ui/ViewControls.tsx";
 import { PromptEditor } from "@/components/prompts/PromptEditor.tsx";
 import { useViewManagement } from "@/hooks/useViewManagement.ts";
 import { useOverlayState } from "@/hooks/useOverlayState.ts";
 import { PromptList } from "@/components/prompts/PromptList.tsx";
+import { PromptBoard } from "@/components/prompts/PromptBoard.tsx";
+import { BulkTagBar } from "@/components/prompts/BulkTagBar.tsx";
 import { useToast } from "@/components/ui/ToastProvider.tsx";
 import { useViewHeader } from "@/contexts/ViewHeaderContext.tsx";
-import { promptManagerService } from "@/services/PromptManagerService.ts";
+import {
+  CommandError,
+  promptManagerService,
+  SearchHit,
+} from "@/services/PromptManagerService.ts";
 import { useViewConfig } from "@/contexts/ViewConfigContext.tsx";
+import { events } from "@/bindings.ts";
 
 // Define search params schema
 interface MainViewSearch {
@@ -44,6 +51,19 @@ function MainViewComponent() {
   const { header, setHeader } = useViewHeader();
   const { viewPrompts, setViewPrompts } = useViewConfig();
   const [showControls, setShowControls] = useState(true);
+  const [selectedIds, setSelectedIds] = useState<Set<string>>(new Set());
+  const [searchHits, setSearchHits] = useState<Map<string, SearchHit>>(
+    new Map(),
+  );
+
+  const toggleSelected = useCallback((prompt: Prompt) => {
+    setSelectedIds((prev) => {
+      const next = new Set(prev);
+      if (next.has(prompt.id)) next.delete(prompt.id);
+      else next.add(prompt.id);
+      return next;
+    });
+  }, []);
 
   // View Management Hook
   const {
@@ -61,12 +81,27 @@ function MainViewComponent() {
 
   const loadPrompts = useCallback(async (config: ViewConfig) => {
     try {
+      // Searches also return where each prompt was hit, for highlighting
+      if (config.filter?.search?.query.trim()) {
+        const hits = await promptManagerService.searchPrompts(
+          config.filter,
+          config.sort,
+        );
+        setSearchHits(new Map(hits.map((hit) => [hit.prompt.id, hit])));
+        setViewPrompts(hits.map((hit) => hit.prompt));
+        return;
+      }
       const data = await promptManagerService.getPrompts({
         filter: config.filter,
         sort: config.sort,
       });
+      setSearchHits(new Map());
       setViewPrompts(data);
     } catch (error) {
+      // Keep the last results while a search query is still being typed (e.g. `/regex`)
+      if (error instanceof CommandError && error.kind === "invalidInput") {
+        return;
+      }
       console.error("Failed to load filtered prompts", error);
       pushToast({
         title: "Failed to load prompts",
@@ -95,6 +130,13 @@ function MainViewComponent() {
     promptOverlay.openEdit(prompt);
   }, [getContextSignature, promptOverlay]);
 
+  // The board has no room for an inline editor, so it edits above the columns
+  const openEditPinned = useCallback((prompt: Prompt) => {
+    editContextSignatureRef.current = getContextSignature();
+    setPinEditor(true);
+    promptOverlay.openEdit(prompt);
+  }, [getContextSignature, promptOverlay]);
+
   const openNewPrompt = useCallback(() => {
     editContextSignatureRef.current = getContextSignature();
     setPinEditor(true);
@@ -121,6 +163,37 @@ function MainViewComponent() {
   const parentRef = useRef<HTMLDivElement>(null);
 
   const canRename = Boolean(activeView && activeView.type === "custom");
+
+  // The view's layout decides which details show; global settings fill the gaps
+  const layout = currentConfig.layout;
+  const showColumn = (column: string, fallback: boolean) =>
+    layout?.columns ? layout.columns.some((c) => c === column) : fallback;
+
+  const handleArchive = async (prompt: Prompt) => {
+    try {
+      await promptManagerService.setArchived(prompt.id, !prompt.archived);
+      await loadPrompts(currentConfig);
+    } catch (error) {
+      pushToast({
+        title: "Failed to archive prompt",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  const handleStatusChange = async (prompt: Prompt, status: PromptStatus) => {
+    try {
+      await promptManagerService.setPromptStatus(prompt.id, status);
+      await loadPrompts(currentConfig);
+    } catch (error) {
+      pushToast({
+        title: "Failed to change status",
+        description: error instanceof Error ? error.message : String(error),
+        variant: "error",
+      });
+    }
+  };
   const nextHeader = useMemo(
     () => ({
       title: getDisplayTitle(),
@@ -177,6 +250,16 @@ function MainViewComponent() {
     }
   }, [getContextSignature, pinEditor, promptOverlay.editingItem, promptOverlay.isNew]);
 
+  // Show prompts opened, created or imported through prompt-manager:// links
+  useEffect(() => {
+    const listener = events.deepLinkOpened.listen(({ payload }) => {
+      openEditInline(promptManagerService.mapPromptFromRust(payload));
+    });
+    return () => {
+      listener.then((stop) => stop());
+    };
+  }, [openEditInline]);
+
   useEffect(() => {
     if (!promptOverlay.editingItem && !promptOverlay.isNew) {
       setPinEditor(false);
@@ -207,6 +290,14 @@ function MainViewComponent() {
       {/* Main Content List */}
       <div ref={parentRef} className="flex-1 overflow-y-auto p-4">
         <div className="mx-auto max-w-4xl">
+          {selectedIds.size > 0 && (
+            <BulkTagBar
+              selectedIds={[...selectedIds]}
+              allTags={allTags}
+              onApplied={() => loadPrompts(currentConfig)}
+              onClear={() => setSelectedIds(new Set())}
+            />
+          )}
           {shouldPinEditor &&
             (promptOverlay.editingItem || promptOverlay.isNew) && (
             <div className="mb-4">
@@ -230,49 +321,87 @@ function MainViewComponent() {
               />
             </div>
           )}
-          <PromptList
-            prompts={listPrompts}
-            parentRef={parentRef}
-            onEdit={openEditInline}
-            onDelete={async (prompt) => {
-              await removePrompt(prompt.id);
-              await loadPrompts(currentConfig);
-            }}
-            renderPrompt={(prompt) => {
-              if (
-                !shouldPinEditor &&
-                !promptOverlay.isNew &&
-                promptOverlay.editingItem &&
-                prompt.id === promptOverlay.editingItem.id
-              ) {
-                return (
-                  <PromptEditor
-                    prompt={promptOverlay.editingItem}
-                    isNew={false}
-                    allTags={allTags}
-                    initialTags={activeViewTags}
-                    onSave={handlePromptSave}
-                    onDelete={async (id) => {
-                      await removePrompt(id);
-                      await loadPrompts(currentConfig);
-                      promptOverlay.close();
-                    }}
-                    onDuplicate={async (id) => {
-                      await duplicatePrompt(id);
-                      await loadPrompts(currentConfig);
-                      promptOverlay.close();
-                    }}
-                    onClose={promptOverlay.close}
-                  />
-                );
-              }
-              return null;
-            }}
-            showTitles={config?.view?.showPromptTitles ?? true}
-            showFullPrompt={config?.view?.showFullPrompt ?? false}
-            showTags={config?.view?.showPromptTags ?? true}
-            showCreatedDate={config?.view?.showCreatedDate ?? true}
-          />
+          {layout?.mode === "board"
+            ? (
+              <PromptBoard
+                prompts={listPrompts}
+                onEdit={openEditPinned}
+                onDelete={async (prompt) => {
+                  await removePrompt(prompt.id);
+                  await loadPrompts(currentConfig);
+                }}
+                onArchive={handleArchive}
+                onStatusChange={handleStatusChange}
+                showTitles={showColumn(
+                  "title",
+                  config?.view?.showPromptTitles ?? true,
+                )}
+              />
+            )
+            : (
+              <PromptList
+                prompts={listPrompts}
+                parentRef={parentRef}
+                onEdit={openEditInline}
+                onDelete={async (prompt) => {
+                  await removePrompt(prompt.id);
+                  await loadPrompts(currentConfig);
+                }}
+                onArchive={handleArchive}
+                renderPrompt={(prompt) => {
+                  if (
+                    !shouldPinEditor &&
+                    !promptOverlay.isNew &&
+                    promptOverlay.editingItem &&
+                    prompt.id === promptOverlay.editingItem.id
+                  ) {
+                    return (
+                      <PromptEditor
+                        prompt={promptOverlay.editingItem}
+                        isNew={false}
+                        allTags={allTags}
+                        initialTags={activeViewTags}
+                        onSave={handlePromptSave}
+                        onDelete={async (id) => {
+                          await removePrompt(id);
+                          await loadPrompts(currentConfig);
+                          promptOverlay.close();
+                        }}
+                        onDuplicate={async (id) => {
+                          await duplicatePrompt(id);
+                          await loadPrompts(currentConfig);
+                          promptOverlay.close();
+                        }}
+                        onClose={promptOverlay.close}
+                      />
+                    );
+                  }
+                  return null;
+                }}
+                showTitles={showColumn(
+                  "title",
+                  config?.view?.showPromptTitles ?? true,
+                )}
+                showFullPrompt={config?.view?.showFullPrompt ?? false}
+                showTags={showColumn(
+                  "tags",
+                  config?.view?.showPromptTags ?? true,
+                )}
+                showCreatedDate={showColumn(
+                  "created",
+                  config?.view?.showCreatedDate ?? true,
+                )}
+                showUpdatedDate={showColumn("updated", false)}
+                showTokenCount={showColumn("tokens", false)}
+                showWordCount={showColumn("words", false)}
+                previewLines={layout?.previewLength}
+                compact={layout?.mode === "list"}
+                groupByTag={layout?.groupByTag ?? false}
+                searchHits={searchHits}
+                selectedIds={selectedIds}
+                onToggleSelect={toggleSelected}
+              />
+            )}
         </div>
       </div>
     </div>
@@ -2,25 +2,112 @@ import { createFileRoute } from "@tanstack/react-router";
 import { useEffect, useState } from "react";
 import { open } from "@tauri-apps/plugin-dialog";
 import { usePromptManager } from "@/contexts/PromptManagerContext";
-import { AppConfig } from "@/schemas/schemas.ts";
+import { AppConfig, Profile } from "@/schemas/schemas.ts";
 import { useTheme } from "@/contexts/ThemeContext.tsx";
+import {
+  CommandError,
+  promptManagerService,
+} from "@/services/PromptManagerService.ts";
+import {
+  ApiServerStatus,
+  BackupInfo,
+  ConfigIssue,
+  DeletedPrompt,
+  events,
+  ImportItemReport,
+  ImportStrategy,
+  LogLevel,
+  RemoteSyncStats,
+  SamplePack,
+  SecretName,
+  SyncPhase,
+  SyncProgress,
+  SyncStats,
+} from "@/bindings.ts";
+import { GlobalVariables } from "@/components/settings/GlobalVariables.tsx";
 
 export const Route = createFileRoute("/settings")({
   component: SettingsPage,
 });
 
+const DEFAULT_FILENAME_TEMPLATE = "{date}-{random}";
+
+const DEFAULT_API_PORT = 8765;
+
+const DEFAULT_FIELD_KEYS = {
+  createdProperty: "created",
+  titleProperty: "title",
+  descriptionProperty: "description",
+};
+
+type FieldKey = keyof typeof DEFAULT_FIELD_KEYS;
+
+const FIELD_KEY_LABELS: Record<FieldKey, string> = {
+  createdProperty: "Created Property",
+  titleProperty: "Title Property",
+  descriptionProperty: "Description Property",
+};
+
+const SYNC_PHASE_LABELS: Record<SyncPhase, string> = {
+  scanning: "Reading",
+  caching: "Caching",
+};
+
+const LOG_LEVEL_LABELS: Record<LogLevel, string> = {
+  error: "Errors only",
+  warn: "Warnings",
+  info: "Info",
+  debug: "Debug",
+  trace: "Everything",
+};
+
+// Log entries copied for a bug report
+const COPIED_LOG_ENTRIES = 500;
+
 function SettingsPage() {
-  const { config, saveConfig, refresh, syncVaultNow } = usePromptManager();
+  const { config, saveConfig, switchProfile, refresh, syncVaultNow } =
+    usePromptManager();
   const { theme, setTheme } = useTheme();
   const [isSyncing, setIsSyncing] = useState(false);
-  const [syncResult, setSyncResult] = useState<
-    {
-      found: number;
-      updated: number;
-      deleted: number;
-    } | null
+  const [syncResult, setSyncResult] = useState<SyncStats | null>(null);
+  const [syncProgress, setSyncProgress] = useState<SyncProgress | null>(null);
+  const [remoteSyncResult, setRemoteSyncResult] = useState<
+    RemoteSyncStats | null
   >(null);
   const [promptTagsProperty, setPromptTagsProperty] = useState("tags");
+  const [fieldKeys, setFieldKeys] = useState(DEFAULT_FIELD_KEYS);
+  const [filenameTemplate, setFilenameTemplate] = useState(
+    DEFAULT_FILENAME_TEMPLATE,
+  );
+  const [fileExtensions, setFileExtensions] = useState("md");
+  const [importStrategy, setImportStrategy] = useState<ImportStrategy>(
+    "skip",
+  );
+  const [embeddingApi, setEmbeddingApi] = useState({
+    apiUrl: "",
+    apiModel: "",
+  });
+  const [apiStatus, setApiStatus] = useState<ApiServerStatus | null>(null);
+  const [apiPort, setApiPort] = useState(DEFAULT_API_PORT);
+  const [apiToken, setApiToken] = useState("");
+  const [chatGptFirstOnly, setChatGptFirstOnly] = useState(false);
+  const [obsidianTag, setObsidianTag] = useState("prompt");
+  const [gistUrl, setGistUrl] = useState("");
+  const [isImporting, setIsImporting] = useState(false);
+  const [isAutoTagging, setIsAutoTagging] = useState(false);
+  const [importReport, setImportReport] = useState<
+    ImportItemReport[] | null
+  >(null);
+  const [backups, setBackups] = useState<BackupInfo[]>([]);
+  const [isBackingUp, setIsBackingUp] = useState(false);
+  const [deletedPrompts, setDeletedPrompts] = useState<DeletedPrompt[]>([]);
+  const [samplePack, setSamplePack] = useState<SamplePack>("starter");
+  const [logLevel, setLogLevel] = useState<LogLevel>("info");
+  const [logStatus, setLogStatus] = useState<string | null>(null);
+  const [configIssues, setConfigIssues] = useState<ConfigIssue[]>([]);
+  const [profileName, setProfileName] = useState("");
+  const [databasePath, setDatabasePath] = useState("");
+  const [databaseStatus, setDatabaseStatus] = useState<string | null>(null);
 
   const handleBrowseVault = async () => {
     const selected = await open({
@@ -36,6 +123,27 @@ function SettingsPage() {
         theme: config?.theme || "dark",
       };
       await saveConfig(newConfig);
+    }
+  };
+
+  const handleCreateSampleVault = async () => {
+    const selected = await open({
+      directory: true,
+      multiple: false,
+      title: "Select an Empty Folder for the Sample Vault",
+    });
+    if (!selected || typeof selected !== "string") return;
+
+    try {
+      await promptManagerService.createSampleVault(selected, samplePack);
+      await saveConfig({
+        ...config,
+        vaultPath: selected,
+        theme: config?.theme || "dark",
+      });
+    } catch (e) {
+      console.error("Creating sample vault failed", e);
+      alert("Creating sample vault failed: " + e);
     }
   };
 
@@ -45,6 +153,134 @@ function SettingsPage() {
     }
   }, [config?.frontmatter?.promptTagsProperty]);
 
+  useEffect(() => {
+    if (config?.filenameTemplate) {
+      setFilenameTemplate(config.filenameTemplate);
+    }
+  }, [config?.filenameTemplate]);
+
+  useEffect(() => {
+    setDatabasePath(config?.databasePath ?? "");
+  }, [config?.databasePath]);
+
+  useEffect(() => {
+    if (config?.frontmatter?.fileExtensions) {
+      setFileExtensions(config.frontmatter.fileExtensions.join(", "));
+    }
+  }, [config?.frontmatter?.fileExtensions]);
+
+  useEffect(() => {
+    if (config?.frontmatter) {
+      setFieldKeys({
+        createdProperty: config.frontmatter.createdProperty,
+        titleProperty: config.frontmatter.titleProperty,
+        descriptionProperty: config.frontmatter.descriptionProperty,
+      });
+    }
+  }, [config?.frontmatter]);
+
+  useEffect(() => {
+    if (config?.embeddings) {
+      setEmbeddingApi({
+        apiUrl: config.embeddings.apiUrl,
+        apiModel: config.embeddings.apiModel,
+      });
+    }
+  }, [config?.embeddings]);
+
+  // Check the saved config together with the fields still being edited
+  useEffect(() => {
+    if (!config) return;
+    const timeout = setTimeout(() => {
+      promptManagerService.validateConfig({
+        ...config,
+        filenameTemplate: filenameTemplate.trim() || DEFAULT_FILENAME_TEMPLATE,
+        frontmatter: {
+          ...config.frontmatter,
+          promptTagsProperty,
+          ...fieldKeys,
+        },
+      })
+        .then(setConfigIssues)
+        .catch((e) => console.error("Failed to validate config", e));
+    }, 300);
+    return () => clearTimeout(timeout);
+  }, [config, filenameTemplate, promptTagsProperty, fieldKeys]);
+
+  useEffect(() => {
+    if (!config?.vaultPath) return;
+    promptManagerService.listBackups()
+      .then(setBackups)
+      .catch((e) => console.error("Failed to list backups", e));
+  }, [config?.vaultPath, config?.backup?.directory]);
+
+  useEffect(() => {
+    if (!config?.vaultPath) return;
+    promptManagerService.listRecentlyDeleted()
+      .then(setDeletedPrompts)
+      .catch((e) => console.error("Failed to list deleted prompts", e));
+  }, [config?.vaultPath, config?.deletedRetentionDays]);
+
+  useEffect(() => {
+    promptManagerService.getApiServerStatus()
+      .then(setApiStatus)
+      .catch((e) => console.error("Failed to get API server status", e));
+  }, []);
+
+  const handleToggleApiServer = async () => {
+    try {
+      if (apiStatus?.running) {
+        await promptManagerService.stopApiServer();
+        setApiStatus({ running: false, port: null, token: null });
+      } else {
+        setApiStatus(
+          await promptManagerService.startApiServer(
+            apiPort,
+            apiToken.trim() || null,
+          ),
+        );
+      }
+    } catch (e) {
+      console.error("API server toggle failed", e);
+      alert("API server failed: " + e);
+    }
+  };
+
+  // Apply every suggested tag to the prompts that have none
+  const handleAutoTagUntagged = async () => {
+    setIsAutoTagging(true);
+    try {
+      const suggestions = await promptManagerService.suggestTagsForUntagged();
+      const prompts = await promptManagerService.getPrompts();
+      const updates = [];
+      for (const suggestion of suggestions) {
+        const prompt = prompts.find((p) => p.id === suggestion.promptId);
+        const tags = [...suggestion.existing, ...suggestion.new];
+        if (!prompt || tags.length === 0) continue;
+        updates.push({ ...prompt, tags });
+      }
+      const results = await promptManagerService.savePrompts(updates);
+      const tagged = results.filter((result) => !result.error).length;
+      await refresh({ skipSync: true });
+      alert(`Tagged ${tagged} of ${suggestions.length} untagged prompts.`);
+    } catch (e) {
+      console.error("Auto-tagging failed", e);
+      alert("Auto-tagging failed: " + e);
+    } finally {
+      setIsAutoTagging(false);
+    }
+  };
+
+  const updateEmbeddings = async (
+    updates: Partial<AppConfig["embeddings"]>,
+  ) => {
+    if (!config) return;
+    await saveConfig({
+      ...config,
+      embeddings: { ...config.embeddings, ...updates },
+    });
+  };
+
   const updateFrontmatter = async (
     updates: Partial<AppConfig["frontmatter"]>,
   ) => {
@@ -54,10 +290,23 @@ function SettingsPage() {
       frontmatter: {
         promptTagsProperty: config.frontmatter?.promptTagsProperty ?? "tags",
         addPromptsTagToTags: config.frontmatter?.addPromptsTagToTags ?? false,
+        contentMode: config.frontmatter?.contentMode ?? "fence",
+        parseWikilinks: config.frontmatter?.parseWikilinks ?? false,
+        fileExtensions: config.frontmatter?.fileExtensions ?? ["md"],
+        maxFileSizeKb: config.frontmatter?.maxFileSizeKb ?? 1024,
+        normalizeLineEndings: config.frontmatter?.normalizeLineEndings ??
+          false,
+        ...DEFAULT_FIELD_KEYS,
+        ...config.frontmatter,
         ...updates,
       },
     };
-    await saveConfig(nextConfig);
+    try {
+      await saveConfig(nextConfig);
+    } catch (e) {
+      console.error("Failed to save frontmatter settings", e);
+      alert("Frontmatter settings not saved: " + e);
+    }
   };
 
   const commitPromptTagsProperty = async () => {
@@ -69,24 +318,404 @@ function SettingsPage() {
     }
   };
 
+  const commitFilenameTemplate = async () => {
+    if (!config) return;
+    const normalized = filenameTemplate.trim() || DEFAULT_FILENAME_TEMPLATE;
+    setFilenameTemplate(normalized);
+    if (normalized !== config.filenameTemplate) {
+      await saveConfig({ ...config, filenameTemplate: normalized });
+    }
+  };
+
+  const commitDefaultTags = async (value: string) => {
+    if (!config) return;
+    const tags = value.split(",").map((tag) => tag.trim()).filter(Boolean);
+    if (tags.join(",") !== config.defaultTags.join(",")) {
+      await saveConfig({ ...config, defaultTags: tags });
+    }
+  };
+
+  const commitDefaultFrontmatter = async (value: string) => {
+    if (!config) return;
+    const fields = parseFrontmatterLines(value);
+    if (
+      formatFrontmatterLines(fields) !==
+        formatFrontmatterLines(config.defaultFrontmatter)
+    ) {
+      await saveConfig({ ...config, defaultFrontmatter: fields });
+    }
+  };
+
+  const commitFileExtensions = async () => {
+    if (!config) return;
+    const extensions = fileExtensions
+      .split(",")
+      .map((extension) => extension.trim().replace(/^\.+/, ""))
+      .filter((extension) => extension.length > 0);
+    const normalized = extensions.length > 0 ? extensions : ["md"];
+    setFileExtensions(normalized.join(", "));
+    if (
+      normalized.join(",") !== config.frontmatter?.fileExtensions?.join(",")
+    ) {
+      await updateFrontmatter({ fileExtensions: normalized });
+    }
+  };
+
+  const commitFieldKey = async (key: FieldKey) => {
+    if (!config) return;
+    const normalized = fieldKeys[key].trim() || DEFAULT_FIELD_KEYS[key];
+    setFieldKeys((prev) => ({ ...prev, [key]: normalized }));
+    if (normalized !== config.frontmatter?.[key]) {
+      await updateFrontmatter({ [key]: normalized });
+    }
+  };
+
   const handleSync = async () => {
     setIsSyncing(true);
     setSyncResult(null);
+    const unlisten = await events.syncProgress.listen((event) => {
+      setSyncProgress(event.payload);
+    });
     try {
       const stats = await syncVaultNow();
       setSyncResult(stats);
     } catch (e) {
+      if (e instanceof CommandError && e.kind === "cancelled") return;
       console.error("Sync failed", e);
       alert("Sync failed: " + e);
     } finally {
+      unlisten();
+      setSyncProgress(null);
       setIsSyncing(false);
     }
   };
+
+  const handleCancelSync = async () => {
+    try {
+      await promptManagerService.cancelSync();
+    } catch (e) {
+      console.error("Failed to cancel sync", e);
+    }
+  };
+
+  const handleRemoteSync = async (direction: "pull" | "push" | "both") => {
+    setIsSyncing(true);
+    setRemoteSyncResult(null);
+    const sync = (confirmDeletions: boolean) =>
+      direction === "pull"
+        ? promptManagerService.pullVault(confirmDeletions)
+        : direction === "push"
+        ? promptManagerService.pushVault()
+        : promptManagerService.syncRemoteVault(confirmDeletions);
+    try {
+      try {
+        setRemoteSyncResult(await sync(false));
+      } catch (e) {
+        if (
+          !(e instanceof CommandError && e.kind === "needsConfirmation") ||
+          !confirm(`${e.message}. Delete them?`)
+        ) {
+          throw e;
+        }
+        setRemoteSyncResult(await sync(true));
+      }
+    } catch (e) {
+      console.error("Remote sync failed", e);
+      alert("Remote sync failed: " + e);
+    } finally {
+      setIsSyncing(false);
+    }
+  };
+
+  const handleBackupNow = async () => {
+    setIsBackingUp(true);
+    try {
+      await promptManagerService.createBackupNow();
+      setBackups(await promptManagerService.listBackups());
+    } catch (e) {
+      console.error("Backup failed", e);
+      alert("Backup failed: " + e);
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
+  const handleRestoreBackup = async (backup: BackupInfo) => {
+    if (
+      !confirm(
+        `Replace the vault contents with the backup from ${
+          new Date(backup.created).toLocaleString()
+        }? The current vault is backed up first.`,
+      )
+    ) {
+      return;
+    }
+
+    setIsBackingUp(true);
+    try {
+      const stats = await promptManagerService.restoreBackup(backup.path);
+      setSyncResult(stats);
+      setBackups(await promptManagerService.listBackups());
+    } catch (e) {
+      console.error("Restore failed", e);
+      alert("Restore failed: " + e);
+    } finally {
+      setIsBackingUp(false);
+    }
+  };
+
+  const handleRecoverDeleted = async (deleted: DeletedPrompt) => {
+    try {
+      await promptManagerService.recoverDeletedPrompt(deleted.id);
+      setDeletedPrompts(await promptManagerService.listRecentlyDeleted());
+    } catch (e) {
+      console.error("Recover failed", e);
+      alert("Recover failed: " + e);
+    }
+  };
+
+  const handleLogLevelChange = async (level: LogLevel) => {
+    try {
+      await promptManagerService.setLogLevel(level);
+      setLogLevel(level);
+    } catch (e) {
+      console.error("Failed to set log level", e);
+      alert("Failed to set log level: " + e);
+    }
+  };
+
+  const handleCopyLogs = async () => {
+    try {
+      const entries = await promptManagerService.getRecentLogs(
+        "trace",
+        COPIED_LOG_ENTRIES,
+      );
+      const text = entries
+        .map((entry) =>
+          `${entry.timestamp} ${entry.level.toUpperCase()} ${
+            entry.span ? `[${entry.span}] ` : ""
+          }${entry.target}: ${entry.message}`
+        )
+        .join("\n");
+      await navigator.clipboard.writeText(text);
+      setLogStatus(`Copied ${entries.length} log entries`);
+    } catch (e) {
+      console.error("Failed to copy logs", e);
+      alert("Failed to copy logs: " + e);
+    }
+  };
+
+  const handleImport = async () => {
+    const selected = await open({
+      multiple: false,
+      title: "Import Prompts",
+      filters: [{ name: "Prompts", extensions: ["json", "csv", "yaml", "yml"] }],
+    });
+    if (!selected || typeof selected !== "string") return;
+
+    const extension = selected.split(".").pop()?.toLowerCase();
+    const format = extension === "csv"
+      ? "csv"
+      : extension === "yaml" || extension === "yml"
+      ? "yaml"
+      : "json";
+    setIsImporting(true);
+    setImportReport(null);
+    try {
+      const report = await promptManagerService.importPrompts(
+        selected,
+        format,
+        importStrategy,
+      );
+      setImportReport(report);
+      await refresh({ skipSync: true });
+    } catch (e) {
+      console.error("Import failed", e);
+      alert("Import failed: " + e);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  const handleImportPack = async () => {
+    const selected = await open({
+      multiple: false,
+      title: "Import Prompt Pack",
+      filters: [{ name: "Prompt pack", extensions: ["promptpack"] }],
+    });
+    if (!selected || typeof selected !== "string") return;
+
+    setIsImporting(true);
+    setImportReport(null);
+    try {
+      const report = await promptManagerService.importPromptpack(
+        selected,
+        importStrategy,
+      );
+      setImportReport(report);
+      await refresh({ skipSync: true });
+    } catch (e) {
+      console.error("Prompt pack import failed", e);
+      alert("Import failed: " + e);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  const handleClipboardWatch = async (enabled: boolean) => {
+    if (!config) return;
+    try {
+      await promptManagerService.setClipboardWatch(enabled);
+      await saveConfig({
+        ...config,
+        capture: { ...config.capture, watchClipboard: enabled },
+      });
+    } catch (e) {
+      console.error("Clipboard watcher toggle failed", e);
+      alert("Clipboard watcher failed: " + e);
+    }
+  };
+
+  // Moves the cache to the new file right away; it is filled by the sync that
+  // follows
+  const handleApplyDatabasePath = async () => {
+    if (!config) return;
+    try {
+      await saveConfig({
+        ...config,
+        databasePath: databasePath.trim() || null,
+      });
+      const path = await promptManagerService.reloadDatabase();
+      setDatabaseStatus(`Cache moved to ${path}`);
+      await refresh();
+    } catch (e) {
+      console.error("Moving the cache failed", e);
+      alert("Moving the cache failed: " + e);
+    }
+  };
+
+  // Saves the current vault, frontmatter and view settings under a name,
+  // replacing a profile of that name, and makes it the active profile
+  const handleSaveProfile = async () => {
+    if (!config) return;
+    const name = profileName.trim();
+    if (!name) return;
+    const profile: Profile = {
+      name,
+      vaultPath: config.vaultPath,
+      frontmatter: config.frontmatter,
+      view: config.view,
+    };
+    const profiles = config.profiles.some((p) => p.name === name)
+      ? config.profiles.map((p) => p.name === name ? profile : p)
+      : [...config.profiles, profile];
+    try {
+      await saveConfig({ ...config, profiles, activeProfile: name });
+      setProfileName("");
+    } catch (e) {
+      console.error("Saving profile failed", e);
+      alert("Saving profile failed: " + e);
+    }
+  };
+
+  const handleSwitchProfile = async (name: string) => {
+    try {
+      await switchProfile(name);
+    } catch (e) {
+      console.error("Switching profile failed", e);
+      alert("Switching profile failed: " + e);
+    }
+  };
+
+  const handleDeleteProfile = async (name: string) => {
+    if (!config) return;
+    if (!confirm(`Delete the profile "${name}"? Its vault is not touched.`)) {
+      return;
+    }
+    await saveConfig({
+      ...config,
+      profiles: config.profiles.filter((p) => p.name !== name),
+      activeProfile: config.activeProfile === name ? null : config.activeProfile,
+    });
+  };
+
+  const handleImportGist = async () => {
+    setIsImporting(true);
+    setImportReport(null);
+    try {
+      const report = await promptManagerService.importPromptFromGist(
+        gistUrl.trim(),
+      );
+      setImportReport([report]);
+      setGistUrl("");
+      await refresh({ skipSync: true });
+    } catch (e) {
+      console.error("Gist import failed", e);
+      alert("Import failed: " + e);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  const handleImportChatGpt = async () => {
+    const selected = await open({
+      multiple: false,
+      title: "Import ChatGPT conversations.json",
+      filters: [{ name: "ChatGPT export", extensions: ["json"] }],
+    });
+    if (!selected || typeof selected !== "string") return;
+
+    setIsImporting(true);
+    setImportReport(null);
+    try {
+      const report = await promptManagerService.importChatGptExport(selected, {
+        firstMessageOnly: chatGptFirstOnly,
+      });
+      setImportReport(report);
+      await refresh({ skipSync: true });
+    } catch (e) {
+      console.error("ChatGPT import failed", e);
+      alert("Import failed: " + e);
+    } finally {
+      setIsImporting(false);
+    }
+  };
+
+  const handleImportObsidian = async () => {
+    const selected = await open({
+      directory: true,
+      multiple: false,
+      title: "Select Obsidian Vault",
+    });
+    if (!selected || typeof selected !== "string") return;
+
+    setIsImporting(true);
+    setImportReport(null);
+    try {
+      const report = await promptManagerService.importObsidian(
+        selected,
+        obsidianTag,
+      );
+      setImportReport(report);
+      await refresh({ skipSync: true });
+    } catch (e) {
+      console.error("Obsidian import failed", e);
+      alert("Import failed: " + e);
+    } finally {
+      setIsImporting(false);
+    }
+  };
 
   if (!config) return <div className="p-8">Loading config...</div>;
   const frontmatter = config.frontmatter ?? {
     promptTagsProperty: "tags",
     addPromptsTagToTags: false,
+    contentMode: "fence",
+    parseWikilinks: false,
+    fileExtensions: ["md"],
+    maxFileSizeKb: 1024,
+    normalizeLineEndings: false,
+    ...DEFAULT_FIELD_KEYS,
   };
 
   const themeOptions: {
@@ -160,6 +789,101 @@ function SettingsPage() {
               <p className="mt-2 text-xs text-neutral-500 dark:text-neutral-500">
                 Prompts are stored as Markdown files in this folder.
               </p>
+              <ConfigIssueList issues={configIssues} field="vaultPath" />
+              <div className="mt-3 flex flex-col gap-2 sm:flex-row sm:items-center">
+                <select
+                  value={samplePack}
+                  onChange={(e) => setSamplePack(e.target.value as SamplePack)}
+                  className="rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 dark:text-neutral-200"
+                >
+                  <option value="starter">All starter prompts</option>
+                  <option value="coding">Coding</option>
+                  <option value="writing">Writing</option>
+                  <option value="summarization">Summarization</option>
+                </select>
+                <button
+                  onClick={handleCreateSampleVault}
+                  className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-700 transition hover:border-neutral-500 dark:text-neutral-300"
+                >
+                  Create Sample Vault
+                </button>
+              </div>
+            </div>
+
+            <div className="space-y-3 rounded-lg border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Profiles
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Each profile keeps its own vault path, frontmatter and view
+                  settings. Changes made here are kept in the active profile.
+                </p>
+              </div>
+              {config.profiles.length > 0 && (
+                <ul className="space-y-2">
+                  {config.profiles.map((profile) => {
+                    const isActive = profile.name === config.activeProfile;
+                    return (
+                      <li
+                        key={profile.name}
+                        className="flex flex-col gap-2 rounded-lg border border-panel-border bg-panel-2 px-3 py-2 text-sm sm:flex-row sm:items-center sm:justify-between"
+                      >
+                        <div className="min-w-0">
+                          <div className="font-semibold text-neutral-900 dark:text-neutral-100">
+                            {profile.name}
+                            {isActive && (
+                              <span className="ml-2 text-xs font-normal text-blue-500">
+                                Active
+                              </span>
+                            )}
+                          </div>
+                          <div className="truncate font-mono text-xs text-neutral-500">
+                            {profile.vaultPath || "No vault"}
+                          </div>
+                        </div>
+                        <div className="flex gap-2">
+                          <button
+                            onClick={() => handleSwitchProfile(profile.name)}
+                            disabled={isActive}
+                            className="rounded-lg border border-panel-border px-3 py-1 text-xs font-medium text-neutral-700 transition hover:border-neutral-500 disabled:opacity-50 dark:text-neutral-300"
+                          >
+                            Switch
+                          </button>
+                          <button
+                            onClick={() => handleDeleteProfile(profile.name)}
+                            className="rounded-lg border border-panel-border px-3 py-1 text-xs font-medium text-red-500 transition hover:border-red-500/60"
+                          >
+                            Delete
+                          </button>
+                        </div>
+                      </li>
+                    );
+                  })}
+                </ul>
+              )}
+              <div className="flex flex-col gap-2 sm:flex-row">
+                <input
+                  type="text"
+                  value={profileName}
+                  onChange={(event) => setProfileName(event.target.value)}
+                  onKeyDown={(event) => {
+                    if (event.key === "Enter") {
+                      handleSaveProfile();
+                    }
+                  }}
+                  placeholder={config.activeProfile || "work"}
+                  className="flex-1 rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                />
+                <button
+                  onClick={handleSaveProfile}
+                  disabled={!profileName.trim()}
+                  className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-700 transition hover:border-neutral-500 disabled:opacity-50 dark:text-neutral-300"
+                >
+                  Save Current as Profile
+                </button>
+              </div>
+              <ConfigIssueList issues={configIssues} field="profiles" />
             </div>
 
             {config.vaultPath && (
@@ -172,53 +896,1082 @@ function SettingsPage() {
                     <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
                       Re-scan the vault and update the local cache.
                     </p>
+                    {syncProgress && (
+                      <p className="mt-1 truncate text-xs text-blue-300">
+                        {SYNC_PHASE_LABELS[syncProgress.phase]}{" "}
+                        {syncProgress.scanned} / {syncProgress.total}
+                        {syncProgress.current && (
+                          <span className="ml-1 font-mono">
+                            {syncProgress.current}
+                          </span>
+                        )}
+                      </p>
+                    )}
+                  </div>
+                  <div className="flex gap-2">
+                    {isSyncing && (
+                      <button
+                        onClick={handleCancelSync}
+                        className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-700 transition hover:border-neutral-500 dark:text-neutral-300"
+                      >
+                        Cancel
+                      </button>
+                    )}
+                    <button
+                      onClick={handleSync}
+                      disabled={isSyncing}
+                      className={`rounded-lg px-4 py-2 text-sm font-semibold transition ${
+                        isSyncing
+                          ? "cursor-not-allowed bg-blue-500/20 text-blue-300"
+                          : "bg-blue-600 text-white hover:bg-blue-500"
+                      }`}
+                    >
+                      {isSyncing ? "Syncing..." : "Sync Vault"}
+                    </button>
                   </div>
-                  <button
-                    onClick={handleSync}
-                    disabled={isSyncing}
-                    className={`rounded-lg px-4 py-2 text-sm font-semibold transition ${
-                      isSyncing
-                        ? "cursor-not-allowed bg-blue-500/20 text-blue-300"
-                        : "bg-blue-600 text-white hover:bg-blue-500"
-                    }`}
-                  >
-                    {isSyncing ? "Syncing..." : "Sync Vault"}
-                  </button>
                 </div>
               </div>
             )}
 
-            {syncResult && (
-              <div className="rounded-lg border border-emerald-500/30 bg-emerald-500/10 p-4 text-sm text-emerald-200">
-                <p className="font-semibold">Sync Complete</p>
-                <ul className="mt-2 space-y-1 text-xs text-emerald-200/80">
-                  <li>Found: {syncResult.found} files</li>
-                  <li>Updated: {syncResult.updated} files in cache</li>
-                  <li>Deleted: {syncResult.deleted} orphaned entries</li>
-                </ul>
+            {config.vaultPath && (
+              <div className="space-y-3 rounded-lg border border-panel-border bg-panel p-4">
+                <div className="flex flex-col gap-3 sm:flex-row sm:items-center sm:justify-between">
+                  <div>
+                    <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                      Remote Vault
+                    </h3>
+                    <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                      Keep the vault on a WebDAV server such as Nextcloud or in
+                      an S3-compatible bucket. The vault folder holds a copy
+                      that works offline and is synced on every vault sync.
+                    </p>
+                  </div>
+                  <select
+                    value={config.remote.backend}
+                    onChange={(event) =>
+                      saveConfig({
+                        ...config,
+                        remote: {
+                          ...config.remote,
+                          backend: event.target.value as
+                            | "local"
+                            | "webDav"
+                            | "s3",
+                        },
+                      })}
+                    className="rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+                  >
+                    <option value="local">Local only</option>
+                    <option value="webDav">WebDAV</option>
+                    <option value="s3">S3 / MinIO / B2</option>
+                  </select>
+                </div>
+                {config.remote.backend === "webDav" && (
+                  <div className="space-y-2">
+                    {(
+                      [
+                        ["url", "Folder URL", "text"],
+                        ["username", "Username", "text"],
+                      ] as const
+                    ).map(([key, label, type]) => (
+                      <div key={key}>
+                        <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                          {label}
+                        </label>
+                        <input
+                          type={type}
+                          defaultValue={config.remote.webdav[key] ?? ""}
+                          onBlur={(event) =>
+                            event.target.value !==
+                              (config.remote.webdav[key] ?? "") &&
+                            saveConfig({
+                              ...config,
+                              remote: {
+                                ...config.remote,
+                                webdav: {
+                                  ...config.remote.webdav,
+                                  [key]: event.target.value,
+                                },
+                              },
+                            })}
+                          className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                        />
+                      </div>
+                    ))}
+                    <SecretField
+                      name="webDavPassword"
+                      label="Password or App Token"
+                    />
+                  </div>
+                )}
+                {config.remote.backend === "s3" && (
+                  <div className="space-y-2">
+                    {(
+                      [
+                        ["endpoint", "Endpoint", "text"],
+                        ["region", "Region", "text"],
+                        ["bucket", "Bucket", "text"],
+                        ["prefix", "Folder in Bucket (optional)", "text"],
+                        ["accessKeyId", "Access Key ID", "text"],
+                      ] as const
+                    ).map(([key, label, type]) => (
+                      <div key={key}>
+                        <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                          {label}
+                        </label>
+                        <input
+                          type={type}
+                          defaultValue={config.remote.s3[key] ?? ""}
+                          onBlur={(event) =>
+                            event.target.value !==
+                              (config.remote.s3[key] ?? "") &&
+                            saveConfig({
+                              ...config,
+                              remote: {
+                                ...config.remote,
+                                s3: {
+                                  ...config.remote.s3,
+                                  [key]: event.target.value,
+                                },
+                              },
+                            })}
+                          className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                        />
+                      </div>
+                    ))}
+                    <SecretField
+                      name="s3SecretAccessKey"
+                      label="Secret Access Key"
+                    />
+                  </div>
+                )}
+                {config.remote.backend !== "local" && (
+                  <div className="flex flex-wrap items-center justify-between gap-3">
+                    <p className="text-xs text-neutral-600 dark:text-neutral-500">
+                      {remoteSyncResult &&
+                        `${remoteSyncResult.pulled.length} downloaded, ${remoteSyncResult.pushed.length} uploaded, ${remoteSyncResult.conflicts.length} conflicts`}
+                    </p>
+                    <div className="flex gap-2">
+                      {(
+                        [
+                          ["pull", "Pull"],
+                          ["push", "Push"],
+                          ["both", "Sync with Server"],
+                        ] as const
+                      ).map(([direction, label]) => (
+                        <button
+                          key={direction}
+                          onClick={() => handleRemoteSync(direction)}
+                          disabled={isSyncing}
+                          className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-900 transition hover:border-neutral-500 disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-100"
+                        >
+                          {label}
+                        </button>
+                      ))}
+                    </div>
+                  </div>
+                )}
               </div>
             )}
 
-            <div className="space-y-4 rounded-xl border border-panel-border bg-panel p-4">
-              <div>
-                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
-                  Frontmatter Tags
-                </h3>
-                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
-                  Choose which frontmatter key stores prompt tags.
-                </p>
-              </div>
-              <div className="space-y-3">
+            {config.vaultPath && (
+              <div className="space-y-3 rounded-lg border border-panel-border bg-panel p-4">
+                <div className="flex flex-col gap-3 sm:flex-row sm:items-center sm:justify-between">
+                  <div>
+                    <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                      Backups
+                    </h3>
+                    <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                      Zip snapshots of the vault. Older snapshots beyond the
+                      retention count are deleted.
+                    </p>
+                  </div>
+                  <button
+                    onClick={handleBackupNow}
+                    disabled={isBackingUp}
+                    className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-900 transition hover:border-neutral-500 disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-100"
+                  >
+                    Back Up Now
+                  </button>
+                </div>
+                <label className="flex items-center justify-between gap-3 text-sm text-neutral-700 dark:text-neutral-200">
+                  Back up automatically
+                  <input
+                    type="checkbox"
+                    checked={config.backup.enabled}
+                    onChange={(event) =>
+                      saveConfig({
+                        ...config,
+                        backup: {
+                          ...config.backup,
+                          enabled: event.target.checked,
+                        },
+                      })}
+                    className="h-4 w-4 accent-blue-600"
+                  />
+                </label>
                 <div>
                   <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
-                    Prompt Tags Property
+                    Backup Folder
                   </label>
                   <input
                     type="text"
-                    value={promptTagsProperty}
-                    onChange={(event) =>
-                      setPromptTagsProperty(event.target.value)}
-                    onBlur={commitPromptTagsProperty}
+                    defaultValue={config.backup.directory ?? ""}
+                    placeholder="App data folder"
+                    onBlur={(event) =>
+                      event.target.value !== (config.backup.directory ?? "") &&
+                      saveConfig({
+                        ...config,
+                        backup: {
+                          ...config.backup,
+                          directory: event.target.value,
+                        },
+                      })}
+                    className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                  />
+                </div>
+                <div className="grid gap-2 sm:grid-cols-2">
+                  {(
+                    [
+                      ["intervalHours", "Hours Between Backups", 1],
+                      ["retention", "Backups to Keep (0 = all)", 0],
+                    ] as const
+                  ).map(([key, label, min]) => (
+                    <div key={key}>
+                      <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                        {label}
+                      </label>
+                      <input
+                        type="number"
+                        min={min}
+                        defaultValue={config.backup[key]}
+                        onBlur={(event) => {
+                          const value = Number.parseInt(event.target.value, 10);
+                          if (value >= min && value !== config.backup[key]) {
+                            saveConfig({
+                              ...config,
+                              backup: { ...config.backup, [key]: value },
+                            });
+                          }
+                        }}
+                        className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                      />
+                    </div>
+                  ))}
+                </div>
+                {backups.length > 0 && (
+                  <ul className="divide-y divide-panel-border text-sm">
+                    {backups.map((backup) => (
+                      <li
+                        key={backup.path}
+                        className="flex items-center justify-between gap-3 py-2"
+                      >
+                        <span className="text-neutral-900 dark:text-neutral-200">
+                          {new Date(backup.created).toLocaleString()}
+                          <span className="ml-2 text-xs text-neutral-500">
+                            {backup.promptCount} prompts,{" "}
+                            {Math.ceil(backup.sizeBytes / 1024)} KB
+                          </span>
+                        </span>
+                        <button
+                          onClick={() => handleRestoreBackup(backup)}
+                          disabled={isBackingUp}
+                          className="rounded-lg border border-panel-border px-3 py-1 text-xs font-medium text-neutral-900 transition hover:border-neutral-500 disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-100"
+                        >
+                          Restore
+                        </button>
+                      </li>
+                    ))}
+                  </ul>
+                )}
+              </div>
+            )}
+
+            {config.vaultPath && (
+              <div className="space-y-3 rounded-lg border border-panel-border bg-panel p-4">
+                <div>
+                  <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                    Recently Deleted
+                  </h3>
+                  <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                    Deleted prompts can be recovered to their old path for a
+                    number of days.
+                  </p>
+                </div>
+                <div>
+                  <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                    Days to Keep (0 = until recovered)
+                  </label>
+                  <input
+                    type="number"
+                    min={0}
+                    defaultValue={config.deletedRetentionDays}
+                    onBlur={(event) => {
+                      const value = Number.parseInt(event.target.value, 10);
+                      if (value >= 0 && value !== config.deletedRetentionDays) {
+                        saveConfig({ ...config, deletedRetentionDays: value });
+                      }
+                    }}
+                    className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                  />
+                </div>
+                {deletedPrompts.length > 0
+                  ? (
+                    <ul className="divide-y divide-panel-border text-sm">
+                      {deletedPrompts.map((deleted) => (
+                        <li
+                          key={deleted.id}
+                          className="flex items-center justify-between gap-3 py-2"
+                        >
+                          <span className="truncate text-neutral-900 dark:text-neutral-200">
+                            {deleted.title?.trim() || deleted.id}
+                            <span className="ml-2 text-xs text-neutral-500">
+                              {new Date(deleted.deletedAt).toLocaleString()}
+                            </span>
+                          </span>
+                          <button
+                            onClick={() => handleRecoverDeleted(deleted)}
+                            className="rounded-lg border border-panel-border px-3 py-1 text-xs font-medium text-neutral-900 transition hover:border-neutral-500 dark:text-neutral-100"
+                          >
+                            Recover
+                          </button>
+                        </li>
+                      ))}
+                    </ul>
+                  )
+                  : (
+                    <p className="text-xs text-neutral-500">
+                      No deleted prompts.
+                    </p>
+                  )}
+              </div>
+            )}
+
+            {config.vaultPath && (
+              <div className="space-y-3 rounded-lg border border-panel-border bg-panel p-4">
+                <div className="flex flex-col gap-3 sm:flex-row sm:items-center sm:justify-between">
+                  <div>
+                    <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                      Import Prompts
+                    </h3>
+                    <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                      Create prompt files from a JSON, CSV or YAML export.
+                    </p>
+                  </div>
+                  <div className="flex gap-2">
+                    <select
+                      value={importStrategy}
+                      onChange={(event) =>
+                        setImportStrategy(event.target.value as ImportStrategy)}
+                      title="When an imported prompt matches an existing id or title"
+                      className="rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+                    >
+                      <option value="skip">Skip existing</option>
+                      <option value="overwrite">Overwrite existing</option>
+                      <option value="duplicate">Keep both</option>
+                    </select>
+                    <button
+                      onClick={handleImport}
+                      disabled={isImporting}
+                      className={`rounded-lg px-4 py-2 text-sm font-semibold transition ${
+                        isImporting
+                          ? "cursor-not-allowed bg-blue-500/20 text-blue-300"
+                          : "bg-blue-600 text-white hover:bg-blue-500"
+                      }`}
+                    >
+                      {isImporting ? "Importing..." : "Import"}
+                    </button>
+                  </div>
+                </div>
+                <div className="flex flex-col gap-3 border-t border-panel-border pt-3 sm:flex-row sm:items-center sm:justify-between">
+                  <p className="text-xs text-neutral-600 dark:text-neutral-400">
+                    A .promptpack shared by a teammate (uses the strategy above)
+                  </p>
+                  <button
+                    onClick={handleImportPack}
+                    disabled={isImporting}
+                    className="rounded-lg border border-panel-border bg-panel-2 px-4 py-2 text-sm font-medium text-neutral-900 transition hover:bg-panel disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-200"
+                  >
+                    Import Prompt Pack
+                  </button>
+                </div>
+                <div className="flex flex-col gap-3 border-t border-panel-border pt-3 sm:flex-row sm:items-center sm:justify-between">
+                  <input
+                    type="text"
+                    value={gistUrl}
+                    onChange={(event) => setGistUrl(event.target.value)}
+                    placeholder="https://gist.github.com/user/id"
+                    className="min-w-0 flex-1 rounded-lg border border-panel-border bg-panel px-3 py-1.5 font-mono text-xs text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+                  />
+                  <button
+                    onClick={handleImportGist}
+                    disabled={isImporting || !gistUrl.trim()}
+                    className="rounded-lg border border-panel-border bg-panel-2 px-4 py-2 text-sm font-medium text-neutral-900 transition hover:bg-panel disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-200"
+                  >
+                    Import from Gist
+                  </button>
+                </div>
+                <div className="flex flex-col gap-3 border-t border-panel-border pt-3 sm:flex-row sm:items-center sm:justify-between">
+                  <label className="flex items-center gap-2 text-xs text-neutral-600 dark:text-neutral-400">
+                    <input
+                      type="checkbox"
+                      checked={chatGptFirstOnly}
+                      onChange={(event) =>
+                        setChatGptFirstOnly(event.target.checked)}
+                      className="h-4 w-4 accent-blue-600"
+                    />
+                    Only the first message of each ChatGPT conversation
+                  </label>
+                  <button
+                    onClick={handleImportChatGpt}
+                    disabled={isImporting}
+                    className="rounded-lg border border-panel-border bg-panel-2 px-4 py-2 text-sm font-medium text-neutral-900 transition hover:bg-panel disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-200"
+                  >
+                    Import ChatGPT Export
+                  </button>
+                </div>
+                <div className="flex flex-col gap-3 border-t border-panel-border pt-3 sm:flex-row sm:items-center sm:justify-between">
+                  <label className="flex items-center gap-2 text-xs text-neutral-600 dark:text-neutral-400">
+                    Obsidian notes tagged
+                    <input
+                      type="text"
+                      value={obsidianTag}
+                      onChange={(event) => setObsidianTag(event.target.value)}
+                      placeholder="prompt"
+                      className="w-32 rounded-lg border border-panel-border bg-panel px-3 py-1.5 font-mono text-xs text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+                    />
+                  </label>
+                  <button
+                    onClick={handleImportObsidian}
+                    disabled={isImporting || !obsidianTag.trim()}
+                    className="rounded-lg border border-panel-border bg-panel-2 px-4 py-2 text-sm font-medium text-neutral-900 transition hover:bg-panel disabled:cursor-not-allowed disabled:opacity-50 dark:text-neutral-200"
+                  >
+                    Import Obsidian Vault
+                  </button>
+                </div>
+                {importReport && (
+                  <div className="text-xs text-neutral-600 dark:text-neutral-400">
+                    <p>
+                      {(["created", "overwritten", "duplicated", "skipped", "failed"] as const)
+                        .map((status) =>
+                          `${status}: ${
+                            importReport.filter((item) => item.status === status)
+                              .length
+                          }`
+                        )
+                        .join(" · ")}
+                    </p>
+                    <ul className="mt-2 space-y-1">
+                      {importReport
+                        .filter((item) => item.status === "failed")
+                        .map((item, index) => (
+                          <li key={index} className="text-red-500">
+                            {item.title || item.sourceId || "Untitled"}:{" "}
+                            {item.message}
+                          </li>
+                        ))}
+                    </ul>
+                  </div>
+                )}
+              </div>
+            )}
+
+            {syncResult && (
+              <div className="rounded-lg border border-emerald-500/30 bg-emerald-500/10 p-4 text-sm text-emerald-200">
+                <p className="font-semibold">Sync Complete</p>
+                <ul className="mt-2 space-y-1 text-xs text-emerald-200/80">
+                  <li>Found: {syncResult.found} files</li>
+                  <li>Updated: {syncResult.updated} files in cache</li>
+                  <li>Deleted: {syncResult.deleted} orphaned entries</li>
+                </ul>
+                {syncResult.skipped.length > 0 && (
+                  <div className="mt-3 text-xs text-amber-300">
+                    <p className="font-semibold">
+                      Skipped {syncResult.skipped.length} files
+                    </p>
+                    <ul className="mt-1 space-y-1">
+                      {syncResult.skipped.map((file) => (
+                        <li key={file.path}>
+                          <span className="font-mono">{file.path}</span>:{" "}
+                          {file.message}
+                        </li>
+                      ))}
+                    </ul>
+                  </div>
+                )}
+              </div>
+            )}
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Cache Database
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  File (or folder) for the prompt cache, e.g. on a RAM disk.
+                  Leave empty to keep it in the app data folder.
+                </p>
+              </div>
+              <div className="flex flex-col gap-2 sm:flex-row">
+                <input
+                  type="text"
+                  value={databasePath}
+                  onChange={(event) => setDatabasePath(event.target.value)}
+                  placeholder="cache.db"
+                  className="flex-1 rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                />
+                <button
+                  onClick={handleApplyDatabasePath}
+                  disabled={databasePath.trim() === (config.databasePath ?? "")}
+                  className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-700 transition hover:border-neutral-500 disabled:opacity-50 dark:text-neutral-300"
+                >
+                  Move Cache
+                </button>
+              </div>
+              {databaseStatus && (
+                <p className="text-xs text-neutral-500 dark:text-neutral-500">
+                  {databaseStatus}
+                </p>
+              )}
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  File Names
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Template for new prompt files. Tokens:
+                  <span className="ml-1 font-mono">
+                    {"{title} {date} {time} {uuid} {random}"}
+                  </span>
+                </p>
+              </div>
+              <input
+                type="text"
+                value={filenameTemplate}
+                onChange={(event) => setFilenameTemplate(event.target.value)}
+                onBlur={commitFilenameTemplate}
+                onKeyDown={(event) => {
+                  if (event.key === "Enter") {
+                    event.currentTarget.blur();
+                  }
+                }}
+                placeholder={DEFAULT_FILENAME_TEMPLATE}
+                className="w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+              />
+              <p className="text-xs text-neutral-500 dark:text-neutral-500">
+                Used when a new prompt keeps its suggested file name, e.g.
+                <span className="ml-1 font-mono">{"{title}-{date}"}</span>.
+              </p>
+              <ConfigIssueList issues={configIssues} field="filenameTemplate" />
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  New Prompts
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Tags and frontmatter every new prompt file starts with.
+                </p>
+              </div>
+              <input
+                type="text"
+                defaultValue={config.defaultTags.join(", ")}
+                onBlur={(event) => commitDefaultTags(event.target.value)}
+                placeholder="prompts, draft"
+                className="w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+              />
+              <textarea
+                rows={3}
+                defaultValue={formatFrontmatterLines(config.defaultFrontmatter)}
+                onBlur={(event) => commitDefaultFrontmatter(event.target.value)}
+                placeholder="status: draft"
+                className="w-full rounded-lg border border-panel-border bg-panel px-3 py-2 font-mono text-xs text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+              />
+              <p className="text-xs text-neutral-500 dark:text-neutral-500">
+                One
+                <span className="mx-1 font-mono">key: value</span>per line;
+                numbers, booleans and lists in JSON keep their type.
+              </p>
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  File Extensions
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Comma-separated extensions of the files read as prompts, e.g.
+                  <span className="ml-1 font-mono">md, txt, prompt.md</span>.
+                  Files that are not Markdown hold only the prompt text, with no
+                  tags or other frontmatter.
+                </p>
+              </div>
+              <input
+                type="text"
+                value={fileExtensions}
+                onChange={(event) => setFileExtensions(event.target.value)}
+                onBlur={commitFileExtensions}
+                onKeyDown={(event) => {
+                  if (event.key === "Enter") {
+                    event.currentTarget.blur();
+                  }
+                }}
+                placeholder="md"
+                className="w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+              />
+              <label className="flex items-center justify-between gap-3 text-sm text-neutral-700 dark:text-neutral-200">
+                <span>Skip files larger than (KB, 0 for no limit)</span>
+                <input
+                  type="number"
+                  min={0}
+                  value={frontmatter.maxFileSizeKb}
+                  onChange={(event) =>
+                    updateFrontmatter({
+                      maxFileSizeKb: Math.max(
+                        0,
+                        Math.floor(Number(event.target.value) || 0),
+                      ),
+                    })}
+                  className="w-28 rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 dark:text-neutral-200"
+                />
+              </label>
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Copying
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  How prompts are prepared when copied from the list.
+                </p>
+              </div>
+              <label className="flex items-center justify-between gap-3 text-sm text-neutral-700 dark:text-neutral-200">
+                Trim surrounding blank lines and trailing spaces
+                <input
+                  type="checkbox"
+                  checked={config.copy.trimWhitespace}
+                  onChange={(event) =>
+                    saveConfig({
+                      ...config,
+                      copy: {
+                        ...config.copy,
+                        trimWhitespace: event.target.checked,
+                      },
+                    })}
+                  className="h-4 w-4 accent-blue-600"
+                />
+              </label>
+              <div className="grid grid-cols-2 gap-2">
+                {(
+                  [
+                    ["wrapPrefix", "Wrap Before"],
+                    ["wrapSuffix", "Wrap After"],
+                  ] as const
+                ).map(([key, label]) => (
+                  <div key={key}>
+                    <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                      {label}
+                    </label>
+                    <textarea
+                      rows={2}
+                      defaultValue={config.copy[key]}
+                      onBlur={(event) =>
+                        event.target.value !== config.copy[key] &&
+                        saveConfig({
+                          ...config,
+                          copy: { ...config.copy, [key]: event.target.value },
+                        })}
+                      className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-3 py-2 font-mono text-xs text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+                    />
+                  </div>
+                ))}
+              </div>
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Templates
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Besides your own variables, prompts can use
+                  <span className="mx-1 font-mono">{"{{date:%Y-%m-%d}}"}</span>,
+                  <span className="mx-1 font-mono">{"{{uuid}}"}</span>,
+                  <span className="mx-1 font-mono">{"{{clipboard}}"}</span>,
+                  <span className="mx-1 font-mono">{"{{env:VAR}}"}</span>
+                  and
+                  <span className="ml-1 font-mono">{"{{global:name}}"}</span>.
+                </p>
+              </div>
+              <div>
+                <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                  Allowed Environment Variables
+                </label>
+                <input
+                  type="text"
+                  defaultValue={config.templates.envAllowlist.join(", ")}
+                  onBlur={(event) => {
+                    const envAllowlist = event.target.value
+                      .split(",")
+                      .map((name) => name.trim())
+                      .filter(Boolean);
+                    if (
+                      envAllowlist.join(",") !==
+                        config.templates.envAllowlist.join(",")
+                    ) {
+                      saveConfig({ ...config, templates: { envAllowlist } });
+                    }
+                  }}
+                  placeholder="USER, PROJECT_NAME"
+                  className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 font-mono text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                />
+                <p className="mt-1 text-xs text-neutral-500 dark:text-neutral-500">
+                  Comma-separated. Other variables can't be read by templates.
+                </p>
+              </div>
+              <GlobalVariables />
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Lint
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Limits the Lint page warns about.
+                </p>
+              </div>
+              {(
+                [
+                  ["maxLineLength", "Max Line Length (characters)"],
+                  ["tokenBudget", "Token Budget"],
+                ] as const
+              ).map(([key, label]) => (
+                <div key={key}>
+                  <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                    {label}
+                  </label>
+                  <input
+                    type="number"
+                    min={1}
+                    defaultValue={config.lint[key]}
+                    onBlur={(event) => {
+                      const value = Math.floor(Number(event.target.value));
+                      if (value >= 1 && value !== config.lint[key]) {
+                        saveConfig({
+                          ...config,
+                          lint: { ...config.lint, [key]: value },
+                        });
+                      }
+                    }}
+                    className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                  />
+                </div>
+              ))}
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Running Prompts
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  OpenAI-compatible chat completions endpoint used by Run and
+                  tag suggestions. Every run is kept in the prompt's run
+                  history.
+                </p>
+              </div>
+              {(
+                [
+                  ["apiUrl", "Endpoint"],
+                  ["model", "Model"],
+                ] as const
+              ).map(([key, label]) => (
+                <div key={key}>
+                  <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                    {label}
+                  </label>
+                  <input
+                    type="text"
+                    defaultValue={config.llm[key]}
+                    onBlur={(event) =>
+                      event.target.value !== config.llm[key] &&
+                      saveConfig({
+                        ...config,
+                        llm: { ...config.llm, [key]: event.target.value },
+                      })}
+                    className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                  />
+                </div>
+              ))}
+              <SecretField name="llmApiKey" label="API Key (optional)" />
+              <button
+                type="button"
+                onClick={handleAutoTagUntagged}
+                disabled={isAutoTagging}
+                className="rounded-lg border border-panel-border px-4 py-2 text-sm text-neutral-700 transition hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-200 dark:hover:bg-neutral-800"
+              >
+                {isAutoTagging ? "Tagging..." : "Auto-tag Untagged Prompts"}
+              </button>
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  GitHub Gists
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Personal access token with the gist scope, used by Share in
+                  the prompt editor. Public gists can be imported without one.
+                </p>
+              </div>
+              <SecretField
+                name="githubToken"
+                label="Token"
+                placeholder="ghp_..."
+              />
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Quick Capture
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Tags are added to prompts saved with Capture Clipboard as
+                  Prompt in the command palette. The clipboard watcher offers
+                  to save long copies that contain one of the keywords.
+                </p>
+              </div>
+              {(
+                [
+                  ["tags", "Capture Tags", "inbox"],
+                  ["keywords", "Watcher Keywords", "you are, act as"],
+                ] as const
+              ).map(([key, label, placeholder]) => (
+                <div key={key}>
+                  <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                    {label}
+                  </label>
+                  <input
+                    type="text"
+                    defaultValue={config.capture[key].join(", ")}
+                    onBlur={(event) => {
+                      const values = event.target.value
+                        .split(",")
+                        .map((value) => value.trim())
+                        .filter(Boolean);
+                      if (values.join(",") !== config.capture[key].join(",")) {
+                        saveConfig({
+                          ...config,
+                          capture: { ...config.capture, [key]: values },
+                        });
+                      }
+                    }}
+                    placeholder={placeholder}
+                    className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 font-mono text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                  />
+                </div>
+              ))}
+              <div>
+                <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                  Watcher Minimum Length
+                </label>
+                <input
+                  type="number"
+                  min={1}
+                  defaultValue={config.capture.minLength}
+                  onBlur={(event) => {
+                    const minLength = Number.parseInt(event.target.value, 10);
+                    if (
+                      minLength > 0 && minLength !== config.capture.minLength
+                    ) {
+                      saveConfig({
+                        ...config,
+                        capture: { ...config.capture, minLength },
+                      });
+                    }
+                  }}
+                  className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                />
+              </div>
+              <label className="flex items-center justify-between gap-3 text-sm text-neutral-700 dark:text-neutral-200">
+                Watch the clipboard for prompts
+                <input
+                  type="checkbox"
+                  checked={config.capture.watchClipboard}
+                  onChange={(event) =>
+                    handleClipboardWatch(event.target.checked)}
+                  className="h-4 w-4 accent-blue-600"
+                />
+              </label>
+            </div>
+
+            <label className="flex items-center justify-between gap-3 rounded-xl border border-panel-border bg-panel p-4 text-sm text-neutral-700 dark:text-neutral-200">
+              <span>
+                <span className="block font-semibold text-neutral-900 dark:text-neutral-100">
+                  Git History
+                </span>
+                <span className="mt-1 block text-xs text-neutral-600 dark:text-neutral-500">
+                  Commit every prompt change to a git repository in the vault.
+                </span>
+              </span>
+              <input
+                type="checkbox"
+                checked={config.gitHistory}
+                onChange={(event) =>
+                  saveConfig({ ...config, gitHistory: event.target.checked })}
+                className="h-4 w-4 accent-blue-600"
+              />
+            </label>
+
+            <label className="flex items-center justify-between gap-3 rounded-xl border border-panel-border bg-panel p-4 text-sm text-neutral-700 dark:text-neutral-200">
+              <span>
+                <span className="block font-semibold text-neutral-900 dark:text-neutral-100">
+                  Share Notes
+                </span>
+                <span className="mt-1 block text-xs text-neutral-600 dark:text-neutral-500">
+                  Keep private prompt notes in shared gists and prompt packs.
+                </span>
+              </span>
+              <input
+                type="checkbox"
+                checked={config.shareNotes}
+                onChange={(event) =>
+                  saveConfig({ ...config, shareNotes: event.target.checked })}
+                className="h-4 w-4 accent-blue-600"
+              />
+            </label>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div className="flex items-center justify-between gap-3">
+                <div>
+                  <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                    Semantic Search
+                  </h3>
+                  <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                    How prompt embeddings are computed. The built-in model
+                    matches shared words; an API model also matches meaning.
+                  </p>
+                </div>
+                <select
+                  value={config.embeddings.provider}
+                  onChange={(event) =>
+                    updateEmbeddings({
+                      provider: event.target.value as "local" | "api",
+                    })}
+                  className="rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+                >
+                  <option value="local">Built-in</option>
+                  <option value="api">Embeddings API</option>
+                </select>
+              </div>
+              {config.embeddings.provider === "api" && (
+                <div className="space-y-2">
+                  {(
+                    [
+                      ["apiUrl", "Endpoint"],
+                      ["apiModel", "Model"],
+                    ] as const
+                  ).map(([key, label]) => (
+                    <div key={key}>
+                      <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                        {label}
+                      </label>
+                      <input
+                        type="text"
+                        value={embeddingApi[key]}
+                        onChange={(event) =>
+                          setEmbeddingApi((prev) => ({
+                            ...prev,
+                            [key]: event.target.value,
+                          }))}
+                        onBlur={() =>
+                          updateEmbeddings({ [key]: embeddingApi[key] })}
+                        className="mt-1 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                      />
+                    </div>
+                  ))}
+                  <SecretField
+                    name="embeddingsApiKey"
+                    label="API Key (optional)"
+                  />
+                </div>
+              )}
+            </div>
+
+            <div className="space-y-3 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Local API
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Serve prompts over HTTP on localhost for scripts and editors.
+                  {apiStatus?.running && (
+                    <span className="ml-1 font-mono">
+                      http://127.0.0.1:{apiStatus.port}/api/prompts
+                    </span>
+                  )}
+                </p>
+              </div>
+              {apiStatus?.running && apiStatus.token && (
+                <p className="text-xs text-neutral-600 dark:text-neutral-500">
+                  Token:{" "}
+                  <span className="select-all font-mono">{apiStatus.token}</span>
+                </p>
+              )}
+              <div className="flex items-center gap-2">
+                <input
+                  type="number"
+                  min={0}
+                  max={65535}
+                  value={apiPort}
+                  onChange={(event) => setApiPort(Number(event.target.value))}
+                  disabled={apiStatus?.running}
+                  title="Port (0 picks a free one)"
+                  className="w-28 rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none disabled:opacity-50 dark:text-neutral-200"
+                />
+                <input
+                  type="password"
+                  value={apiToken}
+                  onChange={(event) => setApiToken(event.target.value)}
+                  disabled={apiStatus?.running}
+                  placeholder="Bearer token (generated if empty)"
+                  className="flex-1 rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none disabled:opacity-50 dark:text-neutral-200"
+                />
+                <button
+                  onClick={handleToggleApiServer}
+                  className="rounded-lg bg-blue-600 px-4 py-2 text-sm font-semibold text-white transition hover:bg-blue-500"
+                >
+                  {apiStatus?.running ? "Stop" : "Start"}
+                </button>
+              </div>
+            </div>
+
+            <div className="space-y-4 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Frontmatter Tags
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Choose which frontmatter key stores prompt tags.
+                </p>
+              </div>
+              <div className="space-y-3">
+                <div>
+                  <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                    Prompt Tags Property
+                  </label>
+                  <input
+                    type="text"
+                    value={promptTagsProperty}
+                    onChange={(event) =>
+                      setPromptTagsProperty(event.target.value)}
+                    onBlur={commitPromptTagsProperty}
                     onKeyDown={(event) => {
                       if (event.key === "Enter") {
                         event.currentTarget.blur();
@@ -253,6 +2006,100 @@ function SettingsPage() {
                 </p>
               </div>
             </div>
+
+            <div className="space-y-4 rounded-xl border border-panel-border bg-panel p-4">
+              <div>
+                <h3 className="text-sm font-semibold text-neutral-900 dark:text-neutral-100">
+                  Frontmatter Keys
+                </h3>
+                <p className="mt-1 text-xs text-neutral-600 dark:text-neutral-500">
+                  Map prompt fields to your own frontmatter keys (e.g.
+                  <span className="mx-1 font-mono">date</span>or
+                  <span className="ml-1 font-mono">aliases</span>).
+                </p>
+              </div>
+              <div className="grid gap-3 sm:grid-cols-3">
+                {(Object.keys(DEFAULT_FIELD_KEYS) as FieldKey[]).map((key) => (
+                  <div key={key}>
+                    <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+                      {FIELD_KEY_LABELS[key]}
+                    </label>
+                    <input
+                      type="text"
+                      value={fieldKeys[key]}
+                      onChange={(event) =>
+                        setFieldKeys((prev) => ({
+                          ...prev,
+                          [key]: event.target.value,
+                        }))}
+                      onBlur={() => commitFieldKey(key)}
+                      onKeyDown={(event) => {
+                        if (event.key === "Enter") {
+                          event.currentTarget.blur();
+                        }
+                      }}
+                      placeholder={DEFAULT_FIELD_KEYS[key]}
+                      className="mt-2 w-full rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+                    />
+                  </div>
+                ))}
+              </div>
+              <ConfigIssueList issues={configIssues} field="frontmatter" />
+
+              <label className="flex items-center justify-between gap-3 rounded-lg border border-panel-border bg-panel-2 px-4 py-3 text-sm text-neutral-700 dark:text-neutral-200">
+                <span>
+                  Use the whole note body as the prompt (no
+                  <span className="mx-1 font-mono">```prompt</span>fence)
+                </span>
+                <input
+                  type="checkbox"
+                  checked={frontmatter.contentMode === "body"}
+                  onChange={(event) =>
+                    updateFrontmatter({
+                      contentMode: event.target.checked ? "body" : "fence",
+                    })}
+                  className="h-4 w-4 accent-blue-600"
+                />
+              </label>
+              <p className="text-xs text-neutral-500 dark:text-neutral-500">
+                A file can override this with
+                <span className="ml-1 font-mono">content_mode: body</span> or
+                <span className="ml-1 font-mono">fence</span> in its
+                frontmatter.
+              </p>
+
+              <label className="flex items-center justify-between gap-3 rounded-lg border border-panel-border bg-panel-2 px-4 py-3 text-sm text-neutral-700 dark:text-neutral-200">
+                <span>
+                  Link prompts through
+                  <span className="mx-1 font-mono">[[wikilinks]]</span>in
+                  their files
+                </span>
+                <input
+                  type="checkbox"
+                  checked={frontmatter.parseWikilinks}
+                  onChange={(event) =>
+                    updateFrontmatter({
+                      parseWikilinks: event.target.checked,
+                    })}
+                  className="h-4 w-4 accent-blue-600"
+                />
+              </label>
+
+              <label className="flex items-center justify-between gap-3 rounded-lg border border-panel-border bg-panel-2 px-4 py-3 text-sm text-neutral-700 dark:text-neutral-200">
+                <span>
+                  Save files with LF line endings and no byte order mark
+                </span>
+                <input
+                  type="checkbox"
+                  checked={frontmatter.normalizeLineEndings}
+                  onChange={(event) =>
+                    updateFrontmatter({
+                      normalizeLineEndings: event.target.checked,
+                    })}
+                  className="h-4 w-4 accent-blue-600"
+                />
+              </label>
+            </div>
           </div>
         </section>
 
@@ -296,10 +2143,179 @@ function SettingsPage() {
                 );
               })}
             </div>
+            <ConfigIssueList issues={configIssues} field="theme" />
           </div>
         </section>
+
+        <section className="space-y-6 rounded-2xl border border-panel-border bg-panel p-6 text-neutral-900 dark:text-neutral-100">
+          <div>
+            <h2 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100">
+              Diagnostics
+            </h2>
+            <p className="mt-1 text-sm text-neutral-600 dark:text-neutral-500">
+              A week of logs is kept in the app data folder. Copy recent
+              entries to attach them to a bug report.
+            </p>
+          </div>
+
+          <div className="flex flex-wrap items-center justify-between gap-3">
+            <label className="flex items-center gap-3 text-sm text-neutral-700 dark:text-neutral-200">
+              Record
+              <select
+                value={logLevel}
+                onChange={(event) =>
+                  handleLogLevelChange(event.target.value as LogLevel)}
+                className="rounded-lg border border-panel-border bg-panel px-3 py-2 text-sm text-neutral-900 focus:border-blue-500 focus:outline-none dark:text-neutral-200"
+              >
+                {(Object.keys(LOG_LEVEL_LABELS) as LogLevel[]).map((level) => (
+                  <option key={level} value={level}>
+                    {LOG_LEVEL_LABELS[level]}
+                  </option>
+                ))}
+              </select>
+            </label>
+            <button
+              type="button"
+              onClick={handleCopyLogs}
+              className="rounded-lg border border-panel-border px-4 py-2 text-sm font-medium text-neutral-900 transition hover:border-neutral-500 dark:text-neutral-100"
+            >
+              Copy Recent Logs
+            </button>
+          </div>
+          {logStatus && (
+            <p className="text-xs text-neutral-500 dark:text-neutral-500">
+              {logStatus}
+            </p>
+          )}
+        </section>
       </div>
     </div>
     </div>
   );
 }
+
+interface ConfigIssueListProps {
+  issues: ConfigIssue[];
+  field: string;
+}
+
+// Issues `validateConfig` found with one setting
+function ConfigIssueList({ issues, field }: ConfigIssueListProps) {
+  const fieldIssues = issues.filter((issue) => issue.field === field);
+  if (fieldIssues.length === 0) return null;
+  return (
+    <ul className="mt-2 space-y-1 text-xs">
+      {fieldIssues.map((issue, index) => (
+        <li
+          key={index}
+          className={issue.severity === "error"
+            ? "text-red-500"
+            : "text-amber-500"}
+        >
+          {issue.message}
+        </li>
+      ))}
+    </ul>
+  );
+}
+
+interface SecretFieldProps {
+  name: SecretName;
+  label: string;
+  placeholder?: string;
+}
+
+// Write-only input for a key kept in the OS keyring: shows whether one is
+// stored, never its value
+function SecretField({ name, label, placeholder }: SecretFieldProps) {
+  const [stored, setStored] = useState(false);
+  const [value, setValue] = useState("");
+
+  useEffect(() => {
+    promptManagerService.hasSecret(name).then(setStored).catch((e) => {
+      console.error(`Failed to check ${name}:`, e);
+    });
+  }, [name]);
+
+  const handleSave = async () => {
+    try {
+      await promptManagerService.setSecret(name, value);
+      setStored(true);
+      setValue("");
+    } catch (e) {
+      console.error(`Failed to store ${name}:`, e);
+      alert("Failed to store key: " + e);
+    }
+  };
+
+  const handleClear = async () => {
+    try {
+      await promptManagerService.deleteSecret(name);
+      setStored(false);
+    } catch (e) {
+      console.error(`Failed to remove ${name}:`, e);
+      alert("Failed to remove key: " + e);
+    }
+  };
+
+  return (
+    <div>
+      <label className="block text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+        {label}
+      </label>
+      <div className="mt-1 flex items-center gap-2">
+        <input
+          type="password"
+          value={value}
+          onChange={(event) => setValue(event.target.value)}
+          placeholder={stored ? "Stored in the system keyring" : placeholder}
+          className="flex-1 rounded-lg border border-panel-border bg-panel px-4 py-2 text-sm text-neutral-900 placeholder:text-neutral-400 focus:border-blue-500 focus:outline-none focus:ring-2 focus:ring-blue-500/20 dark:text-neutral-200"
+        />
+        <button
+          type="button"
+          onClick={handleSave}
+          disabled={!value.trim()}
+          className="rounded-lg border border-panel-border px-3 py-2 text-sm text-neutral-700 transition hover:bg-neutral-100 disabled:opacity-50 dark:text-neutral-200 dark:hover:bg-neutral-800"
+        >
+          Save
+        </button>
+        {stored && (
+          <button
+            type="button"
+            onClick={handleClear}
+            className="rounded-lg border border-panel-border px-3 py-2 text-sm text-neutral-700 transition hover:bg-neutral-100 dark:text-neutral-200 dark:hover:bg-neutral-800"
+          >
+            Clear
+          </button>
+        )}
+      </div>
+    </div>
+  );
+}
+
+// `key: value` lines; values that parse as JSON (numbers, booleans, lists) keep
+// their type, anything else is a string
+function parseFrontmatterLines(text: string): Record<string, unknown> {
+  const fields: Record<string, unknown> = {};
+  for (const line of text.split("\n")) {
+    const index = line.indexOf(":");
+    const key = line.slice(0, index).trim();
+    if (index === -1 || !key) continue;
+    const raw = line.slice(index + 1).trim();
+    try {
+      const value = JSON.parse(raw);
+      fields[key] = value === null ? raw : value;
+    } catch {
+      fields[key] = raw;
+    }
+  }
+  return fields;
+}
+
+function formatFrontmatterLines(fields: Record<string, unknown>): string {
+  return Object.entries(fields)
+    .map(([key, value]) =>
+      `${key}: ${typeof value === "string" ? value : JSON.stringify(value)}`
+    )
+    .join("\n");
+}
@@ -0,0 +1,108 @@
+import { createFileRoute } from "@tanstack/react-router";
+import { useEffect, useState } from "react";
+import { FiArchive } from "react-icons/fi";
+import { Prompt } from "@/schemas/schemas.ts";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+import { useToast } from "@/components/ui/ToastProvider.tsx";
+
+export const Route = createFileRoute("/stale")({
+  component: StalePromptsPage,
+});
+
+const DAY_OPTIONS = [30, 90, 180, 365];
+
+function promptLabel(prompt: Prompt): string {
+  return prompt.title?.trim() || prompt.text.split("\n")[0].trim() ||
+    prompt.id;
+}
+
+function StalePromptsPage() {
+  const { pushToast } = useToast();
+  const [days, setDays] = useState(90);
+  const [prompts, setPrompts] = useState<Prompt[]>([]);
+  const [isLoading, setIsLoading] = useState(false);
+
+  useEffect(() => {
+    setIsLoading(true);
+    promptManagerService.getStalePrompts(days)
+      .then(setPrompts)
+      .catch((error) => {
+        console.error("Failed to load stale prompts", error);
+      })
+      .finally(() => setIsLoading(false));
+  }, [days]);
+
+  const handleArchive = async (prompt: Prompt) => {
+    try {
+      await promptManagerService.setArchived(prompt.id, true);
+      setPrompts((prev) => prev.filter((item) => item.id !== prompt.id));
+    } catch (error) {
+      pushToast({
+        title: "Archive failed",
+        description: String(error),
+        variant: "error",
+      });
+    }
+  };
+
+  return (
+    <div className="flex min-h-full w-full flex-col bg-main-background">
+      <div className="mx-auto w-full max-w-5xl space-y-6 p-8 pb-12">
+        <div className="flex flex-wrap items-end justify-between gap-4">
+          <div>
+            <p className="text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+              Review
+            </p>
+            <h1 className="text-3xl font-semibold text-neutral-900 dark:text-neutral-100">
+              Stale Prompts
+            </h1>
+            <p className="mt-2 text-sm text-neutral-600 dark:text-neutral-500">
+              Prompts nobody has copied or edited in a while. Archive the ones
+              you no longer need.
+            </p>
+          </div>
+          <select
+            value={days}
+            onChange={(event) => setDays(Number(event.target.value))}
+            className="rounded-md border border-panel-border bg-panel px-2 py-1 text-neutral-700 text-sm dark:text-neutral-300"
+          >
+            {DAY_OPTIONS.map((option) => (
+              <option key={option} value={option}>
+                Untouched for {option} days
+              </option>
+            ))}
+          </select>
+        </div>
+
+        <section className="rounded-2xl border border-panel-border bg-panel p-6">
+          {!isLoading && prompts.length === 0 && (
+            <p className="text-neutral-500 text-sm">Nothing stale.</p>
+          )}
+          <ul className="space-y-1">
+            {prompts.map((prompt) => (
+              <li
+                key={prompt.id}
+                className="flex items-center justify-between gap-2 text-neutral-700 text-sm dark:text-neutral-300"
+              >
+                <span className="truncate">{promptLabel(prompt)}</span>
+                <div className="flex shrink-0 items-center gap-2">
+                  <span className="text-neutral-500 text-xs tabular-nums">
+                    {prompt.updatedAt ?? prompt.created}
+                  </span>
+                  <button
+                    type="button"
+                    onClick={() => handleArchive(prompt)}
+                    className="flex items-center gap-1 px-2 py-1 text-neutral-600 text-xs hover:bg-neutral-100 dark:text-neutral-400 dark:hover:bg-neutral-700"
+                  >
+                    <FiArchive size={12} />
+                    Archive
+                  </button>
+                </div>
+              </li>
+            ))}
+          </ul>
+        </section>
+      </div>
+    </div>
+  );
+}
@@ -0,0 +1,95 @@
+import { createFileRoute } from "@tanstack/react-router";
+import { useEffect, useMemo, useState } from "react";
+import { TagGraph } from "@/bindings.ts";
+import { TagGraphView } from "@/components/tags/TagGraphView.tsx";
+import { promptManagerService } from "@/services/PromptManagerService.ts";
+
+export const Route = createFileRoute("/tag_graph")({
+  component: TagGraphPage,
+});
+
+/** Pairs overlapping at least this much are listed as possible synonyms */
+const SIMILARITY_THRESHOLD = 0.6;
+
+function TagGraphPage() {
+  const [graph, setGraph] = useState<TagGraph | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    promptManagerService.getTagGraph()
+      .then(setGraph)
+      .catch((err) => {
+        setError(err instanceof Error ? err.message : String(err));
+      });
+  }, []);
+
+  const similarPairs = useMemo(
+    () =>
+      (graph?.edges ?? [])
+        .filter((edge) => edge.similarity >= SIMILARITY_THRESHOLD)
+        .sort((a, b) => b.similarity - a.similarity || b.weight - a.weight),
+    [graph],
+  );
+
+  return (
+    <div className="flex min-h-full w-full flex-col bg-main-background">
+      <div className="mx-auto w-full max-w-5xl space-y-6 p-8 pb-12">
+        <div>
+          <p className="text-xs uppercase tracking-[0.2em] text-neutral-500 dark:text-neutral-400">
+            Tags
+          </p>
+          <h1 className="text-3xl font-semibold text-neutral-900 dark:text-neutral-100">
+            Tag Graph
+          </h1>
+          <p className="mt-2 text-sm text-neutral-600 dark:text-neutral-500">
+            Tags linked by the prompts they share. Thicker lines mean more
+            shared prompts.
+          </p>
+        </div>
+
+        {error && (
+          <div className="rounded-lg border border-red-200 bg-red-50 p-3 text-red-700 text-sm dark:border-red-800 dark:bg-red-900/20 dark:text-red-400">
+            {error}
+          </div>
+        )}
+
+        {graph && <TagGraphView graph={graph} />}
+
+        {graph && (
+          <section className="rounded-2xl border border-panel-border bg-panel p-6">
+            <h2 className="text-lg font-semibold text-neutral-900 dark:text-neutral-100">
+              Possibly Redundant Tags
+            </h2>
+            <p className="mt-1 text-sm text-neutral-600 dark:text-neutral-500">
+              Pairs that mostly appear on the same prompts.
+            </p>
+            {similarPairs.length === 0
+              ? (
+                <p className="mt-3 text-neutral-500 text-sm">
+                  No strongly overlapping tags.
+                </p>
+              )
+              : (
+                <ul className="mt-3 space-y-1 text-sm">
+                  {similarPairs.map((edge) => (
+                    <li
+                      key={`${edge.source}\u0000${edge.target}`}
+                      className="flex items-center justify-between gap-2 text-neutral-700 dark:text-neutral-300"
+                    >
+                      <span className="truncate font-mono">
+                        {edge.source} ↔ {edge.target}
+                      </span>
+                      <span className="shrink-0 text-neutral-500 text-xs tabular-nums">
+                        {Math.round(edge.similarity * 100)}% overlap ·{" "}
+                        {edge.weight} prompts
+                      </span>
+                    </li>
+                  ))}
+                </ul>
+              )}
+          </section>
+        )}
+      </div>
+    </div>
+  );
+}
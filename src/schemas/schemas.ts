@@ -1,5 +1,24 @@
 import { z } from "zod";
 
+export const TemplateVariableSchema = z.object({
+  name: z.string(),
+  default: z.unknown().optional(),
+  required: z.boolean().optional(),
+  options: z.array(z.string()).optional(),
+  description: z.string().nullable().optional(),
+});
+
+export type TemplateVariable = z.infer<typeof TemplateVariableSchema>;
+
+export const PromptStatusSchema = z.enum([
+  "draft",
+  "testing",
+  "production",
+  "deprecated",
+]);
+
+export type PromptStatus = z.infer<typeof PromptStatusSchema>;
+
 export const PromptSchema = z.object({
   id: z.string(),
   created: z.string().nullable(),
@@ -8,20 +27,89 @@ export const PromptSchema = z.object({
   filePath: z.string().nullable().optional(),
   previousFilePath: z.string().nullable().optional(),
   title: z.string().nullable().optional(),
-  description: z.string().nullable().optional(),
-});
+  description: z.string().nullable().optional(),
+  fileHash: z.string().nullable().optional(),
+  customFields: z.record(z.unknown()).optional(),
+  tokenCount: z.number().nullable().optional(),
+  variant: z.string().nullable().optional(),
+  archived: z.boolean().optional(),
+  updatedAt: z.string().nullable().optional(),
+  variables: z.array(TemplateVariableSchema).optional(),
+  revision: z.number().optional(),
+  color: z.string().nullable().optional(),
+  icon: z.string().nullable().optional(),
+  notes: z.string().nullable().optional(),
+  modelTargets: z.array(z.string()).optional(),
+  language: z.string().nullable().optional(),
+  status: PromptStatusSchema.nullable().optional(),
+  charCount: z.number().nullable().optional(),
+  wordCount: z.number().nullable().optional(),
+  lineCount: z.number().nullable().optional(),
+});
 
 export type Prompt = z.infer<typeof PromptSchema>;
 
+const SortKeySchema = z.enum([
+  "created",
+  "updated",
+  "title",
+  "usage_count",
+  "last_used",
+  "char_count",
+  "word_count",
+  "line_count",
+  "token_count",
+]);
+
+export const SearchFieldSchema = z.enum(["title", "description", "text"]);
+
+export type SearchField = z.infer<typeof SearchFieldSchema>;
+
+export const SearchConfigSchema = z.object({
+  query: z.string(),
+  caseSensitive: z.boolean().optional(),
+  wholeWord: z.boolean().optional(),
+  // Empty means the title and text
+  fields: z.array(SearchFieldSchema).optional(),
+});
+
+export type SearchConfig = z.infer<typeof SearchConfigSchema>;
+
 export const ViewConfigSchema = z.object({
   filter: z.object({
     tags: z.array(z.string()).optional(),
-    search: z.string().optional(),
+    // Whether prompts need all of the tags or any one of them
+    tagMode: z.enum(["and", "or"]).optional(),
+    search: SearchConfigSchema.optional(),
     favorite: z.boolean().optional(),
+    untagged: z.boolean().optional(),
+    missingTitle: z.boolean().optional(),
+    createdAfter: z.string().optional(),
+    createdBefore: z.string().optional(),
+    updatedAfter: z.string().optional(),
+    archived: z.boolean().optional(),
+    fields: z.record(z.string()).optional(),
+    modelTarget: z.string().optional(),
+    language: z.string().optional(),
+    status: PromptStatusSchema.optional(),
+    minWords: z.number().int().nonnegative().optional(),
+    maxWords: z.number().int().nonnegative().optional(),
   }).optional(),
   sort: z.object({
-    by: z.enum(["created"]),
+    by: SortKeySchema,
     order: z.enum(["asc", "desc"]),
+    then: z.array(z.object({
+      by: SortKeySchema,
+      order: z.enum(["asc", "desc"]),
+    })).optional(),
+  }).optional(),
+  layout: z.object({
+    mode: z.enum(["card", "list", "board"]),
+    columns: z.array(
+      z.enum(["title", "tags", "created", "updated", "tokens", "words"]),
+    ).optional(),
+    previewLength: z.number().int().min(1).max(50).optional(),
+    groupByTag: z.boolean(),
   }).optional(),
 });
 
@@ -37,19 +125,97 @@ export const ViewSchema = z.object({
 
 export type View = z.infer<typeof ViewSchema>;
 
+export const ViewSettingsSchema = z.object({
+  showPromptTitles: z.boolean(),
+  showFullPrompt: z.boolean(),
+  showPromptTags: z.boolean(),
+  showCreatedDate: z.boolean(),
+});
+
+export const FrontmatterSettingsSchema = z.object({
+  promptTagsProperty: z.string(),
+  addPromptsTagToTags: z.boolean(),
+  createdProperty: z.string(),
+  titleProperty: z.string(),
+  descriptionProperty: z.string(),
+  contentMode: z.enum(["fence", "body"]),
+  parseWikilinks: z.boolean(),
+  fileExtensions: z.array(z.string()),
+  maxFileSizeKb: z.number().int().nonnegative(),
+  normalizeLineEndings: z.boolean(),
+});
+
+// Vault, frontmatter and view settings saved under a name
+export const ProfileSchema = z.object({
+  name: z.string().min(1),
+  vaultPath: z.string().nullable(),
+  frontmatter: FrontmatterSettingsSchema,
+  view: ViewSettingsSchema,
+});
+
+export type Profile = z.infer<typeof ProfileSchema>;
+
 export const AppConfigSchema = z.object({
   vaultPath: z.string().nullable(),
   theme: z.string(),
-  view: z.object({
-    showPromptTitles: z.boolean(),
-    showFullPrompt: z.boolean(),
-    showPromptTags: z.boolean(),
-    showCreatedDate: z.boolean(),
+  view: ViewSettingsSchema,
+  frontmatter: FrontmatterSettingsSchema,
+  filenameTemplate: z.string(),
+  defaultTags: z.array(z.string()),
+  defaultFrontmatter: z.record(z.unknown()),
+  gitHistory: z.boolean(),
+  deletedRetentionDays: z.number(),
+  shareNotes: z.boolean(),
+  embeddings: z.object({
+    provider: z.enum(["local", "api"]),
+    apiUrl: z.string(),
+    apiModel: z.string(),
+  }),
+  copy: z.object({
+    trimWhitespace: z.boolean(),
+    wrapPrefix: z.string(),
+    wrapSuffix: z.string(),
   }),
-  frontmatter: z.object({
-    promptTagsProperty: z.string(),
-    addPromptsTagToTags: z.boolean(),
+  llm: z.object({
+    apiUrl: z.string(),
+    model: z.string(),
   }),
+  templates: z.object({
+    envAllowlist: z.array(z.string()),
+  }),
+  lint: z.object({
+    maxLineLength: z.number().int().min(1),
+    tokenBudget: z.number().int().min(1),
+  }),
+  capture: z.object({
+    tags: z.array(z.string()),
+    watchClipboard: z.boolean(),
+    minLength: z.number().int().min(1),
+    keywords: z.array(z.string()),
+  }),
+  remote: z.object({
+    backend: z.enum(["local", "webDav", "s3"]),
+    webdav: z.object({
+      url: z.string(),
+      username: z.string().nullable(),
+    }),
+    s3: z.object({
+      endpoint: z.string(),
+      region: z.string(),
+      bucket: z.string(),
+      prefix: z.string(),
+      accessKeyId: z.string().nullable(),
+    }),
+  }),
+  backup: z.object({
+    enabled: z.boolean(),
+    directory: z.string().nullable(),
+    intervalHours: z.number(),
+    retention: z.number(),
+  }),
+  profiles: z.array(ProfileSchema),
+  activeProfile: z.string().nullable(),
+  databasePath: z.string().nullable(),
 });
 
 export type AppConfig = z.infer<typeof AppConfigSchema>;
@@ -62,6 +228,9 @@ export const PromptFileSchema = z.object({
   content: z.string(),
   fileHash: z.string().nullable().optional(),
   description: z.string().nullable().optional(),
-});
+  customFields: z.record(z.unknown()).optional(),
+  variants: z.array(z.object({ name: z.string(), content: z.string() }))
+    .optional(),
+});
 
 export type PromptFile = z.infer<typeof PromptFileSchema>;
@@ -3,25 +3,121 @@
  */
 
 import {
+  ApiServerStatus,
   AppConfig as RsAppConfig,
+  AppError,
+  BackupInfo,
+  ChatGptImportOptions,
   commands,
+  ConfigIssue,
+  DeletedPrompt,
+  ErrorKind,
+  ExportFormat,
+  ExportTool,
+  FrontmatterSettings as RsFrontmatterSettings,
+  FuzzyPromptMatch as RsFuzzyPromptMatch,
+  GroupBy,
+  ImportItemReport,
+  ImportStrategy,
+  JsonValue,
+  LinkedPrompt as RsLinkedPrompt,
+  LogEntry,
+  LogLevel,
+  MatrixOutput,
+  PageRequest,
+  Profile as RsProfile,
   Prompt as RsPrompt,
+  PromptCommit,
+  PromptDiff,
   PromptFile as RsPromptFile,
+  PromptGroup as RsPromptGroup,
   PromptInput as RsPromptInput,
+  PromptLint,
+  PromptRun,
+  PromptSummaryPage,
+  PromptTest,
+  PromptTestInput,
+  PromptTestResult,
+  RecentKind,
+  RemoteSyncStats,
+  RenderedPrompt,
+  RepairKind,
+  RevisionConflict,
+  SamplePack,
+  SaveConflict,
+  SearchHit as RsSearchHit,
+  SecretName,
+  SavePromptResult,
+  SharedGist,
+  SplitBy,
   SyncStats,
+  TagGraph,
+  TagNode,
+  TagSuggestions,
+  TemplatePreset,
+  TokenCount,
+  UndoneOperation,
+  UndoStatus,
+  VaultHealthReport,
+  VaultRepairReport,
+  VaultWatchStatus,
   View as RsView,
   ViewConfig as RsViewConfig,
   ViewInput as RsViewInput,
+  ViewSettings as RsViewSettings,
 } from "@/bindings.ts";
-import { AppConfig, Prompt, View, ViewConfig } from "@/schemas/schemas.ts";
+import {
+  AppConfig,
+  Profile,
+  Prompt,
+  PromptStatus,
+  View,
+  ViewConfig,
+} from "@/schemas/schemas.ts";
+
+/** Fuzzy match with char indices to highlight in the title and file name */
+export type FuzzyPromptMatch = Omit<RsFuzzyPromptMatch, "prompt"> & {
+  prompt: Prompt;
+};
+
+/** Prompt matched by a search, with char ranges of the hits to highlight */
+export type SearchHit = Omit<RsSearchHit, "prompt"> & { prompt: Prompt };
+
+/** Prompt linked to or from another one */
+export type LinkedPrompt = Omit<RsLinkedPrompt, "prompt"> & { prompt: Prompt };
+
+/** Prompts sharing a tag, created month, model target or folder */
+export type PromptGroup = Omit<RsPromptGroup, "prompts"> & {
+  prompts: Prompt[];
+};
+
+const DEFAULT_EMBEDDING_API_URL = "http://localhost:11434/v1/embeddings";
+const DEFAULT_EMBEDDING_API_MODEL = "nomic-embed-text";
+const DEFAULT_LLM_API_URL = "http://localhost:11434/v1/chat/completions";
+const DEFAULT_LLM_MODEL = "llama3.2";
+
+// Error thrown for a failed command; branch on `kind` (e.g. "vaultNotConfigured")
+export class CommandError extends Error {
+  readonly kind: ErrorKind;
+  readonly conflict: SaveConflict | null;
+  readonly revisionConflict: RevisionConflict | null;
+
+  constructor(error: AppError) {
+    super(error.message);
+    this.name = "CommandError";
+    this.kind = error.kind;
+    this.conflict = error.conflict;
+    this.revisionConflict = error.revisionConflict;
+  }
+}
 
 // Helper to unwrap Tauri Result
 function unwrap<T>(
-  result: { status: "ok"; data: T } | { status: "error"; error: unknown },
+  result: { status: "ok"; data: T } | { status: "error"; error: AppError },
 ): T {
   if (result.status === "error") {
-    console.error("Database operation failed:", result.error);
-    throw new Error(JSON.stringify(result.error));
+    console.error("Command failed:", result.error);
+    throw new CommandError(result.error);
   }
   return result.data;
 }
@@ -44,21 +140,85 @@ class TauriPromptManagerService {
     return data.map(this.mapPromptFromRust);
   }
 
+  async getPromptGroups(groupBy: GroupBy, options?: {
+    filter?: ViewConfig["filter"];
+    sort?: ViewConfig["sort"];
+  }): Promise<PromptGroup[]> {
+    const res = await commands.getPromptGroups(
+      options?.filter ?? null,
+      options?.sort ?? null,
+      groupBy,
+    );
+    return unwrap(res).map((group) => ({
+      ...group,
+      prompts: group.prompts.map(this.mapPromptFromRust),
+    }));
+  }
+
+  // Metadata and excerpts only, for lists; load the full prompt with getPrompt
+  async getPromptSummaries(options?: {
+    filter?: ViewConfig["filter"];
+    sort?: ViewConfig["sort"];
+    page?: PageRequest;
+  }): Promise<PromptSummaryPage> {
+    const res = await commands.getPromptSummaries(
+      options?.filter ?? null,
+      options?.sort ?? null,
+      options?.page ?? null,
+    );
+    return unwrap(res);
+  }
+
+  async getPrompt(id: string): Promise<Prompt | null> {
+    const res = await commands.getPrompt(id);
+    const data = unwrap(res);
+    return data ? this.mapPromptFromRust(data) : null;
+  }
+
+  async searchPrompts(
+    filter: NonNullable<ViewConfig["filter"]>,
+    sort?: ViewConfig["sort"],
+  ): Promise<SearchHit[]> {
+    const res = await commands.searchPrompts(filter, sort ?? null);
+    return unwrap(res).map((hit) => ({
+      ...hit,
+      prompt: this.mapPromptFromRust(hit.prompt),
+    }));
+  }
+
+  async fuzzySearchPrompts(
+    query: string,
+    limit: number,
+  ): Promise<FuzzyPromptMatch[]> {
+    const res = await commands.fuzzySearchPrompts(query, limit);
+    return unwrap(res).map((match) => ({
+      ...match,
+      prompt: this.mapPromptFromRust(match.prompt),
+    }));
+  }
+
+  async getRecentPrompts(kind: RecentKind, limit: number): Promise<Prompt[]> {
+    const res = await commands.getRecentPrompts(kind, limit);
+    return unwrap(res).map(this.mapPromptFromRust);
+  }
+
+  async getStalePrompts(days: number): Promise<Prompt[]> {
+    const res = await commands.getStalePrompts(days);
+    return unwrap(res).map(this.mapPromptFromRust);
+  }
+
   async savePrompt(prompt: Prompt): Promise<void> {
-    const input = {
-      id: prompt.id,
-      created: prompt.created,
-      text: prompt.text,
-      tags: prompt.tags,
-      filePath: prompt.filePath ?? null,
-      previousFilePath: prompt.previousFilePath ?? null,
-      title: prompt.title ?? null,
-      description: prompt.description ?? null,
-    } as RsPromptInput;
-
-    const res = await commands.savePrompt(input);
+    const res = await commands.savePrompt(this.mapPromptToRustInput(prompt));
     unwrap(res);
   }
+
+  // Saves in one batch; failures are reported per prompt instead of thrown
+  async savePrompts(prompts: Prompt[]): Promise<SavePromptResult[]> {
+    const res = await commands.savePrompts(
+      prompts.map((prompt) => this.mapPromptToRustInput(prompt)),
+    );
+    return unwrap(res);
+  }
 
   async deletePrompt(id: string): Promise<void> {
     const res = await commands.deletePrompt(id);
@@ -71,6 +231,131 @@ class TauriPromptManagerService {
     return data ? this.mapPromptFromRust(data) : null;
   }
 
+  async renamePrompt(id: string, newPath: string): Promise<void> {
+    const res = await commands.renamePrompt(id, newPath);
+    unwrap(res);
+  }
+
+  async setArchived(id: string, archived: boolean): Promise<void> {
+    const res = await commands.setArchived(id, archived);
+    unwrap(res);
+  }
+
+  async setPromptStatus(id: string, status: PromptStatus): Promise<void> {
+    const res = await commands.setPromptStatus(id, status);
+    unwrap(res);
+  }
+
+  // Returns the ids of the new prompts; the original is archived
+  async splitPrompt(id: string, split: SplitBy): Promise<string[]> {
+    const res = await commands.splitPrompt(id, split);
+    return unwrap(res);
+  }
+
+  async listRecentlyDeleted(): Promise<DeletedPrompt[]> {
+    const res = await commands.listRecentlyDeleted();
+    return unwrap(res);
+  }
+
+  async recoverDeletedPrompt(id: string): Promise<Prompt | null> {
+    const res = await commands.recoverDeletedPrompt(id);
+    const data = unwrap(res);
+    return data ? this.mapPromptFromRust(data) : null;
+  }
+
+  // Null when there was nothing to undo
+  async undoLastOperation(): Promise<UndoneOperation | null> {
+    const res = await commands.undoLastOperation();
+    return unwrap(res);
+  }
+
+  // Null when there was nothing to redo
+  async redo(): Promise<UndoneOperation | null> {
+    const res = await commands.redo();
+    return unwrap(res);
+  }
+
+  async getUndoStatus(): Promise<UndoStatus> {
+    const res = await commands.getUndoStatus();
+    return unwrap(res);
+  }
+
+  async exportPrompts(
+    format: ExportFormat,
+    filter: ViewConfig["filter"] | null,
+    destinationPath: string,
+  ): Promise<number> {
+    const res = await commands.exportPrompts(
+      format,
+      (filter ?? null) as RsViewConfig["filter"],
+      destinationPath,
+    );
+    return unwrap(res);
+  }
+
+  // Includes are expanded and placeholders converted to the tool's template syntax
+  async exportPromptsFor(
+    tool: ExportTool,
+    filter: ViewConfig["filter"] | null,
+    destinationPath: string,
+  ): Promise<number> {
+    const res = await commands.exportPromptsFor(
+      tool,
+      (filter ?? null) as RsViewConfig["filter"],
+      destinationPath,
+    );
+    return unwrap(res);
+  }
+
+  async importPrompts(
+    path: string,
+    format: ExportFormat,
+    strategy: ImportStrategy,
+  ): Promise<ImportItemReport[]> {
+    const res = await commands.importPrompts(path, format, strategy);
+    return unwrap(res);
+  }
+
+  async exportPromptpack(ids: string[], path: string): Promise<number> {
+    const res = await commands.exportPromptpack(ids, path);
+    return unwrap(res);
+  }
+
+  async importPromptpack(
+    path: string,
+    strategy: ImportStrategy,
+  ): Promise<ImportItemReport[]> {
+    const res = await commands.importPromptpack(path, strategy);
+    return unwrap(res);
+  }
+
+  // Needs a GitHub token in settings; secret unless `isPublic`
+  async sharePromptAsGist(id: string, isPublic: boolean): Promise<SharedGist> {
+    const res = await commands.sharePromptAsGist(id, isPublic);
+    return unwrap(res);
+  }
+
+  async importPromptFromGist(url: string): Promise<ImportItemReport> {
+    const res = await commands.importPromptFromGist(url);
+    return unwrap(res);
+  }
+
+  async importChatGptExport(
+    path: string,
+    options: ChatGptImportOptions,
+  ): Promise<ImportItemReport[]> {
+    const res = await commands.importChatgptExport(path, options);
+    return unwrap(res);
+  }
+
+  async importObsidian(
+    path: string,
+    tagFilter: string,
+  ): Promise<ImportItemReport[]> {
+    const res = await commands.importObsidian(path, tagFilter);
+    return unwrap(res);
+  }
+
   // ============================================================
   // VAULT (Direct file operations)
   // ============================================================
@@ -95,39 +380,185 @@ class TauriPromptManagerService {
     unwrap(res);
   }
 
+  async getPromptGitHistory(id: string): Promise<PromptCommit[]> {
+    const res = await commands.getPromptGitHistory(id);
+    return unwrap(res);
+  }
+
+  async restorePromptFromCommit(id: string, sha: string): Promise<void> {
+    const res = await commands.restorePromptFromCommit(id, sha);
+    unwrap(res);
+  }
+
+  async diffPrompts(
+    idA: string,
+    idB: string,
+    wordLevel = false,
+  ): Promise<PromptDiff> {
+    const res = await commands.diffPrompts(idA, idB, wordLevel);
+    return unwrap(res);
+  }
+
+  // A null version means the current file in the vault
+  async diffPromptVersions(
+    id: string,
+    v1: string | null,
+    v2: string | null,
+    wordLevel = false,
+  ): Promise<PromptDiff> {
+    const res = await commands.diffPromptVersions(id, v1, v2, wordLevel);
+    return unwrap(res);
+  }
+
+  async semanticSearch(
+    query: string,
+    topK?: number,
+  ): Promise<{ prompt: Prompt; score: number }[]> {
+    const res = await commands.semanticSearch(query, topK ?? null);
+    return unwrap(res).map((match) => ({
+      prompt: this.mapPromptFromRust(match.prompt),
+      score: match.score,
+    }));
+  }
+
+  async findSimilarPrompts(
+    id: string,
+    topK?: number,
+  ): Promise<{ prompt: Prompt; score: number }[]> {
+    const res = await commands.findSimilarPrompts(id, topK ?? null);
+    return unwrap(res).map((match) => ({
+      prompt: this.mapPromptFromRust(match.prompt),
+      score: match.score,
+    }));
+  }
+
   // ============================================================
   // CONFIG
   // ============================================================
 
   async getConfig(): Promise<AppConfig> {
     const res = await commands.getConfig();
-    const data = unwrap(res);
-    const frontmatter = (data as {
-      frontmatter?: {
-        promptTagsProperty?: string;
-        addPromptsTagToTags?: boolean;
-      };
-    }).frontmatter;
+    return this.mapConfigFromRust(unwrap(res));
+  }
+
+  mapConfigFromRust(data: RsAppConfig): AppConfig {
     return {
       vaultPath: data.vaultPath,
       theme: data.theme || "dark",
-      view: {
-        showPromptTitles: data.view?.showPromptTitles ?? true,
-        showFullPrompt: data.view?.showFullPrompt ?? false,
-        showPromptTags: data.view?.showPromptTags ?? true,
-        showCreatedDate: data.view?.showCreatedDate ?? true,
+      view: this.mapViewSettingsFromRust(data.view),
+      frontmatter: this.mapFrontmatterFromRust(data.frontmatter),
+      filenameTemplate: data.filenameTemplate?.trim() || "{date}-{random}",
+      defaultTags: data.defaultTags ?? [],
+      defaultFrontmatter: data.defaultFrontmatter ?? {},
+      gitHistory: data.gitHistory ?? false,
+      deletedRetentionDays: data.deletedRetentionDays ?? 30,
+      shareNotes: data.shareNotes ?? false,
+      embeddings: {
+        provider: data.embeddings?.provider ?? "local",
+        apiUrl: data.embeddings?.apiUrl?.trim() ||
+          DEFAULT_EMBEDDING_API_URL,
+        apiModel: data.embeddings?.apiModel?.trim() ||
+          DEFAULT_EMBEDDING_API_MODEL,
+      },
+      copy: {
+        trimWhitespace: data.copy?.trimWhitespace ?? true,
+        wrapPrefix: data.copy?.wrapPrefix ?? "",
+        wrapSuffix: data.copy?.wrapSuffix ?? "",
+      },
+      llm: {
+        apiUrl: data.llm?.apiUrl?.trim() || DEFAULT_LLM_API_URL,
+        model: data.llm?.model?.trim() || DEFAULT_LLM_MODEL,
+      },
+      templates: {
+        envAllowlist: data.templates?.envAllowlist ?? [],
       },
-      frontmatter: {
-        promptTagsProperty: frontmatter?.promptTagsProperty?.trim() || "tags",
-        addPromptsTagToTags: frontmatter?.addPromptsTagToTags ?? false,
+      lint: {
+        maxLineLength: data.lint?.maxLineLength ?? 200,
+        tokenBudget: data.lint?.tokenBudget ?? 4000,
       },
+      capture: {
+        tags: data.capture?.tags ?? [],
+        watchClipboard: data.capture?.watchClipboard ?? false,
+        minLength: data.capture?.minLength ?? 200,
+        keywords: data.capture?.keywords ?? [],
+      },
+      remote: {
+        backend: data.remote?.backend ?? "local",
+        webdav: {
+          url: data.remote?.webdav?.url ?? "",
+          username: data.remote?.webdav?.username ?? null,
+        },
+        s3: {
+          endpoint: data.remote?.s3?.endpoint ?? "",
+          region: data.remote?.s3?.region ?? "us-east-1",
+          bucket: data.remote?.s3?.bucket ?? "",
+          prefix: data.remote?.s3?.prefix ?? "",
+          accessKeyId: data.remote?.s3?.accessKeyId ?? null,
+        },
+      },
+      backup: {
+        enabled: data.backup?.enabled ?? false,
+        directory: data.backup?.directory ?? null,
+        intervalHours: data.backup?.intervalHours ?? 24,
+        retention: data.backup?.retention ?? 10,
+      },
+      profiles: (data.profiles ?? []).map((profile) =>
+        this.mapProfileFromRust(profile)
+      ),
+      activeProfile: data.activeProfile ?? null,
+      databasePath: data.databasePath ?? null,
+    };
+  }
+
+  mapProfileFromRust(profile: RsProfile): Profile {
+    return {
+      name: profile.name,
+      vaultPath: profile.vaultPath,
+      view: this.mapViewSettingsFromRust(profile.view),
+      frontmatter: this.mapFrontmatterFromRust(profile.frontmatter),
+    };
+  }
+
+  mapViewSettingsFromRust(view?: RsViewSettings): AppConfig["view"] {
+    return {
+      showPromptTitles: view?.showPromptTitles ?? true,
+      showFullPrompt: view?.showFullPrompt ?? false,
+      showPromptTags: view?.showPromptTags ?? true,
+      showCreatedDate: view?.showCreatedDate ?? true,
+    };
+  }
+
+  mapFrontmatterFromRust(
+    frontmatter?: RsFrontmatterSettings,
+  ): AppConfig["frontmatter"] {
+    return {
+      promptTagsProperty: frontmatter?.promptTagsProperty?.trim() || "tags",
+      addPromptsTagToTags: frontmatter?.addPromptsTagToTags ?? false,
+      createdProperty: frontmatter?.createdProperty?.trim() || "created",
+      titleProperty: frontmatter?.titleProperty?.trim() || "title",
+      descriptionProperty: frontmatter?.descriptionProperty?.trim() ||
+        "description",
+      contentMode: frontmatter?.contentMode ?? "fence",
+      parseWikilinks: frontmatter?.parseWikilinks ?? false,
+      fileExtensions: frontmatter?.fileExtensions ?? ["md"],
+      maxFileSizeKb: frontmatter?.maxFileSizeKb ?? 1024,
+      normalizeLineEndings: frontmatter?.normalizeLineEndings ?? false,
     };
   }
 
   async saveConfig(config: AppConfig): Promise<void> {
-    const promptTagsProperty = config.frontmatter.promptTagsProperty.trim() ||
-      "tags";
-    const input = {
+    const res = await commands.saveConfig(this.mapConfigToRust(config));
+    unwrap(res);
+  }
+
+  // Problems with a config that is not saved yet, for inline feedback
+  async validateConfig(config: AppConfig): Promise<ConfigIssue[]> {
+    const res = await commands.validateConfig(this.mapConfigToRust(config));
+    return unwrap(res);
+  }
+
+  mapConfigToRust(config: AppConfig): RsAppConfig {
+    return {
       vaultPath: config.vaultPath,
       theme: config.theme,
       view: {
@@ -136,14 +567,141 @@ class TauriPromptManagerService {
         showPromptTags: config.view.showPromptTags,
         showCreatedDate: config.view.showCreatedDate,
       },
-      frontmatter: {
-        promptTagsProperty,
-        addPromptsTagToTags: config.frontmatter.addPromptsTagToTags,
+      frontmatter: this.mapFrontmatterToRust(config.frontmatter),
+      filenameTemplate: config.filenameTemplate.trim() || "{date}-{random}",
+      defaultTags: config.defaultTags.map((tag) => tag.trim()).filter(Boolean),
+      defaultFrontmatter: config.defaultFrontmatter as Record<
+        string,
+        JsonValue
+      >,
+      gitHistory: config.gitHistory,
+      deletedRetentionDays: config.deletedRetentionDays,
+      shareNotes: config.shareNotes,
+      embeddings: {
+        provider: config.embeddings.provider,
+        apiUrl: config.embeddings.apiUrl.trim() || DEFAULT_EMBEDDING_API_URL,
+        apiModel: config.embeddings.apiModel.trim() ||
+          DEFAULT_EMBEDDING_API_MODEL,
+      },
+      copy: config.copy,
+      llm: {
+        apiUrl: config.llm.apiUrl.trim() || DEFAULT_LLM_API_URL,
+        model: config.llm.model.trim() || DEFAULT_LLM_MODEL,
+      },
+      templates: {
+        envAllowlist: config.templates.envAllowlist
+          .map((name) => name.trim())
+          .filter(Boolean),
+      },
+      lint: config.lint,
+      capture: {
+        ...config.capture,
+        tags: config.capture.tags.map((tag) => tag.trim()).filter(Boolean),
+        keywords: config.capture.keywords
+          .map((keyword) => keyword.trim())
+          .filter(Boolean),
+      },
+      remote: {
+        backend: config.remote.backend,
+        webdav: {
+          url: config.remote.webdav.url.trim(),
+          username: config.remote.webdav.username?.trim() || null,
+        },
+        s3: {
+          endpoint: config.remote.s3.endpoint.trim(),
+          region: config.remote.s3.region.trim() || "us-east-1",
+          bucket: config.remote.s3.bucket.trim(),
+          prefix: config.remote.s3.prefix.trim(),
+          accessKeyId: config.remote.s3.accessKeyId?.trim() || null,
+        },
       },
+      backup: {
+        ...config.backup,
+        directory: config.backup.directory?.trim() || null,
+      },
+      profiles: config.profiles.map((profile) => ({
+        name: profile.name.trim(),
+        vaultPath: profile.vaultPath,
+        view: profile.view,
+        frontmatter: this.mapFrontmatterToRust(profile.frontmatter),
+      })),
+      activeProfile: config.activeProfile,
+      databasePath: config.databasePath?.trim() || null,
     } as RsAppConfig;
-    const res = await commands.saveConfig(input);
+  }
+
+  mapFrontmatterToRust(
+    frontmatter: AppConfig["frontmatter"],
+  ): RsFrontmatterSettings {
+    return {
+      promptTagsProperty: frontmatter.promptTagsProperty.trim() || "tags",
+      addPromptsTagToTags: frontmatter.addPromptsTagToTags,
+      createdProperty: frontmatter.createdProperty.trim() || "created",
+      titleProperty: frontmatter.titleProperty.trim() || "title",
+      descriptionProperty: frontmatter.descriptionProperty.trim() ||
+        "description",
+      contentMode: frontmatter.contentMode,
+      parseWikilinks: frontmatter.parseWikilinks,
+      fileExtensions: frontmatter.fileExtensions,
+      maxFileSizeKb: frontmatter.maxFileSizeKb,
+      normalizeLineEndings: frontmatter.normalizeLineEndings,
+    };
+  }
+
+  async listProfiles(): Promise<Profile[]> {
+    const res = await commands.listProfiles();
+    return unwrap(res).map((profile) => this.mapProfileFromRust(profile));
+  }
+
+  // Saves the switch; returns the config with the profile's settings in place
+  async switchProfile(name: string): Promise<AppConfig> {
+    const res = await commands.switchProfile(name);
+    return this.mapConfigFromRust(unwrap(res));
+  }
+
+  // API keys live in the OS keyring: they can be set and cleared here but are
+  // never read back
+  async setSecret(name: SecretName, value: string): Promise<void> {
+    const res = await commands.setSecret(name, value);
     unwrap(res);
   }
+
+  async hasSecret(name: SecretName): Promise<boolean> {
+    const res = await commands.hasSecret(name);
+    return unwrap(res);
+  }
+
+  async deleteSecret(name: SecretName): Promise<void> {
+    const res = await commands.deleteSecret(name);
+    unwrap(res);
+  }
+
+  // Encrypted prompts: the passphrase stays in backend memory until locked
+  async unlockVault(passphrase: string): Promise<void> {
+    const res = await commands.unlockVault(passphrase);
+    unwrap(res);
+  }
+
+  async lockVault(): Promise<void> {
+    const res = await commands.lockVault();
+    unwrap(res);
+  }
+
+  async isVaultUnlocked(): Promise<boolean> {
+    const res = await commands.isVaultUnlocked();
+    return unwrap(res);
+  }
+
+  async decryptPrompt(id: string): Promise<string> {
+    const res = await commands.decryptPrompt(id);
+    return unwrap(res);
+  }
+
+  // Reopens the cache at the saved databasePath; returns the file in use
+  async reloadDatabase(): Promise<string> {
+    const res = await commands.reloadDatabase();
+    return unwrap(res);
+  }
 
   // ============================================================
   // VIEWS
@@ -173,6 +731,11 @@ class TauriPromptManagerService {
     unwrap(res);
   }
 
+  async reorderViews(ids: string[]): Promise<void> {
+    const res = await commands.reorderViews(ids);
+    unwrap(res);
+  }
+
   async getViewById(id: string): Promise<View | undefined> {
     const res = await commands.getViewById(id);
     const data = unwrap(res);
@@ -188,6 +751,51 @@ class TauriPromptManagerService {
     return unwrap(res);
   }
 
+  async getTagTree(): Promise<TagNode[]> {
+    const res = await commands.getTagTree();
+    return unwrap(res);
+  }
+
+  async getTagGraph(): Promise<TagGraph> {
+    const res = await commands.getTagGraph();
+    return unwrap(res);
+  }
+
+  async bulkUpdateTags(
+    promptIds: string[],
+    addTags: string[],
+    removeTags: string[],
+  ): Promise<SavePromptResult[]> {
+    const res = await commands.bulkUpdateTags(promptIds, addTags, removeTags);
+    return unwrap(res);
+  }
+
+  async linkPrompts(
+    source: string,
+    target: string,
+    relation: string,
+  ): Promise<void> {
+    const res = await commands.linkPrompts(source, target, relation);
+    unwrap(res);
+  }
+
+  async unlinkPrompts(
+    source: string,
+    target: string,
+    relation: string,
+  ): Promise<void> {
+    const res = await commands.unlinkPrompts(source, target, relation);
+    unwrap(res);
+  }
+
+  async getLinkedPrompts(id: string): Promise<LinkedPrompt[]> {
+    const res = await commands.getLinkedPrompts(id);
+    return unwrap(res).map((link) => ({
+      ...link,
+      prompt: this.mapPromptFromRust(link.prompt),
+    }));
+  }
+
   // ============================================================
   // SYNC
   // ============================================================
@@ -197,16 +805,297 @@ class TauriPromptManagerService {
     unwrap(res);
   }
 
+  async stopVaultWatch(): Promise<void> {
+    const res = await commands.stopVaultWatch();
+    unwrap(res);
+  }
+
+  async getVaultWatchStatus(): Promise<VaultWatchStatus> {
+    const res = await commands.getVaultWatchStatus();
+    return unwrap(res);
+  }
+
   async syncVault(): Promise<SyncStats> {
     const res = await commands.syncVault();
     return unwrap(res);
   }
 
+  // Stop running vault syncs; they fail with a "cancelled" CommandError
+  async cancelSync(): Promise<void> {
+    const res = await commands.cancelSync();
+    unwrap(res);
+  }
+
+  // Two-way sync with the remote vault; prompts it changed are re-read. Fails
+  // with a "needsConfirmation" CommandError before deleting many local files
+  // unless confirmDeletions is set.
+  async syncRemoteVault(confirmDeletions = false): Promise<RemoteSyncStats> {
+    const res = await commands.syncRemoteVault(confirmDeletions);
+    return unwrap(res);
+  }
+
+  async pushVault(): Promise<RemoteSyncStats> {
+    const res = await commands.pushVault();
+    return unwrap(res);
+  }
+
+  async pullVault(confirmDeletions = false): Promise<RemoteSyncStats> {
+    const res = await commands.pullVault(confirmDeletions);
+    return unwrap(res);
+  }
+
+  async syncPaths(paths: string[]): Promise<SyncStats> {
+    const res = await commands.syncPaths(paths);
+    return unwrap(res);
+  }
+
+  // ============================================================
+  // Backups
+  // ============================================================
+
+  async createBackupNow(): Promise<BackupInfo> {
+    const res = await commands.createBackupNow();
+    return unwrap(res);
+  }
+
+  async listBackups(): Promise<BackupInfo[]> {
+    const res = await commands.listBackups();
+    return unwrap(res);
+  }
+
+  // Replaces the vault contents with a snapshot (the current vault is backed up
+  // first) and re-syncs the cache
+  async restoreBackup(path: string): Promise<SyncStats> {
+    const res = await commands.restoreBackup(path);
+    return unwrap(res);
+  }
+
+  // Writes starter prompts and views into a new vault folder; returns the
+  // created file names. The config is not switched to the new vault.
+  async createSampleVault(path: string, pack: SamplePack): Promise<string[]> {
+    const res = await commands.createSampleVault(path, pack);
+    return unwrap(res);
+  }
+
+  // ============================================================
+  // TEMPLATES & HTTP API
+  // ============================================================
+
+  async renderPrompt(
+    id: string,
+    values: Record<string, JsonValue>,
+    preset?: string,
+  ): Promise<RenderedPrompt> {
+    const res = await commands.renderPrompt(id, values, preset ?? null);
+    return unwrap(res);
+  }
+
+  // One output per combination of the given values
+  async renderPromptMatrix(
+    id: string,
+    valueSets: Record<string, JsonValue[]>,
+  ): Promise<MatrixOutput[]> {
+    const res = await commands.renderPromptMatrix(id, valueSets);
+    return unwrap(res);
+  }
+
+  async saveTemplatePreset(
+    promptId: string,
+    name: string,
+    values: Record<string, JsonValue>,
+  ): Promise<TemplatePreset> {
+    const res = await commands.saveTemplatePreset(promptId, name, values);
+    return unwrap(res);
+  }
+
+  async listTemplatePresets(promptId: string): Promise<TemplatePreset[]> {
+    const res = await commands.listTemplatePresets(promptId);
+    return unwrap(res);
+  }
+
+  async deleteTemplatePreset(promptId: string, name: string): Promise<void> {
+    const res = await commands.deleteTemplatePreset(promptId, name);
+    unwrap(res);
+  }
+
+  async getGlobalVariables(): Promise<Record<string, string>> {
+    const res = await commands.getGlobalVariables();
+    return unwrap(res) as Record<string, string>;
+  }
+
+  async setGlobalVariable(name: string, value: string): Promise<void> {
+    const res = await commands.setGlobalVariable(name, value);
+    unwrap(res);
+  }
+
+  async deleteGlobalVariable(name: string): Promise<void> {
+    const res = await commands.deleteGlobalVariable(name);
+    unwrap(res);
+  }
+
+  async copyPromptToClipboard(
+    id: string,
+    values?: Record<string, JsonValue>,
+  ): Promise<RenderedPrompt> {
+    const res = await commands.copyPromptToClipboard(id, values ?? null);
+    return unwrap(res);
+  }
+
+  async countTokens(text: string, model?: string): Promise<TokenCount> {
+    return await commands.countTokens(text, model ?? null);
+  }
+
+  // Saves the clipboard when `text` is omitted; capture tags from settings are added
+  async quickCapture(
+    text?: string,
+    title?: string,
+    tags?: string[],
+  ): Promise<Prompt> {
+    const res = await commands.quickCapture(
+      text ?? null,
+      title ?? null,
+      tags ?? null,
+    );
+    return this.mapPromptFromRust(unwrap(res));
+  }
+
+  // Also saves the choice in settings
+  async setClipboardWatch(enabled: boolean): Promise<void> {
+    const res = await commands.setClipboardWatch(enabled);
+    unwrap(res);
+  }
+
+  async getClipboardWatchStatus(): Promise<boolean> {
+    const res = await commands.getClipboardWatchStatus();
+    return unwrap(res);
+  }
+
+  // ============================================================
+  // LINT
+  // ============================================================
+
+  async lintPrompt(id: string): Promise<PromptLint> {
+    const res = await commands.lintPrompt(id);
+    return unwrap(res);
+  }
+
+  // Only prompts with warnings are returned
+  async lintVault(): Promise<PromptLint[]> {
+    const res = await commands.lintVault();
+    return unwrap(res);
+  }
+
+  // Broken prompt files and drift between the vault and the cache
+  async checkVaultHealth(): Promise<VaultHealthReport> {
+    const res = await commands.checkVaultHealth();
+    return unwrap(res);
+  }
+
+  // A dry run returns the planned changes without writing them
+  async repairVault(
+    fixes: RepairKind[],
+    dryRun: boolean,
+  ): Promise<VaultRepairReport> {
+    const res = await commands.repairVault(fixes, dryRun);
+    return unwrap(res);
+  }
+
+  // ============================================================
+  // RUNS
+  // ============================================================
+
+  async runPrompt(
+    id: string,
+    values?: Record<string, JsonValue>,
+    model?: string,
+  ): Promise<PromptRun> {
+    const res = await commands.runPrompt(id, values ?? null, model ?? null);
+    return unwrap(res);
+  }
+
+  async getPromptRuns(id: string): Promise<PromptRun[]> {
+    const res = await commands.getPromptRuns(id);
+    return unwrap(res);
+  }
+
+  async deletePromptRun(runId: string): Promise<void> {
+    const res = await commands.deletePromptRun(runId);
+    unwrap(res);
+  }
+
+  async savePromptTest(test: PromptTestInput): Promise<PromptTest> {
+    const res = await commands.savePromptTest(test);
+    return unwrap(res);
+  }
+
+  async listPromptTests(promptId: string): Promise<PromptTest[]> {
+    const res = await commands.listPromptTests(promptId);
+    return unwrap(res);
+  }
+
+  async deletePromptTest(id: string): Promise<void> {
+    const res = await commands.deletePromptTest(id);
+    unwrap(res);
+  }
+
+  // Every test of the prompt is run and its pass/fail recorded
+  async runPromptTests(
+    promptId: string,
+    model?: string,
+  ): Promise<PromptTestResult[]> {
+    const res = await commands.runPromptTests(promptId, model ?? null);
+    return unwrap(res);
+  }
+
+  async suggestTagsForPrompt(id: string): Promise<TagSuggestions> {
+    const res = await commands.suggestTagsForPrompt(id);
+    return unwrap(res);
+  }
+
+  async suggestTagsForUntagged(): Promise<TagSuggestions[]> {
+    const res = await commands.suggestTagsForUntagged();
+    return unwrap(res);
+  }
+
+  async startApiServer(
+    port: number,
+    token: string | null,
+  ): Promise<ApiServerStatus> {
+    const res = await commands.startApiServer(port, token);
+    return unwrap(res);
+  }
+
+  async stopApiServer(): Promise<void> {
+    const res = await commands.stopApiServer();
+    unwrap(res);
+  }
+
+  async getApiServerStatus(): Promise<ApiServerStatus> {
+    const res = await commands.getApiServerStatus();
+    return unwrap(res);
+  }
+
+  // ============================================================
+  // LOGS
+  // ============================================================
+
+  // Newest `limit` entries at `level` or more severe, oldest first
+  async getRecentLogs(level: LogLevel, limit: number): Promise<LogEntry[]> {
+    const res = await commands.getRecentLogs(level, limit);
+    return unwrap(res);
+  }
+
+  // Applies until the app restarts
+  async setLogLevel(level: LogLevel): Promise<void> {
+    const res = await commands.setLogLevel(level);
+    unwrap(res);
+  }
+
   // ============================================================
   // HELPERS
   // ============================================================
 
-  private mapPromptFromRust(p: RsPrompt): Prompt {
+  mapPromptFromRust(p: RsPrompt): Prompt {
     const withDescription = p as RsPrompt & { description?: string | null };
     return {
       id: p.id,
@@ -216,10 +1105,50 @@ class TauriPromptManagerService {
       filePath: p.filePath,
       title: p.title ?? null,
       description: withDescription.description ?? null,
+      fileHash: p.fileHash ?? null,
+      customFields: p.customFields ?? {},
+      tokenCount: p.tokenCount ?? null,
+      archived: p.archived,
+      updatedAt: p.updatedAt ?? null,
+      variables: p.variables ?? [],
+      revision: p.revision,
+      color: p.color ?? null,
+      icon: p.icon ?? null,
+      notes: p.notes ?? null,
+      modelTargets: p.modelTargets ?? [],
+      language: p.language ?? null,
+      status: p.status ?? null,
+      charCount: p.charCount ?? null,
+      wordCount: p.wordCount ?? null,
+      lineCount: p.lineCount ?? null,
     };
   }
 
-  private mapViewFromRust(v: RsView): View {
+  private mapPromptToRustInput(prompt: Prompt): RsPromptInput {
+    return {
+      id: prompt.id,
+      created: prompt.created,
+      text: prompt.text,
+      tags: prompt.tags,
+      filePath: prompt.filePath ?? null,
+      previousFilePath: prompt.previousFilePath ?? null,
+      title: prompt.title ?? null,
+      description: prompt.description ?? null,
+      fileHash: prompt.fileHash ?? null,
+      customFields: prompt.customFields ?? null,
+      variant: prompt.variant ?? null,
+      expectedRevision: prompt.revision ?? null,
+      // Null keeps the file's value; an empty string clears it
+      color: prompt.color ?? null,
+      icon: prompt.icon ?? null,
+      notes: prompt.notes ?? null,
+      modelTargets: prompt.modelTargets ?? null,
+      language: prompt.language ?? null,
+      status: prompt.status ?? null,
+    } as RsPromptInput;
+  }
+
+  mapViewFromRust(v: RsView): View {
     return {
       id: v.id,
       name: v.name,
@@ -235,32 +1164,195 @@ export interface IPromptManagerService {
   getPrompts(
     options?: { filter?: ViewConfig["filter"]; sort?: ViewConfig["sort"] },
   ): Promise<Prompt[]>;
+  getPromptGroups(groupBy: GroupBy, options?: {
+    filter?: ViewConfig["filter"];
+    sort?: ViewConfig["sort"];
+  }): Promise<PromptGroup[]>;
+  getPromptSummaries(options?: {
+    filter?: ViewConfig["filter"];
+    sort?: ViewConfig["sort"];
+    page?: PageRequest;
+  }): Promise<PromptSummaryPage>;
+  getPrompt(id: string): Promise<Prompt | null>;
+  searchPrompts(
+    filter: NonNullable<ViewConfig["filter"]>,
+    sort?: ViewConfig["sort"],
+  ): Promise<SearchHit[]>;
+  fuzzySearchPrompts(
+    query: string,
+    limit: number,
+  ): Promise<FuzzyPromptMatch[]>;
+  getRecentPrompts(kind: RecentKind, limit: number): Promise<Prompt[]>;
+  getStalePrompts(days: number): Promise<Prompt[]>;
   savePrompt(prompt: Prompt): Promise<void>;
+  savePrompts(prompts: Prompt[]): Promise<SavePromptResult[]>;
   deletePrompt(id: string): Promise<void>;
   duplicatePrompt(id: string): Promise<Prompt | null>;
+  renamePrompt(id: string, newPath: string): Promise<void>;
+  setArchived(id: string, archived: boolean): Promise<void>;
+  setPromptStatus(id: string, status: PromptStatus): Promise<void>;
+  splitPrompt(id: string, split: SplitBy): Promise<string[]>;
+  listRecentlyDeleted(): Promise<DeletedPrompt[]>;
+  recoverDeletedPrompt(id: string): Promise<Prompt | null>;
+  undoLastOperation(): Promise<UndoneOperation | null>;
+  redo(): Promise<UndoneOperation | null>;
+  getUndoStatus(): Promise<UndoStatus>;
+  exportPrompts(
+    format: ExportFormat,
+    filter: ViewConfig["filter"] | null,
+    destinationPath: string,
+  ): Promise<number>;
+  exportPromptsFor(
+    tool: ExportTool,
+    filter: ViewConfig["filter"] | null,
+    destinationPath: string,
+  ): Promise<number>;
+  importPrompts(
+    path: string,
+    format: ExportFormat,
+    strategy: ImportStrategy,
+  ): Promise<ImportItemReport[]>;
+  exportPromptpack(ids: string[], path: string): Promise<number>;
+  importPromptpack(
+    path: string,
+    strategy: ImportStrategy,
+  ): Promise<ImportItemReport[]>;
+  sharePromptAsGist(id: string, isPublic: boolean): Promise<SharedGist>;
+  importPromptFromGist(url: string): Promise<ImportItemReport>;
+  importChatGptExport(
+    path: string,
+    options: ChatGptImportOptions,
+  ): Promise<ImportItemReport[]>;
+  importObsidian(path: string, tagFilter: string): Promise<ImportItemReport[]>;
 
   // Vault
   scanVault(): Promise<RsPromptFile[]>;
   readPromptFile(id: string): Promise<RsPromptFile>;
   writePromptFile(promptFile: RsPromptFile): Promise<void>;
   deletePromptFile(id: string): Promise<void>;
+  getPromptGitHistory(id: string): Promise<PromptCommit[]>;
+  restorePromptFromCommit(id: string, sha: string): Promise<void>;
+  diffPrompts(idA: string, idB: string, wordLevel?: boolean): Promise<PromptDiff>;
+  diffPromptVersions(
+    id: string,
+    v1: string | null,
+    v2: string | null,
+    wordLevel?: boolean,
+  ): Promise<PromptDiff>;
+  semanticSearch(
+    query: string,
+    topK?: number,
+  ): Promise<{ prompt: Prompt; score: number }[]>;
+  findSimilarPrompts(
+    id: string,
+    topK?: number,
+  ): Promise<{ prompt: Prompt; score: number }[]>;
 
   // Config
   getConfig(): Promise<AppConfig>;
   saveConfig(config: AppConfig): Promise<void>;
+  validateConfig(config: AppConfig): Promise<ConfigIssue[]>;
+  listProfiles(): Promise<Profile[]>;
+  switchProfile(name: string): Promise<AppConfig>;
+  reloadDatabase(): Promise<string>;
 
   // Views
   getViews(): Promise<View[]>;
   saveView(view: View): Promise<void>;
   deleteView(id: string): Promise<void>;
+  reorderViews(ids: string[]): Promise<void>;
   getViewById(id: string): Promise<View | undefined>;
 
   // Tags
   getAllTags(): Promise<string[]>;
+  getTagGraph(): Promise<TagGraph>;
+  bulkUpdateTags(
+    promptIds: string[],
+    addTags: string[],
+    removeTags: string[],
+  ): Promise<SavePromptResult[]>;
+
+  // Links
+  linkPrompts(source: string, target: string, relation: string): Promise<void>;
+  unlinkPrompts(
+    source: string,
+    target: string,
+    relation: string,
+  ): Promise<void>;
+  getLinkedPrompts(id: string): Promise<LinkedPrompt[]>;
 
   // Sync
   syncVault(): Promise<SyncStats>;
+  cancelSync(): Promise<void>;
+  syncRemoteVault(confirmDeletions?: boolean): Promise<RemoteSyncStats>;
+  pushVault(): Promise<RemoteSyncStats>;
+  pullVault(confirmDeletions?: boolean): Promise<RemoteSyncStats>;
   startVaultWatch(): Promise<void>;
+
+  // Backups
+  createBackupNow(): Promise<BackupInfo>;
+  listBackups(): Promise<BackupInfo[]>;
+  restoreBackup(path: string): Promise<SyncStats>;
+  createSampleVault(path: string, pack: SamplePack): Promise<string[]>;
+
+  // Templates
+  renderPrompt(
+    id: string,
+    values: Record<string, JsonValue>,
+    preset?: string,
+  ): Promise<RenderedPrompt>;
+  renderPromptMatrix(
+    id: string,
+    valueSets: Record<string, JsonValue[]>,
+  ): Promise<MatrixOutput[]>;
+  saveTemplatePreset(
+    promptId: string,
+    name: string,
+    values: Record<string, JsonValue>,
+  ): Promise<TemplatePreset>;
+  listTemplatePresets(promptId: string): Promise<TemplatePreset[]>;
+  deleteTemplatePreset(promptId: string, name: string): Promise<void>;
+  getGlobalVariables(): Promise<Record<string, string>>;
+  setGlobalVariable(name: string, value: string): Promise<void>;
+  deleteGlobalVariable(name: string): Promise<void>;
+  copyPromptToClipboard(
+    id: string,
+    values?: Record<string, JsonValue>,
+  ): Promise<RenderedPrompt>;
+  countTokens(text: string, model?: string): Promise<TokenCount>;
+  quickCapture(text?: string, title?: string, tags?: string[]): Promise<Prompt>;
+  setClipboardWatch(enabled: boolean): Promise<void>;
+  getClipboardWatchStatus(): Promise<boolean>;
+
+  // Lint
+  lintPrompt(id: string): Promise<PromptLint>;
+  lintVault(): Promise<PromptLint[]>;
+  checkVaultHealth(): Promise<VaultHealthReport>;
+  repairVault(fixes: RepairKind[], dryRun: boolean): Promise<VaultRepairReport>;
+
+  // Runs
+  runPrompt(
+    id: string,
+    values?: Record<string, JsonValue>,
+    model?: string,
+  ): Promise<PromptRun>;
+  getPromptRuns(id: string): Promise<PromptRun[]>;
+  deletePromptRun(runId: string): Promise<void>;
+  savePromptTest(test: PromptTestInput): Promise<PromptTest>;
+  listPromptTests(promptId: string): Promise<PromptTest[]>;
+  deletePromptTest(id: string): Promise<void>;
+  runPromptTests(promptId: string, model?: string): Promise<PromptTestResult[]>;
+  suggestTagsForPrompt(id: string): Promise<TagSuggestions>;
+  suggestTagsForUntagged(): Promise<TagSuggestions[]>;
+
+  // HTTP API
+  startApiServer(port: number, token: string | null): Promise<ApiServerStatus>;
+  stopApiServer(): Promise<void>;
+  getApiServerStatus(): Promise<ApiServerStatus>;
+
+  // Logs
+  getRecentLogs(level: LogLevel, limit: number): Promise<LogEntry[]>;
+  setLogLevel(level: LogLevel): Promise<void>;
 }
 
 export const promptManagerService = new TauriPromptManagerService();
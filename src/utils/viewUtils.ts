@@ -15,9 +15,37 @@ export function formatViewConfig(config: ViewConfig): string {
                 return `-${normalized}`;
             }
             return trimmed.startsWith("#") ? trimmed : `#${trimmed}`;
-        }).filter(Boolean).join(" "));
+        }).filter(Boolean).join(config.filter.tagMode === "or" ? " OR " : " "));
     }
 
+    if (config.filter?.untagged) {
+        parts.push("untagged");
+    }
+    if (config.filter?.missingTitle) {
+        parts.push("untitled");
+    }
+    if (config.filter?.createdAfter) {
+        parts.push(`after:${config.filter.createdAfter}`);
+    }
+    if (config.filter?.createdBefore) {
+        parts.push(`before:${config.filter.createdBefore}`);
+    }
+    if (config.filter?.modelTarget) {
+        parts.push(`model:${config.filter.modelTarget}`);
+    }
+    if (config.filter?.language) {
+        parts.push(`lang:${config.filter.language}`);
+    }
+    if (config.filter?.status) {
+        parts.push(`status:${config.filter.status}`);
+    }
+    if (config.filter?.minWords != null) {
+        parts.push(`words>=${config.filter.minWords}`);
+    }
+    if (config.filter?.maxWords != null) {
+        parts.push(`words<=${config.filter.maxWords}`);
+    }
+
     // Format sort: sort_desc:created_at
     if (config.sort) {
         parts.push(`sort_${config.sort.order}:${config.sort.by}`);